@@ -32,6 +32,10 @@
           xorg.libXcursor
           xorg.libXi
           mesa
+
+          # Audio
+          pkg-config
+          alsa-lib
         ];
       in {
         devShells.default = pkgs.mkShell {
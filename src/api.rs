@@ -0,0 +1,448 @@
+//! An HTTP API for driving a run from scripts, CI jobs and notebooks.
+//!
+//! The [`ApiServer`] frontend runs the application with nothing drawn and
+//! answers plain HTTP/1.1 requests, one per connection, in JSON:
+//!
+//! ```text
+//! GET  /status              tick, whether it is running, grid and seed
+//! POST /start               run in real time
+//! POST /stop                pause
+//! POST /step?ticks=N        run N ticks (1 if not given), answering once done
+//! GET  /params              the rates, entanglement percentage and tick rate
+//! POST /params?NAME=VALUE   set any of them, by the names /params lists
+//! POST /tool                apply the tool command in the body, `observe 3 4`
+//! GET  /stats               the population, entropy and grade totals
+//! GET  /snapshot            the whole universe as a delta code
+//! GET  /snapshot?format=npy every cell's coefficients as a .npy array
+//! POST /quit                stop serving
+//! ```
+//!
+//! Parameters may come in the query string or, form-encoded, in the body.
+//! Tool commands are those of the WebSocket server (see [`serve::command`]),
+//! and become logged interventions as clicks do. Requests are handled between
+//! frames on the application's thread, so ticks go through the application as
+//! in the window: on the simulation worker when it has one, with autosaves,
+//! recordings and scripts as usual. Start it with `--serve-http 127.0.0.1:8080`
+//! and, for example, `curl -X POST localhost:8080/step?ticks=100`.
+
+use crate::app::AppState;
+use crate::frontend::Frontend;
+use crate::input::AppAction;
+use crate::panel::Setting;
+use crate::serve::{self, Request};
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The longest request body accepted, in bytes.
+const MAX_BODY: usize = 64 * 1024;
+
+/// Every path the API answers.
+const ROUTES: [&str; 9] = [
+    "/status",
+    "/start",
+    "/stop",
+    "/step",
+    "/params",
+    "/tool",
+    "/stats",
+    "/snapshot",
+    "/quit",
+];
+
+/// The parameters `/params` reads and sets, by name.
+const PARAMS: [(&str, Setting); 5] = [
+    ("observation_rate", Setting::ObservationRate),
+    ("decay_rate", Setting::DecayRate),
+    ("fluctuation_rate", Setting::FluctuationRate),
+    ("entanglement_percentage", Setting::EntanglementPercentage),
+    ("tick_rate", Setting::TickRate),
+];
+
+/// Runs the application headless under the control of HTTP requests; see
+/// the [module documentation](self).
+pub struct ApiServer {
+    listener: TcpListener,
+    /// How long to wait between frames when no request is waiting.
+    pub frame: Duration,
+}
+
+impl ApiServer {
+    /// Listens on `address`, given as `http://HOST:PORT` or just `HOST:PORT`.
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let address = address.strip_prefix("http://").unwrap_or(address);
+        let address = address.split('/').next().unwrap_or(address);
+        Ok(ApiServer {
+            listener: TcpListener::bind(address)?,
+            frame: Duration::from_secs(1) / 60,
+        })
+    }
+
+    /// The address the server listens on, with the port filled in if it was
+    /// bound to port 0.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+}
+
+/// A request as the application's thread sees it.
+#[derive(Debug)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    /// The query string's parameters followed by the form-encoded body's.
+    params: Vec<(String, String)>,
+    body: String,
+}
+
+impl HttpRequest {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// A response: its status code, content type and body.
+#[derive(Debug)]
+struct HttpResponse {
+    status: u16,
+    content_type: &'static str,
+    body: Vec<u8>,
+}
+
+impl HttpResponse {
+    fn json(body: String) -> Self {
+        HttpResponse {
+            status: 200,
+            content_type: "application/json",
+            body: body.into_bytes(),
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        HttpResponse {
+            status,
+            ..HttpResponse::json(format!("{{\"error\":{}}}", json_string(message)))
+        }
+    }
+}
+
+/// A request waiting for its answer.
+type Pending = (HttpRequest, Sender<HttpResponse>);
+
+impl Frontend for ApiServer {
+    fn name(&self) -> &str {
+        "http"
+    }
+
+    fn run(&mut self, app: &mut AppState) -> io::Result<()> {
+        let (inbox, inbound) = mpsc::channel::<Pending>();
+        let listener = self.listener.try_clone()?;
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let inbox = inbox.clone();
+                thread::spawn(move || {
+                    let _ = answer(stream, &inbox);
+                });
+            }
+        });
+
+        // `/step` answers once the universe reaches the tick it asked for.
+        let mut stepping: Vec<(u64, Sender<HttpResponse>)> = Vec::new();
+        let mut last_frame = Instant::now();
+        loop {
+            for (request, reply) in inbound.try_iter() {
+                let route = (request.method.as_str(), request.path.as_str());
+                if route == ("POST", "/quit") {
+                    let _ = reply.send(HttpResponse::json(status(app)));
+                    return Ok(());
+                }
+                if route == ("POST", "/step") {
+                    match request.param("ticks").unwrap_or("1").parse::<u64>() {
+                        Ok(ticks) => {
+                            let from = stepping.last().map_or(app.universe.tick_count, |s| s.0);
+                            stepping.push((from.max(app.universe.tick_count) + ticks, reply));
+                        }
+                        Err(_) => {
+                            let _ = reply.send(HttpResponse::error(400, "ticks must be a count"));
+                        }
+                    }
+                    continue;
+                }
+                let _ = reply.send(handle(app, &request));
+            }
+
+            let now = Instant::now();
+            if let Some(&(target, _)) = stepping.first()
+                && app.paused
+                && app.universe.tick_count < target
+            {
+                // Stepped ticks run back to back, whatever the tick rate.
+                app.paused = false;
+                app.apply(AppAction::Tick);
+                app.paused = true;
+            } else {
+                app.apply(AppAction::Advance(
+                    now.duration_since(last_frame).as_secs_f64(),
+                ));
+                thread::sleep(self.frame);
+            }
+            last_frame = now;
+            for line in app.scripts.take_output() {
+                println!("{}", line);
+            }
+            while stepping
+                .first()
+                .is_some_and(|&(target, _)| app.universe.tick_count >= target)
+            {
+                let (_, reply) = stepping.remove(0);
+                let _ = reply.send(HttpResponse::json(status(app)));
+            }
+        }
+    }
+}
+
+/// Answers every request but `/step` and `/quit`.
+fn handle(app: &mut AppState, request: &HttpRequest) -> HttpResponse {
+    match (request.method.as_str(), request.path.as_str()) {
+        ("GET", "/status") => HttpResponse::json(status(app)),
+        ("POST", "/start") => {
+            app.paused = false;
+            HttpResponse::json(status(app))
+        }
+        ("POST", "/stop") => {
+            app.paused = true;
+            HttpResponse::json(status(app))
+        }
+        ("GET", "/params") => HttpResponse::json(params(app)),
+        ("POST", "/params") => {
+            let mut values = Vec::new();
+            for (name, value) in &request.params {
+                let Some(&(_, setting)) = PARAMS.iter().find(|(key, _)| key == name) else {
+                    return HttpResponse::error(400, &format!("unknown parameter {}", name));
+                };
+                match value.parse::<f64>() {
+                    Ok(value) if value.is_finite() => values.push((setting, value)),
+                    _ => return HttpResponse::error(400, &format!("{} must be a number", name)),
+                }
+            }
+            for (setting, value) in values {
+                setting.set(app, value);
+            }
+            HttpResponse::json(params(app))
+        }
+        ("POST", "/tool") => match serve::command(&app.universe, &request.body) {
+            Ok(Request::Intervene(intervention)) => {
+                app.universe.apply(intervention);
+                HttpResponse::json(status(app))
+            }
+            Ok(Request::Cursor(_)) => HttpResponse::error(400, "cursor is not a tool"),
+            Err(message) => HttpResponse::error(400, &message),
+        },
+        ("GET", "/stats") => {
+            let stats = app.universe.stats();
+            HttpResponse::json(format!(
+                "{{\"tick\":{},\"potential\":{},\"observed\":{},\"operators\":{},\
+                 \"entanglements\":{},\"entropy\":{},\"mean_nonzero_coefficients\":{},\
+                 \"grade_totals\":{}}}",
+                stats.tick,
+                stats.potential,
+                stats.observed,
+                stats.operators,
+                stats.entanglements,
+                json_number(stats.entropy),
+                json_number(stats.mean_nonzero_coefficients),
+                json_list(&stats.grade_totals)
+            ))
+        }
+        ("GET", "/snapshot") => match request.param("format").unwrap_or("delta") {
+            "delta" => HttpResponse::json(format!(
+                "{{\"tick\":{},\"state\":{}}}",
+                app.universe.tick_count,
+                json_string(&app.universe.to_delta().encode())
+            )),
+            "npy" => HttpResponse {
+                status: 200,
+                content_type: "application/octet-stream",
+                body: app.universe.to_array().to_npy(),
+            },
+            format => HttpResponse::error(400, &format!("unknown format {}", format)),
+        },
+        (_, path) if ROUTES.contains(&path) => HttpResponse::error(405, "method not allowed"),
+        _ => HttpResponse::error(404, "not found"),
+    }
+}
+
+/// The `/status` document.
+fn status(app: &AppState) -> String {
+    format!(
+        "{{\"tick\":{},\"running\":{},\"grid_dims\":{},\"ga_dims\":{},\"seed\":{}}}",
+        app.universe.tick_count,
+        !app.paused,
+        json_list(&app.universe.grid_dims),
+        app.universe.ga_dims,
+        app.universe.seed
+    )
+}
+
+/// The `/params` document.
+fn params(app: &AppState) -> String {
+    let fields: Vec<String> = PARAMS
+        .iter()
+        .map(|(name, setting)| format!("\"{}\":{}", name, json_number(setting.get(app))))
+        .collect();
+    format!("{{{}}}", fields.join(","))
+}
+
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
+
+fn json_list(values: &[usize]) -> String {
+    let values: Vec<String> = values.iter().map(usize::to_string).collect();
+    format!("[{}]", values.join(","))
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c < ' ' => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Reads a request from `stream`, passes it to the application's thread and
+/// writes back the answer.
+fn answer(stream: TcpStream, inbox: &Sender<Pending>) -> io::Result<()> {
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+    let mut writer = stream.try_clone()?;
+    let response = match read_request(&mut BufReader::new(stream))? {
+        Some(request) => {
+            let (reply, answer) = mpsc::channel();
+            match inbox.send((request, reply)) {
+                Ok(()) => answer
+                    .recv()
+                    .unwrap_or_else(|_| HttpResponse::error(503, "the server is stopping")),
+                Err(_) => HttpResponse::error(503, "the server is stopping"),
+            }
+        }
+        None => HttpResponse::error(400, "bad request"),
+    };
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Service Unavailable",
+    };
+    write!(
+        writer,
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        response.status,
+        reason,
+        response.content_type,
+        response.body.len()
+    )?;
+    writer.write_all(&response.body)
+}
+
+/// Reads one request, or `None` if it is malformed.
+fn read_request(reader: &mut impl BufRead) -> io::Result<Option<HttpRequest>> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let mut words = line.split_whitespace();
+    let (Some(method), Some(target)) = (words.next(), words.next()) else {
+        return Ok(None);
+    };
+    let mut length = 0;
+    let mut form = false;
+    loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                let Ok(value) = value.trim().parse::<usize>() else {
+                    return Ok(None);
+                };
+                length = value;
+            } else if name.trim().eq_ignore_ascii_case("content-type") {
+                form = value
+                    .trim()
+                    .starts_with("application/x-www-form-urlencoded");
+            }
+        }
+    }
+    if length > MAX_BODY {
+        return Ok(None);
+    }
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    let body = String::from_utf8_lossy(&body).into_owned();
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let mut params = form_params(query);
+    if form {
+        params.extend(form_params(&body));
+    }
+    Ok(Some(HttpRequest {
+        method: method.to_string(),
+        path: path.to_string(),
+        params,
+        body,
+    }))
+}
+
+/// The name and value pairs of a form-encoded string.
+fn form_params(text: &str) -> Vec<(String, String)> {
+    text.split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(name), percent_decode(value))
+        })
+        .collect()
+}
+
+fn percent_decode(text: &str) -> String {
+    let mut bytes = Vec::new();
+    let mut rest = text.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        rest = tail;
+        match b {
+            b'+' => bytes.push(b' '),
+            b'%' if rest.len() >= 2 => {
+                match std::str::from_utf8(&rest[..2])
+                    .ok()
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+                {
+                    Some(byte) => {
+                        bytes.push(byte);
+                        rest = &rest[2..];
+                    }
+                    None => bytes.push(b'%'),
+                }
+            }
+            b => bytes.push(b),
+        }
+    }
+    String::from_utf8_lossy(&bytes).into_owned()
+}
@@ -0,0 +1,2014 @@
+//! The interactive application state.
+//!
+//! Everything here is independent of the window and of Piston: the state only
+//! changes through typed [`AppAction`]s, which the `input` module derives from
+//! raw events. Any frontend (GUI, tests, or otherwise) drives the same logic.
+
+#[cfg(feature = "gpu")]
+use crate::gpu::GpuTicker;
+use crate::{
+    autosave::{AutosavePolicy, Autosaver},
+    capture::{Capture, CaptureSettings},
+    color::{ColorMapping, ViewMode},
+    existon::ConsciousnessState,
+    field::{ParameterField, Rate},
+    ga_core::Multivector,
+    history::History,
+    hud::Hud,
+    input::{AppAction, Modifiers},
+    inspect::Inspection,
+    keymap::Keymap,
+    library::PatternLibrary,
+    minimap::Minimap,
+    multiverse::Multiverse,
+    panel::{Control, ControlPanel, Setting},
+    pattern::Pattern,
+    plot::{MetricHistory, Sample},
+    plugin::{PluginCtx, PluginRegistry},
+    profile::Profiler,
+    replay::{Playback, RecordMode, Recorder, Replay},
+    rule::{MatzkeDefaultRule, UpdateRule},
+    schedule::Schedule,
+    script::Scripts,
+    series::StatsLog,
+    session::Session,
+    shape::Shape,
+    share::Scenario,
+    slice::Slice,
+    sonify::Sonifier,
+    timestep::FixedTimestep,
+    toolbar::Toolbar,
+    universe::{BoundaryCondition, OperatorPreset, TickReport, Topology, Universe},
+    volume::OrbitCamera,
+    worker::{Command, SimWorker, Update},
+    zen::ZenMode,
+};
+use arboard::Clipboard;
+use rand::{Rng, rng};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+//================================================================================
+// New UI Components
+//================================================================================
+
+/// Defines the interactive tools the user can switch between.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToolMode {
+    Observe,  // 🔎
+    Entangle, // 🔗
+    Operator, // 🏗️
+    Disrupt,  // 🌊
+    Select,   // ⬚
+    Inspect,  // 🔬
+    Paint,    // 🖌
+}
+
+impl ToolMode {
+    /// Every tool, in toolbar order.
+    pub const ALL: [ToolMode; 7] = [
+        ToolMode::Observe,
+        ToolMode::Entangle,
+        ToolMode::Operator,
+        ToolMode::Disrupt,
+        ToolMode::Select,
+        ToolMode::Inspect,
+        ToolMode::Paint,
+    ];
+}
+
+/// How the Disrupt tool's chance of decaying a cell falls off from the center
+/// of its brush to the edge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Falloff {
+    /// The same chance everywhere in the brush.
+    Hard,
+    /// Falling in a straight line to nothing at the edge.
+    #[default]
+    Linear,
+    /// Flat near the center and the edge, falling fastest halfway out.
+    Smooth,
+}
+
+impl Falloff {
+    /// The share of the full chance a cell gets at `distance`, a fraction of
+    /// the brush radius.
+    pub fn weight(self, distance: f64) -> f64 {
+        let t = distance.clamp(0.0, 1.0);
+        match self {
+            Falloff::Hard => 1.0,
+            Falloff::Linear => 1.0 - t,
+            Falloff::Smooth => 1.0 - t * t * (3.0 - 2.0 * t),
+        }
+    }
+}
+
+/// How long the Disrupt tool takes to charge fully while held, in seconds.
+const DISRUPT_CHARGE_SECONDS: f64 = 1.5;
+
+/// How many times a second the held Paint tool doubles or halves the rate at
+/// the center of its brush.
+const PAINT_DOUBLINGS_PER_SECOND: f64 = 4.0;
+/// The Paint tool raises a rate of zero from here, and lowers a rate below
+/// it to zero.
+const PAINT_FLOOR: f64 = 1e-4;
+
+/// The lattices `[Ctrl+G]` steps the Operator tool through: every cell, then
+/// every 2nd, 4th, 8th and 16th.
+const OPERATOR_SNAPS: [usize; 5] = [1, 2, 4, 8, 16];
+/// How much one notch of the scroll wheel zooms the view.
+const ZOOM_PER_NOTCH: f64 = 1.15;
+/// How much one step of `[`, `]` or the wheel with Ctrl held resizes a brush.
+const BRUSH_PER_STEP: f64 = 1.25;
+/// The smallest brush radius, in pixels, which paints just the cell under the
+/// cursor.
+pub const MIN_BRUSH_RADIUS: f64 = 1.0;
+/// The largest brush radius, in pixels.
+pub const MAX_BRUSH_RADIUS: f64 = 200.0;
+
+/// The view onto the grid: a pan offset in screen pixels and a zoom factor.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Camera {
+    pub offset: [f64; 2],
+    pub zoom: f64,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera {
+            offset: [0.0, 0.0],
+            zoom: 1.0,
+        }
+    }
+}
+
+impl Camera {
+    /// The farthest the view zooms out.
+    pub const MIN_ZOOM: f64 = 0.125;
+    /// The farthest the view zooms in.
+    pub const MAX_ZOOM: f64 = 16.0;
+
+    /// Moves the view by `delta` screen pixels.
+    pub fn pan(&mut self, delta: [f64; 2]) {
+        self.offset = [self.offset[0] + delta[0], self.offset[1] + delta[1]];
+    }
+
+    /// Scales the zoom by `factor`, within [`Camera::MIN_ZOOM`] and
+    /// [`Camera::MAX_ZOOM`], keeping the grid under the screen position
+    /// `anchor` in place.
+    pub fn zoom_about(&mut self, anchor: [f64; 2], factor: f64) {
+        let world = self.screen_to_world(anchor);
+        self.zoom = (self.zoom * factor).clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+        self.offset = [
+            anchor[0] - world[0] * self.zoom,
+            anchor[1] - world[1] * self.zoom,
+        ];
+    }
+
+    /// The view that shows all of `content` grid-space pixels in a window of
+    /// `window` pixels as large as it fits, centered with bars on the sides
+    /// left over.
+    pub fn fit(content: [f64; 2], window: [f64; 2]) -> Self {
+        let zoom = (window[0] / content[0])
+            .min(window[1] / content[1])
+            .clamp(Self::MIN_ZOOM, Self::MAX_ZOOM);
+        Camera {
+            offset: [
+                (window[0] - content[0] * zoom) / 2.0,
+                (window[1] - content[1] * zoom) / 2.0,
+            ],
+            zoom,
+        }
+    }
+
+    /// Pans so that the grid-space position `world` lands in the middle of a
+    /// window of `window` pixels, keeping the zoom.
+    pub fn center_on(&mut self, world: [f64; 2], window: [f64; 2]) {
+        self.offset = [
+            window[0] / 2.0 - world[0] * self.zoom,
+            window[1] / 2.0 - world[1] * self.zoom,
+        ];
+    }
+
+    /// Converts a screen (window) position into unzoomed grid-space pixels.
+    pub fn screen_to_world(&self, pos: [f64; 2]) -> [f64; 2] {
+        [
+            (pos[0] - self.offset[0]) / self.zoom,
+            (pos[1] - self.offset[1]) / self.zoom,
+        ]
+    }
+
+    /// Converts unzoomed grid-space pixels into a screen position, the inverse
+    /// of [`Camera::screen_to_world`].
+    pub fn world_to_screen(&self, pos: [f64; 2]) -> [f64; 2] {
+        [
+            pos[0] * self.zoom + self.offset[0],
+            pos[1] * self.zoom + self.offset[1],
+        ]
+    }
+}
+
+/// Which optional layers are drawn over the grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Overlays {
+    /// Lines flashed between partners when an entanglement fires.
+    pub entanglement_flashes: bool,
+    /// Dimmed lines joining every entangled group, brighter for the group of
+    /// the cell under the cursor.
+    pub entanglement_links: bool,
+    /// Outlines around the cells in the watch list.
+    pub watch_markers: bool,
+    /// The legend explaining how cell colors are derived.
+    pub help: bool,
+    /// The list of plugins and the summaries of the enabled analyzers.
+    pub plugins: bool,
+    /// The plot strip of recent population metrics.
+    pub plot: bool,
+}
+
+impl Default for Overlays {
+    fn default() -> Self {
+        Overlays {
+            entanglement_flashes: true,
+            entanglement_links: false,
+            watch_markers: true,
+            help: false,
+            plugins: false,
+            plot: false,
+        }
+    }
+}
+
+//================================================================================
+// Application Configuration
+//================================================================================
+pub struct Config {
+    pub grid_dims: Vec<usize>,
+    pub ga_dims: usize,
+    /// Whether new universes have square or hexagonal cells.
+    pub topology: Topology,
+    /// The update rule of new universes; see
+    /// [`RuleRegistry`](crate::rule::RuleRegistry).
+    pub rule: Arc<dyn UpdateRule>,
+    pub cell_size: f64,
+    /// The Observe tool's brush radius, in pixels.
+    pub observation_radius: f64,
+    /// The Disrupt tool's brush radius, in pixels, before it charges.
+    pub disruption_radius: f64,
+    /// The Disrupt tool's chance of decaying an `Observed` cell at the center
+    /// of its brush.
+    pub disruption_strength: f64,
+    /// How that chance falls off towards the edge of the brush.
+    pub disruption_falloff: Falloff,
+    /// The amplitude of the disruption wave a Disrupt click emits; see
+    /// [`Universe::emit_disruption`].
+    pub disruption_amplitude: f64,
+    /// The Paint tool's brush radius, in pixels.
+    pub paint_radius: f64,
+    /// The rates new universes start with; see [`Universe::observation_rate`]
+    /// and its neighbours.
+    pub observation_rate: f64,
+    pub decay_rate: f64,
+    pub fluctuation_rate: f64,
+    pub window_size: [f64; 2],
+    pub background_color: [f32; 4],
+    pub toolbar_color: [f32; 4],
+    pub text_color: [f32; 4],
+    pub font_size: u32,
+    /// Where the workspace is saved to and restored from.
+    pub session_path: PathBuf,
+    /// Where calibrated tick tunings are cached between runs.
+    pub preferences_path: PathBuf,
+    /// The simulation time allowed per frame in soft real-time mode.
+    pub tick_budget: Duration,
+    /// How many ticks to run per second of real time; `None` ticks once per
+    /// frame, as fast as frames come.
+    pub ticks_per_second: Option<f64>,
+    /// How many past states can be undone.
+    pub history_len: usize,
+    /// The plane of the grid that is drawn and clicked.
+    pub slice: Slice,
+    /// Where recordings are written to and played back from.
+    pub replay_path: PathBuf,
+    /// The folder of pattern files the library lists after its starter patterns.
+    pub pattern_dir: PathBuf,
+    /// The folder of scripts loaded at startup; see [`Scripts`].
+    pub script_dir: PathBuf,
+    /// Where animated captures are written: an APNG if the name ends in
+    /// `.png`, a GIF otherwise.
+    pub capture_path: PathBuf,
+    /// How often animated captures take a frame, and how large.
+    pub capture: CaptureSettings,
+    /// What recordings capture besides the seed and interventions.
+    pub record_mode: RecordMode,
+    /// The file of keybindings read at startup; see [`Keymap`].
+    pub keybindings_path: PathBuf,
+    /// The settings file read at startup and watched for changes; see
+    /// [`SettingsFile`](crate::settings::SettingsFile).
+    pub settings_path: PathBuf,
+    /// Where and how often the session is checkpointed; `None` turns it off.
+    pub autosave: Option<AutosavePolicy>,
+}
+
+impl Config {
+    /// The size of the viewed plane in unzoomed grid-space pixels, the
+    /// content [`Camera::fit`] fits into the window.
+    pub fn grid_extent(&self) -> [f64; 2] {
+        let [across, down] = self.slice.axes;
+        let length = |axis: usize| self.grid_dims.get(axis).copied().unwrap_or(1).max(1);
+        let height = if self.grid_dims.len() > 1 {
+            length(down)
+        } else {
+            1
+        };
+        [
+            length(across) as f64 * self.cell_size,
+            height as f64 * self.cell_size,
+        ]
+    }
+
+    pub fn new() -> Self {
+        let grid_dims = vec![120, 80];
+        let ga_dims = 3;
+        const CELL_SIZE: f64 = 8.0;
+
+        let window_width = grid_dims.first().copied().unwrap_or(100) as f64 * CELL_SIZE;
+        let window_height = grid_dims.get(1).copied().unwrap_or(100) as f64 * CELL_SIZE;
+
+        let slice = Slice::new(grid_dims.len());
+        Self {
+            grid_dims,
+            ga_dims,
+            topology: Topology::Square,
+            rule: Arc::new(MatzkeDefaultRule),
+            cell_size: CELL_SIZE,
+            observation_radius: 50.0,
+            disruption_radius: 50.0,
+            disruption_strength: 1.0,
+            disruption_falloff: Falloff::Linear,
+            disruption_amplitude: 0.6,
+            paint_radius: 50.0,
+            observation_rate: 0.0005,
+            decay_rate: 0.01,
+            fluctuation_rate: 0.001,
+            window_size: [window_width, window_height],
+            background_color: [0.0, 0.0, 0.0, 1.0],
+            toolbar_color: [0.1, 0.1, 0.12, 1.0],
+            text_color: [1.0, 1.0, 1.0, 0.9],
+            font_size: 14,
+            session_path: PathBuf::from("existons-session.txt"),
+            preferences_path: PathBuf::from("existons-preferences.txt"),
+            tick_budget: Duration::from_millis(8),
+            ticks_per_second: Some(60.0),
+            history_len: 64,
+            slice,
+            replay_path: PathBuf::from("existons-replay.txt"),
+            pattern_dir: PathBuf::from("existons-patterns"),
+            script_dir: PathBuf::from("scripts"),
+            capture_path: PathBuf::from("existons-capture.gif"),
+            capture: CaptureSettings::default(),
+            record_mode: RecordMode::Inputs,
+            keybindings_path: PathBuf::from("keybindings.toml"),
+            settings_path: PathBuf::from("existons.toml"),
+            autosave: Some(AutosavePolicy::default()),
+        }
+    }
+}
+
+impl Config {
+    /// A fresh random universe of the configured shape, topology and rates.
+    pub fn new_universe(&self) -> Universe {
+        let mut universe =
+            Universe::new(self.grid_dims.clone(), self.ga_dims).with_topology(self.topology);
+        universe.set_shared_rule(Arc::clone(&self.rule));
+        universe.observation_rate = self.observation_rate;
+        universe.decay_rate = self.decay_rate;
+        universe.fluctuation_rate = self.fluctuation_rate;
+        universe
+    }
+
+    /// The brush radius of `tool`, for the tools that paint with a brush.
+    pub fn brush_radius(&self, tool: ToolMode) -> Option<f64> {
+        match tool {
+            ToolMode::Observe => Some(self.observation_radius),
+            ToolMode::Disrupt => Some(self.disruption_radius),
+            ToolMode::Paint => Some(self.paint_radius),
+            _ => None,
+        }
+    }
+
+    fn brush_radius_mut(&mut self, tool: ToolMode) -> Option<&mut f64> {
+        match tool {
+            ToolMode::Observe => Some(&mut self.observation_radius),
+            ToolMode::Disrupt => Some(&mut self.disruption_radius),
+            ToolMode::Paint => Some(&mut self.paint_radius),
+            _ => None,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//================================================================================
+// Application State
+//================================================================================
+
+/// The complete interactive state: the universe plus everything the UI tracks.
+pub struct AppState {
+    pub config: Config,
+    pub universe: Universe,
+    pub mouse_pos: [f64; 2],
+    pub current_tool: ToolMode,
+    pub entangle_first_partner: Option<u64>,
+    /// Whether the first partner was picked by pressing the button that is
+    /// still held, so letting go over another cell pairs them.
+    pub entangle_drag: bool,
+    /// How far the held Disrupt tool has charged, from 0 to 1; its brush
+    /// grows to twice its radius at full charge.
+    pub disrupt_charge: f64,
+    /// The rate the Paint tool paints into its parameter field.
+    pub paint_rate: Rate,
+    pub entanglement_flashes: Vec<(Vec<usize>, Vec<usize>, u8)>,
+    // Track if mouse buttons are held down for painting
+    pub is_left_mouse_down: bool,
+    pub is_right_mouse_down: bool,
+    /// Whether the middle button is held, dragging the view along.
+    pub is_middle_mouse_down: bool,
+    /// The system clipboard for share codes, if one is available.
+    pub clipboard: Option<Clipboard>,
+    /// Which key does what.
+    pub keymap: Keymap,
+    pub camera: Camera,
+    /// Physical pixels per window point: 1 on ordinary displays and more on
+    /// HiDPI ones, where text is rasterized this much finer.
+    pub hidpi_factor: f64,
+    pub overlays: Overlays,
+    /// How `Potential` cells are colored.
+    pub color_mapping: ColorMapping,
+    /// What cell colors show; `color_mapping` applies in
+    /// [`ViewMode::Coefficients`].
+    pub view_mode: ViewMode,
+    /// Grid indices of cells the user has marked to keep an eye on.
+    pub watch_list: Vec<usize>,
+    /// Undo/redo states, recorded before every edit and every
+    /// `universe.tuning.snapshot_interval` ticks.
+    pub history: History,
+    /// Whether ticks are suspended; undoing pauses so the past can be inspected.
+    pub paused: bool,
+    /// The modifier keys currently held.
+    pub modifiers: Modifiers,
+    /// What happened during the most recent tick.
+    pub last_report: TickReport,
+    /// Turns ticks into tones for a frontend with sound to play.
+    pub sonifier: Sonifier,
+    /// Whether ticks are spread over frames so a frame never takes longer than
+    /// `config.tick_budget` to simulate, for installations that must not stutter.
+    pub soft_real_time: bool,
+    /// Paces ticks by real time instead of one per frame, if set.
+    pub timestep: Option<FixedTimestep>,
+    /// Overlays and analyzers attached to the universe.
+    pub plugins: PluginRegistry,
+    /// Scripts whose hooks run after ticks, clicks and key presses.
+    pub scripts: Scripts,
+    /// The recording in progress, if any.
+    pub recorder: Option<Recorder>,
+    /// The CSV file every tick's statistics are appended to, if any.
+    pub stats_log: Option<StatsLog>,
+    /// The animated capture in progress, if any.
+    pub capture: Option<Capture>,
+    /// The replay being watched instead of the live universe, if any.
+    pub playback: Option<Playback>,
+    /// The running zen mode, which drifts the rates and steers the camera.
+    pub zen: Option<ZenMode>,
+    /// Sets rates from curves over the ticks before each tick, if any.
+    pub schedule: Option<Schedule>,
+    /// Computes the local interaction on the GPU, when one could be opened.
+    #[cfg(feature = "gpu")]
+    pub gpu: Option<GpuTicker>,
+    /// The rectangle dragged out with the Select tool, as one range per grid dimension.
+    pub selection: Option<Vec<Range<usize>>>,
+    /// The cell a selection drag started from.
+    selection_anchor: Option<Vec<usize>>,
+    /// The most recently copied pattern, pasted when the clipboard holds none.
+    pub copied_pattern: Option<Pattern>,
+    /// The patterns to browse and stamp, shown with `[P]`.
+    pub library: PatternLibrary,
+    /// The state the Operator tool places.
+    pub operator_preset: OperatorPreset,
+    /// A state picked up with the eyedropper, which the Operator tool places
+    /// instead of the preset until the palette is cycled.
+    pub operator_sample: Option<Multivector>,
+    /// The Operator tool places operators only where both coordinates of the
+    /// viewed plane are multiples of this, at the one nearest the cursor; 1
+    /// places them anywhere.
+    pub operator_snap: usize,
+    /// What a drag with the Operator tool draws.
+    pub operator_shape: Shape,
+    /// Where a shape being dragged out with the Operator tool started and
+    /// where the cursor has taken it, as grid coordinates.
+    pub shape_drag: Option<(Vec<usize>, Vec<usize>)>,
+    /// Sliders and buttons for tuning the run, shown with `[K]`.
+    pub panel: ControlPanel,
+    /// The camera of the volume view of a 3D grid, shown instead of the slice
+    /// when set; dragging with the left button turns it instead of using the
+    /// active tool.
+    pub volume: Option<OrbitCamera>,
+    /// The ID of the cell pinned by the Inspect tool, which is inspected
+    /// wherever it moves instead of the cell under the cursor.
+    pub inspected: Option<u64>,
+    /// The frame and tick rates and population, shown with `[I]`.
+    pub hud: Hud,
+    /// Where the time of recent frames and ticks went, shown with `[F3]`.
+    pub profiler: Profiler,
+    /// The population metrics of recent ticks, for the plot strip.
+    pub metrics: MetricHistory,
+    /// Writes crash-recovery checkpoints of the live session, if enabled.
+    pub autosave: Option<Autosaver>,
+    /// The universes ticked alongside the live one, if any. The live universe
+    /// is on loan from the `active` slot, which holds a stale copy between
+    /// ticks.
+    pub multiverse: Option<Multiverse>,
+    /// Ticks a copy of the live universe on a background thread, if set, so a
+    /// slow tick never holds up input or drawing. The live universe is
+    /// swapped for the ticked copy once it is back; interventions made
+    /// meanwhile are applied to it again, and other edits discard it.
+    pub worker: Option<SimWorker>,
+    /// The tick the worker is running, if any.
+    worker_job: Option<WorkerJob>,
+    /// Counts the edits that invalidate a tick the worker is running.
+    edits: u64,
+}
+
+/// A tick of the live universe sent to the [`SimWorker`].
+struct WorkerJob {
+    /// [`AppState::edits`] when the tick was sent.
+    edits: u64,
+    /// The length of the intervention log when the tick was sent.
+    interventions: usize,
+    /// The tick's report, once it has arrived.
+    report: Option<TickReport>,
+}
+
+impl AppState {
+    /// Creates the application with a fresh universe built from `config`.
+    pub fn new(config: Config) -> Self {
+        let universe = config.new_universe();
+        let history = History::new(config.history_len);
+        let timestep = config.ticks_per_second.map(FixedTimestep::new);
+        let panel = ControlPanel::new(config.window_size, universe.entanglement_percentage);
+        let autosave = config
+            .autosave
+            .clone()
+            .map(|policy| Autosaver::new(policy, 0, Instant::now()));
+        AppState {
+            config,
+            universe,
+            mouse_pos: [0.0, 0.0],
+            current_tool: ToolMode::Observe,
+            entangle_first_partner: None,
+            entangle_drag: false,
+            disrupt_charge: 0.0,
+            paint_rate: Rate::Observation,
+            entanglement_flashes: Vec::new(),
+            is_left_mouse_down: false,
+            is_right_mouse_down: false,
+            is_middle_mouse_down: false,
+            clipboard: None,
+            keymap: Keymap::default(),
+            camera: Camera::default(),
+            hidpi_factor: 1.0,
+            overlays: Overlays::default(),
+            color_mapping: ColorMapping::GA_BLEND,
+            view_mode: ViewMode::default(),
+            watch_list: Vec::new(),
+            history,
+            paused: false,
+            modifiers: Modifiers::default(),
+            last_report: TickReport::default(),
+            sonifier: Sonifier::default(),
+            soft_real_time: false,
+            timestep,
+            plugins: PluginRegistry::with_builtins(),
+            scripts: Scripts::default(),
+            recorder: None,
+            stats_log: None,
+            capture: None,
+            playback: None,
+            zen: None,
+            schedule: None,
+            #[cfg(feature = "gpu")]
+            gpu: None,
+            selection: None,
+            selection_anchor: None,
+            copied_pattern: None,
+            library: PatternLibrary::bundled(),
+            panel,
+            volume: None,
+            inspected: None,
+            hud: Hud::default(),
+            profiler: Profiler::default(),
+            metrics: MetricHistory::default(),
+            operator_preset: OperatorPreset::default(),
+            operator_sample: None,
+            operator_snap: 1,
+            operator_shape: Shape::default(),
+            shape_drag: None,
+            autosave,
+            multiverse: None,
+            worker: None,
+            worker_job: None,
+            edits: 0,
+        }
+    }
+
+    /// Swaps in a different universe, keeping the current tick tuning and
+    /// discarding the old one's undo history.
+    ///
+    /// A recording of the old universe is finished and saved first.
+    pub fn replace_universe(&mut self, mut universe: Universe) {
+        if self.recorder.is_some() {
+            self.toggle_recording();
+        }
+        universe.tuning = self.universe.tuning;
+        if universe.grid_dims.len() != 3 {
+            self.volume = None;
+        }
+        self.universe = universe;
+        self.inspected = None;
+        self.metrics.clear();
+        self.history.clear();
+        self.edits += 1;
+    }
+
+    /// Ticks the universes of `multiverse` together from now on, showing its
+    /// active universe.
+    pub fn attach_multiverse(&mut self, multiverse: Multiverse) {
+        self.replace_universe(multiverse.active_universe().clone());
+        self.multiverse = Some(multiverse);
+    }
+
+    /// Shows the next universe of the multiverse, if there is one, the way
+    /// [`AppState::replace_universe`] would.
+    pub fn next_universe(&mut self) {
+        if self.multiverse.is_none() {
+            return;
+        }
+        if self.recorder.is_some() {
+            self.toggle_recording();
+        }
+        let Some(multiverse) = &mut self.multiverse else {
+            return;
+        };
+        let tuning = self.universe.tuning;
+        std::mem::swap(
+            &mut self.universe,
+            &mut multiverse.universes[multiverse.active],
+        );
+        multiverse.active = (multiverse.active + 1) % multiverse.universes.len();
+        self.universe = multiverse.active_universe().clone();
+        self.universe.tuning = tuning;
+        self.history.clear();
+        self.entangle_first_partner = None;
+        self.entanglement_flashes.clear();
+    }
+
+    /// The universe to draw: the replay being watched, or else the live one.
+    pub fn shown_universe(&self) -> &Universe {
+        self.playback
+            .as_ref()
+            .map_or(&self.universe, |playback| playback.universe())
+    }
+
+    /// The color the cell at grid index `idx` of the shown universe is drawn
+    /// in, under the current view mode.
+    pub fn cell_color(&self, idx: usize) -> [f32; 4] {
+        self.view_mode
+            .color(self.shown_universe(), idx, &self.color_mapping)
+    }
+
+    /// Tells the HUD a frame was drawn `seconds` after the previous one.
+    pub fn record_frame(&mut self, seconds: f64) {
+        let universe = self
+            .playback
+            .as_ref()
+            .map_or(&self.universe, |playback| playback.universe());
+        self.hud.record_frame(seconds, universe);
+    }
+
+    /// The cursor position in grid-space pixels, with the camera undone.
+    pub fn cursor_world_pos(&self) -> [f64; 2] {
+        self.camera.screen_to_world(self.mouse_pos)
+    }
+
+    /// Applies a single action to the state.
+    pub fn apply(&mut self, action: AppAction) {
+        // The live universe stays frozen while a replay is watched.
+        if self.playback.is_some() && action.edits_universe() {
+            return;
+        }
+        // Edits that are not logged interventions leave a worker's tick stale.
+        if matches!(
+            action,
+            AppAction::Undo
+                | AppAction::Redo
+                | AppAction::Scrub(_)
+                | AppAction::LoadSession
+                | AppAction::NextUniverse
+                | AppAction::ToggleZen
+        ) {
+            self.edits += 1;
+        }
+        match action {
+            AppAction::SelectTool(tool) => {
+                self.current_tool = tool;
+                self.entangle_first_partner = None;
+            }
+            AppAction::CycleOperatorPreset => {
+                if self.current_tool == ToolMode::Operator {
+                    // Cycling from a sampled state returns to the preset it left.
+                    if self.operator_sample.take().is_none() {
+                        self.operator_preset = self.operator_preset.next();
+                    }
+                } else if self.current_tool == ToolMode::Paint {
+                    let next = (self.paint_rate.index() + 1) % Rate::ALL.len();
+                    self.paint_rate = Rate::ALL[next];
+                    // A view of the painted field follows it to the next rate.
+                    if let ViewMode::Field(_) = self.view_mode {
+                        self.view_mode = ViewMode::Field(self.paint_rate);
+                    }
+                }
+            }
+            AppAction::SampleOperator => self.sample_operator(),
+            AppAction::CycleOperatorShape => {
+                if self.current_tool == ToolMode::Operator {
+                    self.operator_shape = self.operator_shape.next();
+                    self.shape_drag = None;
+                }
+            }
+            AppAction::CycleOperatorSnap => {
+                let at = OPERATOR_SNAPS.iter().position(|&n| n == self.operator_snap);
+                self.operator_snap =
+                    OPERATOR_SNAPS[at.map_or(0, |at| (at + 1) % OPERATOR_SNAPS.len())];
+            }
+            AppAction::CancelSelection => self.entangle_first_partner = None,
+            AppAction::ResetUniverse => {
+                let universe = self.config.new_universe();
+                self.replace_universe(universe);
+            }
+            AppAction::CycleBoundary => {
+                self.history.record(&self.universe);
+                let next = self.universe.boundary.next();
+                self.universe.set_boundary(next);
+            }
+            AppAction::RewireEntanglement => {
+                self.history.record(&self.universe);
+                let percentage = self.universe.entanglement_percentage;
+                self.universe.rewire_entanglement(percentage);
+            }
+            // Copying and pasting share codes and patterns share Ctrl+C and
+            // Ctrl+V: a selection is copied as a pattern, and a clipboard
+            // without a share code pastes a pattern.
+            AppAction::CopyShareCode if self.selection.is_some() => self.copy_pattern(),
+            AppAction::CopyShareCode => self.copy_share_code(),
+            AppAction::PasteShareCode if self.clipboard_has_share_code() => self.paste_share_code(),
+            AppAction::PasteShareCode => {
+                self.history.record(&self.universe);
+                self.paste_pattern();
+            }
+            AppAction::CopyPattern => self.copy_pattern(),
+            AppAction::CutPattern => {
+                self.history.record(&self.universe);
+                self.cut_pattern();
+            }
+            AppAction::PastePattern => {
+                self.history.record(&self.universe);
+                self.paste_pattern();
+            }
+            AppAction::ToggleLibrary => self.library.open = !self.library.open,
+            // While the library is open, the up and down arrows choose a pattern.
+            AppAction::NudgeSelection([_, down]) if self.library.open => {
+                self.library.step(down > 0);
+            }
+            AppAction::NudgeSelection(offset) => self.nudge_selection(offset),
+            AppAction::ToggleHelp => self.overlays.help = !self.overlays.help,
+            AppAction::ToggleWatch => {
+                let coord = get_coord_from_pos(
+                    self.cursor_world_pos(),
+                    &self.config,
+                    self.universe.topology(),
+                );
+                if let Some(idx) = self.universe.get_index_from_coord(&coord) {
+                    match self.watch_list.iter().position(|&w| w == idx) {
+                        Some(pos) => {
+                            self.watch_list.remove(pos);
+                        }
+                        None => self.watch_list.push(idx),
+                    }
+                }
+            }
+            AppAction::SaveSession => {
+                match Session::capture(self).save(&self.config.session_path) {
+                    Ok(()) => println!("Session saved to {}", self.config.session_path.display()),
+                    Err(e) => eprintln!("Could not save session: {}", e),
+                }
+            }
+            AppAction::LoadSession => match Session::load(&self.config.session_path) {
+                Ok(session) => session.restore(self),
+                Err(e) => eprintln!("Could not load session: {}", e),
+            },
+            AppAction::ToggleSoftRealTime => self.soft_real_time = !self.soft_real_time,
+            AppAction::Undo => {
+                self.paused = true;
+                if self.history.undo(&mut self.universe) {
+                    self.entanglement_flashes.clear();
+                }
+            }
+            AppAction::Redo => {
+                if self.history.redo(&mut self.universe) {
+                    self.entanglement_flashes.clear();
+                }
+            }
+            AppAction::NextUniverse => self.next_universe(),
+            AppAction::TogglePause => self.paused = !self.paused,
+            AppAction::ChangeSpeed(faster) => {
+                // Ticking once per frame speeds up or slows down from 60 a second.
+                let rate = match Setting::TickRate.get(self) {
+                    rate if rate < 1.0 => 60.0,
+                    rate => rate,
+                };
+                let rate = if faster { rate * 2.0 } else { rate / 2.0 };
+                Setting::TickRate.set(self, rate.max(1.0));
+            }
+            AppAction::ToggleZen => match self.zen.take() {
+                Some(zen) => zen.stop(&mut self.universe, &mut self.camera),
+                None => self.zen = Some(ZenMode::new(&self.universe, self.camera)),
+            },
+            AppAction::ToggleRecording => self.toggle_recording(),
+            AppAction::ToggleCapture => self.toggle_capture(),
+            AppAction::TogglePlayback => self.toggle_playback(),
+            // With a selection up, the left and right arrows move it instead.
+            AppAction::Scrub(forward)
+                if self.current_tool == ToolMode::Select
+                    && self.selection.is_some()
+                    && self.playback.is_none() =>
+            {
+                self.nudge_selection([if forward { 1 } else { -1 }, 0]);
+            }
+            AppAction::Scrub(forward) => {
+                if let Some(playback) = &mut self.playback {
+                    self.paused = true;
+                    playback.step(forward);
+                } else if !forward && self.universe.is_reversible() {
+                    // A reversible universe steps back through its own past.
+                    self.paused = true;
+                    if self.universe.tick_backward() {
+                        self.entanglement_flashes.clear();
+                    }
+                }
+            }
+            AppAction::SetModifiers(modifiers) => self.modifiers = modifiers,
+            AppAction::TogglePluginPanel => self.overlays.plugins = !self.overlays.plugins,
+            AppAction::TogglePlugin(index) => {
+                self.plugins.toggle(index);
+            }
+            AppAction::ToggleControlPanel => self.panel.open = !self.panel.open,
+            AppAction::ToggleHud => self.hud.visible = !self.hud.visible,
+            AppAction::ToggleProfiler => {
+                self.profiler.visible = !self.profiler.visible;
+                self.profiler.clear();
+            }
+            AppAction::TogglePlot => self.overlays.plot = !self.overlays.plot,
+            AppAction::ToggleEntanglementLinks => {
+                self.overlays.entanglement_links = !self.overlays.entanglement_links
+            }
+            AppAction::ToggleSound => self.sonifier.toggle_mute(),
+            AppAction::CycleViewMode => self.view_mode = self.view_mode.next(),
+            AppAction::SetSetting(setting, value) => setting.set(self, value),
+            AppAction::ToggleVolume => {
+                self.volume = match self.volume {
+                    None if self.universe.grid_dims.len() == 3 => Some(OrbitCamera::default()),
+                    _ => None,
+                };
+            }
+            AppAction::MoveCursor(pos) => {
+                let delta = [pos[0] - self.mouse_pos[0], pos[1] - self.mouse_pos[1]];
+                if self.is_middle_mouse_down {
+                    self.camera.pan(delta);
+                }
+                if self.is_left_mouse_down
+                    && let Some(volume) = &mut self.volume
+                {
+                    volume.orbit(delta);
+                }
+                self.mouse_pos = pos;
+                if let Some(setting) = self.panel.dragging {
+                    self.drag_slider(setting);
+                }
+            }
+            AppAction::PressPrimary if self.panel.contains(self.mouse_pos) => {
+                match self.panel.control_at(self.mouse_pos) {
+                    Some(Control::Slider(setting)) => {
+                        self.panel.dragging = Some(setting);
+                        self.drag_slider(setting);
+                    }
+                    Some(Control::Button(button)) => self.apply(button.action()),
+                    None => {}
+                }
+            }
+            AppAction::PressPrimary if self.over_toolbar() => {
+                let window = self.config.window_size;
+                if let Some(item) = Toolbar::item_at(window, self.mouse_pos) {
+                    self.apply(item.action());
+                }
+            }
+            AppAction::PressPrimary if self.over_minimap() => self.jump_to_minimap(),
+            AppAction::PressPrimary if self.volume.is_some() => self.is_left_mouse_down = true,
+            AppAction::PressPrimary if self.library.open => self.stamp_library_pattern(),
+            AppAction::PressPrimary
+                if self.current_tool == ToolMode::Operator && self.modifiers.alt =>
+            {
+                self.sample_operator()
+            }
+            AppAction::PressPrimary if self.current_tool == ToolMode::Inspect => {
+                self.pin_inspected()
+            }
+            AppAction::PressPrimary => {
+                // A whole stroke undoes in one step.
+                self.history.record(&self.universe);
+                self.is_left_mouse_down = true;
+                self.handle_mouse_click();
+                self.run_click_scripts();
+            }
+            AppAction::ReleasePrimary => {
+                self.is_left_mouse_down = false;
+                self.disrupt_charge = 0.0;
+                if std::mem::take(&mut self.entangle_drag) {
+                    self.finish_entangle_drag();
+                }
+                self.commit_shape();
+                self.selection_anchor = None;
+                // Rewiring is an edit, so it waits for the slider to be let go.
+                if self.panel.dragging.take() == Some(Setting::EntanglementPercentage) {
+                    Setting::EntanglementPercentage.set(self, self.panel.entanglement);
+                }
+            }
+            AppAction::PressSecondary if self.panel.contains(self.mouse_pos) => {}
+            AppAction::PressSecondary if self.over_toolbar() || self.over_minimap() => {}
+            AppAction::PressSecondary if self.volume.is_some() => {}
+            AppAction::PressSecondary if self.current_tool == ToolMode::Entangle => {
+                self.entangle_first_partner = None
+            }
+            AppAction::PressSecondary => {
+                self.history.record(&self.universe);
+                self.is_right_mouse_down = true;
+                // For now, let right-click only work in Operator mode
+                if self.current_tool == ToolMode::Operator {
+                    let clicked_coord = get_coord_from_pos(
+                        self.cursor_world_pos(),
+                        &self.config,
+                        self.universe.topology(),
+                    );
+                    self.universe.clear_operator(&clicked_coord);
+                }
+            }
+            AppAction::ReleaseSecondary => self.is_right_mouse_down = false,
+            AppAction::PressMiddle => self.is_middle_mouse_down = true,
+            AppAction::ReleaseMiddle => self.is_middle_mouse_down = false,
+            AppAction::Zoom(notches) => {
+                let factor = ZOOM_PER_NOTCH.powf(notches);
+                self.camera.zoom_about(self.mouse_pos, factor);
+            }
+            AppAction::ResetView => {
+                self.camera = Camera::fit(self.config.grid_extent(), self.config.window_size)
+            }
+            AppAction::Resize(size, hidpi_factor) => self.resize(size, hidpi_factor),
+            AppAction::ResizeBrush(steps) => {
+                if let Some(radius) = self.config.brush_radius_mut(self.current_tool) {
+                    *radius = (*radius * BRUSH_PER_STEP.powf(steps))
+                        .clamp(MIN_BRUSH_RADIUS, MAX_BRUSH_RADIUS);
+                }
+            }
+            AppAction::StepSlice(forward) => {
+                self.config.slice.step(&self.universe.grid_dims, forward);
+            }
+            AppAction::CycleDepthAxis => {
+                self.config.slice.cycle_depth(self.universe.grid_dims.len());
+            }
+            AppAction::CycleViewAxes => {
+                self.config.slice.cycle_axes(self.universe.grid_dims.len());
+            }
+            AppAction::Tick => self.update(None),
+            AppAction::Advance(seconds) => self.update(Some(seconds)),
+        }
+    }
+
+    /// Moves `setting`'s slider to the cursor.
+    fn drag_slider(&mut self, setting: Setting) {
+        let value = self.panel.slider_value(setting, self.mouse_pos[0]);
+        match setting {
+            Setting::EntanglementPercentage => self.panel.entanglement = value,
+            _ => setting.set(self, value),
+        }
+    }
+
+    /// Runs the ticks due, one when `elapsed` is `None` or there is no fixed
+    /// timestep, and ages the entanglement flashes.
+    fn update(&mut self, elapsed: Option<f64>) {
+        // The universe is paused while an entanglement pair is being selected.
+        let running =
+            !self.paused && (self.playback.is_some() || self.entangle_first_partner.is_none());
+        let steps = match (&mut self.timestep, elapsed) {
+            _ if !running => 0,
+            (Some(timestep), Some(seconds)) => timestep.advance(seconds),
+            _ => 1,
+        };
+        let mut completed = 0;
+        if let Some(playback) = &mut self.playback {
+            while completed < steps && !playback.at_end() {
+                playback.step(true);
+                completed += 1;
+            }
+        } else {
+            // A budgeted or threaded tick may need several frames to complete,
+            // and a budgeted frame has no time for another after it.
+            while completed < steps && self.step() {
+                completed += 1;
+                if self.soft_real_time {
+                    break;
+                }
+            }
+            if completed > 0 {
+                self.autosave_if_due(Instant::now());
+            }
+            if let Some(zen) = &mut self.zen {
+                let report = (completed > 0).then_some(&self.last_report);
+                zen.update(&mut self.universe, &mut self.camera, &self.config, report);
+            }
+        }
+        if let Some(timestep) = &mut self.timestep
+            && elapsed.is_some()
+        {
+            timestep.record(completed);
+        }
+        self.capture_frame();
+        self.age_flashes();
+        if self.is_left_mouse_down && self.current_tool == ToolMode::Disrupt {
+            let seconds = elapsed.unwrap_or(1.0 / 60.0);
+            self.disrupt_charge = (self.disrupt_charge + seconds / DISRUPT_CHARGE_SECONDS).min(1.0);
+        }
+        if self.current_tool == ToolMode::Paint
+            && self.is_left_mouse_down != self.is_right_mouse_down
+        {
+            self.paint_field(elapsed.unwrap_or(1.0 / 60.0), self.is_left_mouse_down);
+        }
+    }
+
+    /// Takes a frame for the animated capture, if one is running and due.
+    fn capture_frame(&mut self) {
+        let Some(capture) = &mut self.capture else {
+            return;
+        };
+        let universe = self
+            .playback
+            .as_ref()
+            .map_or(&self.universe, |playback| playback.universe());
+        if !universe.is_mid_tick() {
+            capture.capture(universe, &self.config.slice, |idx| {
+                self.view_mode.color(universe, idx, &self.color_mapping)
+            });
+        }
+    }
+
+    /// Starts an animated capture, or stops it and encodes the file.
+    fn toggle_capture(&mut self) {
+        let Some(capture) = self.capture.take() else {
+            self.capture = Some(Capture::new(self.config.capture));
+            self.capture_frame();
+            return;
+        };
+        let path = &self.config.capture_path;
+        match capture.finish(path) {
+            Ok(frames) => println!("{} frames captured to {}", frames, path.display()),
+            Err(e) => eprintln!("Could not save capture: {}", e),
+        }
+    }
+
+    /// Follows the window to `size` points: the view scales with the fit of
+    /// the grid, keeping the same part of it in the middle, and the control
+    /// panel stays at the right edge. A minimized window keeps the old layout.
+    fn resize(&mut self, size: [f64; 2], hidpi_factor: f64) {
+        if hidpi_factor.is_finite() && hidpi_factor > 0.0 {
+            self.hidpi_factor = hidpi_factor;
+        }
+        let old = self.config.window_size;
+        if size[0] < 1.0 || size[1] < 1.0 || size == old {
+            return;
+        }
+        let extent = self.config.grid_extent();
+        let scale = Camera::fit(extent, size).zoom / Camera::fit(extent, old).zoom;
+        let center = self.camera.screen_to_world([old[0] / 2.0, old[1] / 2.0]);
+        let zoom = (self.camera.zoom * scale).clamp(Camera::MIN_ZOOM, Camera::MAX_ZOOM);
+        self.camera = Camera {
+            offset: [
+                size[0] / 2.0 - center[0] * zoom,
+                size[1] / 2.0 - center[1] * zoom,
+            ],
+            zoom,
+        };
+        self.panel.origin[0] += size[0] - old[0];
+        self.config.window_size = size;
+    }
+
+    /// Works on the next tick of the live universe, returning whether it
+    /// completed.
+    fn step(&mut self) -> bool {
+        let interval = self.universe.tuning.snapshot_interval.max(1);
+        let between_ticks = !self.universe.is_mid_tick() && self.worker_job.is_none();
+        if between_ticks && self.universe.tick_count.is_multiple_of(interval) {
+            self.history.record(&self.universe);
+        }
+        if let Some(schedule) = &self.schedule
+            && between_ticks
+        {
+            schedule.apply(&mut self.universe);
+        }
+        self.universe.profiling = self.profiler.visible;
+        let report = if self.multiverse.is_some() {
+            Some(self.multiverse_tick())
+        } else if self.worker.is_some() {
+            self.worker_tick()
+        } else if self.soft_real_time {
+            self.universe.tick_within(self.config.tick_budget)
+        } else {
+            Some(self.full_tick())
+        };
+        let Some(report) = report else {
+            return false;
+        };
+        for (coord1, coord2) in &report.entanglements {
+            self.entanglement_flashes
+                .push((coord1.clone(), coord2.clone(), 15));
+        }
+        if let Some(recorder) = &mut self.recorder {
+            recorder.on_tick(&self.universe);
+        }
+        self.plugins.on_tick(&PluginCtx {
+            universe: &self.universe,
+            report: &report,
+        });
+        self.metrics.record(Sample::of(&self.universe, &report));
+        if let Some(log) = &mut self.stats_log
+            && let Err(e) = log.record(&self.universe, &report)
+        {
+            eprintln!("Stopped logging statistics: {}", e);
+            self.stats_log = None;
+        }
+        self.sonifier.listen(&report, &self.universe.grid_dims);
+        self.scripts.on_tick(&mut self.universe);
+        if self.profiler.visible {
+            self.profiler.add_tick(self.universe.tick_timings());
+        }
+        self.last_report = report;
+        true
+    }
+
+    /// Checkpoints the session if the autosave policy says it is time.
+    pub fn autosave_if_due(&mut self, now: Instant) {
+        let tick = self.universe.tick_count;
+        if !self.autosave.as_ref().is_some_and(|a| a.is_due(tick, now)) {
+            return;
+        }
+        let session = Session::capture(self);
+        if let Some(autosave) = &mut self.autosave
+            && let Err(e) = autosave.save(&session, now)
+        {
+            eprintln!("Could not autosave: {}", e);
+            // Try again after another interval rather than every tick.
+            autosave.reset(tick, now);
+        }
+    }
+
+    /// Restores the newest readable autosave checkpoint, returning its path, or
+    /// `None` if there is none.
+    pub fn resume_autosave(&mut self) -> Option<PathBuf> {
+        let policy = self.config.autosave.as_ref()?;
+        let (path, session) = Autosaver::latest(policy)?;
+        session.restore(self);
+        if let Some(autosave) = &mut self.autosave {
+            autosave.reset(self.universe.tick_count, Instant::now());
+        }
+        Some(path)
+    }
+
+    /// Ticks the whole multiverse with the live universe in its slot,
+    /// returning the live universe's report. Ticks are never spread over
+    /// frames here, since the other universes tick in one go anyway.
+    fn multiverse_tick(&mut self) -> TickReport {
+        let Some(multiverse) = &mut self.multiverse else {
+            return self.full_tick();
+        };
+        let active = multiverse.active;
+        std::mem::swap(&mut self.universe, &mut multiverse.universes[active]);
+        let mut report = multiverse.tick();
+        std::mem::swap(&mut self.universe, &mut multiverse.universes[active]);
+        report.reports.swap_remove(active)
+    }
+
+    /// Sends the live universe to the worker for a tick, or adopts the ticked
+    /// copy once it is back, returning its report then.
+    ///
+    /// The copy keeps the interventions made since it was sent, applied again
+    /// after its tick, and the current rates and tuning, which zen mode and
+    /// the sliders change without an edit. After any other edit it is stale
+    /// and dropped, and the next call sends a fresh one.
+    fn worker_tick(&mut self) -> Option<TickReport> {
+        let worker = self.worker.as_ref()?;
+        let Some(job) = &mut self.worker_job else {
+            worker.send(Command::Load(Box::new(self.universe.clone())));
+            worker.send(Command::Tick(1));
+            worker.send(Command::Snapshot);
+            self.worker_job = Some(WorkerJob {
+                edits: self.edits,
+                interventions: self.universe.interventions.len(),
+                report: None,
+            });
+            return None;
+        };
+        let mut ticked = None;
+        while let Some(update) = worker.try_recv() {
+            match update {
+                Update::Ticked { report, .. } => job.report = Some(*report),
+                Update::Snapshot(universe) => ticked = Some(universe),
+            }
+        }
+        let mut ticked = *ticked?;
+        let job = self.worker_job.take()?;
+        if job.edits != self.edits {
+            return None;
+        }
+        for entry in &self.universe.interventions[job.interventions..] {
+            ticked.apply(entry.intervention.clone());
+        }
+        ticked.observation_rate = self.universe.observation_rate;
+        ticked.decay_rate = self.universe.decay_rate;
+        ticked.fluctuation_rate = self.universe.fluctuation_rate;
+        for rate in Rate::ALL {
+            ticked.set_parameter_field(rate, self.universe.parameter_field(rate).cloned());
+        }
+        ticked.tuning = self.universe.tuning;
+        self.universe = ticked;
+        job.report
+    }
+
+    /// Runs a whole tick, on the GPU when one is available.
+    fn full_tick(&mut self) -> TickReport {
+        #[cfg(feature = "gpu")]
+        if let Some(gpu) = &mut self.gpu {
+            match gpu.tick(&mut self.universe) {
+                Ok(report) => return report,
+                Err(e) => {
+                    eprintln!("Falling back to the CPU: {}", e);
+                    self.gpu = None;
+                }
+            }
+        }
+        self.universe.tick()
+    }
+
+    /// Counts down the entanglement flashes, dropping those that have faded.
+    fn age_flashes(&mut self) {
+        self.entanglement_flashes.retain_mut(|(_, _, ttl)| {
+            *ttl = ttl.saturating_sub(1);
+            *ttl > 0
+        });
+    }
+
+    /// Starts recording the live universe, or finishes the recording and
+    /// writes it to the replay file.
+    fn toggle_recording(&mut self) {
+        let Some(recorder) = self.recorder.take() else {
+            self.recorder = Some(Recorder::start(&self.universe, self.config.record_mode));
+            return;
+        };
+        let replay = recorder.finish(&self.universe);
+        match replay.save(&self.config.replay_path) {
+            Ok(()) => println!(
+                "Ticks {} to {} recorded to {}",
+                replay.start,
+                replay.end(),
+                self.config.replay_path.display()
+            ),
+            Err(e) => eprintln!("Could not save recording: {}", e),
+        }
+    }
+
+    /// Opens the replay file for watching, or returns to the live universe.
+    fn toggle_playback(&mut self) {
+        if self.playback.take().is_some() {
+            return;
+        }
+        match Replay::load(&self.config.replay_path) {
+            Ok(replay) => {
+                self.playback = Some(Playback::new(replay));
+                self.entanglement_flashes.clear();
+            }
+            Err(e) => eprintln!("Could not load recording: {}", e),
+        }
+    }
+
+    /// Copies a share code that reproduces the current universe to the system clipboard.
+    fn copy_share_code(&mut self) {
+        let code = Scenario::capture(&self.universe).encode();
+        match self.clipboard.as_mut().map(|cb| cb.set_text(code.clone())) {
+            Some(Ok(())) => println!("Share code copied to clipboard ({} chars)", code.len()),
+            Some(Err(e)) => eprintln!("Could not copy share code: {}\n{}", e, code),
+            // Without a clipboard, the terminal is the only place to share from.
+            None => println!("{}", code),
+        }
+    }
+
+    /// Whether the system clipboard holds a share code.
+    fn clipboard_has_share_code(&mut self) -> bool {
+        self.clipboard
+            .as_mut()
+            .and_then(|cb| cb.get_text().ok())
+            .is_some_and(|text| Scenario::decode(&text).is_ok())
+    }
+
+    /// Replaces the universe with the scenario encoded in the clipboard's share code.
+    fn paste_share_code(&mut self) {
+        let Some(text) = self.clipboard.as_mut().and_then(|cb| cb.get_text().ok()) else {
+            eprintln!("Clipboard does not contain text");
+            return;
+        };
+        match Scenario::decode(&text) {
+            Ok(scenario) => self.replace_universe(scenario.build()),
+            Err(e) => eprintln!("Could not import share code: {}", e),
+        }
+    }
+
+    /// Copies the selected rectangle as a pattern, to the system clipboard when
+    /// there is one.
+    fn copy_pattern(&mut self) {
+        let Some(region) = &self.selection else {
+            eprintln!("Select a rectangle with the Select tool [6] first");
+            return;
+        };
+        let pattern = self.universe.extract_pattern(region);
+        self.hold_pattern(pattern);
+    }
+
+    /// Copies the selected rectangle as a pattern like
+    /// [`AppState::copy_pattern`], then empties it.
+    fn cut_pattern(&mut self) {
+        let Some(region) = &self.selection else {
+            eprintln!("Select a rectangle with the Select tool [6] first");
+            return;
+        };
+        let pattern = self.universe.cut_pattern(region);
+        self.hold_pattern(pattern);
+    }
+
+    /// Keeps a copied or cut pattern for pasting.
+    fn hold_pattern(&mut self, pattern: Pattern) {
+        let code = pattern.encode();
+        match self.clipboard.as_mut().map(|cb| cb.set_text(code.clone())) {
+            Some(Ok(())) => println!("Pattern copied to clipboard ({} chars)", code.len()),
+            Some(Err(e)) => eprintln!("Could not copy pattern: {}\n{}", e, code),
+            None => println!("{}", code),
+        }
+        self.copied_pattern = Some(pattern);
+    }
+
+    /// Stamps the clipboard's pattern, or else the last one copied, with its
+    /// corner at the cursor.
+    fn paste_pattern(&mut self) {
+        let from_clipboard = self
+            .clipboard
+            .as_mut()
+            .and_then(|cb| cb.get_text().ok())
+            .and_then(|text| Pattern::decode(&text).ok());
+        let Some(pattern) = from_clipboard.or_else(|| self.copied_pattern.clone()) else {
+            eprintln!("No pattern to paste");
+            return;
+        };
+        let at = get_coord_from_pos(
+            self.cursor_world_pos(),
+            &self.config,
+            self.universe.topology(),
+        );
+        self.universe.stamp_pattern(&pattern, &at);
+    }
+
+    /// The state the Operator tool places: the eyedropper's sample, or the
+    /// preset's.
+    pub fn operator_state(&self) -> Multivector {
+        match &self.operator_sample {
+            Some(state) => state.clone(),
+            None => self.operator_preset.state(self.universe.ga_dims),
+        }
+    }
+
+    /// The cell a left click with the Operator tool would turn into an operator
+    /// now: the one under the cursor, or with [`AppState::operator_snap`] the
+    /// nearest on its lattice. None while the cursor is off the grid or over
+    /// the interface.
+    pub fn operator_target(&self) -> Option<Vec<usize>> {
+        if self.volume.is_some()
+            || self.panel.contains(self.mouse_pos)
+            || self.over_toolbar()
+            || self.over_minimap()
+        {
+            return None;
+        }
+        let universe = &self.universe;
+        let mut coord =
+            get_coord_from_pos(self.cursor_world_pos(), &self.config, universe.topology());
+        universe.get_index_from_coord(&coord)?;
+        let snap = self.operator_snap.max(1);
+        let dims = universe.grid_dims.len();
+        for axis in self.config.slice.axes.into_iter().take(dims) {
+            let len = universe.grid_dims[axis];
+            let nearest = (coord[axis] + snap / 2) / snap * snap;
+            coord[axis] = if nearest < len {
+                nearest
+            } else {
+                (len - 1) / snap * snap
+            };
+        }
+        Some(coord)
+    }
+
+    /// The cells the shape being dragged out with the Operator tool covers,
+    /// those off the grid left out; none while no shape is being dragged.
+    pub fn shape_cells(&self) -> Vec<Vec<usize>> {
+        let Some((start, end)) = &self.shape_drag else {
+            return Vec::new();
+        };
+        let [across, down] = self.config.slice.axes;
+        let flat = start.len() < 2;
+        let plane = |coord: &[usize]| {
+            let y = if flat { 0 } else { coord[down] as i64 };
+            [coord[across] as i64, y]
+        };
+        let points = self.operator_shape.points(plane(start), plane(end));
+        points
+            .into_iter()
+            .filter_map(|[x, y]| {
+                let mut coord = start.clone();
+                coord[across] = usize::try_from(x).ok()?;
+                if !flat {
+                    coord[down] = usize::try_from(y).ok()?;
+                } else if y != 0 {
+                    return None;
+                }
+                self.universe.get_index_from_coord(&coord)?;
+                Some(coord)
+            })
+            .collect()
+    }
+
+    /// Turns every cell of the shape being dragged out into an operator, and
+    /// ends the drag.
+    fn commit_shape(&mut self) {
+        let cells = self.shape_cells();
+        if self.shape_drag.take().is_none() {
+            return;
+        }
+        let state = self.operator_state();
+        for coord in cells {
+            self.universe.set_operator_with_state(&coord, state.clone());
+        }
+    }
+
+    /// The cell under the cursor, if a drag from the first entanglement partner
+    /// let go there would pair them: a `Potential` cell other than the first.
+    pub fn entangle_drag_target(&self) -> Option<usize> {
+        let first = self.entangle_first_partner?;
+        let universe = &self.universe;
+        let coord = get_coord_from_pos(self.cursor_world_pos(), &self.config, universe.topology());
+        let idx = universe.get_index_from_coord(&coord)?;
+        let cell = &universe.grid[idx];
+        (cell.id != first && cell.consciousness == ConsciousnessState::Potential).then_some(idx)
+    }
+
+    /// Ends a drag from the first entanglement partner: over a partner the two
+    /// are paired, over the first cell the selection stays open for a second
+    /// click, and anywhere else it is dropped.
+    fn finish_entangle_drag(&mut self) {
+        let Some(first) = self.entangle_first_partner else {
+            return;
+        };
+        if let Some(idx) = self.entangle_drag_target() {
+            self.entangle_with_first_partner(idx);
+            return;
+        }
+        let coord = get_coord_from_pos(
+            self.cursor_world_pos(),
+            &self.config,
+            self.universe.topology(),
+        );
+        let on_first = self
+            .universe
+            .get_index_from_coord(&coord)
+            .is_some_and(|idx| self.universe.grid[idx].id == first);
+        if !on_first {
+            self.entangle_first_partner = None;
+        }
+    }
+
+    /// Entangles the cell at `idx` with the first partner and flashes the link.
+    fn entangle_with_first_partner(&mut self, idx: usize) {
+        let Some(id1) = self.entangle_first_partner else {
+            return;
+        };
+        let universe = &mut self.universe;
+        let id2 = universe.grid[idx].id;
+        if id1 == id2 {
+            return;
+        }
+        universe.entangle_pair(id1, id2);
+        if let (Some(coord1), Some(coord2)) = (universe.coord_of_id(id1), universe.coord_of_id(id2))
+        {
+            self.entanglement_flashes.push((coord1, coord2, 15));
+        }
+        // With Shift held, further clicks keep adding to the same group.
+        if !self.modifiers.shift {
+            self.entangle_first_partner = None;
+        }
+    }
+
+    /// Picks up the state of the cell under the cursor in the universe shown
+    /// for the Operator tool, and switches to it.
+    fn sample_operator(&mut self) {
+        let universe = self.shown_universe();
+        let coord = get_coord_from_pos(self.cursor_world_pos(), &self.config, universe.topology());
+        if let Some(idx) = universe.get_index_from_coord(&coord) {
+            self.operator_sample = Some(universe.grid[idx].state.clone());
+            self.current_tool = ToolMode::Operator;
+        }
+    }
+
+    /// Stamps the library's chosen pattern with its corner at the cursor.
+    fn stamp_library_pattern(&mut self) {
+        let Some(entry) = self.library.selected() else {
+            return;
+        };
+        let at = get_coord_from_pos(
+            self.cursor_world_pos(),
+            &self.config,
+            self.universe.topology(),
+        );
+        self.history.record(&self.universe);
+        self.universe.stamp_pattern(&entry.pattern, &at);
+    }
+
+    /// Moves the selected rectangle `[across, down]` the view, taking the
+    /// selection along.
+    fn nudge_selection(&mut self, [across, down]: [i64; 2]) {
+        let Some(region) = self.selection.clone() else {
+            return;
+        };
+        let mut offset = vec![0; region.len()];
+        let [x, y] = self.config.slice.axes;
+        if let Some(step) = offset.get_mut(x) {
+            *step += across;
+        }
+        if let Some(step) = offset.get_mut(y) {
+            *step += down;
+        }
+        // The block stops at the edge of the grid.
+        if self.universe.shifted_region(&region, &offset).is_none() {
+            return;
+        }
+        self.history.record(&self.universe);
+        self.selection = self.universe.move_region(&region, &offset);
+    }
+
+    /// Stretches the selection from its anchor to the cell under the cursor.
+    fn drag_selection(&mut self) {
+        let Some(anchor) = &self.selection_anchor else {
+            return;
+        };
+        let cursor = get_coord_from_pos(
+            self.cursor_world_pos(),
+            &self.config,
+            self.universe.topology(),
+        );
+        // Only the displayed plane can be selected; deeper axes keep the anchor's layer.
+        let axes = self.config.slice.axes;
+        let region = anchor
+            .iter()
+            .zip(&cursor)
+            .enumerate()
+            .map(|(d, (&a, &c))| {
+                let c = if axes.contains(&d) { c } else { a };
+                a.min(c)..a.max(c) + 1
+            })
+            .collect();
+        self.selection = Some(region);
+    }
+
+    /// Handles the specific action of a single left mouse click for the active tool.
+    fn handle_mouse_click(&mut self) {
+        let cursor = self.cursor_world_pos();
+        let topology = self.universe.topology();
+        let clicked_coord = get_coord_from_pos(cursor, &self.config, topology);
+        let clicked_idx = self.universe.get_index_from_coord(&clicked_coord);
+        let universe = &mut self.universe;
+
+        match self.current_tool {
+            ToolMode::Observe => {
+                // Strong observation is now a continuous effect while mouse is held down
+            }
+            ToolMode::Entangle => {
+                if let Some(idx) = clicked_idx
+                    && universe.grid[idx].consciousness == ConsciousnessState::Potential
+                {
+                    if self.entangle_first_partner.is_some() {
+                        self.entangle_with_first_partner(idx);
+                    } else {
+                        // Letting go over a partner pairs them; letting go
+                        // here leaves the selection open for a second click.
+                        self.entangle_first_partner = Some(universe.grid[idx].id);
+                        self.entangle_drag = true;
+                    }
+                }
+            }
+            ToolMode::Operator => {
+                // Handled by continuous effect
+            }
+            // Painting goes on for as long as the button is held; see `update`.
+            ToolMode::Paint => {}
+            ToolMode::Disrupt => {
+                // The brush clears at once; the wave carries on outward.
+                self.disrupt_brush(cursor);
+                let amplitude = self.config.disruption_amplitude;
+                self.universe.emit_disruption(&clicked_coord, amplitude);
+            }
+            ToolMode::Select => {
+                self.selection_anchor = Some(clicked_coord);
+                self.drag_selection();
+            }
+            // Inspecting edits nothing, so its clicks never get here.
+            ToolMode::Inspect => {}
+        }
+    }
+
+    /// Runs the scripts' `on_click` hooks for the cell under the cursor, if
+    /// there is one.
+    fn run_click_scripts(&mut self) {
+        let coord = get_coord_from_pos(
+            self.cursor_world_pos(),
+            &self.config,
+            self.universe.topology(),
+        );
+        if self.universe.get_index_from_coord(&coord).is_some() {
+            self.scripts.on_click(&mut self.universe, &coord);
+        }
+    }
+
+    /// Runs the scripts' `on_key` hooks for a key pressed, named as in the
+    /// keybindings file; scripts leave a replay being watched alone.
+    pub fn run_key_scripts(&mut self, key: &str) {
+        if self.playback.is_none() {
+            self.scripts.on_key(&mut self.universe, key);
+        }
+    }
+
+    /// Pins the cell under the cursor for the Inspect tool, or unpins it if
+    /// it is already pinned.
+    fn pin_inspected(&mut self) {
+        let coord = get_coord_from_pos(
+            self.cursor_world_pos(),
+            &self.config,
+            self.universe.topology(),
+        );
+        let Some(idx) = self.universe.get_index_from_coord(&coord) else {
+            return;
+        };
+        let id = self.universe.grid[idx].id;
+        self.inspected = (self.inspected != Some(id)).then_some(id);
+    }
+
+    /// What the Inspect tool shows, while it is the active tool: the pinned
+    /// cell, or else the cell under the cursor.
+    pub fn inspection(&self) -> Option<Inspection> {
+        if self.current_tool != ToolMode::Inspect {
+            return None;
+        }
+        let universe = self.shown_universe();
+        if let Some(id) = self.inspected {
+            return Inspection::of_id(universe, id);
+        }
+        if self.volume.is_some()
+            || self.panel.contains(self.mouse_pos)
+            || self.over_toolbar()
+            || self.over_minimap()
+        {
+            return None;
+        }
+        let coord = get_coord_from_pos(self.cursor_world_pos(), &self.config, universe.topology());
+        Inspection::at(universe, &coord)
+    }
+
+    /// The minimap, while part of the grid is off screen; none in the volume
+    /// view or zen mode. It sits above the toolbar, and above the plot strip
+    /// when that is shown.
+    pub fn minimap(&self) -> Option<Minimap> {
+        if self.volume.is_some() || self.zen.is_some() {
+            return None;
+        }
+        let window = self.config.window_size;
+        let bottom = window[1] - if self.overlays.plot { 150.0 } else { 50.0 };
+        Minimap::of(self.config.grid_extent(), &self.camera, window, bottom)
+    }
+
+    /// The brush radius of the active tool, for the tools that paint with a
+    /// brush, grown by the Disrupt tool's charge.
+    pub fn brush_radius(&self) -> Option<f64> {
+        let radius = self.config.brush_radius(self.current_tool)?;
+        Some(match self.current_tool {
+            ToolMode::Disrupt => radius * (1.0 + self.disrupt_charge),
+            _ => radius,
+        })
+    }
+
+    /// Decays the `Observed` cells in the Disrupt tool's brush around the
+    /// grid-space position `center`, each with the tool's strength weighted by
+    /// its falloff.
+    fn disrupt_brush(&mut self, center: [f64; 2]) {
+        let Some(radius) = self.brush_radius() else {
+            return;
+        };
+        let (boundary, topology) = (self.universe.boundary, self.universe.topology());
+        let (strength, falloff) = (
+            self.config.disruption_strength,
+            self.config.disruption_falloff,
+        );
+        let universe = &mut self.universe;
+        let mut rng = rng();
+        for_cells_in_radius_weighted(
+            &self.config,
+            boundary,
+            topology,
+            center,
+            radius,
+            falloff,
+            |coord, weight| {
+                if let Some(idx) = universe.get_index_from_coord(&coord) {
+                    universe.disrupt_cell_with_probability(idx, strength * weight, &mut rng);
+                }
+            },
+        );
+    }
+
+    /// Raises (or with `raise` false, lowers) the painted rate under the Paint
+    /// tool's brush for `seconds` of holding, doubling or halving it at the
+    /// center and less toward the edge. The first stroke on a rate without a
+    /// parameter field starts one at the global rate.
+    fn paint_field(&mut self, seconds: f64, raise: bool) {
+        let Some(radius) = self.brush_radius() else {
+            return;
+        };
+        if !self.tool_reaches_grid() {
+            return;
+        }
+        let rate = self.paint_rate;
+        let field = match self.universe.parameter_field(rate) {
+            Some(field) => field.clone(),
+            None => ParameterField::uniform(&self.universe.grid_dims, self.universe.rate(rate)),
+        };
+        let doublings = PAINT_DOUBLINGS_PER_SECOND * seconds * if raise { 1.0 } else { -1.0 };
+        let (boundary, topology) = (self.universe.boundary, self.universe.topology());
+        let center = self.cursor_world_pos();
+        let universe = &self.universe;
+        let mut strokes = Vec::new();
+        for_cells_in_radius_weighted(
+            &self.config,
+            boundary,
+            topology,
+            center,
+            radius,
+            Falloff::Linear,
+            |coord, weight| {
+                if let Some(idx) = universe.get_index_from_coord(&coord) {
+                    let value = field.get(idx).max(PAINT_FLOOR) * 2f64.powf(doublings * weight);
+                    strokes.push((idx, if value < PAINT_FLOOR { 0.0 } else { value }));
+                }
+            },
+        );
+        let painted = field.painted(strokes);
+        self.universe.set_parameter_field(rate, Some(painted));
+    }
+
+    /// Whether the cursor is over the toolbar, which zen mode hides.
+    pub fn over_toolbar(&self) -> bool {
+        self.zen.is_none() && Toolbar::contains(self.config.window_size, self.mouse_pos)
+    }
+
+    /// Whether the cursor is over the minimap.
+    fn over_minimap(&self) -> bool {
+        self.minimap()
+            .is_some_and(|map| map.contains(self.mouse_pos))
+    }
+
+    /// Centers the view on the part of the grid under the cursor on the minimap.
+    fn jump_to_minimap(&mut self) {
+        if let Some(world) = self.minimap().and_then(|map| map.world_at(self.mouse_pos)) {
+            self.camera.center_on(world, self.config.window_size);
+        }
+    }
+
+    /// Whether the active tool acts on the grid under the cursor: not while
+    /// a replay or the 3D view shows, a slider is dragged, or the cursor is
+    /// over a panel, the toolbar or the minimap.
+    fn tool_reaches_grid(&self) -> bool {
+        self.playback.is_none()
+            && self.volume.is_none()
+            && self.panel.dragging.is_none()
+            && !self.panel.contains(self.mouse_pos)
+            && !self.over_toolbar()
+            && !self.over_minimap()
+    }
+
+    /// Applies continuous effects for the active tool.
+    ///
+    /// Frontends call this once per processed event, after that event's actions.
+    pub fn apply_tool_effects(&mut self) {
+        if !self.tool_reaches_grid() {
+            return;
+        }
+        let mut rng = rng();
+        let target = match self.current_tool {
+            ToolMode::Operator => self.operator_target(),
+            _ => None,
+        };
+        let (boundary, topology) = (self.universe.boundary, self.universe.topology());
+        let universe = &mut self.universe;
+        let config = &self.config;
+        let mouse_pos = self.camera.screen_to_world(self.mouse_pos);
+        match self.current_tool {
+            ToolMode::Observe => {
+                let passive_observation_prob = 0.1;
+                let radius = config.observation_radius;
+                for_cells_in_radius(config, boundary, topology, mouse_pos, radius, |coord| {
+                    if let Some(idx) = universe.get_index_from_coord(&coord) {
+                        // Strong observation if mouse is down, otherwise passive
+                        let should_observe = self.is_left_mouse_down
+                            || (universe.grid[idx].consciousness == ConsciousnessState::Potential
+                                && rng.random_bool(passive_observation_prob));
+                        if should_observe {
+                            universe.observe_cell(idx);
+                        }
+                    }
+                });
+            }
+            // Shapes are only drawn when the button is let go.
+            ToolMode::Operator
+                if self.operator_shape != Shape::Freehand && self.is_left_mouse_down =>
+            {
+                if let Some(coord) = target {
+                    match &mut self.shape_drag {
+                        Some((_, end)) => *end = coord,
+                        None => self.shape_drag = Some((coord.clone(), coord)),
+                    }
+                }
+            }
+            ToolMode::Operator => {
+                if self.is_left_mouse_down {
+                    if let Some(coord) = &target {
+                        let state = match &self.operator_sample {
+                            Some(state) => state.clone(),
+                            None => self.operator_preset.state(universe.ga_dims),
+                        };
+                        universe.set_operator_with_state(coord, state);
+                    }
+                } else if self.is_right_mouse_down {
+                    let coord = get_coord_from_pos(mouse_pos, config, topology);
+                    universe.clear_operator(&coord);
+                }
+            }
+            ToolMode::Disrupt if self.is_left_mouse_down => self.disrupt_brush(mouse_pos),
+            ToolMode::Select if self.is_left_mouse_down => self.drag_selection(),
+
+            // Will add Disrupt logic here later
+            _ => {}
+        }
+    }
+}
+
+/// Utility to get a grid coordinate in `config.slice` from a pixel position.
+pub fn get_coord_from_pos(mouse_pos: [f64; 2], config: &Config, topology: Topology) -> Vec<usize> {
+    let dims = config.grid_dims.len();
+    let y = (mouse_pos[1] / config.cell_size).max(0.0) as usize;
+    let row = config.slice.coord(dims, 0, y);
+    // Odd rows of a hexagonal grid are drawn half a cell to the right.
+    let x = mouse_pos[0] / config.cell_size - topology.row_shift(&row);
+    config.slice.coord(dims, x.max(0.0) as usize, y)
+}
+
+/// Utility to get the pixel position of a cell's top-left corner, the inverse
+/// of [`get_coord_from_pos`]. Cells outside `config.slice` are projected onto
+/// it.
+pub fn get_pos_from_coord(coord: &[usize], config: &Config, topology: Topology) -> [f64; 2] {
+    if coord.is_empty() {
+        return [0.0, 0.0];
+    }
+    let [x, y] = config.slice.project(coord);
+    let x = x as f64 + topology.row_shift(coord);
+    [x * config.cell_size, y as f64 * config.cell_size]
+}
+
+/// Utility function to iterate over all grid cells within a given pixel radius of a point.
+///
+/// Cells past the grid edge are mapped through `boundary`, and skipped if they fall into dead space.
+pub fn for_cells_in_radius<F>(
+    config: &Config,
+    boundary: BoundaryCondition,
+    topology: Topology,
+    center_pos: [f64; 2],
+    radius: f64,
+    mut callback: F,
+) where
+    F: FnMut(Vec<usize>),
+{
+    for_cells_in_radius_weighted(
+        config,
+        boundary,
+        topology,
+        center_pos,
+        radius,
+        Falloff::Hard,
+        |coord, _| callback(coord),
+    );
+}
+
+/// Like [`for_cells_in_radius`], but also passes each cell's weight under
+/// `falloff`, from the distance of its center to `center_pos` as a fraction of
+/// `radius`. The cell under the cursor always has full weight.
+pub fn for_cells_in_radius_weighted<F>(
+    config: &Config,
+    boundary: BoundaryCondition,
+    topology: Topology,
+    center_pos: [f64; 2],
+    radius: f64,
+    falloff: Falloff,
+    mut callback: F,
+) where
+    F: FnMut(Vec<usize>, f64),
+{
+    let radius_sq = radius * radius;
+    let cell_radius_x = (radius / config.cell_size).ceil() as i32;
+    let cell_radius_y = (radius / config.cell_size).ceil() as i32;
+
+    let center_grid_x = (center_pos[0] / config.cell_size) as i32;
+    let center_grid_y = (center_pos[1] / config.cell_size) as i32;
+    let hexagonal = topology.is_hexagonal(config.grid_dims.len());
+
+    for dy in -cell_radius_y..=cell_radius_y {
+        for dx in -cell_radius_x..=cell_radius_x {
+            let cell_x = center_grid_x + dx;
+            let cell_y = center_grid_y + dy;
+
+            let shift = if hexagonal && cell_y.rem_euclid(2) == 1 {
+                0.5
+            } else {
+                0.0
+            };
+            let cell_center_x = (cell_x as f64 + 0.5 + shift) * config.cell_size;
+            let cell_center_y = (cell_y as f64 + 0.5) * config.cell_size;
+            let dist_sq =
+                (cell_center_x - center_pos[0]).powi(2) + (cell_center_y - center_pos[1]).powi(2);
+
+            // The cell under the cursor is always in reach, however small the brush.
+            if dist_sq <= radius_sq || (dx == 0 && dy == 0) {
+                let dims = config.grid_dims.len();
+                let [across, down] = config.slice.axes;
+                let Some(x) = boundary.resolve(cell_x as i64, config.grid_dims[across]) else {
+                    continue;
+                };
+                let y = if dims > 1 {
+                    let Some(y) = boundary.resolve(cell_y as i64, config.grid_dims[down]) else {
+                        continue;
+                    };
+                    y
+                } else {
+                    0
+                };
+                let weight = if dx == 0 && dy == 0 {
+                    1.0
+                } else {
+                    falloff.weight(dist_sq.sqrt() / radius.max(f64::EPSILON))
+                };
+                callback(config.slice.coord(dims, x, y), weight);
+            }
+        }
+    }
+}
@@ -0,0 +1,200 @@
+//! The grid as N-dimensional arrays, for quantitative work outside the app.
+//!
+//! [`Universe::to_array`] copies every cell's coefficients into a
+//! [`GridArray`] of shape `grid_dims × 2^p`, and [`Universe::mask`] marks the
+//! cells in one consciousness state with an array of shape `grid_dims`.
+//! Arrays are laid out in row-major order, the last axis varying fastest, as
+//! NumPy lays arrays out by default, and [`GridArray::to_npy`] writes them in
+//! NumPy's `.npy` format, so `numpy.load` reads them directly. Each cell keeps
+//! its coefficients in a vector of its own, so the arrays are always copies
+//! rather than views of the grid.
+//!
+//! With the `ndarray` feature, `Universe::to_ndarray` returns the same
+//! coefficients as an `ndarray::ArrayD`, and a [`GridArray`] can be viewed as
+//! one with `GridArray::view` or turned into one, both without copying its
+//! elements.
+
+use crate::existon::ConsciousnessState;
+use crate::universe::Universe;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A type that can be stored in a `.npy` file.
+pub trait NpyElement: Copy {
+    /// NumPy's description of the type, such as `|i1`.
+    const DESCR: &'static str;
+    /// Appends the value's little-endian bytes to `out`.
+    fn write(self, out: &mut Vec<u8>);
+}
+
+impl NpyElement for i8 {
+    const DESCR: &'static str = "|i1";
+    fn write(self, out: &mut Vec<u8>) {
+        out.push(self as u8);
+    }
+}
+
+impl NpyElement for bool {
+    const DESCR: &'static str = "|b1";
+    fn write(self, out: &mut Vec<u8>) {
+        out.push(self as u8);
+    }
+}
+
+impl NpyElement for f64 {
+    const DESCR: &'static str = "<f8";
+    fn write(self, out: &mut Vec<u8>) {
+        out.extend(self.to_le_bytes());
+    }
+}
+
+/// An N-dimensional array in row-major order; see the
+/// [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct GridArray<T> {
+    shape: Vec<usize>,
+    data: Vec<T>,
+}
+
+impl<T> GridArray<T> {
+    /// An array of `shape` holding `data` in row-major order, or `None` if
+    /// the lengths disagree.
+    pub fn from_shape_vec(shape: Vec<usize>, data: Vec<T>) -> Option<Self> {
+        (shape.iter().product::<usize>() == data.len()).then_some(GridArray { shape, data })
+    }
+
+    /// The length of each axis.
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// How many elements one step along each axis skips.
+    pub fn strides(&self) -> Vec<usize> {
+        let mut strides = vec![1; self.shape.len()];
+        for axis in (0..self.shape.len().saturating_sub(1)).rev() {
+            strides[axis] = strides[axis + 1] * self.shape[axis + 1];
+        }
+        strides
+    }
+
+    /// Every element, in row-major order.
+    pub fn as_slice(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.data
+    }
+
+    /// The element at `index`, one entry per axis, if it is inside the array.
+    pub fn get(&self, index: &[usize]) -> Option<&T> {
+        if index.len() != self.shape.len() || index.iter().zip(&self.shape).any(|(i, n)| i >= n) {
+            return None;
+        }
+        let offset: usize = index.iter().zip(self.strides()).map(|(i, s)| i * s).sum();
+        self.data.get(offset)
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<T> GridArray<T> {
+    /// The array as an `ndarray` view of the same elements.
+    pub fn view(&self) -> ndarray::ArrayViewD<'_, T> {
+        ndarray::ArrayViewD::from_shape(self.shape.as_slice(), &self.data)
+            .expect("a grid array's shape covers its data")
+    }
+}
+
+#[cfg(feature = "ndarray")]
+impl<T> From<GridArray<T>> for ndarray::ArrayD<T> {
+    /// Hands the array's elements over to `ndarray` without copying them.
+    fn from(array: GridArray<T>) -> Self {
+        ndarray::ArrayD::from_shape_vec(array.shape, array.data)
+            .expect("a grid array's shape covers its data")
+    }
+}
+
+impl GridArray<i8> {
+    /// Every cell's coefficients in `universe`, each -1, 0 or 1, as an array
+    /// of shape `grid_dims × 2^p` indexed by coordinate and then by blade.
+    pub fn coefficients(universe: &Universe) -> Self {
+        let blades = 1 << universe.ga_dims;
+        let mut data = vec![0; universe.grid.len() * blades];
+        for (idx, cell) in universe.grid.iter().enumerate() {
+            let at = row_major(&universe.get_coord_from_index(idx), &universe.grid_dims) * blades;
+            for (slot, c) in data[at..at + blades]
+                .iter_mut()
+                .zip(&cell.state.coefficients)
+            {
+                *slot = c.0;
+            }
+        }
+        let mut shape = universe.grid_dims.clone();
+        shape.push(blades);
+        GridArray { shape, data }
+    }
+}
+
+impl GridArray<bool> {
+    /// Which cells of `universe` are in `state`, as an array of shape
+    /// `grid_dims`.
+    pub fn mask(universe: &Universe, state: ConsciousnessState) -> Self {
+        let mut data = vec![false; universe.grid.len()];
+        for (idx, cell) in universe.grid.iter().enumerate() {
+            data[row_major(&universe.get_coord_from_index(idx), &universe.grid_dims)] =
+                cell.consciousness == state;
+        }
+        GridArray {
+            shape: universe.grid_dims.clone(),
+            data,
+        }
+    }
+}
+
+impl<T: NpyElement> GridArray<T> {
+    /// The array as the bytes of a version 1.0 `.npy` file.
+    pub fn to_npy(&self) -> Vec<u8> {
+        let mut shape = String::new();
+        for n in &self.shape {
+            let _ = write!(shape, "{}, ", n);
+        }
+        if self.shape.len() > 1 {
+            shape.truncate(shape.len() - 2);
+        } else {
+            shape.pop();
+        }
+        let mut header = format!(
+            "{{'descr': '{}', 'fortran_order': False, 'shape': ({}), }}",
+            T::DESCR,
+            shape
+        );
+        // The magic string, version and header length take 10 bytes, and the
+        // data must start on a multiple of 64.
+        while (10 + header.len() + 1) % 64 != 0 {
+            header.push(' ');
+        }
+        header.push('\n');
+        let mut out = b"\x93NUMPY\x01\x00".to_vec();
+        out.extend((header.len() as u16).to_le_bytes());
+        out.extend(header.as_bytes());
+        for &value in &self.data {
+            value.write(&mut out);
+        }
+        out
+    }
+
+    /// Writes the array to a `.npy` file.
+    pub fn save_npy(&self, path: &Path) -> io::Result<()> {
+        fs::write(path, self.to_npy())
+    }
+}
+
+/// The position of the cell at `coord` in a row-major walk of `grid_dims`.
+fn row_major(coord: &[usize], grid_dims: &[usize]) -> usize {
+    coord
+        .iter()
+        .zip(grid_dims)
+        .fold(0, |offset, (&c, &dim)| offset * dim + c)
+}
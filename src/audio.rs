@@ -0,0 +1,183 @@
+//! Sound for the window: the [`Tone`]s of a [`Sonifier`](crate::sonify::Sonifier),
+//! synthesized and played.
+//!
+//! A [`Synth`] mixes tones into mono samples, each a sine that starts sharply
+//! and dies away over [`NOTE_SECONDS`]. An [`AudioOutput`] plays one through
+//! the system's default output device with `cpal`, whose callback asks for
+//! samples as the device needs them. Without a device there is simply no
+//! sound.
+
+use crate::sonify::Tone;
+use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
+use cpal::{FromSample, SampleFormat, SizedSample, Stream, StreamConfig};
+use std::f64::consts::TAU;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+
+/// How long a note rings.
+pub const NOTE_SECONDS: f64 = 0.15;
+/// How long a note takes to reach full volume, short enough to sound struck
+/// but long enough not to click.
+const ATTACK_SECONDS: f64 = 0.005;
+/// The volume of a single tone at full strength, leaving headroom for chords.
+const MASTER: f64 = 0.2;
+
+/// A tone that is sounding, and how far along it is.
+struct Note {
+    tone: Tone,
+    phase: f64,
+    age: usize,
+}
+
+/// Mixes tones into samples; see the [module documentation](self).
+pub struct Synth {
+    sample_rate: f64,
+    notes: Vec<Note>,
+}
+
+impl Synth {
+    pub fn new(sample_rate: u32) -> Self {
+        Synth {
+            sample_rate: sample_rate as f64,
+            notes: Vec::new(),
+        }
+    }
+
+    /// Strikes `tones`, to sound from the next sample on.
+    pub fn play(&mut self, tones: &[Tone]) {
+        self.notes.extend(tones.iter().map(|&tone| Note {
+            tone,
+            phase: 0.0,
+            age: 0,
+        }));
+    }
+
+    /// How many notes are still sounding.
+    pub fn sounding(&self) -> usize {
+        self.notes.len()
+    }
+
+    /// Overwrites `out` with the next samples, each between -1 and 1, and lets
+    /// notes that have died away go.
+    pub fn fill(&mut self, out: &mut [f32]) {
+        let length = (NOTE_SECONDS * self.sample_rate) as usize;
+        let attack = ATTACK_SECONDS * self.sample_rate;
+        out.fill(0.0);
+        for note in &mut self.notes {
+            let step = note.tone.frequency / self.sample_rate;
+            for sample in out.iter_mut() {
+                if note.age >= length {
+                    break;
+                }
+                let release = 1.0 - note.age as f64 / length as f64;
+                let envelope = (note.age as f64 / attack).min(1.0) * release * release;
+                *sample += (MASTER * note.tone.volume * envelope * (TAU * note.phase).sin()) as f32;
+                note.phase = (note.phase + step).fract();
+                note.age += 1;
+            }
+        }
+        self.notes.retain(|note| note.age < length);
+        for sample in out {
+            *sample = sample.clamp(-1.0, 1.0);
+        }
+    }
+}
+
+/// Why the sound could not be started.
+#[derive(Debug)]
+pub enum AudioError {
+    /// The system has no output device.
+    NoDevice,
+    /// The device would not say how it plays.
+    Config(cpal::DefaultStreamConfigError),
+    /// The device plays samples of a format the synth does not write.
+    UnsupportedFormat(SampleFormat),
+    /// The device refused to open a stream.
+    Build(cpal::BuildStreamError),
+    /// The stream could not be started.
+    Play(cpal::PlayStreamError),
+}
+
+impl fmt::Display for AudioError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AudioError::NoDevice => write!(f, "no audio output device"),
+            AudioError::Config(e) => write!(f, "could not configure the audio device: {}", e),
+            AudioError::UnsupportedFormat(format) => {
+                write!(f, "the audio device plays unsupported {} samples", format)
+            }
+            AudioError::Build(e) => write!(f, "could not open the audio device: {}", e),
+            AudioError::Play(e) => write!(f, "could not start the sound: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for AudioError {}
+
+/// Plays tones through the default output device; dropping it stops the
+/// sound.
+pub struct AudioOutput {
+    tones: Sender<Vec<Tone>>,
+    _stream: Stream,
+}
+
+impl AudioOutput {
+    /// Opens the default output device and starts the stream that plays the
+    /// synth on it.
+    pub fn open() -> Result<Self, AudioError> {
+        let device = cpal::default_host()
+            .default_output_device()
+            .ok_or(AudioError::NoDevice)?;
+        let supported = device.default_output_config().map_err(AudioError::Config)?;
+        let format = supported.sample_format();
+        let config = supported.config();
+        let (sender, receiver) = mpsc::channel();
+        let stream = match format {
+            SampleFormat::F32 => stream::<f32>(&device, &config, receiver),
+            SampleFormat::I16 => stream::<i16>(&device, &config, receiver),
+            SampleFormat::U16 => stream::<u16>(&device, &config, receiver),
+            SampleFormat::I32 => stream::<i32>(&device, &config, receiver),
+            format => return Err(AudioError::UnsupportedFormat(format)),
+        }
+        .map_err(AudioError::Build)?;
+        stream.play().map_err(AudioError::Play)?;
+        Ok(AudioOutput {
+            tones: sender,
+            _stream: stream,
+        })
+    }
+
+    /// Strikes `tones` as soon as the device asks for more samples.
+    pub fn play(&self, tones: Vec<Tone>) {
+        if !tones.is_empty() {
+            let _ = self.tones.send(tones);
+        }
+    }
+}
+
+/// An output stream on `device` that plays the tones sent on `tones`, the
+/// same mono samples on every channel.
+fn stream<T: SizedSample + FromSample<f32>>(
+    device: &cpal::Device,
+    config: &StreamConfig,
+    tones: Receiver<Vec<Tone>>,
+) -> Result<Stream, cpal::BuildStreamError> {
+    let channels = config.channels as usize;
+    let mut synth = Synth::new(config.sample_rate.0);
+    let mut mono = Vec::new();
+    device.build_output_stream(
+        config,
+        move |out: &mut [T], _: &cpal::OutputCallbackInfo| {
+            for tones in tones.try_iter() {
+                synth.play(&tones);
+            }
+            mono.resize(out.len() / channels, 0.0);
+            synth.fill(&mut mono);
+            for (frame, &sample) in out.chunks_mut(channels).zip(&mono) {
+                frame.fill(T::from_sample(sample));
+            }
+        },
+        |e| eprintln!("Sound stopped: {}", e),
+        None,
+    )
+}
@@ -0,0 +1,140 @@
+//! Periodic checkpoints of the running session, for crash recovery.
+//!
+//! An [`Autosaver`] writes the [`Session`] to a numbered file every so many
+//! ticks or seconds and deletes all but the last few, so closing the window or
+//! a crash loses at most one interval of a long run. Each checkpoint is written
+//! to a temporary file and renamed into place, so a crash mid-write leaves the
+//! previous checkpoints intact. [`Autosaver::latest`] finds the newest
+//! checkpoint that still reads back, which `--resume` restores on startup.
+
+use crate::session::Session;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+/// When and where an [`Autosaver`] writes checkpoints.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutosavePolicy {
+    /// The directory the checkpoint files are written to, created if needed.
+    pub dir: PathBuf,
+    /// Save once this many ticks have passed since the last checkpoint.
+    pub every_ticks: Option<u64>,
+    /// Save once this much time has passed since the last checkpoint.
+    pub every: Option<Duration>,
+    /// The number of most recent checkpoint files kept, at least one.
+    pub keep: usize,
+}
+
+impl Default for AutosavePolicy {
+    /// Every 5000 ticks or two minutes, whichever comes first, in three files.
+    fn default() -> Self {
+        AutosavePolicy {
+            dir: PathBuf::from("existons-autosave"),
+            every_ticks: Some(5000),
+            every: Some(Duration::from_secs(120)),
+            keep: 3,
+        }
+    }
+}
+
+impl AutosavePolicy {
+    /// The path of checkpoint number `n`.
+    pub fn checkpoint_path(&self, n: u64) -> PathBuf {
+        self.dir.join(format!("checkpoint-{}.txt", n))
+    }
+
+    /// The numbers of the checkpoint files in the directory, oldest first.
+    pub fn checkpoints(&self) -> Vec<u64> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let mut numbers: Vec<u64> = entries
+            .filter_map(|entry| {
+                let name = entry.ok()?.file_name();
+                let n = name.to_str()?.strip_prefix("checkpoint-")?;
+                n.strip_suffix(".txt")?.parse().ok()
+            })
+            .collect();
+        numbers.sort_unstable();
+        numbers
+    }
+}
+
+/// Writes checkpoints as an [`AutosavePolicy`] asks.
+#[derive(Debug, Clone)]
+pub struct Autosaver {
+    pub policy: AutosavePolicy,
+    /// The tick and time of the last checkpoint, or of the start.
+    last_tick: u64,
+    last_time: Instant,
+    /// The number of the next checkpoint.
+    next: u64,
+}
+
+impl Autosaver {
+    /// Starts counting from `tick` and `now`, numbering checkpoints after
+    /// those already in the directory.
+    pub fn new(policy: AutosavePolicy, tick: u64, now: Instant) -> Self {
+        let next = policy.checkpoints().last().map_or(0, |&n| n + 1);
+        Autosaver {
+            policy,
+            last_tick: tick,
+            last_time: now,
+            next,
+        }
+    }
+
+    /// Whether a checkpoint is due at `tick` and `now`. Nothing is due until
+    /// the universe has moved on since the last one.
+    pub fn is_due(&self, tick: u64, now: Instant) -> bool {
+        if tick == self.last_tick {
+            return false;
+        }
+        let ticks = self
+            .policy
+            .every_ticks
+            .is_some_and(|every| tick.abs_diff(self.last_tick) >= every);
+        let time = self
+            .policy
+            .every
+            .is_some_and(|every| now.duration_since(self.last_time) >= every);
+        ticks || time
+    }
+
+    /// Restarts the count from `tick` and `now` without saving, as after a
+    /// checkpoint was restored.
+    pub fn reset(&mut self, tick: u64, now: Instant) {
+        self.last_tick = tick;
+        self.last_time = now;
+    }
+
+    /// Writes `session` as the next checkpoint and deletes those beyond the
+    /// most recent `keep`, returning the new file's path.
+    pub fn save(&mut self, session: &Session, now: Instant) -> io::Result<PathBuf> {
+        fs::create_dir_all(&self.policy.dir)?;
+        let path = self.policy.checkpoint_path(self.next);
+        let partial = path.with_extension("tmp");
+        session.save(&partial)?;
+        fs::rename(&partial, &path)?;
+        self.next += 1;
+        self.reset(session.scenario.ticks, now);
+        let numbers = self.policy.checkpoints();
+        let stale = numbers.len().saturating_sub(self.policy.keep.max(1));
+        for &n in &numbers[..stale] {
+            fs::remove_file(self.policy.checkpoint_path(n))?;
+        }
+        Ok(path)
+    }
+
+    /// The newest checkpoint of `policy` that reads back, with its path.
+    ///
+    /// Damaged files are skipped, falling back to older ones.
+    pub fn latest(policy: &AutosavePolicy) -> Option<(PathBuf, Session)> {
+        policy.checkpoints().into_iter().rev().find_map(|n| {
+            let path = policy.checkpoint_path(n);
+            let session = Session::load(&path).ok()?;
+            Some((path, session))
+        })
+    }
+}
@@ -0,0 +1,203 @@
+//! Animated captures: the run saved as a GIF or APNG to share.
+//!
+//! While a [`Capture`] is running it renders the viewed plane of the grid into
+//! an image every [`CaptureSettings::every`] ticks, each cell a block of
+//! [`CaptureSettings::scale`] pixels colored as the window colors it. Stopping
+//! it encodes the frames into an animated GIF, or an APNG when the file name
+//! ends in `.png`. Frames are rendered from the grid rather than read back from
+//! the window, so a capture looks the same whatever the camera is doing.
+
+use crate::slice::Slice;
+use crate::universe::Universe;
+use image::codecs::gif::{GifEncoder, Repeat};
+use image::{Delay, Frame, ImageError, ImageResult, Rgba, RgbaImage};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+/// The file format a capture is encoded in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CaptureFormat {
+    Gif,
+    /// Animated PNG: larger files than GIF, but every color kept.
+    Apng,
+}
+
+impl CaptureFormat {
+    /// The format for a file name: APNG for `.png`, GIF for anything else.
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension() {
+            Some(ext) if ext.eq_ignore_ascii_case("png") => CaptureFormat::Apng,
+            _ => CaptureFormat::Gif,
+        }
+    }
+}
+
+/// How a capture samples and sizes the run.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CaptureSettings {
+    /// How many ticks pass between frames.
+    pub every: u64,
+    /// The width and height of a cell in pixels.
+    pub scale: u32,
+    /// How many frames the animation shows per second.
+    pub frames_per_second: u32,
+}
+
+impl Default for CaptureSettings {
+    fn default() -> Self {
+        CaptureSettings {
+            every: 1,
+            scale: 4,
+            frames_per_second: 20,
+        }
+    }
+}
+
+/// A capture in progress; see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct Capture {
+    settings: CaptureSettings,
+    frames: Vec<RgbaImage>,
+    /// The tick of the last frame taken.
+    last_tick: Option<u64>,
+}
+
+impl Capture {
+    /// Upper bound on the frames kept, so a forgotten capture cannot use up
+    /// the memory.
+    pub const MAX_FRAMES: usize = 2000;
+
+    pub fn new(settings: CaptureSettings) -> Self {
+        Capture {
+            settings: CaptureSettings {
+                every: settings.every.max(1),
+                scale: settings.scale.max(1),
+                frames_per_second: settings.frames_per_second.max(1),
+            },
+            frames: Vec::new(),
+            last_tick: None,
+        }
+    }
+
+    pub fn settings(&self) -> &CaptureSettings {
+        &self.settings
+    }
+
+    pub fn frames(&self) -> &[RgbaImage] {
+        &self.frames
+    }
+
+    /// Whether a frame is due at `tick`: the first one always is, later ones
+    /// once [`CaptureSettings::every`] ticks have passed, until the capture is
+    /// full. A rewind takes a frame straight away.
+    pub fn is_due(&self, tick: u64) -> bool {
+        self.frames.len() < Self::MAX_FRAMES
+            && self
+                .last_tick
+                .is_none_or(|last| tick < last || tick >= last + self.settings.every)
+    }
+
+    /// Takes a frame of the plane `slice` of `universe` if one is due, with
+    /// `color` giving the color of the cell at each grid index.
+    pub fn capture(
+        &mut self,
+        universe: &Universe,
+        slice: &Slice,
+        color: impl Fn(usize) -> [f32; 4],
+    ) {
+        let tick = universe.tick_count;
+        if self.is_due(tick) {
+            self.frames
+                .push(render_frame(universe, slice, self.settings.scale, color));
+            self.last_tick = Some(tick);
+        }
+    }
+
+    /// Encodes the frames to `path`, in the format its name calls for, and
+    /// returns how many there were.
+    pub fn finish(self, path: &Path) -> ImageResult<usize> {
+        let count = self.frames.len();
+        let file = BufWriter::new(File::create(path).map_err(ImageError::IoError)?);
+        let fps = self.settings.frames_per_second;
+        match CaptureFormat::for_path(path) {
+            CaptureFormat::Gif => {
+                let mut encoder = GifEncoder::new(file);
+                encoder.set_repeat(Repeat::Infinite)?;
+                let delay = Delay::from_numer_denom_ms(1000, fps);
+                encoder.encode_frames(
+                    self.frames
+                        .into_iter()
+                        .map(|frame| Frame::from_parts(frame, 0, 0, delay)),
+                )?;
+            }
+            CaptureFormat::Apng => write_apng(file, &self.frames, fps)?,
+        }
+        Ok(count)
+    }
+}
+
+/// Renders the plane `slice` of `universe` with every cell a `scale`-pixel
+/// block colored by `color`, blended onto black.
+pub fn render_frame(
+    universe: &Universe,
+    slice: &Slice,
+    scale: u32,
+    color: impl Fn(usize) -> [f32; 4],
+) -> RgbaImage {
+    let dims = universe.grid_dims.len();
+    let [across, down] = slice.axes;
+    let width = universe.grid_dims.get(across).copied().unwrap_or(1);
+    let height = if dims > 1 {
+        universe.grid_dims.get(down).copied().unwrap_or(1)
+    } else {
+        1
+    };
+    let mut image = RgbaImage::new(width as u32 * scale, height as u32 * scale);
+    for y in 0..height {
+        for x in 0..width {
+            let Some(idx) = universe.get_index_from_coord(&slice.coord(dims, x, y)) else {
+                continue;
+            };
+            let [r, g, b, a] = color(idx);
+            let channel = |c: f32| ((c * a).clamp(0.0, 1.0) * 255.0).round() as u8;
+            let pixel = Rgba([channel(r), channel(g), channel(b), 255]);
+            let (left, top) = (x as u32 * scale, y as u32 * scale);
+            for dy in 0..scale {
+                for dx in 0..scale {
+                    image.put_pixel(left + dx, top + dy, pixel);
+                }
+            }
+        }
+    }
+    image
+}
+
+/// Writes `frames` as an animated PNG showing `fps` frames a second.
+fn write_apng(file: BufWriter<File>, frames: &[RgbaImage], fps: u32) -> ImageResult<()> {
+    let Some(first) = frames.first() else {
+        return Err(ImageError::IoError(std::io::Error::other(
+            "no frames captured",
+        )));
+    };
+    let to_image_error = |e: png::EncodingError| match e {
+        png::EncodingError::IoError(e) => ImageError::IoError(e),
+        e => ImageError::IoError(std::io::Error::other(e)),
+    };
+    let mut encoder = png::Encoder::new(file, first.width(), first.height());
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .set_animated(frames.len() as u32, 0)
+        .map_err(to_image_error)?;
+    encoder
+        .set_frame_delay(1, fps.min(u16::MAX as u32) as u16)
+        .map_err(to_image_error)?;
+    let mut writer = encoder.write_header().map_err(to_image_error)?;
+    for frame in frames {
+        writer
+            .write_image_data(frame.as_raw())
+            .map_err(to_image_error)?;
+    }
+    writer.finish().map_err(to_image_error)
+}
@@ -0,0 +1,257 @@
+//! Structure detection: still lifes, oscillators and gliders.
+//!
+//! A [`Census`] keeps a sliding window of recent ticks. Each tick it splits the
+//! collapsed cells (`Observed` and `Operator`) into connected clusters, following
+//! the universe's neighborhood and boundary, and hashes every cluster's shape
+//! and states relative to its corner. A cluster whose hash turns up again in an
+//! earlier tick of the window is a structure: at the same place one tick ago it
+//! is a still life, at the same place `p` ticks ago an oscillator of period `p`,
+//! and moved by at most one cell per tick a glider. [`Universe::census`] runs a
+//! window on a copy of the universe and returns what it found.
+//!
+//! [`Universe::census`]: crate::universe::Universe::census
+
+use crate::existon::ConsciousnessState;
+use crate::plugin::{Analyzer, PluginCtx};
+use crate::universe::{BoundaryCondition, Universe};
+use std::collections::VecDeque;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// What kind of structure a cluster is.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum StructureKind {
+    /// Unchanged from one tick to the next.
+    StillLife,
+    /// Back in the same place and shape every `period` ticks.
+    Oscillator { period: u64 },
+    /// Back in the same shape every `period` ticks, moved by `displacement`.
+    Glider {
+        period: u64,
+        /// One entry per grid dimension, the shortest way around on a torus.
+        displacement: Vec<i64>,
+    },
+}
+
+/// A structure found by a [`Census`], as it is at the latest tick recorded.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Structure {
+    pub kind: StructureKind,
+    /// The lowest coordinate on every axis of the cluster's bounding box.
+    pub origin: Vec<usize>,
+    /// The coordinates of its cells, in grid index order.
+    pub cells: Vec<Vec<usize>>,
+    /// The hash of its shape and states; equal structures share it wherever
+    /// they are.
+    pub signature: u64,
+}
+
+/// A connected cluster of collapsed cells at one tick.
+#[derive(Debug, Clone)]
+struct Cluster {
+    origin: Vec<usize>,
+    cells: Vec<Vec<usize>>,
+    signature: u64,
+}
+
+/// Finds structures over a sliding window of ticks; see the [module
+/// documentation](self).
+#[derive(Debug, Clone)]
+pub struct Census {
+    /// The most ticks looked back over, which is also the longest period found.
+    pub window: usize,
+    /// The clusters of each recorded tick, oldest first.
+    frames: VecDeque<Vec<Cluster>>,
+    /// The grid shape and boundary of the latest frame.
+    grid_dims: Vec<usize>,
+    toroidal: bool,
+}
+
+impl Census {
+    /// The window [`Census::default`] uses.
+    pub const DEFAULT_WINDOW: usize = 16;
+
+    /// An empty census looking back `window` ticks, at least one.
+    pub fn new(window: usize) -> Self {
+        Census {
+            window: window.max(1),
+            frames: VecDeque::new(),
+            grid_dims: Vec::new(),
+            toroidal: false,
+        }
+    }
+
+    /// Adds the universe's current clusters as the newest tick of the window.
+    ///
+    /// A grid of another shape starts the window over.
+    pub fn record(&mut self, universe: &Universe) {
+        if universe.grid_dims != self.grid_dims {
+            self.frames.clear();
+            self.grid_dims = universe.grid_dims.clone();
+        }
+        self.toroidal = universe.boundary == BoundaryCondition::Toroidal;
+        if self.frames.len() > self.window {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(clusters(universe));
+    }
+
+    /// The number of ticks recorded in the window.
+    pub fn frames(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// The structures among the latest tick's clusters, in grid index order of
+    /// their first cell.
+    ///
+    /// Each cluster is matched against the tick `p` ticks back for the smallest
+    /// `p` that has a cluster with its signature in the same place or, failing
+    /// that, within `p` cells. Clusters with no match are transients and are
+    /// left out.
+    pub fn structures(&self) -> Vec<Structure> {
+        let Some(latest) = self.frames.back() else {
+            return Vec::new();
+        };
+        latest
+            .iter()
+            .filter_map(|cluster| {
+                let kind = self
+                    .frames
+                    .iter()
+                    .rev()
+                    .skip(1)
+                    .zip(1..)
+                    .find_map(|(frame, period)| self.match_in(cluster, frame, period))?;
+                Some(Structure {
+                    kind,
+                    origin: cluster.origin.clone(),
+                    cells: cluster.cells.clone(),
+                    signature: cluster.signature,
+                })
+            })
+            .collect()
+    }
+
+    /// The kind of `cluster` if `frame`, `period` ticks back, has its twin.
+    fn match_in(&self, cluster: &Cluster, frame: &[Cluster], period: u64) -> Option<StructureKind> {
+        let twins = frame.iter().filter(|c| c.signature == cluster.signature);
+        let displacements: Vec<Vec<i64>> = twins
+            .map(|twin| self.displacement(&twin.origin, &cluster.origin))
+            .collect();
+        if displacements.iter().any(|d| d.iter().all(|&c| c == 0)) {
+            return Some(match period {
+                1 => StructureKind::StillLife,
+                period => StructureKind::Oscillator { period },
+            });
+        }
+        let displacement = displacements
+            .into_iter()
+            .filter(|d| d.iter().all(|&c| c.unsigned_abs() <= period))
+            .min_by_key(|d| d.iter().map(|c| c.unsigned_abs()).max())?;
+        Some(StructureKind::Glider {
+            period,
+            displacement,
+        })
+    }
+
+    /// The move from `from` to `to`, the shortest way around on a torus.
+    fn displacement(&self, from: &[usize], to: &[usize]) -> Vec<i64> {
+        from.iter()
+            .zip(to)
+            .zip(&self.grid_dims)
+            .map(|((&a, &b), &dim)| {
+                let d = b as i64 - a as i64;
+                let dim = dim as i64;
+                if self.toroidal && d.abs() * 2 > dim {
+                    d - d.signum() * dim
+                } else {
+                    d
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for Census {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_WINDOW)
+    }
+}
+
+/// Splits the collapsed cells of `universe` into connected clusters, in grid
+/// index order of their first cell.
+fn clusters(universe: &Universe) -> Vec<Cluster> {
+    let collapsed = |idx: usize| universe.grid[idx].consciousness != ConsciousnessState::Potential;
+    let mut seen = vec![false; universe.grid.len()];
+    let mut clusters = Vec::new();
+    for start in 0..universe.grid.len() {
+        if seen[start] || !collapsed(start) {
+            continue;
+        }
+        seen[start] = true;
+        let mut members = vec![start];
+        let mut next = 0;
+        while let Some(&idx) = members.get(next) {
+            next += 1;
+            for neighbor in universe.neighbors(&universe.get_coord_from_index(idx)) {
+                if !seen[neighbor] && collapsed(neighbor) {
+                    seen[neighbor] = true;
+                    members.push(neighbor);
+                }
+            }
+        }
+        members.sort_unstable();
+        clusters.push(cluster(universe, &members));
+    }
+    clusters
+}
+
+/// The cluster of the cells at `members`, hashed relative to its corner.
+fn cluster(universe: &Universe, members: &[usize]) -> Cluster {
+    let cells: Vec<Vec<usize>> = members
+        .iter()
+        .map(|&idx| universe.get_coord_from_index(idx))
+        .collect();
+    let origin: Vec<usize> = (0..universe.grid_dims.len())
+        .map(|axis| cells.iter().map(|c| c[axis]).min().unwrap_or(0))
+        .collect();
+    let mut hasher = DefaultHasher::new();
+    for (&idx, coord) in members.iter().zip(&cells) {
+        let cell = &universe.grid[idx];
+        for (c, o) in coord.iter().zip(&origin) {
+            (c - o).hash(&mut hasher);
+        }
+        (cell.consciousness == ConsciousnessState::Operator).hash(&mut hasher);
+        for c in &cell.state.coefficients {
+            c.0.hash(&mut hasher);
+        }
+    }
+    Cluster {
+        origin,
+        cells,
+        signature: hasher.finish(),
+    }
+}
+
+impl Analyzer for Census {
+    fn name(&self) -> &str {
+        "Census"
+    }
+
+    fn on_tick(&mut self, ctx: &PluginCtx) {
+        self.record(ctx.universe);
+    }
+
+    fn summary(&self) -> Vec<String> {
+        let structures = self.structures();
+        let count =
+            |f: fn(&StructureKind) -> bool| structures.iter().filter(|s| f(&s.kind)).count();
+        vec![format!(
+            "{} still lifes, {} oscillators, {} gliders over {} ticks",
+            count(|k| *k == StructureKind::StillLife),
+            count(|k| matches!(k, StructureKind::Oscillator { .. })),
+            count(|k| matches!(k, StructureKind::Glider { .. })),
+            self.frames.len()
+        )]
+    }
+}
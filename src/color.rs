@@ -0,0 +1,292 @@
+//! How Existon states are turned into colors.
+//!
+//! The mapping from multivector blades to color channels is described as data,
+//! so the renderer and the in-app help overlay are generated from the same table
+//! and can never disagree. A [`ViewMode`] chooses whether cells are colored by
+//! that mapping or by one measure of the cell, such as its age.
+
+use crate::existon::{ConsciousnessState, Existon};
+use crate::field::Rate;
+use crate::ga_core::{Multivector, blade_name};
+use crate::universe::Universe;
+
+/// The color of an `Observed` Existon.
+pub const OBSERVED_COLOR: [f32; 4] = [1.0, 1.0, 0.8, 1.0];
+/// The color of an `Operator` Existon.
+pub const OPERATOR_COLOR: [f32; 4] = [0.0, 1.0, 1.0, 1.0];
+
+/// One RGBA channel of a color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Channel {
+    Red,
+    Green,
+    Blue,
+    Alpha,
+}
+
+/// Drives one color channel from one blade: `value = (coefficient + 1) * scale + bias`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelMapping {
+    pub channel: Channel,
+    /// The index of the blade whose coefficient drives the channel.
+    pub blade: usize,
+    pub scale: f32,
+    pub bias: f32,
+}
+
+impl ChannelMapping {
+    /// The channel value for a `Mod3` coefficient in `{-1, 0, 1}`.
+    pub fn value(&self, coefficient: i8) -> f32 {
+        (coefficient + 1) as f32 * self.scale + self.bias
+    }
+}
+
+/// A named way of coloring `Potential` Existons from their multivector state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorMapping {
+    pub name: &'static str,
+    /// One mapping per channel, in RGBA order.
+    pub channels: [ChannelMapping; 4],
+}
+
+impl ColorMapping {
+    /// The default blend: the scalar and the first two vectors drive RGB, and
+    /// their bivector drives transparency.
+    pub const GA_BLEND: ColorMapping = ColorMapping {
+        name: "GA blend",
+        channels: [
+            ChannelMapping {
+                channel: Channel::Red,
+                blade: 0b00,
+                scale: 0.35,
+                bias: 0.0,
+            },
+            ChannelMapping {
+                channel: Channel::Green,
+                blade: 0b01,
+                scale: 0.35,
+                bias: 0.0,
+            },
+            ChannelMapping {
+                channel: Channel::Blue,
+                blade: 0b10,
+                scale: 0.35,
+                bias: 0.0,
+            },
+            ChannelMapping {
+                channel: Channel::Alpha,
+                blade: 0b11,
+                scale: 0.4,
+                bias: 0.5,
+            },
+        ],
+    };
+
+    /// The color of an Existon under this mapping.
+    ///
+    /// Blades that do not exist in the Existon's algebra read as zero.
+    pub fn color(&self, existon: &Existon) -> [f32; 4] {
+        self.color_of(existon.consciousness, &existon.state)
+    }
+
+    /// The color of a cell with this consciousness and state, such as a cell
+    /// of a pattern that is not on the grid yet.
+    pub fn color_of(&self, consciousness: ConsciousnessState, state: &Multivector) -> [f32; 4] {
+        match consciousness {
+            ConsciousnessState::Potential => self.channels.map(|mapping| {
+                let coefficient = state.coefficients.get(mapping.blade).map_or(0, |c| c.0);
+                mapping.value(coefficient)
+            }),
+            ConsciousnessState::Observed => OBSERVED_COLOR,
+            ConsciousnessState::Operator => OPERATOR_COLOR,
+        }
+    }
+
+    /// A human-readable legend of this mapping for a `Cl(ga_dims,0)` algebra.
+    pub fn describe(&self, ga_dims: usize) -> Vec<String> {
+        let num_blades = 1usize << ga_dims;
+        let mut lines = vec![format!(
+            "Render mode: {}  (Cl({},0), {} blades)",
+            self.name, ga_dims, num_blades
+        )];
+        lines.push("Potential cells, per channel (coefficient -1 / 0 / +1):".to_string());
+        for mapping in &self.channels {
+            let present = if mapping.blade < num_blades {
+                String::new()
+            } else {
+                format!(" - absent in Cl({},0), always 0", ga_dims)
+            };
+            lines.push(format!(
+                "  {:?} <- {}: {:.2} / {:.2} / {:.2}{}",
+                mapping.channel,
+                blade_name(mapping.blade),
+                mapping.value(-1),
+                mapping.value(0),
+                mapping.value(1),
+                present
+            ));
+        }
+
+        // High-dimensional algebras have thousands of blades; list only the first few.
+        const MAX_LISTED: usize = 8;
+        let hidden: Vec<usize> = (0..num_blades)
+            .filter(|&blade| self.channels.iter().all(|m| m.blade != blade))
+            .collect();
+        if !hidden.is_empty() {
+            let mut listed: Vec<String> = hidden
+                .iter()
+                .take(MAX_LISTED)
+                .map(|&b| blade_name(b))
+                .collect();
+            if hidden.len() > MAX_LISTED {
+                listed.push(format!("... (+{} more)", hidden.len() - MAX_LISTED));
+            }
+            lines.push(format!("  Not shown: {}", listed.join(", ")));
+        }
+        lines.push(format!(
+            "Observed cells: solid {}.  Operators: solid {}.",
+            rgb(OBSERVED_COLOR),
+            rgb(OPERATOR_COLOR)
+        ));
+        lines
+    }
+}
+
+/// What the colors of the cells show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ViewMode {
+    /// `Potential` cells colored from their coefficients by the
+    /// [`ColorMapping`], the others by their state.
+    #[default]
+    Coefficients,
+    /// The share of the cell's blades that are nonzero, across every grade.
+    GradeWeight,
+    /// Only the consciousness state.
+    Consciousness,
+    /// Ticks since the cell came into being.
+    Age,
+    /// How many partners the cell is entangled with.
+    EntanglementDegree,
+    /// The cell's value of a rate, from its parameter field or else the
+    /// global rate.
+    Field(Rate),
+}
+
+/// The color of a `Potential` cell in [`ViewMode::Consciousness`].
+pub const POTENTIAL_COLOR: [f32; 4] = [0.15, 0.15, 0.3, 1.0];
+/// The color of an unentangled cell in [`ViewMode::EntanglementDegree`].
+pub const UNENTANGLED_COLOR: [f32; 4] = [0.1, 0.1, 0.12, 1.0];
+
+impl ViewMode {
+    /// Every mode, in the order the view cycles through them.
+    pub const ALL: [ViewMode; 8] = [
+        ViewMode::Coefficients,
+        ViewMode::GradeWeight,
+        ViewMode::Consciousness,
+        ViewMode::Age,
+        ViewMode::EntanglementDegree,
+        ViewMode::Field(Rate::Observation),
+        ViewMode::Field(Rate::Decay),
+        ViewMode::Field(Rate::Fluctuation),
+    ];
+    /// The age at which a cell is drawn halfway along the heat scale.
+    pub const HALF_AGE: u64 = 100;
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ViewMode::Coefficients => "Coefficients",
+            ViewMode::GradeWeight => "Grade weight",
+            ViewMode::Consciousness => "Consciousness",
+            ViewMode::Age => "Age",
+            ViewMode::EntanglementDegree => "Entanglement degree",
+            ViewMode::Field(Rate::Observation) => "Observation rate",
+            ViewMode::Field(Rate::Decay) => "Decay rate",
+            ViewMode::Field(Rate::Fluctuation) => "Fluctuation rate",
+        }
+    }
+
+    /// The mode after this one, wrapping around.
+    pub fn next(self) -> ViewMode {
+        let i = ViewMode::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        ViewMode::ALL[(i + 1) % ViewMode::ALL.len()]
+    }
+
+    /// The color of the cell at grid index `idx` of `universe`, with
+    /// `mapping` for [`ViewMode::Coefficients`].
+    pub fn color(self, universe: &Universe, idx: usize, mapping: &ColorMapping) -> [f32; 4] {
+        let existon = &universe.grid[idx];
+        match self {
+            ViewMode::Coefficients => mapping.color(existon),
+            ViewMode::GradeWeight => {
+                let blades = existon.state.coefficients.len().max(1);
+                let nonzero = existon.state.coefficients.iter().filter(|c| c.0 != 0);
+                heat_color(nonzero.count() as f32 / blades as f32)
+            }
+            ViewMode::Consciousness => match existon.consciousness {
+                ConsciousnessState::Potential => POTENTIAL_COLOR,
+                ConsciousnessState::Observed => OBSERVED_COLOR,
+                ConsciousnessState::Operator => OPERATOR_COLOR,
+            },
+            ViewMode::Age => {
+                let age = universe.tick_count.saturating_sub(existon.born) as f32;
+                heat_color(age / (age + Self::HALF_AGE as f32))
+            }
+            ViewMode::EntanglementDegree => {
+                let degree = universe.entanglement.partners(existon.id).count();
+                if degree == 0 {
+                    UNENTANGLED_COLOR
+                } else {
+                    heat_color(degree as f32 / (degree + 2) as f32)
+                }
+            }
+            ViewMode::Field(rate) => {
+                let (value, global) = (universe.rate_at(rate, idx), universe.rate(rate));
+                if value + global > 0.0 {
+                    heat_color((value / (value + global)) as f32)
+                } else {
+                    heat_color(0.0)
+                }
+            }
+        }
+    }
+
+    /// A human-readable legend of this mode for a `Cl(ga_dims,0)` algebra.
+    pub fn describe(self, mapping: &ColorMapping, ga_dims: usize) -> Vec<String> {
+        let detail = match self {
+            ViewMode::Coefficients => return mapping.describe(ga_dims),
+            ViewMode::GradeWeight => format!(
+                "Blue for no nonzero blades to red for all {} of them, every grade counted.",
+                1usize << ga_dims
+            ),
+            ViewMode::Consciousness => format!(
+                "Potential {}, Observed {}, Operator {}.",
+                rgb(POTENTIAL_COLOR),
+                rgb(OBSERVED_COLOR),
+                rgb(OPERATOR_COLOR)
+            ),
+            ViewMode::Age => format!(
+                "Blue for newborn cells to red for old ones; halfway at {} ticks.",
+                Self::HALF_AGE
+            ),
+            ViewMode::EntanglementDegree => {
+                "Dark when unentangled, then blue for one partner to red for many.".to_string()
+            }
+            ViewMode::Field(rate) => format!(
+                "Blue where the {} rate is zero to red far above the global rate; halfway at it.",
+                rate.name()
+            ),
+        };
+        vec![format!("View mode: {}", self.name()), detail]
+    }
+}
+
+/// A blue-to-red heat color for `t` from 0 to 1, like the heat map overlays.
+pub fn heat_color(t: f32) -> [f32; 4] {
+    let t = t.clamp(0.0, 1.0);
+    [t, 0.2, 1.0 - t, 1.0]
+}
+
+/// Formats the RGB part of a color for display.
+fn rgb(color: [f32; 4]) -> String {
+    format!("RGB({:.2}, {:.2}, {:.2})", color[0], color[1], color[2])
+}
@@ -0,0 +1,274 @@
+//! Deltas: only what changed between two states of a universe.
+//!
+//! [`Universe::diff`] compares a universe with a later state of itself and keeps
+//! the cells that differ plus the entanglement groups that formed, broke or
+//! changed. [`Universe::apply_delta`] turns the earlier state into the later
+//! one. Between nearby ticks a delta is a small fraction of the grid, so
+//! autosaves and network streams can send deltas instead of whole snapshots.
+//! Like share codes, deltas are stored as short text codes.
+//!
+//! [`Universe::diff`]: crate::universe::Universe::diff
+//! [`Universe::apply_delta`]: crate::universe::Universe::apply_delta
+
+use crate::entanglement::GroupsDelta;
+use crate::existon::{ConsciousnessState, Existon};
+use crate::share::{
+    Reader, ShareError, pack, unpack, write_grid_dims, write_pair_operator, write_signed,
+    write_state, write_varint,
+};
+use crate::universe::InFlightCollapse;
+use crate::wave::DisruptionWave;
+
+/// Every delta code starts with this tag, which also versions the format.
+const PREFIX: &str = "EXD1-";
+
+/// The changes that bring a universe from one state to another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UniverseDelta {
+    /// The tick the universe is at afterwards.
+    pub tick: u64,
+    /// The grid's dimensions afterwards. After a resize every cell is listed.
+    pub grid_dims: Vec<usize>,
+    /// The GA dimensions of every cell's state.
+    pub ga_dims: usize,
+    /// The cells that changed, by grid index in ascending order.
+    pub cells: Vec<(usize, Existon)>,
+    pub entanglement: GroupsDelta,
+    /// Every collapse still in flight afterwards, if they changed; see
+    /// [`Universe::in_flight_collapses`].
+    ///
+    /// [`Universe::in_flight_collapses`]: crate::universe::Universe::in_flight_collapses
+    pub collapses: Option<Vec<InFlightCollapse>>,
+    /// Every disruption wave still spreading afterwards, if they changed; see
+    /// [`Universe::disruption_waves`].
+    ///
+    /// [`Universe::disruption_waves`]: crate::universe::Universe::disruption_waves
+    pub waves: Option<Vec<DisruptionWave>>,
+    /// The first ID the universe has not handed out yet.
+    pub next_id: u64,
+}
+
+impl UniverseDelta {
+    /// Whether no cell or entanglement group changed.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+            && self.entanglement.is_empty()
+            && self.collapses.is_none()
+            && self.waves.is_none()
+    }
+
+    /// Serializes the delta into a compressed, URL-safe code.
+    pub fn encode(&self) -> String {
+        let mut buf = Vec::new();
+        write_varint(&mut buf, self.tick);
+        write_grid_dims(&mut buf, &self.grid_dims);
+        write_varint(&mut buf, self.ga_dims as u64);
+        write_varint(&mut buf, self.next_id);
+        // Indices are stored as gaps, which keeps clustered changes small.
+        write_varint(&mut buf, self.cells.len() as u64);
+        let mut next_idx = 0;
+        for (idx, cell) in &self.cells {
+            write_varint(&mut buf, (idx - next_idx) as u64);
+            next_idx = idx + 1;
+            write_varint(&mut buf, cell.id);
+            buf.push(match cell.consciousness {
+                ConsciousnessState::Potential => 0,
+                ConsciousnessState::Observed => 1,
+                ConsciousnessState::Operator => 2,
+            });
+            write_state(&mut buf, &cell.state);
+            match &cell.velocity {
+                Some(velocity) => {
+                    buf.push(1);
+                    for &v in velocity {
+                        write_signed(&mut buf, v);
+                    }
+                }
+                None => buf.push(0),
+            }
+            write_varint(&mut buf, cell.born);
+            // Shifted by one so that 0 means never observed.
+            write_varint(&mut buf, cell.observed_since.map_or(0, |since| since + 1));
+        }
+        let entanglement = &self.entanglement;
+        write_varint(&mut buf, entanglement.next_group);
+        write_varint(&mut buf, entanglement.removed.len() as u64);
+        for &group in &entanglement.removed {
+            write_varint(&mut buf, group);
+        }
+        write_varint(&mut buf, entanglement.changed.len() as u64);
+        for (group, members, strength, operator) in &entanglement.changed {
+            write_varint(&mut buf, *group);
+            write_varint(&mut buf, members.len() as u64);
+            for &id in members {
+                write_varint(&mut buf, id);
+            }
+            buf.extend_from_slice(&strength.to_le_bytes());
+            write_pair_operator(&mut buf, operator.as_ref());
+        }
+        match &self.collapses {
+            Some(collapses) => {
+                buf.push(1);
+                write_varint(&mut buf, collapses.len() as u64);
+                for collapse in collapses {
+                    write_varint(&mut buf, collapse.due);
+                    write_varint(&mut buf, collapse.source);
+                    write_varint(&mut buf, collapse.target);
+                }
+            }
+            None => buf.push(0),
+        }
+        match &self.waves {
+            Some(waves) => {
+                buf.push(1);
+                write_varint(&mut buf, waves.len() as u64);
+                for wave in waves {
+                    for &c in &wave.center {
+                        write_varint(&mut buf, c as u64);
+                    }
+                    buf.extend_from_slice(&wave.amplitude.to_le_bytes());
+                    write_varint(&mut buf, wave.radius as u64);
+                }
+            }
+            None => buf.push(0),
+        }
+        pack(PREFIX, &buf)
+    }
+
+    /// Parses a code produced by [`UniverseDelta::encode`].
+    pub fn decode(code: &str) -> Result<Self, ShareError> {
+        let buf = unpack(PREFIX, code)?;
+        let mut reader = Reader::new(&buf);
+        let tick = reader.varint()?;
+        let grid_dims = reader.grid_dims()?;
+        let ga_dims = reader.varint()? as usize;
+        if ga_dims > 12 {
+            return Err(ShareError::Invalid("too many GA dimensions"));
+        }
+        let next_id = reader.varint()?;
+        let size: usize = grid_dims.iter().product();
+        let count = reader.varint()? as usize;
+        if count > size {
+            return Err(ShareError::Invalid("too many changed cells"));
+        }
+        let mut cells = Vec::with_capacity(count.min(buf.len()));
+        let mut next_idx = 0;
+        for _ in 0..count {
+            let idx = (reader.varint()? as usize)
+                .checked_add(next_idx)
+                .filter(|&idx| idx < size)
+                .ok_or(ShareError::Invalid("cell outside the grid"))?;
+            next_idx = idx + 1;
+            let id = reader.varint()?;
+            let consciousness = match reader.byte()? {
+                0 => ConsciousnessState::Potential,
+                1 => ConsciousnessState::Observed,
+                2 => ConsciousnessState::Operator,
+                _ => return Err(ShareError::Invalid("unknown consciousness state")),
+            };
+            let state = reader.state(ga_dims)?;
+            let velocity = match reader.byte()? {
+                0 => None,
+                1 => Some(
+                    (0..grid_dims.len())
+                        .map(|_| reader.signed())
+                        .collect::<Result<Vec<i64>, ShareError>>()?,
+                ),
+                _ => return Err(ShareError::Invalid("unknown velocity tag")),
+            };
+            let born = reader.varint()?;
+            let observed_since = reader.varint()?.checked_sub(1);
+            cells.push((
+                idx,
+                Existon {
+                    id,
+                    consciousness,
+                    state,
+                    velocity,
+                    born,
+                    observed_since,
+                },
+            ));
+        }
+        let next_group = reader.varint()?;
+        let count = reader.varint()? as usize;
+        let mut removed = Vec::with_capacity(count.min(buf.len()));
+        for _ in 0..count {
+            removed.push(reader.varint()?);
+        }
+        let count = reader.varint()? as usize;
+        let mut changed = Vec::with_capacity(count.min(buf.len()));
+        for _ in 0..count {
+            let group = reader.varint()?;
+            let len = reader.varint()? as usize;
+            if len < 2 || len > buf.len() {
+                return Err(ShareError::Invalid("entanglement group of the wrong size"));
+            }
+            let mut members: Vec<u64> = Vec::with_capacity(len);
+            for _ in 0..len {
+                let id = reader.varint()?;
+                if members.last().is_some_and(|&last| last >= id) {
+                    return Err(ShareError::Invalid("entanglement members out of order"));
+                }
+                members.push(id);
+            }
+            let strength = reader.probability()?;
+            let operator = reader.pair_operator(ga_dims)?;
+            changed.push((group, members, strength, operator));
+        }
+        let collapses = match reader.byte()? {
+            0 => None,
+            1 => {
+                let count = reader.varint()? as usize;
+                let mut collapses = Vec::with_capacity(count.min(buf.len()));
+                for _ in 0..count {
+                    collapses.push(InFlightCollapse {
+                        due: reader.varint()?,
+                        source: reader.varint()?,
+                        target: reader.varint()?,
+                    });
+                }
+                Some(collapses)
+            }
+            _ => return Err(ShareError::Invalid("unknown in-flight collapse flag")),
+        };
+        let waves = match reader.byte()? {
+            0 => None,
+            1 => {
+                let count = reader.varint()? as usize;
+                let mut waves = Vec::with_capacity(count.min(buf.len()));
+                for _ in 0..count {
+                    let mut center = Vec::with_capacity(grid_dims.len());
+                    for &dim in &grid_dims {
+                        let c = reader.varint()? as usize;
+                        if c >= dim {
+                            return Err(ShareError::Invalid("wave outside the grid"));
+                        }
+                        center.push(c);
+                    }
+                    waves.push(DisruptionWave {
+                        center,
+                        amplitude: reader.probability()?,
+                        radius: reader.varint()? as usize,
+                    });
+                }
+                Some(waves)
+            }
+            _ => return Err(ShareError::Invalid("unknown disruption wave flag")),
+        };
+        Ok(UniverseDelta {
+            tick,
+            grid_dims,
+            ga_dims,
+            cells,
+            entanglement: GroupsDelta {
+                removed,
+                changed,
+                next_group,
+            },
+            collapses,
+            waves,
+            next_id,
+        })
+    }
+}
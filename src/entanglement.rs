@@ -0,0 +1,366 @@
+//! Entanglement groups: sets of Existons that collapse together.
+//!
+//! Entanglement is not limited to pairs. Any number of Existons can share a
+//! group, GHZ-style, and when one of them is observed every other member that is
+//! still `Potential` collapses in the same tick.
+//!
+//! Entanglement also decoheres: every group has a strength that fades each tick
+//! according to a [`Decoherence`], and the group breaks apart once it is too weak.
+//! By default collapses are instantaneous; a [`CollapseSpeed`] limits how fast
+//! they travel, to compare true nonlocality with a light cone.
+//!
+//! What a collapse does to the partner is a [`PairOperator`], the analogue of a
+//! two-qubit gate: the universe has one for every link, and any group can
+//! override it.
+
+use crate::ga_core::Multivector;
+use std::collections::{BTreeMap, HashMap};
+
+/// Identifies a group within one [`EntanglementGroups`].
+pub type GroupId = u64;
+
+/// How quickly entanglement weakens, and when it breaks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Decoherence {
+    /// The fraction of its strength a group loses each tick.
+    pub rate: f64,
+    /// Groups whose strength falls below this break apart.
+    pub threshold: f64,
+    /// Whether a group returns to full strength when its entanglement fires.
+    pub refresh_on_fire: bool,
+}
+
+impl Decoherence {
+    /// Perfect entanglement that never weakens.
+    pub const NONE: Decoherence = Decoherence {
+        rate: 0.0,
+        threshold: 0.0,
+        refresh_on_fire: false,
+    };
+}
+
+impl Default for Decoherence {
+    /// A group that never fires lasts a little under 1400 ticks.
+    fn default() -> Self {
+        Decoherence {
+            rate: 0.001,
+            threshold: 0.25,
+            refresh_on_fire: true,
+        }
+    }
+}
+
+/// How fast the collapse of an observed cell reaches its entangled partners.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum CollapseSpeed {
+    /// Partners collapse in the same tick, however far away they are.
+    #[default]
+    Instant,
+    /// Collapses travel this many cells per tick, so a partner `d` cells away
+    /// collapses `d / speed` ticks later, rounded up. At 1, entanglement
+    /// spreads no faster than local influence through a radius-1 neighborhood.
+    Limited(f64),
+}
+
+impl CollapseSpeed {
+    /// The number of ticks a collapse takes to cross `distance` cells.
+    pub fn delay(self, distance: usize) -> u64 {
+        match self {
+            CollapseSpeed::Instant => 0,
+            CollapseSpeed::Limited(speed) => (distance as f64 / speed).ceil().max(1.0) as u64,
+        }
+    }
+}
+
+/// What a collapsing partner's state becomes, given the state of the cell whose
+/// observation collapsed it.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum PairOperator {
+    /// The partner keeps its collapsed state.
+    Identity,
+    /// The partner's state is negated: perfect anticorrelation.
+    #[default]
+    Inversion,
+    /// The partner and the observed cell exchange states.
+    Swap,
+    /// The partner's state `x` is conjugated by this rotor: `R x R~`.
+    Rotor(Multivector),
+}
+
+impl PairOperator {
+    /// Applies the operator to a partner's collapsed `state`, except for
+    /// [`PairOperator::Swap`], which involves both cells and is left to the caller.
+    pub fn apply(&self, state: &Multivector) -> Multivector {
+        match self {
+            PairOperator::Identity | PairOperator::Swap => state.clone(),
+            PairOperator::Inversion => -state,
+            PairOperator::Rotor(rotor) => &(rotor * state) * &rotor.reverse(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Group {
+    /// In ascending ID order.
+    members: Vec<u64>,
+    /// 1 for a fresh group, fading towards 0.
+    strength: f64,
+    /// Overrides the universe's [`PairOperator`] for this group's links.
+    operator: Option<PairOperator>,
+}
+
+/// The changes that turn one [`EntanglementGroups`] into another; see
+/// [`EntanglementGroups::diff`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroupsDelta {
+    /// Groups that no longer exist, in ascending order.
+    pub removed: Vec<GroupId>,
+    /// Groups that are new or whose members, strength or operator changed, in
+    /// ascending order, with their members in ascending order, their strength
+    /// and their operator.
+    pub changed: Vec<(GroupId, Vec<u64>, f64, Option<PairOperator>)>,
+    /// The ID the next new group will get.
+    pub next_group: GroupId,
+}
+
+impl GroupsDelta {
+    /// Whether no group changed.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Which Existons are entangled with which, as disjoint groups of two or more IDs.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntanglementGroups {
+    group_of: HashMap<u64, GroupId>,
+    groups: BTreeMap<GroupId, Group>,
+    next_group: GroupId,
+}
+
+impl EntanglementGroups {
+    /// The group `id` belongs to, if it is entangled.
+    pub fn group_of(&self, id: u64) -> Option<GroupId> {
+        self.group_of.get(&id).copied()
+    }
+
+    /// Whether `id` belongs to any group.
+    pub fn is_entangled(&self, id: u64) -> bool {
+        self.group_of.contains_key(&id)
+    }
+
+    /// The members of a group in ascending order, or nothing if there is no such group.
+    pub fn members(&self, group: GroupId) -> &[u64] {
+        self.groups
+            .get(&group)
+            .map_or(&[], |group| group.members.as_slice())
+    }
+
+    /// How strongly a group is still entangled: 1 when fresh, fading towards 0.
+    /// Groups that do not exist have no strength.
+    pub fn strength(&self, group: GroupId) -> f64 {
+        self.groups.get(&group).map_or(0.0, |group| group.strength)
+    }
+
+    /// The operator a group's links use instead of the universe's, if any.
+    pub fn operator(&self, group: GroupId) -> Option<&PairOperator> {
+        self.groups.get(&group)?.operator.as_ref()
+    }
+
+    /// Gives a group's links their own operator, or with `None` returns them to
+    /// the universe's. Groups that do not exist are left alone.
+    pub fn set_operator(&mut self, group: GroupId, operator: Option<PairOperator>) {
+        if let Some(group) = self.groups.get_mut(&group) {
+            group.operator = operator;
+        }
+    }
+
+    /// Every other member of `id`'s group.
+    pub fn partners(&self, id: u64) -> impl Iterator<Item = u64> + '_ {
+        let members = self
+            .group_of(id)
+            .map_or(&[][..], |group| self.members(group));
+        members.iter().copied().filter(move |&member| member != id)
+    }
+
+    /// Every group's members, in the order the groups were created.
+    pub fn groups(&self) -> impl Iterator<Item = &[u64]> {
+        self.groups.values().map(|group| group.members.as_slice())
+    }
+
+    /// Every group's ID, in the order the groups were created.
+    pub fn group_ids(&self) -> impl Iterator<Item = GroupId> + '_ {
+        self.groups.keys().copied()
+    }
+
+    /// The number of groups.
+    pub fn len(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// Whether nothing is entangled.
+    pub fn is_empty(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// Entangles all of `ids` in a new group at full strength, taking each out of
+    /// any group it was in before. Duplicates are ignored; fewer than two distinct
+    /// IDs do nothing.
+    pub fn entangle(&mut self, ids: &[u64]) -> Option<GroupId> {
+        let mut ids = ids.to_vec();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.len() < 2 {
+            return None;
+        }
+        for &id in &ids {
+            self.remove(id);
+        }
+        let group = self.next_group;
+        self.next_group += 1;
+        for &id in &ids {
+            self.group_of.insert(id, group);
+        }
+        self.groups.insert(
+            group,
+            Group {
+                members: ids,
+                strength: 1.0,
+                operator: None,
+            },
+        );
+        Some(group)
+    }
+
+    /// Adds `id` to an existing group, taking it out of the one it was in.
+    pub fn join(&mut self, id: u64, group: GroupId) {
+        if !self.groups.contains_key(&group) || self.group_of(id) == Some(group) {
+            return;
+        }
+        self.remove(id);
+        // `id` was not in `group`, so taking it out of its old group leaves this one intact.
+        let members = &mut self.group_mut(group).members;
+        let pos = members.partition_point(|&m| m < id);
+        members.insert(pos, id);
+        self.group_of.insert(id, group);
+    }
+
+    /// Takes `id` out of its group, dissolving the group if one member is left.
+    pub fn remove(&mut self, id: u64) {
+        let Some(group) = self.group_of.remove(&id) else {
+            return;
+        };
+        let members = &mut self.group_mut(group).members;
+        members.retain(|&m| m != id);
+        if members.len() < 2 {
+            self.dissolve(group);
+        }
+    }
+
+    /// Weakens every group by one tick of `decoherence` and breaks those that fall
+    /// below its threshold, returning their members in group order.
+    ///
+    /// If the decoherence allows it, the groups of the `fired` IDs are first
+    /// refreshed to full strength.
+    pub fn decohere(
+        &mut self,
+        decoherence: &Decoherence,
+        fired: impl IntoIterator<Item = u64>,
+    ) -> Vec<Vec<u64>> {
+        if decoherence.refresh_on_fire {
+            for id in fired {
+                if let Some(group) = self.group_of(id) {
+                    self.group_mut(group).strength = 1.0;
+                }
+            }
+        }
+        let mut broken = Vec::new();
+        for (&id, group) in &mut self.groups {
+            group.strength *= 1.0 - decoherence.rate;
+            if group.strength < decoherence.threshold {
+                broken.push(id);
+            }
+        }
+        broken
+            .into_iter()
+            .map(|group| self.dissolve(group))
+            .collect()
+    }
+
+    /// The groups that broke, formed or changed between `self` and `other`.
+    ///
+    /// Decoherence weakens every group each tick, so unless its rate is zero
+    /// every group's strength counts as a change.
+    pub fn diff(&self, other: &EntanglementGroups) -> GroupsDelta {
+        GroupsDelta {
+            removed: self
+                .groups
+                .keys()
+                .filter(|group| !other.groups.contains_key(group))
+                .copied()
+                .collect(),
+            changed: other
+                .groups
+                .iter()
+                .filter(|&(group, after)| self.groups.get(group) != Some(after))
+                .map(|(&group, after)| {
+                    (
+                        group,
+                        after.members.clone(),
+                        after.strength,
+                        after.operator.clone(),
+                    )
+                })
+                .collect(),
+            next_group: other.next_group,
+        }
+    }
+
+    /// Applies the changes captured by [`EntanglementGroups::diff`].
+    pub fn apply_delta(&mut self, delta: &GroupsDelta) {
+        // Members may move between changed groups, so every old group goes first.
+        let stale = delta.changed.iter().map(|(group, ..)| group);
+        for group in delta.removed.iter().chain(stale) {
+            self.dissolve(*group);
+        }
+        for (group, members, strength, operator) in &delta.changed {
+            for &id in members {
+                self.remove(id);
+                self.group_of.insert(id, *group);
+            }
+            self.groups.insert(
+                *group,
+                Group {
+                    members: members.clone(),
+                    strength: *strength,
+                    operator: operator.clone(),
+                },
+            );
+        }
+        self.next_group = delta.next_group;
+    }
+
+    /// Disentangles everything.
+    pub fn clear(&mut self) {
+        self.group_of.clear();
+        self.groups.clear();
+    }
+
+    fn group_mut(&mut self, group: GroupId) -> &mut Group {
+        self.groups
+            .get_mut(&group)
+            .expect("every member has a group")
+    }
+
+    /// Removes a group, returning its members.
+    fn dissolve(&mut self, group: GroupId) -> Vec<u64> {
+        let members = self
+            .groups
+            .remove(&group)
+            .map_or_else(Vec::new, |group| group.members);
+        for member in &members {
+            self.group_of.remove(member);
+        }
+        members
+    }
+}
@@ -4,9 +4,10 @@
 //! "topological bit" whose state is described by a Geometric Algebra multivector. [cite: 108, 111]
 
 use crate::ga_core::Multivector;
+use rand::Rng;
 
 /// Represents the discrete states of consciousness for an Existon.
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum ConsciousnessState {
     /// The Existon is in a superposition of states, unobserved. [cite: 115]
     Potential,
@@ -16,6 +17,49 @@ pub enum ConsciousnessState {
     Operator,
 }
 
+/// How an observation simplifies a state: the toy model's measurement postulate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum CollapsePolicy {
+    /// Keep the scalar and vector blades, zeroing every blade of grade 2 or higher.
+    #[default]
+    KeepScalarVector,
+    /// Keep a single one of the nonzero blades.
+    ///
+    /// The blade is picked pseudo-randomly from the cell's ID and state rather
+    /// than drawn from the universe's RNG, so the choice is reproducible and the
+    /// same however a tick is split across threads.
+    ProjectToSingleRandomBlade,
+    /// Keep only the e0 blade, the direction of the default `Operator` state, as
+    /// if every measurement were made along it.
+    ProjectOntoOperatorBlade,
+    /// Keep the blades of even grade (scalar, bivectors, ...), zeroing the odd ones.
+    KeepEvenGrades,
+}
+
+impl CollapsePolicy {
+    /// Every policy, in the order share codes number them.
+    pub const ALL: [CollapsePolicy; 4] = [
+        CollapsePolicy::KeepScalarVector,
+        CollapsePolicy::ProjectToSingleRandomBlade,
+        CollapsePolicy::ProjectOntoOperatorBlade,
+        CollapsePolicy::KeepEvenGrades,
+    ];
+
+    /// The blade of `state` that [`CollapsePolicy::ProjectToSingleRandomBlade`] keeps.
+    fn pick_blade(id: u64, state: &Multivector) -> Option<usize> {
+        let nonzero: Vec<usize> = (0..state.coefficients.len())
+            .filter(|&i| state.coefficients[i].0 != 0)
+            .collect();
+        // A SplitMix64-style mix of the ID and coefficients.
+        let mut hash = id;
+        for c in &state.coefficients {
+            hash = (hash ^ (c.0 + 1) as u64).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+            hash ^= hash >> 31;
+        }
+        (!nonzero.is_empty()).then(|| nonzero[(hash % nonzero.len() as u64) as usize])
+    }
+}
+
 /// The Existon: a primitive unit of reality and consciousness. [cite: 105]
 ///
 /// Each Existon has a unique ID, a state of consciousness, and a `Multivector`
@@ -28,35 +72,72 @@ pub struct Existon {
     pub consciousness: ConsciousnessState,
     /// The Geometric Algebra multivector representing the Existon's state. [cite: 114]
     pub state: Multivector,
+    /// How many cells an `Operator` moves along each grid dimension whenever
+    /// operators move; see [`crate::universe::Universe::set_operator_velocity`].
+    /// `None` for stationary operators and every other cell.
+    pub velocity: Option<Vec<i64>>,
+    /// The tick the Existon came into being: when its ID was handed out, or
+    /// when a fluctuation or randomization last replaced its state wholesale.
+    pub born: u64,
+    /// The tick the Existon was last observed, while it stays `Observed`.
+    pub observed_since: Option<u64>,
 }
 
 impl Existon {
     /// Creates a new Existon with a unique ID, initialized in a random `Potential`
     /// state within a space of `p` dimensions.
-    pub fn new(id: u64, p: usize) -> Self {
+    pub fn new<R: Rng + ?Sized>(id: u64, p: usize, rng: &mut R) -> Self {
         Existon {
             id,
             consciousness: ConsciousnessState::Potential,
             // Initialize with a random state in a p-dimensional algebra.
-            state: Multivector::random(p),
+            state: Multivector::random(p, rng),
+            velocity: None,
+            born: 0,
+            observed_since: None,
+        }
+    }
+
+    /// How many ticks the Existon has existed at tick `now`.
+    pub fn age_ticks(&self, now: u64) -> u64 {
+        now.saturating_sub(self.born)
+    }
+
+    /// How many ticks the Existon has been `Observed` at tick `now`, or 0 if
+    /// it is not `Observed`.
+    pub fn observed_duration(&self, now: u64) -> u64 {
+        match (self.consciousness, self.observed_since) {
+            (ConsciousnessState::Observed, Some(since)) => now.saturating_sub(since),
+            _ => 0,
         }
     }
 
     /// The "It from Bit" event: collapses a `Potential` state to an `Observed` state.
     ///
-    /// Upon observation, the state simplifies. This is modeled by zeroing out all
-    /// higher-grade blades (bivectors, trivectors, etc.), leaving only the scalar
-    /// and vector components. This is a dimension-agnostic way to represent a
-    /// collapse from a complex superposition to a more "classical" state.
-    pub fn observe(&mut self) {
+    /// Upon observation, the state simplifies according to `policy`. By default
+    /// this zeroes out all higher-grade blades (bivectors, trivectors, etc.),
+    /// leaving only the scalar and vector components: a dimension-agnostic way to
+    /// represent a collapse from a complex superposition to a more "classical" state.
+    pub fn observe(&mut self, policy: CollapsePolicy) {
         if self.consciousness == ConsciousnessState::Potential {
             self.consciousness = ConsciousnessState::Observed;
+            let kept = match policy {
+                CollapsePolicy::ProjectToSingleRandomBlade => {
+                    CollapsePolicy::pick_blade(self.id, &self.state)
+                }
+                _ => None,
+            };
             // Iterate through all coefficients in the multivector's state.
             for i in 0..self.state.coefficients.len() {
                 // The grade of a blade is the number of set bits in its index.
                 // Grade 0 = scalar, Grade 1 = vector.
-                // We zero out all coefficients for blades of grade 2 or higher.
-                if i.count_ones() >= 2 {
+                let keep = match policy {
+                    CollapsePolicy::KeepScalarVector => i.count_ones() < 2,
+                    CollapsePolicy::ProjectToSingleRandomBlade => kept == Some(i),
+                    CollapsePolicy::ProjectOntoOperatorBlade => i == 0b1,
+                    CollapsePolicy::KeepEvenGrades => i.count_ones() % 2 == 0,
+                };
+                if !keep {
                     self.state.coefficients[i] = crate::ga_core::Mod3::new(0);
                 }
             }
@@ -66,11 +147,11 @@ impl Existon {
     /// Returns an `Observed` Existon to a new, random `Potential` state.
     /// This represents decoherence or the loss of a persistent observation, allowing
     /// "reality" to dissolve back into the quantum foam.
-    pub fn decay(&mut self) {
+    pub fn decay<R: Rng + ?Sized>(&mut self, rng: &mut R) {
         if self.consciousness == ConsciousnessState::Observed {
             self.consciousness = ConsciousnessState::Potential;
             // Return to a random superposition in the same p-dimensional space.
-            self.state = Multivector::random(self.state.p);
+            self.state = Multivector::random(self.state.p, rng);
         }
     }
 }
@@ -0,0 +1,205 @@
+//! Experiments: quantitative tests run on a universe.
+//!
+//! A [`BellTest`] treats every entanglement that fires as one run of a Bell
+//! experiment. The observed cell is Alice's particle and the partner it
+//! collapsed is Bob's; each side "measures" its cell along one of two blades,
+//! chosen at random per trial, and reads the sign of that coefficient as the
+//! outcome. The four correlations combine into the CHSH statistic
+//! `S = E(a, b) - E(a, b') + E(a', b) + E(a', b')`, which no local hidden
+//! variable model can push beyond 2 in magnitude.
+
+use crate::ga_core::Multivector;
+use crate::plugin::{Analyzer, PluginCtx};
+use crate::universe::{TickReport, Universe};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The blades each side of a [`BellTest`] measures along, by blade index
+/// (bit `i` set for `e_i`, so 1 is `e0`, 2 is `e1` and 3 is `e0e1`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BellSettings {
+    /// Alice's two settings, `a` and `a'`.
+    pub alice: [usize; 2],
+    /// Bob's two settings, `b` and `b'`.
+    pub bob: [usize; 2],
+}
+
+impl Default for BellSettings {
+    /// Both sides measure along the vectors `e0` and `e1`, Bob in the other
+    /// order, since the default collapse keeps only the scalar and vectors.
+    fn default() -> Self {
+        BellSettings {
+            alice: [1, 2],
+            bob: [2, 1],
+        }
+    }
+}
+
+/// The trials of one pair of settings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Tally {
+    pub trials: u64,
+    /// The sum of the products of the two outcomes, each +1 or -1.
+    pub product_sum: i64,
+}
+
+impl Tally {
+    /// The mean product of the outcomes, once there is a trial.
+    pub fn correlation(&self) -> Option<f64> {
+        (self.trials > 0).then(|| self.product_sum as f64 / self.trials as f64)
+    }
+}
+
+/// A CHSH Bell test accumulated over the entanglements a universe fires.
+///
+/// Feed it every tick with [`BellTest::record`], or let [`BellTest::run`] drive
+/// the universe and keep it supplied with fresh pairs. It is also an
+/// [`Analyzer`], so the app can show the statistic in the plugin panel.
+#[derive(Debug, Clone)]
+pub struct BellTest {
+    pub settings: BellSettings,
+    /// The fraction of unentangled cells [`BellTest::prepare`] pairs up.
+    pub pair_fraction: f64,
+    /// By Alice's setting, then Bob's.
+    tallies: [[Tally; 2]; 2],
+    /// Trials skipped because a measured coefficient was zero.
+    discarded: u64,
+    /// Chooses the settings of each trial.
+    rng: StdRng,
+}
+
+impl BellTest {
+    /// A test with no trials yet, choosing settings from `seed`.
+    pub fn new(settings: BellSettings, seed: u64) -> Self {
+        BellTest {
+            settings,
+            pair_fraction: 0.1,
+            tallies: [[Tally::default(); 2]; 2],
+            discarded: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Entangles fresh random pairs among the cells that are not entangled, so
+    /// the test keeps getting trials after the first pairs have fired.
+    ///
+    /// The pairs are logged interventions, so replays reproduce the experiment.
+    pub fn prepare(&self, universe: &mut Universe) {
+        let everywhere: Vec<_> = universe.grid_dims.iter().map(|&dim| 0..dim).collect();
+        universe.entangle_region_random(&everywhere, self.pair_fraction);
+    }
+
+    /// Measures both cells of every entanglement fired in `report`.
+    ///
+    /// Call it right after the tick, while `universe` still shows its result.
+    /// A trial is discarded when either cell has a zero coefficient on its
+    /// blade, the way a detector can fail to click.
+    pub fn record(&mut self, universe: &Universe, report: &TickReport) {
+        for (source, target) in &report.entanglements {
+            let (x, y) = (self.rng.random_range(0..2), self.rng.random_range(0..2));
+            let cell = |coord: &[usize]| {
+                let idx = universe.get_index_from_coord(coord)?;
+                Some(&universe.grid[idx].state)
+            };
+            let (Some(alice), Some(bob)) = (cell(source), cell(target)) else {
+                continue;
+            };
+            match (
+                outcome(alice, self.settings.alice[x]),
+                outcome(bob, self.settings.bob[y]),
+            ) {
+                (Some(a), Some(b)) => {
+                    let tally = &mut self.tallies[x][y];
+                    tally.trials += 1;
+                    tally.product_sum += a * b;
+                }
+                _ => self.discarded += 1,
+            }
+        }
+    }
+
+    /// Runs `ticks` ticks of `universe`, preparing pairs before and recording
+    /// trials after each one.
+    pub fn run(&mut self, universe: &mut Universe, ticks: u64) {
+        for _ in 0..ticks {
+            self.prepare(universe);
+            let report = universe.tick();
+            self.record(universe, &report);
+        }
+    }
+
+    /// The trials of Alice's setting `x` and Bob's setting `y`, each 0 or 1.
+    pub fn tally(&self, x: usize, y: usize) -> Tally {
+        self.tallies[x][y]
+    }
+
+    /// The number of trials counted, over all settings.
+    pub fn trials(&self) -> u64 {
+        self.tallies
+            .iter()
+            .flatten()
+            .map(|tally| tally.trials)
+            .sum()
+    }
+
+    /// The number of trials discarded for a zero coefficient.
+    pub fn discarded(&self) -> u64 {
+        self.discarded
+    }
+
+    /// The CHSH statistic, once every pair of settings has a trial.
+    pub fn chsh(&self) -> Option<f64> {
+        let e = |x: usize, y: usize| self.tallies[x][y].correlation();
+        Some(e(0, 0)? - e(0, 1)? + e(1, 0)? + e(1, 1)?)
+    }
+}
+
+impl Default for BellTest {
+    fn default() -> Self {
+        Self::new(BellSettings::default(), 0)
+    }
+}
+
+/// The outcome of measuring `state` along `blade`: the sign of its coefficient,
+/// or `None` if it is zero or the blade does not exist.
+fn outcome(state: &Multivector, blade: usize) -> Option<i64> {
+    match state.coefficients.get(blade)?.0 {
+        0 => None,
+        c => Some(c.signum() as i64),
+    }
+}
+
+impl Analyzer for BellTest {
+    fn name(&self) -> &str {
+        "Bell test"
+    }
+
+    fn on_tick(&mut self, ctx: &PluginCtx) {
+        self.record(ctx.universe, ctx.report);
+    }
+
+    fn summary(&self) -> Vec<String> {
+        let Some(s) = self.chsh() else {
+            return vec![format!(
+                "{} trials; waiting for every setting pair",
+                self.trials()
+            )];
+        };
+        let e = |x: usize, y: usize| self.tallies[x][y].correlation().unwrap_or(0.0);
+        vec![
+            format!(
+                "S = {:.3} over {} trials ({} discarded); |S| <= 2 classically",
+                s,
+                self.trials(),
+                self.discarded
+            ),
+            format!(
+                "E(a,b) {:.2}  E(a,b') {:.2}  E(a',b) {:.2}  E(a',b') {:.2}",
+                e(0, 0),
+                e(0, 1),
+                e(1, 0),
+                e(1, 1)
+            ),
+        ]
+    }
+}
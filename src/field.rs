@@ -0,0 +1,196 @@
+//! Parameter fields: simulation rates that vary from cell to cell.
+//!
+//! By default every cell of a [`Universe`] shares the global observation, decay
+//! and fluctuation rates. A [`ParameterField`] overrides one of them with a value
+//! per cell, so a universe can have "hot" regions that churn and "cold" ones
+//! where structure settles. Fields are painted as gradients, computed from each
+//! cell's coordinate, or loaded from a grayscale image.
+//!
+//! [`Universe`]: crate::universe::Universe
+
+use image::imageops::{self, FilterType};
+use std::path::Path;
+use std::sync::Arc;
+
+/// The rates a [`ParameterField`] can override.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rate {
+    /// The probability of a `Potential` cell being observed each tick.
+    Observation,
+    /// The probability of an `Observed` cell decaying back to `Potential`.
+    Decay,
+    /// The probability of a `Potential` cell re-randomizing its state.
+    Fluctuation,
+}
+
+impl Rate {
+    /// Every rate, in the order fields are stored.
+    pub const ALL: [Rate; 3] = [Rate::Observation, Rate::Decay, Rate::Fluctuation];
+
+    /// The rate's name in lowercase, as schedules and the UI spell it.
+    pub fn name(self) -> &'static str {
+        match self {
+            Rate::Observation => "observation",
+            Rate::Decay => "decay",
+            Rate::Fluctuation => "fluctuation",
+        }
+    }
+
+    /// The rate's position in [`Rate::ALL`].
+    pub(crate) fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// One value in `[0, 1]` for every cell of a grid, by grid index.
+///
+/// Values are stored in single precision, which is plenty for probabilities and
+/// keeps share codes small. The values are shared rather than copied when a
+/// field is cloned, so every tick can hand the field to the update rule cheaply.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParameterField {
+    values: Arc<Vec<f32>>,
+}
+
+impl ParameterField {
+    /// The same value everywhere.
+    pub fn uniform(grid_dims: &[usize], value: f64) -> Self {
+        Self::from_fn(grid_dims, |_| value)
+    }
+
+    /// Computes every cell's value from its coordinate. Values are clamped to `[0, 1]`.
+    pub fn from_fn(grid_dims: &[usize], mut f: impl FnMut(&[usize]) -> f64) -> Self {
+        let size: usize = grid_dims.iter().product();
+        let mut values = Vec::with_capacity(size);
+        let mut coord = vec![0; grid_dims.len()];
+        for _ in 0..size {
+            values.push(f(&coord).clamp(0.0, 1.0) as f32);
+            // The first dimension varies fastest, matching grid index order.
+            for (c, &dim) in coord.iter_mut().zip(grid_dims) {
+                *c += 1;
+                if *c < dim {
+                    break;
+                }
+                *c = 0;
+            }
+        }
+        ParameterField {
+            values: Arc::new(values),
+        }
+    }
+
+    /// Fades from `from` at the start of grid dimension `axis` to `to` at its end.
+    pub fn linear_gradient(grid_dims: &[usize], axis: usize, from: f64, to: f64) -> Self {
+        let span = grid_dims.get(axis).map_or(1, |&dim| dim.max(2) - 1) as f64;
+        Self::from_fn(grid_dims, |coord| {
+            let t = coord.get(axis).map_or(0.0, |&c| c as f64 / span);
+            from + (to - from) * t
+        })
+    }
+
+    /// Fades from `inner` at `center` to `outer` at `radius` cells away and beyond.
+    pub fn radial_gradient(
+        grid_dims: &[usize],
+        center: &[f64],
+        radius: f64,
+        inner: f64,
+        outer: f64,
+    ) -> Self {
+        Self::from_fn(grid_dims, |coord| {
+            let distance = coord
+                .iter()
+                .zip(center)
+                .map(|(&c, &m)| (c as f64 - m).powi(2))
+                .sum::<f64>()
+                .sqrt();
+            let t = if radius > 0.0 {
+                (distance / radius).min(1.0)
+            } else {
+                1.0
+            };
+            inner + (outer - inner) * t
+        })
+    }
+
+    /// Loads a grayscale version of an image, stretched over the first two grid
+    /// dimensions, with black mapping to `low` and white to `high`.
+    ///
+    /// Further grid dimensions repeat the same picture.
+    pub fn from_image(
+        grid_dims: &[usize],
+        path: &Path,
+        low: f64,
+        high: f64,
+    ) -> image::ImageResult<Self> {
+        let width = grid_dims.first().copied().unwrap_or(1).max(1);
+        let height = grid_dims.get(1).copied().unwrap_or(1).max(1);
+        let picture = image::open(path)?.to_luma8();
+        let picture = imageops::resize(&picture, width as u32, height as u32, FilterType::Triangle);
+        Ok(Self::from_fn(grid_dims, |coord| {
+            let x = coord.first().copied().unwrap_or(0) as u32;
+            let y = coord.get(1).copied().unwrap_or(0) as u32;
+            let brightness = picture.get_pixel(x, y).0[0] as f64 / 255.0;
+            low + (high - low) * brightness
+        }))
+    }
+
+    /// The field for a grid resized from `old_dims` to `new_dims`, matching
+    /// [`Universe::resize`]: values in the overlap stay, added dimensions repeat the
+    /// old values, and other new cells take the nearest value on the old grid's edge.
+    ///
+    /// [`Universe::resize`]: crate::universe::Universe::resize
+    pub fn resized(&self, old_dims: &[usize], new_dims: &[usize]) -> Self {
+        Self::from_fn(new_dims, |coord| {
+            let (mut idx, mut stride) = (0, 1);
+            for (d, &dim) in old_dims.iter().enumerate() {
+                let c = coord.get(d).copied().unwrap_or(0).min(dim.max(1) - 1);
+                idx += c * stride;
+                stride *= dim;
+            }
+            self.get(idx)
+        })
+    }
+
+    /// A copy with the cells at the given grid indices set to new values,
+    /// clamped to `[0, 1]`.
+    ///
+    /// # Panics
+    /// If an index is outside the grid the field was made for.
+    pub fn painted(&self, strokes: impl IntoIterator<Item = (usize, f64)>) -> Self {
+        let mut values = self.values.as_ref().clone();
+        for (idx, value) in strokes {
+            values[idx] = value.clamp(0.0, 1.0) as f32;
+        }
+        Self::from_values(values)
+    }
+
+    /// Wraps values already known to lie in `[0, 1]`.
+    pub(crate) fn from_values(values: Vec<f32>) -> Self {
+        ParameterField {
+            values: Arc::new(values),
+        }
+    }
+
+    /// The value of the cell at a grid index.
+    ///
+    /// # Panics
+    /// If the index is outside the grid the field was made for.
+    pub fn get(&self, idx: usize) -> f64 {
+        self.values[idx] as f64
+    }
+
+    /// Every cell's value, by grid index.
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+
+    /// The number of cells the field covers.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    /// Whether the field covers no cells.
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+}
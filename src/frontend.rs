@@ -0,0 +1,137 @@
+//! Frontends by name: the ways of presenting and driving an [`AppState`].
+//!
+//! A [`Frontend`] takes over a fully configured application and runs it until
+//! the user quits. A [`FrontendRegistry`] holds them by name so one is picked
+//! when the application starts (`--frontend NAME`); it comes with the
+//! terminal frontend and a [`Headless`] one, and the binary adds the window.
+//! A third-party frontend only needs the library: everything it has to drive
+//! goes through the application state's actions.
+
+use crate::app::AppState;
+use crate::input::AppAction;
+use crate::tui;
+use std::io;
+use std::time::{Duration, Instant};
+
+/// A way of running the application.
+pub trait Frontend {
+    /// The name it is chosen by.
+    fn name(&self) -> &str;
+    /// Runs `app` until the user quits.
+    fn run(&mut self, app: &mut AppState) -> io::Result<()>;
+}
+
+/// The terminal frontend of the [`tui`] module.
+pub struct Terminal;
+
+impl Frontend for Terminal {
+    fn name(&self) -> &str {
+        "tui"
+    }
+
+    fn run(&mut self, app: &mut AppState) -> io::Result<()> {
+        tui::run(app)
+    }
+}
+
+/// Ticks as fast as it can with nothing drawn, printing a line of statistics
+/// every [`Headless::report_every`], for long runs on servers. Autosaves,
+/// recordings and scripts work as in the window.
+pub struct Headless {
+    /// How many ticks to run before stopping; forever if `None`.
+    pub ticks: Option<u64>,
+    pub report_every: Duration,
+}
+
+impl Default for Headless {
+    fn default() -> Self {
+        Headless {
+            ticks: None,
+            report_every: Duration::from_secs(1),
+        }
+    }
+}
+
+impl Frontend for Headless {
+    fn name(&self) -> &str {
+        "headless"
+    }
+
+    fn run(&mut self, app: &mut AppState) -> io::Result<()> {
+        // Nothing waits on the ticks, so a worker thread would only add copies,
+        // and nothing is watched, so they need not keep to real time.
+        app.worker = None;
+        app.timestep = None;
+        let start = app.universe.tick_count;
+        let mut last_frame = Instant::now();
+        let mut last_report = last_frame;
+        loop {
+            let now = Instant::now();
+            app.apply(AppAction::Advance(
+                now.duration_since(last_frame).as_secs_f64(),
+            ));
+            last_frame = now;
+            for line in app.scripts.take_output() {
+                println!("{}", line);
+            }
+            let done = self
+                .ticks
+                .is_some_and(|ticks| app.universe.tick_count - start >= ticks);
+            if done || now.duration_since(last_report) >= self.report_every {
+                last_report = now;
+                let stats = app.universe.stats();
+                println!(
+                    "tick {}: {} potential, {} observed, {} operators, {} entanglements, entropy {:.3}",
+                    stats.tick,
+                    stats.potential,
+                    stats.observed,
+                    stats.operators,
+                    stats.entanglements,
+                    stats.entropy
+                );
+            }
+            if done {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Frontends by name.
+#[derive(Default)]
+pub struct FrontendRegistry {
+    frontends: Vec<Box<dyn Frontend>>,
+}
+
+impl FrontendRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the frontends the library provides: [`Terminal`] and
+    /// [`Headless`].
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register(Terminal);
+        registry.register(Headless::default());
+        registry
+    }
+
+    /// Adds a frontend, replacing any registered by the same name.
+    pub fn register(&mut self, frontend: impl Frontend + 'static) {
+        self.frontends.retain(|f| f.name() != frontend.name());
+        self.frontends.push(Box::new(frontend));
+    }
+
+    /// The names of the registered frontends, in registration order.
+    pub fn names(&self) -> Vec<&str> {
+        self.frontends.iter().map(|f| f.name()).collect()
+    }
+
+    /// The frontend registered as `name`, if there is one.
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut (dyn Frontend + 'static)> {
+        let frontend = self.frontends.iter_mut().find(|f| f.name() == name)?;
+        Some(frontend.as_mut())
+    }
+}
@@ -1,6 +1,10 @@
 #![allow(clippy::suspicious_arithmetic_impl)]
-use rand::{Rng, rng};
-use std::ops::{Add, Mul};
+use rand::Rng;
+use std::fmt;
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, Neg, Sub};
+
+pub mod verify;
 
 //================================================================================
 // Mod3 - A Tristate Scalar Value {-1, 0, 1}
@@ -10,20 +14,43 @@ use std::ops::{Add, Mul};
 ///
 /// This is the fundamental numeric type in this algebra, ensuring all calculations
 /// remain within a minimal, closed system as described in Doug Matzke's work[cite: 145, 208, 1095].
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Mod3(pub i8);
 
 impl Mod3 {
+    pub const ZERO: Mod3 = Mod3(0);
+    pub const ONE: Mod3 = Mod3(1);
+    pub const MINUS_ONE: Mod3 = Mod3(-1);
+
     /// Creates a new `Mod3` value, normalizing any `i8` to its sign (`-1`, `0`, or `1`).
+    ///
+    /// This is not reduction modulo 3, which [`Mod3::from`] does: `new(2)` is
+    /// `1`, while `Mod3::from(2)` is `-1`.
     pub fn new(val: i8) -> Self {
         Mod3(val.signum())
     }
+
+    /// Clamping addition: the sum in the integers, held to `{-1, 1}`, so
+    /// `1 + 1 = 1` and `-1 + -1 = -1`.
+    ///
+    /// The `+` operator wraps instead, as `Z(3)` does; it is the one the
+    /// algebra uses. Clamping is for rules that want a sum to saturate, like
+    /// a vote that cannot overflow into its opposite.
+    pub fn saturating_add(self, rhs: Self) -> Self {
+        Mod3((self.0 + rhs.0).clamp(-1, 1))
+    }
+
+    /// Clamping subtraction; see [`Mod3::saturating_add`].
+    pub fn saturating_sub(self, rhs: Self) -> Self {
+        self.saturating_add(-rhs)
+    }
 }
 
 /// Implements a custom wrapping addition `Z(3)`.
 ///
 /// If the sum exceeds the bounds of `{-1, 1}`, it wraps around.
 /// For example, `1 + 1 = -1` and `-1 + -1 = 1`[cite: 215, 1097].
+/// [`Mod3::saturating_add`] clamps instead.
 impl Add for Mod3 {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
@@ -38,6 +65,22 @@ impl Add for Mod3 {
     }
 }
 
+/// Negation in `Z(3)`: `+1` and `-1` swap, `0` stays.
+impl Neg for Mod3 {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        Mod3(-self.0)
+    }
+}
+
+/// Wrapping subtraction in `Z(3)`, the inverse of addition: `-1 - 1 = 1`.
+impl Sub for Mod3 {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
 /// Implements standard multiplication for `Mod3` values.
 impl Mul for Mod3 {
     type Output = Self;
@@ -46,6 +89,74 @@ impl Mul for Mod3 {
     }
 }
 
+/// The wrapping sum in `Z(3)`; zero for no values.
+impl Sum for Mod3 {
+    fn sum<I: Iterator<Item = Mod3>>(iter: I) -> Self {
+        iter.fold(Mod3::ZERO, |sum, x| sum + x)
+    }
+}
+
+impl<'a> Sum<&'a Mod3> for Mod3 {
+    fn sum<I: Iterator<Item = &'a Mod3>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+/// The product; one for no values.
+impl Product for Mod3 {
+    fn product<I: Iterator<Item = Mod3>>(iter: I) -> Self {
+        iter.fold(Mod3::ONE, |product, x| product * x)
+    }
+}
+
+impl<'a> Product<&'a Mod3> for Mod3 {
+    fn product<I: Iterator<Item = &'a Mod3>>(iter: I) -> Self {
+        iter.copied().product()
+    }
+}
+
+/// Reduction modulo 3, so `2` is `-1` and `3` is `0`; see [`Mod3::new`] for
+/// taking the sign instead.
+impl From<i8> for Mod3 {
+    fn from(val: i8) -> Self {
+        match val.rem_euclid(3) {
+            0 => Mod3::ZERO,
+            1 => Mod3::ONE,
+            _ => Mod3::MINUS_ONE,
+        }
+    }
+}
+
+impl From<Mod3> for i8 {
+    fn from(val: Mod3) -> Self {
+        val.0
+    }
+}
+
+/// Only `-1`, `0` and `1` convert; anything else is an error rather than
+/// being reduced or clamped.
+impl TryFrom<i64> for Mod3 {
+    type Error = Mod3RangeError;
+    fn try_from(val: i64) -> Result<Self, Self::Error> {
+        match val {
+            -1..=1 => Ok(Mod3(val as i8)),
+            _ => Err(Mod3RangeError(val)),
+        }
+    }
+}
+
+/// A value [`Mod3::try_from`] was given outside `{-1, 0, 1}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Mod3RangeError(pub i64);
+
+impl fmt::Display for Mod3RangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} is not -1, 0 or 1", self.0)
+    }
+}
+
+impl std::error::Error for Mod3RangeError {}
+
 //================================================================================
 // Multivector - The State of an Existon
 //================================================================================
@@ -75,14 +186,282 @@ impl Multivector {
         }
     }
 
-    /// Creates a new `Multivector` with randomized `Mod3` coefficients.
-    pub fn random(p: usize) -> Self {
-        let mut rng = rng();
+    /// A multivector with the given coefficients by blade bitmask, e.g.
+    /// `from_blades(3, &[(0b000, 1), (0b011, -1)])` for `1 - e01`. Values are
+    /// reduced modulo 3 (see [`Mod3::from`]), blades not listed are zero, and
+    /// a blade listed twice keeps its last value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a bitmask names a basis vector beyond `p`.
+    pub fn from_blades(p: usize, blades: &[(usize, i8)]) -> Self {
+        blades
+            .iter()
+            .fold(Multivector::zero(p), |mv, &(mask, value)| {
+                mv.with_blade(mask, value)
+            })
+    }
+
+    /// The multivector with the coefficient of the blade with bitmask `mask`
+    /// set to `value`, for building states one blade at a time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `mask` names a basis vector beyond `p`.
+    pub fn with_blade(mut self, mask: usize, value: impl Into<Mod3>) -> Self {
+        assert!(
+            mask < self.coefficients.len(),
+            "blade {:#b} is outside a space of {} dimensions",
+            mask,
+            self.p
+        );
+        self.coefficients[mask] = value.into();
+        self
+    }
+
+    /// The scalar `value`, reduced modulo 3.
+    pub fn scalar(p: usize, value: i8) -> Self {
+        Multivector::zero(p).with_blade(0, value)
+    }
+
+    /// The basis vector `e_i`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is not below `p`.
+    pub fn basis_vector(p: usize, i: usize) -> Self {
+        Multivector::zero(p).with_blade(1 << i, 1)
+    }
+
+    /// The unit bivector `e_i e_j`, which is `-e_j e_i`: `bivector(p, 1, 0)`
+    /// is `-e01`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` and `j` are equal or either is not below `p`.
+    pub fn bivector(p: usize, i: usize, j: usize) -> Self {
+        assert_ne!(i, j, "a bivector needs two distinct basis vectors");
+        let sign = blade_sign(1 << i, 1 << j);
+        Multivector::zero(p).with_blade((1 << i) | (1 << j), sign)
+    }
+
+    /// The unit pseudoscalar `e_0 e_1 ... e_(p-1)`.
+    pub fn pseudoscalar(p: usize) -> Self {
+        Multivector::zero(p).with_blade((1 << p) - 1, 1)
+    }
+
+    /// Creates a new `Multivector` with randomized `Mod3` coefficients drawn
+    /// from the given RNG, so that seeded universes can be reproduced exactly.
+    pub fn random<R: Rng + ?Sized>(p: usize, rng: &mut R) -> Self {
         let coefficients = (0..(1 << p))
             .map(|_| Mod3::new(rng.random_range(-1..=1)))
             .collect();
         Multivector { p, coefficients }
     }
+
+    /// Creates a sparse random `Multivector` whose components are limited to
+    /// blades of grade `max_grade` or lower.
+    ///
+    /// Each eligible coefficient is zero with probability `sparsity` and
+    /// otherwise `+1` or `-1` with equal chance, so `sparsity = 0.7` gives
+    /// roughly 30% density. Blades above `max_grade` are always zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `sparsity` is not in `[0, 1]`.
+    pub fn random_with<R: Rng + ?Sized>(
+        p: usize,
+        sparsity: f64,
+        max_grade: usize,
+        rng: &mut R,
+    ) -> Self {
+        let coefficients = (0..(1usize << p))
+            .map(|blade| {
+                // The grade of a blade is the number of set bits in its index.
+                if blade.count_ones() as usize > max_grade || rng.random_bool(sparsity) {
+                    Mod3::new(0)
+                } else if rng.random_bool(0.5) {
+                    Mod3::new(1)
+                } else {
+                    Mod3::new(-1)
+                }
+            })
+            .collect();
+        Multivector { p, coefficients }
+    }
+
+    /// The reverse `x~`, which reverses the order of the basis vectors in each
+    /// blade: a grade-`k` blade changes sign when `k(k-1)/2` is odd.
+    pub fn reverse(&self) -> Multivector {
+        let coefficients = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .map(|(blade, &c)| {
+                let k = blade.count_ones();
+                if (k * k.saturating_sub(1) / 2) % 2 == 1 {
+                    -c
+                } else {
+                    c
+                }
+            })
+            .collect();
+        Multivector {
+            p: self.p,
+            coefficients,
+        }
+    }
+
+    /// The outer (wedge) product `a ^ b`: the geometric product with every
+    /// pair of blades that share a basis vector left out.
+    pub fn wedge(&self, rhs: &Multivector) -> Multivector {
+        assert_eq!(self.p, rhs.p);
+        let mut result = Multivector::zero(self.p);
+        for (i, &a) in self.coefficients.iter().enumerate() {
+            if a.0 == 0 {
+                continue;
+            }
+            for (j, &b) in rhs.coefficients.iter().enumerate() {
+                if b.0 == 0 || i & j != 0 {
+                    continue;
+                }
+                result.coefficients[i | j] = result.coefficients[i | j] + a * b * blade_sign(i, j);
+            }
+        }
+        result
+    }
+
+    /// Relabels the basis vectors, sending each `e_i` to `e_{permutation[i]}`.
+    ///
+    /// Every blade moves to the blade of its relabeled vectors, changing sign
+    /// when putting them back in ascending order takes an odd number of
+    /// swaps, so this is [`Multivector::map_basis`] for a permutation matrix
+    /// at a fraction of the cost. It preserves the geometric product.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `permutation` is not a permutation of `0..p`.
+    pub fn permute_basis(&self, permutation: &[usize]) -> Multivector {
+        let p = self.p;
+        let mut seen = vec![false; p];
+        assert!(
+            permutation.len() == p
+                && permutation
+                    .iter()
+                    .all(|&i| i < p && !std::mem::replace(&mut seen[i], true)),
+            "{:?} is not a permutation of {} basis vectors",
+            permutation,
+            p
+        );
+        let mut result = Multivector::zero(p);
+        for (blade, &c) in self.coefficients.iter().enumerate() {
+            if c.0 == 0 {
+                continue;
+            }
+            let images: Vec<usize> = (0..p)
+                .filter(|i| blade & (1 << i) != 0)
+                .map(|i| permutation[i])
+                .collect();
+            let inversions: usize = images
+                .iter()
+                .enumerate()
+                .map(|(k, &a)| images[k + 1..].iter().filter(|&&b| b < a).count())
+                .sum();
+            let target = images.iter().fold(0, |mask, &i| mask | (1 << i));
+            result.coefficients[target] = if inversions.is_multiple_of(2) { c } else { -c };
+        }
+        result
+    }
+
+    /// Swaps the basis vectors `e_i` and `e_j`; see
+    /// [`Multivector::permute_basis`].
+    pub fn swap_basis(&self, i: usize, j: usize) -> Multivector {
+        let mut permutation: Vec<usize> = (0..self.p).collect();
+        permutation.swap(i, j);
+        self.permute_basis(&permutation)
+    }
+
+    /// Applies the outermorphism of a linear map of the basis vectors: each
+    /// `e_i` becomes `sum_j matrix[j][i] e_j`, so column `i` of the matrix is
+    /// the image of `e_i`, and every blade `e_i ^ e_j ^ ...` becomes the wedge
+    /// of the images of its vectors. Scalars are unchanged and the whole map
+    /// is linear, so the pseudoscalar is scaled by the matrix's determinant.
+    ///
+    /// A permutation matrix relabels the frame and a shear skews it, which
+    /// lets a rule give the universe a direction. Only orthogonal maps also
+    /// preserve the geometric product.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `matrix` is not `p` by `p`.
+    pub fn map_basis(&self, matrix: &[Vec<Mod3>]) -> Multivector {
+        let p = self.p;
+        assert!(
+            matrix.len() == p && matrix.iter().all(|row| row.len() == p),
+            "a basis map of {} dimensions needs a {0} by {0} matrix",
+            p
+        );
+        let images: Vec<Multivector> = (0..p)
+            .map(|i| {
+                let mut image = Multivector::zero(p);
+                for (j, row) in matrix.iter().enumerate() {
+                    image.coefficients[1 << j] = row[i];
+                }
+                image
+            })
+            .collect();
+        let mut result = Multivector::zero(p);
+        for (blade, &c) in self.coefficients.iter().enumerate() {
+            if c.0 == 0 {
+                continue;
+            }
+            let mut image = Multivector::zero(p);
+            image.coefficients[0] = c;
+            for (i, vector) in images.iter().enumerate() {
+                if blade & (1 << i) != 0 {
+                    image = image.wedge(vector);
+                }
+            }
+            result += &image;
+        }
+        result
+    }
+}
+
+/// Returns the conventional name of a basis blade from its bitmask index.
+///
+/// Index 0 is the scalar `1`; otherwise the name lists the set bits in ascending
+/// order, e.g. index 5 (0b101) is `e02`. Above nine dimensions the indices are
+/// separated by underscores (`e0_10`) to stay unambiguous.
+pub fn blade_name(index: usize) -> String {
+    if index == 0 {
+        return "1".to_string();
+    }
+    let bits: Vec<String> = (0..usize::BITS as usize)
+        .filter(|bit| (index >> bit) & 1 != 0)
+        .map(|bit| bit.to_string())
+        .collect();
+    let separator = if bits.iter().any(|b| b.len() > 1) {
+        "_"
+    } else {
+        ""
+    };
+    format!("e{}", bits.join(separator))
+}
+
+/// The sign of the product of the basis blades with bitmasks `i` and `j`.
+fn blade_sign(i: usize, j: usize) -> Mod3 {
+    let mut sign_flips = 0;
+    let mut shifted_i = i >> 1;
+    while shifted_i != 0 {
+        sign_flips += (shifted_i & j).count_ones();
+        shifted_i >>= 1;
+    }
+    if sign_flips % 2 == 0 {
+        Mod3::ONE
+    } else {
+        Mod3::MINUS_ONE
+    }
 }
 
 /// Implements the core update rule: the Geometric Product `a * b`.
@@ -120,24 +499,8 @@ impl Mul for &Multivector {
                 // This correctly handles `e_i * e_i = 1` by removing common basis vectors.
                 let result_blade = i ^ j;
 
-                // --- CORRECTED SIGN CALCULATION ---
-                // To find the sign, we count the number of times a basis vector from `rhs` (j)
-                // must swap places with a basis vector of `self` (i) that has a higher index.
-                let mut sign_flips = 0;
-                for bit_j in 0..self.p {
-                    // If the k-th basis vector exists in blade j...
-                    if (j >> bit_j) & 1 != 0 {
-                        // ...count how many basis vectors in blade i have a higher index.
-                        // These are the vectors that e_k must be moved past, causing a sign flip.
-                        let mask_i = i >> (bit_j + 1);
-                        sign_flips += mask_i.count_ones();
-                    }
-                }
-
-                let sign = if sign_flips % 2 == 0 { 1 } else { -1 };
-
                 // Calculate the product of the coefficients and apply the sign.
-                let product_coeff = a_coeff * b_coeff * Mod3::new(sign);
+                let product_coeff = a_coeff * b_coeff * blade_sign(i, j);
 
                 // Add the result to the correct component of the final multivector.
                 result.coefficients[result_blade] =
@@ -162,3 +525,76 @@ impl Add for &Multivector {
         result
     }
 }
+
+/// Implements component-wise subtraction, the inverse of addition in `Z(3)`.
+impl Sub for &Multivector {
+    type Output = Multivector;
+    fn sub(self, rhs: &Multivector) -> Self::Output {
+        assert_eq!(self.p, rhs.p);
+        let mut result = Multivector::zero(self.p);
+        for i in 0..(1 << self.p) {
+            result.coefficients[i] = self.coefficients[i] - rhs.coefficients[i];
+        }
+        result
+    }
+}
+
+/// Scales every coefficient by a `Mod3` scalar, the same as the geometric
+/// product with that scalar but without the product's cost.
+impl Mul<Mod3> for &Multivector {
+    type Output = Multivector;
+    fn mul(self, rhs: Mod3) -> Self::Output {
+        Multivector {
+            p: self.p,
+            coefficients: self.coefficients.iter().map(|&c| c * rhs).collect(),
+        }
+    }
+}
+
+/// Scalar multiplication with the scalar on the left; `Mod3` products commute.
+impl Mul<&Multivector> for Mod3 {
+    type Output = Multivector;
+    fn mul(self, rhs: &Multivector) -> Self::Output {
+        rhs * self
+    }
+}
+
+/// Negates every coefficient, so `a + -a` is zero.
+impl Neg for &Multivector {
+    type Output = Multivector;
+    fn neg(self) -> Self::Output {
+        self * Mod3::MINUS_ONE
+    }
+}
+
+/// Implements in-place component-wise addition, avoiding a new allocation per sum.
+impl AddAssign<&Multivector> for Multivector {
+    fn add_assign(&mut self, rhs: &Multivector) {
+        assert_eq!(self.p, rhs.p);
+        for (a, &b) in self.coefficients.iter_mut().zip(rhs.coefficients.iter()) {
+            *a = *a + b;
+        }
+    }
+}
+
+/// Writes the multivector in blade notation, e.g. `1 - e0 + e01`, leaving out
+/// zero coefficients; the zero multivector is `0`.
+impl fmt::Display for Multivector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut terms = self
+            .coefficients
+            .iter()
+            .enumerate()
+            .filter(|(_, c)| c.0 != 0);
+        let Some((blade, first)) = terms.next() else {
+            return write!(f, "0");
+        };
+        let sign = if first.0 < 0 { "-" } else { "" };
+        write!(f, "{}{}", sign, blade_name(blade))?;
+        for (blade, c) in terms {
+            let sign = if c.0 < 0 { '-' } else { '+' };
+            write!(f, " {} {}", sign, blade_name(blade))?;
+        }
+        Ok(())
+    }
+}
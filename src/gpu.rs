@@ -0,0 +1,335 @@
+//! The local-interaction pass on the GPU, behind the `gpu` feature.
+//!
+//! Summing a cell's neighbors and taking one geometric product is the same
+//! small computation for every cell, which suits a compute shader. A
+//! [`GpuTicker`] uploads the grid packed at two bits per coefficient, runs that
+//! pass for all cells at once and reads back only the packed products. The
+//! stochastic part of the rule still runs on the CPU in index order, so a seeded
+//! run is bit-identical with and without the GPU.
+//!
+//! Only rules that build on [`RuleCtx::local_interaction`](crate::rule::RuleCtx::local_interaction),
+//! such as the default rule, benefit; others ignore the precomputed products.
+
+use crate::ga_core::{Mod3, Multivector};
+use crate::universe::{BoundaryCondition, Neighborhood, TickReport, Topology, Universe};
+use std::fmt;
+use wgpu::util::DeviceExt;
+
+/// Packing two bits per coefficient into a `u32` limits the GPU path to `Cl(4,0)`.
+pub const MAX_GA_DIMS: usize = 4;
+/// Threads per workgroup; must match `@workgroup_size` in the shader.
+const WORKGROUP: u32 = 64;
+/// The most workgroups a single dispatch dimension may have.
+const MAX_GROUPS_PER_DIM: u32 = 65_535;
+
+const SHADER: &str = r#"
+struct Params {
+    blades: u32,
+    cells: u32,
+    row: u32,
+}
+
+@group(0) @binding(0) var<uniform> params: Params;
+@group(0) @binding(1) var<storage, read> states: array<u32>;
+@group(0) @binding(2) var<storage, read> starts: array<u32>;
+@group(0) @binding(3) var<storage, read> table: array<u32>;
+@group(0) @binding(4) var<storage, read_write> products: array<u32>;
+
+// Unpacks a coefficient stored as 0, 1 or 2 (for -1).
+fn coeff(packed: u32, blade: u32) -> i32 {
+    let bits = (packed >> (2u * blade)) & 3u;
+    return select(i32(bits), -1, bits == 2u);
+}
+
+// Reduces an integer into {-1, 0, 1} modulo 3.
+// The remainder is taken on the magnitude, as signed `%` is not portable.
+fn wrap(value: i32) -> i32 {
+    let r = i32(u32(abs(value)) % 3u);
+    let folded = select(r, -1, r == 2);
+    return select(folded, -folded, value < 0);
+}
+
+// The reordering sign of the blade product a * b in Cl(p,0).
+fn reorder_sign(a: u32, b: u32) -> i32 {
+    var flips = 0u;
+    var shifted = a >> 1u;
+    loop {
+        if shifted == 0u {
+            break;
+        }
+        flips += countOneBits(shifted & b);
+        shifted = shifted >> 1u;
+    }
+    return select(1, -1, (flips & 1u) == 1u);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>) {
+    let cell = id.x + id.y * params.row;
+    if cell >= params.cells {
+        return;
+    }
+    // The neighbor sum, coefficient by coefficient.
+    var sum: array<i32, 16>;
+    for (var n = starts[cell]; n < starts[cell + 1u]; n++) {
+        let neighbor = states[table[n]];
+        for (var blade = 0u; blade < params.blades; blade++) {
+            sum[blade] += coeff(neighbor, blade);
+        }
+    }
+    var result: array<i32, 16>;
+    let state = states[cell];
+    for (var i = 0u; i < params.blades; i++) {
+        let a = wrap(sum[i]);
+        if a == 0 {
+            continue;
+        }
+        for (var j = 0u; j < params.blades; j++) {
+            let b = coeff(state, j);
+            if b != 0 {
+                result[i ^ j] += a * b * reorder_sign(i, j);
+            }
+        }
+    }
+    var packed = 0u;
+    for (var blade = 0u; blade < params.blades; blade++) {
+        let c = wrap(result[blade]);
+        packed |= select(u32(c), 2u, c == -1) << (2u * blade);
+    }
+    products[cell] = packed;
+}
+"#;
+
+/// Why the GPU path could not be set up.
+#[derive(Debug)]
+pub enum GpuError {
+    /// No compatible adapter was found.
+    NoAdapter(wgpu::RequestAdapterError),
+    /// The adapter refused to create a device.
+    Device(wgpu::RequestDeviceError),
+    /// The universe's algebra has more than [`MAX_GA_DIMS`] dimensions.
+    UnsupportedDims(usize),
+}
+
+impl fmt::Display for GpuError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuError::NoAdapter(e) => write!(f, "no GPU adapter available: {}", e),
+            GpuError::Device(e) => write!(f, "could not open the GPU: {}", e),
+            GpuError::UnsupportedDims(p) => write!(
+                f,
+                "the GPU path supports up to {} GA dimensions, not {}",
+                MAX_GA_DIMS, p
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GpuError {}
+
+/// The neighbor table and buffers sized for one grid shape.
+struct Buffers {
+    /// The boundary, neighborhood, topology and cell count the table was built for.
+    key: (BoundaryCondition, Neighborhood, Topology, usize),
+    states: wgpu::Buffer,
+    products: wgpu::Buffer,
+    readback: wgpu::Buffer,
+    bind_group: wgpu::BindGroup,
+    groups: [u32; 2],
+}
+
+/// Ticks universes with their local interaction computed on the GPU.
+pub struct GpuTicker {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    pipeline: wgpu::ComputePipeline,
+    buffers: Option<Buffers>,
+}
+
+impl GpuTicker {
+    /// Opens the default GPU and compiles the interaction shader.
+    pub fn new() -> Result<Self, GpuError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter =
+            pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+                .map_err(GpuError::NoAdapter)?;
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default()))
+                .map_err(GpuError::Device)?;
+        let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("existons interaction"),
+            source: wgpu::ShaderSource::Wgsl(SHADER.into()),
+        });
+        let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+            label: Some("existons interaction"),
+            layout: None,
+            module: &module,
+            entry_point: Some("main"),
+            compilation_options: Default::default(),
+            cache: None,
+        });
+        Ok(GpuTicker {
+            device,
+            queue,
+            pipeline,
+            buffers: None,
+        })
+    }
+
+    /// Advances `universe` by one tick, exactly as [`Universe::tick`] would.
+    pub fn tick(&mut self, universe: &mut Universe) -> Result<TickReport, GpuError> {
+        let interactions = self.interactions(universe)?;
+        Ok(universe.tick_with_interactions(&interactions))
+    }
+
+    /// Every cell's local interaction, computed on the GPU.
+    pub fn interactions(&mut self, universe: &Universe) -> Result<Vec<Multivector>, GpuError> {
+        if universe.ga_dims > MAX_GA_DIMS {
+            return Err(GpuError::UnsupportedDims(universe.ga_dims));
+        }
+        let key = (
+            universe.boundary,
+            universe.neighborhood().clone(),
+            universe.topology(),
+            universe.grid.len(),
+        );
+        if self.buffers.as_ref().is_none_or(|b| b.key != key) {
+            self.buffers = Some(self.upload_layout(universe, key));
+        }
+        let buffers = self.buffers.as_ref().expect("uploaded above");
+
+        let packed: Vec<u32> = universe.grid.iter().map(|e| pack(&e.state)).collect();
+        self.queue
+            .write_buffer(&buffers.states, 0, bytemuck::cast_slice(&packed));
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        {
+            let mut pass = encoder.begin_compute_pass(&Default::default());
+            pass.set_pipeline(&self.pipeline);
+            pass.set_bind_group(0, &buffers.bind_group, &[]);
+            pass.dispatch_workgroups(buffers.groups[0], buffers.groups[1], 1);
+        }
+        let size = buffers.products.size();
+        encoder.copy_buffer_to_buffer(&buffers.products, 0, &buffers.readback, 0, size);
+        self.queue.submit([encoder.finish()]);
+
+        let slice = buffers.readback.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| {
+            result.expect("mapping the readback buffer failed")
+        });
+        self.device
+            .poll(wgpu::PollType::Wait)
+            .expect("the GPU stopped responding");
+        let products = {
+            let bytes = slice.get_mapped_range();
+            let products: &[u32] = bytemuck::cast_slice(&bytes);
+            products
+                .iter()
+                .take(universe.grid.len())
+                .map(|&p| unpack(p, universe.ga_dims))
+                .collect()
+        };
+        buffers.readback.unmap();
+        Ok(products)
+    }
+
+    /// Uploads the neighbor table and allocates buffers for the universe's shape.
+    fn upload_layout(
+        &self,
+        universe: &Universe,
+        key: (BoundaryCondition, Neighborhood, Topology, usize),
+    ) -> Buffers {
+        let device = &self.device;
+        let cells = universe.grid.len() as u32;
+        let (starts, table) = universe.neighbor_table();
+        let to_u32 = |v: &[usize]| v.iter().map(|&i| i as u32).collect::<Vec<u32>>();
+        let storage = |label, contents: &[u32]| {
+            device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+                label: Some(label),
+                // Empty storage buffers are not allowed.
+                contents: bytemuck::cast_slice(if contents.is_empty() { &[0] } else { contents }),
+                usage: wgpu::BufferUsages::STORAGE,
+            })
+        };
+        let starts = storage("neighbor starts", &to_u32(starts));
+        let table = storage("neighbor table", &to_u32(table));
+
+        let groups_needed = cells.div_ceil(WORKGROUP);
+        let groups = [
+            groups_needed.clamp(1, MAX_GROUPS_PER_DIM),
+            groups_needed.div_ceil(MAX_GROUPS_PER_DIM).max(1),
+        ];
+        let params = [1u32 << universe.ga_dims, cells, groups[0] * WORKGROUP];
+        let params = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("params"),
+            contents: bytemuck::cast_slice(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        });
+        let bytes = (cells.max(1) as u64) * 4;
+        let buffer = |label, usage| {
+            device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some(label),
+                size: bytes,
+                usage,
+                mapped_at_creation: false,
+            })
+        };
+        let states = buffer(
+            "states",
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+        );
+        let products = buffer(
+            "products",
+            wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC,
+        );
+        let readback = buffer(
+            "readback",
+            wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+        );
+        let entries: Vec<_> = [&params, &states, &starts, &table, &products]
+            .into_iter()
+            .enumerate()
+            .map(|(binding, buffer)| wgpu::BindGroupEntry {
+                binding: binding as u32,
+                resource: buffer.as_entire_binding(),
+            })
+            .collect();
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("existons interaction"),
+            layout: &self.pipeline.get_bind_group_layout(0),
+            entries: &entries,
+        });
+        Buffers {
+            key,
+            states,
+            products,
+            readback,
+            bind_group,
+            groups,
+        }
+    }
+}
+
+/// Packs a multivector of up to [`MAX_GA_DIMS`] dimensions at two bits per
+/// coefficient, storing `-1` as `2`.
+pub fn pack(mv: &Multivector) -> u32 {
+    mv.coefficients
+        .iter()
+        .enumerate()
+        .fold(0, |packed, (blade, c)| {
+            let bits = if c.0 < 0 { 2 } else { c.0 as u32 };
+            packed | bits << (2 * blade)
+        })
+}
+
+/// Reverses [`pack`].
+pub fn unpack(packed: u32, p: usize) -> Multivector {
+    let mut mv = Multivector::zero(p);
+    for (blade, c) in mv.coefficients.iter_mut().enumerate() {
+        *c = match (packed >> (2 * blade)) & 3 {
+            1 => Mod3(1),
+            2 => Mod3(-1),
+            _ => Mod3(0),
+        };
+    }
+    mv
+}
@@ -0,0 +1,182 @@
+//! The entanglement network as a graph file for external tools.
+//!
+//! [`EntanglementGraph`] turns a universe's entanglement groups into nodes and
+//! edges: every entangled cell is a node, and every pair of cells sharing a
+//! group is an edge, since each collapses the other. Graphs are written as DOT
+//! (Graphviz, networkx) or GraphML (Gephi, networkx, igraph), so degree
+//! distributions and clustering can be measured as a run goes on.
+
+use crate::existon::ConsciousnessState;
+use crate::universe::Universe;
+use std::fmt::Write;
+
+/// The file formats an [`EntanglementGraph`] can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    /// Graphviz's DOT language.
+    #[default]
+    Dot,
+    /// The XML-based GraphML format.
+    GraphMl,
+}
+
+impl GraphFormat {
+    /// The usual file extension, without the dot.
+    pub fn extension(self) -> &'static str {
+        match self {
+            GraphFormat::Dot => "dot",
+            GraphFormat::GraphMl => "graphml",
+        }
+    }
+}
+
+/// An entangled cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphNode {
+    pub id: u64,
+    pub coord: Vec<usize>,
+    pub consciousness: ConsciousnessState,
+}
+
+/// Two cells in the same entanglement group.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphEdge {
+    /// The IDs of the two cells, the lower first.
+    pub ids: (u64, u64),
+    pub group: u64,
+    /// The group's strength; see [`EntanglementGroups::strength`].
+    ///
+    /// [`EntanglementGroups::strength`]: crate::entanglement::EntanglementGroups::strength
+    pub strength: f64,
+}
+
+/// The entanglement network of a universe at one tick; see
+/// [`Universe::entanglement_graph`].
+///
+/// Cells that are not entangled are left out: their degree is zero, and there
+/// are `grid.len() - nodes.len()` of them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EntanglementGraph {
+    /// The tick the graph describes.
+    pub tick: u64,
+    /// In ascending ID order.
+    pub nodes: Vec<GraphNode>,
+    /// Group by group, each group's pairs in ascending ID order.
+    pub edges: Vec<GraphEdge>,
+}
+
+impl EntanglementGraph {
+    /// Builds the graph of `universe`'s entanglement groups.
+    pub fn of(universe: &Universe) -> Self {
+        let entanglement = &universe.entanglement;
+        let mut graph = EntanglementGraph {
+            tick: universe.tick_count,
+            ..EntanglementGraph::default()
+        };
+        for group in entanglement.group_ids() {
+            let members = entanglement.members(group);
+            let strength = entanglement.strength(group);
+            for (n, &a) in members.iter().enumerate() {
+                for &b in &members[n + 1..] {
+                    graph.edges.push(GraphEdge {
+                        ids: (a, b),
+                        group,
+                        strength,
+                    });
+                }
+                if let Some(idx) = universe.index_of_id(a) {
+                    graph.nodes.push(GraphNode {
+                        id: a,
+                        coord: universe.get_coord_from_index(idx),
+                        consciousness: universe.grid[idx].consciousness,
+                    });
+                }
+            }
+        }
+        graph.nodes.sort_unstable_by_key(|node| node.id);
+        graph
+    }
+
+    /// The graph written in `format`.
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Dot => self.to_dot(),
+            GraphFormat::GraphMl => self.to_graphml(),
+        }
+    }
+
+    /// The graph in Graphviz's DOT language. Nodes are named `n<ID>`.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("graph entanglement {\n");
+        writeln!(out, "  // tick {}", self.tick).unwrap();
+        for node in &self.nodes {
+            writeln!(
+                out,
+                "  n{} [label=\"{}\", coord=\"{}\", consciousness=\"{:?}\"];",
+                node.id,
+                node.id,
+                coord_label(&node.coord),
+                node.consciousness
+            )
+            .unwrap();
+        }
+        for edge in &self.edges {
+            writeln!(
+                out,
+                "  n{} -- n{} [group={}, strength={}];",
+                edge.ids.0, edge.ids.1, edge.group, edge.strength
+            )
+            .unwrap();
+        }
+        out.push_str("}\n");
+        out
+    }
+
+    /// The graph in GraphML, with the coordinate and consciousness as node
+    /// attributes and the group and strength as edge attributes.
+    pub fn to_graphml(&self) -> String {
+        let mut out = String::from(concat!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n",
+            "<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n",
+            "  <key id=\"coord\" for=\"node\" attr.name=\"coord\" attr.type=\"string\"/>\n",
+            "  <key id=\"consciousness\" for=\"node\" attr.name=\"consciousness\" attr.type=\"string\"/>\n",
+            "  <key id=\"group\" for=\"edge\" attr.name=\"group\" attr.type=\"long\"/>\n",
+            "  <key id=\"strength\" for=\"edge\" attr.name=\"strength\" attr.type=\"double\"/>\n",
+        ));
+        writeln!(
+            out,
+            "  <graph id=\"entanglement-{}\" edgedefault=\"undirected\">",
+            self.tick
+        )
+        .unwrap();
+        for node in &self.nodes {
+            writeln!(
+                out,
+                "    <node id=\"n{}\"><data key=\"coord\">{}</data><data key=\"consciousness\">{:?}</data></node>",
+                node.id,
+                coord_label(&node.coord),
+                node.consciousness
+            )
+            .unwrap();
+        }
+        for edge in &self.edges {
+            writeln!(
+                out,
+                "    <edge source=\"n{}\" target=\"n{}\"><data key=\"group\">{}</data><data key=\"strength\">{}</data></edge>",
+                edge.ids.0, edge.ids.1, edge.group, edge.strength
+            )
+            .unwrap();
+        }
+        out.push_str("  </graph>\n</graphml>\n");
+        out
+    }
+}
+
+/// A coordinate as comma-separated numbers.
+fn coord_label(coord: &[usize]) -> String {
+    coord
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -0,0 +1,79 @@
+//! The help overlay behind `[H]`: every tool with what the mouse does with it,
+//! every key, and the color legend of the current view mode.
+//!
+//! The keys are read from the app's [`Keymap`](crate::keymap::Keymap), so the
+//! overlay shows the bindings in use, rebindings included, and lists an action
+//! only while a key triggers it.
+
+use crate::app::{AppState, ToolMode};
+use crate::input::AppAction;
+
+/// One line of the overlay.
+#[derive(Debug, Clone, PartialEq)]
+pub enum HelpLine {
+    /// The title of a section.
+    Heading(&'static str),
+    /// Keys or mouse buttons, and what they do.
+    Entry(String, String),
+    /// Running text, such as a line of the color legend.
+    Text(String),
+}
+
+/// What each tool does with the mouse.
+fn tool_help(tool: ToolMode) -> &'static str {
+    match tool {
+        ToolMode::Observe => {
+            "Observe: hovering collapses a few cells in the brush, holding left all of them"
+        }
+        ToolMode::Entangle => {
+            "Entangle: left-click two Potential cells to pair them, Shift to grow the group"
+        }
+        ToolMode::Operator => {
+            "Operator: hold left to paint operators, right to clear them, Alt+click to pick one up"
+        }
+        ToolMode::Disrupt => {
+            "Disrupt: left-click to scramble the brush and send out a wave, hold to grow the brush"
+        }
+        ToolMode::Select => "Select: drag with left to select a rectangle",
+        ToolMode::Inspect => "Inspect: hover to look inside a cell, left-click to pin it",
+        ToolMode::Paint => {
+            "Paint: hold left to raise a rate under the brush, right to lower it, O for the rate"
+        }
+    }
+}
+
+/// The mouse controls that work with every tool.
+const MOUSE: [(&str, &str); 3] = [
+    ("Middle drag", "Pan the view"),
+    ("Wheel", "Zoom about the cursor"),
+    ("Ctrl+Wheel", "Resize the brush"),
+];
+
+/// The overlay's lines for `app`, section by section.
+pub fn help_lines(app: &AppState) -> Vec<HelpLine> {
+    let keymap = &app.keymap;
+    let mut lines = vec![HelpLine::Heading("Tools")];
+    for tool in ToolMode::ALL {
+        let keys = keymap
+            .keys(AppAction::SelectTool(tool))
+            .unwrap_or_else(|| "-".to_string());
+        lines.push(HelpLine::Entry(keys, tool_help(tool).to_string()));
+    }
+    for (buttons, what) in MOUSE {
+        lines.push(HelpLine::Entry(buttons.to_string(), what.to_string()));
+    }
+
+    lines.push(HelpLine::Heading("Keys"));
+    for (action, keys, what) in keymap.help() {
+        if !matches!(action, AppAction::SelectTool(_)) {
+            lines.push(HelpLine::Entry(keys, what.to_string()));
+        }
+    }
+
+    lines.push(HelpLine::Heading("Colors"));
+    let legend = app
+        .view_mode
+        .describe(&app.color_mapping, app.shown_universe().ga_dims);
+    lines.extend(legend.into_iter().map(HelpLine::Text));
+    lines
+}
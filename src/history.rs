@@ -0,0 +1,77 @@
+//! Undo and redo for the universe.
+//!
+//! [`History`] is a bounded ring of [`Checkpoint`]s. The app records one before
+//! every edit and every few ticks, so a stray click can be taken back without
+//! losing the setup around it.
+
+use crate::universe::{Checkpoint, Universe};
+use std::collections::VecDeque;
+
+/// Past and undone states of one universe.
+#[derive(Debug, Clone)]
+pub struct History {
+    past: VecDeque<Checkpoint>,
+    future: Vec<Checkpoint>,
+    capacity: usize,
+}
+
+impl History {
+    /// An empty history keeping at most `capacity` past states.
+    pub fn new(capacity: usize) -> Self {
+        History {
+            past: VecDeque::with_capacity(capacity),
+            future: Vec::new(),
+            capacity,
+        }
+    }
+
+    /// Remembers the universe's current state, forgetting the oldest one if full.
+    ///
+    /// Recording a new state discards everything that was undone.
+    pub fn record(&mut self, universe: &Universe) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.past.len() == self.capacity {
+            self.past.pop_front();
+        }
+        self.past.push_back(universe.checkpoint());
+        self.future.clear();
+    }
+
+    /// Steps back to the most recently recorded state, returning whether there was one.
+    pub fn undo(&mut self, universe: &mut Universe) -> bool {
+        let Some(previous) = self.past.pop_back() else {
+            return false;
+        };
+        self.future.push(universe.checkpoint());
+        universe.restore(previous);
+        true
+    }
+
+    /// Steps forward to the most recently undone state, returning whether there was one.
+    pub fn redo(&mut self, universe: &mut Universe) -> bool {
+        let Some(next) = self.future.pop() else {
+            return false;
+        };
+        self.past.push_back(universe.checkpoint());
+        universe.restore(next);
+        true
+    }
+
+    /// Forgets everything, for when the universe itself is replaced.
+    pub fn clear(&mut self) {
+        self.past.clear();
+        self.future.clear();
+    }
+
+    /// The number of states that can be undone.
+    pub fn undo_depth(&self) -> usize {
+        self.past.len()
+    }
+
+    /// The number of states that can be redone.
+    pub fn redo_depth(&self) -> usize {
+        self.future.len()
+    }
+}
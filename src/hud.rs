@@ -0,0 +1,121 @@
+//! The heads-up display: how fast the run is going and what it holds.
+//!
+//! A [`Hud`] is told about every drawn frame and keeps the last second of
+//! them, which gives the frame rate and the tick rate actually achieved. It
+//! also holds a copy of [`Universe::stats`], refreshed a few times a second
+//! rather than every frame, since the statistics take a pass over the grid.
+
+use crate::stats::UniverseStats;
+use crate::universe::Universe;
+use std::collections::VecDeque;
+
+/// The frame and tick rates and population of the run; see the
+/// [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hud {
+    /// Whether the frontend shows the HUD.
+    pub visible: bool,
+    /// The time and tick of every frame in the last [`Hud::WINDOW`] seconds.
+    frames: VecDeque<(f64, u64)>,
+    /// Seconds since the first frame.
+    clock: f64,
+    stats: Option<UniverseStats>,
+    /// When `stats` was last refreshed, on `clock`.
+    refreshed: f64,
+}
+
+impl Default for Hud {
+    fn default() -> Self {
+        Hud {
+            visible: true,
+            frames: VecDeque::new(),
+            clock: 0.0,
+            stats: None,
+            refreshed: 0.0,
+        }
+    }
+}
+
+impl Hud {
+    /// How many seconds of frames the rates are averaged over.
+    pub const WINDOW: f64 = 1.0;
+    /// How many seconds the statistics are kept before they are recomputed.
+    pub const REFRESH: f64 = 0.25;
+
+    /// Records a frame drawn `seconds` after the previous one, showing
+    /// `universe`.
+    pub fn record_frame(&mut self, seconds: f64, universe: &Universe) {
+        self.clock += seconds;
+        let tick = universe.tick_count;
+        // A reset or rewind starts the tick rate afresh.
+        if self.frames.back().is_some_and(|&(_, last)| tick < last) {
+            self.frames.clear();
+        }
+        self.frames.push_back((self.clock, tick));
+        while self
+            .frames
+            .front()
+            .is_some_and(|&(at, _)| self.clock - at > Self::WINDOW)
+        {
+            self.frames.pop_front();
+        }
+        let stale = self
+            .stats
+            .as_ref()
+            .is_none_or(|stats| stats.tick != tick && self.clock - self.refreshed >= Self::REFRESH);
+        if stale {
+            self.stats = Some(universe.stats());
+            self.refreshed = self.clock;
+        }
+    }
+
+    /// The seconds spanned by the recorded frames.
+    fn span(&self) -> f64 {
+        match (self.frames.front(), self.frames.back()) {
+            (Some(&(first, _)), Some(&(last, _))) => last - first,
+            _ => 0.0,
+        }
+    }
+
+    /// Frames drawn per second over the last second.
+    pub fn fps(&self) -> f64 {
+        let span = self.span();
+        if span > 0.0 {
+            (self.frames.len() - 1) as f64 / span
+        } else {
+            0.0
+        }
+    }
+
+    /// Ticks run per second over the last second.
+    pub fn ticks_per_second(&self) -> f64 {
+        let span = self.span();
+        match (self.frames.front(), self.frames.back()) {
+            (Some(&(_, first)), Some(&(_, last))) if span > 0.0 => (last - first) as f64 / span,
+            _ => 0.0,
+        }
+    }
+
+    /// The most recent statistics of the universe, if a frame has been drawn.
+    pub fn stats(&self) -> Option<&UniverseStats> {
+        self.stats.as_ref()
+    }
+
+    /// A one-line summary for the frontend to show: the live tick, the rates,
+    /// and the population as of the last refresh.
+    pub fn describe(&self) -> String {
+        let rates = format!(
+            "{:.0} fps  {:.0} ticks/s",
+            self.fps(),
+            self.ticks_per_second()
+        );
+        let tick = self.frames.back().map_or(0, |&(_, tick)| tick);
+        match &self.stats {
+            Some(stats) => format!(
+                "Tick {}  {}  Potential {}  Observed {}  Operators {}  Entanglements {}",
+                tick, rates, stats.potential, stats.observed, stats.operators, stats.entanglements
+            ),
+            None => rates,
+        }
+    }
+}
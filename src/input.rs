@@ -0,0 +1,281 @@
+//! Translation of raw Piston events into typed [`AppAction`]s.
+//!
+//! This is the only place that knows about Piston's input types; the rest of
+//! the application reacts to actions alone. Which key does what is up to the
+//! [`Keymap`].
+
+use crate::app::{AppState, ToolMode};
+use crate::keymap::{Chord, Keymap};
+use crate::panel::Setting;
+use piston_window::{Button, GenericEvent, Key, MouseButton};
+
+/// The modifier keys held down, which select a key's alternate action.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Modifiers {
+    pub ctrl: bool,
+    /// Keeps an entanglement selection open so more members can join the group.
+    pub shift: bool,
+    /// Turns a left click with the Operator tool into the eyedropper.
+    pub alt: bool,
+}
+
+/// Everything a user (or a test) can ask the application to do.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AppAction {
+    /// Switch the active tool.
+    SelectTool(ToolMode),
+    /// Switch the Operator tool to the next state in the operator palette.
+    CycleOperatorPreset,
+    /// Make the state of the cell under the cursor the one the Operator tool
+    /// places, until the palette is cycled.
+    SampleOperator,
+    /// Snap the Operator tool to the next coarser lattice, or back to every cell.
+    CycleOperatorSnap,
+    /// Switch the Operator tool to the next shape it draws.
+    CycleOperatorShape,
+    /// Abandon a half-finished entanglement selection.
+    CancelSelection,
+    /// Replace the universe with a fresh random one.
+    ResetUniverse,
+    /// Switch to the next boundary condition.
+    CycleBoundary,
+    /// Break every entanglement and wire new random pairs, keeping the grid.
+    RewireEntanglement,
+    /// Copy a share code for the current run, or the selected rectangle as a
+    /// pattern while there is one.
+    CopyShareCode,
+    /// Import the share code on the clipboard, or else paste a pattern like
+    /// [`AppAction::PastePattern`].
+    PasteShareCode,
+    /// Copy the selected rectangle as a pattern.
+    CopyPattern,
+    /// Copy the selected rectangle as a pattern and empty it.
+    CutPattern,
+    /// Stamp the copied pattern at the cursor.
+    PastePattern,
+    /// Open or close the pattern library browser.
+    ToggleLibrary,
+    /// Move the selected rectangle this many cells across and down the view,
+    /// with the entanglements among its cells.
+    NudgeSelection([i64; 2]),
+    /// Show or hide the help overlay of tools, keys and colors.
+    ToggleHelp,
+    /// Add the cell under the cursor to the watch list, or remove it.
+    ToggleWatch,
+    /// Save the universe and the workspace around it to the session file.
+    SaveSession,
+    /// Restore the universe and workspace from the session file.
+    LoadSession,
+    /// Switch soft real-time mode, which spreads ticks over frames, on or off.
+    ToggleSoftRealTime,
+    /// Step back to the previous recorded state, pausing the simulation.
+    Undo,
+    /// Step forward to the most recently undone state.
+    Redo,
+    /// Show the next universe of the multiverse.
+    NextUniverse,
+    /// Suspend or resume ticking.
+    TogglePause,
+    /// Double (`true`) or halve the tick rate.
+    ChangeSpeed(bool),
+    /// Start or stop zen mode, which drifts the rates and steers the camera.
+    ToggleZen,
+    /// Start recording the run, or stop and write the replay file.
+    ToggleRecording,
+    /// Start an animated capture of the run, or stop and write the GIF or APNG.
+    ToggleCapture,
+    /// Watch the replay file, or return to the live universe.
+    TogglePlayback,
+    /// Step the replay being watched forward (`true`) or back, pausing it.
+    /// Without a replay, stepping back runs a reversible universe backwards.
+    Scrub(bool),
+    /// The held modifier keys changed.
+    SetModifiers(Modifiers),
+    /// Show or hide the plugin panel.
+    TogglePluginPanel,
+    /// Switch the plugin at this position in the plugin list on or off.
+    TogglePlugin(usize),
+    /// Show or hide the control panel.
+    ToggleControlPanel,
+    /// Show or hide the frame rate, tick rate and population.
+    ToggleHud,
+    /// Show or hide the profiler overlay, and time ticks while it shows.
+    ToggleProfiler,
+    /// Show or hide the plot strip of recent population metrics.
+    TogglePlot,
+    /// Show or hide every entanglement link, not just the ones that fire.
+    ToggleEntanglementLinks,
+    /// Mute or unmute the sound of the simulation.
+    ToggleSound,
+    /// Color the cells by the next view mode.
+    CycleViewMode,
+    /// Set a control panel setting to a value.
+    SetSetting(Setting, f64),
+    /// The cursor moved to a pixel position.
+    MoveCursor([f64; 2]),
+    /// The primary (left) button went down at the cursor.
+    PressPrimary,
+    /// The primary (left) button was released.
+    ReleasePrimary,
+    /// The secondary (right) button went down at the cursor.
+    PressSecondary,
+    /// The secondary (right) button was released.
+    ReleaseSecondary,
+    /// The middle button went down, starting to drag the view.
+    PressMiddle,
+    /// The middle button was released.
+    ReleaseMiddle,
+    /// The scroll wheel turned this many notches; positive zooms in about the
+    /// cursor.
+    Zoom(f64),
+    /// Return the view to the whole grid fitted to the window.
+    ResetView,
+    /// The window was resized to this many points, with this many physical
+    /// pixels per point.
+    Resize([f64; 2], f64),
+    /// Grow (positive) or shrink the active tool's brush by this many steps.
+    ResizeBrush(f64),
+    /// Show the next (`true`) or previous layer along the depth axis.
+    StepSlice(bool),
+    /// Page along the next axis outside the viewing plane.
+    CycleDepthAxis,
+    /// View the grid along the next pair of axes.
+    CycleViewAxes,
+    /// Show a 3D grid whole through an orbit camera, or return to the slice.
+    ToggleVolume,
+    /// Advance the simulation one step.
+    Tick,
+    /// This many seconds of real time passed: run the ticks due under the
+    /// fixed timestep, or one step without it.
+    Advance(f64),
+}
+
+impl AppAction {
+    /// Whether the action changes the live universe, and so must wait while a
+    /// replay is being watched.
+    pub fn edits_universe(&self) -> bool {
+        matches!(
+            self,
+            AppAction::ResetUniverse
+                | AppAction::CycleBoundary
+                | AppAction::RewireEntanglement
+                | AppAction::PasteShareCode
+                | AppAction::CutPattern
+                | AppAction::PastePattern
+                | AppAction::NudgeSelection(_)
+                | AppAction::LoadSession
+                | AppAction::Undo
+                | AppAction::Redo
+                | AppAction::NextUniverse
+                | AppAction::PressPrimary
+                | AppAction::PressSecondary
+        )
+    }
+}
+
+/// The action `key` triggers with `modifiers` held under the default
+/// [`Keymap`].
+pub fn key_action(key: Key, modifiers: Modifiers) -> Option<AppAction> {
+    Keymap::default().action(key, modifiers)
+}
+
+/// Converts the input carried by an event into actions, in the order they happened.
+///
+/// `modifiers` are the modifier keys held before the event, and `keymap` maps
+/// key presses to actions. Update events are not included; see
+/// [`handle_event`].
+pub fn input_actions<E: GenericEvent>(
+    e: &E,
+    modifiers: Modifiers,
+    keymap: &Keymap,
+) -> Vec<AppAction> {
+    let mut actions = Vec::new();
+    e.mouse_cursor(|pos| actions.push(AppAction::MoveCursor(pos)));
+    e.resize(|args| {
+        let hidpi_factor = args.draw_size[0] as f64 / args.window_size[0].max(1.0);
+        actions.push(AppAction::Resize(args.window_size, hidpi_factor))
+    });
+    // With Ctrl held the wheel sizes the brush instead of zooming.
+    e.mouse_scroll(|[_, notches]| {
+        actions.push(if modifiers.ctrl {
+            AppAction::ResizeBrush(notches)
+        } else {
+            AppAction::Zoom(notches)
+        })
+    });
+
+    if let Some(button) = e.press_args() {
+        match button {
+            Button::Keyboard(Key::LCtrl | Key::RCtrl) => {
+                actions.push(AppAction::SetModifiers(Modifiers {
+                    ctrl: true,
+                    ..modifiers
+                }))
+            }
+            Button::Keyboard(Key::LShift | Key::RShift) => {
+                actions.push(AppAction::SetModifiers(Modifiers {
+                    shift: true,
+                    ..modifiers
+                }))
+            }
+            Button::Keyboard(Key::LAlt | Key::RAlt) => {
+                actions.push(AppAction::SetModifiers(Modifiers {
+                    alt: true,
+                    ..modifiers
+                }))
+            }
+            Button::Keyboard(key) => actions.extend(keymap.action(key, modifiers)),
+            Button::Mouse(MouseButton::Left) => actions.push(AppAction::PressPrimary),
+            Button::Mouse(MouseButton::Right) => actions.push(AppAction::PressSecondary),
+            Button::Mouse(MouseButton::Middle) => actions.push(AppAction::PressMiddle),
+            _ => {}
+        }
+    }
+    if let Some(button) = e.release_args() {
+        match button {
+            Button::Keyboard(Key::LCtrl | Key::RCtrl) => {
+                actions.push(AppAction::SetModifiers(Modifiers {
+                    ctrl: false,
+                    ..modifiers
+                }))
+            }
+            Button::Keyboard(Key::LShift | Key::RShift) => {
+                actions.push(AppAction::SetModifiers(Modifiers {
+                    shift: false,
+                    ..modifiers
+                }))
+            }
+            Button::Keyboard(Key::LAlt | Key::RAlt) => {
+                actions.push(AppAction::SetModifiers(Modifiers {
+                    alt: false,
+                    ..modifiers
+                }))
+            }
+            Button::Mouse(MouseButton::Left) => actions.push(AppAction::ReleasePrimary),
+            Button::Mouse(MouseButton::Right) => actions.push(AppAction::ReleaseSecondary),
+            Button::Mouse(MouseButton::Middle) => actions.push(AppAction::ReleaseMiddle),
+            _ => {}
+        }
+    }
+    actions
+}
+
+/// Processes one event from the event loop: input actions, the scripts' key
+/// hooks, tool effects and simulation ticks, in that order.
+///
+/// Rendering is left to the caller.
+pub fn handle_event<E: GenericEvent>(state: &mut AppState, e: &E) {
+    for action in input_actions(e, state.modifiers, &state.keymap) {
+        state.apply(action);
+    }
+    if let Some(Button::Keyboard(key)) = e.press_args() {
+        let Modifiers { ctrl, alt, .. } = state.modifiers;
+        state.run_key_scripts(&Chord { key, ctrl, alt }.to_string());
+    }
+
+    state.apply_tool_effects();
+
+    if let Some(args) = e.update_args() {
+        state.apply(AppAction::Advance(args.dt));
+    }
+}
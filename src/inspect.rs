@@ -0,0 +1,86 @@
+//! The cell inspector: everything about one Existon, spelled out.
+//!
+//! The grid's colors show at most four coefficients of each cell. An
+//! [`Inspection`] reads a cell in full instead, for the Inspect tool's
+//! tooltip: its coordinate and ID, consciousness, whole multivector in blade
+//! notation, age, and the entanglement group it belongs to, with the
+//! coordinates of its partners so the frontend can draw lines to them.
+
+use crate::existon::ConsciousnessState;
+use crate::ga_core::Multivector;
+use crate::universe::Universe;
+
+/// One cell of a universe at the moment it was inspected; see the
+/// [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inspection {
+    pub coord: Vec<usize>,
+    pub id: u64,
+    pub consciousness: ConsciousnessState,
+    pub state: Multivector,
+    /// Ticks since the cell came into being.
+    pub age: u64,
+    /// Ticks since the cell was observed, while it stays `Observed`.
+    pub observed_for: Option<u64>,
+    /// The strength of the cell's entanglement group, if it is entangled.
+    pub strength: Option<f64>,
+    /// The coordinates of the other members of its entanglement group.
+    pub partners: Vec<Vec<usize>>,
+}
+
+impl Inspection {
+    /// Inspects the cell at `coord`, if it is on the grid.
+    pub fn at(universe: &Universe, coord: &[usize]) -> Option<Self> {
+        let idx = universe.get_index_from_coord(coord)?;
+        let existon = &universe.grid[idx];
+        let entanglement = &universe.entanglement;
+        let now = universe.tick_count;
+        Some(Inspection {
+            coord: coord.to_vec(),
+            id: existon.id,
+            consciousness: existon.consciousness,
+            state: existon.state.clone(),
+            age: now.saturating_sub(existon.born),
+            observed_for: existon.observed_since.map(|t| now.saturating_sub(t)),
+            strength: entanglement
+                .group_of(existon.id)
+                .map(|group| entanglement.strength(group)),
+            partners: entanglement
+                .partners(existon.id)
+                .filter_map(|id| universe.coord_of_id(id))
+                .collect(),
+        })
+    }
+
+    /// Inspects the cell with ID `id`, wherever it has moved to.
+    pub fn of_id(universe: &Universe, id: u64) -> Option<Self> {
+        Self::at(universe, &universe.coord_of_id(id)?)
+    }
+
+    /// The inspection as the tooltip shows it, one line per fact.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![
+            format!("Cell {:?}  #{}", self.coord, self.id),
+            match self.observed_for {
+                Some(ticks) => format!("{:?} for {} ticks", self.consciousness, ticks),
+                None => format!("{:?}", self.consciousness),
+            },
+            format!("State {}", self.state),
+            format!("Age {} ticks", self.age),
+        ];
+        lines.push(match self.strength {
+            Some(strength) => format!(
+                "Entangled with {} at strength {:.2}: {}",
+                self.partners.len(),
+                strength,
+                self.partners
+                    .iter()
+                    .map(|c| format!("{:?}", c))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            None => "Not entangled".to_string(),
+        });
+        lines
+    }
+}
@@ -0,0 +1,713 @@
+//! Keybindings, loaded from `keybindings.toml`.
+//!
+//! Every key-driven [`AppAction`] has a name, such as `pause` or
+//! `tool_observe`, and one or more default chords: a key, with `Ctrl+` in front
+//! for the alternate bindings and `Alt+` for the plugins. A keybindings file
+//! rebinds actions by name, one per line, to a chord or a list of them:
+//!
+//! ```toml
+//! [keys]
+//! tool_observe = "Q"
+//! pause = ["Space", "Return"]
+//! toggle_zen = []   # unbound
+//! ```
+//!
+//! Actions the file leaves out keep their defaults, and a chord bound in the
+//! file is taken from whatever action had it by default. Keys are named as on
+//! the keyboard: letters, digits, `F1` to `F12`, `Space`, `Tab`, the arrows as
+//! `Left`, `Right`, `Up` and `Down`, `PageUp`, `PageDown` and punctuation such
+//! as `[` and `=`. [`Keymap::to_toml`] writes the defaults in this format.
+
+use crate::app::ToolMode;
+use crate::input::{AppAction, Modifiers};
+use crate::settings::{TomlEntry, TomlValue, toml_entries};
+use piston_window::Key;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Every bindable action: its name in the keybindings file, what it does in
+/// a few words for the help overlay, the action, and its default chords.
+const ACTIONS: &[(&str, &str, AppAction, &[&str])] = &[
+    (
+        "tool_observe",
+        "Observe tool",
+        AppAction::SelectTool(ToolMode::Observe),
+        &["1"],
+    ),
+    (
+        "tool_entangle",
+        "Entangle tool",
+        AppAction::SelectTool(ToolMode::Entangle),
+        &["2"],
+    ),
+    (
+        "tool_operator",
+        "Operator tool",
+        AppAction::SelectTool(ToolMode::Operator),
+        &["3"],
+    ),
+    (
+        "tool_disrupt",
+        "Disrupt tool",
+        AppAction::SelectTool(ToolMode::Disrupt),
+        &["4"],
+    ),
+    (
+        "tool_select",
+        "Select tool",
+        AppAction::SelectTool(ToolMode::Select),
+        &["6"],
+    ),
+    (
+        "tool_inspect",
+        "Inspect tool",
+        AppAction::SelectTool(ToolMode::Inspect),
+        &["5"],
+    ),
+    (
+        "tool_paint",
+        "Paint tool",
+        AppAction::SelectTool(ToolMode::Paint),
+        &["7"],
+    ),
+    (
+        "cycle_operator_preset",
+        "Next operator state or painted rate",
+        AppAction::CycleOperatorPreset,
+        &["O"],
+    ),
+    (
+        "sample_operator",
+        "Pick up the operator state under the cursor",
+        AppAction::SampleOperator,
+        &[],
+    ),
+    (
+        "cycle_operator_snap",
+        "Snap operators to every 2nd, 4th, 8th or 16th cell",
+        AppAction::CycleOperatorSnap,
+        &["Ctrl+G"],
+    ),
+    (
+        "cycle_operator_shape",
+        "Next operator shape: freehand, line, rectangle, circle",
+        AppAction::CycleOperatorShape,
+        &["F"],
+    ),
+    (
+        "cancel_selection",
+        "Drop a half-finished entanglement",
+        AppAction::CancelSelection,
+        &["Backspace"],
+    ),
+    (
+        "reset_view",
+        "Fit the grid to the window",
+        AppAction::ResetView,
+        &["0"],
+    ),
+    (
+        "shrink_brush",
+        "Shrink the brush",
+        AppAction::ResizeBrush(-1.0),
+        &["["],
+    ),
+    (
+        "grow_brush",
+        "Grow the brush",
+        AppAction::ResizeBrush(1.0),
+        &["]"],
+    ),
+    (
+        "pause",
+        "Pause or resume",
+        AppAction::TogglePause,
+        &["Space"],
+    ),
+    (
+        "slower",
+        "Halve the tick rate",
+        AppAction::ChangeSpeed(false),
+        &["-"],
+    ),
+    (
+        "faster",
+        "Double the tick rate",
+        AppAction::ChangeSpeed(true),
+        &["="],
+    ),
+    (
+        "toggle_soft_real_time",
+        "Soft real-time",
+        AppAction::ToggleSoftRealTime,
+        &["T"],
+    ),
+    (
+        "reset_universe",
+        "New random universe",
+        AppAction::ResetUniverse,
+        &["R"],
+    ),
+    (
+        "cycle_boundary",
+        "Next boundary condition",
+        AppAction::CycleBoundary,
+        &["B"],
+    ),
+    (
+        "rewire_entanglement",
+        "Rewire the entanglement",
+        AppAction::RewireEntanglement,
+        &["Ctrl+E"],
+    ),
+    (
+        "save_session",
+        "Save the session",
+        AppAction::SaveSession,
+        &["S"],
+    ),
+    (
+        "load_session",
+        "Load the session",
+        AppAction::LoadSession,
+        &["L"],
+    ),
+    (
+        "copy_share_code",
+        "Copy the selection, or else a share code",
+        AppAction::CopyShareCode,
+        &["Ctrl+C"],
+    ),
+    (
+        "paste_share_code",
+        "Paste a share code, or else a pattern",
+        AppAction::PasteShareCode,
+        &["Ctrl+V"],
+    ),
+    (
+        "copy_pattern",
+        "Copy the selection",
+        AppAction::CopyPattern,
+        &[],
+    ),
+    (
+        "cut_pattern",
+        "Cut the selection",
+        AppAction::CutPattern,
+        &["Ctrl+X"],
+    ),
+    (
+        "paste_pattern",
+        "Paste a pattern at the cursor",
+        AppAction::PastePattern,
+        &[],
+    ),
+    (
+        "toggle_library",
+        "Pattern library",
+        AppAction::ToggleLibrary,
+        &["P"],
+    ),
+    (
+        "nudge_up",
+        "Move the selection up",
+        AppAction::NudgeSelection([0, -1]),
+        &["Up"],
+    ),
+    (
+        "nudge_down",
+        "Move the selection down",
+        AppAction::NudgeSelection([0, 1]),
+        &["Down"],
+    ),
+    (
+        "scrub_back",
+        "Step the replay back",
+        AppAction::Scrub(false),
+        &["Left"],
+    ),
+    (
+        "scrub_forward",
+        "Step the replay forward",
+        AppAction::Scrub(true),
+        &["Right"],
+    ),
+    ("undo", "Undo", AppAction::Undo, &["Ctrl+Z"]),
+    ("redo", "Redo", AppAction::Redo, &["Ctrl+Y"]),
+    (
+        "toggle_recording",
+        "Record a replay",
+        AppAction::ToggleRecording,
+        &["Ctrl+R"],
+    ),
+    (
+        "toggle_playback",
+        "Watch the replay",
+        AppAction::TogglePlayback,
+        &["Ctrl+O"],
+    ),
+    (
+        "toggle_capture",
+        "Capture an animation",
+        AppAction::ToggleCapture,
+        &["F9"],
+    ),
+    (
+        "next_universe",
+        "Next universe",
+        AppAction::NextUniverse,
+        &["Tab"],
+    ),
+    (
+        "slice_forward",
+        "Next layer",
+        AppAction::StepSlice(true),
+        &["PageUp"],
+    ),
+    (
+        "slice_back",
+        "Previous layer",
+        AppAction::StepSlice(false),
+        &["PageDown"],
+    ),
+    (
+        "cycle_depth_axis",
+        "Next depth axis",
+        AppAction::CycleDepthAxis,
+        &["Ctrl+PageUp", "Ctrl+PageDown"],
+    ),
+    (
+        "cycle_view_axes",
+        "Turn the view",
+        AppAction::CycleViewAxes,
+        &["X"],
+    ),
+    (
+        "toggle_volume",
+        "Volume view",
+        AppAction::ToggleVolume,
+        &["D"],
+    ),
+    (
+        "cycle_view_mode",
+        "Next view mode",
+        AppAction::CycleViewMode,
+        &["V"],
+    ),
+    ("toggle_help", "This help", AppAction::ToggleHelp, &["H"]),
+    (
+        "toggle_watch",
+        "Watch the cell under the cursor",
+        AppAction::ToggleWatch,
+        &["W"],
+    ),
+    (
+        "toggle_hud",
+        "Heads-up display",
+        AppAction::ToggleHud,
+        &["I"],
+    ),
+    (
+        "toggle_profiler",
+        "Profiler",
+        AppAction::ToggleProfiler,
+        &["F3"],
+    ),
+    ("toggle_plot", "Plot strip", AppAction::TogglePlot, &["G"]),
+    (
+        "toggle_entanglement_links",
+        "Entanglement links",
+        AppAction::ToggleEntanglementLinks,
+        &["E"],
+    ),
+    ("toggle_sound", "Sound", AppAction::ToggleSound, &["A"]),
+    (
+        "toggle_control_panel",
+        "Control panel",
+        AppAction::ToggleControlPanel,
+        &["K"],
+    ),
+    ("toggle_zen", "Zen mode", AppAction::ToggleZen, &["Z"]),
+    (
+        "toggle_plugin_panel",
+        "Plugin panel",
+        AppAction::TogglePluginPanel,
+        &["Alt+0"],
+    ),
+    (
+        "plugin_1",
+        "Plugin 1",
+        AppAction::TogglePlugin(0),
+        &["Alt+1"],
+    ),
+    (
+        "plugin_2",
+        "Plugin 2",
+        AppAction::TogglePlugin(1),
+        &["Alt+2"],
+    ),
+    (
+        "plugin_3",
+        "Plugin 3",
+        AppAction::TogglePlugin(2),
+        &["Alt+3"],
+    ),
+    (
+        "plugin_4",
+        "Plugin 4",
+        AppAction::TogglePlugin(3),
+        &["Alt+4"],
+    ),
+    (
+        "plugin_5",
+        "Plugin 5",
+        AppAction::TogglePlugin(4),
+        &["Alt+5"],
+    ),
+    (
+        "plugin_6",
+        "Plugin 6",
+        AppAction::TogglePlugin(5),
+        &["Alt+6"],
+    ),
+    (
+        "plugin_7",
+        "Plugin 7",
+        AppAction::TogglePlugin(6),
+        &["Alt+7"],
+    ),
+    (
+        "plugin_8",
+        "Plugin 8",
+        AppAction::TogglePlugin(7),
+        &["Alt+8"],
+    ),
+    (
+        "plugin_9",
+        "Plugin 9",
+        AppAction::TogglePlugin(8),
+        &["Alt+9"],
+    ),
+];
+
+/// The keys a chord can name, besides letters, digits and function keys.
+const KEY_NAMES: &[(&str, Key)] = &[
+    ("Space", Key::Space),
+    ("Tab", Key::Tab),
+    ("Return", Key::Return),
+    ("Backspace", Key::Backspace),
+    ("Delete", Key::Delete),
+    ("Insert", Key::Insert),
+    ("Home", Key::Home),
+    ("End", Key::End),
+    ("PageUp", Key::PageUp),
+    ("PageDown", Key::PageDown),
+    ("Left", Key::Left),
+    ("Right", Key::Right),
+    ("Up", Key::Up),
+    ("Down", Key::Down),
+    ("[", Key::LeftBracket),
+    ("]", Key::RightBracket),
+    ("-", Key::Minus),
+    ("=", Key::Equals),
+    (",", Key::Comma),
+    (".", Key::Period),
+    ("/", Key::Slash),
+    ("\\", Key::Backslash),
+    (";", Key::Semicolon),
+    ("'", Key::Quote),
+    ("`", Key::Backquote),
+];
+
+const LETTERS: [Key; 26] = [
+    Key::A,
+    Key::B,
+    Key::C,
+    Key::D,
+    Key::E,
+    Key::F,
+    Key::G,
+    Key::H,
+    Key::I,
+    Key::J,
+    Key::K,
+    Key::L,
+    Key::M,
+    Key::N,
+    Key::O,
+    Key::P,
+    Key::Q,
+    Key::R,
+    Key::S,
+    Key::T,
+    Key::U,
+    Key::V,
+    Key::W,
+    Key::X,
+    Key::Y,
+    Key::Z,
+];
+
+const DIGITS: [Key; 10] = [
+    Key::D0,
+    Key::D1,
+    Key::D2,
+    Key::D3,
+    Key::D4,
+    Key::D5,
+    Key::D6,
+    Key::D7,
+    Key::D8,
+    Key::D9,
+];
+
+const FUNCTION_KEYS: [Key; 12] = [
+    Key::F1,
+    Key::F2,
+    Key::F3,
+    Key::F4,
+    Key::F5,
+    Key::F6,
+    Key::F7,
+    Key::F8,
+    Key::F9,
+    Key::F10,
+    Key::F11,
+    Key::F12,
+];
+
+/// A key, and whether Ctrl and Alt are held with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Chord {
+    pub key: Key,
+    pub ctrl: bool,
+    pub alt: bool,
+}
+
+impl Chord {
+    /// Reads a chord such as `"Ctrl+Z"`, `"Alt+1"`, `"f5"` or `"["`; names
+    /// are not case-sensitive.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (mut ctrl, mut alt) = (false, false);
+        let mut name = text.trim();
+        while let Some((modifier, rest)) = name.split_once('+') {
+            match modifier.trim().to_ascii_lowercase().as_str() {
+                "ctrl" => ctrl = true,
+                "alt" => alt = true,
+                _ => break,
+            }
+            name = rest.trim();
+        }
+        let mut chars = name.chars();
+        let key = match (chars.next(), chars.next()) {
+            (Some(c), None) if c.is_ascii_alphabetic() => {
+                LETTERS[(c.to_ascii_uppercase() as u8 - b'A') as usize]
+            }
+            (Some(c), None) if c.is_ascii_digit() => DIGITS[(c as u8 - b'0') as usize],
+            _ => match name
+                .strip_prefix(['F', 'f'])
+                .and_then(|n| n.parse::<usize>().ok())
+            {
+                Some(n @ 1..=12) => FUNCTION_KEYS[n - 1],
+                _ => {
+                    KEY_NAMES
+                        .iter()
+                        .find(|(known, _)| known.eq_ignore_ascii_case(name))?
+                        .1
+                }
+            },
+        };
+        Some(Chord { key, ctrl, alt })
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ctrl {
+            write!(f, "Ctrl+")?;
+        }
+        if self.alt {
+            write!(f, "Alt+")?;
+        }
+        if let Some(i) = LETTERS.iter().position(|&key| key == self.key) {
+            write!(f, "{}", (b'A' + i as u8) as char)
+        } else if let Some(i) = DIGITS.iter().position(|&key| key == self.key) {
+            write!(f, "{}", i)
+        } else if let Some(i) = FUNCTION_KEYS.iter().position(|&key| key == self.key) {
+            write!(f, "F{}", i + 1)
+        } else {
+            match KEY_NAMES.iter().find(|(_, key)| *key == self.key) {
+                Some((name, _)) => write!(f, "{}", name),
+                None => write!(f, "{:?}", self.key),
+            }
+        }
+    }
+}
+
+/// The reasons a keybindings file can fail to load.
+#[derive(Debug)]
+pub enum KeymapError {
+    /// The file exists but could not be read.
+    Io(io::Error),
+    /// The line is not `name = "chord"` or `name = ["chord", ...]`.
+    Syntax { line: usize },
+    /// The line names an action that does not exist.
+    UnknownAction { line: usize, name: String },
+    /// The line binds a key that cannot be named.
+    UnknownKey { line: usize, key: String },
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapError::Io(e) => write!(f, "could not read keybindings: {}", e),
+            KeymapError::Syntax { line } => {
+                write!(
+                    f,
+                    "line {}: expected name = \"key\" or name = [\"key\", ...]",
+                    line
+                )
+            }
+            KeymapError::UnknownAction { line, name } => {
+                write!(f, "line {}: no action is called {:?}", line, name)
+            }
+            KeymapError::UnknownKey { line, key } => {
+                write!(f, "line {}: no key is called {:?}", line, key)
+            }
+        }
+    }
+}
+
+impl std::error::Error for KeymapError {}
+
+/// Which chord triggers which action; see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Keymap {
+    bindings: Vec<(Chord, AppAction)>,
+}
+
+impl Default for Keymap {
+    /// The built-in bindings.
+    fn default() -> Self {
+        let bindings = ACTIONS
+            .iter()
+            .flat_map(|&(_, _, action, chords)| {
+                chords
+                    .iter()
+                    .map(move |chord| (Chord::parse(chord).expect("default chord"), action))
+            })
+            .collect();
+        Keymap { bindings }
+    }
+}
+
+impl Keymap {
+    /// The action `key` triggers with `modifiers` held. Shift plays no part
+    /// in the chords; it only changes what clicks do.
+    pub fn action(&self, key: Key, modifiers: Modifiers) -> Option<AppAction> {
+        let chord = Chord {
+            key,
+            ctrl: modifiers.ctrl,
+            alt: modifiers.alt,
+        };
+        self.bindings
+            .iter()
+            .find(|(bound, _)| *bound == chord)
+            .map(|&(_, action)| action)
+    }
+
+    /// The chords that trigger `action`, in the order they were bound.
+    pub fn chords(&self, action: AppAction) -> Vec<Chord> {
+        self.bindings
+            .iter()
+            .filter(|(_, bound)| *bound == action)
+            .map(|&(chord, _)| chord)
+            .collect()
+    }
+
+    /// The chords of `action` as the help overlay shows them, such as
+    /// `"Ctrl+PageUp / Ctrl+PageDown"`, or `None` if it is unbound.
+    pub fn keys(&self, action: AppAction) -> Option<String> {
+        let chords: Vec<String> = self.chords(action).iter().map(Chord::to_string).collect();
+        (!chords.is_empty()).then(|| chords.join(" / "))
+    }
+
+    /// Every bound action, in the order of the binding table, with its
+    /// [`Keymap::keys`] and what it does in a few words.
+    pub fn help(&self) -> Vec<(AppAction, String, &'static str)> {
+        ACTIONS
+            .iter()
+            .filter_map(|&(_, label, action, _)| Some((action, self.keys(action)?, label)))
+            .collect()
+    }
+
+    /// Replaces the chords of `action` with `chords`, taking each of them from
+    /// any other action it was bound to.
+    pub fn bind(&mut self, action: AppAction, chords: &[Chord]) {
+        self.bindings
+            .retain(|(chord, bound)| *bound != action && !chords.contains(chord));
+        self.bindings
+            .extend(chords.iter().map(|&chord| (chord, action)));
+    }
+
+    /// The defaults with the bindings in `text` applied. Section headers such
+    /// as `[keys]` are skipped.
+    pub fn parse(text: &str) -> Result<Self, KeymapError> {
+        let entries = toml_entries(text).map_err(|line| KeymapError::Syntax { line })?;
+        let mut keymap = Keymap::default();
+        for TomlEntry {
+            line, key, value, ..
+        } in entries
+        {
+            let Some(&(_, _, action, _)) = ACTIONS.iter().find(|(name, ..)| *name == key) else {
+                return Err(KeymapError::UnknownAction { line, name: key });
+            };
+            let keys = match value {
+                TomlValue::String(key) => vec![TomlValue::String(key)],
+                TomlValue::Array(keys) => keys,
+                _ => return Err(KeymapError::Syntax { line }),
+            };
+            let chords = keys
+                .into_iter()
+                .map(|key| match key {
+                    TomlValue::String(key) => {
+                        Chord::parse(&key).ok_or(KeymapError::UnknownKey { line, key })
+                    }
+                    _ => Err(KeymapError::Syntax { line }),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            keymap.bind(action, &chords);
+        }
+        Ok(keymap)
+    }
+
+    /// Reads the keybindings file at `path`, or the defaults if there is none.
+    pub fn load(path: &Path) -> Result<Self, KeymapError> {
+        match fs::read_to_string(path) {
+            Ok(text) => Keymap::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Keymap::default()),
+            Err(e) => Err(KeymapError::Io(e)),
+        }
+    }
+
+    /// The keymap as a keybindings file binding every action, which
+    /// [`Keymap::parse`] reads back unchanged.
+    pub fn to_toml(&self) -> String {
+        let mut text = String::from("[keys]\n");
+        for &(name, _, action, _) in ACTIONS {
+            let chords: Vec<String> = self
+                .chords(action)
+                .iter()
+                .map(|chord| format!("{:?}", chord.to_string()))
+                .collect();
+            let value = match chords.as_slice() {
+                [chord] => chord.clone(),
+                _ => format!("[{}]", chords.join(", ")),
+            };
+            text.push_str(&format!("{} = {}\n", name, value));
+        }
+        text
+    }
+}
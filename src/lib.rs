@@ -0,0 +1,88 @@
+//! # Existons
+//!
+//! The simulation core of the Existon Automaton: the Geometric Algebra, the
+//! Existon itself, the N-dimensional `Universe`, and the window-independent
+//! application state that the GUI drives.
+//!
+//! The application state and the frontends need the default `desktop`
+//! feature. Without it the crate is the bare engine, which builds for
+//! `wasm32-unknown-unknown` and runs in a browser through the `web` module.
+
+#[cfg(feature = "desktop")]
+pub mod api;
+#[cfg(feature = "desktop")]
+pub mod app;
+pub mod array;
+#[cfg(feature = "audio")]
+pub mod audio;
+#[cfg(feature = "desktop")]
+pub mod autosave;
+pub mod capture;
+pub mod census;
+pub mod color;
+pub mod delta;
+pub mod entanglement;
+pub mod existon;
+pub mod experiment;
+pub mod field;
+#[cfg(feature = "desktop")]
+pub mod frontend;
+pub mod ga_core;
+#[cfg(feature = "gpu")]
+pub mod gpu;
+pub mod graph;
+#[cfg(feature = "desktop")]
+pub mod help;
+pub mod history;
+pub mod hud;
+#[cfg(feature = "desktop")]
+pub mod input;
+pub mod inspect;
+#[cfg(feature = "desktop")]
+pub mod keymap;
+pub mod library;
+#[cfg(feature = "desktop")]
+pub mod minimap;
+pub mod multiverse;
+#[cfg(feature = "desktop")]
+pub mod panel;
+pub mod pattern;
+pub mod plot;
+pub mod plugin;
+pub mod profile;
+pub mod render;
+pub mod replay;
+pub mod rule;
+pub mod schedule;
+#[cfg(feature = "scripting")]
+pub mod script;
+pub mod seed_image;
+pub mod series;
+#[cfg(feature = "desktop")]
+pub mod serve;
+#[cfg(feature = "desktop")]
+pub mod session;
+#[cfg(feature = "desktop")]
+pub mod settings;
+pub mod shape;
+pub mod share;
+pub mod slice;
+pub mod sonify;
+pub mod sparse;
+pub mod stats;
+#[cfg(feature = "desktop")]
+pub mod sweep;
+pub mod timestep;
+#[cfg(feature = "desktop")]
+pub mod toolbar;
+#[cfg(feature = "desktop")]
+pub mod tui;
+pub mod tuning;
+pub mod universe;
+pub mod volume;
+pub mod watch;
+pub mod wave;
+pub mod web;
+pub mod worker;
+#[cfg(feature = "desktop")]
+pub mod zen;
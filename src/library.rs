@@ -0,0 +1,126 @@
+//! The pattern library: named patterns to browse and stamp.
+//!
+//! A [`PatternLibrary`] starts with the starter patterns bundled from
+//! `assets/patterns` (operator lattices, entangled rings, seeded clusters) and
+//! takes in any folder of pattern files saved with [`Pattern::save`]. While
+//! the browser is open the frontend lists the entries, previews the chosen one,
+//! and a click stamps it with its corner at the cursor.
+
+use crate::pattern::Pattern;
+use std::fs;
+use std::path::Path;
+
+/// The starter patterns compiled into the binary, by name.
+const BUNDLED: [(&str, &str); 4] = [
+    (
+        "entangled-ladder",
+        include_str!("../assets/patterns/entangled-ladder.txt"),
+    ),
+    (
+        "entangled-ring",
+        include_str!("../assets/patterns/entangled-ring.txt"),
+    ),
+    (
+        "operator-lattice",
+        include_str!("../assets/patterns/operator-lattice.txt"),
+    ),
+    (
+        "seeded-cluster",
+        include_str!("../assets/patterns/seeded-cluster.txt"),
+    ),
+];
+
+/// A pattern in the library.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LibraryEntry {
+    /// The pattern file's name without its extension.
+    pub name: String,
+    pub pattern: Pattern,
+}
+
+/// The patterns the browser lists; see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct PatternLibrary {
+    entries: Vec<LibraryEntry>,
+    /// The position of the chosen entry.
+    selected: usize,
+    /// Whether the browser is showing.
+    pub open: bool,
+}
+
+impl PatternLibrary {
+    /// A closed library holding the starter patterns.
+    pub fn bundled() -> Self {
+        let entries = BUNDLED
+            .iter()
+            .map(|&(name, code)| LibraryEntry {
+                name: name.to_string(),
+                pattern: Pattern::decode(code).expect("bundled pattern is valid"),
+            })
+            .collect();
+        PatternLibrary {
+            entries,
+            selected: 0,
+            open: false,
+        }
+    }
+
+    /// Adds every `.txt` pattern file in `dir`, in name order, and returns how
+    /// many were added. Files that are not patterns are skipped.
+    pub fn load_folder(&mut self, dir: &Path) -> std::io::Result<usize> {
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+            .collect();
+        paths.sort();
+        let before = self.entries.len();
+        for path in paths {
+            let Ok(pattern) = Pattern::load(&path) else {
+                continue;
+            };
+            let name = path
+                .file_stem()
+                .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+            self.entries.push(LibraryEntry { name, pattern });
+        }
+        Ok(self.entries.len() - before)
+    }
+
+    pub fn entries(&self) -> &[LibraryEntry] {
+        &self.entries
+    }
+
+    /// The chosen entry, unless the library is empty.
+    pub fn selected(&self) -> Option<&LibraryEntry> {
+        self.entries.get(self.selected)
+    }
+
+    /// Chooses the next (`true`) or previous entry, wrapping around.
+    pub fn step(&mut self, forward: bool) {
+        let len = self.entries.len();
+        if len == 0 {
+            return;
+        }
+        self.selected = if forward {
+            (self.selected + 1) % len
+        } else {
+            (self.selected + len - 1) % len
+        };
+    }
+
+    /// The browser's lines: a title, then one line per entry with the chosen
+    /// one marked.
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = vec!["Patterns (Up/Down choose, click stamps):".to_string()];
+        for (i, entry) in self.entries.iter().enumerate() {
+            let size: Vec<String> = entry.pattern.size.iter().map(usize::to_string).collect();
+            lines.push(format!(
+                "  {} {} ({})",
+                if i == self.selected { ">" } else { " " },
+                entry.name,
+                size.join("x")
+            ));
+        }
+        lines
+    }
+}
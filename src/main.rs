@@ -5,333 +5,628 @@
 //! 1. Setting up the application window and configuration.
 //! 2. Initializing the N-dimensional `Universe` with a `p`-dimensional GA space.
 //! 3. Running the main event loop to handle user input, simulation ticks, and rendering a 2D slice of the universe.
+//!
+//! Input handling and application state live in the library's `app` module.
+//! The window is a [`Frontend`], picked by name with `--frontend` like the
+//! library's terminal and headless ones; `--tui` is short for `--frontend tui`,
+//! `--serve ADDRESS` streams the run to WebSocket clients instead, and
+//! `--serve-http ADDRESS` hands it to an HTTP API.
 
-mod existon;
-mod ga_core;
-mod universe;
-
-use crate::{existon::ConsciousnessState, universe::Universe};
+use arboard::Clipboard;
+use existons::{
+    api::ApiServer,
+    app::{AppState, Config, Falloff, ToolMode, get_coord_from_pos, get_pos_from_coord},
+    audio::AudioOutput,
+    existon::ConsciousnessState,
+    frontend::{Frontend, FrontendRegistry, Headless},
+    ga_core::verify,
+    help::{self, HelpLine},
+    input::{self, AppAction},
+    inspect::Inspection,
+    keymap::Keymap,
+    minimap::Minimap,
+    multiverse::Multiverse,
+    panel::{Control, ControlPanel},
+    plot::Metric,
+    plugin::{Canvas, PluginCtx},
+    profile::{CountingAllocator, FrameTimings},
+    render::GridImage,
+    replay::Replay,
+    rule::RuleRegistry,
+    schedule::Schedule,
+    seed_image::ImageMapping,
+    series::StatsLog,
+    serve::Server,
+    settings::{SettingsFile, SettingsWatcher},
+    shape::Shape,
+    share,
+    sweep::Sweep,
+    timestep::FixedTimestep,
+    toolbar::Toolbar,
+    tuning::PreferencesCache,
+    universe::{BoundaryCondition, Topology, Universe},
+    volume::{self, OrbitCamera},
+    worker::SimWorker,
+};
 use find_folder::Search;
 use piston_window::{
-    Button, Ellipse, Glyphs, Key, Line, MouseButton, MouseCursorEvent, PistonWindow, PressEvent,
-    ReleaseEvent, RenderEvent, TextureSettings, Transformed, UpdateEvent, WindowSettings, clear,
-    rectangle, text,
+    DrawState, Ellipse, Filter, G2d, G2dTexture, G2dTextureContext, Glyphs, Image, ImageSize, Line,
+    PistonWindow, Rectangle, RenderEvent, Texture, TextureSettings, Transformed, Window,
+    WindowSettings, character::CharacterCache, clear, math::Matrix2d, polygon, rectangle, text,
 };
-use rand::{Rng, rng};
-
-//================================================================================
-// New UI Components
-//================================================================================
-
-/// Defines the interactive tools the user can switch between.
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum ToolMode {
-    Observe,  // 🔎
-    Entangle, // 🔗
-    Operator, // 🏗️
-    Disrupt,  // 🌊
-}
+use std::io;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
-//================================================================================
-// Application Configuration
-//================================================================================
-struct Config {
-    grid_dims: Vec<usize>,
-    ga_dims: usize,
-    cell_size: f64,
-    observation_radius: f64,
-    window_size: [f64; 2],
-    background_color: [f32; 4],
-    toolbar_color: [f32; 4],
-    text_color: [f32; 4],
-    font_size: u32,
-}
+/// Counts allocations for the profiler overlay.
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator::new();
 
-impl Config {
-    fn new() -> Self {
-        let grid_dims = vec![120, 80];
-        let ga_dims = 3;
-        const CELL_SIZE: f64 = 8.0;
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    // `existons sweep FILE` runs a parameter sweep instead of the app.
+    if args.first().is_some_and(|arg| arg == "sweep") {
+        let Some(path) = args.get(1) else {
+            eprintln!("Usage: existons sweep FILE");
+            std::process::exit(2);
+        };
+        if !run_sweep(Path::new(path)) {
+            std::process::exit(1);
+        }
+        return;
+    }
+    // `existons verify-ga [--dims 1..=6]` checks the laws of the geometric product.
+    if args.first().is_some_and(|arg| arg == "verify-ga") {
+        if !verify_ga(&args[1..]) {
+            std::process::exit(1);
+        }
+        return;
+    }
+    // `existons verify-replay FILE` checks a recording's state hashes.
+    if args.first().is_some_and(|arg| arg == "verify-replay") {
+        let Some(path) = args.get(1) else {
+            eprintln!("Usage: existons verify-replay FILE");
+            std::process::exit(2);
+        };
+        if !verify_replay(Path::new(path)) {
+            std::process::exit(1);
+        }
+        return;
+    }
 
-        let window_width = grid_dims.first().copied().unwrap_or(100) as f64 * CELL_SIZE;
-        let window_height = grid_dims.get(1).copied().unwrap_or(100) as f64 * CELL_SIZE;
+    // --- Main Application State ---
+    // `existons.toml` overrides the compiled-in settings and is watched for
+    // changes while the window is open.
+    let mut config = Config::new();
+    let settings = SettingsFile::load(&config.settings_path).unwrap_or_else(|e| {
+        eprintln!("Ignoring {}: {}", config.settings_path.display(), e);
+        SettingsFile::default()
+    });
+    settings.apply(&mut config);
+    let watcher = SettingsWatcher::new(config.settings_path.clone(), settings);
+    let mut app = AppState::new(config);
+    // The clipboard must outlive a copy on some platforms, so it is kept for the whole run.
+    app.clipboard = Clipboard::new()
+        .map_err(|e| eprintln!("System clipboard unavailable: {}", e))
+        .ok();
 
-        Self {
-            grid_dims,
-            ga_dims,
-            cell_size: CELL_SIZE,
-            observation_radius: 50.0,
-            window_size: [window_width, window_height],
-            background_color: [0.0, 0.0, 0.0, 1.0],
-            toolbar_color: [0.1, 0.1, 0.12, 1.0],
-            text_color: [1.0, 1.0, 1.0, 0.9],
-            font_size: 14,
-        }
+    match Keymap::load(&app.config.keybindings_path) {
+        Ok(keymap) => app.keymap = keymap,
+        Err(e) => eprintln!(
+            "Ignoring {}, using the default keys: {}",
+            app.config.keybindings_path.display(),
+            e
+        ),
     }
-}
 
-fn main() {
-    let config = Config::new();
-    let mut universe = Universe::new(config.grid_dims.clone(), config.ga_dims);
-
-    // --- Window and Asset Setup ---
-    let mut window: PistonWindow = WindowSettings::new(
-        "Existon Automaton: An Interactive Model of Source Science",
-        config.window_size,
-    )
-    .exit_on_esc(true)
-    .build()
-    .unwrap_or_else(|e| panic!("Failed to build PistonWindow: {}", e));
-
-    let assets = Search::ParentsThenKids(3, 3).for_folder("assets").unwrap();
-    let font_path = assets.join("NotoSans-Regular.ttf");
-    let mut glyphs = Glyphs::new(
-        &font_path,
-        window.create_texture_context(),
-        TextureSettings::new(),
-    )
-    .expect("Could not load font");
+    if let Ok(count) = app.library.load_folder(&app.config.pattern_dir)
+        && count > 0
+    {
+        println!(
+            "Added {} patterns from {}",
+            count,
+            app.config.pattern_dir.display()
+        );
+    }
+    if let Ok(count) = app.scripts.load_folder(&app.config.script_dir)
+        && count > 0
+    {
+        println!(
+            "Running {} scripts from {}",
+            count,
+            app.config.script_dir.display()
+        );
+    }
 
-    // --- Main Application State ---
-    let mut mouse_pos = [0.0, 0.0];
-    let mut current_tool = ToolMode::Observe;
-    let mut entangle_first_partner: Option<u64> = None;
-    let mut entanglement_flashes: Vec<(Vec<usize>, Vec<usize>, u8)> = Vec::new();
-
-    // New: Track if mouse buttons are held down for painting
-    let mut is_left_mouse_down = false;
-    let mut is_right_mouse_down = false;
-
-    while let Some(e) = window.next() {
-        e.mouse_cursor(|pos| mouse_pos = pos);
-
-        // Modified: Handle press and release events separately
-        if let Some(button) = e.press_args() {
-            handle_press(
-                button,
-                &mut universe,
-                &config,
-                &mut current_tool,
-                &mut entangle_first_partner,
-                &mut entanglement_flashes,
-                &mut is_left_mouse_down,
-                &mut is_right_mouse_down,
-                mouse_pos,
+    #[cfg(feature = "gpu")]
+    {
+        app.gpu = existons::gpu::GpuTicker::new()
+            .map_err(|e| eprintln!("GPU unavailable, ticking on the CPU: {}", e))
+            .ok();
+    }
+
+    // The value after a `--name value` flag.
+    let flag = |name: &str| {
+        let at = args.iter().position(|arg| arg == name)?;
+        args.get(at + 1)
+    };
+
+    // `--rule NAME` runs every universe under a registered update rule, the
+    // scripts' rules among them.
+    let mut rules = RuleRegistry::with_builtins();
+    app.scripts.register_rules(&mut rules);
+    if let Some(name) = flag("--rule") {
+        let Some(rule) = rules.create(name) else {
+            eprintln!(
+                "Unknown rule {}; the rules are {}",
+                name,
+                rules.names().join(", ")
             );
+            return;
+        };
+        app.universe.set_shared_rule(Arc::clone(&rule));
+        app.config.rule = rule;
+    }
+
+    // `--seed-image PATH` starts from a picture, one cell per pixel.
+    if let Some(path) = flag("--seed-image") {
+        let mapping = ImageMapping {
+            ga_dims: app.config.ga_dims,
+            seed: rand::random(),
+            ..ImageMapping::default()
+        };
+        let mut universe = match Universe::from_image(Path::new(path), &mapping) {
+            Ok(universe) => universe.with_topology(app.config.topology),
+            Err(e) => {
+                eprintln!("Could not read {}: {}", path, e);
+                return;
+            }
+        };
+        universe.set_shared_rule(Arc::clone(&app.config.rule));
+        universe.observation_rate = app.config.observation_rate;
+        universe.decay_rate = app.config.decay_rate;
+        universe.fluctuation_rate = app.config.fluctuation_rate;
+        app.config.grid_dims = universe.grid_dims.clone();
+        app.replace_universe(universe);
+    }
+
+    // `--universes N` ticks N bridged universes together, switched with Tab.
+    if let Some(count) = flag("--universes")
+        .and_then(|count| count.parse::<usize>().ok())
+        .filter(|&count| count > 1)
+    {
+        let config = &app.config;
+        let universes = (0..count).map(|_| config.new_universe()).collect();
+        let mut multiverse = Multiverse::new(universes);
+        // Bridge a twentieth of the cells of each universe to the next.
+        let bridges = app.universe.grid.len() / 20;
+        for i in 1..count {
+            multiverse.bridge_random(i - 1, i, bridges, &mut rand::rng());
         }
-        if let Some(button) = e.release_args() {
-            handle_release(button, &mut is_left_mouse_down, &mut is_right_mouse_down);
-        }
+        app.attach_multiverse(multiverse);
+    }
 
-        apply_tool_effects(
-            &mut universe,
-            &config,
-            &current_tool,
-            mouse_pos,
-            is_left_mouse_down,
-            is_right_mouse_down,
-        );
+    // `--reversible` runs the universe under the second-order rule, which
+    // [Left] steps backwards.
+    if args.iter().any(|arg| arg == "--reversible") {
+        app.universe.set_reversible(true);
+    }
 
-        if e.update_args().is_some() {
-            if entangle_first_partner.is_none() {
-                let triggered_pairs = universe.tick();
-                for (id1, id2) in triggered_pairs {
-                    let coord1 = universe.get_coord_from_index(id1 as usize);
-                    let coord2 = universe.get_coord_from_index(id2 as usize);
-                    entanglement_flashes.push((coord1, coord2, 15));
-                }
-            }
+    // `--schedule SPEC` or `--schedule-file PATH` anneals the rates over the run.
+    let schedule = match (flag("--schedule"), flag("--schedule-file")) {
+        (Some(spec), _) => Some(spec.parse::<Schedule>()),
+        (None, Some(path)) => Some(Schedule::load(Path::new(path))),
+        (None, None) => None,
+    };
+    match schedule {
+        Some(Ok(schedule)) => app.schedule = Some(schedule),
+        Some(Err(e)) => eprintln!("Ignoring the schedule: {}", e),
+        None => {}
+    }
 
-            entanglement_flashes.retain_mut(|(_, _, ttl)| {
-                *ttl = ttl.saturating_sub(1);
-                *ttl > 0
-            });
+    // `--resume` picks a long run back up from its newest autosave checkpoint.
+    if args.iter().any(|arg| arg == "--resume") {
+        match app.resume_autosave() {
+            Some(path) => println!("Resumed from {}", path.display()),
+            None => eprintln!("No autosave checkpoint to resume from"),
         }
+    }
 
-        if e.render_args().is_some() {
-            window.draw_2d(&e, |c, g, device| {
-                draw_app(
-                    c,
-                    g,
-                    device,
-                    &mut glyphs,
-                    &universe,
-                    &config,
-                    &current_tool,
-                    mouse_pos,
-                    entangle_first_partner,
-                    &entanglement_flashes,
-                );
-            });
+    // `--capture PATH` names the [F9] capture file (`.png` for APNG), taking a
+    // frame every `--capture-every N` ticks with `--capture-scale N` pixels a cell.
+    if let Some(path) = flag("--capture") {
+        app.config.capture_path = path.into();
+    }
+    if let Some(every) = flag("--capture-every").and_then(|n| n.parse().ok()) {
+        app.config.capture.every = every;
+    }
+    if let Some(scale) = flag("--capture-scale").and_then(|n| n.parse().ok()) {
+        app.config.capture.scale = scale;
+    }
+
+    // `--stats-out PATH` appends every tick's statistics to a CSV file.
+    if let Some(path) = flag("--stats-out") {
+        match StatsLog::append(Path::new(path)) {
+            Ok(log) => app.stats_log = Some(log),
+            Err(e) => eprintln!("Could not log statistics to {}: {}", path, e),
         }
     }
-}
 
-/// Handles all discrete press input events (key/mouse down).
-fn handle_press(
-    button: Button,
-    universe: &mut Universe,
-    config: &Config,
-    current_tool: &mut ToolMode,
-    entangle_first_partner: &mut Option<u64>,
-    entanglement_flashes: &mut Vec<(Vec<usize>, Vec<usize>, u8)>,
-    is_left_mouse_down: &mut bool,
-    is_right_mouse_down: &mut bool,
-    mouse_pos: [f64; 2],
-) {
-    match button {
-        Button::Keyboard(key) => {
-            *entangle_first_partner = None;
-            match key {
-                Key::D1 => *current_tool = ToolMode::Observe,
-                Key::D2 => *current_tool = ToolMode::Entangle,
-                Key::D3 => *current_tool = ToolMode::Operator,
-                Key::D4 => *current_tool = ToolMode::Disrupt,
-                Key::R => *universe = Universe::new(config.grid_dims.clone(), config.ga_dims),
-                _ => {}
+    // Pick the fastest way to tick this configuration, measuring it on first run.
+    let mut preferences = PreferencesCache::load(&app.config.preferences_path);
+    app.universe.tuning = preferences.tuning_for(&app.universe);
+    if let Err(e) = preferences.save(&app.config.preferences_path) {
+        eprintln!("Could not save preferences: {}", e);
+    }
+
+    // `--tick-rate N` runs N ticks per second of real time, or one per frame
+    // when N is 0.
+    if let Some(rate) = flag("--tick-rate").and_then(|rate| rate.parse::<f64>().ok()) {
+        app.timestep = (rate > 0.0).then(|| FixedTimestep::new(rate));
+    }
+
+    // Tick on a worker thread so slow ticks never stall input or drawing,
+    // unless `--no-worker` is given. The GPU ticker stays on this thread.
+    #[cfg(feature = "gpu")]
+    let on_gpu = app.gpu.is_some();
+    #[cfg(not(feature = "gpu"))]
+    let on_gpu = false;
+    if !on_gpu && !args.iter().any(|arg| arg == "--no-worker") {
+        app.worker = Some(SimWorker::spawn(app.universe.clone(), false));
+    }
+
+    // `--frontend NAME` picks what shows the run: the window (`piston`), the
+    // terminal (`tui`, also `--tui`) or nothing (`headless`), which stops
+    // after `--ticks N` ticks if given. `--serve ws://HOST:PORT` runs
+    // headless too, streaming to WebSocket clients, and `--serve-http
+    // HOST:PORT` runs under the control of HTTP requests.
+    let mut frontends = FrontendRegistry::with_builtins();
+    frontends.register(Headless {
+        ticks: flag("--ticks").and_then(|n| n.parse().ok()),
+        ..Headless::default()
+    });
+    frontends.register(PistonFrontend { watcher });
+    if let Some(address) = flag("--serve") {
+        match Server::bind(address) {
+            Ok(mut server) => {
+                server.ticks = flag("--ticks").and_then(|n| n.parse().ok());
+                if let Ok(address) = server.local_addr() {
+                    println!("Serving on ws://{}", address);
+                }
+                frontends.register(server);
             }
-        }
-        Button::Mouse(button) => match button {
-            MouseButton::Left => {
-                *is_left_mouse_down = true;
-                handle_mouse_click(
-                    universe,
-                    config,
-                    current_tool,
-                    entangle_first_partner,
-                    entanglement_flashes,
-                    mouse_pos,
-                );
+            Err(e) => {
+                eprintln!("Could not listen on {}: {}", address, e);
+                std::process::exit(1);
             }
-            MouseButton::Right => {
-                *is_right_mouse_down = true;
-                // For now, let right-click only work in Operator mode
-                if *current_tool == ToolMode::Operator {
-                    let clicked_coord = get_coord_from_pos(mouse_pos, config);
-                    universe.clear_operator(&clicked_coord);
+        }
+    }
+    if let Some(address) = flag("--serve-http") {
+        match ApiServer::bind(address) {
+            Ok(server) => {
+                if let Ok(address) = server.local_addr() {
+                    println!("Serving the API on http://{}", address);
                 }
+                frontends.register(server);
             }
-            _ => {}
-        },
-        _ => {}
+            Err(e) => {
+                eprintln!("Could not listen on {}: {}", address, e);
+                std::process::exit(1);
+            }
+        }
+    }
+    let name = match flag("--frontend") {
+        _ if args.iter().any(|arg| arg == "--tui") => "tui",
+        _ if args.iter().any(|arg| arg == "--serve") => "serve",
+        _ if args.iter().any(|arg| arg == "--serve-http") => "http",
+        Some(name) => name.as_str(),
+        None => "piston",
+    };
+    match frontends.get_mut(name) {
+        Some(frontend) => {
+            if let Err(e) = frontend.run(&mut app) {
+                eprintln!("Could not run the {} frontend: {}", name, e);
+            }
+        }
+        None => eprintln!(
+            "Unknown frontend {}; the frontends are {}",
+            name,
+            frontends.names().join(", ")
+        ),
     }
 }
 
-/// New: Handles mouse release events to stop painting.
-fn handle_release(button: Button, is_left_mouse_down: &mut bool, is_right_mouse_down: &mut bool) {
-    if let Button::Mouse(button) = button {
-        match button {
-            MouseButton::Left => *is_left_mouse_down = false,
-            MouseButton::Right => *is_right_mouse_down = false,
-            _ => {}
+/// Runs the sweep described in the file at `path`, reporting each run as it
+/// finishes; returns whether every run completed.
+fn run_sweep(path: &Path) -> bool {
+    let sweep = match Sweep::load(path) {
+        Ok(sweep) => sweep,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", path.display(), e);
+            return false;
+        }
+    };
+    let runs = sweep.runs().len();
+    println!(
+        "Sweeping {} runs of {} ticks into {}",
+        runs,
+        sweep.ticks,
+        sweep.out_dir.display()
+    );
+    let result = sweep.run(|run| {
+        let p = &run.params;
+        println!(
+            "Run {} of {} (seed {}, rates {} / {} / {}): {} observed",
+            p.index + 1,
+            runs,
+            p.seed,
+            p.observation_rate,
+            p.decay_rate,
+            p.fluctuation_rate,
+            run.last.observed
+        );
+    });
+    match result {
+        Ok(_) => {
+            println!("Wrote {}", sweep.out_dir.join("summary.csv").display());
+            true
+        }
+        Err(e) => {
+            eprintln!("The sweep stopped: {}", e);
+            false
         }
     }
 }
 
-/// Handles the specific action of a single left mouse click for the active tool.
-fn handle_mouse_click(
-    universe: &mut Universe,
-    config: &Config,
-    current_tool: &ToolMode,
-    entangle_first_partner: &mut Option<u64>,
-    entanglement_flashes: &mut Vec<(Vec<usize>, Vec<usize>, u8)>,
-    mouse_pos: [f64; 2],
-) {
-    let clicked_coord = get_coord_from_pos(mouse_pos, config);
-    let clicked_idx = universe.get_index_from_coord(&clicked_coord);
-
-    match *current_tool {
-        ToolMode::Observe => {
-            // Strong observation is now a continuous effect while mouse is held down
-        }
-        ToolMode::Entangle => {
-            if let Some(idx) = clicked_idx {
-                if universe.grid[idx].consciousness == ConsciousnessState::Potential {
-                    if let Some(id1) = *entangle_first_partner {
-                        let id2 = universe.grid[idx].id;
-                        if id1 != id2 {
-                            universe.entangle_pair(id1, id2);
-                            let coord1 = universe.get_coord_from_index(id1 as usize);
-                            let coord2 = universe.get_coord_from_index(id2 as usize);
-                            entanglement_flashes.push((coord1, coord2, 15));
-                            *entangle_first_partner = None;
-                        }
-                    } else {
-                        *entangle_first_partner = Some(universe.grid[idx].id);
-                    }
+/// Checks every GA axiom for each number of dimensions in `--dims` (a count,
+/// `A..B` or `A..=B`; 1 to 6 by default) on `--samples` random triples from
+/// `--seed`; returns whether they all hold.
+fn verify_ga(args: &[String]) -> bool {
+    let value = |name: &str| {
+        args.iter()
+            .position(|arg| arg == name)
+            .and_then(|i| args.get(i + 1))
+    };
+    let dims = match value("--dims") {
+        None => 1..=6,
+        Some(text) => {
+            let range = match text.split_once("..") {
+                Some((start, end)) => match end.strip_prefix('=') {
+                    Some(end) => start.parse().ok().zip(end.parse().ok()),
+                    None => start
+                        .parse()
+                        .ok()
+                        .zip(end.parse::<usize>().ok().and_then(|e| e.checked_sub(1))),
+                },
+                None => text.parse().ok().map(|n| (n, n)),
+            };
+            match range {
+                Some((start, end)) => start..=end,
+                None => {
+                    eprintln!("Invalid --dims {}; expected N, A..B or A..=B", text);
+                    return false;
                 }
             }
         }
-        ToolMode::Operator => {
-            // Handled by continuous effect
+    };
+    if *dims.end() > share::MAX_GA_DIMS {
+        eprintln!(
+            "Invalid --dims: at most {} dimensions can be checked",
+            share::MAX_GA_DIMS
+        );
+        return false;
+    }
+    fn number<T: std::str::FromStr>(name: &str, text: Option<&String>, default: T) -> Option<T> {
+        let Some(text) = text else {
+            return Some(default);
+        };
+        let number = text.parse().ok();
+        if number.is_none() {
+            eprintln!("Invalid {} {}; expected a whole number", name, text);
+        }
+        number
+    }
+    let samples = number("--samples", value("--samples"), 100);
+    let seed = number("--seed", value("--seed"), 0);
+    let (Some(samples), Some(seed)) = (samples, seed) else {
+        return false;
+    };
+    let mut holds = true;
+    for p in dims {
+        let failures = verify::check_all(p, samples, seed);
+        if failures.is_empty() {
+            println!("Cl({},0): every axiom holds", p);
         }
-        ToolMode::Disrupt => {
-            for_cells_in_radius(config, mouse_pos, |coord| {
-                if let Some(idx) = universe.get_index_from_coord(&coord) {
-                    universe.disrupt_cell(idx);
-                }
-            });
+        for failure in failures {
+            eprintln!("Cl({},0): {}", p, failure);
+            holds = false;
         }
     }
+    holds
 }
 
-/// Applies continuous effects for the active tool.
-fn apply_tool_effects(
-    universe: &mut Universe,
-    config: &Config,
-    current_tool: &ToolMode,
-    mouse_pos: [f64; 2],
-    is_left_mouse_down: bool,
-    is_right_mouse_down: bool,
-) {
-    let mut rng = rng();
-    match *current_tool {
-        ToolMode::Observe => {
-            let passive_observation_prob = 0.1;
-            for_cells_in_radius(config, mouse_pos, |coord| {
-                if let Some(idx) = universe.get_index_from_coord(&coord) {
-                    // Strong observation if mouse is down, otherwise passive
-                    let should_observe = is_left_mouse_down
-                        || (universe.grid[idx].consciousness == ConsciousnessState::Potential
-                            && rng.random_bool(passive_observation_prob));
-                    if should_observe {
-                        universe.observe_cell(idx);
+/// Re-simulates the recording at `path` and checks its state hashes; returns
+/// whether every one matched.
+fn verify_replay(path: &Path) -> bool {
+    let replay = match Replay::load(path) {
+        Ok(replay) => replay,
+        Err(e) => {
+            eprintln!("Could not read {}: {}", path.display(), e);
+            return false;
+        }
+    };
+    match replay.verify() {
+        Ok(0) => {
+            eprintln!("{} has no state hashes to check", path.display());
+            false
+        }
+        Ok(checked) => {
+            println!(
+                "{}: {} state hashes match through tick {}",
+                path.display(),
+                checked,
+                replay.end()
+            );
+            true
+        }
+        Err(mismatch) => {
+            eprintln!("{}: diverged at {}", path.display(), mismatch);
+            false
+        }
+    }
+}
+
+/// The window, drawn with Piston; settings file changes apply while it is open.
+struct PistonFrontend {
+    watcher: SettingsWatcher,
+}
+
+impl Frontend for PistonFrontend {
+    fn name(&self) -> &str {
+        "piston"
+    }
+
+    fn run(&mut self, app: &mut AppState) -> io::Result<()> {
+        // --- Window and Asset Setup ---
+        let mut window: PistonWindow = WindowSettings::new(
+            "Existon Automaton: An Interactive Model of Source Science",
+            app.config.window_size,
+        )
+        .exit_on_esc(true)
+        .build()
+        .map_err(|e| io::Error::other(format!("Failed to build PistonWindow: {}", e)))?;
+        // The window may open at another size than asked for, and on a HiDPI
+        // display no resize event says so.
+        let (size, draw_size) = (window.size(), window.draw_size());
+        app.apply(AppAction::Resize(
+            [size.width, size.height],
+            draw_size.width / size.width.max(1.0),
+        ));
+
+        let assets = Search::ParentsThenKids(3, 3).for_folder("assets").unwrap();
+        let font_path = assets.join("NotoSans-Regular.ttf");
+        let mut glyphs = Glyphs::new(
+            &font_path,
+            window.create_texture_context(),
+            TextureSettings::new(),
+        )
+        .expect("Could not load font");
+        let mut grid = GridTexture::new(&mut window);
+        let mut minimap = GridTexture::new(&mut window);
+
+        let mut last_frame = Instant::now();
+        // Time spent handling events since the last frame was drawn, and the
+        // allocation count then, for the profiler.
+        let mut updating = Duration::ZERO;
+        let mut allocations = ALLOCATOR.allocations();
+        // Opened the first time the sound is unmuted.
+        let mut audio: Option<AudioOutput> = None;
+        while let Some(e) = window.next() {
+            let handling = Instant::now();
+            input::handle_event(app, &e);
+            updating += handling.elapsed();
+            if !app.sonifier.muted {
+                if audio.is_none() {
+                    match AudioOutput::open() {
+                        Ok(output) => audio = Some(output),
+                        Err(e) => {
+                            eprintln!("No sound: {}", e);
+                            app.sonifier.muted = true;
+                        }
                     }
                 }
-            });
-        }
-        ToolMode::Operator => {
-            if is_left_mouse_down {
-                let coord = get_coord_from_pos(mouse_pos, config);
-                universe.set_operator(&coord);
-            } else if is_right_mouse_down {
-                let coord = get_coord_from_pos(mouse_pos, config);
-                universe.clear_operator(&coord);
+                if let Some(audio) = &audio {
+                    audio.play(app.sonifier.take());
+                }
             }
-        }
-        ToolMode::Disrupt => {
-            if is_left_mouse_down {
-                for_cells_in_radius(config, mouse_pos, |coord| {
-                    if let Some(idx) = universe.get_index_from_coord(&coord) {
-                        universe.disrupt_cell(idx);
-                    }
+            for line in app.scripts.take_output() {
+                println!("{}", line);
+            }
+            match self.watcher.poll(Instant::now(), app) {
+                Some(Ok(restart)) if restart.is_empty() => {
+                    println!("Reloaded {}", app.config.settings_path.display())
+                }
+                Some(Ok(restart)) => println!(
+                    "Reloaded {}; restart to apply {}",
+                    app.config.settings_path.display(),
+                    restart.join(", ")
+                ),
+                Some(Err(e)) => eprintln!(
+                    "Ignoring the changes to {}: {}",
+                    app.config.settings_path.display(),
+                    e
+                ),
+                None => {}
+            }
+
+            if e.render_args().is_some() {
+                let now = Instant::now();
+                app.record_frame(now.duration_since(last_frame).as_secs_f64());
+                last_frame = now;
+                window.draw_2d(&e, |c, g, device| {
+                    draw_app(c, g, device, &mut glyphs, [&mut grid, &mut minimap], app);
                 });
+                let count = ALLOCATOR.allocations();
+                if app.profiler.visible {
+                    app.profiler.add_frame(FrameTimings {
+                        update: updating,
+                        render: now.elapsed(),
+                        allocations: Some(count - allocations),
+                    });
+                }
+                updating = Duration::ZERO;
+                allocations = count;
             }
         }
-
-        // Will add Disrupt logic here later
-        _ => {}
+        Ok(())
     }
 }
 
-/// New utility to get a grid coordinate from a pixel position.
-fn get_coord_from_pos(mouse_pos: [f64; 2], config: &Config) -> Vec<usize> {
-    let mut coord = vec![0; config.grid_dims.len()];
-    coord[0] = (mouse_pos[0] / config.cell_size).max(0.0) as usize;
-    if config.grid_dims.len() > 1 {
-        coord[1] = (mouse_pos[1] / config.cell_size).max(0.0) as usize;
+/// The viewed plane as a texture, one texel per cell or per every few cells,
+/// redrawn in a single call.
+struct GridTexture {
+    context: G2dTextureContext,
+    texture: Option<G2dTexture>,
+    image: GridImage,
+}
+
+impl GridTexture {
+    fn new(window: &mut PistonWindow) -> Self {
+        GridTexture {
+            context: window.create_texture_context(),
+            texture: None,
+            image: GridImage::default(),
+        }
+    }
+
+    /// Brings the texture up to date with the viewed plane, a texel for every
+    /// `step`th cell, uploading only when a texel's color changed.
+    fn refresh(
+        &mut self,
+        device: &mut piston_window::GfxDevice,
+        app: &AppState,
+        universe: &Universe,
+        step: usize,
+    ) -> Option<&G2dTexture> {
+        let slice = &app.config.slice;
+        let changed = self
+            .image
+            .update_sampled(universe, slice, step, |idx| app.cell_color(idx));
+        let image = self.image.image();
+        let stale = match &self.texture {
+            Some(texture) => texture.get_size() != image.dimensions(),
+            None => true,
+        };
+        if stale {
+            let settings = TextureSettings::new().filter(Filter::Nearest);
+            self.texture = Texture::from_image(&mut self.context, image, &settings).ok();
+        } else if changed && let Some(texture) = &mut self.texture {
+            let _ = texture.update(&mut self.context, image);
+        }
+        self.context.encoder.flush(device);
+        self.texture.as_ref()
     }
-    coord
 }
 
 /// Handles all drawing logic for the application.
@@ -340,136 +635,776 @@ fn draw_app(
     g: &mut piston_window::G2d,
     device: &mut piston_window::GfxDevice,
     glyphs: &mut Glyphs,
-    universe: &Universe,
-    config: &Config,
-    current_tool: &ToolMode,
-    mouse_pos: [f64; 2],
-    entangle_first_partner: Option<u64>,
-    entanglement_flashes: &[(Vec<usize>, Vec<usize>, u8)],
+    [grid, minimap]: [&mut GridTexture; 2],
+    app: &AppState,
 ) {
+    let (universe, config) = (app.shown_universe(), &app.config);
+    // Grid-space drawing goes through the camera; the toolbar does not.
+    let view = c
+        .transform
+        .trans(app.camera.offset[0], app.camera.offset[1])
+        .zoom(app.camera.zoom);
+    let mouse_pos = app.cursor_world_pos();
     clear(config.background_color, g);
 
-    // --- Draw the 2D slice of the Grid ---
-    let (width, height) = (config.grid_dims[0], config.grid_dims[1]);
-    for y in 0..height {
-        for x in 0..width {
-            let mut coord = vec![0; universe.grid_dims.len()];
-            coord[0] = x;
-            if coord.len() > 1 {
-                coord[1] = y;
-            }
+    if let Some(orbit) = &app.volume {
+        draw_volume(c, g, app, universe, orbit, view);
+        if app.zen.is_none() {
+            draw_chrome(c, g, glyphs, app, universe, view, mouse_pos);
+        }
+        glyphs.factory.encoder.flush(device);
+        return;
+    }
 
-            if let Some(idx) = universe.get_index_from_coord(&coord) {
-                // *** THIS ENTIRE BLOCK WAS MISSING ***
-                let existon = &universe.grid[idx];
-                let x_pos = x as f64 * config.cell_size;
-                let y_pos = y as f64 * config.cell_size;
-
-                let color = match existon.consciousness {
-                    ConsciousnessState::Potential => {
-                        let s = existon.state.coefficients.get(0).map_or(0, |c| c.0);
-                        let e0 = existon.state.coefficients.get(1).map_or(0, |c| c.0);
-                        let e1 = existon.state.coefficients.get(2).map_or(0, |c| c.0);
-                        let e01 = existon.state.coefficients.get(3).map_or(0, |c| c.0);
-
-                        let r = (s + 1) as f32 * 0.35;
-                        let g = (e0 + 1) as f32 * 0.35;
-                        let b = (e1 + 1) as f32 * 0.35;
-                        let a = (e01 + 1) as f32 * 0.4 + 0.5;
-                        [r, g, b, a]
-                    }
-                    ConsciousnessState::Observed => [1.0, 1.0, 0.8, 1.0],
-                    ConsciousnessState::Operator => [0.0, 1.0, 1.0, 1.0],
-                };
-                rectangle(
-                    color,
-                    [x_pos, y_pos, config.cell_size, config.cell_size],
-                    c.transform,
-                    g,
-                );
-                // *** END OF MISSING BLOCK ***
+    // --- Draw the 2D slice of the Grid ---
+    let slice = &config.slice;
+    let dims = universe.grid_dims.len();
+    let [across, down] = slice.axes;
+    let width = config.grid_dims[across];
+    let height = if dims > 1 { config.grid_dims[down] } else { 1 };
+    let topology = universe.topology();
+    let hexagonal = topology.is_hexagonal(universe.grid_dims.len());
+    if hexagonal {
+        // Offset rows don't fit a texture; only the cells in view are drawn,
+        // with a cell's margin.
+        let [left, top] = app.camera.screen_to_world([0.0, 0.0]);
+        let [right, bottom] = app.camera.screen_to_world(config.window_size);
+        let visible = |from: f64, to: f64, len: usize| {
+            let first = (from / config.cell_size - 1.0).max(0.0) as usize;
+            let last = ((to / config.cell_size + 1.0).max(0.0) as usize).min(len);
+            first..last
+        };
+        for y in visible(top, bottom, height) {
+            for x in visible(left, right, width) {
+                let coord = slice.coord(dims, x, y);
+                if let Some(idx) = universe.get_index_from_coord(&coord) {
+                    let pos = get_pos_from_coord(&coord, config, topology);
+                    let color = app.cell_color(idx);
+                    fill_cell(color, pos, config.cell_size, hexagonal, view, g);
+                }
             }
         }
+    } else if let Some(texture) = grid.refresh(device, app, universe, 1) {
+        let cell_size = config.cell_size;
+        Image::new().draw(texture, &c.draw_state, view.scale(cell_size, cell_size), g);
     }
 
     // --- Draw Entanglement Selection Highlight ---
-    if let Some(id) = entangle_first_partner {
-        let coord = universe.get_coord_from_index(id as usize);
-        if !coord.is_empty() {
-            let x_pos = coord[0] as f64 * config.cell_size;
-            let y_pos = if coord.len() > 1 {
-                coord[1] as f64 * config.cell_size
-            } else {
-                0.0
-            };
-            rectangle(
-                [1.0, 0.8, 0.0, 0.5], // Transparent yellow border
+    if let Some(coord) = app
+        .entangle_first_partner
+        .and_then(|id| universe.coord_of_id(id))
+        && !coord.is_empty()
+        && slice.contains(&coord)
+    {
+        let [x_pos, y_pos] = get_pos_from_coord(&coord, config, topology);
+        rectangle(
+            [1.0, 0.8, 0.0, 0.5], // Transparent yellow border
+            [x_pos, y_pos, config.cell_size, config.cell_size],
+            view,
+            g,
+        );
+        // A rubber band to the cursor, green over a cell it would pair with.
+        let half = config.cell_size / 2.0;
+        let color = match app.entangle_drag_target() {
+            Some(_) => [0.3, 1.0, 0.4, 0.9],
+            None => [1.0, 0.8, 0.0, 0.6],
+        };
+        Line::new(color, 1.0).draw(
+            [x_pos + half, y_pos + half, mouse_pos[0], mouse_pos[1]],
+            &c.draw_state,
+            view,
+            g,
+        );
+    }
+
+    // --- Draw Watch Markers ---
+    if app.overlays.watch_markers {
+        let marker = Rectangle::new_border([1.0, 0.4, 0.8, 0.9], 1.0);
+        for &idx in &app.watch_list {
+            let coord = universe.get_coord_from_index(idx);
+            if !slice.contains(&coord) {
+                continue;
+            }
+            let [x_pos, y_pos] = get_pos_from_coord(&coord, config, topology);
+            marker.draw(
                 [x_pos, y_pos, config.cell_size, config.cell_size],
-                c.transform,
+                &c.draw_state,
+                view,
                 g,
             );
         }
     }
 
-    // --- Draw Entanglement Flashes ---
-    for (coord1, coord2, ttl) in entanglement_flashes.iter() {
-        if !coord1.is_empty() && !coord2.is_empty() {
-            let c1_x = (coord1[0] as f64 + 0.5) * config.cell_size;
-            let c1_y = if coord1.len() > 1 {
-                (coord1[1] as f64 + 0.5) * config.cell_size
-            } else {
-                config.cell_size / 2.0
+    // --- Draw the Selection ---
+    if let Some(region) = &app.selection {
+        let x = region[across].start as f64 * config.cell_size;
+        let width = region[across].len() as f64 * config.cell_size;
+        let (y, height) =
+            region
+                .get(down)
+                .filter(|_| dims > 1)
+                .map_or((0.0, config.cell_size), |r| {
+                    (
+                        r.start as f64 * config.cell_size,
+                        r.len() as f64 * config.cell_size,
+                    )
+                });
+        Rectangle::new_border([0.3, 0.9, 1.0, 0.9], 1.0).draw(
+            [x, y, width, height],
+            &c.draw_state,
+            view,
+            g,
+        );
+    }
+
+    // --- Draw the Library Pattern About to Be Stamped ---
+    if app.library.open
+        && let Some(entry) = app.library.selected()
+    {
+        let at = get_coord_from_pos(mouse_pos, config, topology);
+        let placements = entry.pattern.placements(universe, &at);
+        for (cell, idx) in entry.pattern.cells.iter().zip(placements) {
+            let Some(coord) = idx.map(|idx| universe.get_coord_from_index(idx)) else {
+                continue;
             };
-            let c2_x = (coord2[0] as f64 + 0.5) * config.cell_size;
-            let c2_y = if coord2.len() > 1 {
-                (coord2[1] as f64 + 0.5) * config.cell_size
+            if !slice.contains(&coord) {
+                continue;
+            }
+            let mut color = app.color_mapping.color_of(cell.consciousness, &cell.state);
+            color[3] = 0.6;
+            let pos = get_pos_from_coord(&coord, config, topology);
+            fill_cell(color, pos, config.cell_size, hexagonal, view, g);
+        }
+    }
+
+    // --- Draw the Operators About to Be Placed ---
+    // The shape being dragged out, or else the cell a click would fill.
+    if app.current_tool == ToolMode::Operator
+        && !app.library.open
+        && app.playback.is_none()
+        && app.zen.is_none()
+    {
+        let cells = match &app.shape_drag {
+            Some(_) => app.shape_cells(),
+            None => app.operator_target().into_iter().collect(),
+        };
+        let state = app.operator_state();
+        let mut color = app
+            .color_mapping
+            .color_of(ConsciousnessState::Operator, &state);
+        color[3] = 0.5;
+        for coord in cells.iter().filter(|coord| slice.contains(coord)) {
+            let pos = get_pos_from_coord(coord, config, topology);
+            fill_cell(color, pos, config.cell_size, hexagonal, view, g);
+        }
+    }
+
+    // --- Draw Disruption Waves ---
+    // Between ticks a ring grows toward the next one it reaches.
+    let alpha = app.timestep.as_ref().map_or(0.0, |t| t.alpha());
+    for wave in universe.disruption_waves() {
+        let half = config.cell_size / 2.0;
+        let [x, y] = get_pos_from_coord(&wave.center, config, topology).map(|p| p + half);
+        let reach = (wave.radius as f64 - 0.5 + alpha).max(0.5) * config.cell_size;
+        let alpha = (wave.strength() as f32).max(0.15);
+        Rectangle::new_border([0.5, 0.0, 1.0, alpha], 1.0).draw(
+            [x - reach, y - reach, reach * 2.0, reach * 2.0],
+            &c.draw_state,
+            view,
+            g,
+        );
+    }
+
+    // --- Draw Plugin Overlays ---
+    let ctx = PluginCtx {
+        universe,
+        report: &app.last_report,
+    };
+    let mut canvas = PistonCanvas {
+        transform: view,
+        draw_state: &c.draw_state,
+        g,
+        config,
+        topology,
+    };
+    app.plugins.draw_overlays(&ctx, &mut canvas);
+
+    // --- Draw Entanglement Links ---
+    // Every group dimmed, with the group of the cell under the cursor picked out.
+    if app.overlays.entanglement_links {
+        let hovered = universe
+            .get_index_from_coord(&get_coord_from_pos(mouse_pos, config, topology))
+            .and_then(|idx| universe.entanglement.group_of(universe.grid[idx].id));
+        let half = config.cell_size / 2.0;
+        for link in universe.entanglement_links() {
+            let from = universe.get_coord_from_index(link.from);
+            let to = universe.get_coord_from_index(link.to);
+            if !slice.contains(&from) || !slice.contains(&to) {
+                continue;
+            }
+            let [x1, y1] = get_pos_from_coord(&from, config, topology).map(|p| p + half);
+            let [x2, y2] = get_pos_from_coord(&to, config, topology).map(|p| p + half);
+            let (color, width) = if hovered == Some(link.group) {
+                ([1.0, 0.9, 0.3, 0.9], 1.5)
             } else {
-                config.cell_size / 2.0
+                ([1.0, 1.0, 1.0, 0.1 + 0.2 * link.strength as f32], 0.75)
             };
+            Line::new(color, width).draw([x1, y1, x2, y2], &c.draw_state, view, g);
+        }
+    }
+
+    // --- Draw Entanglement Flashes ---
+    for (coord1, coord2, ttl) in app.entanglement_flashes.iter() {
+        if app.overlays.entanglement_flashes && !coord1.is_empty() && !coord2.is_empty() {
+            let half = config.cell_size / 2.0;
+            let [c1_x, c1_y] = get_pos_from_coord(coord1, config, topology).map(|p| p + half);
+            let [c2_x, c2_y] = get_pos_from_coord(coord2, config, topology).map(|p| p + half);
 
             let alpha = (*ttl as f32) / 15.0;
             let line = Line::new([1.0, 1.0, 1.0, alpha], 1.5);
-            line.draw([c1_x, c1_y, c2_x, c2_y], &c.draw_state, c.transform, g);
-        }
-    }
-
-    // Draw the visual effect for the active tool
-    match *current_tool {
-        ToolMode::Observe => {
-            let radius = config.observation_radius;
-            let circle = Ellipse::new([1.0, 1.0, 0.8, 0.1]); // Faint yellow
-            circle.draw(
-                [
-                    mouse_pos[0] - radius,
-                    mouse_pos[1] - radius,
-                    radius * 2.0,
-                    radius * 2.0,
-                ],
+            line.draw([c1_x, c1_y, c2_x, c2_y], &c.draw_state, view, g);
+        }
+    }
+
+    // --- Draw the Minimap ---
+    // The whole plane, a texel for every few cells, with the view outlined.
+    if let Some(map) = app.minimap() {
+        let longest = width.max(height) as f64;
+        let step = (longest / Minimap::SIZE).ceil().max(1.0) as usize;
+        rectangle([0.0, 0.0, 0.0, 0.7], map.bounds, c.transform, g);
+        if let Some(texture) = minimap.refresh(device, app, universe, step) {
+            Image::new()
+                .rect(map.bounds)
+                .draw(texture, &c.draw_state, c.transform, g);
+        }
+        Rectangle::new_border([1.0, 1.0, 1.0, 0.9], 1.0).draw(
+            map.viewport(&app.camera, config.window_size),
+            &c.draw_state,
+            c.transform,
+            g,
+        );
+    }
+
+    // Zen mode is ambient: no tools, panels or toolbar.
+    if app.zen.is_none() {
+        draw_chrome(c, g, glyphs, app, universe, view, mouse_pos);
+    }
+    glyphs.factory.encoder.flush(device);
+}
+
+/// Draws the whole of a 3D grid through the volume view's orbit camera, with
+/// its entanglement links, instead of a slice.
+fn draw_volume(
+    c: piston_window::Context,
+    g: &mut G2d,
+    app: &AppState,
+    universe: &Universe,
+    orbit: &OrbitCamera,
+    view: Matrix2d,
+) {
+    let size = app.config.window_size;
+    for splat in volume::splats(universe, |idx| app.cell_color(idx), orbit, size) {
+        let half = splat.size / 2.0;
+        let [x, y] = splat.pos;
+        rectangle(
+            splat.color,
+            [x - half, y - half, splat.size, splat.size],
+            view,
+            g,
+        );
+    }
+    if app.overlays.entanglement_flashes {
+        for (line, strength) in volume::links(universe, orbit, size) {
+            let alpha = 0.15 + 0.35 * strength as f32;
+            Line::new([1.0, 1.0, 1.0, alpha], 0.75).draw(line, &c.draw_state, view, g);
+        }
+    }
+}
+
+/// Draws everything that belongs to the interface rather than the universe:
+/// the active tool's cursor, the text panels and the toolbar.
+fn draw_chrome(
+    c: piston_window::Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+    app: &AppState,
+    universe: &Universe,
+    view: Matrix2d,
+    mouse_pos: [f64; 2],
+) {
+    let config = &app.config;
+    // Draw the visual effect for the active tool, with its brush radius.
+    let brush_color = match app.current_tool {
+        ToolMode::Observe => Some([1.0, 1.0, 0.8, 0.1]), // Faint yellow
+        ToolMode::Disrupt => Some([0.5, 0.0, 1.0, 0.15]), // Faint purple
+        ToolMode::Paint => Some([1.0, 0.5, 0.0, 0.12]),  // Faint orange
+        _ => None,
+    };
+    if let (Some(color), Some(radius)) = (brush_color, app.brush_radius()) {
+        // A brush that falls off is drawn as nested discs, densest in the middle.
+        let rings = match app.current_tool {
+            ToolMode::Disrupt if config.disruption_falloff != Falloff::Hard => 3,
+            ToolMode::Paint => 3,
+            _ => 1,
+        };
+        for ring in 0..rings {
+            let r = radius * (rings - ring) as f64 / rings as f64;
+            Ellipse::new(color).draw(
+                [mouse_pos[0] - r, mouse_pos[1] - r, r * 2.0, r * 2.0],
                 &c.draw_state,
-                c.transform,
+                view,
                 g,
             );
         }
-        ToolMode::Disrupt => {
-            let radius = config.observation_radius;
-            let circle = Ellipse::new([0.5, 0.0, 1.0, 0.15]); // Faint purple
-            circle.draw(
-                [
-                    mouse_pos[0] - radius,
-                    mouse_pos[1] - radius,
-                    radius * 2.0,
-                    radius * 2.0,
-                ],
+        let [x, y] = app.mouse_pos;
+        let label_x = x + radius * app.camera.zoom + 6.0;
+        crisp_text(config.text_color, config.font_size, app.hidpi_factor)
+            .draw(
+                &match app.current_tool {
+                    ToolMode::Paint => {
+                        format!("{:.0}px [ ]  {} rate [O]", radius, app.paint_rate.name())
+                    }
+                    _ => format!("{:.0}px [ ]", radius),
+                },
+                glyphs,
+                &c.draw_state,
+                c.transform
+                    .trans(label_x, y + config.font_size as f64 / 2.0)
+                    .zoom(1.0 / app.hidpi_factor),
+                g,
+            )
+            .unwrap();
+    }
+    if let Some(inspection) = app.inspection() {
+        draw_inspection(c, g, glyphs, app, &inspection, view);
+    }
+    let mut panel_y = 20.0;
+    if app.hud.visible {
+        panel_y = draw_panel(c, g, glyphs, app, &[app.hud.describe()], panel_y);
+    }
+    if app.overlays.plugins {
+        panel_y = draw_panel(c, g, glyphs, app, &app.plugins.describe(), panel_y);
+    }
+    if app.library.open {
+        panel_y = draw_panel(c, g, glyphs, app, &app.library.describe(), panel_y);
+    }
+    if app.profiler.visible {
+        draw_panel(c, g, glyphs, app, &app.profiler.describe(), panel_y);
+    }
+    if app.overlays.plot {
+        draw_plot_strip(c, g, glyphs, app);
+    }
+    if app.panel.open {
+        draw_control_panel(c, g, glyphs, app);
+    }
+    if app.overlays.help {
+        draw_help(c, g, glyphs, app);
+    }
+
+    // Draw the Toolbar
+    let status = match (&app.playback, &app.recorder) {
+        (Some(playback), _) => format!(
+            "Replay {}/{} [Left/Right]",
+            playback.position(),
+            playback.replay().end()
+        ),
+        (None, Some(recorder)) => format!("REC since {}", recorder.start_tick()),
+        (None, None) => String::new(),
+    };
+    let status = match app.sonifier.muted {
+        false => format!("SOUND [A]  {}", status),
+        true => status,
+    };
+    let status = match &app.capture {
+        Some(capture) => format!("CAPTURE {} frames [F9]  {}", capture.frames().len(), status),
+        None => status,
+    };
+    let status = match (
+        &app.volume,
+        config.slice.describe(&universe.grid_dims).as_str(),
+    ) {
+        (Some(orbit), _) => format!("{} [D]  {}", orbit.describe(), status),
+        (None, "") => status,
+        (None, plane) => format!("{} [PgUp/PgDn]  {}", plane, status),
+    };
+    let status = match &app.timestep {
+        Some(timestep) => format!("{}  {}", timestep.describe(), status),
+        None => status,
+    };
+    let status = match &app.schedule {
+        Some(schedule) => format!("{}  {}", schedule.describe(universe.tick_count), status),
+        None => status,
+    };
+    let status = match &app.multiverse {
+        Some(multiverse) => format!(
+            "[Tab] Universe {}/{}  {}",
+            multiverse.active + 1,
+            multiverse.universes.len(),
+            status
+        ),
+        None => status,
+    };
+    let status = if app.current_tool == ToolMode::Operator {
+        let mut state = match &app.operator_sample {
+            Some(state) => format!("picked {}", state),
+            None => app.operator_preset.name().to_string(),
+        };
+        if app.operator_shape != Shape::Freehand {
+            state = format!("{}  {}", state, app.operator_shape.name());
+        }
+        if app.operator_snap > 1 {
+            state = format!("{}  snap {}", state, app.operator_snap);
+        }
+        match app.keymap.keys(AppAction::CycleOperatorPreset) {
+            Some(keys) => format!("[{}] {}  {}", keys, state, status),
+            None => format!("{}  {}", state, status),
+        }
+    } else {
+        status
+    };
+    draw_toolbar(c, g, glyphs, app, universe.boundary, &status);
+}
+
+/// Draws the Inspect tool's view of a cell: an outline around it, lines to its
+/// entanglement partners, and a tooltip beside the cursor.
+fn draw_inspection(
+    c: piston_window::Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+    app: &AppState,
+    inspection: &Inspection,
+    view: Matrix2d,
+) {
+    let config = &app.config;
+    let topology = app.shown_universe().topology();
+    let cell_size = config.cell_size;
+    let half = cell_size / 2.0;
+    if app.volume.is_none() {
+        let [x, y] = get_pos_from_coord(&inspection.coord, config, topology);
+        for partner in &inspection.partners {
+            let [px, py] = get_pos_from_coord(partner, config, topology);
+            Line::new([1.0, 0.8, 0.0, 0.8], 1.0).draw(
+                [x + half, y + half, px + half, py + half],
                 &c.draw_state,
-                c.transform,
+                view,
                 g,
             );
         }
-        _ => {}
+        Rectangle::new_border([1.0, 0.8, 0.0, 1.0], 1.0).draw(
+            [x, y, cell_size, cell_size],
+            &c.draw_state,
+            view,
+            g,
+        );
+    }
+
+    let lines = inspection.lines();
+    let line_height = config.font_size as f64 * 1.5;
+    let padding = 8.0;
+    let longest = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    let width = longest as f64 * config.font_size as f64 * 0.6 + padding * 2.0;
+    let height = lines.len() as f64 * line_height + padding * 2.0;
+    // Beside the cursor, kept inside the window.
+    let [window_width, window_height] = config.window_size;
+    let x = (app.mouse_pos[0] + 16.0).min(window_width - width).max(0.0);
+    let y = (app.mouse_pos[1] + 16.0)
+        .min(window_height - height)
+        .max(0.0);
+    rectangle([0.0, 0.0, 0.0, 0.85], [x, y, width, height], c.transform, g);
+    for (i, line) in lines.iter().enumerate() {
+        let baseline = y + padding + line_height * (i as f64 + 1.0) - line_height * 0.3;
+        crisp_text(config.text_color, config.font_size, app.hidpi_factor)
+            .draw(
+                line,
+                glyphs,
+                &c.draw_state,
+                c.transform
+                    .trans(x + padding, baseline)
+                    .zoom(1.0 / app.hidpi_factor),
+                g,
+            )
+            .unwrap();
+    }
+}
+
+/// Draws the plot strip of recent population metrics above the toolbar, each
+/// metric scaled to its own peak, with a legend of the latest values.
+fn draw_plot_strip(c: piston_window::Context, g: &mut G2d, glyphs: &mut Glyphs, app: &AppState) {
+    let config = &app.config;
+    let [window_width, window_height] = config.window_size;
+    let height = 90.0;
+    let strip = [
+        20.0,
+        window_height - 40.0 - height - 10.0,
+        window_width - 40.0,
+        height,
+    ];
+    rectangle([0.0, 0.0, 0.0, 0.7], strip, c.transform, g);
+    let padding = 8.0;
+    let plot = [
+        strip[0] + padding,
+        strip[1] + padding,
+        strip[2] - padding * 2.0,
+        strip[3] - padding * 2.0,
+    ];
+    let mut legend_x = plot[0];
+    for metric in Metric::ALL {
+        let points = app.metrics.polyline(metric, plot);
+        let line = Line::new(metric.color(), 1.0);
+        for pair in points.windows(2) {
+            let ([x1, y1], [x2, y2]) = (pair[0], pair[1]);
+            line.draw([x1, y1, x2, y2], &c.draw_state, c.transform, g);
+        }
+        let latest = app.metrics.latest().map_or(0, |sample| metric.of(sample));
+        let label = format!(
+            "{} {} (peak {})",
+            metric.name(),
+            latest,
+            app.metrics.peak(metric)
+        );
+        crisp_text(metric.color(), config.font_size, app.hidpi_factor)
+            .draw(
+                &label,
+                glyphs,
+                &c.draw_state,
+                c.transform
+                    .trans(legend_x, plot[1] + config.font_size as f64)
+                    .zoom(1.0 / app.hidpi_factor),
+                g,
+            )
+            .unwrap();
+        legend_x += 260.0;
+    }
+}
+
+/// Draws a translucent text panel starting at `top`, returning the y just below it.
+fn draw_panel(
+    c: piston_window::Context,
+    g: &mut G2d,
+    glyphs: &mut Glyphs,
+    app: &AppState,
+    lines: &[String],
+    top: f64,
+) -> f64 {
+    let config = &app.config;
+    let line_height = config.font_size as f64 * 1.5;
+    let padding = 12.0;
+    let height = lines.len() as f64 * line_height + padding * 2.0;
+
+    rectangle(
+        [0.0, 0.0, 0.0, 0.8],
+        [20.0, top, config.window_size[0] - 40.0, height],
+        c.transform,
+        g,
+    );
+    for (i, line) in lines.iter().enumerate() {
+        let y = top + padding + (i as f64 + 1.0) * line_height - line_height * 0.3;
+        crisp_text(config.text_color, config.font_size, app.hidpi_factor)
+            .draw(
+                line,
+                glyphs,
+                &c.draw_state,
+                c.transform
+                    .trans(20.0 + padding, y)
+                    .zoom(1.0 / app.hidpi_factor),
+                g,
+            )
+            .unwrap();
+    }
+    top + height + padding
+}
+
+/// Draws the help overlay over most of the window: each section under its
+/// heading, with short entries such as the keys flowed into columns.
+fn draw_help(c: piston_window::Context, g: &mut G2d, glyphs: &mut Glyphs, app: &AppState) {
+    let config = &app.config;
+    let [window_width, window_height] = config.window_size;
+    let (padding, line_height) = (16.0, config.font_size as f64 * 1.4);
+    let bounds = [20.0, 20.0, window_width - 40.0, window_height - 80.0];
+    rectangle([0.0, 0.0, 0.0, 0.85], bounds, c.transform, g);
+
+    let hidpi = app.hidpi_factor;
+    let size = (config.font_size as f64 * hidpi).round() as u32;
+    let mut label = |g: &mut G2d, line: &str, x: f64, y: f64, color: [f32; 4]| -> f64 {
+        let transform = c.transform.trans(x, y).zoom(1.0 / hidpi);
+        crisp_text(color, config.font_size, hidpi)
+            .draw(line, glyphs, &c.draw_state, transform, g)
+            .unwrap();
+        glyphs.width(size, line).unwrap_or(0.0) / hidpi
+    };
+    let highlight = [1.0, 0.8, 0.0, 1.0];
+    const COLUMN_WIDTH: f64 = 300.0;
+
+    let mut sections: Vec<(&str, Vec<HelpLine>)> = Vec::new();
+    for line in help::help_lines(app) {
+        match line {
+            HelpLine::Heading(title) => sections.push((title, Vec::new())),
+            line => match sections.last_mut() {
+                Some((_, lines)) => lines.push(line),
+                None => sections.push(("", vec![line])),
+            },
+        }
+    }
+    let left = bounds[0] + padding;
+    let mut y = bounds[1] + padding;
+    for (title, lines) in sections {
+        y += line_height;
+        label(g, title, left, y, highlight);
+        let short = lines.iter().all(
+            |line| matches!(line, HelpLine::Entry(keys, what) if keys.len() + what.len() < 36),
+        );
+        let columns = if short {
+            ((bounds[2] - padding * 2.0) / COLUMN_WIDTH)
+                .floor()
+                .max(1.0) as usize
+        } else {
+            1
+        };
+        let rows = lines.len().div_ceil(columns);
+        for (i, line) in lines.iter().enumerate() {
+            let x = left + (i / rows) as f64 * COLUMN_WIDTH;
+            let line_y = y + ((i % rows) + 1) as f64 * line_height;
+            match line {
+                HelpLine::Entry(keys, what) => {
+                    let width = label(g, keys, x, line_y, highlight);
+                    label(g, what, x + width + 10.0, line_y, config.text_color);
+                }
+                HelpLine::Text(text) => {
+                    label(g, text, x, line_y, config.text_color);
+                }
+                HelpLine::Heading(_) => {}
+            }
+        }
+        y += rows as f64 * line_height + line_height * 0.5;
+    }
+}
+
+/// Draws the control panel's sliders and buttons in screen space.
+fn draw_control_panel(c: piston_window::Context, g: &mut G2d, glyphs: &mut Glyphs, app: &AppState) {
+    let config = &app.config;
+    rectangle([0.0, 0.0, 0.0, 0.8], app.panel.bounds(), c.transform, g);
+    let mut label = |g: &mut G2d, line: &str, x: f64, y: f64, color: [f32; 4]| {
+        let transform = c.transform.trans(x, y).zoom(1.0 / app.hidpi_factor);
+        crisp_text(color, config.font_size, app.hidpi_factor)
+            .draw(line, glyphs, &c.draw_state, transform, g)
+            .unwrap();
     };
-    // Draw the Toolbar
-    draw_toolbar(c, g, glyphs, config, current_tool);
-    glyphs.factory.encoder.flush(device);
+    let baseline = |y: f64, height: f64| y + height / 2.0 + config.font_size as f64 / 2.0 - 2.0;
+    for (control, [x, y, width, height]) in app.panel.controls() {
+        let text_y = baseline(y, height);
+        match control {
+            Control::Slider(setting) => {
+                let value = setting.get(app);
+                let filled = width * setting.fraction(value);
+                let color = if app.panel.dragging == Some(setting) {
+                    [1.0, 0.8, 0.0, 1.0]
+                } else {
+                    config.text_color
+                };
+                let left = app.panel.origin[0] + ControlPanel::PADDING;
+                label(g, setting.name(), left, text_y, config.text_color);
+                label(g, &setting.format(value), x + width + 8.0, text_y, color);
+                rectangle([0.3, 0.3, 0.35, 1.0], [x, y, width, height], c.transform, g);
+                rectangle(color, [x, y, filled, height], c.transform, g);
+            }
+            Control::Button(button) => {
+                rectangle(
+                    [0.25, 0.25, 0.3, 1.0],
+                    [x, y, width, height],
+                    c.transform,
+                    g,
+                );
+                let text_x = x + width / 2.0 - button.label().len() as f64 * 3.5;
+                label(g, button.label(), text_x, text_y, config.text_color);
+            }
+        }
+    }
+}
+
+/// Text of `font_size` points, rasterized at the display's resolution; its
+/// transform must be zoomed by `1 / hidpi_factor` to draw it in points.
+fn crisp_text(color: [f32; 4], font_size: u32, hidpi_factor: f64) -> text::Text {
+    text::Text::new_color(color, (font_size as f64 * hidpi_factor).round() as u32)
+}
+
+/// Fills the cell whose top-left corner is at `pos`: a square, or a hexagon
+/// stretched so that rows `cell_size` apart tile without gaps.
+fn fill_cell(
+    color: [f32; 4],
+    [x, y]: [f64; 2],
+    cell_size: f64,
+    hexagonal: bool,
+    transform: Matrix2d,
+    g: &mut G2d,
+) {
+    if !hexagonal {
+        rectangle(color, [x, y, cell_size, cell_size], transform, g);
+        return;
+    }
+    let (half, third) = (cell_size / 2.0, cell_size / 3.0);
+    let (cx, cy) = (x + half, y + half);
+    let corners = [
+        [cx, cy - 2.0 * third],
+        [cx + half, cy - third],
+        [cx + half, cy + third],
+        [cx, cy + 2.0 * third],
+        [cx - half, cy + third],
+        [cx - half, cy - third],
+    ];
+    polygon(color, &corners, transform, g);
+}
+
+/// Draws plugin overlays onto the grid view, one cell per `cell_size` pixels.
+struct PistonCanvas<'a, 'g> {
+    transform: Matrix2d,
+    draw_state: &'a DrawState,
+    g: &'a mut G2d<'g>,
+    config: &'a Config,
+    topology: Topology,
+}
+
+impl PistonCanvas<'_, '_> {
+    /// The top-left pixel of a cell in the displayed slice.
+    fn origin(&self, coord: &[usize]) -> [f64; 2] {
+        get_pos_from_coord(coord, self.config, self.topology)
+    }
+}
+
+impl Canvas for PistonCanvas<'_, '_> {
+    fn fill_cell(&mut self, coord: &[usize], color: [f32; 4]) {
+        if !self.config.slice.contains(coord) {
+            return;
+        }
+        let hexagonal = self.topology.is_hexagonal(coord.len());
+        let pos = self.origin(coord);
+        let cell_size = self.config.cell_size;
+        fill_cell(color, pos, cell_size, hexagonal, self.transform, self.g);
+    }
+
+    fn outline_cell(&mut self, coord: &[usize], color: [f32; 4]) {
+        if !self.config.slice.contains(coord) {
+            return;
+        }
+        let [x, y] = self.origin(coord);
+        let cell_size = self.config.cell_size;
+        Rectangle::new_border(color, 1.0).draw(
+            [x, y, cell_size, cell_size],
+            self.draw_state,
+            self.transform,
+            self.g,
+        );
+    }
+
+    fn line(&mut self, from: &[usize], to: &[usize], color: [f32; 4]) {
+        let half = self.config.cell_size / 2.0;
+        let ([x1, y1], [x2, y2]) = (self.origin(from), self.origin(to));
+        Line::new(color, 1.0).draw(
+            [x1 + half, y1 + half, x2 + half, y2 + half],
+            self.draw_state,
+            self.transform,
+            self.g,
+        );
+    }
 }
 
 /// Draws the interactive toolbar at the bottom of the screen.
@@ -477,81 +1412,58 @@ fn draw_toolbar(
     c: piston_window::Context,
     g: &mut piston_window::G2d,
     glyphs: &mut Glyphs,
-    config: &Config,
-    current_tool: &ToolMode,
+    app: &AppState,
+    boundary: BoundaryCondition,
+    status: &str,
 ) {
-    let toolbar_height = 40.0;
-    let window_height = config.window_size[1];
-    let toolbar_y = window_height - toolbar_height;
-
+    let config = &app.config;
+    let window_size = config.window_size;
     rectangle(
         config.toolbar_color,
-        [0.0, toolbar_y, config.window_size[0], toolbar_height],
+        Toolbar::bounds(window_size),
         c.transform,
         g,
     );
 
-    let tools = [
-        (ToolMode::Observe, "[1] Observe 🔎"),
-        (ToolMode::Entangle, "[2] Entangle 🔗"),
-        (ToolMode::Operator, "[3] Operator 🏗️"),
-        (ToolMode::Disrupt, "[4] Disrupt 🌊"),
-    ];
-
-    let mut start_x = 20.0;
+    let [_, toolbar_y, _, toolbar_height] = Toolbar::bounds(window_size);
     let text_y = toolbar_y + toolbar_height / 2.0 + (config.font_size as f64 / 2.0) - 2.0;
-
-    for (tool_mode, tool_text) in tools.iter() {
-        let is_active = tool_mode == current_tool;
-        let color = if is_active {
+    let hovered = if app.over_toolbar() {
+        Toolbar::item_at(window_size, app.mouse_pos)
+    } else {
+        None
+    };
+    for (item, rect) in Toolbar::items(window_size) {
+        if hovered == Some(item) {
+            rectangle([1.0, 1.0, 1.0, 0.12], rect, c.transform, g);
+        }
+        let color = if item.is_active(app) {
             [1.0, 0.8, 0.0, 1.0]
         } else {
             config.text_color
         };
-
-        text::Text::new_color(color, config.font_size)
+        crisp_text(color, config.font_size, app.hidpi_factor)
             .draw(
-                tool_text,
+                &item.label(app),
                 glyphs,
                 &c.draw_state,
-                c.transform.trans(start_x, text_y),
+                c.transform
+                    .trans(rect[0] + 6.0, text_y)
+                    .zoom(1.0 / app.hidpi_factor),
                 g,
             )
             .unwrap();
-        start_x += 200.0;
     }
-}
 
-/// Utility function to iterate over all grid cells within a given pixel radius of a point.
-fn for_cells_in_radius<F>(config: &Config, center_pos: [f64; 2], mut callback: F)
-where
-    F: FnMut(Vec<usize>),
-{
-    let radius_sq = config.observation_radius * config.observation_radius;
-    let cell_radius_x = (config.observation_radius / config.cell_size).ceil() as i32;
-    let cell_radius_y = (config.observation_radius / config.cell_size).ceil() as i32;
-
-    let center_grid_x = (center_pos[0] / config.cell_size) as i32;
-    let center_grid_y = (center_pos[1] / config.cell_size) as i32;
-
-    for dy in -cell_radius_y..=cell_radius_y {
-        for dx in -cell_radius_x..=cell_radius_x {
-            let cell_x = center_grid_x + dx;
-            let cell_y = center_grid_y + dy;
-
-            let cell_center_x = (cell_x as f64 + 0.5) * config.cell_size;
-            let cell_center_y = (cell_y as f64 + 0.5) * config.cell_size;
-            let dist_sq =
-                (cell_center_x - center_pos[0]).powi(2) + (cell_center_y - center_pos[1]).powi(2);
-
-            if dist_sq <= radius_sq {
-                let mut coord = vec![0; config.grid_dims.len()];
-                coord[0] = cell_x.rem_euclid(config.grid_dims[0] as i32) as usize;
-                if config.grid_dims.len() > 1 {
-                    coord[1] = cell_y.rem_euclid(config.grid_dims[1] as i32) as usize;
-                }
-                callback(coord);
-            }
-        }
-    }
+    // The boundary condition is shown after the buttons, right of the last item.
+    crisp_text(config.text_color, config.font_size, app.hidpi_factor)
+        .draw(
+            &format!("[B] {:?}  {}", boundary, status),
+            glyphs,
+            &c.draw_state,
+            c.transform
+                .trans(Toolbar::end(window_size) + 6.0, text_y)
+                .zoom(1.0 / app.hidpi_factor),
+            g,
+        )
+        .unwrap();
 }
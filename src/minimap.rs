@@ -0,0 +1,82 @@
+//! The minimap: the whole viewed plane in a corner, with the part on screen
+//! outlined.
+//!
+//! It only appears while some of the grid is off screen, after zooming in or
+//! panning away. A [`Minimap`] is laid out afresh from the camera each time it
+//! is asked for, in screen pixels, and answers where on the grid a point on it
+//! lies, so a click there can center the view. The frontend draws the grid
+//! into [`Minimap::bounds`] from a downsampled [`GridImage`](crate::render::GridImage).
+
+use crate::app::Camera;
+
+/// Where the minimap sits and how it maps onto the grid; see the
+/// [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Minimap {
+    /// The minimap's rectangle as `[x, y, width, height]`.
+    pub bounds: [f64; 4],
+    /// Minimap pixels per unzoomed grid-space pixel.
+    pub scale: f64,
+}
+
+impl Minimap {
+    /// The length of the minimap's longer side.
+    pub const SIZE: f64 = 160.0;
+    /// The gap between the minimap and the window's right edge.
+    const MARGIN: f64 = 20.0;
+
+    /// The minimap for a grid of `extent` grid-space pixels seen through
+    /// `camera` in a window of `window` pixels, with its bottom edge at
+    /// `bottom`, or `None` while the whole grid is on screen.
+    pub fn of(extent: [f64; 2], camera: &Camera, window: [f64; 2], bottom: f64) -> Option<Self> {
+        // Half a pixel of slack, so rounding in a fitted view does not count.
+        let [left, top] = camera.screen_to_world([0.0, 0.0]).map(|p| p - 0.5);
+        let [right, lower] = camera.screen_to_world(window).map(|p| p + 0.5);
+        if left <= 0.0 && top <= 0.0 && right >= extent[0] && lower >= extent[1] {
+            return None;
+        }
+        let scale = (Self::SIZE / extent[0]).min(Self::SIZE / extent[1]);
+        let [width, height] = [extent[0] * scale, extent[1] * scale];
+        Some(Minimap {
+            bounds: [
+                window[0] - Self::MARGIN - width,
+                bottom - height,
+                width,
+                height,
+            ],
+            scale,
+        })
+    }
+
+    /// Whether the screen point `pos` lies on the minimap.
+    pub fn contains(&self, pos: [f64; 2]) -> bool {
+        let [x, y, width, height] = self.bounds;
+        (x..=x + width).contains(&pos[0]) && (y..=y + height).contains(&pos[1])
+    }
+
+    /// The grid-space position under the screen point `pos`, if it lies on
+    /// the minimap.
+    pub fn world_at(&self, pos: [f64; 2]) -> Option<[f64; 2]> {
+        self.contains(pos).then(|| {
+            [
+                (pos[0] - self.bounds[0]) / self.scale,
+                (pos[1] - self.bounds[1]) / self.scale,
+            ]
+        })
+    }
+
+    /// The part of the grid `camera` shows in a window of `window` pixels, as
+    /// a rectangle on the minimap cut to its bounds.
+    pub fn viewport(&self, camera: &Camera, window: [f64; 2]) -> [f64; 4] {
+        let [x, y, width, height] = self.bounds;
+        let to_map = |world: [f64; 2]| {
+            [
+                (x + world[0] * self.scale).clamp(x, x + width),
+                (y + world[1] * self.scale).clamp(y, y + height),
+            ]
+        };
+        let [left, top] = to_map(camera.screen_to_world([0.0, 0.0]));
+        let [right, bottom] = to_map(camera.screen_to_world(window));
+        [left, top, right - left, bottom - top]
+    }
+}
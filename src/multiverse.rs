@@ -0,0 +1,199 @@
+//! Several universes ticking side by side, coupled by bridges.
+//!
+//! A [`Multiverse`] holds independent [`Universe`]s, each a slice of a larger
+//! space with its own seed, rates and grid, and ticks them together. A
+//! [`Bridge`] entangles a cell of one universe with a cell of another: when
+//! either is observed, the other collapses right after the tick, through its
+//! universe's [`PairOperator`](crate::entanglement::PairOperator). Cells are
+//! named across universes by [`CellRef`], the universe's position plus the
+//! cell's stable ID, so bridges follow `Operator`s as they move.
+//!
+//! Each bridge collapse is logged as an intervention of the universe it lands
+//! in, so every universe still replays on its own from its share code.
+
+use crate::existon::ConsciousnessState;
+use crate::universe::{TickReport, Universe};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use std::collections::HashSet;
+
+/// A cell anywhere in a [`Multiverse`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct CellRef {
+    /// The universe's position in [`Multiverse::universes`].
+    pub universe: usize,
+    /// The cell's ID within that universe.
+    pub id: u64,
+}
+
+/// Two cells in different universes, entangled with each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bridge {
+    pub a: CellRef,
+    pub b: CellRef,
+}
+
+/// What happened during one tick of a [`Multiverse`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MultiverseReport {
+    /// Each universe's own report, in universe order.
+    pub reports: Vec<TickReport>,
+    /// The bridges that fired, as (observed, collapsed) cells.
+    pub bridges: Vec<(CellRef, CellRef)>,
+}
+
+/// Universes ticked together and the bridges between them.
+#[derive(Debug, Clone)]
+pub struct Multiverse {
+    pub universes: Vec<Universe>,
+    /// The universe a frontend shows and edits.
+    pub active: usize,
+    /// In the order they were made; no cell is in more than one.
+    bridges: Vec<Bridge>,
+}
+
+impl Multiverse {
+    /// A multiverse of `universes`, with no bridges yet and the first active.
+    pub fn new(universes: Vec<Universe>) -> Self {
+        Multiverse {
+            universes,
+            active: 0,
+            bridges: Vec::new(),
+        }
+    }
+
+    /// The bridges, in the order they were made.
+    pub fn bridges(&self) -> &[Bridge] {
+        &self.bridges
+    }
+
+    /// The bridge `cell` is an end of, if any.
+    pub fn bridge_of(&self, cell: CellRef) -> Option<&Bridge> {
+        self.bridges.iter().find(|b| b.a == cell || b.b == cell)
+    }
+
+    /// Entangles two cells of different universes, returning whether it did.
+    ///
+    /// Cells that do not exist, cells of the same universe and cells that
+    /// already have a bridge are refused.
+    pub fn bridge(&mut self, a: CellRef, b: CellRef) -> bool {
+        let exists = |cell: CellRef| {
+            self.universes
+                .get(cell.universe)
+                .is_some_and(|u| u.index_of_id(cell.id).is_some())
+        };
+        let ok = a.universe != b.universe
+            && exists(a)
+            && exists(b)
+            && self.bridge_of(a).is_none()
+            && self.bridge_of(b).is_none();
+        if ok {
+            self.bridges.push(Bridge { a, b });
+        }
+        ok
+    }
+
+    /// Removes the bridge `cell` is an end of, if any.
+    pub fn unbridge(&mut self, cell: CellRef) {
+        self.bridges.retain(|b| b.a != cell && b.b != cell);
+    }
+
+    /// Bridges up to `count` random cells of universe `a` with as many of
+    /// universe `b`, skipping cells that already have a bridge, and returns
+    /// how many it made.
+    pub fn bridge_random<R: Rng + ?Sized>(
+        &mut self,
+        a: usize,
+        b: usize,
+        count: usize,
+        rng: &mut R,
+    ) -> usize {
+        let mut from = self.free_cells(a);
+        let mut to = self.free_cells(b);
+        from.shuffle(rng);
+        to.shuffle(rng);
+        from.into_iter()
+            .zip(to)
+            .take(count)
+            .filter(|&(x, y)| self.bridge(x, y))
+            .count()
+    }
+
+    /// The cells of universe `universe` without a bridge, in grid order.
+    fn free_cells(&self, universe: usize) -> Vec<CellRef> {
+        self.universes.get(universe).map_or_else(Vec::new, |u| {
+            u.grid
+                .iter()
+                .map(|cell| CellRef {
+                    universe,
+                    id: cell.id,
+                })
+                .filter(|&cell| self.bridge_of(cell).is_none())
+                .collect()
+        })
+    }
+
+    /// The universe a frontend shows.
+    pub fn active_universe(&self) -> &Universe {
+        &self.universes[self.active]
+    }
+
+    /// Ticks every universe, then fires the bridges.
+    ///
+    /// A bridge fires when exactly one end was observed during the tick, by
+    /// its rule or by entanglement, and the other is still `Potential`; ends
+    /// observed together do not trigger each other, as within a universe.
+    /// Bridges whose cells no longer exist are dropped.
+    pub fn tick(&mut self) -> MultiverseReport {
+        let reports: Vec<TickReport> = self.universes.iter_mut().map(|u| u.tick()).collect();
+        let observed: Vec<HashSet<&[usize]>> = reports
+            .iter()
+            .map(|report| {
+                let collapsed = report.entanglements.iter().map(|(_, to)| to);
+                report
+                    .observations
+                    .iter()
+                    .chain(collapsed)
+                    .map(Vec::as_slice)
+                    .collect()
+            })
+            .collect();
+        let universes = &self.universes;
+        let coord = |cell: CellRef| universes[cell.universe].coord_of_id(cell.id);
+        self.bridges
+            .retain(|b| coord(b.a).is_some() && coord(b.b).is_some());
+
+        let seen = |cell: CellRef| {
+            coord(cell).is_some_and(|at| observed[cell.universe].contains(at.as_slice()))
+        };
+        let mut fired = Vec::new();
+        for bridge in &self.bridges {
+            match (seen(bridge.a), seen(bridge.b)) {
+                (true, false) => fired.push((bridge.a, bridge.b)),
+                (false, true) => fired.push((bridge.b, bridge.a)),
+                _ => {}
+            }
+        }
+
+        let mut bridges = Vec::new();
+        for (source, target) in fired {
+            let from = &self.universes[source.universe];
+            let Some(idx) = from.index_of_id(source.id) else {
+                continue;
+            };
+            let state = from.grid[idx].state.clone();
+            let universe = &mut self.universes[target.universe];
+            let Some(idx) = universe.index_of_id(target.id) else {
+                continue;
+            };
+            if universe.grid[idx].consciousness == ConsciousnessState::Potential
+                && state.p == universe.ga_dims
+            {
+                let to = universe.get_coord_from_index(idx);
+                universe.collapse_as_partner(&to, &state);
+                bridges.push((source, target));
+            }
+        }
+        MultiverseReport { reports, bridges }
+    }
+}
@@ -0,0 +1,259 @@
+//! The control panel: sliders and buttons for tuning a run while it plays.
+//!
+//! A [`ControlPanel`] lays out one slider per [`Setting`] and a row of
+//! [`PanelButton`]s in screen pixels, and answers which control is under a
+//! point. It holds no widgets of its own: the frontend draws the controls from
+//! [`ControlPanel::controls`] and [`Setting::fraction`], and routes presses and
+//! drags over the panel to it instead of the active tool, in the immediate-mode
+//! style of the rest of the UI.
+//!
+//! Rates set here are not interventions, like zen mode's drift, so a share
+//! code records only the rates of its moment. Entanglement is the exception:
+//! letting go of its slider rewires the grid at the chosen percentage, which
+//! is logged.
+
+use crate::app::AppState;
+use crate::input::AppAction;
+use crate::timestep::FixedTimestep;
+
+/// A value the control panel has a slider for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setting {
+    ObservationRate,
+    DecayRate,
+    FluctuationRate,
+    /// The share of the grid entangled by the next rewire.
+    EntanglementPercentage,
+    /// Ticks per second of real time; 0 ticks once per frame.
+    TickRate,
+    /// The Observe tool's reach around the cursor, in pixels.
+    ObservationRadius,
+}
+
+impl Setting {
+    /// Every setting, in panel order.
+    pub const ALL: [Setting; 6] = [
+        Setting::ObservationRate,
+        Setting::DecayRate,
+        Setting::FluctuationRate,
+        Setting::EntanglementPercentage,
+        Setting::TickRate,
+        Setting::ObservationRadius,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Setting::ObservationRate => "Observation",
+            Setting::DecayRate => "Decay",
+            Setting::FluctuationRate => "Fluctuation",
+            Setting::EntanglementPercentage => "Entanglement",
+            Setting::TickRate => "Tick rate",
+            Setting::ObservationRadius => "Observe radius",
+        }
+    }
+
+    /// The lowest and highest values the slider reaches.
+    pub fn range(self) -> (f64, f64) {
+        match self {
+            Setting::TickRate => (0.0, 240.0),
+            Setting::ObservationRadius => (0.0, 200.0),
+            _ => (0.0, 1.0),
+        }
+    }
+
+    /// The setting's current value in `app`.
+    pub fn get(self, app: &AppState) -> f64 {
+        match self {
+            Setting::ObservationRate => app.universe.observation_rate,
+            Setting::DecayRate => app.universe.decay_rate,
+            Setting::FluctuationRate => app.universe.fluctuation_rate,
+            Setting::EntanglementPercentage => app.panel.entanglement,
+            Setting::TickRate => app.timestep.as_ref().map_or(0.0, |t| 1.0 / t.dt),
+            Setting::ObservationRadius => app.config.observation_radius,
+        }
+    }
+
+    /// Sets the value in `app`, clamped to the setting's range. Setting the
+    /// entanglement percentage rewires the grid at it.
+    pub fn set(self, app: &mut AppState, value: f64) {
+        let (min, max) = self.range();
+        let value = value.clamp(min, max);
+        match self {
+            Setting::ObservationRate => app.universe.observation_rate = value,
+            Setting::DecayRate => app.universe.decay_rate = value,
+            Setting::FluctuationRate => app.universe.fluctuation_rate = value,
+            Setting::EntanglementPercentage => {
+                app.panel.entanglement = value;
+                app.history.record(&app.universe);
+                app.universe.rewire_entanglement(value);
+            }
+            Setting::TickRate => {
+                let rate = value.round();
+                app.timestep = (rate > 0.0).then(|| FixedTimestep::new(rate));
+            }
+            Setting::ObservationRadius => app.config.observation_radius = value,
+        }
+    }
+
+    /// Where `value` sits in the setting's range, from 0 to 1.
+    pub fn fraction(self, value: f64) -> f64 {
+        let (min, max) = self.range();
+        ((value - min) / (max - min)).clamp(0.0, 1.0)
+    }
+
+    /// The value at `fraction` of the way along the setting's range.
+    pub fn value_at(self, fraction: f64) -> f64 {
+        let (min, max) = self.range();
+        min + (max - min) * fraction.clamp(0.0, 1.0)
+    }
+
+    /// The value as the panel shows it.
+    pub fn format(self, value: f64) -> String {
+        match self {
+            Setting::TickRate if value < 1.0 => "per frame".to_string(),
+            Setting::TickRate => format!("{:.0}/s", value),
+            Setting::ObservationRadius => format!("{:.0}px", value),
+            Setting::EntanglementPercentage => format!("{:.0}%", value * 100.0),
+            _ => format!("{:.3}", value),
+        }
+    }
+}
+
+/// A button on the control panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PanelButton {
+    Reset,
+    Save,
+    Load,
+}
+
+impl PanelButton {
+    /// Every button, in panel order.
+    pub const ALL: [PanelButton; 3] = [PanelButton::Reset, PanelButton::Save, PanelButton::Load];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            PanelButton::Reset => "Reset",
+            PanelButton::Save => "Save",
+            PanelButton::Load => "Load",
+        }
+    }
+
+    /// What pressing the button does; the same as its key.
+    pub fn action(self) -> AppAction {
+        match self {
+            PanelButton::Reset => AppAction::ResetUniverse,
+            PanelButton::Save => AppAction::SaveSession,
+            PanelButton::Load => AppAction::LoadSession,
+        }
+    }
+}
+
+/// One control on the panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Slider(Setting),
+    Button(PanelButton),
+}
+
+/// The control panel's layout and interaction state; see the
+/// [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ControlPanel {
+    /// Whether the panel is shown and takes clicks.
+    pub open: bool,
+    /// The panel's top-left corner, in screen pixels.
+    pub origin: [f64; 2],
+    /// The slider being dragged, if any.
+    pub dragging: Option<Setting>,
+    /// The entanglement slider's value, which the grid is rewired at when
+    /// the slider is let go.
+    pub entanglement: f64,
+}
+
+impl ControlPanel {
+    pub const WIDTH: f64 = 320.0;
+    pub const ROW_HEIGHT: f64 = 28.0;
+    pub const PADDING: f64 = 12.0;
+    /// Where the slider tracks start, right of their labels.
+    const TRACK_LEFT: f64 = 120.0;
+    const TRACK_WIDTH: f64 = 100.0;
+
+    /// A closed panel in the top-right corner of a window of `window_size`.
+    pub fn new(window_size: [f64; 2], entanglement: f64) -> Self {
+        ControlPanel {
+            open: false,
+            origin: [window_size[0] - Self::WIDTH - 20.0, 20.0],
+            dragging: None,
+            entanglement,
+        }
+    }
+
+    /// The panel's rectangle as `[x, y, width, height]`.
+    pub fn bounds(&self) -> [f64; 4] {
+        let rows = Setting::ALL.len() + 1;
+        let height = rows as f64 * Self::ROW_HEIGHT + Self::PADDING * 2.0;
+        [self.origin[0], self.origin[1], Self::WIDTH, height]
+    }
+
+    /// Every control with the rectangle it responds in: a slider's track, or
+    /// a button's face.
+    pub fn controls(&self) -> Vec<(Control, [f64; 4])> {
+        let [x, y] = [
+            self.origin[0] + Self::PADDING,
+            self.origin[1] + Self::PADDING,
+        ];
+        let row_y = |row: usize| y + row as f64 * Self::ROW_HEIGHT;
+        let sliders = Setting::ALL.iter().enumerate().map(|(row, &setting)| {
+            let rect = [
+                x + Self::TRACK_LEFT,
+                row_y(row) + 6.0,
+                Self::TRACK_WIDTH,
+                Self::ROW_HEIGHT - 12.0,
+            ];
+            (Control::Slider(setting), rect)
+        });
+        let count = PanelButton::ALL.len() as f64;
+        let gap = 8.0;
+        let width = (Self::WIDTH - Self::PADDING * 2.0 - gap * (count - 1.0)) / count;
+        let buttons_y = row_y(Setting::ALL.len()) + 2.0;
+        let buttons = PanelButton::ALL.iter().enumerate().map(|(i, &button)| {
+            let rect = [
+                x + i as f64 * (width + gap),
+                buttons_y,
+                width,
+                Self::ROW_HEIGHT - 4.0,
+            ];
+            (Control::Button(button), rect)
+        });
+        sliders.chain(buttons).collect()
+    }
+
+    /// Whether the panel is open and covers the screen point `pos`.
+    pub fn contains(&self, pos: [f64; 2]) -> bool {
+        self.open && within(self.bounds(), pos)
+    }
+
+    /// The control under the screen point `pos`, if the panel is open.
+    pub fn control_at(&self, pos: [f64; 2]) -> Option<Control> {
+        if !self.open {
+            return None;
+        }
+        self.controls()
+            .into_iter()
+            .find(|&(_, rect)| within(rect, pos))
+            .map(|(control, _)| control)
+    }
+
+    /// The value of `setting` for a slider dragged to the screen x coordinate
+    /// `x`.
+    pub fn slider_value(&self, setting: Setting, x: f64) -> f64 {
+        let left = self.origin[0] + Self::PADDING + Self::TRACK_LEFT;
+        setting.value_at((x - left) / Self::TRACK_WIDTH)
+    }
+}
+
+/// Whether `pos` lies in the rectangle `[x, y, width, height]`.
+fn within([x, y, width, height]: [f64; 4], pos: [f64; 2]) -> bool {
+    (x..=x + width).contains(&pos[0]) && (y..=y + height).contains(&pos[1])
+}
@@ -0,0 +1,250 @@
+//! Patterns: pieces of a universe that can be saved and stamped elsewhere.
+//!
+//! A [`Pattern`] is cut out of a box of the grid with
+//! [`Universe::extract_pattern`]: the offset, consciousness and state of every
+//! cell, plus the entanglement groups among its own cells. Stamping it with
+//! [`Universe::stamp_pattern`] writes those cells back at any position. Like
+//! share codes, patterns are stored as short text codes, so a shared library of
+//! them is just a folder of text files.
+
+use crate::existon::ConsciousnessState;
+use crate::ga_core::Multivector;
+use crate::share::{Reader, ShareError, pack, unpack, write_state, write_varint};
+use crate::universe::Universe;
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+/// Every pattern code starts with this tag, which also versions the format.
+const PREFIX: &str = "EXP1-";
+
+/// Upper bound that keeps a malformed code from allocating an absurd pattern.
+const MAX_CELLS: usize = 1 << 20;
+
+/// One cell of a [`Pattern`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PatternCell {
+    /// The cell's position relative to the pattern's corner, one entry per grid
+    /// dimension.
+    pub offset: Vec<usize>,
+    pub consciousness: ConsciousnessState,
+    pub state: Multivector,
+}
+
+/// A box of cells with the entanglements between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    /// The extent of the box along each grid dimension.
+    pub size: Vec<usize>,
+    /// The GA dimensions of every cell's state.
+    pub ga_dims: usize,
+    /// The cells, which need not fill the whole box.
+    pub cells: Vec<PatternCell>,
+    /// Entanglement groups, each listing positions in `cells`.
+    pub entanglements: Vec<Vec<usize>>,
+}
+
+impl Pattern {
+    /// Cuts the box spanned by `region` out of the universe, clipped to the grid.
+    ///
+    /// Entanglement groups keep only their members inside the box, and are
+    /// dropped if fewer than two are.
+    pub fn extract(universe: &Universe, region: &[Range<usize>]) -> Self {
+        let indices = universe.region_indices(region);
+        let corner: Vec<usize> = match indices.first() {
+            Some(&first) => universe.get_coord_from_index(first),
+            None => vec![0; universe.grid_dims.len()],
+        };
+        let size = match indices.last() {
+            Some(&last) => universe
+                .get_coord_from_index(last)
+                .iter()
+                .zip(&corner)
+                .map(|(c, start)| c - start + 1)
+                .collect(),
+            None => vec![0; universe.grid_dims.len()],
+        };
+        let position: HashMap<u64, usize> = indices
+            .iter()
+            .enumerate()
+            .map(|(pos, &idx)| (universe.grid[idx].id, pos))
+            .collect();
+        let cells = indices
+            .iter()
+            .map(|&idx| {
+                let cell = &universe.grid[idx];
+                PatternCell {
+                    offset: universe
+                        .get_coord_from_index(idx)
+                        .iter()
+                        .zip(&corner)
+                        .map(|(c, start)| c - start)
+                        .collect(),
+                    consciousness: cell.consciousness,
+                    state: cell.state.clone(),
+                }
+            })
+            .collect();
+        let entanglements = universe
+            .entanglement
+            .groups()
+            .map(|members| {
+                let mut inside: Vec<usize> = members
+                    .iter()
+                    .filter_map(|id| position.get(id).copied())
+                    .collect();
+                inside.sort_unstable();
+                inside
+            })
+            .filter(|inside| inside.len() >= 2)
+            .collect();
+        Pattern {
+            size,
+            ga_dims: universe.ga_dims,
+            cells,
+            entanglements,
+        }
+    }
+
+    /// The grid index each cell lands on when the pattern's corner is placed at
+    /// `at`, or `None` for cells that fall into dead space.
+    ///
+    /// Cells past the edge of the grid are mapped through its boundary condition,
+    /// like the brush tools.
+    pub fn placements(&self, universe: &Universe, at: &[usize]) -> Vec<Option<usize>> {
+        let dims = &universe.grid_dims;
+        self.cells
+            .iter()
+            .map(|cell| {
+                if at.len() != dims.len() || cell.offset.len() != dims.len() {
+                    return None;
+                }
+                let coord: Option<Vec<usize>> = (0..dims.len())
+                    .map(|d| {
+                        let c = at[d] as i64 + cell.offset[d] as i64;
+                        universe.boundary.resolve(c, dims[d])
+                    })
+                    .collect();
+                coord.and_then(|coord| universe.get_index_from_coord(&coord))
+            })
+            .collect()
+    }
+
+    /// Appends the pattern's binary encoding to `buf`.
+    pub(crate) fn write(&self, buf: &mut Vec<u8>) {
+        write_varint(buf, self.size.len() as u64);
+        for &extent in &self.size {
+            write_varint(buf, extent as u64);
+        }
+        write_varint(buf, self.ga_dims as u64);
+        write_varint(buf, self.cells.len() as u64);
+        for cell in &self.cells {
+            for &o in &cell.offset {
+                write_varint(buf, o as u64);
+            }
+            buf.push(match cell.consciousness {
+                ConsciousnessState::Potential => 0,
+                ConsciousnessState::Observed => 1,
+                ConsciousnessState::Operator => 2,
+            });
+            write_state(buf, &cell.state);
+        }
+        write_varint(buf, self.entanglements.len() as u64);
+        for group in &self.entanglements {
+            write_varint(buf, group.len() as u64);
+            for &pos in group {
+                write_varint(buf, pos as u64);
+            }
+        }
+    }
+
+    /// Reads a pattern written by [`Pattern::write`].
+    pub(crate) fn read(reader: &mut Reader) -> Result<Self, ShareError> {
+        let n_dims = reader.varint()? as usize;
+        if n_dims == 0 || n_dims > 8 {
+            return Err(ShareError::Invalid("unsupported number of grid dimensions"));
+        }
+        let size = (0..n_dims)
+            .map(|_| Ok(reader.varint()? as usize))
+            .collect::<Result<Vec<usize>, ShareError>>()?;
+        let ga_dims = reader.varint()? as usize;
+        if ga_dims > 12 {
+            return Err(ShareError::Invalid("too many GA dimensions"));
+        }
+        let count = reader.varint()? as usize;
+        if count > MAX_CELLS {
+            return Err(ShareError::Invalid("pattern too large"));
+        }
+        let mut cells = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut offset = Vec::with_capacity(n_dims);
+            for &extent in &size {
+                let o = reader.varint()? as usize;
+                if o >= extent {
+                    return Err(ShareError::Invalid("cell outside the pattern"));
+                }
+                offset.push(o);
+            }
+            let consciousness = match reader.byte()? {
+                0 => ConsciousnessState::Potential,
+                1 => ConsciousnessState::Observed,
+                2 => ConsciousnessState::Operator,
+                _ => return Err(ShareError::Invalid("unknown consciousness state")),
+            };
+            let state = reader.state(ga_dims)?;
+            cells.push(PatternCell {
+                offset,
+                consciousness,
+                state,
+            });
+        }
+        let count = reader.varint()? as usize;
+        let mut entanglements = Vec::with_capacity(count.min(cells.len()));
+        for _ in 0..count {
+            let len = reader.varint()? as usize;
+            if len > cells.len() {
+                return Err(ShareError::Invalid("entanglement group too large"));
+            }
+            let mut group = Vec::with_capacity(len);
+            for _ in 0..len {
+                let pos = reader.varint()? as usize;
+                if pos >= cells.len() {
+                    return Err(ShareError::Invalid("entanglement outside the pattern"));
+                }
+                group.push(pos);
+            }
+            entanglements.push(group);
+        }
+        Ok(Pattern {
+            size,
+            ga_dims,
+            cells,
+            entanglements,
+        })
+    }
+
+    /// Serializes the pattern into a compressed, URL-safe code.
+    pub fn encode(&self) -> String {
+        let mut buf = Vec::new();
+        self.write(&mut buf);
+        pack(PREFIX, &buf)
+    }
+
+    /// Parses a code produced by [`Pattern::encode`].
+    pub fn decode(code: &str) -> Result<Self, ShareError> {
+        let buf = unpack(PREFIX, code)?;
+        Self::read(&mut Reader::new(&buf))
+    }
+
+    /// Writes the pattern's code to a file.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        fs::write(path, format!("{}\n", self.encode()))
+    }
+
+    /// Reads a pattern from a file.
+    pub fn load(path: &Path) -> Result<Self, ShareError> {
+        let text = fs::read_to_string(path).map_err(ShareError::Io)?;
+        Self::decode(&text)
+    }
+}
@@ -0,0 +1,161 @@
+//! The plot strip: how the population has moved over the last thousand ticks.
+//!
+//! Every tick adds a [`Sample`] of a few population metrics to a
+//! [`MetricHistory`], a ring buffer of the most recent ones. The frontend draws
+//! each [`Metric`] as a line along the bottom of the window from
+//! [`MetricHistory::polyline`], so trends such as a sudden change in how many
+//! cells stay observed show up while the rates are being tuned.
+
+use crate::existon::ConsciousnessState;
+use crate::universe::{TickReport, Universe};
+use std::collections::VecDeque;
+
+/// A quantity the plot strip follows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    /// The number of `Observed` cells after the tick.
+    Observed,
+    /// The number of entanglements that fired during the tick.
+    Entanglements,
+    /// Every event of the tick together: observations, decays, fluctuations,
+    /// fired entanglements, operator interactions and moves, and decoherences.
+    Activity,
+}
+
+impl Metric {
+    /// Every metric, in legend order.
+    pub const ALL: [Metric; 3] = [Metric::Observed, Metric::Entanglements, Metric::Activity];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Metric::Observed => "Observed",
+            Metric::Entanglements => "Entanglements",
+            Metric::Activity => "Activity",
+        }
+    }
+
+    /// The color of the metric's line.
+    pub fn color(self) -> [f32; 4] {
+        match self {
+            Metric::Observed => [1.0, 1.0, 0.6, 0.9],
+            Metric::Entanglements => [0.3, 0.9, 1.0, 0.9],
+            Metric::Activity => [0.8, 0.4, 1.0, 0.9],
+        }
+    }
+
+    /// The metric's value in `sample`.
+    pub fn of(self, sample: &Sample) -> usize {
+        match self {
+            Metric::Observed => sample.observed,
+            Metric::Entanglements => sample.entanglements,
+            Metric::Activity => sample.activity,
+        }
+    }
+}
+
+/// The metrics of one tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Sample {
+    pub tick: u64,
+    pub observed: usize,
+    pub entanglements: usize,
+    pub activity: usize,
+}
+
+impl Sample {
+    /// The metrics of the tick that left `universe` as it is and produced
+    /// `report`.
+    pub fn of(universe: &Universe, report: &TickReport) -> Self {
+        let observed = universe
+            .grid
+            .iter()
+            .filter(|existon| existon.consciousness == ConsciousnessState::Observed)
+            .count();
+        let activity = report.observations.len()
+            + report.decays.len()
+            + report.fluctuations.len()
+            + report.entanglements.len()
+            + report.operator_interactions.len()
+            + report.decoherences.len()
+            + report.operator_moves.len();
+        Sample {
+            tick: universe.tick_count,
+            observed,
+            entanglements: report.entanglements.len(),
+            activity,
+        }
+    }
+}
+
+/// The most recent samples, oldest first; see the
+/// [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricHistory {
+    samples: VecDeque<Sample>,
+    capacity: usize,
+}
+
+impl Default for MetricHistory {
+    fn default() -> Self {
+        Self::with_capacity(Self::CAPACITY)
+    }
+}
+
+impl MetricHistory {
+    /// How many ticks the plot strip covers by default.
+    pub const CAPACITY: usize = 1000;
+
+    /// An empty history keeping the last `capacity` samples.
+    pub fn with_capacity(capacity: usize) -> Self {
+        MetricHistory {
+            samples: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Adds a sample, dropping the oldest once the history is full.
+    pub fn record(&mut self, sample: Sample) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(sample);
+    }
+
+    pub fn samples(&self) -> &VecDeque<Sample> {
+        &self.samples
+    }
+
+    pub fn latest(&self) -> Option<&Sample> {
+        self.samples.back()
+    }
+
+    pub fn clear(&mut self) {
+        self.samples.clear();
+    }
+
+    /// The highest value of `metric` in the history, at least 1.
+    pub fn peak(&self, metric: Metric) -> usize {
+        self.samples
+            .iter()
+            .map(|sample| metric.of(sample))
+            .max()
+            .unwrap_or(0)
+            .max(1)
+    }
+
+    /// The points of `metric`'s line in the rectangle `[x, y, width, height]`:
+    /// one per sample, spaced so a full history spans the width, from zero at
+    /// the bottom to the metric's peak at the top.
+    pub fn polyline(&self, metric: Metric, [x, y, width, height]: [f64; 4]) -> Vec<[f64; 2]> {
+        let peak = self.peak(metric) as f64;
+        let step = width / (self.capacity.max(2) - 1) as f64;
+        self.samples
+            .iter()
+            .enumerate()
+            .map(|(i, sample)| {
+                let value = metric.of(sample) as f64 / peak;
+                [x + i as f64 * step, y + height * (1.0 - value)]
+            })
+            .collect()
+    }
+}
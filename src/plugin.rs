@@ -0,0 +1,352 @@
+//! Third-party overlays and per-tick analyses.
+//!
+//! A [`PluginRegistry`] holds named [`Overlay`]s, which draw over the grid, and
+//! [`Analyzer`]s, which watch every tick and summarize what they saw. Both only
+//! see the universe through a [`PluginCtx`], and overlays draw through the
+//! renderer-independent [`Canvas`], so they work with any frontend. The UI lists
+//! registered plugins and toggles them by their position in [`PluginRegistry::list`].
+
+use crate::census::Census;
+use crate::existon::ConsciousnessState;
+use crate::experiment::BellTest;
+use crate::stats::{GradeTotals, UniverseStats};
+use crate::universe::{TickReport, Universe};
+use std::collections::VecDeque;
+
+/// What a plugin may look at.
+pub struct PluginCtx<'a> {
+    pub universe: &'a Universe,
+    /// The events of the most recent tick.
+    pub report: &'a TickReport,
+}
+
+/// Drawing primitives in grid coordinates, implemented by each renderer.
+///
+/// Coordinates beyond the second dimension are ignored by 2D renderers.
+pub trait Canvas {
+    fn fill_cell(&mut self, coord: &[usize], color: [f32; 4]);
+    fn outline_cell(&mut self, coord: &[usize], color: [f32; 4]);
+    /// A line between the centers of two cells.
+    fn line(&mut self, from: &[usize], to: &[usize], color: [f32; 4]);
+}
+
+/// A named layer drawn over the grid.
+pub trait Overlay: Send {
+    fn name(&self) -> &str;
+    fn draw(&self, ctx: &PluginCtx, canvas: &mut dyn Canvas);
+}
+
+/// A named analysis updated after every tick.
+pub trait Analyzer: Send {
+    fn name(&self) -> &str;
+    fn on_tick(&mut self, ctx: &PluginCtx);
+    /// Lines of text describing the analysis so far, for the plugin panel.
+    fn summary(&self) -> Vec<String>;
+}
+
+/// Whether a registered plugin draws or analyzes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PluginKind {
+    Overlay,
+    Analyzer,
+}
+
+/// A registered plugin as listed in the UI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PluginInfo {
+    pub name: String,
+    pub kind: PluginKind,
+    pub enabled: bool,
+}
+
+/// The overlays and analyzers attached to a universe, each with an on/off switch.
+#[derive(Default)]
+pub struct PluginRegistry {
+    overlays: Vec<(Box<dyn Overlay>, bool)>,
+    analyzers: Vec<(Box<dyn Analyzer>, bool)>,
+}
+
+impl PluginRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the built-in plugins, all switched off.
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry
+            .overlays
+            .push((Box::new(OperatorReachOverlay), false));
+        registry
+            .overlays
+            .push((Box::new(LifetimeHeatMap(Lifetime::Age)), false));
+        registry
+            .overlays
+            .push((Box::new(LifetimeHeatMap(Lifetime::ObservedDuration)), false));
+        registry
+            .analyzers
+            .push((Box::new(PopulationAnalyzer::default()), false));
+        registry
+            .analyzers
+            .push((Box::new(AccountingAnalyzer::default()), false));
+        registry
+            .analyzers
+            .push((Box::new(BellTest::default()), false));
+        registry
+            .analyzers
+            .push((Box::new(Census::default()), false));
+        registry
+    }
+
+    /// Adds an overlay, switched on.
+    pub fn register_overlay(&mut self, overlay: impl Overlay + 'static) {
+        self.overlays.push((Box::new(overlay), true));
+    }
+
+    /// Adds an analyzer, switched on.
+    pub fn register_analyzer(&mut self, analyzer: impl Analyzer + 'static) {
+        self.analyzers.push((Box::new(analyzer), true));
+    }
+
+    /// Every registered plugin: overlays first, then analyzers, each in
+    /// registration order.
+    pub fn list(&self) -> Vec<PluginInfo> {
+        let overlays = self.overlays.iter().map(|(o, on)| PluginInfo {
+            name: o.name().to_string(),
+            kind: PluginKind::Overlay,
+            enabled: *on,
+        });
+        let analyzers = self.analyzers.iter().map(|(a, on)| PluginInfo {
+            name: a.name().to_string(),
+            kind: PluginKind::Analyzer,
+            enabled: *on,
+        });
+        overlays.chain(analyzers).collect()
+    }
+
+    /// Switches the plugin at `index` in [`PluginRegistry::list`] on or off,
+    /// returning whether it exists.
+    pub fn toggle(&mut self, index: usize) -> bool {
+        let enabled = if index < self.overlays.len() {
+            &mut self.overlays[index].1
+        } else if let Some((_, on)) = self.analyzers.get_mut(index - self.overlays.len()) {
+            on
+        } else {
+            return false;
+        };
+        *enabled = !*enabled;
+        true
+    }
+
+    /// Feeds a finished tick to every enabled analyzer.
+    pub fn on_tick(&mut self, ctx: &PluginCtx) {
+        for (analyzer, _) in self.analyzers.iter_mut().filter(|(_, on)| *on) {
+            analyzer.on_tick(ctx);
+        }
+    }
+
+    /// Draws every enabled overlay, in registration order.
+    pub fn draw_overlays(&self, ctx: &PluginCtx, canvas: &mut dyn Canvas) {
+        for (overlay, _) in self.overlays.iter().filter(|(_, on)| *on) {
+            overlay.draw(ctx, canvas);
+        }
+    }
+
+    /// The plugin panel: one line per plugin with its toggle key, followed by the
+    /// summaries of the enabled analyzers.
+    pub fn describe(&self) -> Vec<String> {
+        let mut lines = vec!["Plugins (Alt+1 to Alt+9 toggle):".to_string()];
+        for (i, info) in self.list().iter().enumerate() {
+            lines.push(format!(
+                "  [Alt+{}] [{}] {} ({:?})",
+                i + 1,
+                if info.enabled { "x" } else { " " },
+                info.name,
+                info.kind
+            ));
+        }
+        for (analyzer, _) in self.analyzers.iter().filter(|(_, on)| *on) {
+            lines.push(format!("{}:", analyzer.name()));
+            lines.extend(analyzer.summary().into_iter().map(|l| format!("  {}", l)));
+        }
+        lines
+    }
+}
+
+//================================================================================
+// Built-in Plugins
+//================================================================================
+
+/// Outlines every cell an `Operator` influenced during the last tick.
+pub struct OperatorReachOverlay;
+
+impl Overlay for OperatorReachOverlay {
+    fn name(&self) -> &str {
+        "Operator reach"
+    }
+
+    fn draw(&self, ctx: &PluginCtx, canvas: &mut dyn Canvas) {
+        for (_, cell) in &ctx.report.operator_interactions {
+            canvas.outline_cell(cell, [0.0, 1.0, 1.0, 0.6]);
+        }
+    }
+}
+
+/// Which lifetime a [`LifetimeHeatMap`] shows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lifetime {
+    /// [`Existon::age_ticks`](crate::existon::Existon::age_ticks).
+    Age,
+    /// [`Existon::observed_duration`](crate::existon::Existon::observed_duration),
+    /// drawn only over `Observed` cells.
+    ObservedDuration,
+}
+
+/// Fills every cell by its lifetime, from blue for the youngest to red for
+/// the oldest. Nothing is drawn while every cell's lifetime is the same.
+pub struct LifetimeHeatMap(pub Lifetime);
+
+impl Overlay for LifetimeHeatMap {
+    fn name(&self) -> &str {
+        match self.0 {
+            Lifetime::Age => "Age heat map",
+            Lifetime::ObservedDuration => "Observed heat map",
+        }
+    }
+
+    fn draw(&self, ctx: &PluginCtx, canvas: &mut dyn Canvas) {
+        let now = ctx.universe.tick_count;
+        let cells: Vec<(Vec<usize>, u64)> = ctx
+            .universe
+            .iter_cells()
+            .filter_map(|(coord, cell)| match self.0 {
+                Lifetime::Age => Some((coord, cell.age_ticks(now))),
+                Lifetime::ObservedDuration => (cell.consciousness == ConsciousnessState::Observed)
+                    .then(|| (coord, cell.observed_duration(now))),
+            })
+            .collect();
+        let low = match self.0 {
+            Lifetime::Age => cells.iter().map(|&(_, v)| v).min(),
+            Lifetime::ObservedDuration => Some(0),
+        };
+        let high = cells.iter().map(|&(_, v)| v).max();
+        let (Some(low), Some(high)) = (low, high) else {
+            return;
+        };
+        if high == low {
+            return;
+        }
+        for (coord, value) in &cells {
+            let heat = (value - low) as f32 / (high - low) as f32;
+            canvas.fill_cell(coord, [heat, 0.2, 1.0 - heat, 0.55]);
+        }
+    }
+}
+
+/// Tracks population counts and entropy, with the peak entropy seen so far.
+#[derive(Default)]
+pub struct PopulationAnalyzer {
+    latest: Option<UniverseStats>,
+    peak_entropy: f64,
+}
+
+impl Analyzer for PopulationAnalyzer {
+    fn name(&self) -> &str {
+        "Population"
+    }
+
+    fn on_tick(&mut self, ctx: &PluginCtx) {
+        let stats = ctx.universe.stats();
+        self.peak_entropy = self.peak_entropy.max(stats.entropy);
+        self.latest = Some(stats);
+    }
+
+    fn summary(&self) -> Vec<String> {
+        let Some(stats) = &self.latest else {
+            return vec!["waiting for the first tick".to_string()];
+        };
+        vec![
+            format!(
+                "tick {}: {} potential, {} observed, {} operators",
+                stats.tick, stats.potential, stats.observed, stats.operators
+            ),
+            format!(
+                "entropy {:.2} bits (peak {:.2})",
+                stats.entropy, self.peak_entropy
+            ),
+        ]
+    }
+}
+
+/// Tracks the grid's per-grade weight and charge, how the last tick changed
+/// them, and a text plot of the total weight over recent ticks.
+#[derive(Default)]
+pub struct AccountingAnalyzer {
+    latest: Option<GradeTotals>,
+    change: GradeTotals,
+    /// The total weight after each recent tick, oldest first.
+    history: VecDeque<i64>,
+}
+
+impl AccountingAnalyzer {
+    /// The number of ticks the plot covers.
+    pub const WINDOW: usize = 48;
+}
+
+impl Analyzer for AccountingAnalyzer {
+    fn name(&self) -> &str {
+        "Accounting"
+    }
+
+    fn on_tick(&mut self, ctx: &PluginCtx) {
+        let totals = ctx.universe.grade_totals();
+        if self.history.len() == Self::WINDOW {
+            self.history.pop_front();
+        }
+        self.history.push_back(totals.total_weight());
+        self.latest = Some(totals);
+        self.change = ctx.report.totals_change.clone();
+    }
+
+    fn summary(&self) -> Vec<String> {
+        let Some(totals) = &self.latest else {
+            return vec!["waiting for the first tick".to_string()];
+        };
+        let signed = |values: &[i64]| {
+            let values: Vec<String> = values.iter().map(|v| format!("{:+}", v)).collect();
+            values.join(" ")
+        };
+        vec![
+            format!(
+                "weight {} ({:+}), charge {} ({:+})",
+                totals.total_weight(),
+                self.change.total_weight(),
+                totals.total_charge(),
+                self.change.total_charge()
+            ),
+            format!(
+                "by grade: weight {} charge {}",
+                signed(&self.change.weight),
+                signed(&self.change.charge)
+            ),
+            format!("weight |{}|", plot(&self.history)),
+        ]
+    }
+}
+
+/// A one-line plot of `values`, one character per value from low to high.
+fn plot(values: &VecDeque<i64>) -> String {
+    const LEVELS: &[u8] = b"_.-=+*#";
+    let (Some(&low), Some(&high)) = (values.iter().min(), values.iter().max()) else {
+        return String::new();
+    };
+    let span = (high - low).max(1) as f64;
+    values
+        .iter()
+        .map(|&v| {
+            let level = ((v - low) as f64 / span * (LEVELS.len() - 1) as f64).round();
+            LEVELS[level as usize] as char
+        })
+        .collect()
+}
@@ -0,0 +1,222 @@
+//! The profiler overlay: where the time of a frame goes.
+//!
+//! A universe with [`Universe::profiling`] set times every tick it runs into
+//! [`TickTimings`]: the update rule over the grid, with an estimate of how
+//! much of it went on neighbor sums and how much on geometric products, the
+//! entanglement step, and the rest of the tick. A [`Profiler`] collects those
+//! alongside the time each frame spent updating and drawing, and how many
+//! allocations it made when the [`CountingAllocator`] is installed, and
+//! describes the averages for an overlay, toggled with `[F3]`.
+//!
+//! Timing never changes what a tick computes, so a profiled run is the same
+//! run.
+//!
+//! [`Universe::profiling`]: crate::universe::Universe::profiling
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// Where the time of one tick went; see the [module documentation](self).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct TickTimings {
+    /// Running the update rule over the grid, including bringing the cached
+    /// local interactions up to date.
+    pub local: Duration,
+    /// The part of `local` estimated to have gone on summing neighbor states.
+    pub neighbor_sums: Duration,
+    /// The part of `local` estimated to have gone on geometric products.
+    pub products: Duration,
+    /// Finding and propagating collapses through entangled groups.
+    pub entanglement: Duration,
+    /// Waves, lifetimes, decoherence, moving operators and accounting.
+    pub other: Duration,
+}
+
+impl TickTimings {
+    /// The whole tick.
+    pub fn total(&self) -> Duration {
+        self.local + self.entanglement + self.other
+    }
+
+    /// The sum of `timings`, field by field.
+    fn sum<'t>(timings: impl Iterator<Item = &'t TickTimings>) -> TickTimings {
+        timings.fold(TickTimings::default(), |sum, t| TickTimings {
+            local: sum.local + t.local,
+            neighbor_sums: sum.neighbor_sums + t.neighbor_sums,
+            products: sum.products + t.products,
+            entanglement: sum.entanglement + t.entanglement,
+            other: sum.other + t.other,
+        })
+    }
+}
+
+/// Where the time of one frame went.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameTimings {
+    /// Handling input and running the ticks due.
+    pub update: Duration,
+    /// Drawing.
+    pub render: Duration,
+    /// Allocations made over the frame, if they are being counted.
+    pub allocations: Option<u64>,
+}
+
+/// Recent tick and frame timings, averaged for the overlay; see the
+/// [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Profiler {
+    /// Whether the frontend shows the overlay, and the live universe is
+    /// profiled.
+    pub visible: bool,
+    ticks: VecDeque<TickTimings>,
+    frames: VecDeque<FrameTimings>,
+}
+
+impl Profiler {
+    /// How many recent ticks and frames are averaged.
+    pub const WINDOW: usize = 60;
+
+    /// Records a completed tick.
+    pub fn add_tick(&mut self, timings: TickTimings) {
+        if self.ticks.len() == Self::WINDOW {
+            self.ticks.pop_front();
+        }
+        self.ticks.push_back(timings);
+    }
+
+    /// Records a drawn frame.
+    pub fn add_frame(&mut self, timings: FrameTimings) {
+        if self.frames.len() == Self::WINDOW {
+            self.frames.pop_front();
+        }
+        self.frames.push_back(timings);
+    }
+
+    /// Forgets every recorded tick and frame.
+    pub fn clear(&mut self) {
+        self.ticks.clear();
+        self.frames.clear();
+    }
+
+    /// The average of the recent ticks, if any were recorded.
+    pub fn tick_average(&self) -> Option<TickTimings> {
+        let n = self.ticks.len() as u32;
+        (n > 0).then(|| {
+            let sum = TickTimings::sum(self.ticks.iter());
+            TickTimings {
+                local: sum.local / n,
+                neighbor_sums: sum.neighbor_sums / n,
+                products: sum.products / n,
+                entanglement: sum.entanglement / n,
+                other: sum.other / n,
+            }
+        })
+    }
+
+    /// The average of the recent frames, if any were recorded. Allocations
+    /// are averaged only if every frame counted them.
+    pub fn frame_average(&self) -> Option<FrameTimings> {
+        let n = self.frames.len() as u32;
+        (n > 0).then(|| FrameTimings {
+            update: self.frames.iter().map(|f| f.update).sum::<Duration>() / n,
+            render: self.frames.iter().map(|f| f.render).sum::<Duration>() / n,
+            allocations: self
+                .frames
+                .iter()
+                .map(|f| f.allocations)
+                .sum::<Option<u64>>()
+                .map(|total| total / n as u64),
+        })
+    }
+
+    /// Whether recent frames spent longer updating the simulation than
+    /// drawing it, if any were recorded.
+    pub fn simulation_bound(&self) -> Option<bool> {
+        self.frame_average()
+            .map(|frame| frame.update > frame.render)
+    }
+
+    /// The overlay's lines of text.
+    pub fn describe(&self) -> Vec<String> {
+        let ms = |d: Duration| d.as_secs_f64() * 1000.0;
+        let mut lines = vec!["Profiler".to_string()];
+        match self.frame_average() {
+            Some(frame) => {
+                lines.push(format!(
+                    "Frame: update {:.2} ms, render {:.2} ms",
+                    ms(frame.update),
+                    ms(frame.render)
+                ));
+                if let Some(allocations) = frame.allocations {
+                    lines.push(format!("Allocations: {} per frame", allocations));
+                }
+            }
+            None => lines.push("Frame: no frames yet".to_string()),
+        }
+        match self.tick_average() {
+            Some(tick) => {
+                lines.push(format!("Tick: {:.2} ms", ms(tick.total())));
+                lines.push(format!(
+                    "  Local rule {:.2} ms (sums ~{:.2}, products ~{:.2})",
+                    ms(tick.local),
+                    ms(tick.neighbor_sums),
+                    ms(tick.products)
+                ));
+                lines.push(format!("  Entanglement {:.2} ms", ms(tick.entanglement)));
+                lines.push(format!("  Other {:.2} ms", ms(tick.other)));
+            }
+            None => lines.push("Tick: no ticks yet".to_string()),
+        }
+        match self.simulation_bound() {
+            Some(true) => lines.push("Simulation-bound".to_string()),
+            Some(false) => lines.push("Render-bound".to_string()),
+            None => {}
+        }
+        lines
+    }
+}
+
+/// The system allocator, counting the allocations made through it.
+///
+/// Install it as the `#[global_allocator]` to give the profiler allocation
+/// counts.
+#[derive(Debug, Default)]
+pub struct CountingAllocator {
+    allocations: AtomicU64,
+}
+
+impl CountingAllocator {
+    pub const fn new() -> Self {
+        CountingAllocator {
+            allocations: AtomicU64::new(0),
+        }
+    }
+
+    /// Allocations and reallocations made so far.
+    pub fn allocations(&self) -> u64 {
+        self.allocations.load(Ordering::Relaxed)
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc_zeroed(layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.realloc(ptr, layout, new_size) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
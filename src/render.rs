@@ -0,0 +1,83 @@
+//! Drawing the grid in one go: the viewed plane as an image, a pixel a cell.
+//!
+//! Filling a rectangle per cell costs a draw call per cell per frame, which
+//! stops keeping up long before a 500x500 grid. A [`GridImage`] holds the
+//! colors of the whole plane as an RGBA image with one pixel per cell instead.
+//! The frontend uploads it to a texture and draws it as a single quad scaled up
+//! by the cell size, with nearest-neighbour filtering so cells keep their hard
+//! edges. [`GridImage::update`] reports whether any pixel changed, so a paused
+//! grid is not uploaded again. [`GridImage::update_sampled`] keeps only every
+//! few cells, for a small overview such as the minimap.
+
+use crate::slice::Slice;
+use crate::universe::Universe;
+use image::{Rgba, RgbaImage};
+
+/// The colors of the viewed plane; see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GridImage {
+    image: RgbaImage,
+}
+
+impl GridImage {
+    /// The plane as last updated, one pixel per cell, column `x` and row `y`
+    /// of the plane at pixel `(x, y)`.
+    pub fn image(&self) -> &RgbaImage {
+        &self.image
+    }
+
+    /// Recolors the image from the plane `slice` of `universe`, with `color`
+    /// giving the color of the cell at each grid index, and returns whether any
+    /// pixel or the image's size changed.
+    pub fn update(
+        &mut self,
+        universe: &Universe,
+        slice: &Slice,
+        color: impl Fn(usize) -> [f32; 4],
+    ) -> bool {
+        self.update_sampled(universe, slice, 1, color)
+    }
+
+    /// Like [`GridImage::update`], but with a pixel for only every `step`th
+    /// cell along each axis, the one at the pixel's top-left corner.
+    pub fn update_sampled(
+        &mut self,
+        universe: &Universe,
+        slice: &Slice,
+        step: usize,
+        color: impl Fn(usize) -> [f32; 4],
+    ) -> bool {
+        let step = step.max(1);
+        let dims = universe.grid_dims.len();
+        let [across, down] = slice.axes;
+        let length = |axis: usize| universe.grid_dims.get(axis).copied().unwrap_or(1);
+        let width = length(across).div_ceil(step) as u32;
+        let height = if dims > 1 {
+            length(down).div_ceil(step) as u32
+        } else {
+            1
+        };
+        let mut changed = false;
+        if self.image.dimensions() != (width, height) {
+            self.image = RgbaImage::new(width, height);
+            changed = true;
+        }
+        for y in 0..height {
+            for x in 0..width {
+                let coord = slice.coord(dims, x as usize * step, y as usize * step);
+                let pixel = match universe.get_index_from_coord(&coord) {
+                    Some(idx) => {
+                        Rgba(color(idx).map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8))
+                    }
+                    None => Rgba([0, 0, 0, 0]),
+                };
+                let old = self.image.get_pixel_mut(x, y);
+                if *old != pixel {
+                    *old = pixel;
+                    changed = true;
+                }
+            }
+        }
+        changed
+    }
+}
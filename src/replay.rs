@@ -0,0 +1,496 @@
+//! Recording a run and playing it back.
+//!
+//! A [`Recorder`] follows the live universe and produces a [`Replay`]: the
+//! run's [`Scenario`] plus, if asked, full snapshots of the grid at intervals.
+//! Replays are small text files meant to be shared instead of screen captures.
+//! A [`Playback`] shows a replay one tick at a time and can seek in both
+//! directions.
+//!
+//! An input-only replay re-simulates the run from its seed and intervention log,
+//! so it is tiny but, like share codes, only reproduces runs of the default
+//! update rule. Snapshot replays store what was on screen and play back anything,
+//! at the cost of size and of only showing the recorded ticks.
+//!
+//! Every replay also notes the universe's [`Universe::state_hash`] at intervals.
+//! [`Replay::verify`] re-simulates the run from its seed and intervention log
+//! and checks each of them, which turns any recording into a golden run: a
+//! change to the tick that alters what it computes shows up as a mismatch.
+//! `existons verify-replay FILE` does this from the command line.
+
+use crate::existon::{ConsciousnessState, Existon};
+use crate::ga_core::{Mod3, Multivector};
+use crate::share::{Reader, Scenario, ShareError, pack, unpack, write_grid_dims, write_varint};
+use crate::universe::{Checkpoint, Universe};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Every recording code starts with this tag, which also versions the format.
+const PREFIX: &str = "EXR1-";
+
+/// Every state hash code starts with this tag, which also versions the format.
+const HASH_PREFIX: &str = "EXH1-";
+
+/// How often an input-only playback keeps a checkpoint, so seeking backwards
+/// re-simulates at most this many ticks.
+const KEYFRAME_INTERVAL: u64 = 50;
+
+/// How often a recorder notes the state hash, in ticks.
+const HASH_INTERVAL: u64 = 50;
+
+/// What a [`Recorder`] captures besides the seed and intervention log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordMode {
+    /// Nothing: playback re-simulates the run.
+    Inputs,
+    /// The whole grid, every `interval` ticks.
+    Snapshots { interval: u64 },
+}
+
+/// The cells of the grid at one tick.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    pub tick: u64,
+    /// The grid's dimensions at that tick, which a resize may have changed.
+    pub grid_dims: Vec<usize>,
+    pub grid: Vec<Existon>,
+}
+
+/// The universe's [`Universe::state_hash`] at one tick of a recording.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateHash {
+    pub tick: u64,
+    /// How many interventions had been logged when it was taken, since more
+    /// may be made at the same tick afterwards.
+    pub interventions: usize,
+    pub hash: u64,
+}
+
+impl StateHash {
+    /// The hash of `universe` as it is now.
+    fn of(universe: &Universe) -> Self {
+        StateHash {
+            tick: universe.tick_count,
+            interventions: universe.interventions.len(),
+            hash: universe.state_hash(),
+        }
+    }
+}
+
+/// A recorded state hash that re-simulating the run did not reproduce.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HashMismatch {
+    pub tick: u64,
+    pub expected: u64,
+    pub found: u64,
+}
+
+impl fmt::Display for HashMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "tick {}: expected state hash {:016x}, found {:016x}",
+            self.tick, self.expected, self.found
+        )
+    }
+}
+
+//================================================================================
+// Replay
+//================================================================================
+
+/// A recorded stretch of a run, from tick `start` to `scenario.ticks`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    /// The configuration, seed and intervention log of the whole run.
+    pub scenario: Scenario,
+    /// The tick the recording began at.
+    pub start: u64,
+    /// Snapshots in tick order; empty for an input-only replay.
+    pub frames: Vec<Frame>,
+    /// State hashes in tick order; empty for recordings made before they were
+    /// kept.
+    pub hashes: Vec<StateHash>,
+}
+
+impl Replay {
+    /// The tick the recording ended at.
+    pub fn end(&self) -> u64 {
+        self.scenario.ticks
+    }
+
+    /// The replay as text: the scenario code, the recording code and, if
+    /// there are any, the state hashes on separate lines.
+    pub fn to_text(&self) -> String {
+        let mut buf = Vec::new();
+        write_varint(&mut buf, self.start);
+        write_varint(&mut buf, self.frames.len() as u64);
+        let mut last_tick = self.start;
+        for frame in &self.frames {
+            write_varint(&mut buf, frame.tick - last_tick);
+            last_tick = frame.tick;
+            write_grid_dims(&mut buf, &frame.grid_dims);
+            for existon in &frame.grid {
+                buf.push(match existon.consciousness {
+                    ConsciousnessState::Potential => 0,
+                    ConsciousnessState::Observed => 1,
+                    ConsciousnessState::Operator => 2,
+                });
+                // Shifted to 0..=2 so every coefficient is one byte.
+                buf.extend(existon.state.coefficients.iter().map(|c| (c.0 + 1) as u8));
+                write_varint(&mut buf, existon.born);
+                write_varint(
+                    &mut buf,
+                    existon.observed_since.map_or(0, |since| since + 1),
+                );
+            }
+        }
+        let mut text = format!("{}\n{}\n", self.scenario.encode(), pack(PREFIX, &buf));
+        if !self.hashes.is_empty() {
+            let mut buf = Vec::new();
+            write_varint(&mut buf, self.hashes.len() as u64);
+            let (mut last_tick, mut last_count) = (self.start, 0);
+            for hash in &self.hashes {
+                write_varint(&mut buf, hash.tick - last_tick);
+                write_varint(&mut buf, (hash.interventions - last_count) as u64);
+                buf.extend_from_slice(&hash.hash.to_le_bytes());
+                (last_tick, last_count) = (hash.tick, hash.interventions);
+            }
+            text.push_str(&pack(HASH_PREFIX, &buf));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Parses text produced by [`Replay::to_text`].
+    pub fn from_text(text: &str) -> Result<Self, ShareError> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let scenario = Scenario::decode(lines.next().ok_or(ShareError::Truncated)?)?;
+        let buf = unpack(PREFIX, lines.next().ok_or(ShareError::Truncated)?)?;
+        let mut reader = Reader::new(&buf);
+        let start = reader.varint()?;
+        if start > scenario.ticks {
+            return Err(ShareError::Invalid("recording starts after it ends"));
+        }
+        let cells: usize = scenario.grid_dims.iter().product();
+        let coefficients = 1 << scenario.ga_dims;
+        let count = reader.varint()? as usize;
+        let mut frames = Vec::with_capacity(count.min(buf.len() / cells));
+        let mut tick = start;
+        for _ in 0..count {
+            tick = tick
+                .checked_add(reader.varint()?)
+                .filter(|&t| t <= scenario.ticks)
+                .ok_or(ShareError::Invalid("frame outside the recording"))?;
+            let grid_dims = reader.grid_dims()?;
+            let cells: usize = grid_dims.iter().product();
+            let mut grid = Vec::with_capacity(cells);
+            for id in 0..cells as u64 {
+                let consciousness = match reader.byte()? {
+                    0 => ConsciousnessState::Potential,
+                    1 => ConsciousnessState::Observed,
+                    2 => ConsciousnessState::Operator,
+                    _ => return Err(ShareError::Invalid("unknown consciousness state")),
+                };
+                let mut state = Multivector::zero(scenario.ga_dims);
+                for c in state.coefficients.iter_mut().take(coefficients) {
+                    *c = match reader.byte()? {
+                        b @ 0..=2 => Mod3(b as i8 - 1),
+                        _ => return Err(ShareError::Invalid("coefficient out of range")),
+                    };
+                }
+                grid.push(Existon {
+                    id,
+                    consciousness,
+                    state,
+                    velocity: None,
+                    born: reader.varint()?,
+                    observed_since: reader.varint()?.checked_sub(1),
+                });
+            }
+            frames.push(Frame {
+                tick,
+                grid_dims,
+                grid,
+            });
+        }
+        let hashes = match lines.next() {
+            Some(line) => Self::hashes_from_code(line, &scenario, start)?,
+            None => Vec::new(),
+        };
+        Ok(Replay {
+            scenario,
+            start,
+            frames,
+            hashes,
+        })
+    }
+
+    /// Parses the state hash line of a replay of `scenario` starting at `start`.
+    fn hashes_from_code(
+        code: &str,
+        scenario: &Scenario,
+        start: u64,
+    ) -> Result<Vec<StateHash>, ShareError> {
+        let buf = unpack(HASH_PREFIX, code)?;
+        let mut reader = Reader::new(&buf);
+        let count = reader.varint()? as usize;
+        let mut hashes = Vec::with_capacity(count.min(buf.len() / 10));
+        let (mut tick, mut interventions) = (start, 0usize);
+        for _ in 0..count {
+            tick = tick
+                .checked_add(reader.varint()?)
+                .filter(|&t| t <= scenario.ticks)
+                .ok_or(ShareError::Invalid("state hash outside the recording"))?;
+            interventions = usize::try_from(reader.varint()?)
+                .ok()
+                .and_then(|n| interventions.checked_add(n))
+                .filter(|&n| n <= scenario.interventions.len())
+                .ok_or(ShareError::Invalid("state hash after the intervention log"))?;
+            hashes.push(StateHash {
+                tick,
+                interventions,
+                hash: reader.u64()?,
+            });
+        }
+        Ok(hashes)
+    }
+
+    /// Re-simulates the run from its seed and intervention log, checking every
+    /// recorded state hash, and returns how many were checked or the first
+    /// that did not match.
+    ///
+    /// Like playback, this only reproduces runs of the default update rule.
+    pub fn verify(&self) -> Result<usize, HashMismatch> {
+        let log = &self.scenario.interventions;
+        let mut universe = Scenario {
+            ticks: 0,
+            interventions: Vec::new(),
+            ..self.scenario.clone()
+        }
+        .build();
+        for expected in &self.hashes {
+            universe.replay_until(&log[..expected.interventions], expected.tick);
+            let found = universe.state_hash();
+            if found != expected.hash {
+                return Err(HashMismatch {
+                    tick: expected.tick,
+                    expected: expected.hash,
+                    found,
+                });
+            }
+        }
+        Ok(self.hashes.len())
+    }
+
+    /// Writes the replay to a file.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    /// Reads a replay from a file.
+    pub fn load(path: &Path) -> Result<Self, ShareError> {
+        let text = fs::read_to_string(path).map_err(ShareError::Io)?;
+        Self::from_text(&text)
+    }
+}
+
+//================================================================================
+// Recorder
+//================================================================================
+
+/// Follows a live universe from the tick recording began.
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    mode: RecordMode,
+    start: u64,
+    frames: Vec<Frame>,
+    hashes: Vec<StateHash>,
+}
+
+impl Recorder {
+    /// Starts recording `universe` from its current tick.
+    pub fn start(universe: &Universe, mode: RecordMode) -> Self {
+        let mut recorder = Recorder {
+            mode,
+            start: universe.tick_count,
+            frames: Vec::new(),
+            hashes: Vec::new(),
+        };
+        recorder.on_tick(universe);
+        recorder
+    }
+
+    /// The tick recording began at.
+    pub fn start_tick(&self) -> u64 {
+        self.start
+    }
+
+    /// Takes a snapshot and notes the state hash if either is due; call after
+    /// every completed tick.
+    pub fn on_tick(&mut self, universe: &Universe) {
+        let tick = universe.tick_count;
+        // An undo can rewind past earlier snapshots and hashes; those no
+        // longer happened.
+        let logged = universe.interventions.len();
+        self.hashes
+            .retain(|hash| hash.tick < tick && hash.interventions <= logged);
+        if tick >= self.start && (tick - self.start).is_multiple_of(HASH_INTERVAL) {
+            self.hashes.push(StateHash::of(universe));
+        }
+        let RecordMode::Snapshots { interval } = self.mode else {
+            return;
+        };
+        self.frames.retain(|frame| frame.tick < tick);
+        if tick >= self.start && (tick - self.start).is_multiple_of(interval.max(1)) {
+            self.frames.push(Frame {
+                tick,
+                grid_dims: universe.grid_dims.clone(),
+                grid: universe.grid.clone(),
+            });
+        }
+    }
+
+    /// Ends the recording at the universe's current tick.
+    pub fn finish(mut self, universe: &Universe) -> Replay {
+        let end = universe.tick_count;
+        if let Some(last) = self.frames.last()
+            && last.tick < end
+            && matches!(self.mode, RecordMode::Snapshots { .. })
+        {
+            // The last tick is always shown, however the interval falls.
+            self.frames.push(Frame {
+                tick: end,
+                grid_dims: universe.grid_dims.clone(),
+                grid: universe.grid.clone(),
+            });
+        }
+        // The end is always checked, and replaces any hash an undo left behind.
+        let logged = universe.interventions.len();
+        self.hashes
+            .retain(|hash| hash.tick < end && hash.interventions <= logged);
+        self.hashes.push(StateHash::of(universe));
+        Replay {
+            scenario: Scenario::capture(universe),
+            start: self.start.min(end),
+            frames: self.frames,
+            hashes: self.hashes,
+        }
+    }
+}
+
+//================================================================================
+// Playback
+//================================================================================
+
+/// A replay being watched, positioned at one of its ticks.
+#[derive(Debug, Clone)]
+pub struct Playback {
+    replay: Replay,
+    /// The universe as it was at the current position.
+    universe: Universe,
+    /// Checkpoints of input-only playback, by tick.
+    keyframes: BTreeMap<u64, Checkpoint>,
+}
+
+impl Playback {
+    /// Opens a replay at its first tick.
+    pub fn new(replay: Replay) -> Self {
+        let start = Scenario {
+            ticks: if replay.frames.is_empty() {
+                replay.start
+            } else {
+                0
+            },
+            ..replay.scenario.clone()
+        };
+        let mut playback = Playback {
+            universe: start.build(),
+            replay,
+            keyframes: BTreeMap::new(),
+        };
+        if playback.replay.frames.is_empty() {
+            let universe = &playback.universe;
+            playback
+                .keyframes
+                .insert(universe.tick_count, universe.checkpoint());
+        } else {
+            playback.seek(playback.replay.start);
+        }
+        playback
+    }
+
+    /// The recording being played.
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// The universe at the current position.
+    pub fn universe(&self) -> &Universe {
+        &self.universe
+    }
+
+    /// The tick being shown.
+    pub fn position(&self) -> u64 {
+        self.universe.tick_count
+    }
+
+    /// Whether the last recorded tick is being shown.
+    pub fn at_end(&self) -> bool {
+        self.position() >= self.replay.end()
+    }
+
+    /// Moves to `tick`, clamped to the recording.
+    ///
+    /// A snapshot replay shows the last snapshot at or before `tick`.
+    pub fn seek(&mut self, tick: u64) {
+        let tick = tick.clamp(self.replay.start, self.replay.end());
+        if !self.replay.frames.is_empty() {
+            let i = self.replay.frames.partition_point(|f| f.tick <= tick);
+            let frame = &self.replay.frames[i.saturating_sub(1)];
+            self.universe.grid_dims.clone_from(&frame.grid_dims);
+            self.universe.grid.clone_from(&frame.grid);
+            self.universe.tick_count = frame.tick;
+            return;
+        }
+        if tick < self.universe.tick_count {
+            let (_, keyframe) = self
+                .keyframes
+                .range(..=tick)
+                .next_back()
+                .expect("the first tick is always a keyframe");
+            self.universe.restore(keyframe.clone());
+        }
+        let log = &self.replay.scenario.interventions;
+        while self.universe.tick_count < tick {
+            let next = self.universe.tick_count + 1;
+            self.universe.replay_until(log, next);
+            if next.is_multiple_of(KEYFRAME_INTERVAL) {
+                self.keyframes
+                    .entry(next)
+                    .or_insert_with(|| self.universe.checkpoint());
+            }
+        }
+        self.universe.replay_until(log, tick);
+    }
+
+    /// Moves one step forward (`true`) or back: one tick, or one snapshot.
+    pub fn step(&mut self, forward: bool) {
+        let position = self.position();
+        let target = if self.replay.frames.is_empty() {
+            if forward {
+                position + 1
+            } else {
+                position.saturating_sub(1)
+            }
+        } else if forward {
+            let i = self.replay.frames.partition_point(|f| f.tick <= position);
+            self.replay.frames.get(i).map_or(position, |f| f.tick)
+        } else {
+            let i = self.replay.frames.partition_point(|f| f.tick < position);
+            self.replay.frames[i.saturating_sub(1)].tick
+        };
+        self.seek(target);
+    }
+}
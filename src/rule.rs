@@ -0,0 +1,338 @@
+//! The local update rule: how one Existon evolves from its neighborhood.
+//!
+//! A [`Universe`] hands every cell, its neighbors and a [`RuleCtx`] to its
+//! [`UpdateRule`] once per tick. [`MatzkeDefaultRule`] is the rule the automaton
+//! has always used; alternatives can be swapped in with
+//! [`Universe::with_rule`] without touching the universe itself, or registered
+//! by name in a [`RuleRegistry`] to be picked when the application starts.
+
+use crate::existon::{CollapsePolicy, ConsciousnessState, Existon};
+use crate::field::{ParameterField, Rate};
+use crate::ga_core::Multivector;
+use crate::universe::{CellRng, RandomScheme, Universe};
+use rand::{Rng, RngCore};
+use std::cell::{Cell, RefCell};
+use std::fmt;
+use std::sync::Arc;
+
+/// Computes the next state of a single cell.
+pub trait UpdateRule: fmt::Debug + Send + Sync {
+    /// The next generation of `cell`, given the current states of its `neighbors`.
+    ///
+    /// The result must keep `cell`'s ID, which is how entanglement finds it.
+    fn apply(&self, cell: &Existon, neighbors: &[&Existon], ctx: &RuleCtx) -> Existon;
+
+    /// Whether `apply` never draws randomness from `ctx`.
+    ///
+    /// Stochastic rules are evaluated one cell at a time in index order, so every
+    /// run with the same seed draws the same numbers. Deterministic rules, and
+    /// every rule under [`RandomScheme::PerCell`], are instead spread over
+    /// threads according to the universe's tuning.
+    fn is_deterministic(&self) -> bool {
+        false
+    }
+
+    /// Whether `apply` builds on [`RuleCtx::local_interaction`].
+    ///
+    /// A universe running such a rule keeps every cell's interaction between
+    /// ticks and recomputes only those whose neighborhood changed, which makes
+    /// settled regions nearly free.
+    fn uses_local_interaction(&self) -> bool {
+        false
+    }
+}
+
+/// Everything an [`UpdateRule`] may consult besides the cells themselves.
+pub struct RuleCtx<'a> {
+    pub ga_dims: usize,
+    pub observation_rate: f64,
+    pub decay_rate: f64,
+    pub fluctuation_rate: f64,
+    /// How observed cells simplify; pass it to [`Existon::observe`].
+    pub collapse_policy: CollapsePolicy,
+    /// The tick being computed, counting from zero.
+    pub tick: u64,
+    /// Per-cell overrides of the rates above, by [`Rate::index`].
+    fields: [Option<ParameterField>; 3],
+    rng: Option<RefCell<&'a mut dyn RngCore>>,
+    /// The seed of every cell's own stream under [`RandomScheme::PerCell`], and
+    /// the stream of the cell being updated.
+    cell_rng: Option<(u64, RefCell<CellRng>)>,
+    /// Every cell's local interaction, when it was computed ahead of time.
+    interactions: Option<&'a [Multivector]>,
+    /// IDs passed to [`RuleCtx::report_fluctuation`], in call order.
+    fluctuations: RefCell<Vec<u64>>,
+    /// The grid index of the cell being updated.
+    position: Cell<usize>,
+}
+
+impl RuleCtx<'_> {
+    /// A context with the universe's parameters. Under [`RandomScheme::PerCell`]
+    /// it draws from each cell's own stream; otherwise it has no randomness.
+    pub fn new(universe: &Universe) -> Self {
+        let seed = universe.seed;
+        RuleCtx {
+            fields: Rate::ALL.map(|rate| universe.parameter_field(rate).cloned()),
+            cell_rng: (universe.random_scheme() == RandomScheme::PerCell).then(|| {
+                (
+                    seed,
+                    RefCell::new(CellRng::new(seed, universe.tick_count, 0)),
+                )
+            }),
+            ..Self::with_rates(
+                universe.ga_dims,
+                [
+                    universe.observation_rate,
+                    universe.decay_rate,
+                    universe.fluctuation_rate,
+                ],
+                universe.collapse_policy,
+                universe.tick_count,
+            )
+        }
+    }
+
+    /// A context with the given observation, decay and fluctuation rates, no
+    /// parameter fields and no randomness.
+    pub(crate) fn with_rates(
+        ga_dims: usize,
+        [observation_rate, decay_rate, fluctuation_rate]: [f64; 3],
+        collapse_policy: CollapsePolicy,
+        tick: u64,
+    ) -> Self {
+        RuleCtx {
+            ga_dims,
+            observation_rate,
+            decay_rate,
+            fluctuation_rate,
+            collapse_policy,
+            tick,
+            fields: [None, None, None],
+            rng: None,
+            cell_rng: None,
+            interactions: None,
+            fluctuations: RefCell::default(),
+            position: Cell::new(0),
+        }
+    }
+}
+
+impl<'a> RuleCtx<'a> {
+    /// The same context, drawing randomness from `rng`.
+    pub fn with_rng<'b>(self, rng: &'b mut dyn RngCore) -> RuleCtx<'b>
+    where
+        'a: 'b,
+    {
+        RuleCtx {
+            ga_dims: self.ga_dims,
+            observation_rate: self.observation_rate,
+            decay_rate: self.decay_rate,
+            fluctuation_rate: self.fluctuation_rate,
+            collapse_policy: self.collapse_policy,
+            tick: self.tick,
+            fields: self.fields,
+            rng: Some(RefCell::new(rng)),
+            cell_rng: self.cell_rng,
+            interactions: self.interactions,
+            fluctuations: self.fluctuations,
+            position: self.position,
+        }
+    }
+
+    /// The same context computing tick `tick` and drawing from each cell's own
+    /// stream whatever the universe's [`RandomScheme`], so every cell's result
+    /// is a pure function of the grid and the tick.
+    pub(crate) fn keyed(mut self, seed: u64, tick: u64) -> Self {
+        self.tick = tick;
+        self.cell_rng = Some((seed, RefCell::new(CellRng::new(seed, tick, 0))));
+        self
+    }
+
+    /// The same context, answering [`RuleCtx::local_interaction`] from `interactions`,
+    /// which holds one product per cell, by grid index.
+    pub(crate) fn with_interactions(mut self, interactions: &'a [Multivector]) -> Self {
+        self.interactions = Some(interactions);
+        self
+    }
+
+    /// The sum of the `neighbors`' states multiplied by `cell`'s state: the local
+    /// interaction at the heart of [`MatzkeDefaultRule`].
+    ///
+    /// When the universe computed every cell's interaction ahead of time (on a
+    /// GPU, say), the precomputed product is returned instead, so rules that
+    /// build on this method are accelerated for free.
+    pub fn local_interaction(&self, cell: &Existon, neighbors: &[&Existon]) -> Multivector {
+        if let Some(interactions) = self.interactions {
+            return interactions[self.position()].clone();
+        }
+        interaction(self.ga_dims, cell, neighbors.iter().copied())
+    }
+
+    /// The grid index of the cell being updated.
+    ///
+    /// Cells keep their IDs when they move, so this is the only reliable way for
+    /// a rule to tell where a cell is.
+    pub fn position(&self) -> usize {
+        self.position.get()
+    }
+
+    /// Moves the context on to the cell `id` at grid index `idx`.
+    pub(crate) fn visit(&self, idx: usize, id: u64) {
+        self.position.set(idx);
+        if let Some((seed, rng)) = &self.cell_rng {
+            *rng.borrow_mut() = CellRng::new(*seed, self.tick, id);
+        }
+    }
+
+    /// The rate that applies to the cell being updated: its value in the
+    /// universe's [`ParameterField`] for `rate`, or the global rate if there is none.
+    pub fn rate_at(&self, rate: Rate) -> f64 {
+        match &self.fields[rate.index()] {
+            Some(field) => field.get(self.position()),
+            None => match rate {
+                Rate::Observation => self.observation_rate,
+                Rate::Decay => self.decay_rate,
+                Rate::Fluctuation => self.fluctuation_rate,
+            },
+        }
+    }
+
+    /// Runs `f` with the context's RNG: the stream of the cell being updated under
+    /// [`RandomScheme::PerCell`], or else the generator given to [`RuleCtx::with_rng`].
+    ///
+    /// # Panics
+    /// If the context has no RNG, which means a rule that claims to be
+    /// deterministic tried to draw a random number.
+    pub fn with_random<T>(&self, f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+        if let Some((_, rng)) = &self.cell_rng {
+            return f(&mut *rng.borrow_mut());
+        }
+        let rng = self
+            .rng
+            .as_ref()
+            .expect("a deterministic update rule drew a random number");
+        f(&mut **rng.borrow_mut())
+    }
+
+    /// Returns `true` with probability `p`.
+    pub fn random_bool(&self, p: f64) -> bool {
+        self.with_random(|rng| rng.random_bool(p))
+    }
+
+    /// Records that the cell `id` spontaneously re-randomized this tick.
+    ///
+    /// Observations and decays show up as consciousness changes, so the universe
+    /// reports those itself; a fluctuation is only visible to the rule.
+    pub fn report_fluctuation(&self, id: u64) {
+        self.fluctuations.borrow_mut().push(id);
+    }
+
+    /// The fluctuations reported so far.
+    pub(crate) fn into_fluctuations(self) -> Vec<u64> {
+        self.fluctuations.into_inner()
+    }
+}
+
+/// The sum of the `neighbors`' states multiplied by `cell`'s state; see
+/// [`RuleCtx::local_interaction`].
+pub(crate) fn interaction<'n>(
+    ga_dims: usize,
+    cell: &Existon,
+    neighbors: impl Iterator<Item = &'n Existon>,
+) -> Multivector {
+    let mut operator = Multivector::zero(ga_dims);
+    for neighbor in neighbors {
+        operator += &neighbor.state;
+    }
+    &operator * &cell.state
+}
+
+/// The original Existon rule.
+///
+/// A cell's next state is its state multiplied by the sum of its neighbors'
+/// states. `Potential` cells are then spontaneously observed or re-randomized
+/// (fluctuation), and `Observed` cells may decay back to `Potential`, each at the
+/// cell's own rate (see [`RuleCtx::rate_at`]).
+/// `Operator` cells never change.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MatzkeDefaultRule;
+
+impl UpdateRule for MatzkeDefaultRule {
+    fn uses_local_interaction(&self) -> bool {
+        true
+    }
+
+    fn apply(&self, cell: &Existon, neighbors: &[&Existon], ctx: &RuleCtx) -> Existon {
+        if cell.consciousness == ConsciousnessState::Operator {
+            return cell.clone();
+        }
+        let mut next = Existon {
+            id: cell.id,
+            consciousness: cell.consciousness,
+            state: ctx.local_interaction(cell, neighbors),
+            velocity: None,
+            born: cell.born,
+            observed_since: cell.observed_since,
+        };
+        if cell.consciousness == ConsciousnessState::Potential {
+            if ctx.random_bool(ctx.rate_at(Rate::Observation)) {
+                next.observe(ctx.collapse_policy);
+            } else if ctx.random_bool(ctx.rate_at(Rate::Fluctuation)) {
+                next = ctx.with_random(|rng| Existon::new(cell.id, ctx.ga_dims, rng));
+                ctx.report_fluctuation(cell.id);
+            }
+        } else if cell.consciousness == ConsciousnessState::Observed
+            && ctx.random_bool(ctx.rate_at(Rate::Decay))
+        {
+            ctx.with_random(|rng| next.decay(rng));
+        }
+        next
+    }
+}
+
+/// Makes a fresh instance of a rule.
+pub type RuleFactory = Box<dyn Fn() -> Arc<dyn UpdateRule> + Send + Sync>;
+
+/// Update rules by name, so one can be chosen when the application starts
+/// (`--rule NAME`) without the frontends knowing about it.
+#[derive(Default)]
+pub struct RuleRegistry {
+    rules: Vec<(String, RuleFactory)>,
+}
+
+impl RuleRegistry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the built-in rules: `matzke`, for [`MatzkeDefaultRule`].
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry.register("matzke", || Arc::new(MatzkeDefaultRule));
+        registry
+    }
+
+    /// Adds a rule under `name`, replacing any rule already registered by
+    /// that name.
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        factory: impl Fn() -> Arc<dyn UpdateRule> + Send + Sync + 'static,
+    ) {
+        let name = name.into();
+        self.rules.retain(|(existing, _)| *existing != name);
+        self.rules.push((name, Box::new(factory)));
+    }
+
+    /// The names of the registered rules, in registration order.
+    pub fn names(&self) -> Vec<&str> {
+        self.rules.iter().map(|(name, _)| name.as_str()).collect()
+    }
+
+    /// A new instance of the rule registered as `name`, if there is one.
+    pub fn create(&self, name: &str) -> Option<Arc<dyn UpdateRule>> {
+        let (_, factory) = self.rules.iter().find(|(n, _)| n == name)?;
+        Some(factory())
+    }
+}
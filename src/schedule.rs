@@ -0,0 +1,213 @@
+//! Annealing: rates that follow a curve over the ticks.
+//!
+//! A [`Schedule`] gives some of a universe's rates a [`Curve`] each, evaluated
+//! at the universe's tick count, so a run can start hot and chaotic and cool
+//! into frozen structures on its own. Schedules are written one rate per line:
+//!
+//! ```text
+//! # rate        curve   parameters
+//! fluctuation   linear  0.3 0.0 5000       # from, to, over ticks
+//! observation   sine    0.05 0.04 1000     # mean, amplitude, period
+//! decay         step    0.1 2000 0.01      # initial, then tick value pairs
+//! ```
+//!
+//! Blank lines and `#` comments are ignored, and `;` also ends a line so a
+//! schedule fits on a command line. Like zen mode's drift, scheduled rates are
+//! not interventions, so a share code records only the rates of its moment.
+
+use crate::field::Rate;
+use crate::universe::Universe;
+use std::f64::consts::TAU;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+/// How a rate changes with the tick.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Curve {
+    /// From `from` to `to` evenly over the first `ticks` ticks, then held.
+    Linear { from: f64, to: f64, ticks: u64 },
+    /// `mean + amplitude * sin(2π tick / period)`.
+    Sine {
+        mean: f64,
+        amplitude: f64,
+        period: u64,
+    },
+    /// `initial` until the first step, then each step's value from its tick on.
+    Step {
+        initial: f64,
+        /// (tick, value) pairs in increasing tick order.
+        steps: Vec<(u64, f64)>,
+    },
+}
+
+impl Curve {
+    /// The curve's value at `tick`, clamped to `[0, 1]` since rates are
+    /// probabilities.
+    pub fn value_at(&self, tick: u64) -> f64 {
+        let value = match self {
+            &Curve::Linear { from, to, ticks } => {
+                let progress = if ticks == 0 {
+                    1.0
+                } else {
+                    (tick as f64 / ticks as f64).min(1.0)
+                };
+                from + (to - from) * progress
+            }
+            &Curve::Sine {
+                mean,
+                amplitude,
+                period,
+            } => {
+                let phase = if period == 0 {
+                    0.0
+                } else {
+                    (tick % period) as f64 / period as f64
+                };
+                mean + amplitude * (TAU * phase).sin()
+            }
+            Curve::Step { initial, steps } => steps
+                .iter()
+                .take_while(|&&(at, _)| at <= tick)
+                .last()
+                .map_or(*initial, |&(_, value)| value),
+        };
+        value.clamp(0.0, 1.0)
+    }
+}
+
+/// Why a schedule could not be read.
+#[derive(Debug)]
+pub enum ScheduleError {
+    Io(io::Error),
+    /// A line that does not describe a rate's curve, counting from 1.
+    Invalid {
+        line: usize,
+        reason: &'static str,
+    },
+}
+
+impl fmt::Display for ScheduleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScheduleError::Io(e) => write!(f, "could not read schedule: {}", e),
+            ScheduleError::Invalid { line, reason } => {
+                write!(f, "schedule line {} is invalid: {}", line, reason)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScheduleError {}
+
+/// Curves for some of a universe's rates; see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Schedule {
+    /// At most one curve per rate, in the order they were given.
+    pub curves: Vec<(Rate, Curve)>,
+}
+
+impl Schedule {
+    /// Reads a schedule file.
+    pub fn load(path: &Path) -> Result<Self, ScheduleError> {
+        fs::read_to_string(path).map_err(ScheduleError::Io)?.parse()
+    }
+
+    /// Every scheduled rate's value at `tick`.
+    pub fn values_at(&self, tick: u64) -> Vec<(Rate, f64)> {
+        self.curves
+            .iter()
+            .map(|(rate, curve)| (*rate, curve.value_at(tick)))
+            .collect()
+    }
+
+    /// Sets the scheduled rates of `universe` for the tick it is about to run.
+    pub fn apply(&self, universe: &mut Universe) {
+        for (rate, value) in self.values_at(universe.tick_count) {
+            *match rate {
+                Rate::Observation => &mut universe.observation_rate,
+                Rate::Decay => &mut universe.decay_rate,
+                Rate::Fluctuation => &mut universe.fluctuation_rate,
+            } = value;
+        }
+    }
+
+    /// A one-line summary of the scheduled rates at `tick`, for the HUD.
+    pub fn describe(&self, tick: u64) -> String {
+        let values: Vec<String> = self
+            .values_at(tick)
+            .into_iter()
+            .map(|(rate, value)| format!("{} {:.3}", rate.name(), value))
+            .collect();
+        format!("Anneal {}", values.join(" "))
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = ScheduleError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut curves: Vec<(Rate, Curve)> = Vec::new();
+        let lines = text.lines().enumerate().flat_map(|(n, line)| {
+            let line = line.split('#').next().unwrap_or_default();
+            line.split(';').map(move |part| (n + 1, part))
+        });
+        for (line, part) in lines {
+            let invalid = |reason| ScheduleError::Invalid { line, reason };
+            let words: Vec<&str> = part.split_whitespace().collect();
+            let Some((&rate, rest)) = words.split_first() else {
+                continue;
+            };
+            let rate = Rate::ALL
+                .into_iter()
+                .find(|&r| r.name() == rate)
+                .ok_or(invalid("unknown rate"))?;
+            if curves.iter().any(|&(r, _)| r == rate) {
+                return Err(invalid("rate scheduled twice"));
+            }
+            let (&kind, params) = rest.split_first().ok_or(invalid("missing curve"))?;
+            let numbers: Vec<f64> = params
+                .iter()
+                .map(|p| p.parse().map_err(|_| invalid("parameter is not a number")))
+                .collect::<Result<_, _>>()?;
+            let ticks = |value: f64| {
+                (value >= 0.0 && value.fract() == 0.0)
+                    .then_some(value as u64)
+                    .ok_or(invalid("tick count is not a whole number"))
+            };
+            let curve = match (kind, &numbers[..]) {
+                ("linear", &[from, to, over]) => Curve::Linear {
+                    from,
+                    to,
+                    ticks: ticks(over)?,
+                },
+                ("sine", &[mean, amplitude, period]) => Curve::Sine {
+                    mean,
+                    amplitude,
+                    period: ticks(period)?,
+                },
+                ("step", [initial, pairs @ ..]) if pairs.len() % 2 == 0 => {
+                    let steps = pairs
+                        .chunks(2)
+                        .map(|pair| Ok((ticks(pair[0])?, pair[1])))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    if !steps.is_sorted_by_key(|&(at, _)| at) {
+                        return Err(invalid("steps are not in tick order"));
+                    }
+                    Curve::Step {
+                        initial: *initial,
+                        steps,
+                    }
+                }
+                ("linear" | "sine" | "step", _) => {
+                    return Err(invalid("wrong number of parameters"));
+                }
+                _ => return Err(invalid("unknown curve")),
+            };
+            curves.push((rate, curve));
+        }
+        Ok(Schedule { curves })
+    }
+}
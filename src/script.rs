@@ -0,0 +1,820 @@
+//! Scripts: experiments, tools and update rules without recompiling.
+//!
+//! A [`Script`] is a [Rhai](https://rhai.rs) program with hooks the
+//! application calls: `on_tick(tick)` after every tick, `on_click(coord)` when
+//! a cell is clicked and `on_key(key)` when a key is pressed, with the key
+//! named as in the keybindings file. A hook works on the universe through the
+//! functions below, and variables declared outside any function keep their
+//! values from one call to the next:
+//!
+//! ```text
+//! // Observes a cross around every click and halves the decay rate on J.
+//! let clicks = 0;
+//!
+//! fn on_click(coord) {
+//!     clicks += 1;
+//!     for d in range(-2, 3) {
+//!         observe([coord[0] + d, coord[1]]);
+//!         observe([coord[0], coord[1] + d]);
+//!     }
+//! }
+//!
+//! fn on_key(key) {
+//!     if key == "J" {
+//!         set_rate("decay", rate("decay") / 2);
+//!         print(`decay ${rate("decay")} after ${clicks} clicks`);
+//!     }
+//! }
+//! ```
+//!
+//! Coordinates are lists with an entry per grid dimension, states are lists
+//! of `2^p` coefficients, each -1, 0 or 1, and the cell functions ignore
+//! coordinates off the grid, returning nothing:
+//!
+//! | Function | Does |
+//! |---|---|
+//! | `dims()`, `tick()` | The grid dimensions; the tick count. |
+//! | `state(c)`, `set_state(c, coefficients)` | A cell's coefficients. Setting them, like stamping a one-cell pattern, unentangles the cell. |
+//! | `consciousness(c)` | `"potential"`, `"observed"` or `"operator"`. |
+//! | `observe(c)`, `disrupt(c)` | Collapses a cell; decays an `Observed` one. |
+//! | `operator(c)`, `operator(c, coefficients)`, `clear(c)` | Places or removes an `Operator`. |
+//! | `entangle(a, b)`, `partners(c)` | Entangles two cells; a cell's partners. |
+//! | `neighbors(c)`, `cells(from, to)` | A cell's neighbors; every cell of the box from `from` up to `to`. |
+//! | `stats(name)` | `"potential"`, `"observed"`, `"operators"`, `"entanglements"` or `"entropy"`. |
+//! | `rate(name)`, `set_rate(name, value)` | The global `"observation"`, `"decay"` or `"fluctuation"` rate. |
+//! | `product(a, b)`, `sum(a, b)` | The geometric product of two states; their sum, coefficient by coefficient. |
+//! | `rand()`, `print(text)` | A number from 0 up to 1; a line for the terminal. |
+//!
+//! A script that defines `rule(cell, neighbors)` is also an update rule, a
+//! [`ScriptRule`]. The cell and each of its neighbors are maps holding a
+//! `state` and a `consciousness`, and the rule returns the cell's next map,
+//! where a key left out keeps its value. A rule cannot touch the universe,
+//! but `tick()`, `rate(name)` (the cell's own, see
+//! [`RuleCtx::rate_at`]), `rand()`, `product` and `sum` work as in hooks.
+//!
+//! Everything a hook does to the universe goes through its logged
+//! interventions, so it is undone, replayed and shared like the tools' edits.
+//! A hook that fails, or runs for more than [`MAX_STEPS`] operations, stops
+//! its script, as does building a value of more than [`MAX_VALUE_SIZE`] items
+//! or characters. [`Scripts`] loads a folder of them and runs every hook of
+//! each.
+
+use crate::existon::{ConsciousnessState, Existon};
+use crate::field::Rate;
+use crate::ga_core::{Mod3, Multivector};
+use crate::pattern::{Pattern, PatternCell};
+use crate::rule::{RuleCtx, RuleRegistry, UpdateRule};
+use crate::universe::Universe;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rhai::{
+    AST, Array, CallFnOptions, Dynamic, Engine, EvalAltResult, INT, Map, NativeCallContext,
+    ParseError, Scope,
+};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// The most operations one call of a hook or rule may take, so a script
+/// stuck in a loop stops instead of freezing the window.
+pub const MAX_STEPS: u64 = 1_000_000;
+/// The most items and characters a value may hold, counting those of the
+/// lists inside it, so a script that keeps growing a list or a string stops
+/// long before it runs out of memory.
+pub const MAX_VALUE_SIZE: usize = 10_000;
+/// How deeply functions may call each other.
+const MAX_DEPTH: usize = 64;
+
+type Fallible<T> = Result<T, Box<EvalAltResult>>;
+
+/// The reasons a script can fail to load or run.
+#[derive(Debug)]
+pub enum ScriptError {
+    /// The file could not be read.
+    Io(io::Error),
+    /// The text is not a valid script.
+    Syntax {
+        line: Option<usize>,
+        message: String,
+    },
+    /// Running the script went wrong.
+    Runtime {
+        line: Option<usize>,
+        message: String,
+    },
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScriptError::Io(e) => write!(f, "could not read the script: {}", e),
+            ScriptError::Syntax { line, message } | ScriptError::Runtime { line, message } => {
+                match line {
+                    Some(line) => write!(f, "line {}: {}", line, message),
+                    None => write!(f, "{}", message),
+                }
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+fn syntax(error: ParseError) -> ScriptError {
+    ScriptError::Syntax {
+        line: error.1.line(),
+        message: error.0.to_string(),
+    }
+}
+
+fn runtime(mut error: Box<EvalAltResult>) -> ScriptError {
+    // The error inside the functions a hook called, where the line is known.
+    while let EvalAltResult::ErrorInFunctionCall(_, _, inner, _) = *error {
+        error = inner;
+    }
+    let line = error.take_position().line();
+    ScriptError::Runtime {
+        line,
+        message: error.to_string(),
+    }
+}
+
+//================================================================================
+// Values
+//================================================================================
+
+/// The grid index of `coord`, if it is a coordinate on the grid.
+fn cell_index(universe: &Universe, coord: &[Dynamic]) -> Option<usize> {
+    let coord = coord
+        .iter()
+        .map(|c| c.as_int().ok().and_then(|c| usize::try_from(c).ok()))
+        .collect::<Option<Vec<usize>>>()?;
+    (coord.len() == universe.grid_dims.len())
+        .then(|| universe.get_index_from_coord(&coord))
+        .flatten()
+}
+
+fn coord_value(coord: &[usize]) -> Dynamic {
+    let coord: Array = coord.iter().map(|&c| Dynamic::from(c as INT)).collect();
+    coord.into()
+}
+
+/// The coordinates of the cells at `indices`.
+fn coords_value(universe: &Universe, indices: impl Iterator<Item = usize>) -> Dynamic {
+    let coords: Array = indices
+        .map(|idx| coord_value(&universe.get_coord_from_index(idx)))
+        .collect();
+    coords.into()
+}
+
+fn state_value(state: &Multivector) -> Dynamic {
+    let coefficients: Array = state
+        .coefficients
+        .iter()
+        .map(|c| Dynamic::from(c.0 as INT))
+        .collect();
+    coefficients.into()
+}
+
+/// The state a list of coefficients describes, in an algebra of as many
+/// dimensions as its length implies.
+fn multivector(coefficients: &[Dynamic]) -> Fallible<Multivector> {
+    let parsed = coefficients
+        .iter()
+        .map(|c| match c.as_int() {
+            Ok(c @ -1..=1) => Some(Mod3::new(c as i8)),
+            _ => None,
+        })
+        .collect::<Option<Vec<Mod3>>>();
+    match parsed {
+        Some(coefficients) if coefficients.len().is_power_of_two() => Ok(Multivector {
+            p: coefficients.len().trailing_zeros() as usize,
+            coefficients,
+        }),
+        _ => Err("a state is a list of 2^p numbers, each -1, 0 or 1".into()),
+    }
+}
+
+/// The state a list of coefficients describes in a `Cl(ga_dims,0)` algebra.
+fn multivector_in(ga_dims: usize, coefficients: &[Dynamic]) -> Fallible<Multivector> {
+    match multivector(coefficients)? {
+        state if state.p == ga_dims => Ok(state),
+        _ => Err(format!("a state here is a list of {} numbers", 1 << ga_dims).into()),
+    }
+}
+
+/// Two states of the same algebra.
+fn multivectors(a: &[Dynamic], b: &[Dynamic]) -> Fallible<(Multivector, Multivector)> {
+    let a = multivector(a)?;
+    let b = multivector_in(a.p, b)?;
+    Ok((a, b))
+}
+
+fn consciousness_name(consciousness: ConsciousnessState) -> &'static str {
+    match consciousness {
+        ConsciousnessState::Potential => "potential",
+        ConsciousnessState::Observed => "observed",
+        ConsciousnessState::Operator => "operator",
+    }
+}
+
+fn rate_named(name: &str) -> Fallible<Rate> {
+    Rate::ALL
+        .into_iter()
+        .find(|rate| rate.name() == name)
+        .ok_or_else(|| format!("there is no rate called {:?}", name).into())
+}
+
+/// An engine with the limits every script runs under and the functions that
+/// work on states alone.
+fn engine() -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_max_operations(MAX_STEPS)
+        .set_max_call_levels(MAX_DEPTH)
+        .set_max_string_size(MAX_VALUE_SIZE)
+        .set_max_array_size(MAX_VALUE_SIZE)
+        .set_max_map_size(MAX_VALUE_SIZE);
+    engine.register_fn("product", |a: Array, b: Array| -> Fallible<Dynamic> {
+        let (a, b) = multivectors(&a, &b)?;
+        Ok(state_value(&(&a * &b)))
+    });
+    engine.register_fn("sum", |a: Array, b: Array| -> Fallible<Dynamic> {
+        let (a, b) = multivectors(&a, &b)?;
+        Ok(state_value(&(&a + &b)))
+    });
+    engine
+}
+
+//================================================================================
+// Scripts and their hooks
+//================================================================================
+
+/// What a script's functions work on while one of its hooks runs.
+struct Host {
+    /// The universe the hook runs on; between calls, an empty stand-in.
+    universe: Universe,
+    rng: StdRng,
+    output: Vec<String>,
+    /// How many characters the running hook has printed.
+    printed: usize,
+}
+
+fn lock(host: &Mutex<Host>) -> MutexGuard<'_, Host> {
+    host.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// An engine whose functions work on the universe in `host`.
+fn hook_engine(host: &Arc<Mutex<Host>>) -> Engine {
+    let mut engine = engine();
+    let h = Arc::clone(host);
+    engine.on_print(move |text| {
+        let mut host = lock(&h);
+        host.printed += text.len();
+        if host.printed <= MAX_VALUE_SIZE {
+            host.output.push(text.to_string());
+        }
+    });
+    let h = Arc::clone(host);
+    engine.register_fn("rand", move || -> f64 { lock(&h).rng.random() });
+    let h = Arc::clone(host);
+    engine.register_fn("dims", move || coord_value(&lock(&h).universe.grid_dims));
+    let h = Arc::clone(host);
+    engine.register_fn("tick", move || lock(&h).universe.tick_count as INT);
+    let h = Arc::clone(host);
+    engine.register_fn("state", move |c: Array| {
+        let universe = &lock(&h).universe;
+        cell_index(universe, &c).map_or(Dynamic::UNIT, |idx| state_value(&universe.grid[idx].state))
+    });
+    let h = Arc::clone(host);
+    engine.register_fn(
+        "set_state",
+        move |c: Array, coefficients: Array| -> Fallible<()> {
+            let universe = &mut lock(&h).universe;
+            let state = multivector_in(universe.ga_dims, &coefficients)?;
+            if let Some(idx) = cell_index(universe, &c) {
+                let dims = universe.grid_dims.len();
+                let pattern = Pattern {
+                    size: vec![1; dims],
+                    ga_dims: universe.ga_dims,
+                    cells: vec![PatternCell {
+                        offset: vec![0; dims],
+                        consciousness: universe.grid[idx].consciousness,
+                        state,
+                    }],
+                    entanglements: Vec::new(),
+                };
+                let at = universe.get_coord_from_index(idx);
+                universe.stamp_pattern(&pattern, &at);
+            }
+            Ok(())
+        },
+    );
+    let h = Arc::clone(host);
+    engine.register_fn("consciousness", move |c: Array| {
+        let universe = &lock(&h).universe;
+        cell_index(universe, &c).map_or(Dynamic::UNIT, |idx| {
+            consciousness_name(universe.grid[idx].consciousness).into()
+        })
+    });
+    let h = Arc::clone(host);
+    engine.register_fn("observe", move |c: Array| {
+        let universe = &mut lock(&h).universe;
+        if let Some(idx) = cell_index(universe, &c) {
+            universe.observe_cell(idx);
+        }
+    });
+    let h = Arc::clone(host);
+    engine.register_fn("disrupt", move |c: Array| {
+        let universe = &mut lock(&h).universe;
+        if let Some(idx) = cell_index(universe, &c) {
+            universe.disrupt_cell(idx);
+        }
+    });
+    let h = Arc::clone(host);
+    engine.register_fn("operator", move |c: Array| {
+        let universe = &mut lock(&h).universe;
+        if let Some(idx) = cell_index(universe, &c) {
+            universe.set_operator(&universe.get_coord_from_index(idx));
+        }
+    });
+    let h = Arc::clone(host);
+    engine.register_fn(
+        "operator",
+        move |c: Array, coefficients: Array| -> Fallible<()> {
+            let universe = &mut lock(&h).universe;
+            let state = multivector_in(universe.ga_dims, &coefficients)?;
+            if let Some(idx) = cell_index(universe, &c) {
+                universe.set_operator_with_state(&universe.get_coord_from_index(idx), state);
+            }
+            Ok(())
+        },
+    );
+    let h = Arc::clone(host);
+    engine.register_fn("clear", move |c: Array| {
+        let universe = &mut lock(&h).universe;
+        if let Some(idx) = cell_index(universe, &c) {
+            universe.clear_operator(&universe.get_coord_from_index(idx));
+        }
+    });
+    let h = Arc::clone(host);
+    engine.register_fn("entangle", move |a: Array, b: Array| {
+        let universe = &mut lock(&h).universe;
+        if let (Some(a), Some(b)) = (cell_index(universe, &a), cell_index(universe, &b)) {
+            let (a, b) = (universe.grid[a].id, universe.grid[b].id);
+            universe.entangle_pair(a, b);
+        }
+    });
+    let h = Arc::clone(host);
+    engine.register_fn("partners", move |c: Array| {
+        let universe = &lock(&h).universe;
+        cell_index(universe, &c).map_or(Dynamic::UNIT, |idx| {
+            let partners = universe
+                .entanglement
+                .partners(universe.grid[idx].id)
+                .filter_map(|partner| universe.index_of_id(partner));
+            coords_value(universe, partners)
+        })
+    });
+    let h = Arc::clone(host);
+    engine.register_fn("neighbors", move |c: Array| {
+        let universe = &lock(&h).universe;
+        cell_index(universe, &c).map_or(Dynamic::UNIT, |idx| {
+            let coord = universe.get_coord_from_index(idx);
+            coords_value(universe, universe.neighbors(&coord))
+        })
+    });
+    let h = Arc::clone(host);
+    engine.register_fn(
+        "cells",
+        move |from: Array, to: Array| -> Fallible<Dynamic> {
+            let universe = &lock(&h).universe;
+            let corner = |corner: &Array| {
+                corner
+                    .iter()
+                    .map(|c| c.as_int().ok().map(|c| c.max(0) as usize))
+                    .collect::<Option<Vec<usize>>>()
+            };
+            let (Some(from), Some(to)) = (corner(&from), corner(&to)) else {
+                return Err("cells expects two coordinates".into());
+            };
+            let region: Vec<Range<usize>> = from.iter().zip(&to).map(|(&a, &b)| a..b).collect();
+            let indices = universe.region_indices(&region);
+            if indices.len() * (universe.grid_dims.len() + 1) > MAX_VALUE_SIZE {
+                return Err(format!("a list holds at most {} items", MAX_VALUE_SIZE).into());
+            }
+            Ok(coords_value(universe, indices.into_iter()))
+        },
+    );
+    let h = Arc::clone(host);
+    engine.register_fn("stats", move |name: &str| -> Fallible<Dynamic> {
+        let stats = lock(&h).universe.stats();
+        Ok(match name {
+            "potential" => (stats.potential as INT).into(),
+            "observed" => (stats.observed as INT).into(),
+            "operators" => (stats.operators as INT).into(),
+            "entanglements" => (stats.entanglements as INT).into(),
+            "entropy" => stats.entropy.into(),
+            other => return Err(format!("there is no statistic called {:?}", other).into()),
+        })
+    });
+    let h = Arc::clone(host);
+    engine.register_fn("rate", move |name: &str| -> Fallible<f64> {
+        Ok(lock(&h).universe.rate(rate_named(name)?))
+    });
+    let set_rate = {
+        let h = Arc::clone(host);
+        move |name: &str, value: f64| -> Fallible<()> {
+            let universe = &mut lock(&h).universe;
+            *match rate_named(name)? {
+                Rate::Observation => &mut universe.observation_rate,
+                Rate::Decay => &mut universe.decay_rate,
+                Rate::Fluctuation => &mut universe.fluctuation_rate,
+            } = value.clamp(0.0, 1.0);
+            Ok(())
+        }
+    };
+    let set_whole_rate = set_rate.clone();
+    engine.register_fn("set_rate", set_rate);
+    engine.register_fn("set_rate", move |name: &str, value: INT| {
+        set_whole_rate(name, value as f64)
+    });
+    engine
+}
+
+/// Whether `ast` defines the function `name`.
+fn defines(ast: &AST, name: &str) -> bool {
+    ast.iter_functions().any(|function| function.name == name)
+}
+
+/// A compiled script with the values of its globals; see the
+/// [module documentation](self).
+pub struct Script {
+    source: String,
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    host: Arc<Mutex<Host>>,
+    started: bool,
+}
+
+impl fmt::Debug for Script {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Script")
+            .field("started", &self.started)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Script {
+    /// Reads a script from its text.
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        let host = Arc::new(Mutex::new(Host {
+            universe: Universe::with_seed(vec![0], 0, 0),
+            rng: StdRng::seed_from_u64(0),
+            output: Vec::new(),
+            printed: 0,
+        }));
+        let engine = hook_engine(&host);
+        let ast = engine.compile(source).map_err(syntax)?;
+        Ok(Script {
+            source: source.to_string(),
+            engine,
+            ast,
+            scope: Scope::new(),
+            host,
+            started: false,
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ScriptError> {
+        Self::parse(&fs::read_to_string(path).map_err(ScriptError::Io)?)
+    }
+
+    /// Whether the script defines the function `name`.
+    pub fn defines(&self, name: &str) -> bool {
+        defines(&self.ast, name)
+    }
+
+    /// Calls the script's function `name` with `args`, after running its
+    /// top-level statements if this is the first call. Returns nothing if the
+    /// script has no such function; a hook may leave out trailing arguments
+    /// it has no use for.
+    pub fn call(
+        &mut self,
+        universe: &mut Universe,
+        name: &str,
+        args: Vec<Dynamic>,
+    ) -> Result<Dynamic, ScriptError> {
+        std::mem::swap(universe, &mut lock(&self.host).universe);
+        lock(&self.host).printed = 0;
+        let result = self.run(name, args);
+        std::mem::swap(universe, &mut lock(&self.host).universe);
+        result
+    }
+
+    fn run(&mut self, name: &str, mut args: Vec<Dynamic>) -> Result<Dynamic, ScriptError> {
+        if !self.started {
+            self.started = true;
+            self.engine
+                .run_ast_with_scope(&mut self.scope, &self.ast)
+                .map_err(runtime)?;
+        }
+        let Some(function) = self.ast.iter_functions().find(|f| f.name == name) else {
+            return Ok(Dynamic::UNIT);
+        };
+        args.truncate(function.params.len());
+        let options = CallFnOptions::new().eval_ast(false);
+        self.engine
+            .call_fn_with_options(options, &mut self.scope, &self.ast, name, args)
+            .map_err(runtime)
+    }
+
+    /// Takes the lines printed since the last call, oldest first.
+    pub fn take_output(&mut self) -> Vec<String> {
+        std::mem::take(&mut lock(&self.host).output)
+    }
+}
+
+/// The scripts the application runs, each by name, with their hooks called in
+/// the order they were added. A script whose hook fails is stopped and left
+/// out from then on.
+#[derive(Default)]
+pub struct Scripts {
+    scripts: Vec<(String, Script, bool)>,
+    /// The rules the scripts define, and whether a failure of each has been
+    /// reported.
+    rules: Vec<(String, ScriptRule, bool)>,
+    output: Vec<String>,
+}
+
+impl Scripts {
+    pub fn add(&mut self, name: impl Into<String>, script: Script) {
+        self.scripts.push((name.into(), script, true));
+    }
+
+    /// Adds every `.rhai` file in `dir`, in name order, each named after its
+    /// file. Scripts that fail to parse are reported in the output and
+    /// skipped. Returns how many were added.
+    pub fn load_folder(&mut self, dir: &Path) -> io::Result<usize> {
+        let mut paths: Vec<_> = fs::read_dir(dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rhai"))
+            .collect();
+        paths.sort();
+        let before = self.scripts.len();
+        for path in paths {
+            let name = path
+                .file_stem()
+                .map_or_else(String::new, |stem| stem.to_string_lossy().into_owned());
+            match Script::load(&path) {
+                Ok(script) => self.add(name, script),
+                Err(e) => self.output.push(format!("{}: {}", name, e)),
+            }
+        }
+        Ok(self.scripts.len() - before)
+    }
+
+    /// The names of the scripts, running or stopped.
+    pub fn names(&self) -> Vec<&str> {
+        self.scripts
+            .iter()
+            .map(|(name, ..)| name.as_str())
+            .collect()
+    }
+
+    /// Whether no script is running.
+    pub fn is_idle(&self) -> bool {
+        !self.scripts.iter().any(|&(_, _, running)| running)
+    }
+
+    /// Registers the rule of every script that defines `rule(cell, neighbors)`
+    /// in `registry`, under the script's name.
+    pub fn register_rules(&mut self, registry: &mut RuleRegistry) {
+        for (name, script, _) in &self.scripts {
+            if !script.defines("rule") {
+                continue;
+            }
+            match ScriptRule::parse(&script.source) {
+                Ok(rule) => {
+                    let shared = rule.clone();
+                    registry.register(name.clone(), move || Arc::new(shared.clone()));
+                    self.rules.push((name.clone(), rule, false));
+                }
+                Err(e) => self.output.push(format!("{}: {}", name, e)),
+            }
+        }
+    }
+
+    /// Calls `hook` with `args` in every running script.
+    fn call(&mut self, universe: &mut Universe, hook: &str, args: Vec<Dynamic>) {
+        for (name, script, running) in &mut self.scripts {
+            if !*running || !script.defines(hook) {
+                continue;
+            }
+            let result = script.call(universe, hook, args.clone());
+            self.output.extend(
+                script
+                    .take_output()
+                    .into_iter()
+                    .map(|line| format!("{}: {}", name, line)),
+            );
+            if let Err(e) = result {
+                self.output
+                    .push(format!("{}: {}; the script is stopped", name, e));
+                *running = false;
+            }
+        }
+    }
+
+    /// Runs every `on_tick(tick)`, after a tick, and reports the first
+    /// failure of each script's rule.
+    pub fn on_tick(&mut self, universe: &mut Universe) {
+        for (name, rule, reported) in &mut self.rules {
+            if !*reported && let Some(e) = rule.failure() {
+                self.output.push(format!(
+                    "{}: {}; the rule leaves the cells it fails on as they are",
+                    name, e
+                ));
+                *reported = true;
+            }
+        }
+        let tick = Dynamic::from(universe.tick_count as INT);
+        self.call(universe, "on_tick", vec![tick]);
+    }
+
+    /// Runs every `on_click(coord)`, after a click on the cell at `coord`.
+    pub fn on_click(&mut self, universe: &mut Universe, coord: &[usize]) {
+        self.call(universe, "on_click", vec![coord_value(coord)]);
+    }
+
+    /// Runs every `on_key(key)`, after the key named `key` is pressed.
+    pub fn on_key(&mut self, universe: &mut Universe, key: &str) {
+        self.call(universe, "on_key", vec![Dynamic::from(key.to_string())]);
+    }
+
+    /// Takes the lines the scripts printed, and their errors, since the last
+    /// call, each starting with the script's name.
+    pub fn take_output(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.output)
+    }
+}
+
+//================================================================================
+// Rules
+//================================================================================
+
+/// What a rule's functions know of the cell being updated, passed to them as
+/// the tag of the call.
+#[derive(Clone)]
+struct RuleCall {
+    tick: u64,
+    /// The cell's rates, by [`Rate::index`].
+    rates: [f64; 3],
+    rng: Arc<Mutex<StdRng>>,
+}
+
+fn rule_call(context: &NativeCallContext) -> RuleCall {
+    context
+        .tag()
+        .and_then(|tag| tag.clone().try_cast::<RuleCall>())
+        .expect("rules are called with their cell's tag")
+}
+
+/// An engine for rules, whose functions read the cell being updated.
+fn rule_engine() -> Engine {
+    let mut engine = engine();
+    engine.register_fn("tick", |context: NativeCallContext| {
+        rule_call(&context).tick as INT
+    });
+    engine.register_fn(
+        "rate",
+        |context: NativeCallContext, name: &str| -> Fallible<f64> {
+            Ok(rule_call(&context).rates[rate_named(name)?.index()])
+        },
+    );
+    engine.register_fn("rand", |context: NativeCallContext| -> f64 {
+        let rng = rule_call(&context).rng;
+        let mut rng = rng.lock().unwrap_or_else(PoisonError::into_inner);
+        rng.random()
+    });
+    engine.on_print(|_| {});
+    engine
+}
+
+fn cell_value(cell: &Existon) -> Dynamic {
+    let mut map = Map::new();
+    map.insert("state".into(), state_value(&cell.state));
+    map.insert(
+        "consciousness".into(),
+        consciousness_name(cell.consciousness).into(),
+    );
+    map.into()
+}
+
+/// `cell` with the state and consciousness a rule gave back for it.
+fn next_cell(cell: &Existon, next: Dynamic) -> Fallible<Existon> {
+    let Some(next) = next.try_cast::<Map>() else {
+        return Err("a rule returns a map with the cell's state and consciousness".into());
+    };
+    let mut cell = cell.clone();
+    if let Some(state) = next.get("state") {
+        let state = state
+            .clone()
+            .into_array()
+            .map_err(|_| "a state is a list")?;
+        cell.state = multivector_in(cell.state.p, &state)?;
+    }
+    if let Some(consciousness) = next.get("consciousness") {
+        let name = consciousness.clone().into_string().unwrap_or_default();
+        cell.consciousness = match name.as_str() {
+            "potential" => ConsciousnessState::Potential,
+            "observed" => ConsciousnessState::Observed,
+            "operator" => ConsciousnessState::Operator,
+            _ => return Err(format!("there is no consciousness called {:?}", name).into()),
+        };
+    }
+    Ok(cell)
+}
+
+/// An [`UpdateRule`] defined by a script's `rule(cell, neighbors)`; see the
+/// [module documentation](self).
+///
+/// A cell the rule fails on keeps its state, and the first failure is kept
+/// for [`ScriptRule::failure`].
+#[derive(Clone)]
+pub struct ScriptRule {
+    engine: Arc<Engine>,
+    ast: AST,
+    failure: Arc<Mutex<Option<String>>>,
+}
+
+impl ScriptRule {
+    /// Reads a rule from the text of a script defining `rule(cell, neighbors)`.
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        let engine = rule_engine();
+        let ast = engine.compile(source).map_err(syntax)?;
+        if !defines(&ast, "rule") {
+            return Err(ScriptError::Syntax {
+                line: None,
+                message: "the script defines no rule(cell, neighbors)".to_string(),
+            });
+        }
+        Ok(ScriptRule {
+            engine: Arc::new(engine),
+            ast,
+            failure: Arc::default(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ScriptError> {
+        Self::parse(&fs::read_to_string(path).map_err(ScriptError::Io)?)
+    }
+
+    /// The first error the rule ran into, if it has failed on a cell.
+    pub fn failure(&self) -> Option<String> {
+        self.failure
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl fmt::Debug for ScriptRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScriptRule").finish_non_exhaustive()
+    }
+}
+
+impl UpdateRule for ScriptRule {
+    fn apply(&self, cell: &Existon, neighbors: &[&Existon], ctx: &RuleCtx) -> Existon {
+        let seed = ctx.with_random(|rng| rng.next_u64());
+        let call = RuleCall {
+            tick: ctx.tick,
+            rates: Rate::ALL.map(|rate| ctx.rate_at(rate)),
+            rng: Arc::new(Mutex::new(StdRng::seed_from_u64(seed))),
+        };
+        let neighbors: Array = neighbors.iter().map(|n| cell_value(n)).collect();
+        let options = CallFnOptions::new().eval_ast(false).with_tag(call);
+        let next = self
+            .engine
+            .call_fn_with_options::<Dynamic>(
+                options,
+                &mut Scope::new(),
+                &self.ast,
+                "rule",
+                (cell_value(cell), neighbors),
+            )
+            .and_then(|next| next_cell(cell, next));
+        next.unwrap_or_else(|e| {
+            self.failure
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .get_or_insert_with(|| runtime(e).to_string());
+            cell.clone()
+        })
+    }
+}
@@ -0,0 +1,116 @@
+//! Seeding a universe from a picture.
+//!
+//! [`Universe::from_image`] makes a grid the size of an image, one cell per
+//! pixel, and reads each pixel's color back into a cell through an
+//! [`ImageMapping`]. Pixels close to the window's `Observed` color become
+//! `Observed` cells and those close to its `Operator` color become operators;
+//! every other pixel becomes a `Potential` cell whose coefficients are the
+//! ones the [`ColorMapping`] would show in that color, channel by channel, so
+//! a picture drawn in the app's palette comes back as the states it shows.
+//! Blades no channel shows are left at zero, and `Observed` cells and
+//! operators keep the seeded random state. Start the app with
+//! `--seed-image logo.png` to begin from a picture.
+//!
+//! The picture is written onto the seeded grid as one stamped [`Pattern`], so
+//! share codes and recordings carry it and replays reproduce it.
+//!
+//! [`Universe::from_image`]: crate::universe::Universe::from_image
+
+use crate::color::{Channel, ColorMapping, OBSERVED_COLOR, OPERATOR_COLOR};
+use crate::existon::ConsciousnessState;
+use crate::ga_core::{Mod3, Multivector};
+use crate::pattern::{Pattern, PatternCell};
+use crate::universe::Universe;
+use image::{Rgba, RgbaImage};
+
+/// How pixel colors become cells; see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageMapping {
+    /// The mapping whose colors are read back into coefficients.
+    pub colors: ColorMapping,
+    /// The GA dimensions of every cell's state.
+    pub ga_dims: usize,
+    /// The seed of the universe the picture is written onto.
+    pub seed: u64,
+    /// How far, from 0 to 1 in every channel, a pixel may be from the
+    /// `Observed` or `Operator` color and still place one.
+    pub tolerance: f32,
+}
+
+impl Default for ImageMapping {
+    /// The window's colors, three GA dimensions and seed 0.
+    fn default() -> Self {
+        ImageMapping {
+            colors: ColorMapping::GA_BLEND,
+            ga_dims: 3,
+            seed: 0,
+            tolerance: 0.1,
+        }
+    }
+}
+
+impl ImageMapping {
+    /// What a pixel places: a consciousness state, and for a `Potential`
+    /// cell its state.
+    pub fn cell(&self, pixel: Rgba<u8>) -> (ConsciousnessState, Option<Multivector>) {
+        let rgb = [0, 1, 2].map(|i| pixel.0[i] as f32 / 255.0);
+        let near = |color: [f32; 4]| {
+            rgb.iter()
+                .zip(color)
+                .all(|(c, target)| (c - target).abs() <= self.tolerance)
+        };
+        if near(OBSERVED_COLOR) {
+            return (ConsciousnessState::Observed, None);
+        }
+        if near(OPERATOR_COLOR) {
+            return (ConsciousnessState::Operator, None);
+        }
+        let mut state = Multivector::zero(self.ga_dims);
+        for mapping in &self.colors.channels {
+            let Some(slot) = state.coefficients.get_mut(mapping.blade) else {
+                continue;
+            };
+            let channel = match mapping.channel {
+                Channel::Red => 0,
+                Channel::Green => 1,
+                Channel::Blue => 2,
+                Channel::Alpha => 3,
+            };
+            let value = pixel.0[channel] as f32 / 255.0;
+            let closest = [-1, 0, 1]
+                .into_iter()
+                .min_by(|&a, &b| {
+                    let distance = |c: i8| (mapping.value(c).clamp(0.0, 1.0) - value).abs();
+                    distance(a).total_cmp(&distance(b))
+                })
+                .unwrap();
+            *slot = Mod3(closest);
+        }
+        (ConsciousnessState::Potential, Some(state))
+    }
+
+    /// The picture as a pattern to stamp on `universe` at its corner, with
+    /// `Observed` cells and operators keeping the states they have there.
+    pub fn pattern(&self, picture: &RgbaImage, universe: &Universe) -> Pattern {
+        let (width, height) = (picture.width() as usize, picture.height() as usize);
+        let mut cells = Vec::with_capacity(width * height);
+        for (x, y, &pixel) in picture.enumerate_pixels() {
+            let offset = vec![x as usize, y as usize];
+            let Some(idx) = universe.get_index_from_coord(&offset) else {
+                continue;
+            };
+            let (consciousness, state) = self.cell(pixel);
+            cells.push(PatternCell {
+                offset,
+                consciousness,
+                state: state.unwrap_or_else(|| universe.grid[idx].state.clone()),
+            });
+        }
+        Pattern {
+            size: vec![width, height],
+            ga_dims: self.ga_dims,
+            cells,
+            entanglements: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//! Tick statistics as a time series, for parameter sweeps run without a window.
+//!
+//! A [`StatsRow`] gathers one tick's population counts, entropy, events and
+//! parameter values, and a [`StatsLog`] appends a row per tick to a CSV file,
+//! writing the header only when it starts the file. Every row carries the
+//! universe's seed, so the runs of a sweep can share one file and still be
+//! told apart. Start the app with `--stats-out stats.csv` to log the run.
+
+use crate::universe::{TickReport, Universe};
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::Path;
+
+/// The measures of one tick; see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StatsRow {
+    pub seed: u64,
+    pub tick: u64,
+    pub potential: usize,
+    pub observed: usize,
+    pub operators: usize,
+    /// The number of entanglement groups.
+    pub entanglement_groups: usize,
+    /// The Shannon entropy of the cells' states, in bits.
+    pub entropy: f64,
+    pub mean_nonzero_coefficients: f64,
+    /// Cells the local rule observed during the tick.
+    pub observations: usize,
+    pub decays: usize,
+    pub fluctuations: usize,
+    /// Partners collapsed by an observed twin during the tick.
+    pub entanglement_triggers: usize,
+    pub decoherences: usize,
+    pub observation_rate: f64,
+    pub decay_rate: f64,
+    pub fluctuation_rate: f64,
+    pub entanglement_percentage: f64,
+}
+
+impl StatsRow {
+    /// The column names, in the order [`StatsRow::to_csv`] writes the values.
+    pub const HEADER: &'static str = "seed,tick,potential,observed,operators,entanglement_groups,\
+        entropy,mean_nonzero_coefficients,observations,decays,fluctuations,\
+        entanglement_triggers,decoherences,observation_rate,decay_rate,fluctuation_rate,\
+        entanglement_percentage";
+
+    /// The measures of the tick that left `universe` as it is and produced
+    /// `report`.
+    pub fn of(universe: &Universe, report: &TickReport) -> Self {
+        let stats = universe.stats();
+        StatsRow {
+            seed: universe.seed,
+            tick: stats.tick,
+            potential: stats.potential,
+            observed: stats.observed,
+            operators: stats.operators,
+            entanglement_groups: stats.entanglements,
+            entropy: stats.entropy,
+            mean_nonzero_coefficients: stats.mean_nonzero_coefficients,
+            observations: report.observations.len(),
+            decays: report.decays.len(),
+            fluctuations: report.fluctuations.len(),
+            entanglement_triggers: report.entanglements.len(),
+            decoherences: report.decoherences.len(),
+            observation_rate: universe.observation_rate,
+            decay_rate: universe.decay_rate,
+            fluctuation_rate: universe.fluctuation_rate,
+            entanglement_percentage: universe.entanglement_percentage,
+        }
+    }
+
+    /// The row as a line of CSV, without the line break.
+    pub fn to_csv(&self) -> String {
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.seed,
+            self.tick,
+            self.potential,
+            self.observed,
+            self.operators,
+            self.entanglement_groups,
+            self.entropy,
+            self.mean_nonzero_coefficients,
+            self.observations,
+            self.decays,
+            self.fluctuations,
+            self.entanglement_triggers,
+            self.decoherences,
+            self.observation_rate,
+            self.decay_rate,
+            self.fluctuation_rate,
+            self.entanglement_percentage
+        )
+    }
+}
+
+/// Appends a [`StatsRow`] per tick to a CSV file.
+///
+/// Each row is written whole as it comes, so a run that is killed leaves every
+/// finished tick in the file.
+#[derive(Debug)]
+pub struct StatsLog {
+    file: File,
+}
+
+impl StatsLog {
+    /// Opens `path` for appending, creating it with a header if it is missing
+    /// or empty.
+    pub fn append(path: &Path) -> io::Result<Self> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{}", StatsRow::HEADER)?;
+        }
+        Ok(StatsLog { file })
+    }
+
+    /// Appends the row of the tick that left `universe` as it is and produced
+    /// `report`.
+    pub fn record(&mut self, universe: &Universe, report: &TickReport) -> io::Result<()> {
+        self.write(&StatsRow::of(universe, report))
+    }
+
+    /// Appends `row`.
+    pub fn write(&mut self, row: &StatsRow) -> io::Result<()> {
+        self.file
+            .write_all(format!("{}\n", row.to_csv()).as_bytes())
+    }
+}
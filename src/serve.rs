@@ -0,0 +1,545 @@
+//! Serving a run over WebSocket, for web dashboards and shared viewing.
+//!
+//! The [`Server`] frontend ticks the application with nothing drawn and
+//! streams it to every connected client as text messages. A client is first
+//! sent `you ID COLOR`, its player number and cursor color, then `state CODE`,
+//! the whole universe as a [`UniverseDelta`] code (see
+//! [`Universe::to_delta`]), and after that `delta CODE` whenever the universe
+//! changes, which turns the previous state into the new one. Each client is
+//! written to on a thread of its own, so a slow one holds up neither the run
+//! nor the others: frames it has no room for are dropped, and it is sent a
+//! fresh `state` once it catches up. Clients drive the run by sending tool
+//! commands back, one per message, each naming cells by their grid
+//! coordinates:
+//!
+//! ```text
+//! observe X Y         collapse a Potential cell
+//! operator X Y        place an Operator
+//! clear X Y           remove an Operator or decay an Observed cell
+//! disrupt X Y         decay an Observed cell
+//! entangle X Y X Y    entangle the second cell with the first
+//! cursor X Y          show the player's cursor over a cell
+//! cursor              hide it
+//! ```
+//!
+//! Every player acts on the one shared universe. Tool commands become logged
+//! interventions, exactly as clicks in the window do, and one that cannot be
+//! carried out is answered with `error: MESSAGE`. Cursors are passed on to the
+//! other players as `cursor ID COLOR X Y`, or `cursor ID COLOR` when hidden,
+//! and a player who disconnects is announced with `left ID`, so every client
+//! can draw everyone's cursor in their color. The page in `web/` is such a
+//! client when opened with `?server=ws://HOST:PORT`.
+//!
+//! Start a server with `--serve ws://0.0.0.0:9000`. The handshake and framing
+//! of RFC 6455 are implemented here, for text messages only, without
+//! extensions or TLS.
+//!
+//! [`UniverseDelta`]: crate::delta::UniverseDelta
+
+use crate::app::AppState;
+use crate::frontend::Frontend;
+use crate::input::AppAction;
+use crate::universe::{Intervention, Universe};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender, SyncSender, TrySendError};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+
+/// Appended to a client's key before hashing it in the handshake.
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// The longest message a client may send, in bytes.
+const MAX_MESSAGE: usize = 64 * 1024;
+
+/// The longest handshake request a client may send, in bytes.
+const MAX_REQUEST: usize = 8 * 1024;
+
+/// How many frames may wait to be written to a client before more are
+/// dropped.
+const OUTBOX: usize = 64;
+
+/// How long a finished run waits for its clients to be sent what they are
+/// owed.
+const CLOSE_WAIT: Duration = Duration::from_secs(1);
+
+const OP_CONTINUATION: u8 = 0x0;
+const OP_TEXT: u8 = 0x1;
+const OP_BINARY: u8 = 0x2;
+const OP_CLOSE: u8 = 0x8;
+const OP_PING: u8 = 0x9;
+const OP_PONG: u8 = 0xA;
+
+/// Runs the application headless, streaming it to WebSocket clients; see the
+/// [module documentation](self).
+pub struct Server {
+    listener: TcpListener,
+    /// How many ticks to run before stopping; forever if `None`.
+    pub ticks: Option<u64>,
+    /// How long to wait between frames. Ticks keep to the application's
+    /// timestep, or come one per frame without one.
+    pub frame: Duration,
+}
+
+impl Server {
+    /// Listens on `address`, given as `ws://HOST:PORT` or just `HOST:PORT`.
+    pub fn bind(address: &str) -> io::Result<Self> {
+        let address = address.strip_prefix("ws://").unwrap_or(address);
+        let address = address.split('/').next().unwrap_or(address);
+        Ok(Server {
+            listener: TcpListener::bind(address)?,
+            ticks: None,
+            frame: Duration::from_secs(1) / 60,
+        })
+    }
+
+    /// The address the server listens on, with the port filled in if it was
+    /// bound to port 0.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+}
+
+/// What the connections report to the server's thread.
+enum Inbound {
+    /// A client completed the handshake; messages go out on this stream.
+    Joined(usize, TcpStream),
+    Text(usize, String),
+    Ping(usize, Vec<u8>),
+    /// The client closed the connection or broke the protocol.
+    Left(usize),
+}
+
+impl Frontend for Server {
+    fn name(&self) -> &str {
+        "serve"
+    }
+
+    fn run(&mut self, app: &mut AppState) -> io::Result<()> {
+        let (inbox, inbound) = mpsc::channel();
+        let listener = self.listener.try_clone()?;
+        thread::spawn(move || accept(listener, inbox));
+
+        let mut players: Vec<Player> = Vec::new();
+        let mut shown = app.universe.clone();
+        let start = app.universe.tick_count;
+        let mut last_frame = Instant::now();
+        loop {
+            for message in inbound.try_iter() {
+                match message {
+                    Inbound::Joined(id, stream) => {
+                        let Ok(mut player) = Player::join(id, stream) else {
+                            continue;
+                        };
+                        let mut greeting = vec![
+                            format!("you {} {}", id, player_color(id)),
+                            format!("state {}", shown.to_delta().encode()),
+                        ];
+                        greeting.extend(players.iter().filter_map(Player::cursor_message));
+                        if greeting
+                            .iter()
+                            .all(|line| player.send(OP_TEXT, line.as_bytes()))
+                        {
+                            players.push(player);
+                        }
+                    }
+                    Inbound::Text(id, line) => match command(&app.universe, &line) {
+                        Ok(Request::Intervene(intervention)) => app.universe.apply(intervention),
+                        Ok(Request::Cursor(cursor)) => {
+                            let Some(player) = players.iter_mut().find(|p| p.id == id) else {
+                                continue;
+                            };
+                            player.cursor = cursor;
+                            let moved = player
+                                .cursor_message()
+                                .unwrap_or_else(|| format!("cursor {} {}", id, player_color(id)));
+                            send_to(&mut players, |p| p != id, OP_TEXT, moved.as_bytes());
+                        }
+                        Err(message) => {
+                            let reply = format!("error: {}", message);
+                            send_to(&mut players, |p| p == id, OP_TEXT, reply.as_bytes());
+                        }
+                    },
+                    Inbound::Ping(id, payload) => {
+                        send_to(&mut players, |p| p == id, OP_PONG, &payload)
+                    }
+                    Inbound::Left(id) => {
+                        let count = players.len();
+                        players.retain(|p| p.id != id);
+                        if players.len() < count {
+                            let left = format!("left {}", id);
+                            send_to(&mut players, |_| true, OP_TEXT, left.as_bytes());
+                        }
+                    }
+                }
+            }
+
+            let now = Instant::now();
+            app.apply(AppAction::Advance(
+                now.duration_since(last_frame).as_secs_f64(),
+            ));
+            last_frame = now;
+            for line in app.scripts.take_output() {
+                println!("{}", line);
+            }
+
+            let delta = shown.diff(&app.universe);
+            if !delta.is_empty() || delta.tick != shown.tick_count {
+                let message = format!("delta {}", delta.encode());
+                let mut state = None;
+                players.retain_mut(|player| {
+                    if !player.behind {
+                        return player.send(OP_TEXT, message.as_bytes());
+                    }
+                    // A player that missed a delta starts over from the whole
+                    // universe.
+                    let state = state.get_or_insert_with(|| {
+                        format!("state {}", app.universe.to_delta().encode())
+                    });
+                    player.behind = false;
+                    player.send(OP_TEXT, state.as_bytes())
+                });
+                shown = app.universe.clone();
+            }
+
+            if self
+                .ticks
+                .is_some_and(|ticks| app.universe.tick_count - start >= ticks)
+            {
+                for player in &mut players {
+                    player.send(OP_CLOSE, &1000u16.to_be_bytes());
+                }
+                // Dropping the outboxes lets the writers finish what is queued.
+                let writers: Vec<_> = players
+                    .into_iter()
+                    .map(|player| (player.writer, player.stream))
+                    .collect();
+                let deadline = Instant::now() + CLOSE_WAIT;
+                for (writer, stream) in writers {
+                    while !writer.is_finished() && Instant::now() < deadline {
+                        thread::sleep(Duration::from_millis(1));
+                    }
+                    let _ = stream.shutdown(Shutdown::Both);
+                }
+                return Ok(());
+            }
+            thread::sleep(self.frame);
+        }
+    }
+}
+
+/// A connected client.
+struct Player {
+    id: usize,
+    /// Frames waiting for the player's writer thread.
+    outbox: SyncSender<(u8, Vec<u8>)>,
+    writer: JoinHandle<()>,
+    stream: TcpStream,
+    /// The cell the player's pointer is over, if it is over the grid.
+    cursor: Option<Vec<usize>>,
+    /// Whether a frame to the player was dropped, so it needs the whole
+    /// universe again before it can follow the deltas.
+    behind: bool,
+}
+
+impl Player {
+    /// Starts writing to a client that has completed the handshake.
+    fn join(id: usize, stream: TcpStream) -> io::Result<Self> {
+        let (outbox, frames) = mpsc::sync_channel::<(u8, Vec<u8>)>(OUTBOX);
+        let mut out = stream.try_clone()?;
+        let writer = thread::spawn(move || {
+            for (opcode, payload) in frames {
+                if send(&mut out, opcode, &payload).is_err() || opcode == OP_CLOSE {
+                    break;
+                }
+            }
+            let _ = out.shutdown(Shutdown::Both);
+        });
+        Ok(Player {
+            id,
+            outbox,
+            writer,
+            stream,
+            cursor: None,
+            behind: false,
+        })
+    }
+
+    /// Queues a frame for the player, returning `false` if it has gone. A
+    /// frame the outbox has no room for is dropped.
+    fn send(&mut self, opcode: u8, payload: &[u8]) -> bool {
+        match self.outbox.try_send((opcode, payload.to_vec())) {
+            Ok(()) => true,
+            Err(TrySendError::Full(_)) => {
+                self.behind = true;
+                true
+            }
+            Err(TrySendError::Disconnected(_)) => false,
+        }
+    }
+
+    /// The message showing the other players where this one's cursor is, if
+    /// it is over the grid.
+    fn cursor_message(&self) -> Option<String> {
+        let cursor = self.cursor.as_ref()?;
+        let mut message = format!("cursor {} {}", self.id, player_color(self.id));
+        for c in cursor {
+            message.push_str(&format!(" {}", c));
+        }
+        Some(message)
+    }
+}
+
+/// Sends a frame to every player whose ID `to` accepts, dropping those that
+/// have gone.
+fn send_to(players: &mut Vec<Player>, to: impl Fn(usize) -> bool, opcode: u8, payload: &[u8]) {
+    players.retain_mut(|player| !to(player.id) || player.send(opcode, payload));
+}
+
+/// The colors players' cursors are drawn in, handed out in turn.
+const PLAYER_COLORS: [&str; 8] = [
+    "#e6194b", "#3cb44b", "#ffe119", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6",
+];
+
+/// The color of player `id`'s cursor, as `#RRGGBB`.
+pub fn player_color(id: usize) -> &'static str {
+    PLAYER_COLORS[id % PLAYER_COLORS.len()]
+}
+
+/// Accepts connections for as long as the listener lasts, each on a thread
+/// of its own.
+fn accept(listener: TcpListener, inbox: Sender<Inbound>) {
+    for (id, stream) in listener.incoming().enumerate() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        let inbox = inbox.clone();
+        thread::spawn(move || {
+            let _ = connect(id, stream, &inbox);
+            let _ = inbox.send(Inbound::Left(id));
+        });
+    }
+}
+
+/// Carries out the handshake with a new client, then passes on its messages
+/// until it leaves.
+fn connect(id: usize, stream: TcpStream, inbox: &Sender<Inbound>) -> io::Result<()> {
+    stream.set_nodelay(true)?;
+    stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+    let mut writer = stream.try_clone()?;
+    let mut reader = BufReader::new(stream);
+    let Some(key) = read_handshake(&mut reader)? else {
+        writer.write_all(b"HTTP/1.1 400 Bad Request\r\nContent-Length: 0\r\n\r\n")?;
+        return Ok(());
+    };
+    write!(
+        writer,
+        "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(&key)
+    )?;
+    if inbox.send(Inbound::Joined(id, writer)).is_err() {
+        return Ok(());
+    }
+    let mut message = Vec::new();
+    loop {
+        let (fin, opcode, payload) = read_frame(&mut reader)?;
+        let inbound = match opcode {
+            OP_TEXT | OP_BINARY | OP_CONTINUATION => {
+                message.extend(payload);
+                if message.len() > MAX_MESSAGE {
+                    return Ok(());
+                }
+                if !fin {
+                    continue;
+                }
+                let text = String::from_utf8_lossy(&std::mem::take(&mut message)).into_owned();
+                Inbound::Text(id, text)
+            }
+            OP_PING => Inbound::Ping(id, payload),
+            OP_PONG => continue,
+            _ => return Ok(()),
+        };
+        if inbox.send(inbound).is_err() {
+            return Ok(());
+        }
+    }
+}
+
+/// Reads an HTTP upgrade request, returning its `Sec-WebSocket-Key`, or
+/// `None` if it has none.
+fn read_handshake(reader: &mut impl BufRead) -> io::Result<Option<String>> {
+    let mut key = None;
+    let mut read = 0;
+    loop {
+        let mut line = String::new();
+        let n = reader.read_line(&mut line)?;
+        read += n;
+        if n == 0 || read > MAX_REQUEST {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            return Ok(key);
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.trim().eq_ignore_ascii_case("sec-websocket-key")
+        {
+            key = Some(value.trim().to_string());
+        }
+    }
+}
+
+/// The `Sec-WebSocket-Accept` value answering a client's `Sec-WebSocket-Key`.
+pub fn accept_key(key: &str) -> String {
+    STANDARD.encode(sha1(format!("{}{}", key, HANDSHAKE_GUID).as_bytes()))
+}
+
+/// Reads one frame from a client, unmasking it: whether it ends a message,
+/// its opcode and its payload.
+fn read_frame(reader: &mut impl Read) -> io::Result<(bool, u8, Vec<u8>)> {
+    let mut head = [0; 2];
+    reader.read_exact(&mut head)?;
+    let (fin, opcode) = (head[0] & 0x80 != 0, head[0] & 0x0F);
+    // Clients must mask every frame they send.
+    if head[1] & 0x80 == 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "unmasked frame"));
+    }
+    let len = match head[1] & 0x7F {
+        126 => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len)?;
+            u16::from_be_bytes(len) as u64
+        }
+        127 => {
+            let mut len = [0; 8];
+            reader.read_exact(&mut len)?;
+            u64::from_be_bytes(len)
+        }
+        len => len as u64,
+    };
+    if len > MAX_MESSAGE as u64 {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "frame too long"));
+    }
+    let mut mask = [0; 4];
+    reader.read_exact(&mut mask)?;
+    let mut payload = vec![0; len as usize];
+    reader.read_exact(&mut payload)?;
+    for (i, byte) in payload.iter_mut().enumerate() {
+        *byte ^= mask[i % 4];
+    }
+    Ok((fin, opcode, payload))
+}
+
+/// Sends one unmasked, unfragmented frame.
+fn send(stream: &mut impl Write, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut frame = vec![0x80 | opcode];
+    match payload.len() {
+        len @ 0..=125 => frame.push(len as u8),
+        len @ 126..=0xFFFF => {
+            frame.push(126);
+            frame.extend((len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(127);
+            frame.extend((len as u64).to_be_bytes());
+        }
+    }
+    frame.extend(payload);
+    stream.write_all(&frame)
+}
+
+/// What a client's command asks for.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Request {
+    Intervene(Intervention),
+    /// Show the player's cursor over this cell to the others, or hide it.
+    Cursor(Option<Vec<usize>>),
+}
+
+/// What a client's command asks for; see the [module documentation](self).
+pub fn command(universe: &Universe, line: &str) -> Result<Request, String> {
+    let mut words = line.split_whitespace();
+    let name = words.next().ok_or("empty command")?;
+    let numbers = words
+        .map(|word| {
+            word.parse::<usize>()
+                .map_err(|_| format!("{} is not a coordinate", word))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let dims = universe.grid_dims.len();
+    if name == "cursor" && numbers.is_empty() {
+        return Ok(Request::Cursor(None));
+    }
+    let cells = match name {
+        "entangle" => 2,
+        "cursor" | "observe" | "operator" | "clear" | "disrupt" => 1,
+        _ => return Err(format!("unknown command {}", name)),
+    };
+    if numbers.len() != cells * dims {
+        return Err(format!("{} takes {} coordinates", name, cells * dims));
+    }
+    let mut indices = numbers.chunks(dims).map(|coord| {
+        universe
+            .get_index_from_coord(coord)
+            .ok_or_else(|| format!("{:?} is outside the grid", coord))
+    });
+    let idx = indices.next().unwrap()?;
+    Ok(Request::Intervene(match name {
+        "cursor" => return Ok(Request::Cursor(Some(numbers))),
+        "observe" => Intervention::Observe(idx),
+        "operator" => Intervention::SetOperator(idx),
+        "clear" => Intervention::ClearOperator(idx),
+        "disrupt" => Intervention::Disrupt(idx),
+        _ => {
+            let partner = indices.next().unwrap()?;
+            Intervention::Entangle(universe.grid[idx].id, universe.grid[partner].id)
+        }
+    }))
+}
+
+/// The SHA-1 digest of `data`, which the handshake calls for.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend((data.len() as u64 * 8).to_be_bytes());
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let t = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(word);
+            (e, d, c, b, a) = (d, c, b.rotate_left(30), a, t);
+        }
+        for (h, v) in h.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+    let mut digest = [0; 20];
+    for (out, h) in digest.chunks_mut(4).zip(h) {
+        out.copy_from_slice(&h.to_be_bytes());
+    }
+    digest
+}
@@ -0,0 +1,174 @@
+//! Saving and restoring the whole workspace, not just the grid.
+//!
+//! A [`Session`] pairs the universe's [`Scenario`] share code with a
+//! [`Workspace`] code describing the UI around it: the active tool, camera,
+//! overlays, selection and watch list. Restoring a session after switching
+//! render backends (or restarting) brings everything back as it was.
+
+use crate::app::{AppState, Camera, Overlays, ToolMode};
+use crate::share::{Reader, Scenario, ShareError, pack, unpack, write_varint};
+use std::fs;
+use std::path::Path;
+
+/// Every workspace code starts with this tag, which also versions the format.
+const PREFIX: &str = "EXW1-";
+
+//================================================================================
+// Workspace
+//================================================================================
+
+/// The serializable part of the UI state.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Workspace {
+    pub tool: ToolMode,
+    pub camera: Camera,
+    pub overlays: Overlays,
+    /// The first cell of a half-finished entanglement selection.
+    pub selection: Option<u64>,
+    pub watch_list: Vec<usize>,
+}
+
+impl Workspace {
+    /// Captures the workspace of the given application state.
+    pub fn capture(app: &AppState) -> Self {
+        Workspace {
+            tool: app.current_tool,
+            camera: app.camera,
+            overlays: app.overlays,
+            selection: app.entangle_first_partner,
+            watch_list: app.watch_list.clone(),
+        }
+    }
+
+    /// Serializes the workspace into a compressed, URL-safe code.
+    pub fn encode(&self) -> String {
+        let mut buf = Vec::new();
+        let tool = ToolMode::ALL.iter().position(|&t| t == self.tool);
+        buf.push(tool.unwrap_or(0) as u8);
+        for value in [
+            self.camera.offset[0],
+            self.camera.offset[1],
+            self.camera.zoom,
+        ] {
+            buf.extend_from_slice(&value.to_le_bytes());
+        }
+        let flags = [
+            self.overlays.entanglement_flashes,
+            self.overlays.watch_markers,
+            self.overlays.help,
+            self.overlays.plugins,
+            self.overlays.plot,
+            self.overlays.entanglement_links,
+        ];
+        buf.push(flags.iter().rev().fold(0, |acc, &f| (acc << 1) | f as u8));
+        // The selection is stored off by one so that zero can mean "none".
+        write_varint(&mut buf, self.selection.map_or(0, |id| id + 1));
+        write_varint(&mut buf, self.watch_list.len() as u64);
+        for &idx in &self.watch_list {
+            write_varint(&mut buf, idx as u64);
+        }
+        pack(PREFIX, &buf)
+    }
+
+    /// Parses a code produced by [`Workspace::encode`].
+    pub fn decode(code: &str) -> Result<Self, ShareError> {
+        let buf = unpack(PREFIX, code)?;
+        let mut reader = Reader::new(&buf);
+        let tool = *ToolMode::ALL
+            .get(reader.byte()? as usize)
+            .ok_or(ShareError::Invalid("unknown tool"))?;
+        let offset = [reader.f64()?, reader.f64()?];
+        let zoom = reader.f64()?;
+        if !(zoom.is_finite() && zoom > 0.0 && offset.iter().all(|o| o.is_finite())) {
+            return Err(ShareError::Invalid("camera out of range"));
+        }
+        let flags = reader.byte()?;
+        let overlays = Overlays {
+            entanglement_flashes: flags & 1 != 0,
+            watch_markers: flags & 2 != 0,
+            help: flags & 4 != 0,
+            plugins: flags & 8 != 0,
+            plot: flags & 16 != 0,
+            entanglement_links: flags & 32 != 0,
+        };
+        let selection = reader.varint()?.checked_sub(1);
+        let count = reader.varint()? as usize;
+        let mut watch_list = Vec::with_capacity(count.min(buf.len()));
+        for _ in 0..count {
+            watch_list.push(reader.varint()? as usize);
+        }
+        Ok(Workspace {
+            tool,
+            camera: Camera { offset, zoom },
+            overlays,
+            selection,
+            watch_list,
+        })
+    }
+}
+
+//================================================================================
+// Session
+//================================================================================
+
+/// A universe together with the workspace it was being explored in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Session {
+    pub scenario: Scenario,
+    pub workspace: Workspace,
+}
+
+impl Session {
+    /// Captures the full session of the given application state.
+    pub fn capture(app: &AppState) -> Self {
+        Session {
+            scenario: Scenario::capture(&app.universe),
+            workspace: Workspace::capture(app),
+        }
+    }
+
+    /// Rebuilds the universe and puts the workspace back in place.
+    ///
+    /// Watched or selected cells that no longer exist in the rebuilt grid are dropped.
+    pub fn restore(self, app: &mut AppState) {
+        app.replace_universe(self.scenario.build());
+        let cells = app.universe.grid.len();
+        let workspace = self.workspace;
+        app.current_tool = workspace.tool;
+        app.camera = workspace.camera;
+        app.overlays = workspace.overlays;
+        app.entangle_first_partner = workspace
+            .selection
+            .filter(|&id| app.universe.index_of_id(id).is_some());
+        app.watch_list = workspace.watch_list;
+        app.watch_list.retain(|&idx| idx < cells);
+        app.entanglement_flashes.clear();
+    }
+
+    /// The session as text: the scenario code and the workspace code on separate lines.
+    pub fn to_text(&self) -> String {
+        format!("{}\n{}\n", self.scenario.encode(), self.workspace.encode())
+    }
+
+    /// Parses text produced by [`Session::to_text`].
+    pub fn from_text(text: &str) -> Result<Self, ShareError> {
+        let mut lines = text.lines().filter(|line| !line.trim().is_empty());
+        let scenario = Scenario::decode(lines.next().ok_or(ShareError::Truncated)?)?;
+        let workspace = Workspace::decode(lines.next().ok_or(ShareError::Truncated)?)?;
+        Ok(Session {
+            scenario,
+            workspace,
+        })
+    }
+
+    /// Writes the session to a file.
+    pub fn save(&self, path: &Path) -> std::io::Result<()> {
+        fs::write(path, self.to_text())
+    }
+
+    /// Reads a session from a file.
+    pub fn load(path: &Path) -> Result<Self, ShareError> {
+        let text = fs::read_to_string(path).map_err(ShareError::Io)?;
+        Self::from_text(&text)
+    }
+}
@@ -0,0 +1,493 @@
+//! The settings file, `existons.toml`, read at startup and reloaded live.
+//!
+//! The file sets what [`Config::new`] otherwise compiles in, grouped into
+//! sections:
+//!
+//! ```toml
+//! [grid]
+//! dims = [120, 80]
+//! ga_dims = 3
+//! topology = "square"      # or "hexagonal"
+//! cell_size = 8.0
+//!
+//! [rates]
+//! observation = 0.0005
+//! decay = 0.01
+//! fluctuation = 0.001
+//! ticks_per_second = 60    # 0 ticks once per frame
+//!
+//! [tools]
+//! observation_radius = 50.0
+//! disruption_radius = 50.0
+//! disruption_strength = 1.0
+//! disruption_falloff = "linear"   # or "hard" or "smooth"
+//! disruption_amplitude = 0.6
+//! paint_radius = 50.0
+//!
+//! [colors]
+//! background = [0.0, 0.0, 0.0, 1.0]
+//! toolbar = [0.1, 0.1, 0.12, 1.0]
+//! text = [1.0, 1.0, 1.0, 0.9]
+//! font_size = 14
+//! ```
+//!
+//! Settings left out keep their compiled-in values. A [`SettingsWatcher`]
+//! notices when the file is saved, and [`SettingsFile::apply_changes`] applies
+//! what changed to the running app. The `[grid]` section shapes the universe
+//! and the window, so changes to it wait for a restart.
+
+use crate::app::{AppState, Config, Falloff};
+use crate::panel::Setting;
+use crate::slice::Slice;
+use crate::universe::Topology;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::iter::Peekable;
+use std::path::{Path, PathBuf};
+use std::str::Chars;
+use std::time::{Duration, Instant, SystemTime};
+
+/// A value in a TOML file, of the kinds the settings and keybindings use.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum TomlValue {
+    Bool(bool),
+    Number(f64),
+    String(String),
+    Array(Vec<TomlValue>),
+}
+
+/// One `key = value` line of a TOML file, under the section it sits in.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct TomlEntry {
+    pub line: usize,
+    pub section: String,
+    pub key: String,
+    pub value: TomlValue,
+}
+
+/// The `key = value` lines of `text`: a subset of TOML with `[section]`
+/// headers, `#` comments, booleans, numbers, basic and literal strings and
+/// arrays of them. Fails with the number of the first line it cannot read.
+pub(crate) fn toml_entries(text: &str) -> Result<Vec<TomlEntry>, usize> {
+    let mut entries = Vec::new();
+    let mut section = String::new();
+    for (i, line) in text.lines().enumerate() {
+        let line_number = i + 1;
+        let line = strip_comment(line).trim();
+        if line.is_empty() {
+            continue;
+        }
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            section = name.trim().to_string();
+            continue;
+        }
+        let (key, value) = line.split_once('=').ok_or(line_number)?;
+        let key = key.trim().trim_matches('"');
+        let mut chars = value.trim().chars().peekable();
+        let value = parse_value(&mut chars).ok_or(line_number)?;
+        if key.is_empty() || chars.next().is_some() {
+            return Err(line_number);
+        }
+        entries.push(TomlEntry {
+            line: line_number,
+            section: section.clone(),
+            key: key.to_string(),
+            value,
+        });
+    }
+    Ok(entries)
+}
+
+/// `line` up to a `#` that is not inside a string.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in line.char_indices() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some('"') if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if c == '#' => return &line[..i],
+            None => {}
+        }
+    }
+    line
+}
+
+/// Reads one value off the front of `chars`; basic strings may escape `\\`
+/// and `\"`.
+fn parse_value(chars: &mut Peekable<Chars>) -> Option<TomlValue> {
+    match *chars.peek()? {
+        quote @ ('"' | '\'') => {
+            chars.next();
+            let mut string = String::new();
+            loop {
+                match chars.next()? {
+                    c if c == quote => break,
+                    '\\' if quote == '"' => string.push(chars.next()?),
+                    c => string.push(c),
+                }
+            }
+            Some(TomlValue::String(string))
+        }
+        '[' => {
+            chars.next();
+            let mut items = Vec::new();
+            loop {
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if chars.next_if_eq(&']').is_some() {
+                    break;
+                }
+                items.push(parse_value(chars)?);
+                while chars.next_if(|c| c.is_whitespace()).is_some() {}
+                if chars.next_if_eq(&',').is_none() {
+                    chars.next_if_eq(&']')?;
+                    break;
+                }
+            }
+            Some(TomlValue::Array(items))
+        }
+        _ => {
+            let mut word = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace() && !matches!(c, ',' | ']')) {
+                word.push(c);
+            }
+            match word.as_str() {
+                "true" => Some(TomlValue::Bool(true)),
+                "false" => Some(TomlValue::Bool(false)),
+                _ => word.replace('_', "").parse().ok().map(TomlValue::Number),
+            }
+        }
+    }
+}
+
+/// The reasons a settings file can fail to load.
+#[derive(Debug)]
+pub enum SettingsError {
+    /// The file exists but could not be read.
+    Io(io::Error),
+    /// The line is not a `[section]` header or `name = value`.
+    Syntax { line: usize },
+    /// The line names a setting that does not exist.
+    UnknownSetting { line: usize, name: String },
+    /// The line gives a setting a value it cannot take.
+    Invalid { line: usize, name: String },
+}
+
+impl fmt::Display for SettingsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SettingsError::Io(e) => write!(f, "could not read settings: {}", e),
+            SettingsError::Syntax { line } => {
+                write!(f, "line {}: expected [section] or name = value", line)
+            }
+            SettingsError::UnknownSetting { line, name } => {
+                write!(f, "line {}: no setting is called {:?}", line, name)
+            }
+            SettingsError::Invalid { line, name } => {
+                write!(f, "line {}: {} cannot take that value", line, name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SettingsError {}
+
+/// The settings a file sets; those it leaves out are `None`. See the
+/// [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SettingsFile {
+    pub grid_dims: Option<Vec<usize>>,
+    pub ga_dims: Option<usize>,
+    pub topology: Option<Topology>,
+    pub cell_size: Option<f64>,
+    pub observation_rate: Option<f64>,
+    pub decay_rate: Option<f64>,
+    pub fluctuation_rate: Option<f64>,
+    /// Ticks per second of real time; 0 ticks once per frame.
+    pub ticks_per_second: Option<f64>,
+    pub observation_radius: Option<f64>,
+    pub disruption_radius: Option<f64>,
+    pub disruption_strength: Option<f64>,
+    pub disruption_falloff: Option<Falloff>,
+    pub disruption_amplitude: Option<f64>,
+    pub paint_radius: Option<f64>,
+    pub background_color: Option<[f32; 4]>,
+    pub toolbar_color: Option<[f32; 4]>,
+    pub text_color: Option<[f32; 4]>,
+    pub font_size: Option<u32>,
+}
+
+impl SettingsFile {
+    /// Reads the settings in `text`.
+    pub fn parse(text: &str) -> Result<Self, SettingsError> {
+        let entries = toml_entries(text).map_err(|line| SettingsError::Syntax { line })?;
+        let mut file = SettingsFile::default();
+        for entry in entries {
+            let name = format!("{}.{}", entry.section, entry.key);
+            let invalid = || SettingsError::Invalid {
+                line: entry.line,
+                name: name.clone(),
+            };
+            let number = |min: f64| match entry.value {
+                TomlValue::Number(n) if n >= min && n.is_finite() => Some(n),
+                _ => None,
+            };
+            let count = |min: f64| number(min).filter(|n| n.fract() == 0.0).map(|n| n as usize);
+            let color = || match &entry.value {
+                TomlValue::Array(items) if items.len() == 4 => {
+                    let mut color = [0.0; 4];
+                    for (channel, item) in color.iter_mut().zip(items) {
+                        match item {
+                            TomlValue::Number(n) if (0.0..=1.0).contains(n) => *channel = *n as f32,
+                            _ => return None,
+                        }
+                    }
+                    Some(color)
+                }
+                _ => None,
+            };
+            match name.as_str() {
+                "grid.dims" => {
+                    let TomlValue::Array(items) = &entry.value else {
+                        return Err(invalid());
+                    };
+                    let dims = items
+                        .iter()
+                        .map(|item| match item {
+                            TomlValue::Number(n) if *n >= 1.0 && n.fract() == 0.0 => {
+                                Some(*n as usize)
+                            }
+                            _ => None,
+                        })
+                        .collect::<Option<Vec<_>>>()
+                        .filter(|dims| !dims.is_empty());
+                    file.grid_dims = Some(dims.ok_or_else(invalid)?);
+                }
+                "grid.ga_dims" => file.ga_dims = Some(count(1.0).ok_or_else(invalid)?),
+                "grid.topology" => {
+                    file.topology = Some(match &entry.value {
+                        TomlValue::String(s) if s.eq_ignore_ascii_case("square") => {
+                            Topology::Square
+                        }
+                        TomlValue::String(s) if s.eq_ignore_ascii_case("hexagonal") => {
+                            Topology::Hexagonal
+                        }
+                        _ => return Err(invalid()),
+                    })
+                }
+                "grid.cell_size" => file.cell_size = Some(number(1.0).ok_or_else(invalid)?),
+                "rates.observation" => {
+                    file.observation_rate = Some(number(0.0).ok_or_else(invalid)?)
+                }
+                "rates.decay" => file.decay_rate = Some(number(0.0).ok_or_else(invalid)?),
+                "rates.fluctuation" => {
+                    file.fluctuation_rate = Some(number(0.0).ok_or_else(invalid)?)
+                }
+                "rates.ticks_per_second" => {
+                    file.ticks_per_second = Some(number(0.0).ok_or_else(invalid)?)
+                }
+                "tools.observation_radius" => {
+                    file.observation_radius = Some(number(0.0).ok_or_else(invalid)?)
+                }
+                "tools.disruption_radius" => {
+                    file.disruption_radius = Some(number(0.0).ok_or_else(invalid)?)
+                }
+                "tools.disruption_strength" => {
+                    let strength = number(0.0).filter(|&n| n <= 1.0);
+                    file.disruption_strength = Some(strength.ok_or_else(invalid)?)
+                }
+                "tools.disruption_falloff" => {
+                    file.disruption_falloff = Some(match &entry.value {
+                        TomlValue::String(s) if s.eq_ignore_ascii_case("hard") => Falloff::Hard,
+                        TomlValue::String(s) if s.eq_ignore_ascii_case("linear") => Falloff::Linear,
+                        TomlValue::String(s) if s.eq_ignore_ascii_case("smooth") => Falloff::Smooth,
+                        _ => return Err(invalid()),
+                    })
+                }
+                "tools.disruption_amplitude" => {
+                    file.disruption_amplitude = Some(number(0.0).ok_or_else(invalid)?)
+                }
+                "tools.paint_radius" => file.paint_radius = Some(number(0.0).ok_or_else(invalid)?),
+                "colors.background" => file.background_color = Some(color().ok_or_else(invalid)?),
+                "colors.toolbar" => file.toolbar_color = Some(color().ok_or_else(invalid)?),
+                "colors.text" => file.text_color = Some(color().ok_or_else(invalid)?),
+                "colors.font_size" => file.font_size = Some(count(1.0).ok_or_else(invalid)? as u32),
+                _ => {
+                    return Err(SettingsError::UnknownSetting {
+                        line: entry.line,
+                        name,
+                    });
+                }
+            }
+        }
+        Ok(file)
+    }
+
+    /// Reads the settings file at `path`; a missing file sets nothing.
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        match fs::read_to_string(path) {
+            Ok(text) => SettingsFile::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(SettingsFile::default()),
+            Err(e) => Err(SettingsError::Io(e)),
+        }
+    }
+
+    /// Overrides what the file sets in `config`, before the app starts. The
+    /// window is sized to fit the grid at the file's cell size.
+    pub fn apply(&self, config: &mut Config) {
+        if let Some(dims) = &self.grid_dims {
+            config.grid_dims = dims.clone();
+            config.slice = Slice::new(dims.len());
+        }
+        set(&mut config.ga_dims, self.ga_dims);
+        set(&mut config.topology, self.topology);
+        set(&mut config.cell_size, self.cell_size);
+        if self.grid_dims.is_some() || self.cell_size.is_some() {
+            let length = |axis: usize| config.grid_dims.get(axis).copied().unwrap_or(100) as f64;
+            config.window_size = [length(0) * config.cell_size, length(1) * config.cell_size];
+        }
+        set(&mut config.observation_rate, self.observation_rate);
+        set(&mut config.decay_rate, self.decay_rate);
+        set(&mut config.fluctuation_rate, self.fluctuation_rate);
+        if let Some(rate) = self.ticks_per_second {
+            config.ticks_per_second = (rate > 0.0).then_some(rate);
+        }
+        set(&mut config.observation_radius, self.observation_radius);
+        set(&mut config.disruption_radius, self.disruption_radius);
+        set(&mut config.disruption_strength, self.disruption_strength);
+        set(&mut config.disruption_falloff, self.disruption_falloff);
+        set(&mut config.disruption_amplitude, self.disruption_amplitude);
+        set(&mut config.paint_radius, self.paint_radius);
+        set(&mut config.background_color, self.background_color);
+        set(&mut config.toolbar_color, self.toolbar_color);
+        set(&mut config.text_color, self.text_color);
+        set(&mut config.font_size, self.font_size);
+    }
+
+    /// Applies the settings that differ from `previous`, the file as it was
+    /// last loaded, to the running `app`: rates reach the live universe and
+    /// the tick rate its timestep. Settings the file no longer sets keep their
+    /// current values. Returns the names of the changed `[grid]` settings,
+    /// which only take effect after a restart.
+    pub fn apply_changes(&self, previous: &SettingsFile, app: &mut AppState) -> Vec<&'static str> {
+        let changed = SettingsFile {
+            grid_dims: None,
+            ga_dims: None,
+            topology: None,
+            cell_size: None,
+            observation_rate: changed(self.observation_rate, previous.observation_rate),
+            decay_rate: changed(self.decay_rate, previous.decay_rate),
+            fluctuation_rate: changed(self.fluctuation_rate, previous.fluctuation_rate),
+            ticks_per_second: changed(self.ticks_per_second, previous.ticks_per_second),
+            observation_radius: changed(self.observation_radius, previous.observation_radius),
+            disruption_radius: changed(self.disruption_radius, previous.disruption_radius),
+            disruption_strength: changed(self.disruption_strength, previous.disruption_strength),
+            disruption_falloff: changed(self.disruption_falloff, previous.disruption_falloff),
+            disruption_amplitude: changed(self.disruption_amplitude, previous.disruption_amplitude),
+            paint_radius: changed(self.paint_radius, previous.paint_radius),
+            background_color: changed(self.background_color, previous.background_color),
+            toolbar_color: changed(self.toolbar_color, previous.toolbar_color),
+            text_color: changed(self.text_color, previous.text_color),
+            font_size: changed(self.font_size, previous.font_size),
+        };
+        changed.apply(&mut app.config);
+        if let Some(rate) = changed.observation_rate {
+            Setting::ObservationRate.set(app, rate);
+        }
+        if let Some(rate) = changed.decay_rate {
+            Setting::DecayRate.set(app, rate);
+        }
+        if let Some(rate) = changed.fluctuation_rate {
+            Setting::FluctuationRate.set(app, rate);
+        }
+        if let Some(rate) = changed.ticks_per_second {
+            Setting::TickRate.set(app, rate);
+        }
+
+        let mut restart = Vec::new();
+        if self.grid_dims != previous.grid_dims {
+            restart.push("grid.dims");
+        }
+        if self.ga_dims != previous.ga_dims {
+            restart.push("grid.ga_dims");
+        }
+        if self.topology != previous.topology {
+            restart.push("grid.topology");
+        }
+        if self.cell_size != previous.cell_size {
+            restart.push("grid.cell_size");
+        }
+        restart
+    }
+}
+
+fn set<T>(field: &mut T, value: Option<T>) {
+    if let Some(value) = value {
+        *field = value;
+    }
+}
+
+/// `value` if it is set and differs from `previous`.
+fn changed<T: PartialEq>(value: Option<T>, previous: Option<T>) -> Option<T> {
+    value.filter(|value| previous.as_ref() != Some(value))
+}
+
+/// Watches a settings file for saves by its modification time, checking at
+/// most every [`SettingsWatcher::INTERVAL`].
+#[derive(Debug, Clone)]
+pub struct SettingsWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    checked: Instant,
+    /// The file as last loaded, which changes are measured against.
+    pub current: SettingsFile,
+}
+
+impl SettingsWatcher {
+    pub const INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Watches `path` from now on, taking `current` as what it holds.
+    pub fn new(path: impl Into<PathBuf>, current: SettingsFile) -> Self {
+        let path = path.into();
+        let modified = modified(&path);
+        SettingsWatcher {
+            path,
+            modified,
+            checked: Instant::now(),
+            current,
+        }
+    }
+
+    /// Reads the file again if it changed since the last look and `now` is
+    /// far enough on, applying the changes to `app`. Returns what happened:
+    /// the `[grid]` settings waiting for a restart, or why the file could not
+    /// be read, which leaves the app as it was.
+    pub fn poll(
+        &mut self,
+        now: Instant,
+        app: &mut AppState,
+    ) -> Option<Result<Vec<&'static str>, SettingsError>> {
+        if now.duration_since(self.checked) < Self::INTERVAL {
+            return None;
+        }
+        self.checked = now;
+        let modified = modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        Some(SettingsFile::load(&self.path).map(|file| {
+            let restart = file.apply_changes(&self.current, app);
+            self.current = file;
+            restart
+        }))
+    }
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
@@ -0,0 +1,127 @@
+//! Shapes the Operator tool draws, as the grid cells they cover.
+//!
+//! A [`Shape`] is dragged out between two points of the viewed plane and
+//! rasterized with integer arithmetic only: lines with Bresenham's algorithm,
+//! rectangles as four lines, and circles with the midpoint algorithm. Points
+//! are signed `[x, y]` plane coordinates and may lie off the grid; callers
+//! keep the ones that land on it.
+
+/// What a drag with the Operator tool draws.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Shape {
+    /// Every cell the cursor passes over while the button is held.
+    #[default]
+    Freehand,
+    /// A straight line from where the drag started to where it ended.
+    Line,
+    /// The outline of the rectangle with the two ends as opposite corners.
+    Rectangle,
+    /// A circle around where the drag started, through where it ended.
+    Circle,
+}
+
+impl Shape {
+    /// Every shape, in the order the tool cycles through them.
+    pub const ALL: [Shape; 4] = [
+        Shape::Freehand,
+        Shape::Line,
+        Shape::Rectangle,
+        Shape::Circle,
+    ];
+
+    /// The next shape, wrapping around.
+    pub fn next(self) -> Self {
+        let pos = Self::ALL.iter().position(|&s| s == self).unwrap_or(0);
+        Self::ALL[(pos + 1) % Self::ALL.len()]
+    }
+
+    /// A short label for the UI.
+    pub fn name(self) -> &'static str {
+        match self {
+            Shape::Freehand => "freehand",
+            Shape::Line => "line",
+            Shape::Rectangle => "rectangle",
+            Shape::Circle => "circle",
+        }
+    }
+
+    /// The points of the shape dragged from `from` to `to`, each once, or
+    /// none for [`Shape::Freehand`], which draws as it goes.
+    pub fn points(self, from: [i64; 2], to: [i64; 2]) -> Vec<[i64; 2]> {
+        let mut points = match self {
+            Shape::Freehand => Vec::new(),
+            Shape::Line => line(from, to),
+            Shape::Rectangle => rectangle(from, to),
+            Shape::Circle => {
+                let [dx, dy] = [to[0] - from[0], to[1] - from[1]];
+                let radius = ((dx * dx + dy * dy) as f64).sqrt().round() as i64;
+                circle(from, radius)
+            }
+        };
+        points.sort_unstable();
+        points.dedup();
+        points
+    }
+}
+
+/// The points of the line from `from` to `to`, ends included, by Bresenham's
+/// algorithm.
+pub fn line(from: [i64; 2], to: [i64; 2]) -> Vec<[i64; 2]> {
+    let [dx, dy] = [(to[0] - from[0]).abs(), -(to[1] - from[1]).abs()];
+    let [sx, sy] = [(to[0] - from[0]).signum(), (to[1] - from[1]).signum()];
+    let [mut x, mut y] = from;
+    let mut err = dx + dy;
+    let mut points = vec![from];
+    while [x, y] != to {
+        let e2 = 2 * err;
+        if e2 >= dy {
+            err += dy;
+            x += sx;
+        }
+        if e2 <= dx {
+            err += dx;
+            y += sy;
+        }
+        points.push([x, y]);
+    }
+    points
+}
+
+/// The outline of the rectangle with opposite corners `a` and `b`.
+pub fn rectangle(a: [i64; 2], b: [i64; 2]) -> Vec<[i64; 2]> {
+    let corners = [a, [b[0], a[1]], b, [a[0], b[1]]];
+    (0..4)
+        .flat_map(|i| line(corners[i], corners[(i + 1) % 4]))
+        .collect()
+}
+
+/// The points of the circle of `radius` around `center`, by the midpoint
+/// algorithm. A radius of 0 gives just the center.
+pub fn circle(center: [i64; 2], radius: i64) -> Vec<[i64; 2]> {
+    let [cx, cy] = center;
+    let (mut x, mut y) = (radius.max(0), 0);
+    let mut err = 1 - x;
+    let mut points = Vec::new();
+    while x >= y {
+        for [px, py] in [
+            [x, y],
+            [y, x],
+            [-y, x],
+            [-x, y],
+            [-x, -y],
+            [-y, -x],
+            [y, -x],
+            [x, -y],
+        ] {
+            points.push([cx + px, cy + py]);
+        }
+        y += 1;
+        if err < 0 {
+            err += 2 * y + 1;
+        } else {
+            x -= 1;
+            err += 2 * (y - x) + 1;
+        }
+    }
+    points
+}
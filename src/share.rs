@@ -0,0 +1,752 @@
+//! Compact, URL-safe "share codes" for reproducible scenarios.
+//!
+//! A share code captures everything needed to rebuild a run: the universe's
+//! configuration, its RNG seed and the log of user interventions. Because the
+//! universe is fully deterministic given those, the receiver replays the log
+//! and ends up with exactly the same state. The binary payload is deflated and
+//! encoded as URL-safe base64 so it can be pasted into chat messages or issues.
+
+use crate::entanglement::{CollapseSpeed, Decoherence, PairOperator};
+use crate::existon::CollapsePolicy;
+use crate::field::{ParameterField, Rate};
+use crate::ga_core::{Mod3, Multivector};
+use crate::pattern::Pattern;
+use crate::universe::{
+    BoundaryCondition, Intervention, LoggedIntervention, Neighborhood, RandomScheme, RegionOp,
+    Topology, Universe, UpdateOrder,
+};
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use flate2::Compression;
+use flate2::read::DeflateDecoder;
+use flate2::write::DeflateEncoder;
+use std::fmt;
+use std::io::{Read, Write};
+use std::ops::Range;
+
+/// Every share code starts with this tag, which also versions the format.
+const PREFIX: &str = "EXS1-";
+
+/// Upper bounds that keep a malformed code from allocating an absurd universe.
+const MAX_CELLS: usize = 1 << 24;
+/// The most dimensions of an algebra a share code may carry, whose
+/// multivectors have 4096 coefficients.
+pub const MAX_GA_DIMS: usize = 12;
+const MAX_GRID_DIMS: usize = 8;
+
+//================================================================================
+// Errors
+//================================================================================
+
+/// The reasons a share code (or a session file built from them) can fail to load.
+#[derive(Debug)]
+pub enum ShareError {
+    /// A file holding codes could not be read.
+    Io(std::io::Error),
+    /// The text does not start with the expected tag.
+    UnknownFormat,
+    /// The text after the tag is not valid URL-safe base64.
+    Base64(base64::DecodeError),
+    /// The payload could not be decompressed.
+    Decompress(std::io::Error),
+    /// The payload ended before all fields were read.
+    Truncated,
+    /// The payload was read but describes an impossible scenario.
+    Invalid(&'static str),
+}
+
+impl fmt::Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ShareError::Io(e) => write!(f, "could not read share code: {}", e),
+            ShareError::UnknownFormat => write!(f, "not an existons share code"),
+            ShareError::Base64(e) => write!(f, "share code is not valid base64: {}", e),
+            ShareError::Decompress(e) => write!(f, "share code payload is corrupt: {}", e),
+            ShareError::Truncated => write!(f, "share code is truncated"),
+            ShareError::Invalid(what) => write!(f, "share code is invalid: {}", what),
+        }
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+//================================================================================
+// Scenario
+//================================================================================
+
+/// A reproducible setup: configuration, seed and intervention log.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Scenario {
+    /// The grid the run starts on; later resizes are in the intervention log.
+    pub grid_dims: Vec<usize>,
+    pub ga_dims: usize,
+    pub observation_rate: f64,
+    pub decay_rate: f64,
+    pub fluctuation_rate: f64,
+    pub entanglement_percentage: f64,
+    pub decoherence: Decoherence,
+    pub collapse_speed: CollapseSpeed,
+    pub pair_operator: PairOperator,
+    pub operator_move_interval: u64,
+    pub collapse_policy: CollapsePolicy,
+    pub update_order: UpdateOrder,
+    pub random_scheme: RandomScheme,
+    /// Whether ticks follow the reversible second-order rule.
+    pub reversible: bool,
+    /// Per-cell rate overrides, in [`Rate::ALL`] order, covering `grid_dims`.
+    pub parameter_fields: [Option<ParameterField>; 3],
+    pub neighborhood: Neighborhood,
+    pub topology: Topology,
+    pub seed: u64,
+    /// The number of ticks to simulate when rebuilding.
+    pub ticks: u64,
+    pub interventions: Vec<LoggedIntervention>,
+}
+
+impl Scenario {
+    /// Captures the scenario that reproduces the universe's current state.
+    ///
+    /// Parameter fields are recorded on the grid the run started on, so after a
+    /// resize the rebuilt fields only match inside the original grid.
+    pub fn capture(universe: &Universe) -> Self {
+        let initial_dims = universe.initial_dims();
+        let field = |rate| {
+            universe
+                .parameter_field(rate)
+                .map(|field| field.resized(&universe.grid_dims, initial_dims))
+        };
+        Scenario {
+            grid_dims: initial_dims.to_vec(),
+            ga_dims: universe.ga_dims,
+            observation_rate: universe.observation_rate,
+            decay_rate: universe.decay_rate,
+            fluctuation_rate: universe.fluctuation_rate,
+            entanglement_percentage: universe.entanglement_percentage,
+            decoherence: universe.decoherence,
+            collapse_speed: universe.collapse_speed,
+            pair_operator: universe.pair_operator.clone(),
+            operator_move_interval: universe.operator_move_interval,
+            collapse_policy: universe.collapse_policy,
+            update_order: universe.update_order(),
+            random_scheme: universe.random_scheme(),
+            reversible: universe.is_reversible(),
+            parameter_fields: Rate::ALL.map(field),
+            neighborhood: universe.neighborhood().clone(),
+            topology: universe.topology(),
+            seed: universe.seed,
+            ticks: universe.tick_count,
+            interventions: universe.interventions.clone(),
+        }
+    }
+
+    /// Rebuilds the universe by replaying the intervention log from the seed.
+    pub fn build(&self) -> Universe {
+        let mut universe = Universe::with_seed(self.grid_dims.clone(), self.ga_dims, self.seed);
+        universe.observation_rate = self.observation_rate;
+        universe.decay_rate = self.decay_rate;
+        universe.fluctuation_rate = self.fluctuation_rate;
+        universe.decoherence = self.decoherence;
+        universe.collapse_speed = self.collapse_speed;
+        universe.pair_operator = self.pair_operator.clone();
+        universe.operator_move_interval = self.operator_move_interval;
+        universe.collapse_policy = self.collapse_policy;
+        universe.set_update_order(self.update_order);
+        universe.set_random_scheme(self.random_scheme);
+        universe.set_reversible(self.reversible);
+        for (rate, field) in Rate::ALL.into_iter().zip(&self.parameter_fields) {
+            universe.set_parameter_field(rate, field.clone());
+        }
+        universe.set_neighborhood(self.neighborhood.clone());
+        universe.set_topology(self.topology);
+        if universe.entanglement_percentage != self.entanglement_percentage {
+            universe.set_entanglement_percentage(self.entanglement_percentage);
+        }
+        universe.replay(&self.interventions, self.ticks)
+    }
+
+    /// Serializes the scenario into a compressed, URL-safe share code.
+    pub fn encode(&self) -> String {
+        let mut buf = Vec::new();
+        write_grid_dims(&mut buf, &self.grid_dims);
+        write_varint(&mut buf, self.ga_dims as u64);
+        for rate in [
+            self.observation_rate,
+            self.decay_rate,
+            self.fluctuation_rate,
+            self.entanglement_percentage,
+            self.decoherence.rate,
+            self.decoherence.threshold,
+        ] {
+            buf.extend_from_slice(&rate.to_le_bytes());
+        }
+        buf.push(self.decoherence.refresh_on_fire as u8);
+        match self.collapse_speed {
+            CollapseSpeed::Instant => buf.push(0),
+            CollapseSpeed::Limited(speed) => {
+                buf.push(1);
+                buf.extend_from_slice(&speed.to_le_bytes());
+            }
+        }
+        write_pair_operator(&mut buf, Some(&self.pair_operator));
+        write_varint(&mut buf, self.operator_move_interval);
+        let policy = CollapsePolicy::ALL
+            .iter()
+            .position(|&p| p == self.collapse_policy);
+        buf.push(policy.unwrap_or(0) as u8);
+        let order = UpdateOrder::ALL
+            .iter()
+            .position(|&o| o == self.update_order);
+        buf.push(order.unwrap_or(0) as u8);
+        let scheme = RandomScheme::ALL
+            .iter()
+            .position(|&r| r == self.random_scheme);
+        buf.push(scheme.unwrap_or(0) as u8);
+        buf.push(self.reversible as u8);
+        for field in &self.parameter_fields {
+            match field {
+                Some(field) => {
+                    buf.push(1);
+                    for value in field.values() {
+                        buf.extend_from_slice(&value.to_le_bytes());
+                    }
+                }
+                None => buf.push(0),
+            }
+        }
+        match &self.neighborhood {
+            Neighborhood::Moore { radius } => write_tagged(&mut buf, 0, &[*radius as u64]),
+            Neighborhood::VonNeumann => buf.push(1),
+            Neighborhood::Custom(offsets) => {
+                write_tagged(&mut buf, 2, &[offsets.len() as u64]);
+                for offset in offsets {
+                    write_varint(&mut buf, offset.len() as u64);
+                    for &o in offset {
+                        write_signed(&mut buf, o);
+                    }
+                }
+            }
+        }
+        let topology = Topology::ALL.iter().position(|&t| t == self.topology);
+        buf.push(topology.unwrap_or(0) as u8);
+        buf.extend_from_slice(&self.seed.to_le_bytes());
+        write_varint(&mut buf, self.ticks);
+
+        // Ticks are stored as deltas, which keeps long logs small.
+        write_varint(&mut buf, self.interventions.len() as u64);
+        let mut last_tick = 0;
+        for entry in &self.interventions {
+            write_varint(&mut buf, entry.tick - last_tick);
+            last_tick = entry.tick;
+            match &entry.intervention {
+                &Intervention::Observe(idx) => write_tagged(&mut buf, 0, &[idx as u64]),
+                &Intervention::SetOperator(idx) => write_tagged(&mut buf, 1, &[idx as u64]),
+                &Intervention::ClearOperator(idx) => write_tagged(&mut buf, 2, &[idx as u64]),
+                &Intervention::Entangle(id1, id2) => write_tagged(&mut buf, 3, &[id1, id2]),
+                &Intervention::Disrupt(idx) => write_tagged(&mut buf, 4, &[idx as u64]),
+                &Intervention::SetBoundary(boundary) => {
+                    let pos = BoundaryCondition::ALL.iter().position(|&b| b == boundary);
+                    write_tagged(&mut buf, 5, &[pos.unwrap_or(0) as u64])
+                }
+                Intervention::Region(region, op) => {
+                    buf.push(6);
+                    write_region(&mut buf, region);
+                    match op {
+                        RegionOp::Fill(state) => {
+                            buf.push(0);
+                            write_state(&mut buf, state);
+                        }
+                        RegionOp::Clear => buf.push(1),
+                        RegionOp::Randomize => buf.push(2),
+                        RegionOp::Observe => buf.push(3),
+                        RegionOp::Disrupt => buf.push(4),
+                        RegionOp::Cut => buf.push(5),
+                    }
+                }
+                Intervention::SetOperatorState(idx, state) => {
+                    write_tagged(&mut buf, 8, &[*idx as u64]);
+                    write_state(&mut buf, state);
+                }
+                Intervention::SetVelocity(idx, velocity) => {
+                    write_tagged(&mut buf, 9, &[*idx as u64]);
+                    for &v in velocity {
+                        write_signed(&mut buf, v);
+                    }
+                }
+                Intervention::Stamp(at, pattern) => {
+                    buf.push(7);
+                    for &c in at {
+                        write_varint(&mut buf, c as u64);
+                    }
+                    pattern.write(&mut buf);
+                }
+                Intervention::Resize(new_dims) => {
+                    buf.push(10);
+                    write_grid_dims(&mut buf, new_dims);
+                }
+                Intervention::EntangleRegion(region, pairs) => {
+                    buf.push(11);
+                    write_region(&mut buf, region);
+                    write_varint(&mut buf, *pairs as u64);
+                }
+                &Intervention::Rewire(pairs) => write_tagged(&mut buf, 12, &[pairs as u64]),
+                Intervention::SetPairOperator(id, operator) => {
+                    write_tagged(&mut buf, 13, &[*id]);
+                    write_pair_operator(&mut buf, operator.as_ref());
+                }
+                Intervention::CollapsePartner(idx, source) => {
+                    write_tagged(&mut buf, 14, &[*idx as u64]);
+                    write_state(&mut buf, source);
+                }
+                &Intervention::EmitDisruption(idx, amplitude) => {
+                    write_tagged(&mut buf, 15, &[idx as u64]);
+                    buf.extend_from_slice(&amplitude.to_le_bytes());
+                }
+            }
+        }
+
+        pack(PREFIX, &buf)
+    }
+
+    /// Parses a share code produced by [`Scenario::encode`].
+    ///
+    /// Surrounding whitespace is ignored so codes can be pasted straight from chat.
+    pub fn decode(code: &str) -> Result<Self, ShareError> {
+        let buf = unpack(PREFIX, code)?;
+        let mut reader = Reader::new(&buf);
+        let grid_dims = reader.grid_dims()?;
+        let cells: usize = grid_dims.iter().product();
+        let ga_dims = reader.varint()? as usize;
+        if ga_dims > MAX_GA_DIMS {
+            return Err(ShareError::Invalid("too many GA dimensions"));
+        }
+
+        let observation_rate = reader.probability()?;
+        let decay_rate = reader.probability()?;
+        let fluctuation_rate = reader.probability()?;
+        let entanglement_percentage = reader.probability()?;
+        let decoherence = Decoherence {
+            rate: reader.probability()?,
+            threshold: reader.probability()?,
+            refresh_on_fire: match reader.byte()? {
+                0 => false,
+                1 => true,
+                _ => return Err(ShareError::Invalid("unknown decoherence refresh flag")),
+            },
+        };
+        let collapse_speed = match reader.byte()? {
+            0 => CollapseSpeed::Instant,
+            1 => match reader.f64()? {
+                speed if speed > 0.0 => CollapseSpeed::Limited(speed),
+                _ => return Err(ShareError::Invalid("collapse speed not positive")),
+            },
+            _ => return Err(ShareError::Invalid("unknown collapse speed")),
+        };
+        let pair_operator = reader
+            .pair_operator(ga_dims)?
+            .ok_or(ShareError::Invalid("missing pair operator"))?;
+        let operator_move_interval = reader.varint()?;
+        let collapse_policy = *CollapsePolicy::ALL
+            .get(reader.byte()? as usize)
+            .ok_or(ShareError::Invalid("unknown collapse policy"))?;
+        let update_order = *UpdateOrder::ALL
+            .get(reader.byte()? as usize)
+            .ok_or(ShareError::Invalid("unknown update order"))?;
+        let random_scheme = *RandomScheme::ALL
+            .get(reader.byte()? as usize)
+            .ok_or(ShareError::Invalid("unknown random scheme"))?;
+        let reversible = match reader.byte()? {
+            0 => false,
+            1 => true,
+            _ => return Err(ShareError::Invalid("unknown reversible flag")),
+        };
+        let mut parameter_fields = [None, None, None];
+        for field in &mut parameter_fields {
+            *field = match reader.byte()? {
+                0 => None,
+                1 => {
+                    let values = (0..cells)
+                        .map(|_| reader.f32_probability())
+                        .collect::<Result<Vec<f32>, _>>()?;
+                    Some(ParameterField::from_values(values))
+                }
+                _ => return Err(ShareError::Invalid("unknown parameter field flag")),
+            };
+        }
+        let neighborhood = match reader.byte()? {
+            0 => {
+                let radius = reader.varint()? as usize;
+                if radius == 0 || radius > 8 {
+                    return Err(ShareError::Invalid("neighborhood radius out of range"));
+                }
+                Neighborhood::Moore { radius }
+            }
+            1 => Neighborhood::VonNeumann,
+            2 => {
+                let count = reader.varint()? as usize;
+                let mut offsets = Vec::with_capacity(count.min(buf.len()));
+                for _ in 0..count {
+                    let len = reader.varint()? as usize;
+                    // The neighborhood may be meant for a resized grid with more dimensions.
+                    let offset = (0..len.min(MAX_GRID_DIMS + 1))
+                        .map(|_| reader.signed())
+                        .collect::<Result<Vec<i64>, _>>()?;
+                    if len != offset.len() {
+                        return Err(ShareError::Invalid("neighborhood offset too long"));
+                    }
+                    offsets.push(offset);
+                }
+                Neighborhood::Custom(offsets)
+            }
+            _ => return Err(ShareError::Invalid("unknown neighborhood")),
+        };
+        let topology = *Topology::ALL
+            .get(reader.byte()? as usize)
+            .ok_or(ShareError::Invalid("unknown topology"))?;
+        let seed = reader.u64()?;
+        let ticks = reader.varint()?;
+
+        let count = reader.varint()? as usize;
+        let mut interventions = Vec::with_capacity(count.min(buf.len()));
+        let mut tick: u64 = 0;
+        // Resizes change the grid that later interventions refer to, and add IDs.
+        let (mut shape, mut size, mut id_limit) = (grid_dims.clone(), cells, cells as u64);
+        for _ in 0..count {
+            tick = tick
+                .checked_add(reader.varint()?)
+                .ok_or(ShareError::Invalid("intervention tick overflow"))?;
+            let index = |value: u64| -> Result<usize, ShareError> {
+                if (value as usize) < size {
+                    Ok(value as usize)
+                } else {
+                    Err(ShareError::Invalid("intervention outside the grid"))
+                }
+            };
+            let intervention = match reader.byte()? {
+                0 => Intervention::Observe(index(reader.varint()?)?),
+                1 => Intervention::SetOperator(index(reader.varint()?)?),
+                2 => Intervention::ClearOperator(index(reader.varint()?)?),
+                3 => {
+                    let (id1, id2) = (reader.varint()?, reader.varint()?);
+                    if id1 >= id_limit || id2 >= id_limit {
+                        return Err(ShareError::Invalid("entanglement of an unknown cell"));
+                    }
+                    Intervention::Entangle(id1, id2)
+                }
+                4 => Intervention::Disrupt(index(reader.varint()?)?),
+                5 => {
+                    let boundary = BoundaryCondition::ALL
+                        .get(reader.varint()? as usize)
+                        .ok_or(ShareError::Invalid("unknown boundary condition"))?;
+                    Intervention::SetBoundary(*boundary)
+                }
+                6 => {
+                    let region = reader.region(&shape)?;
+                    let op = match reader.byte()? {
+                        0 => RegionOp::Fill(reader.state(ga_dims)?),
+                        1 => RegionOp::Clear,
+                        2 => RegionOp::Randomize,
+                        3 => RegionOp::Observe,
+                        4 => RegionOp::Disrupt,
+                        5 => RegionOp::Cut,
+                        _ => return Err(ShareError::Invalid("unknown region operation")),
+                    };
+                    Intervention::Region(region, op)
+                }
+                7 => {
+                    let mut at = Vec::with_capacity(shape.len());
+                    for &dim in &shape {
+                        let c = reader.varint()? as usize;
+                        if c >= dim {
+                            return Err(ShareError::Invalid("stamp outside the grid"));
+                        }
+                        at.push(c);
+                    }
+                    Intervention::Stamp(at, Pattern::read(&mut reader)?)
+                }
+                8 => {
+                    let idx = index(reader.varint()?)?;
+                    Intervention::SetOperatorState(idx, reader.state(ga_dims)?)
+                }
+                9 => {
+                    let idx = index(reader.varint()?)?;
+                    let velocity = (0..shape.len())
+                        .map(|_| reader.signed())
+                        .collect::<Result<Vec<i64>, _>>()?;
+                    Intervention::SetVelocity(idx, velocity)
+                }
+                10 => {
+                    shape = reader.grid_dims()?;
+                    size = shape.iter().product();
+                    id_limit += size as u64;
+                    Intervention::Resize(shape.clone())
+                }
+                11 => {
+                    let region = reader.region(&shape)?;
+                    Intervention::EntangleRegion(region, reader.varint()? as usize)
+                }
+                12 => Intervention::Rewire(reader.varint()? as usize),
+                13 => {
+                    let id = reader.varint()?;
+                    if id >= id_limit {
+                        return Err(ShareError::Invalid("pair operator of an unknown cell"));
+                    }
+                    Intervention::SetPairOperator(id, reader.pair_operator(ga_dims)?)
+                }
+                14 => {
+                    let idx = index(reader.varint()?)?;
+                    Intervention::CollapsePartner(idx, reader.state(ga_dims)?)
+                }
+                15 => {
+                    let idx = index(reader.varint()?)?;
+                    Intervention::EmitDisruption(idx, reader.probability()?)
+                }
+                _ => return Err(ShareError::Invalid("unknown intervention kind")),
+            };
+            interventions.push(LoggedIntervention { tick, intervention });
+        }
+
+        Ok(Scenario {
+            grid_dims,
+            ga_dims,
+            observation_rate,
+            decay_rate,
+            fluctuation_rate,
+            entanglement_percentage,
+            decoherence,
+            collapse_speed,
+            pair_operator,
+            operator_move_interval,
+            collapse_policy,
+            update_order,
+            random_scheme,
+            reversible,
+            parameter_fields,
+            neighborhood,
+            topology,
+            seed,
+            ticks,
+            interventions,
+        })
+    }
+}
+
+//================================================================================
+// Binary Helpers
+//================================================================================
+
+/// Deflates a binary payload and encodes it as URL-safe base64 behind `prefix`.
+pub(crate) fn pack(prefix: &str, payload: &[u8]) -> String {
+    let mut encoder = DeflateEncoder::new(Vec::new(), Compression::best());
+    encoder
+        .write_all(payload)
+        .expect("writing to a Vec cannot fail");
+    let compressed = encoder.finish().expect("writing to a Vec cannot fail");
+    format!("{}{}", prefix, URL_SAFE_NO_PAD.encode(compressed))
+}
+
+/// Reverses [`pack`]. Surrounding whitespace is ignored.
+pub(crate) fn unpack(prefix: &str, code: &str) -> Result<Vec<u8>, ShareError> {
+    let body = code
+        .trim()
+        .strip_prefix(prefix)
+        .ok_or(ShareError::UnknownFormat)?;
+    let compressed = URL_SAFE_NO_PAD.decode(body).map_err(ShareError::Base64)?;
+    let mut buf = Vec::new();
+    DeflateDecoder::new(compressed.as_slice())
+        .read_to_end(&mut buf)
+        .map_err(ShareError::Decompress)?;
+    Ok(buf)
+}
+
+/// Writes an unsigned LEB128 varint.
+pub(crate) fn write_varint(buf: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf.push(byte);
+            return;
+        }
+        buf.push(byte | 0x80);
+    }
+}
+
+/// Writes a signed integer as a zigzag-encoded varint.
+pub(crate) fn write_signed(buf: &mut Vec<u8>, value: i64) {
+    write_varint(buf, ((value << 1) ^ (value >> 63)) as u64);
+}
+
+/// Writes a multivector's coefficients, one byte each, shifted to 0..=2.
+pub(crate) fn write_state(buf: &mut Vec<u8>, state: &Multivector) {
+    buf.extend(state.coefficients.iter().map(|c| (c.0 + 1) as u8));
+}
+
+/// Writes grid dimensions as their count followed by each extent.
+pub(crate) fn write_grid_dims(buf: &mut Vec<u8>, grid_dims: &[usize]) {
+    write_varint(buf, grid_dims.len() as u64);
+    for &dim in grid_dims {
+        write_varint(buf, dim as u64);
+    }
+}
+
+/// Writes a box of cells as the start and end of its range on every axis.
+fn write_region(buf: &mut Vec<u8>, region: &[Range<usize>]) {
+    for range in region {
+        write_varint(buf, range.start as u64);
+        write_varint(buf, range.end as u64);
+    }
+}
+
+/// Writes an optional pair operator as a one-byte tag, 0 for none, with a
+/// rotor's state after it.
+pub(crate) fn write_pair_operator(buf: &mut Vec<u8>, operator: Option<&PairOperator>) {
+    match operator {
+        None => buf.push(0),
+        Some(PairOperator::Identity) => buf.push(1),
+        Some(PairOperator::Inversion) => buf.push(2),
+        Some(PairOperator::Swap) => buf.push(3),
+        Some(PairOperator::Rotor(rotor)) => {
+            buf.push(4);
+            write_state(buf, rotor);
+        }
+    }
+}
+
+/// Writes a one-byte tag followed by its varint arguments.
+fn write_tagged(buf: &mut Vec<u8>, tag: u8, args: &[u64]) {
+    buf.push(tag);
+    for &arg in args {
+        write_varint(buf, arg);
+    }
+}
+
+/// A cursor over a decompressed payload.
+pub(crate) struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(buf: &'a [u8]) -> Self {
+        Reader { buf, pos: 0 }
+    }
+
+    pub(crate) fn byte(&mut self) -> Result<u8, ShareError> {
+        let byte = *self.buf.get(self.pos).ok_or(ShareError::Truncated)?;
+        self.pos += 1;
+        Ok(byte)
+    }
+
+    pub(crate) fn varint(&mut self) -> Result<u64, ShareError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.byte()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(ShareError::Invalid("varint too long"))
+    }
+
+    /// Reads grid dimensions written by [`write_grid_dims`].
+    pub(crate) fn grid_dims(&mut self) -> Result<Vec<usize>, ShareError> {
+        let n_dims = self.varint()? as usize;
+        if n_dims == 0 || n_dims > MAX_GRID_DIMS {
+            return Err(ShareError::Invalid("unsupported number of grid dimensions"));
+        }
+        let mut grid_dims = Vec::with_capacity(n_dims);
+        let mut cells: usize = 1;
+        for _ in 0..n_dims {
+            let dim = self.varint()? as usize;
+            cells = cells.saturating_mul(dim);
+            grid_dims.push(dim);
+        }
+        if cells == 0 || cells > MAX_CELLS {
+            return Err(ShareError::Invalid("grid size out of range"));
+        }
+        Ok(grid_dims)
+    }
+
+    /// Reads a box written by [`write_region`] that lies inside a grid of `shape`.
+    fn region(&mut self, shape: &[usize]) -> Result<Vec<Range<usize>>, ShareError> {
+        let mut region = Vec::with_capacity(shape.len());
+        for &dim in shape {
+            let (start, end) = (self.varint()? as usize, self.varint()? as usize);
+            if start > end || end > dim {
+                return Err(ShareError::Invalid("region outside the grid"));
+            }
+            region.push(start..end);
+        }
+        Ok(region)
+    }
+
+    pub(crate) fn signed(&mut self) -> Result<i64, ShareError> {
+        let zigzag = self.varint()?;
+        Ok((zigzag >> 1) as i64 ^ -((zigzag & 1) as i64))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, ShareError> {
+        let bytes = self
+            .buf
+            .get(self.pos..self.pos + 8)
+            .ok_or(ShareError::Truncated)?;
+        self.pos += 8;
+        Ok(u64::from_le_bytes(bytes.try_into().unwrap()))
+    }
+
+    pub(crate) fn f64(&mut self) -> Result<f64, ShareError> {
+        Ok(f64::from_bits(self.u64()?))
+    }
+
+    pub(crate) fn probability(&mut self) -> Result<f64, ShareError> {
+        let value = self.f64()?;
+        if (0.0..=1.0).contains(&value) {
+            Ok(value)
+        } else {
+            Err(ShareError::Invalid("rate outside [0, 1]"))
+        }
+    }
+
+    /// Reads a single-precision probability, as stored in parameter fields.
+    pub(crate) fn f32_probability(&mut self) -> Result<f32, ShareError> {
+        let bytes = self
+            .buf
+            .get(self.pos..self.pos + 4)
+            .ok_or(ShareError::Truncated)?;
+        self.pos += 4;
+        let value = f32::from_le_bytes(bytes.try_into().unwrap());
+        if (0.0..=1.0).contains(&value) {
+            Ok(value)
+        } else {
+            Err(ShareError::Invalid("rate outside [0, 1]"))
+        }
+    }
+
+    /// Reads an optional pair operator written by [`write_pair_operator`].
+    pub(crate) fn pair_operator(
+        &mut self,
+        ga_dims: usize,
+    ) -> Result<Option<PairOperator>, ShareError> {
+        Ok(Some(match self.byte()? {
+            0 => return Ok(None),
+            1 => PairOperator::Identity,
+            2 => PairOperator::Inversion,
+            3 => PairOperator::Swap,
+            4 => PairOperator::Rotor(self.state(ga_dims)?),
+            _ => return Err(ShareError::Invalid("unknown pair operator")),
+        }))
+    }
+
+    /// Reads a multivector written by [`write_state`].
+    pub(crate) fn state(&mut self, ga_dims: usize) -> Result<Multivector, ShareError> {
+        let mut state = Multivector::zero(ga_dims);
+        for c in &mut state.coefficients {
+            *c = match self.byte()? {
+                b @ 0..=2 => Mod3(b as i8 - 1),
+                _ => return Err(ShareError::Invalid("coefficient out of range")),
+            };
+        }
+        Ok(state)
+    }
+}
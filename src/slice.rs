@@ -0,0 +1,135 @@
+//! Slicing: which plane of an N-dimensional grid the window shows.
+//!
+//! The window draws one plane of the grid at a time. A [`Slice`] names the two
+//! grid axes laid across and down the screen and an index along every other
+//! axis, so a `[64, 64, 64]` grid is explored a layer at a time: page through
+//! the layers along one depth axis, switch which depth axis that is, or turn
+//! the grid to look at it along another pair of axes. Drawing and the tools go
+//! through [`Config::slice`](crate::app::Config::slice), so the cells drawn are
+//! the cells clicked.
+
+/// A plane through the grid; see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slice {
+    /// The grid axes drawn across and down the screen.
+    pub axes: [usize; 2],
+    /// The index along every grid axis; those of the viewing axes are unused.
+    pub position: Vec<usize>,
+    /// The depth axis that paging moves along.
+    pub depth: usize,
+}
+
+impl Slice {
+    /// The plane of the first two axes through the origin of a grid of
+    /// `dims` dimensions.
+    pub fn new(dims: usize) -> Self {
+        Slice {
+            axes: [0, 1],
+            position: vec![0; dims],
+            depth: 2,
+        }
+    }
+
+    /// The grid coordinate drawn at column `across` and row `down` of the
+    /// plane, on a grid of `dims` dimensions.
+    pub fn coord(&self, dims: usize, across: usize, down: usize) -> Vec<usize> {
+        let mut coord = self.position.clone();
+        coord.resize(dims, 0);
+        if let Some(x) = coord.get_mut(self.axes[0]) {
+            *x = across;
+        }
+        if dims > 1
+            && let Some(y) = coord.get_mut(self.axes[1])
+        {
+            *y = down;
+        }
+        coord
+    }
+
+    /// The column and row of `coord` in the plane, wherever it lies in depth.
+    pub fn project(&self, coord: &[usize]) -> [usize; 2] {
+        let at = |axis: usize| coord.get(axis).copied().unwrap_or(0);
+        if coord.len() > 1 {
+            [at(self.axes[0]), at(self.axes[1])]
+        } else {
+            [at(self.axes[0]), 0]
+        }
+    }
+
+    /// Whether `coord` lies in the plane.
+    pub fn contains(&self, coord: &[usize]) -> bool {
+        coord.iter().enumerate().all(|(axis, &i)| {
+            self.axes.contains(&axis) || i == self.position.get(axis).copied().unwrap_or(0)
+        })
+    }
+
+    /// Moves the plane one layer along the depth axis, wrapping around the
+    /// grid of `grid_dims`.
+    pub fn step(&mut self, grid_dims: &[usize], forward: bool) {
+        let Some(&len) = grid_dims.get(self.depth) else {
+            return;
+        };
+        self.position.resize(grid_dims.len(), 0);
+        let at = &mut self.position[self.depth];
+        *at = if forward {
+            (*at + 1) % len
+        } else {
+            (*at + len - 1) % len
+        };
+    }
+
+    /// Makes the next axis outside the plane the depth axis.
+    pub fn cycle_depth(&mut self, dims: usize) {
+        let depths: Vec<usize> = (0..dims).filter(|a| !self.axes.contains(a)).collect();
+        let next = depths
+            .iter()
+            .position(|&a| a == self.depth)
+            .map_or(0, |i| i + 1);
+        if let Some(&depth) = depths.get(next % depths.len().max(1)) {
+            self.depth = depth;
+        }
+    }
+
+    /// Turns the plane to the next pair of axes, in the order (0, 1), (0, 2),
+    /// ..., (1, 2), ..., keeping the layer indices.
+    pub fn cycle_axes(&mut self, dims: usize) {
+        if dims < 3 {
+            return;
+        }
+        let [a, b] = self.axes;
+        self.axes = if b + 1 < dims {
+            [a, b + 1]
+        } else if a + 2 < dims {
+            [a + 1, a + 2]
+        } else {
+            [0, 1]
+        };
+        if self.axes.contains(&self.depth) || self.depth >= dims {
+            self.depth = (0..dims).find(|a| !self.axes.contains(a)).unwrap_or(0);
+        }
+    }
+
+    /// A one-line summary of the plane on a grid of `grid_dims`, for the HUD:
+    /// the viewing axes, then every depth axis's layer, the paged one in
+    /// brackets. Empty for grids of two dimensions or fewer.
+    pub fn describe(&self, grid_dims: &[usize]) -> String {
+        if grid_dims.len() < 3 {
+            return String::new();
+        }
+        let [a, b] = self.axes;
+        let mut line = format!("Plane x{} x{}", a, b);
+        for (axis, &len) in grid_dims.iter().enumerate() {
+            if self.axes.contains(&axis) {
+                continue;
+            }
+            let at = self.position.get(axis).copied().unwrap_or(0);
+            let layer = format!("x{}={}/{}", axis, at, len);
+            if axis == self.depth {
+                line += &format!(" [{}]", layer);
+            } else {
+                line += &format!(" {}", layer);
+            }
+        }
+        line
+    }
+}
@@ -0,0 +1,151 @@
+//! Sonification: what the automaton sounds like.
+//!
+//! A [`Sonifier`] listens to each tick's [`TickReport`] and turns three kinds
+//! of event into [`Tone`]s, each in its own register: decays low, observation
+//! collapses in the middle and entanglement triggers high. A cell's column
+//! picks the note from a pentatonic scale spanning three octaves, so activity
+//! sweeping across the grid is heard as a melody, and how loud a voice plays
+//! follows how often its event happens, smoothed over a few ticks so the
+//! volume swells and fades rather than jumping. The tones wait in the
+//! sonifier until a frontend with sound takes them; the engine itself plays
+//! nothing.
+
+use crate::universe::TickReport;
+
+/// The steps of the major pentatonic scale, in semitones above its root.
+const PENTATONIC: [usize; 5] = [0, 2, 4, 7, 9];
+/// How many octaves the width of the grid spans.
+const OCTAVES: usize = 3;
+/// The most tones a voice sounds per tick; busier ticks pick events spread
+/// evenly through the report.
+pub const NOTES_PER_VOICE: usize = 3;
+/// The fraction of cells an event must reach per tick for its voice to play at
+/// full volume.
+const FULL_RATE: f64 = 0.01;
+/// How much of a voice's smoothed rate each new tick replaces.
+const SMOOTHING: f64 = 0.3;
+/// The most tones kept waiting, so a frontend without sound never piles them up.
+const MAX_PENDING: usize = 64;
+
+/// The kind of event a tone stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Voice {
+    /// An `Observed` cell decaying back to `Potential`.
+    Decay,
+    /// A cell collapsing to `Observed` under the local rule.
+    Observation,
+    /// An entangled partner collapsed by its observed twin.
+    Entanglement,
+}
+
+impl Voice {
+    /// Every voice, lowest first.
+    pub const ALL: [Voice; 3] = [Voice::Decay, Voice::Observation, Voice::Entanglement];
+
+    /// The frequency of the lowest note the voice plays, in hertz.
+    pub fn root(self) -> f64 {
+        match self {
+            Voice::Decay => 110.0,
+            Voice::Observation => 220.0,
+            Voice::Entanglement => 440.0,
+        }
+    }
+
+    /// The cells of this voice's events in `report`.
+    fn cells(self, report: &TickReport) -> Vec<&[usize]> {
+        match self {
+            Voice::Decay => report.decays.iter().map(Vec::as_slice).collect(),
+            Voice::Observation => report.observations.iter().map(Vec::as_slice).collect(),
+            Voice::Entanglement => report
+                .entanglements
+                .iter()
+                .map(|(_, partner)| partner.as_slice())
+                .collect(),
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// One note to sound.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    pub voice: Voice,
+    /// In hertz.
+    pub frequency: f64,
+    /// From 0 (silent) to 1.
+    pub volume: f64,
+}
+
+/// The frequency of the note for column `x` of a grid `width` columns wide,
+/// in `voice`'s register.
+pub fn pitch(voice: Voice, x: usize, width: usize) -> f64 {
+    let scale = PENTATONIC.len();
+    let step = (x * scale * OCTAVES / width.max(1)).min(scale * OCTAVES - 1);
+    let semitones = 12 * (step / scale) + PENTATONIC[step % scale];
+    voice.root() * 2f64.powf(semitones as f64 / 12.0)
+}
+
+/// Turns ticks into tones; see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct Sonifier {
+    /// Silences every voice, as it starts out; the rates are still followed,
+    /// so unmuting picks up at the current volume.
+    pub muted: bool,
+    /// Each voice's smoothed events per cell per tick.
+    rates: [f64; 3],
+    pending: Vec<Tone>,
+}
+
+impl Default for Sonifier {
+    fn default() -> Self {
+        Sonifier {
+            muted: true,
+            rates: [0.0; 3],
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl Sonifier {
+    /// Hears one tick of a grid of `grid_dims`, queueing its tones unless muted.
+    pub fn listen(&mut self, report: &TickReport, grid_dims: &[usize]) {
+        let cells = grid_dims.iter().product::<usize>().max(1) as f64;
+        let width = grid_dims.first().copied().unwrap_or(1);
+        for voice in Voice::ALL {
+            let events = voice.cells(report);
+            let rate = events.len() as f64 / cells;
+            self.rates[voice.index()] += SMOOTHING * (rate - self.rates[voice.index()]);
+            if self.muted || events.is_empty() {
+                continue;
+            }
+            let notes = events.len().min(NOTES_PER_VOICE);
+            let volume = self.level(voice) / notes as f64;
+            self.pending.extend((0..notes).map(|i| Tone {
+                voice,
+                frequency: pitch(voice, events[i * events.len() / notes][0], width),
+                volume,
+            }));
+        }
+        let excess = self.pending.len().saturating_sub(MAX_PENDING);
+        self.pending.drain(..excess);
+    }
+
+    /// How loud `voice` is playing, from 0 to 1, going by its recent rate.
+    pub fn level(&self, voice: Voice) -> f64 {
+        (self.rates[voice.index()] / FULL_RATE).sqrt().min(1.0)
+    }
+
+    /// Mutes or unmutes, dropping anything still queued.
+    pub fn toggle_mute(&mut self) {
+        self.muted = !self.muted;
+        self.pending.clear();
+    }
+
+    /// Takes the tones queued since the last call, oldest first.
+    pub fn take(&mut self) -> Vec<Tone> {
+        std::mem::take(&mut self.pending)
+    }
+}
@@ -0,0 +1,509 @@
+//! A chunked, sparse alternative to [`Universe`] for huge, mostly quiet worlds.
+//!
+//! A [`SparseUniverse`] starts as vacuum: every cell `Potential` with the zero
+//! state, which the default rule leaves alone. Only chunks that hold something
+//! else are stored, and a tick only evaluates the chunks that changed recently
+//! plus the chunks around them. A chunk of `Potential` cells that has not changed
+//! for a few ticks falls asleep until something near it wakes it up, so a
+//! 4096x4096 world costs memory and time in proportion to its activity.
+//!
+//! For deterministic rules that leave vacuum as it is, the result is exactly what
+//! a [`Universe`] would compute. Sleeping chunks skip their random observations and fluctuations,
+//! though, so with a stochastic rule a sparse run is its own, quieter universe.
+//! Entanglement, parameter fields, update orders and the intervention log are
+//! only available on [`Universe`].
+
+use crate::existon::{CollapsePolicy, ConsciousnessState, Existon};
+use crate::ga_core::Multivector;
+use crate::rule::{MatzkeDefaultRule, RuleCtx, UpdateRule};
+use crate::stats::GradeTotals;
+use crate::universe::{
+    BoundaryCondition, Neighborhood, OperatorPreset, TickReport, Topology, Universe,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+use std::sync::Arc;
+
+/// The extent of a chunk along every grid dimension.
+pub const CHUNK_SIDE: usize = 32;
+
+/// How many ticks a chunk must stay unchanged before it falls asleep.
+const QUIET_TICKS: u64 = 4;
+
+#[derive(Debug, Clone)]
+struct Chunk {
+    /// The chunk's cells, the first dimension fastest.
+    cells: Vec<Existon>,
+    /// Ticks since any of the cells last changed.
+    quiet_for: u64,
+}
+
+impl Chunk {
+    /// Whether the chunk must be evaluated next tick.
+    fn is_awake(&self) -> bool {
+        self.quiet_for < QUIET_TICKS
+            || self
+                .cells
+                .iter()
+                .any(|cell| cell.consciousness != ConsciousnessState::Potential)
+    }
+}
+
+/// A universe stored as fixed-size chunks, most of which are never allocated.
+///
+/// Cells are identified by their flat grid index, as in a fresh [`Universe`].
+#[derive(Debug, Clone)]
+pub struct SparseUniverse {
+    /// The number of dimensions of the Geometric Algebra space for each Existon.
+    pub ga_dims: usize,
+    /// The dimensions of the simulation grid.
+    pub grid_dims: Vec<usize>,
+    pub observation_rate: f64,
+    pub decay_rate: f64,
+    pub fluctuation_rate: f64,
+    pub collapse_policy: CollapsePolicy,
+    /// How neighborhoods behave at the edges of the grid.
+    pub boundary: BoundaryCondition,
+    neighborhood: Neighborhood,
+    topology: Topology,
+    /// The neighborhood expanded for the grid's dimensions, for even and odd rows.
+    offsets: [Vec<Vec<i64>>; 2],
+    rule: Arc<dyn UpdateRule>,
+    /// The stored chunks, by chunk coordinate.
+    chunks: BTreeMap<Vec<usize>, Chunk>,
+    /// A vacuum cell, read for neighbors in chunks that are not stored.
+    vacuum: Existon,
+    /// The seed the RNG was created from.
+    pub seed: u64,
+    /// The number of ticks simulated so far.
+    pub tick_count: u64,
+    rng: StdRng,
+}
+
+impl SparseUniverse {
+    /// An empty universe whose every random choice is derived from `seed`.
+    pub fn new(grid_dims: Vec<usize>, ga_dims: usize, seed: u64) -> Self {
+        let (neighborhood, topology) = (Neighborhood::default(), Topology::default());
+        let offsets = topology.offsets(&neighborhood, grid_dims.len());
+        SparseUniverse {
+            ga_dims,
+            grid_dims,
+            observation_rate: 0.0005,
+            decay_rate: 0.01,
+            fluctuation_rate: 0.001,
+            collapse_policy: CollapsePolicy::default(),
+            boundary: BoundaryCondition::default(),
+            neighborhood,
+            topology,
+            offsets,
+            rule: Arc::new(MatzkeDefaultRule),
+            chunks: BTreeMap::new(),
+            vacuum: Self::vacuum_cell(0, ga_dims),
+            seed,
+            tick_count: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// A sparse copy of a universe's grid and configuration, continuing from its
+    /// tick with its seed.
+    ///
+    /// Cells take their grid index as their ID, and entanglement is left behind.
+    pub fn from_universe(universe: &Universe) -> Self {
+        let mut sparse = Self::new(universe.grid_dims.clone(), universe.ga_dims, universe.seed);
+        sparse.observation_rate = universe.observation_rate;
+        sparse.decay_rate = universe.decay_rate;
+        sparse.fluctuation_rate = universe.fluctuation_rate;
+        sparse.collapse_policy = universe.collapse_policy;
+        sparse.boundary = universe.boundary;
+        sparse.set_neighborhood(universe.neighborhood().clone());
+        sparse.set_topology(universe.topology());
+        sparse.rule = universe.shared_rule();
+        sparse.tick_count = universe.tick_count;
+        for (coord, cell) in universe.iter_cells() {
+            if !Self::is_vacuum(cell) {
+                sparse.set(&coord, cell.clone());
+            }
+        }
+        sparse
+    }
+
+    /// Returns the universe with the given update rule, for use at construction.
+    pub fn with_rule(mut self, rule: impl UpdateRule + 'static) -> Self {
+        self.set_rule(rule);
+        self
+    }
+
+    /// Switches the update rule, waking every chunk.
+    pub fn set_rule(&mut self, rule: impl UpdateRule + 'static) {
+        self.rule = Arc::new(rule);
+        self.wake_all();
+    }
+
+    /// The neighborhood used to build each cell's local operator.
+    pub fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    /// Switches the neighborhood, waking every chunk.
+    pub fn set_neighborhood(&mut self, neighborhood: Neighborhood) {
+        self.offsets = self.topology.offsets(&neighborhood, self.grid_dims.len());
+        self.neighborhood = neighborhood;
+        self.wake_all();
+    }
+
+    /// The shape of the cells.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Switches between square and hexagonal cells, waking every chunk.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.offsets = topology.offsets(&self.neighborhood, self.grid_dims.len());
+        self.topology = topology;
+        self.wake_all();
+    }
+
+    /// The number of chunks stored.
+    pub fn chunk_count(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// The number of stored chunks the next tick will evaluate for their own sake.
+    pub fn awake_chunk_count(&self) -> usize {
+        self.chunks
+            .values()
+            .filter(|chunk| chunk.is_awake())
+            .count()
+    }
+
+    /// The cell at a coordinate, or `None` outside the grid.
+    pub fn get(&self, coord: &[usize]) -> Option<Existon> {
+        let idx = self.index_of(coord)?;
+        Some(
+            self.lookup(coord)
+                .cloned()
+                .unwrap_or_else(|| Self::vacuum_cell(idx as u64, self.ga_dims)),
+        )
+    }
+
+    /// Replaces the cell at a coordinate and wakes its chunk. The cell's ID is
+    /// set to its grid index.
+    pub fn set(&mut self, coord: &[usize], mut cell: Existon) {
+        let Some(idx) = self.index_of(coord) else {
+            return;
+        };
+        cell.id = idx as u64;
+        let (key, local) = self.chunk_of(coord);
+        let chunk = self.chunk_mut(key);
+        chunk.cells[local] = cell;
+        chunk.quiet_for = 0;
+    }
+
+    /// Places a stable `Operator` with the default `e0` state.
+    pub fn set_operator(&mut self, coord: &[usize]) {
+        if let Some(mut cell) = self.get(coord) {
+            cell.consciousness = ConsciousnessState::Operator;
+            cell.state = OperatorPreset::default().state(self.ga_dims);
+            self.set(coord, cell);
+        }
+    }
+
+    /// Fills the box spanned by `region` with random `Potential` cells, seeding
+    /// activity in the vacuum. Ranges are clipped to the grid.
+    pub fn randomize_region(&mut self, region: &[Range<usize>]) {
+        if region.len() != self.grid_dims.len() {
+            return;
+        }
+        let clipped: Vec<Range<usize>> = region
+            .iter()
+            .zip(&self.grid_dims)
+            .map(|(r, &dim)| r.start.min(dim)..r.end.min(dim))
+            .collect();
+        if clipped.iter().any(|r| r.is_empty()) {
+            return;
+        }
+        let mut coord: Vec<usize> = clipped.iter().map(|r| r.start).collect();
+        loop {
+            let cell = Existon::new(0, self.ga_dims, &mut self.rng);
+            self.set(&coord, cell);
+            let Some(axis) = (0..coord.len()).find(|&d| coord[d] + 1 < clipped[d].end) else {
+                return;
+            };
+            coord[axis] += 1;
+            for d in 0..axis {
+                coord[d] = clipped[d].start;
+            }
+        }
+    }
+
+    /// Advances every awake chunk and its surroundings by one step.
+    ///
+    /// The report covers observations, decays, fluctuations and the change in
+    /// grade totals; the sparse backend has no entanglement or moving operators.
+    pub fn tick(&mut self) -> TickReport {
+        let mut due = BTreeSet::new();
+        for (key, _) in self.chunks.iter().filter(|(_, chunk)| chunk.is_awake()) {
+            due.extend(self.chunks_around(key));
+        }
+
+        let mut rng = self.rng.clone();
+        let ctx = RuleCtx::with_rates(
+            self.ga_dims,
+            [
+                self.observation_rate,
+                self.decay_rate,
+                self.fluctuation_rate,
+            ],
+            self.collapse_policy,
+            self.tick_count,
+        )
+        .with_rng(&mut rng);
+        let mut report = TickReport {
+            tick: self.tick_count,
+            totals_change: GradeTotals::zero(self.ga_dims),
+            ..TickReport::default()
+        };
+        let mut updates = Vec::with_capacity(due.len());
+        for key in due {
+            let (corner, extents) = self.chunk_bounds(&key);
+            let size: usize = extents.iter().product();
+            let mut next_cells = Vec::with_capacity(size);
+            let mut changed = false;
+            for local in 0..size {
+                let coord: Vec<usize> = Self::coord_in(&extents, local)
+                    .iter()
+                    .zip(&corner)
+                    .map(|(c, start)| c + start)
+                    .collect();
+                let idx = self.index_of(&coord).expect("chunks lie inside the grid");
+                let vacuum;
+                let cell = match self.lookup(&coord) {
+                    Some(cell) => cell,
+                    None => {
+                        vacuum = Self::vacuum_cell(idx as u64, self.ga_dims);
+                        &vacuum
+                    }
+                };
+                let neighbors = self.neighbors(&coord);
+                ctx.visit(idx, cell.id);
+                let next = self.rule.apply(cell, &neighbors, &ctx);
+                match (cell.consciousness, next.consciousness) {
+                    (ConsciousnessState::Observed, ConsciousnessState::Potential) => {
+                        report.decays.push(coord)
+                    }
+                    (ConsciousnessState::Observed, _) => {}
+                    (_, ConsciousnessState::Observed) => report.observations.push(coord),
+                    _ => {}
+                }
+                if next.state != cell.state {
+                    report.totals_change.add(&next.state, 1);
+                    report.totals_change.add(&cell.state, -1);
+                }
+                changed |= next != *cell;
+                next_cells.push(next);
+            }
+            updates.push((key, next_cells, changed));
+        }
+        report.fluctuations = ctx
+            .into_fluctuations()
+            .into_iter()
+            .map(|id| self.coord_of(id as usize))
+            .collect();
+        self.rng = rng;
+
+        for (key, cells, changed) in updates {
+            match self.chunks.get_mut(&key) {
+                Some(chunk) => {
+                    chunk.cells = cells;
+                    chunk.quiet_for = if changed { 0 } else { chunk.quiet_for + 1 };
+                    // Vacuum that has settled is not worth storing.
+                    if !chunk.is_awake() && chunk.cells.iter().all(Self::is_vacuum) {
+                        self.chunks.remove(&key);
+                    }
+                }
+                None if changed => {
+                    self.chunks.insert(
+                        key,
+                        Chunk {
+                            cells,
+                            quiet_for: 0,
+                        },
+                    );
+                }
+                None => {}
+            }
+        }
+        self.tick_count += 1;
+        report
+    }
+
+    fn vacuum_cell(id: u64, ga_dims: usize) -> Existon {
+        Existon {
+            id,
+            consciousness: ConsciousnessState::Potential,
+            state: Multivector::zero(ga_dims),
+            velocity: None,
+            born: 0,
+            observed_since: None,
+        }
+    }
+
+    fn is_vacuum(cell: &Existon) -> bool {
+        cell.consciousness == ConsciousnessState::Potential
+            && cell.velocity.is_none()
+            && cell.state == Multivector::zero(cell.state.p)
+    }
+
+    fn wake_all(&mut self) {
+        for chunk in self.chunks.values_mut() {
+            chunk.quiet_for = 0;
+        }
+    }
+
+    /// The flat grid index of a coordinate, first dimension fastest.
+    fn index_of(&self, coord: &[usize]) -> Option<usize> {
+        if coord.len() != self.grid_dims.len() {
+            return None;
+        }
+        let (mut idx, mut stride) = (0, 1);
+        for (&c, &dim) in coord.iter().zip(&self.grid_dims) {
+            if c >= dim {
+                return None;
+            }
+            idx += c * stride;
+            stride *= dim;
+        }
+        Some(idx)
+    }
+
+    fn coord_of(&self, idx: usize) -> Vec<usize> {
+        Self::coord_in(&self.grid_dims, idx)
+    }
+
+    /// The coordinate of index `idx` in a box of the given extents.
+    fn coord_in(extents: &[usize], mut idx: usize) -> Vec<usize> {
+        extents
+            .iter()
+            .map(|&extent| {
+                let c = idx % extent;
+                idx /= extent;
+                c
+            })
+            .collect()
+    }
+
+    /// The chunk holding a coordinate inside the grid, and the cell's index within it.
+    fn chunk_of(&self, coord: &[usize]) -> (Vec<usize>, usize) {
+        let key: Vec<usize> = coord.iter().map(|&c| c / CHUNK_SIDE).collect();
+        let (_, extents) = self.chunk_bounds(&key);
+        let (mut local, mut stride) = (0, 1);
+        for (&c, &extent) in coord.iter().zip(&extents) {
+            local += (c % CHUNK_SIDE) * stride;
+            stride *= extent;
+        }
+        (key, local)
+    }
+
+    /// A chunk's first coordinate and its extent along each dimension, which is
+    /// smaller than [`CHUNK_SIDE`] at the far edges of the grid.
+    fn chunk_bounds(&self, key: &[usize]) -> (Vec<usize>, Vec<usize>) {
+        let corner: Vec<usize> = key.iter().map(|&k| k * CHUNK_SIDE).collect();
+        let extents = corner
+            .iter()
+            .zip(&self.grid_dims)
+            .map(|(&start, &dim)| CHUNK_SIDE.min(dim - start))
+            .collect();
+        (corner, extents)
+    }
+
+    /// A chunk, allocated as vacuum if it was not stored.
+    fn chunk_mut(&mut self, key: Vec<usize>) -> &mut Chunk {
+        let (corner, extents) = self.chunk_bounds(&key);
+        let (grid_dims, ga_dims) = (&self.grid_dims, self.ga_dims);
+        self.chunks.entry(key).or_insert_with(|| {
+            let size: usize = extents.iter().product();
+            let cells = (0..size)
+                .map(|local| {
+                    let (mut idx, mut stride) = (0, 1);
+                    for ((c, start), &dim) in Self::coord_in(&extents, local)
+                        .iter()
+                        .zip(&corner)
+                        .zip(grid_dims)
+                    {
+                        idx += (c + start) * stride;
+                        stride *= dim;
+                    }
+                    Self::vacuum_cell(idx as u64, ga_dims)
+                })
+                .collect();
+            Chunk {
+                cells,
+                quiet_for: 0,
+            }
+        })
+    }
+
+    /// The stored cell at a coordinate inside the grid, or `None` for vacuum.
+    fn lookup(&self, coord: &[usize]) -> Option<&Existon> {
+        let (key, local) = self.chunk_of(coord);
+        self.chunks.get(&key).map(|chunk| &chunk.cells[local])
+    }
+
+    /// The neighbors of a cell, with vacuum standing in for unstored cells.
+    fn neighbors(&self, coord: &[usize]) -> Vec<&Existon> {
+        self.offsets[coord.get(1).map_or(0, |row| row % 2)]
+            .iter()
+            .filter_map(|offset| {
+                let neighbor: Option<Vec<usize>> = coord
+                    .iter()
+                    .zip(offset)
+                    .zip(&self.grid_dims)
+                    .map(|((&c, &o), &dim)| self.boundary.resolve(c as i64 + o, dim))
+                    .collect();
+                neighbor.map(|neighbor| self.lookup(&neighbor).unwrap_or(&self.vacuum))
+            })
+            .collect()
+    }
+
+    /// Every chunk with a cell within neighborhood reach of the chunk `key`,
+    /// including itself.
+    fn chunks_around(&self, key: &[usize]) -> Vec<Vec<usize>> {
+        let reach = self
+            .offsets
+            .iter()
+            .flatten()
+            .flatten()
+            .map(|o| o.unsigned_abs() as i64)
+            .max()
+            .unwrap_or(0);
+        let (corner, extents) = self.chunk_bounds(key);
+        // The chunks each axis of the reachable box touches, through the boundary.
+        let axes: Vec<Vec<usize>> = (0..key.len())
+            .map(|d| {
+                let (start, end) = (corner[d] as i64, (corner[d] + extents[d]) as i64);
+                let touched: BTreeSet<usize> = (start - reach..end + reach)
+                    .filter_map(|c| self.boundary.resolve(c, self.grid_dims[d]))
+                    .map(|c| c / CHUNK_SIDE)
+                    .collect();
+                touched.into_iter().collect()
+            })
+            .collect();
+        let mut around = vec![Vec::new()];
+        for axis in &axes {
+            around = around
+                .into_iter()
+                .flat_map(|prefix: Vec<usize>| {
+                    axis.iter().map(move |&k| {
+                        let mut key = prefix.clone();
+                        key.push(k);
+                        key
+                    })
+                })
+                .collect();
+        }
+        around
+    }
+}
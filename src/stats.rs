@@ -0,0 +1,140 @@
+//! Summary statistics of a universe's population.
+//!
+//! Everything here is computed on demand from the public grid, in one pass, so
+//! the HUD and experiment harnesses share one definition of each measure.
+//! [`GradeTotals`] is the exception: every tick reports how it changed them.
+
+use crate::existon::ConsciousnessState;
+use crate::ga_core::Multivector;
+use crate::universe::Universe;
+use std::collections::HashMap;
+
+/// A snapshot of population-level measures; see [`Universe::stats`].
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UniverseStats {
+    /// The tick the statistics describe.
+    pub tick: u64,
+    pub potential: usize,
+    pub observed: usize,
+    pub operators: usize,
+    /// The number of entanglement groups.
+    pub entanglements: usize,
+    /// The average number of nonzero coefficients per cell.
+    pub mean_nonzero_coefficients: f64,
+    /// The number of nonzero coefficients of each grade across the grid, indexed
+    /// by grade (scalar, vector, bivector, ...).
+    pub grade_totals: Vec<usize>,
+    /// The Shannon entropy, in bits, of the distribution of distinct multivector
+    /// states over the cells: `0` when every cell is identical and `log2(cells)`
+    /// when every cell differs.
+    pub entropy: f64,
+}
+
+impl UniverseStats {
+    /// Computes the statistics of `universe`.
+    pub fn of(universe: &Universe) -> Self {
+        let mut stats = UniverseStats {
+            tick: universe.tick_count,
+            entanglements: universe.entanglement.len(),
+            grade_totals: vec![0; universe.ga_dims + 1],
+            ..UniverseStats::default()
+        };
+        let mut nonzero = 0;
+        let mut states: HashMap<_, usize> = HashMap::new();
+        for existon in &universe.grid {
+            match existon.consciousness {
+                ConsciousnessState::Potential => stats.potential += 1,
+                ConsciousnessState::Observed => stats.observed += 1,
+                ConsciousnessState::Operator => stats.operators += 1,
+            }
+            for (blade, coefficient) in existon.state.coefficients.iter().enumerate() {
+                if coefficient.0 != 0 {
+                    nonzero += 1;
+                    // The grade of a blade is the number of basis vectors in it.
+                    if let Some(total) = stats.grade_totals.get_mut(blade.count_ones() as usize) {
+                        *total += 1;
+                    }
+                }
+            }
+            *states
+                .entry(existon.state.coefficients.as_slice())
+                .or_default() += 1;
+        }
+
+        let cells = universe.grid.len();
+        if cells > 0 {
+            stats.mean_nonzero_coefficients = nonzero as f64 / cells as f64;
+            // Summed in a fixed order, so equal grids give equal bits.
+            let mut counts: Vec<usize> = states.into_values().collect();
+            counts.sort_unstable();
+            stats.entropy = -counts
+                .into_iter()
+                .map(|count| {
+                    let p = count as f64 / cells as f64;
+                    p * p.log2()
+                })
+                .sum::<f64>();
+        }
+        stats
+    }
+}
+
+/// Per-grade totals of the grid's states: the quantities to watch for what the
+/// rule conserves and what it dissipates.
+///
+/// As a tick's [`TickReport::totals_change`], each entry is the change over the
+/// tick instead, so the entries may be negative.
+///
+/// [`TickReport::totals_change`]: crate::universe::TickReport::totals_change
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GradeTotals {
+    /// The number of nonzero coefficients of each grade, indexed by grade.
+    pub weight: Vec<i64>,
+    /// The sum of the coefficients of each grade, each `+1` or `-1`, indexed
+    /// by grade.
+    pub charge: Vec<i64>,
+}
+
+impl GradeTotals {
+    /// All-zero totals for states of `ga_dims` dimensions.
+    pub fn zero(ga_dims: usize) -> Self {
+        GradeTotals {
+            weight: vec![0; ga_dims + 1],
+            charge: vec![0; ga_dims + 1],
+        }
+    }
+
+    /// The totals of `states`, each of `ga_dims` dimensions.
+    pub fn of<'a>(ga_dims: usize, states: impl IntoIterator<Item = &'a Multivector>) -> Self {
+        let mut totals = Self::zero(ga_dims);
+        for state in states {
+            totals.add(state, 1);
+        }
+        totals
+    }
+
+    /// Counts `state` in, or takes it out again if `sign` is `-1`.
+    pub fn add(&mut self, state: &Multivector, sign: i64) {
+        for (blade, coefficient) in state.coefficients.iter().enumerate() {
+            if coefficient.0 != 0 {
+                let grade = blade.count_ones() as usize;
+                if let (Some(weight), Some(charge)) =
+                    (self.weight.get_mut(grade), self.charge.get_mut(grade))
+                {
+                    *weight += sign;
+                    *charge += sign * coefficient.0 as i64;
+                }
+            }
+        }
+    }
+
+    /// The weight over every grade.
+    pub fn total_weight(&self) -> i64 {
+        self.weight.iter().sum()
+    }
+
+    /// The charge over every grade.
+    pub fn total_charge(&self) -> i64 {
+        self.charge.iter().sum()
+    }
+}
@@ -0,0 +1,323 @@
+//! Parameter sweeps: many headless runs over a grid of rates and seeds.
+//!
+//! A [`Sweep`] is read from a file in the settings file's TOML subset:
+//!
+//! ```toml
+//! [grid]
+//! dims = [64, 64]
+//! ga_dims = 3
+//! topology = "square"
+//!
+//! [sweep]
+//! observation = [0.0005, 0.001, 0.002]
+//! decay = [0.005, 0.01]
+//! fluctuation = [0.001]
+//! seeds = [1, 2, 3]
+//! ticks = 500
+//! out = "sweep"
+//! ```
+//!
+//! Every combination of an observation rate, a decay rate, a fluctuation rate
+//! and a seed is one run, ticked `ticks` times from a fresh universe. Each run
+//! logs its statistics per tick to `run-N.csv` in the `out` directory, as a
+//! [`StatsLog`] would, and `summary.csv` gets a [`RunSummary`] per run once
+//! they are all done. Runs are spread over the machine's cores; each depends
+//! only on its own parameters, so the results are the same on any machine.
+//! Rates left out keep their compiled-in values, and `seeds` defaults to `[0]`.
+//! Start one with `existons sweep config.toml`.
+
+use crate::app::Config;
+use crate::series::{StatsLog, StatsRow};
+use crate::settings::{SettingsError, TomlValue, toml_entries};
+use crate::universe::{Topology, Universe};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+/// The parameters of one run of a [`Sweep`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RunParams {
+    /// The run's position in the sweep, counting from zero.
+    pub index: usize,
+    pub seed: u64,
+    pub observation_rate: f64,
+    pub decay_rate: f64,
+    pub fluctuation_rate: f64,
+}
+
+/// What one run of a [`Sweep`] came to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    pub params: RunParams,
+    /// The statistics after the last tick.
+    pub last: StatsRow,
+    /// The average number of `Observed` cells over the ticks.
+    pub mean_observed: f64,
+    /// The average entropy over the ticks, in bits.
+    pub mean_entropy: f64,
+    /// Events summed over the ticks.
+    pub observations: usize,
+    pub decays: usize,
+    pub fluctuations: usize,
+    pub entanglement_triggers: usize,
+}
+
+impl RunSummary {
+    /// The column names, in the order [`RunSummary::to_csv`] writes the values.
+    pub const HEADER: &'static str = "run,seed,observation_rate,decay_rate,fluctuation_rate,\
+        ticks,potential,observed,operators,entanglement_groups,entropy,mean_observed,\
+        mean_entropy,observations,decays,fluctuations,entanglement_triggers";
+
+    /// The summary as a line of CSV, without the line break.
+    pub fn to_csv(&self) -> String {
+        let (p, last) = (&self.params, &self.last);
+        format!(
+            "{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            p.index,
+            p.seed,
+            p.observation_rate,
+            p.decay_rate,
+            p.fluctuation_rate,
+            last.tick,
+            last.potential,
+            last.observed,
+            last.operators,
+            last.entanglement_groups,
+            last.entropy,
+            self.mean_observed,
+            self.mean_entropy,
+            self.observations,
+            self.decays,
+            self.fluctuations,
+            self.entanglement_triggers
+        )
+    }
+}
+
+/// A grid of runs; see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Sweep {
+    pub grid_dims: Vec<usize>,
+    pub ga_dims: usize,
+    pub topology: Topology,
+    pub observation_rates: Vec<f64>,
+    pub decay_rates: Vec<f64>,
+    pub fluctuation_rates: Vec<f64>,
+    pub seeds: Vec<u64>,
+    /// How many ticks each run lasts.
+    pub ticks: u64,
+    /// The directory the results are written to.
+    pub out_dir: PathBuf,
+}
+
+impl Default for Sweep {
+    /// A single run of the compiled-in configuration, for 1000 ticks.
+    fn default() -> Self {
+        let config = Config::new();
+        Sweep {
+            grid_dims: config.grid_dims,
+            ga_dims: config.ga_dims,
+            topology: config.topology,
+            observation_rates: vec![config.observation_rate],
+            decay_rates: vec![config.decay_rate],
+            fluctuation_rates: vec![config.fluctuation_rate],
+            seeds: vec![0],
+            ticks: 1000,
+            out_dir: PathBuf::from("sweep"),
+        }
+    }
+}
+
+impl Sweep {
+    /// Reads a sweep from `text`.
+    pub fn parse(text: &str) -> Result<Self, SettingsError> {
+        let entries = toml_entries(text).map_err(|line| SettingsError::Syntax { line })?;
+        let mut sweep = Sweep::default();
+        for entry in entries {
+            let name = format!("{}.{}", entry.section, entry.key);
+            let invalid = || SettingsError::Invalid {
+                line: entry.line,
+                name: name.clone(),
+            };
+            let whole = |value: &TomlValue, min: f64| match value {
+                TomlValue::Number(n) if *n >= min && n.fract() == 0.0 => Some(*n as u64),
+                _ => None,
+            };
+            let rate = |value: &TomlValue| match value {
+                TomlValue::Number(n) if (0.0..=1.0).contains(n) => Some(*n),
+                _ => None,
+            };
+            // A list of values, or a single one; never empty.
+            let list = |read: &dyn Fn(&TomlValue) -> Option<f64>| {
+                let values = match &entry.value {
+                    TomlValue::Array(items) => items.iter().map(read).collect(),
+                    value => read(value).map(|v| vec![v]),
+                };
+                values
+                    .filter(|v: &Vec<f64>| !v.is_empty())
+                    .ok_or_else(invalid)
+            };
+            match name.as_str() {
+                "grid.dims" => {
+                    let dims = list(&|v| whole(v, 1.0).map(|n| n as f64))?;
+                    sweep.grid_dims = dims.into_iter().map(|n| n as usize).collect();
+                }
+                "grid.ga_dims" => {
+                    sweep.ga_dims = whole(&entry.value, 1.0).ok_or_else(invalid)? as usize
+                }
+                "grid.topology" => {
+                    sweep.topology = match &entry.value {
+                        TomlValue::String(s) if s.eq_ignore_ascii_case("square") => {
+                            Topology::Square
+                        }
+                        TomlValue::String(s) if s.eq_ignore_ascii_case("hexagonal") => {
+                            Topology::Hexagonal
+                        }
+                        _ => return Err(invalid()),
+                    }
+                }
+                "sweep.observation" => sweep.observation_rates = list(&rate)?,
+                "sweep.decay" => sweep.decay_rates = list(&rate)?,
+                "sweep.fluctuation" => sweep.fluctuation_rates = list(&rate)?,
+                "sweep.seeds" => {
+                    let seeds = list(&|v| whole(v, 0.0).map(|n| n as f64))?;
+                    sweep.seeds = seeds.into_iter().map(|n| n as u64).collect();
+                }
+                "sweep.ticks" => sweep.ticks = whole(&entry.value, 1.0).ok_or_else(invalid)?,
+                "sweep.out" => match &entry.value {
+                    TomlValue::String(dir) if !dir.is_empty() => sweep.out_dir = dir.into(),
+                    _ => return Err(invalid()),
+                },
+                _ => {
+                    return Err(SettingsError::UnknownSetting {
+                        line: entry.line,
+                        name,
+                    });
+                }
+            }
+        }
+        Ok(sweep)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, SettingsError> {
+        Sweep::parse(&fs::read_to_string(path).map_err(SettingsError::Io)?)
+    }
+
+    /// Every run, the rates varying slowest and the seed fastest.
+    pub fn runs(&self) -> Vec<RunParams> {
+        let mut runs = Vec::new();
+        for &observation_rate in &self.observation_rates {
+            for &decay_rate in &self.decay_rates {
+                for &fluctuation_rate in &self.fluctuation_rates {
+                    for &seed in &self.seeds {
+                        runs.push(RunParams {
+                            index: runs.len(),
+                            seed,
+                            observation_rate,
+                            decay_rate,
+                            fluctuation_rate,
+                        });
+                    }
+                }
+            }
+        }
+        runs
+    }
+
+    /// The universe a run starts from.
+    pub fn universe(&self, params: &RunParams) -> Universe {
+        let mut universe = Universe::with_seed(self.grid_dims.clone(), self.ga_dims, params.seed)
+            .with_topology(self.topology);
+        universe.observation_rate = params.observation_rate;
+        universe.decay_rate = params.decay_rate;
+        universe.fluctuation_rate = params.fluctuation_rate;
+        universe
+    }
+
+    /// Ticks one run to the end, logging every tick to `log`.
+    pub fn run_one(&self, params: &RunParams, log: &mut StatsLog) -> io::Result<RunSummary> {
+        let mut universe = self.universe(params);
+        let mut summary = RunSummary {
+            params: *params,
+            last: StatsRow::default(),
+            mean_observed: 0.0,
+            mean_entropy: 0.0,
+            observations: 0,
+            decays: 0,
+            fluctuations: 0,
+            entanglement_triggers: 0,
+        };
+        for _ in 0..self.ticks {
+            let report = universe.tick();
+            let row = StatsRow::of(&universe, &report);
+            log.write(&row)?;
+            summary.mean_observed += row.observed as f64;
+            summary.mean_entropy += row.entropy;
+            summary.observations += row.observations;
+            summary.decays += row.decays;
+            summary.fluctuations += row.fluctuations;
+            summary.entanglement_triggers += row.entanglement_triggers;
+            summary.last = row;
+        }
+        summary.mean_observed /= self.ticks.max(1) as f64;
+        summary.mean_entropy /= self.ticks.max(1) as f64;
+        Ok(summary)
+    }
+
+    /// The per-tick log of a run.
+    pub fn run_path(&self, params: &RunParams) -> PathBuf {
+        let runs = self.observation_rates.len()
+            * self.decay_rates.len()
+            * self.fluctuation_rates.len()
+            * self.seeds.len();
+        let width = runs.saturating_sub(1).to_string().len();
+        self.out_dir
+            .join(format!("run-{:0width$}.csv", params.index, width = width))
+    }
+
+    /// Carries out every run, on as many threads as the machine has cores,
+    /// calling `done` as each finishes, and writes `summary.csv`. Existing
+    /// logs in the directory are replaced. Returns the summaries in run order.
+    pub fn run(&self, done: impl Fn(&RunSummary) + Sync) -> io::Result<Vec<RunSummary>> {
+        fs::create_dir_all(&self.out_dir)?;
+        let runs = self.runs();
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(runs.iter().map(|_| None).collect::<Vec<_>>());
+        let threads = thread::available_parallelism().map_or(1, |n| n.get());
+        thread::scope(|scope| {
+            for _ in 0..threads.min(runs.len()) {
+                scope.spawn(|| {
+                    while let Some(params) = runs.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        let path = self.run_path(params);
+                        let result = fs::remove_file(&path)
+                            .or_else(|e| match e.kind() {
+                                io::ErrorKind::NotFound => Ok(()),
+                                _ => Err(e),
+                            })
+                            .and_then(|()| StatsLog::append(&path))
+                            .and_then(|mut log| self.run_one(params, &mut log));
+                        if let Ok(summary) = &result {
+                            done(summary);
+                        }
+                        results.lock().unwrap()[params.index] = Some(result);
+                    }
+                });
+            }
+        });
+        let summaries = results
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .flatten()
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut summary = fs::File::create(self.out_dir.join("summary.csv"))?;
+        writeln!(summary, "{}", RunSummary::HEADER)?;
+        for run in &summaries {
+            writeln!(summary, "{}", run.to_csv())?;
+        }
+        Ok(summaries)
+    }
+}
@@ -0,0 +1,84 @@
+//! A fixed timestep: ticks paced by real time rather than by frames.
+//!
+//! Piston delivers update events as often as the machine manages, so ticking
+//! once per event makes the simulation run faster or slower with the load. A
+//! [`FixedTimestep`] instead accumulates the real time that passes between
+//! frames and pays it out as whole ticks of a fixed length, zero or several
+//! per frame. When ticks take longer than they are worth the simulation falls
+//! behind, and [`FixedTimestep::drift`] says by how much.
+//!
+//! The part of a tick accumulated but not yet paid out,
+//! [`FixedTimestep::alpha`], lets drawing blend between the last tick and the
+//! next one so motion stays smooth at any tick rate.
+
+/// Real time is rounded up to a whole tick when this close to one, so frames
+/// of exactly one tick's length are never lost to floating-point error.
+const EPSILON: f64 = 1e-6;
+
+/// Paces ticks by real time; see the [module documentation](self).
+#[derive(Debug, Clone, PartialEq)]
+pub struct FixedTimestep {
+    /// The real time one tick stands for, in seconds.
+    pub dt: f64,
+    /// The most ticks run in one frame, so a slow machine keeps drawing while
+    /// it catches up.
+    pub max_ticks_per_frame: u32,
+    /// Real time passed while running, in seconds.
+    elapsed: f64,
+    /// Ticks completed while running.
+    ticks: u64,
+}
+
+impl FixedTimestep {
+    /// A timestep of `ticks_per_second` ticks, running up to four per frame.
+    pub fn new(ticks_per_second: f64) -> Self {
+        FixedTimestep {
+            dt: 1.0 / ticks_per_second.max(f64::MIN_POSITIVE),
+            max_ticks_per_frame: 4,
+            elapsed: 0.0,
+            ticks: 0,
+        }
+    }
+
+    /// Adds a frame's `seconds` of real time, returning how many ticks are now
+    /// due. Report the ones that complete with [`record`](Self::record); the
+    /// rest stay due.
+    pub fn advance(&mut self, seconds: f64) -> u32 {
+        self.elapsed += seconds.max(0.0);
+        let due = (self.elapsed / self.dt + EPSILON).floor() as u64;
+        let behind = due.saturating_sub(self.ticks);
+        behind.min(u64::from(self.max_ticks_per_frame)) as u32
+    }
+
+    /// Counts `ticks` more completed ticks.
+    pub fn record(&mut self, ticks: u32) {
+        self.ticks += u64::from(ticks);
+    }
+
+    /// Ticks completed so far.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Real time passed so far, in seconds.
+    pub fn elapsed(&self) -> f64 {
+        self.elapsed
+    }
+
+    /// How far the simulation lags real time, in seconds: under one tick when
+    /// it keeps up, growing when ticks cost more than they are worth.
+    pub fn drift(&self) -> f64 {
+        self.elapsed - self.ticks as f64 * self.dt
+    }
+
+    /// How far into the next tick real time is, from 0 just after a tick to 1
+    /// when the next is due; for blending drawing between ticks.
+    pub fn alpha(&self) -> f64 {
+        (self.drift() / self.dt).clamp(0.0, 1.0)
+    }
+
+    /// A one-line summary of the tick rate and drift, for the HUD.
+    pub fn describe(&self) -> String {
+        format!("{:.0} t/s  drift {:.2}s", 1.0 / self.dt, self.drift())
+    }
+}
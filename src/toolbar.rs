@@ -0,0 +1,151 @@
+//! The toolbar along the bottom of the window: a label per tool, then buttons.
+//!
+//! Like the [control panel](crate::panel), a [`Toolbar`] holds no widgets: it
+//! lays out its [`ToolbarItem`]s in screen pixels for a window size and answers
+//! which one is under a point. The frontend draws the items from
+//! [`Toolbar::items`], highlighting the one under the cursor, and presses on
+//! the toolbar go to the item under them instead of the active tool. A new
+//! button is a new [`ToolbarItem`] with a label, a width and an action.
+
+use crate::app::{AppState, ToolMode};
+use crate::input::AppAction;
+
+/// Something on the toolbar that can be clicked.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToolbarItem {
+    /// Switches to the tool.
+    Tool(ToolMode),
+    Pause,
+    Reset,
+    Save,
+}
+
+impl ToolbarItem {
+    /// Every item, left to right.
+    pub const ALL: [ToolbarItem; 10] = [
+        ToolbarItem::Tool(ToolMode::Observe),
+        ToolbarItem::Tool(ToolMode::Entangle),
+        ToolbarItem::Tool(ToolMode::Operator),
+        ToolbarItem::Tool(ToolMode::Disrupt),
+        ToolbarItem::Tool(ToolMode::Select),
+        ToolbarItem::Tool(ToolMode::Inspect),
+        ToolbarItem::Tool(ToolMode::Paint),
+        ToolbarItem::Pause,
+        ToolbarItem::Reset,
+        ToolbarItem::Save,
+    ];
+
+    /// The text shown for the item in `app`; tools are labelled with the keys
+    /// that select them.
+    pub fn label(self, app: &AppState) -> String {
+        match self {
+            ToolbarItem::Tool(tool) => {
+                let name = match tool {
+                    ToolMode::Observe => "Observe 🔎",
+                    ToolMode::Entangle => "Entangle 🔗",
+                    ToolMode::Operator => "Operator 🏗️",
+                    ToolMode::Disrupt => "Disrupt 🌊",
+                    ToolMode::Select => "Select ⬚",
+                    ToolMode::Inspect => "Inspect 🔬",
+                    ToolMode::Paint => "Paint 🖌",
+                };
+                match app.keymap.keys(AppAction::SelectTool(tool)) {
+                    Some(keys) => format!("[{}] {}", keys, name),
+                    None => name.to_string(),
+                }
+            }
+            ToolbarItem::Pause if app.paused => "Resume".to_string(),
+            ToolbarItem::Pause => "Pause".to_string(),
+            ToolbarItem::Reset => "Reset".to_string(),
+            ToolbarItem::Save => "Save".to_string(),
+        }
+    }
+
+    /// Whether the item is switched on in `app`: the active tool, or Pause
+    /// while paused.
+    pub fn is_active(self, app: &AppState) -> bool {
+        match self {
+            ToolbarItem::Tool(tool) => app.current_tool == tool,
+            ToolbarItem::Pause => app.paused,
+            ToolbarItem::Reset | ToolbarItem::Save => false,
+        }
+    }
+
+    /// What clicking the item does; the same as its key.
+    pub fn action(self) -> AppAction {
+        match self {
+            ToolbarItem::Tool(tool) => AppAction::SelectTool(tool),
+            ToolbarItem::Pause => AppAction::TogglePause,
+            ToolbarItem::Reset => AppAction::ResetUniverse,
+            ToolbarItem::Save => AppAction::SaveSession,
+        }
+    }
+
+    /// How much of the toolbar the item takes up.
+    fn width(self) -> f64 {
+        match self {
+            ToolbarItem::Tool(_) => 105.0,
+            ToolbarItem::Pause => 70.0,
+            ToolbarItem::Reset | ToolbarItem::Save => 60.0,
+        }
+    }
+}
+
+/// The toolbar's layout; see the [module documentation](self).
+pub struct Toolbar;
+
+impl Toolbar {
+    pub const HEIGHT: f64 = 40.0;
+    /// The gap before the first item.
+    const LEFT: f64 = 20.0;
+
+    /// The toolbar's rectangle in a window of `window_size`, as `[x, y, width,
+    /// height]`.
+    pub fn bounds(window_size: [f64; 2]) -> [f64; 4] {
+        [
+            0.0,
+            window_size[1] - Self::HEIGHT,
+            window_size[0],
+            Self::HEIGHT,
+        ]
+    }
+
+    /// Every item with the rectangle it responds in, left to right.
+    pub fn items(window_size: [f64; 2]) -> Vec<(ToolbarItem, [f64; 4])> {
+        let y = window_size[1] - Self::HEIGHT;
+        let mut x = Self::LEFT;
+        ToolbarItem::ALL
+            .iter()
+            .map(|&item| {
+                let rect = [x, y, item.width(), Self::HEIGHT];
+                x += item.width();
+                (item, rect)
+            })
+            .collect()
+    }
+
+    /// Where the text after the last item starts.
+    pub fn end(window_size: [f64; 2]) -> f64 {
+        Self::items(window_size)
+            .last()
+            .map_or(Self::LEFT, |(_, [x, _, width, _])| x + width)
+    }
+
+    /// Whether the screen point `pos` lies on the toolbar.
+    pub fn contains(window_size: [f64; 2], pos: [f64; 2]) -> bool {
+        within(Self::bounds(window_size), pos)
+    }
+
+    /// The item under the screen point `pos`, if any.
+    pub fn item_at(window_size: [f64; 2], pos: [f64; 2]) -> Option<ToolbarItem> {
+        Self::items(window_size)
+            .into_iter()
+            .find(|&(_, rect)| within(rect, pos))
+            .map(|(item, _)| item)
+    }
+}
+
+/// Whether `pos` lies in the rectangle `[x, y, width, height]`.
+fn within([x, y, width, height]: [f64; 4], pos: [f64; 2]) -> bool {
+    (x..=x + width).contains(&pos[0]) && (y..=y + height).contains(&pos[1])
+}
@@ -0,0 +1,443 @@
+//! The terminal frontend: the grid drawn with colored block characters, for
+//! machines reached over SSH where no window can open.
+//!
+//! [`run`] puts the terminal in raw mode and drives the same [`AppState`] as the
+//! window, turning keys into the same [`AppAction`]s through its
+//! [`Keymap`](crate::keymap::Keymap). Each character cell shows two grid cells, one above
+//! the other, as a half block with 24-bit foreground and background colors,
+//! and a sidebar shows the tick, the active tool and the population. The
+//! terminal has no mouse, so the arrow keys move a cursor cell and Enter
+//! clicks it with the active tool; `[Q]` or Esc quits.
+//!
+//! Everything but [`run`] is free of terminal I/O: [`parse_keys`] decodes the
+//! bytes a terminal sends, and [`Tui::render`] produces the escape sequences of
+//! a whole frame.
+
+use crate::app::{AppState, get_pos_from_coord};
+use crate::input::{AppAction, Modifiers};
+use piston_window::Key;
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// The width of the sidebar, in characters.
+const SIDEBAR: usize = 32;
+/// How long a frame lasts.
+const FRAME: Duration = Duration::from_millis(50);
+/// How often the terminal is asked for its size.
+const RESIZE_CHECK: Duration = Duration::from_secs(1);
+
+/// A key press decoded from the terminal's input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TermKey {
+    /// A printable character, or Tab.
+    Char(char),
+    /// A letter typed with Ctrl held.
+    Ctrl(char),
+    /// A printable character typed with Alt held, which terminals send as
+    /// Esc followed by the character.
+    Alt(char),
+    Up,
+    Down,
+    Left,
+    Right,
+    Enter,
+    Esc,
+    PageUp,
+    PageDown,
+    /// A function key, numbered from 1.
+    F(u8),
+}
+
+/// Decodes the bytes a terminal sends for key presses. Unknown escape
+/// sequences are dropped.
+pub fn parse_keys(bytes: &[u8]) -> Vec<TermKey> {
+    let mut keys = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let byte = bytes[i];
+        i += 1;
+        let key = match byte {
+            b'\r' | b'\n' => TermKey::Enter,
+            b'\t' => TermKey::Char('\t'),
+            0x1b => {
+                // Everything up to the final byte of a CSI or SS3 sequence.
+                let sequence = match bytes.get(i) {
+                    Some(b'[') => {
+                        let end = bytes[i + 1..]
+                            .iter()
+                            .position(|b| (0x40..=0x7e).contains(b))
+                            .map_or(bytes.len(), |at| i + 1 + at + 1);
+                        &bytes[i..end]
+                    }
+                    Some(b'O') => &bytes[i..(i + 2).min(bytes.len())],
+                    Some(&c @ 0x20..=0x7e) => {
+                        i += 1;
+                        keys.push(TermKey::Alt(c as char));
+                        continue;
+                    }
+                    _ => &[][..],
+                };
+                i += sequence.len();
+                match sequence {
+                    [] => TermKey::Esc,
+                    b"[A" | b"OA" => TermKey::Up,
+                    b"[B" | b"OB" => TermKey::Down,
+                    b"[C" | b"OC" => TermKey::Right,
+                    b"[D" | b"OD" => TermKey::Left,
+                    b"[5~" => TermKey::PageUp,
+                    b"[6~" => TermKey::PageDown,
+                    b"OP" => TermKey::F(1),
+                    b"OQ" => TermKey::F(2),
+                    b"OR" => TermKey::F(3),
+                    b"OS" => TermKey::F(4),
+                    b"[15~" => TermKey::F(5),
+                    b"[17~" => TermKey::F(6),
+                    b"[18~" => TermKey::F(7),
+                    b"[19~" => TermKey::F(8),
+                    b"[20~" => TermKey::F(9),
+                    b"[21~" => TermKey::F(10),
+                    _ => continue,
+                }
+            }
+            1..=26 => TermKey::Ctrl((b'a' + byte - 1) as char),
+            0x20..=0x7e => TermKey::Char(byte as char),
+            _ => continue,
+        };
+        keys.push(key);
+    }
+    keys
+}
+
+/// The Piston key a terminal key stands for, so the window's bindings apply.
+fn piston_key(key: TermKey) -> Option<Key> {
+    let key = match key {
+        TermKey::Char(c) | TermKey::Ctrl(c) | TermKey::Alt(c) => match c.to_ascii_lowercase() {
+            '0' => Key::D0,
+            '1' => Key::D1,
+            '2' => Key::D2,
+            '3' => Key::D3,
+            '4' => Key::D4,
+            '5' => Key::D5,
+            '6' => Key::D6,
+            '[' => Key::LeftBracket,
+            ']' => Key::RightBracket,
+            ' ' => Key::Space,
+            '\t' => Key::Tab,
+            c @ 'a'..='z' => {
+                const LETTERS: [Key; 26] = [
+                    Key::A,
+                    Key::B,
+                    Key::C,
+                    Key::D,
+                    Key::E,
+                    Key::F,
+                    Key::G,
+                    Key::H,
+                    Key::I,
+                    Key::J,
+                    Key::K,
+                    Key::L,
+                    Key::M,
+                    Key::N,
+                    Key::O,
+                    Key::P,
+                    Key::Q,
+                    Key::R,
+                    Key::S,
+                    Key::T,
+                    Key::U,
+                    Key::V,
+                    Key::W,
+                    Key::X,
+                    Key::Y,
+                    Key::Z,
+                ];
+                LETTERS[(c as u8 - b'a') as usize]
+            }
+            _ => return None,
+        },
+        TermKey::PageUp => Key::PageUp,
+        TermKey::PageDown => Key::PageDown,
+        TermKey::F(1) => Key::F1,
+        TermKey::F(2) => Key::F2,
+        TermKey::F(3) => Key::F3,
+        TermKey::F(4) => Key::F4,
+        TermKey::F(5) => Key::F5,
+        TermKey::F(6) => Key::F6,
+        TermKey::F(7) => Key::F7,
+        TermKey::F(8) => Key::F8,
+        TermKey::F(9) => Key::F9,
+        TermKey::F(10) => Key::F10,
+        _ => return None,
+    };
+    Some(key)
+}
+
+/// The terminal frontend's own state; see the [module documentation](self).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tui {
+    /// The cell of the viewed plane that Enter clicks, as column and row.
+    pub cursor: [usize; 2],
+    /// Set once the user asks to quit.
+    pub quit: bool,
+}
+
+impl Tui {
+    /// The width and height of the viewed plane of `app`'s grid.
+    fn plane(app: &AppState) -> [usize; 2] {
+        let universe = app.shown_universe();
+        let dims = universe.grid_dims.len();
+        let [across, down] = app.config.slice.axes;
+        let height = if dims > 1 {
+            universe.grid_dims[down]
+        } else {
+            1
+        };
+        [universe.grid_dims[across], height]
+    }
+
+    /// Points the application's cursor at the cursor cell.
+    fn aim(&self, app: &mut AppState) {
+        let universe = app.shown_universe();
+        let coord =
+            app.config
+                .slice
+                .coord(universe.grid_dims.len(), self.cursor[0], self.cursor[1]);
+        let half = app.config.cell_size / 2.0;
+        let [x, y] = get_pos_from_coord(&coord, &app.config, universe.topology());
+        let pos = app.camera.world_to_screen([x + half, y + half]);
+        app.apply(AppAction::MoveCursor(pos));
+    }
+
+    /// Acts on a key press: quits, moves or clicks the cursor, or applies the
+    /// action the window binds to the key.
+    pub fn handle(&mut self, key: TermKey, app: &mut AppState) {
+        let [width, height] = Self::plane(app);
+        let [x, y] = &mut self.cursor;
+        match key {
+            TermKey::Esc | TermKey::Char('q' | 'Q') => self.quit = true,
+            TermKey::Left => *x = x.saturating_sub(1),
+            TermKey::Right => *x = (*x + 1).min(width.saturating_sub(1)),
+            TermKey::Up => *y = y.saturating_sub(1),
+            TermKey::Down => *y = (*y + 1).min(height.saturating_sub(1)),
+            TermKey::Enter => {
+                self.aim(app);
+                app.apply(AppAction::PressPrimary);
+                app.apply_tool_effects();
+                app.apply(AppAction::ReleasePrimary);
+            }
+            key => {
+                let modifiers = Modifiers {
+                    ctrl: matches!(key, TermKey::Ctrl(_)),
+                    alt: matches!(key, TermKey::Alt(_)),
+                    ..Modifiers::default()
+                };
+                let action = piston_key(key).and_then(|key| app.keymap.action(key, modifiers));
+                if let Some(action) = action {
+                    app.apply(action);
+                }
+            }
+        }
+        self.aim(app);
+    }
+
+    /// The escape sequences that draw a whole frame of `app` on a terminal of
+    /// `columns` by `rows` characters: the part of the plane around the cursor
+    /// on the left, the sidebar on the right.
+    pub fn render(&self, app: &AppState, [columns, rows]: [usize; 2]) -> String {
+        let universe = app.shown_universe();
+        let dims = universe.grid_dims.len();
+        let [width, height] = Self::plane(app);
+        let view_columns = columns.saturating_sub(SIDEBAR + 1).max(1);
+        let view_rows = rows.max(1);
+        // Keep the cursor in view, scrolling no further than the plane's edge.
+        let origin = |cursor: usize, len: usize, view: usize| {
+            cursor
+                .saturating_sub(view / 2)
+                .min(len.saturating_sub(view))
+        };
+        let left = origin(self.cursor[0], width, view_columns);
+        let top = origin(self.cursor[1], height, view_rows * 2);
+        let color = |x: usize, y: usize| -> [u8; 3] {
+            if [x, y] == self.cursor {
+                return [255, 255, 255];
+            }
+            let coord = app.config.slice.coord(dims, x, y);
+            let Some(idx) = (x < width && y < height)
+                .then(|| universe.get_index_from_coord(&coord))
+                .flatten()
+            else {
+                return [0, 0, 0];
+            };
+            let [r, g, b, a] = app.cell_color(idx);
+            [r, g, b].map(|c| ((c * a).clamp(0.0, 1.0) * 255.0).round() as u8)
+        };
+
+        let sidebar = self.sidebar(app);
+        let mut frame = String::from("\x1b[H");
+        for row in 0..view_rows {
+            for column in 0..view_columns.min(width) {
+                let (x, y) = (left + column, top + row * 2);
+                let [fr, fg, fb] = color(x, y);
+                let [br, bg, bb] = color(x, y + 1);
+                let _ = write!(
+                    frame,
+                    "\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m\u{2580}",
+                    fr, fg, fb, br, bg, bb
+                );
+            }
+            let _ = write!(frame, "\x1b[0m\x1b[K");
+            if let Some(line) = sidebar.get(row) {
+                let _ = write!(
+                    frame,
+                    "\x1b[{}G {}",
+                    view_columns + 2,
+                    line.chars().take(SIDEBAR).collect::<String>()
+                );
+            }
+            if row + 1 < view_rows {
+                frame.push_str("\r\n");
+            }
+        }
+        frame
+    }
+
+    /// The sidebar's lines: where the run is, what the cursor is on, the
+    /// population, and the terminal's own keys.
+    fn sidebar(&self, app: &AppState) -> Vec<String> {
+        let universe = app.shown_universe();
+        let stats = universe.stats();
+        let coord =
+            app.config
+                .slice
+                .coord(universe.grid_dims.len(), self.cursor[0], self.cursor[1]);
+        let state = universe
+            .get_index_from_coord(&coord)
+            .map_or("-".to_string(), |idx| {
+                format!("{:?}", universe.grid[idx].consciousness)
+            });
+        let mut lines = vec![
+            "Existon Automaton".to_string(),
+            String::new(),
+            format!(
+                "Tick {}{}",
+                universe.tick_count,
+                if app.paused { " (paused)" } else { "" }
+            ),
+            format!("Tool {:?}", app.current_tool),
+            format!("Cursor {:?} {}", coord, state),
+            String::new(),
+            format!("Potential     {}", stats.potential),
+            format!("Observed      {}", stats.observed),
+            format!("Operators     {}", stats.operators),
+            format!("Entanglements {}", stats.entanglements),
+        ];
+        let plane = app.config.slice.describe(&universe.grid_dims);
+        if !plane.is_empty() {
+            lines.push(plane);
+        }
+        lines.extend([
+            String::new(),
+            "Arrows move  Enter clicks".to_string(),
+            "1-6 tools  Space pause".to_string(),
+            "Ctrl+Z undo  Q quit".to_string(),
+        ]);
+        lines
+    }
+}
+
+/// Runs a shell `stty` command on the terminal, returning what it printed.
+fn stty(args: &[&str]) -> io::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(Stdio::inherit())
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other("stty failed; is this a terminal?"));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// The terminal's size as columns and rows, if it can be read.
+fn terminal_size() -> Option<[usize; 2]> {
+    let size = stty(&["size"]).ok()?;
+    let mut parts = size.split_whitespace().map(str::parse::<usize>);
+    let rows = parts.next()?.ok()?;
+    let columns = parts.next()?.ok()?;
+    Some([columns, rows])
+}
+
+/// Restores the terminal when the frontend exits, even by panicking.
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enter() -> io::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["raw", "-echo"])?;
+        // The alternate screen, without a cursor.
+        print!("\x1b[?1049h\x1b[?25l\x1b[2J");
+        io::stdout().flush()?;
+        Ok(RawMode { saved })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        print!("\x1b[0m\x1b[?25h\x1b[?1049l");
+        let _ = io::stdout().flush();
+        let _ = stty(&[self.saved.as_str()]);
+    }
+}
+
+/// Runs `app` in the terminal until the user quits.
+pub fn run(app: &mut AppState) -> io::Result<()> {
+    let _raw = RawMode::enter()?;
+    let (sender, keys) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 64];
+        let mut stdin = io::stdin();
+        while let Ok(n) = stdin.read(&mut buf) {
+            if n == 0 || sender.send(buf[..n].to_vec()).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut tui = Tui::default();
+    tui.aim(app);
+    let mut size = terminal_size().unwrap_or([80, 24]);
+    let mut size_checked = Instant::now();
+    let mut last_frame = Instant::now();
+    let mut stdout = io::stdout();
+    while !tui.quit {
+        while let Ok(bytes) = keys.try_recv() {
+            for key in parse_keys(&bytes) {
+                tui.handle(key, app);
+            }
+        }
+        app.apply_tool_effects();
+        let now = Instant::now();
+        let seconds = now.duration_since(last_frame).as_secs_f64();
+        last_frame = now;
+        app.apply(AppAction::Advance(seconds));
+        app.record_frame(seconds);
+
+        if now.duration_since(size_checked) >= RESIZE_CHECK {
+            let resized = terminal_size().unwrap_or(size);
+            if resized != size {
+                print!("\x1b[2J");
+            }
+            size = resized;
+            size_checked = now;
+        }
+        stdout.write_all(tui.render(app, size).as_bytes())?;
+        stdout.flush()?;
+        std::thread::sleep(FRAME.saturating_sub(now.elapsed()));
+    }
+    Ok(())
+}
@@ -0,0 +1,178 @@
+//! Benchmark-driven performance tuning.
+//!
+//! The fastest way to tick a universe depends on both the configuration and the
+//! machine. [`calibrate`] times a few ticks of the actual universe under each
+//! candidate setting and keeps the best; [`PreferencesCache`] remembers the result
+//! per configuration so later runs start tuned without measuring again.
+//!
+//! Tuning never changes simulation results, only how fast they are computed.
+
+use crate::universe::Universe;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+/// How a universe spreads the work of one tick.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickTuning {
+    /// Worker threads used to evaluate deterministic update rules; `1` runs them inline.
+    pub threads: usize,
+    /// The number of cells handed to a worker at a time.
+    pub tile_size: usize,
+    /// How many ticks pass between undo checkpoints of the universe.
+    pub snapshot_interval: u64,
+}
+
+impl Default for TickTuning {
+    /// Single-threaded, which is always correct and is what an untuned universe uses.
+    fn default() -> Self {
+        TickTuning {
+            threads: 1,
+            tile_size: 4096,
+            snapshot_interval: 100,
+        }
+    }
+}
+
+/// The tile sizes tried for multi-threaded candidates.
+const TILE_SIZES: [usize; 4] = [256, 1024, 4096, 16384];
+/// Ticks timed per candidate; the fastest one counts, to ignore warm-up noise.
+const SAMPLE_TICKS: usize = 2;
+/// The share of simulation time snapshots may take up.
+const SNAPSHOT_BUDGET: f64 = 0.02;
+const MAX_SNAPSHOT_INTERVAL: u64 = 10_000;
+
+/// Measures tick cost for `universe` and picks the fastest tuning.
+///
+/// All measuring happens on clones, so the universe itself does not advance.
+pub fn calibrate(universe: &Universe) -> TickTuning {
+    let max_threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let mut candidates = vec![TickTuning::default()];
+    let mut threads = 2;
+    while threads <= max_threads {
+        for tile_size in TILE_SIZES {
+            // A tile larger than each worker's share would leave workers idle.
+            if tile_size * threads <= universe.grid.len() * 2 {
+                candidates.push(TickTuning {
+                    threads,
+                    tile_size,
+                    ..TickTuning::default()
+                });
+            }
+        }
+        threads *= 2;
+    }
+
+    let mut best = (TickTuning::default(), Duration::MAX);
+    for candidate in candidates {
+        let mut trial = universe.clone();
+        trial.tuning = candidate;
+        let cost = (0..SAMPLE_TICKS)
+            .map(|_| time(|| drop(trial.tick())))
+            .min()
+            .unwrap_or(Duration::MAX);
+        if cost < best.1 {
+            best = (candidate, cost);
+        }
+    }
+    let (mut tuning, tick_cost) = best;
+
+    // Snapshot often enough to be useful, but rarely enough to stay within budget.
+    let snapshot_cost = time(|| drop(universe.checkpoint()));
+    let interval = snapshot_cost.as_secs_f64() / (tick_cost.as_secs_f64() * SNAPSHOT_BUDGET);
+    tuning.snapshot_interval = (interval.ceil() as u64).clamp(1, MAX_SNAPSHOT_INTERVAL);
+    tuning
+}
+
+fn time(f: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    f();
+    start.elapsed()
+}
+
+/// A stable hash of everything that affects tick cost on this machine.
+///
+/// FNV-1a is used rather than the standard hasher because the value is stored
+/// on disk and must not change between builds.
+pub fn config_hash(universe: &Universe) -> u64 {
+    let key = format!(
+        "{:?}|{}|{:?}|{}",
+        universe.grid_dims,
+        universe.ga_dims,
+        universe.neighborhood(),
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    );
+    key.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+//================================================================================
+// Preferences Cache
+//================================================================================
+
+/// Calibration results, keyed by [`config_hash`].
+///
+/// Stored as text, one `hash threads tile_size snapshot_interval` line per entry.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PreferencesCache {
+    pub entries: HashMap<u64, TickTuning>,
+}
+
+impl PreferencesCache {
+    /// Reads the cache; a missing or unreadable file gives an empty cache and
+    /// malformed lines are skipped, since everything in it can be re-measured.
+    pub fn load(path: &Path) -> Self {
+        let text = fs::read_to_string(path).unwrap_or_default();
+        let entries = text
+            .lines()
+            .filter_map(|line| {
+                let fields: Vec<u64> = line
+                    .split_whitespace()
+                    .map(|f| f.parse().ok())
+                    .collect::<Option<_>>()?;
+                match fields[..] {
+                    [hash, threads, tile_size, snapshot_interval]
+                        if threads > 0 && tile_size > 0 && snapshot_interval > 0 =>
+                    {
+                        let tuning = TickTuning {
+                            threads: threads as usize,
+                            tile_size: tile_size as usize,
+                            snapshot_interval,
+                        };
+                        Some((hash, tuning))
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+        PreferencesCache { entries }
+    }
+
+    /// Writes the cache to a file.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut hashes: Vec<_> = self.entries.keys().collect();
+        hashes.sort();
+        let text: String = hashes
+            .into_iter()
+            .map(|hash| {
+                let t = &self.entries[hash];
+                format!(
+                    "{} {} {} {}\n",
+                    hash, t.threads, t.tile_size, t.snapshot_interval
+                )
+            })
+            .collect();
+        fs::write(path, text)
+    }
+
+    /// The cached tuning for `universe`, calibrating and caching it on a miss.
+    pub fn tuning_for(&mut self, universe: &Universe) -> TickTuning {
+        *self
+            .entries
+            .entry(config_hash(universe))
+            .or_insert_with(|| calibrate(universe))
+    }
+}
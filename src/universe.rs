@@ -1,79 +1,1798 @@
 //! Defines the `Universe`, which contains the N-dimensional grid of `Existon`
 //! instances and orchestrates the primary simulation rules.
 
-use crate::existon::{ConsciousnessState, Existon};
+use crate::array::GridArray;
+use crate::census::{Census, Structure};
+use crate::delta::UniverseDelta;
+use crate::entanglement::{CollapseSpeed, Decoherence, EntanglementGroups, GroupId, PairOperator};
+use crate::existon::{CollapsePolicy, ConsciousnessState, Existon};
+use crate::field::{ParameterField, Rate};
 use crate::ga_core::Multivector;
+use crate::graph::{EntanglementGraph, GraphFormat};
+use crate::pattern::Pattern;
+use crate::profile::TickTimings;
+use crate::rule::{MatzkeDefaultRule, RuleCtx, UpdateRule, interaction};
+use crate::seed_image::ImageMapping;
+use crate::stats::{GradeTotals, UniverseStats};
+use crate::tuning::TickTuning;
+use crate::watch::{CellHistory, WatchMode};
+use crate::wave::DisruptionWave;
+use rand::rngs::StdRng;
 use rand::seq::SliceRandom;
-use rand::{Rng, rng};
-use std::collections::HashMap;
+use rand::{Rng, RngCore, SeedableRng};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+//================================================================================
+// Boundary Conditions
+//================================================================================
+
+/// How the edges of the grid behave when a neighborhood or brush reaches past them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BoundaryCondition {
+    /// Opposite edges are connected, so the grid wraps around like a torus.
+    #[default]
+    Toroidal,
+    /// Everything beyond the edge is dead space that contributes nothing.
+    Fixed,
+    /// The edge acts as a mirror: a coordinate past the edge maps back onto the grid.
+    Reflective,
+}
+
+impl BoundaryCondition {
+    /// Every boundary condition, in the order they are cycled through.
+    pub const ALL: [BoundaryCondition; 3] = [
+        BoundaryCondition::Toroidal,
+        BoundaryCondition::Fixed,
+        BoundaryCondition::Reflective,
+    ];
+
+    /// Returns the next boundary condition in [`BoundaryCondition::ALL`].
+    pub fn next(self) -> Self {
+        let pos = Self::ALL.iter().position(|&b| b == self).unwrap_or(0);
+        Self::ALL[(pos + 1) % Self::ALL.len()]
+    }
+
+    /// Maps a possibly out-of-range coordinate along an axis of length `len`
+    /// onto the grid, or `None` if it falls into dead space.
+    pub fn resolve(self, c: i64, len: usize) -> Option<usize> {
+        let len = len as i64;
+        if (0..len).contains(&c) {
+            return Some(c as usize);
+        }
+        match self {
+            BoundaryCondition::Toroidal => Some(c.rem_euclid(len) as usize),
+            BoundaryCondition::Fixed => None,
+            BoundaryCondition::Reflective => {
+                // Mirror about the edges, repeating with period 2 * len.
+                let m = c.rem_euclid(2 * len);
+                Some(if m < len { m } else { 2 * len - 1 - m } as usize)
+            }
+        }
+    }
+}
+
+//================================================================================
+// Neighborhoods
+//================================================================================
+
+/// Which surrounding cells feed into a cell's local operator each tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// The `2N` cells that differ by one step along a single axis.
+    VonNeumann,
+    /// Every cell within `radius` steps along all axes at once (Chebyshev distance).
+    Moore { radius: usize },
+    /// An arbitrary list of offsets, one component per grid dimension.
+    Custom(Vec<Vec<i64>>),
+}
+
+impl Default for Neighborhood {
+    fn default() -> Self {
+        Neighborhood::Moore { radius: 1 }
+    }
+}
+
+impl Neighborhood {
+    /// Expands the neighborhood into its list of offsets for an `n_dims`-dimensional grid.
+    ///
+    /// The zero offset (the cell itself) is never included, and custom offsets of
+    /// the wrong length are dropped.
+    pub fn offsets(&self, n_dims: usize) -> Vec<Vec<i64>> {
+        match self {
+            Neighborhood::VonNeumann => (0..n_dims)
+                .flat_map(|d| {
+                    [-1, 1].map(|step| {
+                        let mut offset = vec![0; n_dims];
+                        offset[d] = step;
+                        offset
+                    })
+                })
+                .collect(),
+            Neighborhood::Moore { radius } => {
+                let r = *radius as i64;
+                let side = 2 * r + 1;
+                // Count through every combination in `[-r, r]^N` as a base-`side` number.
+                (0..side.pow(n_dims as u32))
+                    .map(|mut i| {
+                        (0..n_dims)
+                            .map(|_| {
+                                let o = i % side - r;
+                                i /= side;
+                                o
+                            })
+                            .collect::<Vec<i64>>()
+                    })
+                    .filter(|offset| offset.iter().any(|&o| o != 0))
+                    .collect()
+            }
+            Neighborhood::Custom(offsets) => offsets
+                .iter()
+                .filter(|offset| offset.len() == n_dims && offset.iter().any(|&o| o != 0))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// The shape of the cells a 2D grid is tiled with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Topology {
+    /// Square cells, each touching eight others.
+    #[default]
+    Square,
+    /// Hexagonal cells, each touching six others, with no diagonal bias.
+    ///
+    /// The grid keeps its rows and columns, and every odd row sits half a cell
+    /// to the right of the rows around it. Neighborhoods are measured in axial
+    /// coordinates: Moore and von Neumann neighborhoods take every cell within
+    /// `radius` (or one) steps across hexes, and custom offsets are axial
+    /// `[dq, dr]` steps. A toroidal grid needs an even number of rows to wrap
+    /// cleanly. Grids that are not 2D stay square.
+    Hexagonal,
+}
+
+impl Topology {
+    /// Every topology, in the order share codes number them.
+    pub const ALL: [Topology; 2] = [Topology::Square, Topology::Hexagonal];
+
+    /// Whether an `n_dims`-dimensional grid is tiled with hexagons.
+    pub fn is_hexagonal(self, n_dims: usize) -> bool {
+        self == Topology::Hexagonal && n_dims == 2
+    }
+
+    /// Expands `neighborhood` into grid offsets for cells in even and in odd rows.
+    ///
+    /// The two lists only differ on a hexagonal grid, where an odd row's
+    /// neighbors above and below sit half a cell further right.
+    pub fn offsets(self, neighborhood: &Neighborhood, n_dims: usize) -> [Vec<Vec<i64>>; 2] {
+        if !self.is_hexagonal(n_dims) {
+            let offsets = neighborhood.offsets(n_dims);
+            return [offsets.clone(), offsets];
+        }
+        let axial = match neighborhood {
+            Neighborhood::VonNeumann => Self::hex_ball(1),
+            Neighborhood::Moore { radius } => Self::hex_ball(*radius as i64),
+            Neighborhood::Custom(_) => neighborhood.offsets(n_dims),
+        };
+        // Axial `(dq, dr)` lands `dq` columns over plus however far row `r + dr`
+        // is shifted relative to row `r`.
+        [0, 1].map(|parity| {
+            axial
+                .iter()
+                .map(|step| {
+                    let (dq, dr) = (step[0], step[1]);
+                    vec![dq + (dr + parity).div_euclid(2), dr]
+                })
+                .collect()
+        })
+    }
+
+    /// Every axial step of at most `radius` hexes, except standing still.
+    fn hex_ball(radius: i64) -> Vec<Vec<i64>> {
+        let mut steps = Vec::new();
+        for dr in -radius..=radius {
+            for dq in -radius..=radius {
+                if (dq, dr) != (0, 0) && (dq + dr).abs() <= radius {
+                    steps.push(vec![dq, dr]);
+                }
+            }
+        }
+        steps
+    }
+
+    /// How far a cell is drawn to the right of its column, in cells.
+    pub fn row_shift(self, coord: &[usize]) -> f64 {
+        match coord {
+            [_, row] if self.is_hexagonal(2) && row % 2 == 1 => 0.5,
+            _ => 0.0,
+        }
+    }
+}
+
+//================================================================================
+// Update Orders
+//================================================================================
+
+/// The order in which cells are updated within a tick, and which states they see.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpdateOrder {
+    /// Every cell reads the previous generation, as if all updated at once.
+    #[default]
+    Synchronous,
+    /// Cells update one at a time in a freshly shuffled order, each reading the
+    /// live grid, so it sees the neighbors updated before it this tick.
+    RandomSequential,
+    /// Cells whose coordinates sum to an even number update first from the
+    /// previous generation, then the odd ones update from the result.
+    Checkerboard,
+}
+
+impl UpdateOrder {
+    /// Every order, in the order share codes number them.
+    pub const ALL: [UpdateOrder; 3] = [
+        UpdateOrder::Synchronous,
+        UpdateOrder::RandomSequential,
+        UpdateOrder::Checkerboard,
+    ];
+}
+
+//================================================================================
+// Random Schemes
+//================================================================================
+
+/// Where the update rule's random numbers come from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RandomScheme {
+    /// One generator seeded from the universe's seed, drawn from cell by cell in
+    /// index order. A stochastic rule then has to run on a single thread.
+    #[default]
+    Sequential,
+    /// Every cell draws from its own [`CellRng`], keyed by the seed, the tick
+    /// and the cell's ID, so cells draw the same numbers in any order and on any
+    /// thread, and stochastic rules are spread over threads like deterministic ones.
+    PerCell,
+}
+
+impl RandomScheme {
+    /// Every scheme, in the order share codes number them.
+    pub const ALL: [RandomScheme; 2] = [RandomScheme::Sequential, RandomScheme::PerCell];
+}
+
+/// The random stream of one cell in one tick under [`RandomScheme::PerCell`].
+///
+/// The stream is a pure function of `(seed, tick, id)`: the `n`th number is a
+/// SplitMix64 hash of a key derived from those three and the counter `n`, so no
+/// state is shared between cells. Tools that replay a run outside of this crate
+/// can rebuild every draw from [`CellRng::new`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CellRng {
+    key: u64,
+    counter: u64,
+}
+
+impl CellRng {
+    /// The stream the cell `id` draws from while tick `tick` of a universe
+    /// seeded with `seed` is computed.
+    pub fn new(seed: u64, tick: u64, id: u64) -> Self {
+        let key = splitmix64(splitmix64(splitmix64(seed).wrapping_add(tick)).wrapping_add(id));
+        CellRng { key, counter: 0 }
+    }
+}
+
+impl RngCore for CellRng {
+    fn next_u32(&mut self) -> u32 {
+        (self.next_u64() >> 32) as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.counter += 1;
+        splitmix64(
+            self.key
+                .wrapping_add(self.counter.wrapping_mul(GOLDEN_GAMMA)),
+        )
+    }
+
+    fn fill_bytes(&mut self, dst: &mut [u8]) {
+        rand::rand_core::impls::fill_bytes_via_next(self, dst)
+    }
+}
+
+/// The increment of the SplitMix64 sequence: 2^64 divided by the golden ratio.
+const GOLDEN_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// The SplitMix64 finalizer, which scrambles every bit of `z` into every bit
+/// of the result.
+fn splitmix64(z: u64) -> u64 {
+    let z = z.wrapping_add(GOLDEN_GAMMA);
+    let z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    let z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+//================================================================================
+// Interventions
+//================================================================================
+
+/// A user action applied to the universe from outside the simulation rules.
+///
+/// Interventions are logged together with the tick at which they happened so a
+/// seeded run can be replayed exactly (see [`Universe::replay`]).
+#[derive(Debug, Clone, PartialEq)]
+pub enum Intervention {
+    /// Collapse the cell at the given grid index.
+    Observe(usize),
+    /// Place an `Operator` at the given grid index.
+    SetOperator(usize),
+    /// Place an `Operator` with the given state at the given grid index; see
+    /// [`Universe::set_operator_with_state`].
+    SetOperatorState(usize, Multivector),
+    /// Give the `Operator` at the given grid index a velocity, one entry per grid
+    /// dimension; see [`Universe::set_operator_velocity`].
+    SetVelocity(usize, Vec<i64>),
+    /// Remove an `Operator` (or decay an `Observed` cell) at the given grid index.
+    ClearOperator(usize),
+    /// Entangle the second Existon with the first: it joins the first's group,
+    /// or the two form a new pair if the first is not entangled yet.
+    Entangle(u64, u64),
+    /// Decay the `Observed` cell at the given grid index.
+    Disrupt(usize),
+    /// Switch the grid's boundary condition.
+    SetBoundary(BoundaryCondition),
+    /// Apply an operation to every cell in an N-dimensional box; see
+    /// [`Universe::apply_region`].
+    Region(Vec<Range<usize>>, RegionOp),
+    /// Stamp a pattern with its corner at the given coordinate; see
+    /// [`Universe::stamp_pattern`].
+    Stamp(Vec<usize>, Pattern),
+    /// Give the grid new dimensions; see [`Universe::resize`].
+    Resize(Vec<usize>),
+    /// Entangle up to this many random pairs among the cells of an N-dimensional
+    /// box that are not entangled yet; see [`Universe::entangle_region_random`].
+    EntangleRegion(Vec<Range<usize>>, usize),
+    /// Break every entanglement group and entangle this many random pairs across
+    /// the whole grid; see [`Universe::rewire_entanglement`].
+    Rewire(usize),
+    /// Give the links of the given Existon's entanglement group their own
+    /// operator, or return them to the universe's with `None`; see
+    /// [`Universe::set_link_operator`].
+    SetPairOperator(u64, Option<PairOperator>),
+    /// Collapse the `Potential` cell at the given grid index as the partner of
+    /// an observed cell with the given state elsewhere; see
+    /// [`Universe::collapse_as_partner`].
+    CollapsePartner(usize, Multivector),
+    /// Emit a disruption wave from the given grid index with the given
+    /// amplitude; see [`Universe::emit_disruption`].
+    EmitDisruption(usize, f64),
+}
+
+/// What [`Universe::apply_region`] does to each cell of the region.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RegionOp {
+    /// Make every cell `Potential` with the given state.
+    Fill(Multivector),
+    /// Make every cell `Potential` with the zero state, emptying the region.
+    Clear,
+    /// Make every cell `Potential` with a fresh random state.
+    Randomize,
+    /// Collapse every `Potential` cell.
+    Observe,
+    /// Decay every `Observed` cell.
+    Disrupt,
+    /// Empty every cell like [`RegionOp::Clear`] and take it out of its
+    /// entanglement group, as cutting a block out of the grid does.
+    Cut,
+}
+
+/// Named operator states to place with [`Universe::set_operator_with_state`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OperatorPreset {
+    /// The scalar +1, which leaves the neighborhood product unchanged.
+    ScalarPlus,
+    /// The scalar -1, which negates it.
+    ScalarMinus,
+    /// The vector e0, the operator [`Universe::set_operator`] places.
+    #[default]
+    E0,
+    /// The bivector e0e1, a rotation in the e0-e1 plane.
+    E0E1,
+    /// The pseudoscalar, the product of every basis vector.
+    Pseudoscalar,
+}
+
+impl OperatorPreset {
+    /// Every preset, in palette order.
+    pub const ALL: [OperatorPreset; 5] = [
+        OperatorPreset::ScalarPlus,
+        OperatorPreset::ScalarMinus,
+        OperatorPreset::E0,
+        OperatorPreset::E0E1,
+        OperatorPreset::Pseudoscalar,
+    ];
+
+    /// The next preset in the palette, wrapping around.
+    pub fn next(self) -> Self {
+        let pos = Self::ALL.iter().position(|&p| p == self).unwrap_or(0);
+        Self::ALL[(pos + 1) % Self::ALL.len()]
+    }
+
+    /// A short label for the UI.
+    pub fn name(self) -> &'static str {
+        match self {
+            OperatorPreset::ScalarPlus => "+1",
+            OperatorPreset::ScalarMinus => "-1",
+            OperatorPreset::E0 => "e0",
+            OperatorPreset::E0E1 => "e0e1",
+            OperatorPreset::Pseudoscalar => "I",
+        }
+    }
+
+    /// The preset's state in a GA space of `ga_dims` dimensions.
+    ///
+    /// A blade the space is too small for gives the zero state.
+    pub fn state(self, ga_dims: usize) -> Multivector {
+        match self {
+            OperatorPreset::ScalarPlus => Multivector::scalar(ga_dims, 1),
+            OperatorPreset::ScalarMinus => Multivector::scalar(ga_dims, -1),
+            OperatorPreset::E0 if ga_dims >= 1 => Multivector::basis_vector(ga_dims, 0),
+            OperatorPreset::E0E1 if ga_dims >= 2 => Multivector::bivector(ga_dims, 0, 1),
+            OperatorPreset::Pseudoscalar => Multivector::pseudoscalar(ga_dims),
+            _ => Multivector::zero(ga_dims),
+        }
+    }
+}
+
+/// An [`Intervention`] stamped with the tick count at which it was applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LoggedIntervention {
+    pub tick: u64,
+    pub intervention: Intervention,
+}
 
 //================================================================================
 // Universe
 //================================================================================
 
-/// Represents the simulation space, containing all Existons and simulation parameters.
-/// The grid is a generic N-dimensional lattice.
-#[derive(Debug, Clone)]
-pub struct Universe {
-    /// The number of dimensions of the Geometric Algebra space for each Existon.
-    pub ga_dims: usize,
-    /// The dimensions of the simulation grid (e.g., `vec![120, 80]` for a 2D grid).
-    pub grid_dims: Vec<usize>,
-    /// A flat vector containing all `Existon` instances in the grid.
-    pub grid: Vec<Existon>,
-    /// Models non-locality by mapping an Existon's ID to its entangled partner's ID.
-    pub entangled_pairs: HashMap<u64, u64>,
-    /// The probability of a `Potential` Existon being spontaneously observed each tick.
-    pub observation_rate: f64,
-    /// The probability of an `Observed` Existon decaying back into a `Potential` state.
-    pub decay_rate: f64,
-    /// The percentage of Existons that are entangled with a partner.
-    pub entanglement_percentage: f64,
-    /// The probability of a `Potential` Existon spontaneously re-randomizing its state.
-    pub fluctuation_rate: f64,
-}
+/// Everything that happened during one tick, with grid coordinates.
+///
+/// Returned by [`Universe::tick`] so frontends and external tools can react to
+/// every kind of event, not just entanglement.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TickReport {
+    /// The tick that was computed, counting from zero.
+    pub tick: u64,
+    /// Cells observed by the local rule this tick.
+    pub observations: Vec<Vec<usize>>,
+    /// Observed cells that decayed back to `Potential`.
+    pub decays: Vec<Vec<usize>>,
+    /// `Potential` cells whose state spontaneously re-randomized.
+    pub fluctuations: Vec<Vec<usize>>,
+    /// Entanglements that fired, as (observed cell, partner it collapsed).
+    pub entanglements: Vec<(Vec<usize>, Vec<usize>)>,
+    /// Cells whose update was influenced by a neighboring `Operator`, as
+    /// (operator, influenced cell).
+    pub operator_interactions: Vec<(Vec<usize>, Vec<usize>)>,
+    /// Entanglement groups that decohered and broke apart, as their members' cells.
+    pub decoherences: Vec<Vec<Vec<usize>>>,
+    /// Moving `Operator`s that were relocated, as (from, to).
+    pub operator_moves: Vec<(Vec<usize>, Vec<usize>)>,
+    /// How the tick changed the grid's per-grade totals. Interventions
+    /// between ticks are not counted.
+    pub totals_change: GradeTotals,
+}
+
+/// An entanglement collapse on its way to a partner under [`CollapseSpeed::Limited`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InFlightCollapse {
+    /// The tick during which the collapse arrives.
+    pub due: u64,
+    /// The ID of the observed cell that sent it.
+    pub source: u64,
+    /// The ID of the partner it collapses, if that is still `Potential` by then.
+    pub target: u64,
+}
+
+/// A line to draw between two entangled cells; see [`Universe::entanglement_links`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntanglementLink {
+    /// The group the two cells belong to.
+    pub group: GroupId,
+    /// The grid index of the group's first member.
+    pub from: usize,
+    /// The grid index of another member.
+    pub to: usize,
+    /// The group's strength, 1 when fresh and fading towards 0.
+    pub strength: f64,
+}
+
+/// The progress of a tick being computed a chunk at a time.
+#[derive(Debug, Clone)]
+struct PendingTick {
+    /// The RNG as it was before the tick started, so checkpoints stay on tick boundaries.
+    rng_at_start: StdRng,
+    next_grid: Vec<Existon>,
+    /// The first cell the update rule has not visited yet.
+    next_cell: usize,
+    /// Whether the rule reads the universe's cached local interactions.
+    cached: bool,
+    /// How many cells' local interactions are computed this tick.
+    interactions: usize,
+    /// Time spent so far on the local step, when profiling.
+    local: Duration,
+    fluctuations: Vec<u64>,
+}
+
+/// The evolving part of a universe at a tick boundary; see [`Universe::checkpoint`].
+///
+/// Configuration (shape, rates, neighborhood, rule) is not included, so a
+/// checkpoint can only be restored into the universe it was taken from.
+#[derive(Debug, Clone)]
+pub struct Checkpoint {
+    grid_dims: Vec<usize>,
+    grid: Vec<Existon>,
+    entanglement: EntanglementGroups,
+    boundary: BoundaryCondition,
+    tick_count: u64,
+    interventions: Vec<LoggedIntervention>,
+    next_id: u64,
+    in_flight: Vec<InFlightCollapse>,
+    waves: Vec<DisruptionWave>,
+    previous: Vec<Existon>,
+    rng: StdRng,
+}
+
+/// Represents the simulation space, containing all Existons and simulation parameters.
+/// The grid is a generic N-dimensional lattice.
+#[derive(Debug, Clone)]
+pub struct Universe {
+    /// The number of dimensions of the Geometric Algebra space for each Existon.
+    pub ga_dims: usize,
+    /// The dimensions of the simulation grid (e.g., `vec![120, 80]` for a 2D grid).
+    ///
+    /// Change them with [`Universe::resize`] so the cells follow.
+    pub grid_dims: Vec<usize>,
+    /// The grid dimensions the universe was created with.
+    initial_dims: Vec<usize>,
+    /// A flat vector containing all `Existon` instances in the grid.
+    ///
+    /// An Existon keeps its `id` wherever it moves, so the ID is not its index;
+    /// look it up with [`Universe::index_of_id`]. Cells may be edited in place,
+    /// but code that rearranges them must leave the IDs where the universe put them.
+    pub grid: Vec<Existon>,
+    /// The grid index of every Existon, by ID.
+    id_index: HashMap<u64, usize>,
+    /// The ID the next new Existon will get; IDs are never reused.
+    next_id: u64,
+    /// Models non-locality: groups of Existon IDs that collapse together.
+    pub entanglement: EntanglementGroups,
+    /// The probability of a `Potential` Existon being spontaneously observed each tick.
+    pub observation_rate: f64,
+    /// The probability of an `Observed` Existon decaying back into a `Potential` state.
+    pub decay_rate: f64,
+    /// The percentage of Existons that are entangled with a partner.
+    pub entanglement_percentage: f64,
+    /// How quickly entanglement groups weaken and break; see [`Decoherence`].
+    pub decoherence: Decoherence,
+    /// How fast collapses reach entangled partners; see [`CollapseSpeed`].
+    pub collapse_speed: CollapseSpeed,
+    /// What a collapse does to a partner, unless its group overrides it; see
+    /// [`PairOperator`].
+    pub pair_operator: PairOperator,
+    /// `Operator`s with a velocity move at the end of every this many ticks;
+    /// 0 keeps them in place.
+    pub operator_move_interval: u64,
+    /// The probability of a `Potential` Existon spontaneously re-randomizing its state.
+    pub fluctuation_rate: f64,
+    /// How a state simplifies when it is observed, by the rule, a tool or entanglement.
+    pub collapse_policy: CollapsePolicy,
+    /// How neighborhoods behave at the edges of the grid.
+    ///
+    /// Change it with [`Universe::set_boundary`] so the change is logged for replay.
+    pub boundary: BoundaryCondition,
+    /// Per-cell overrides of the rates, by [`Rate`]; see
+    /// [`Universe::set_parameter_field`].
+    fields: [Option<ParameterField>; 3],
+    /// Which cells count as neighbors; see [`Universe::set_neighborhood`].
+    neighborhood: Neighborhood,
+    /// The shape of the cells; see [`Universe::set_topology`].
+    topology: Topology,
+    /// How the cells of a tick are sequenced; see [`Universe::set_update_order`].
+    update_order: UpdateOrder,
+    /// Where the update rule's randomness comes from; see [`Universe::set_random_scheme`].
+    random_scheme: RandomScheme,
+    /// Whether ticks follow the reversible second-order rule; see
+    /// [`Universe::set_reversible`].
+    reversible: bool,
+    /// The generation before the current one, by grid index, under the
+    /// reversible rule. Empty stands for all-zero `Potential` cells.
+    previous: Vec<Existon>,
+    /// How each cell evolves from its neighborhood; see [`Universe::set_rule`].
+    rule: Arc<dyn UpdateRule>,
+    /// The neighbor indices of every cell, concatenated; cell `i`'s neighbors are
+    /// `neighbor_table[neighbor_starts[i]..neighbor_starts[i + 1]]`.
+    ///
+    /// Rebuilt whenever the grid shape, boundary or neighborhood changes, so the
+    /// tick loop only does indexed reads.
+    neighbor_table: Vec<usize>,
+    neighbor_starts: Vec<usize>,
+    /// Scratch buffer for the next generation, reused across ticks.
+    next_grid: Vec<Existon>,
+    /// Every cell's local interaction as of the last tick, for rules that
+    /// [use it](UpdateRule::uses_local_interaction). Only cells whose
+    /// neighborhood changed since need their product recomputed.
+    interactions: Vec<Multivector>,
+    /// Whether `interactions` matches the generation now in `next_grid`.
+    interactions_valid: bool,
+    /// A tick that [`Universe::tick_within`] has started but not finished.
+    pending: Option<PendingTick>,
+    /// Interventions made while a tick was in progress, applied once it finishes.
+    deferred: Vec<Intervention>,
+    /// The seed the universe's RNG was created from.
+    pub seed: u64,
+    /// The number of ticks simulated so far.
+    pub tick_count: u64,
+    /// Every effective user intervention, in the order it was applied.
+    pub interventions: Vec<LoggedIntervention>,
+    /// How tick work is spread across threads. Never affects results.
+    pub tuning: TickTuning,
+    /// Whether ticks are timed; see [`Universe::tick_timings`]. Never
+    /// affects results.
+    pub profiling: bool,
+    /// Where the time of the last profiled tick went.
+    timings: TickTimings,
+    /// Collapses sent under [`CollapseSpeed::Limited`] that have not arrived yet,
+    /// in the order they were sent.
+    in_flight: Vec<InFlightCollapse>,
+    /// Disruption waves still spreading, in the order they were emitted.
+    waves: Vec<DisruptionWave>,
+    /// The recent history of every watched cell, by ID; see [`Universe::watch`].
+    watches: HashMap<u64, CellHistory>,
+    /// The RNG driving all stochastic rules, seeded from `seed`.
+    rng: StdRng,
+}
+
+impl Universe {
+    /// Creates a new `Universe` with given grid dimensions and GA dimensions,
+    /// seeded from the thread RNG.
+    ///
+    /// The thread RNG draws from the OS, so this needs the `desktop` feature;
+    /// a browser build seeds with [`Universe::with_seed`].
+    #[cfg(feature = "desktop")]
+    pub fn new(grid_dims: Vec<usize>, ga_dims: usize) -> Self {
+        Self::with_seed(grid_dims, ga_dims, rand::rng().random())
+    }
+
+    /// Creates a new `Universe` whose every random choice is derived from `seed`.
+    pub fn with_seed(grid_dims: Vec<usize>, ga_dims: usize, seed: u64) -> Self {
+        Self::build(grid_dims, ga_dims, seed, |rng| {
+            Multivector::random(ga_dims, rng)
+        })
+    }
+
+    /// Like [`Universe::with_seed`], but initializes each cell with a sparse state
+    /// limited to blades of grade `max_grade` or lower
+    /// (see [`Multivector::random_with`]).
+    pub fn with_sparse_init(
+        grid_dims: Vec<usize>,
+        ga_dims: usize,
+        seed: u64,
+        sparsity: f64,
+        max_grade: usize,
+    ) -> Self {
+        Self::build(grid_dims, ga_dims, seed, |rng| {
+            Multivector::random_with(ga_dims, sparsity, max_grade, rng)
+        })
+    }
+
+    /// Private helper that builds a seeded universe, drawing each cell's initial state from `init`.
+    fn build(
+        grid_dims: Vec<usize>,
+        ga_dims: usize,
+        seed: u64,
+        mut init: impl FnMut(&mut StdRng) -> Multivector,
+    ) -> Self {
+        let size: usize = grid_dims.iter().product();
+        let initial_entanglement = 0.05;
+        let mut universe = Universe {
+            initial_dims: grid_dims.clone(),
+            grid_dims,
+            ga_dims,
+            grid: Vec::with_capacity(size),
+            id_index: HashMap::with_capacity(size),
+            next_id: 0,
+            entanglement: EntanglementGroups::default(),
+            observation_rate: 0.0005,
+            decay_rate: 0.01,
+            entanglement_percentage: initial_entanglement,
+            decoherence: Decoherence::default(),
+            collapse_speed: CollapseSpeed::default(),
+            pair_operator: PairOperator::default(),
+            operator_move_interval: 4,
+            fluctuation_rate: 0.001,
+            collapse_policy: CollapsePolicy::default(),
+            boundary: BoundaryCondition::default(),
+            neighbor_table: Vec::new(),
+            neighbor_starts: Vec::new(),
+            next_grid: Vec::new(),
+            interactions: Vec::new(),
+            interactions_valid: false,
+            pending: None,
+            deferred: Vec::new(),
+            fields: [None, None, None],
+            neighborhood: Neighborhood::default(),
+            topology: Topology::default(),
+            update_order: UpdateOrder::default(),
+            random_scheme: RandomScheme::default(),
+            reversible: false,
+            previous: Vec::new(),
+            rule: Arc::new(MatzkeDefaultRule),
+            seed,
+            tick_count: 0,
+            interventions: Vec::new(),
+            tuning: TickTuning::default(),
+            profiling: false,
+            timings: TickTimings::default(),
+            in_flight: Vec::new(),
+            waves: Vec::new(),
+            watches: HashMap::new(),
+            rng: StdRng::seed_from_u64(seed),
+        };
+        for idx in 0..size {
+            // Each Existon is created within the specified p-dimensional GA space.
+            let id = universe.allocate_id();
+            universe.grid.push(Existon {
+                id,
+                consciousness: ConsciousnessState::Potential,
+                state: init(&mut universe.rng),
+                velocity: None,
+                born: 0,
+                observed_since: None,
+            });
+            universe.id_index.insert(id, idx);
+        }
+        universe.entanglement =
+            Self::generate_entangled_pairs(&universe.grid, initial_entanglement, &mut universe.rng);
+        universe.rebuild_neighbor_table();
+        universe
+    }
+
+    /// Hands out a fresh Existon ID.
+    fn allocate_id(&mut self) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        id
+    }
+
+    /// The grid index of the Existon with ID `id`, if it is in the grid.
+    pub fn index_of_id(&self, id: u64) -> Option<usize> {
+        self.id_index.get(&id).copied()
+    }
+
+    /// The coordinate of the Existon with ID `id`, if it is in the grid.
+    pub fn coord_of_id(&self, id: u64) -> Option<Vec<usize>> {
+        self.index_of_id(id)
+            .map(|idx| self.get_coord_from_index(idx))
+    }
+
+    /// Recomputes the index of every ID from the grid.
+    fn rebuild_id_index(&mut self) {
+        self.id_index = self
+            .grid
+            .iter()
+            .enumerate()
+            .map(|(idx, cell)| (cell.id, idx))
+            .collect();
+    }
+
+    /// The entanglement collapses sent under [`CollapseSpeed::Limited`] that have
+    /// not reached their partners yet, in the order they were sent.
+    pub fn in_flight_collapses(&self) -> &[InFlightCollapse] {
+        &self.in_flight
+    }
+
+    /// Every entanglement group as links from its first member to each of the
+    /// others, group by group in the order they were created. Members no longer
+    /// in the grid are left out.
+    pub fn entanglement_links(&self) -> Vec<EntanglementLink> {
+        let groups = &self.entanglement;
+        let mut links = Vec::new();
+        for group in groups.group_ids() {
+            let mut members = groups
+                .members(group)
+                .iter()
+                .filter_map(|&id| self.index_of_id(id));
+            let Some(from) = members.next() else {
+                continue;
+            };
+            let strength = groups.strength(group);
+            links.extend(members.map(|to| EntanglementLink {
+                group,
+                from,
+                to,
+                strength,
+            }));
+        }
+        links
+    }
+
+    /// The disruption waves still spreading, in the order they were emitted;
+    /// see [`Universe::emit_disruption`].
+    pub fn disruption_waves(&self) -> &[DisruptionWave] {
+        &self.waves
+    }
+
+    /// The grid dimensions the universe was created with, before any
+    /// [`Universe::resize`].
+    pub fn initial_dims(&self) -> &[usize] {
+        &self.initial_dims
+    }
+
+    /// Rebuilds the random entanglement pairs for a new percentage of the grid.
+    pub fn set_entanglement_percentage(&mut self, percentage: f64) {
+        self.settle();
+        self.entanglement_percentage = percentage;
+        self.entanglement = Self::generate_entangled_pairs(&self.grid, percentage, &mut self.rng);
+    }
+
+    /// Entangles random pairs among the cells in the box spanned by `region`, so
+    /// that `percentage` of its cells end up in a new pair.
+    ///
+    /// Only cells that are not entangled yet are paired, so wiring a region adds
+    /// local entanglement without touching the groups already there. Regions are
+    /// clipped like those of [`Universe::apply_region`], and the call is logged
+    /// as one intervention so it replays exactly.
+    pub fn entangle_region_random(&mut self, region: &[Range<usize>], percentage: f64) {
+        let pairs = Self::pair_count(self.region_indices(region).len(), percentage);
+        self.apply(Intervention::EntangleRegion(region.to_vec(), pairs));
+    }
+
+    /// Breaks every entanglement group and entangles new random pairs covering
+    /// `percentage` of the grid, leaving every cell's state alone.
+    ///
+    /// Unlike [`Universe::set_entanglement_percentage`] this is an intervention:
+    /// it is logged so replays and share codes reproduce it, and
+    /// `entanglement_percentage` keeps describing how the run started.
+    pub fn rewire_entanglement(&mut self, percentage: f64) {
+        let pairs = Self::pair_count(self.grid.len(), percentage);
+        self.apply(Intervention::Rewire(pairs));
+    }
+
+    /// Gives the links of `id`'s entanglement group their own [`PairOperator`],
+    /// overriding [`Universe::pair_operator`], or returns them to it with `None`.
+    ///
+    /// The operator belongs to the group: it lasts until the group breaks, and
+    /// a cell that leaves takes none with it. Nothing happens if `id` is not
+    /// entangled or a rotor is from another algebra.
+    pub fn set_link_operator(&mut self, id: u64, operator: Option<PairOperator>) {
+        self.apply(Intervention::SetPairOperator(id, operator));
+    }
+
+    /// The operator a collapse from `source` applies to `target`, both cell IDs:
+    /// their group's own, or the universe's.
+    pub fn link_operator(&self, source: u64, target: u64) -> &PairOperator {
+        [target, source]
+            .into_iter()
+            .find_map(|id| {
+                let group = self.entanglement.group_of(id)?;
+                self.entanglement.operator(group)
+            })
+            .unwrap_or(&self.pair_operator)
+    }
+
+    /// Returns the universe with the given boundary condition, for use at construction.
+    pub fn with_boundary(mut self, boundary: BoundaryCondition) -> Self {
+        self.set_boundary(boundary);
+        self
+    }
+
+    /// Switches the boundary condition of the grid.
+    pub fn set_boundary(&mut self, boundary: BoundaryCondition) {
+        self.apply(Intervention::SetBoundary(boundary));
+    }
+
+    /// Returns the universe with the given neighborhood, for use at construction.
+    pub fn with_neighborhood(mut self, neighborhood: Neighborhood) -> Self {
+        self.set_neighborhood(neighborhood);
+        self
+    }
+
+    /// The neighborhood used to build each cell's local operator.
+    pub fn neighborhood(&self) -> &Neighborhood {
+        &self.neighborhood
+    }
+
+    /// Switches the neighborhood, expanding its offsets once for the whole grid.
+    ///
+    /// The neighborhood is part of the universe's configuration: share codes record
+    /// only its latest value, so set it before the run starts.
+    pub fn set_neighborhood(&mut self, neighborhood: Neighborhood) {
+        self.settle();
+        self.neighborhood = neighborhood;
+        self.rebuild_neighbor_table();
+    }
+
+    /// Returns the universe with the given topology, for use at construction.
+    pub fn with_topology(mut self, topology: Topology) -> Self {
+        self.set_topology(topology);
+        self
+    }
+
+    /// The shape of the cells.
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// Switches between square and hexagonal cells.
+    ///
+    /// Like the neighborhood, the topology is part of the universe's
+    /// configuration: share codes record only its latest value.
+    pub fn set_topology(&mut self, topology: Topology) {
+        self.settle();
+        self.topology = topology;
+        self.rebuild_neighbor_table();
+    }
+
+    /// Returns the universe with the given update order, for use at construction.
+    pub fn with_update_order(mut self, order: UpdateOrder) -> Self {
+        self.set_update_order(order);
+        self
+    }
+
+    /// The order in which cells are updated within a tick.
+    pub fn update_order(&self) -> UpdateOrder {
+        self.update_order
+    }
+
+    /// Switches how the cells of each tick are sequenced.
+    ///
+    /// Only [`UpdateOrder::Synchronous`] spreads a tick over threads, the GPU or
+    /// several frames; the other orders compute each tick in one go.
+    pub fn set_update_order(&mut self, order: UpdateOrder) {
+        self.settle();
+        self.update_order = order;
+    }
+
+    /// Returns the universe with the given random scheme, for use at construction.
+    pub fn with_random_scheme(mut self, scheme: RandomScheme) -> Self {
+        self.set_random_scheme(scheme);
+        self
+    }
+
+    /// Where the update rule's random numbers come from.
+    pub fn random_scheme(&self) -> RandomScheme {
+        self.random_scheme
+    }
+
+    /// Switches where the update rule draws its random numbers from.
+    ///
+    /// The two schemes draw different numbers, so a seeded run only repeats
+    /// under the scheme it was made with; share codes and replays record it.
+    /// Tools, entanglement and the shuffle of [`UpdateOrder::RandomSequential`]
+    /// always draw from the universe's own generator.
+    pub fn set_random_scheme(&mut self, scheme: RandomScheme) {
+        self.settle();
+        self.random_scheme = scheme;
+    }
+
+    /// Returns the universe with the reversible rule on or off, for use at
+    /// construction.
+    pub fn with_reversible(mut self, reversible: bool) -> Self {
+        self.set_reversible(reversible);
+        self
+    }
+
+    /// Whether ticks follow the reversible second-order rule.
+    pub fn is_reversible(&self) -> bool {
+        self.reversible
+    }
+
+    /// Switches the reversible second-order rule on or off.
+    ///
+    /// In the style of Fredkin's reversible automata, each tick computes `F`,
+    /// the update rule applied to the current generation, and subtracts the
+    /// previous generation from it: `next = F(current) - previous`, blade by
+    /// blade in `Z(3)`, with a cell `Observed` when exactly one of `F` and the
+    /// previous generation has it observed. Since `previous = F(current) - next`
+    /// too, [`Universe::tick_backward`] runs the same rule in reverse exactly.
+    /// `F` draws from per-cell streams keyed by the tick whatever the
+    /// [`RandomScheme`], so it can be recomputed, and `Operator` cells are left
+    /// as they are.
+    ///
+    /// Only the local rule runs: entanglement, decoherence, moving operators,
+    /// disruption waves and cell lifetimes would lose what is needed to go
+    /// back, so they wait until the rule is switched off. Switching on starts
+    /// from an empty previous generation, which makes the first tick an
+    /// ordinary one.
+    pub fn set_reversible(&mut self, reversible: bool) {
+        self.settle();
+        self.reversible = reversible;
+        self.previous.clear();
+    }
+
+    /// Returns the universe with the given update rule, for use at construction.
+    pub fn with_rule(mut self, rule: impl UpdateRule + 'static) -> Self {
+        self.set_rule(rule);
+        self
+    }
+
+    /// The rule that computes each cell's next state.
+    pub fn rule(&self) -> &dyn UpdateRule {
+        self.rule.as_ref()
+    }
+
+    /// A shared handle to the update rule, for other backends running the same physics.
+    pub(crate) fn shared_rule(&self) -> Arc<dyn UpdateRule> {
+        Arc::clone(&self.rule)
+    }
+
+    /// Switches the update rule used by subsequent ticks.
+    ///
+    /// Like the neighborhood, the rule is configuration rather than an
+    /// intervention. Share codes cannot describe custom rules, so they always
+    /// rebuild with [`MatzkeDefaultRule`].
+    pub fn set_rule(&mut self, rule: impl UpdateRule + 'static) {
+        self.set_shared_rule(Arc::new(rule));
+    }
+
+    /// Switches to a rule that may be shared with other universes, such as
+    /// one made by a [`RuleRegistry`](crate::rule::RuleRegistry).
+    pub fn set_shared_rule(&mut self, rule: Arc<dyn UpdateRule>) {
+        self.settle();
+        self.rule = rule;
+    }
+
+    /// The global value of a rate, which cells without a field follow.
+    pub fn rate(&self, rate: Rate) -> f64 {
+        match rate {
+            Rate::Observation => self.observation_rate,
+            Rate::Decay => self.decay_rate,
+            Rate::Fluctuation => self.fluctuation_rate,
+        }
+    }
+
+    /// The rate that applies to the cell at grid index `idx`: its value in the
+    /// rate's field, or the global rate if there is none.
+    pub fn rate_at(&self, rate: Rate, idx: usize) -> f64 {
+        match self.parameter_field(rate) {
+            Some(field) => field.get(idx),
+            None => self.rate(rate),
+        }
+    }
+
+    /// The per-cell values overriding a rate, if any.
+    pub fn parameter_field(&self, rate: Rate) -> Option<&ParameterField> {
+        self.fields[rate.index()].as_ref()
+    }
+
+    /// Overrides a rate with a value per cell, or with `None` returns every cell
+    /// to the global rate.
+    ///
+    /// Like the rates, fields are configuration: share codes record the fields in
+    /// place when the code is made.
+    ///
+    /// # Panics
+    /// If the field does not have exactly one value per cell.
+    pub fn set_parameter_field(&mut self, rate: Rate, field: Option<ParameterField>) {
+        if let Some(field) = &field {
+            assert_eq!(
+                field.len(),
+                self.grid.len(),
+                "field does not match the grid"
+            );
+        }
+        self.settle();
+        self.fields[rate.index()] = field;
+    }
+
+    /// Population counts, grade distribution and entropy of the current grid.
+    pub fn stats(&self) -> UniverseStats {
+        UniverseStats::of(self)
+    }
+
+    /// The per-grade weight and charge of the current grid.
+    pub fn grade_totals(&self) -> GradeTotals {
+        GradeTotals::of(self.ga_dims, self.grid.iter().map(|cell| &cell.state))
+    }
+
+    /// The entanglement network: every entangled cell and the links between them.
+    pub fn entanglement_graph(&self) -> EntanglementGraph {
+        EntanglementGraph::of(self)
+    }
+
+    /// Writes the entanglement network to a file in `format`, for graph tools
+    /// such as Gephi or networkx.
+    pub fn export_entanglement_graph(&self, path: &Path, format: GraphFormat) -> io::Result<()> {
+        fs::write(path, self.entanglement_graph().render(format))
+    }
+
+    /// Every cell's coefficients as an array of shape `grid_dims × 2^p`; see
+    /// [`GridArray`].
+    pub fn to_array(&self) -> GridArray<i8> {
+        GridArray::coefficients(self)
+    }
+
+    /// Every cell's coefficients as an `ndarray` array of shape
+    /// `grid_dims × 2^p`, laid out as [`Universe::to_array`] lays them out.
+    #[cfg(feature = "ndarray")]
+    pub fn to_ndarray(&self) -> ndarray::ArrayD<i8> {
+        self.to_array().into()
+    }
+
+    /// A two-dimensional universe seeded from the picture at `path`, one cell
+    /// per pixel; see [`ImageMapping`].
+    pub fn from_image(path: &Path, mapping: &ImageMapping) -> image::ImageResult<Self> {
+        let picture = image::open(path)?.to_rgba8();
+        let dims = vec![picture.width() as usize, picture.height() as usize];
+        let mut universe = Universe::with_seed(dims, mapping.ga_dims, mapping.seed);
+        let pattern = mapping.pattern(&picture, &universe);
+        universe.stamp_pattern(&pattern, &[0, 0]);
+        Ok(universe)
+    }
+
+    /// Which cells are in `state`, as an array of shape `grid_dims`.
+    pub fn mask(&self, state: ConsciousnessState) -> GridArray<bool> {
+        GridArray::mask(self, state)
+    }
+
+    /// Runs a [`Census`] over the next `window` ticks of a copy of the universe
+    /// and returns the structures found at the end; the universe is left alone.
+    ///
+    /// Structures with a period longer than `window` are not recognized.
+    pub fn census(&self, window: usize) -> Vec<Structure> {
+        let mut universe = self.clone();
+        let mut census = Census::new(window);
+        census.record(&universe);
+        for _ in 0..window {
+            universe.tick();
+            census.record(&universe);
+        }
+        census.structures()
+    }
+
+    /// Starts recording the cell at `coord` after every tick, keeping its last
+    /// [`CellHistory::DEFAULT_CAPACITY`] states; see [`Universe::history`].
+    pub fn watch(&mut self, coord: &[usize]) {
+        self.watch_with(coord, CellHistory::DEFAULT_CAPACITY, WatchMode::EveryTick);
+    }
+
+    /// Starts recording the cell at `coord`, keeping up to `capacity` samples of
+    /// the ticks `mode` selects. Watching a watched cell starts its history over.
+    ///
+    /// The history follows the cell's ID, so a moving `Operator` takes it along.
+    /// Histories are an observer's notes rather than state: checkpoints, share
+    /// codes and deltas leave them out.
+    pub fn watch_with(&mut self, coord: &[usize], capacity: usize, mode: WatchMode) {
+        if let Some(idx) = self.get_index_from_coord(coord) {
+            let cell = &self.grid[idx];
+            let history = CellHistory::new(cell, self.tick_count, capacity, mode);
+            self.watches.insert(cell.id, history);
+        }
+    }
+
+    /// Stops recording the cell at `coord` and forgets its history.
+    pub fn unwatch(&mut self, coord: &[usize]) {
+        if let Some(idx) = self.get_index_from_coord(coord) {
+            self.watches.remove(&self.grid[idx].id);
+        }
+    }
+
+    /// The recorded history of the cell at `coord`, if it is watched.
+    pub fn history(&self, coord: &[usize]) -> Option<&CellHistory> {
+        let idx = self.get_index_from_coord(coord)?;
+        self.watches.get(&self.grid[idx].id)
+    }
+
+    /// Places a stable `Operator` cell on the grid at an N-dimensional coordinate.
+    pub fn set_operator(&mut self, coord: &[usize]) {
+        if let Some(idx) = self.get_index_from_coord(coord) {
+            self.apply(Intervention::SetOperator(idx));
+        }
+    }
+
+    /// Places an `Operator` with any state, such as one of the
+    /// [`OperatorPreset`]s. The state must have the universe's GA dimensions.
+    ///
+    /// An existing `Operator` takes on the new state.
+    pub fn set_operator_with_state(&mut self, coord: &[usize], state: Multivector) {
+        if let Some(idx) = self.get_index_from_coord(coord) {
+            self.apply(Intervention::SetOperatorState(idx, state));
+        }
+    }
+
+    /// Sets how far the `Operator` at `coord` moves along each grid dimension every
+    /// [`Universe::operator_move_interval`] ticks. An all-zero velocity stops it.
+    ///
+    /// Operators wrap around the edges of the grid whatever its boundary condition,
+    /// swapping places with the cell they move onto, and wait while another
+    /// `Operator` is in the way.
+    pub fn set_operator_velocity(&mut self, coord: &[usize], velocity: &[i64]) {
+        if let Some(idx) = self.get_index_from_coord(coord) {
+            self.apply(Intervention::SetVelocity(idx, velocity.to_vec()));
+        }
+    }
+
+    /// Clears an `Operator` cell, returning it to a `Potential` state.
+    pub fn clear_operator(&mut self, coord: &[usize]) {
+        if let Some(idx) = self.get_index_from_coord(coord) {
+            self.apply(Intervention::ClearOperator(idx));
+        }
+    }
+
+    /// Applies `op` to every cell in the box spanned by `region`, one range per grid
+    /// dimension.
+    ///
+    /// Ranges are clipped to the grid, and a region with the wrong number of ranges
+    /// is empty. Like the single-cell tools, the operation is logged as one
+    /// intervention so it replays exactly.
+    pub fn apply_region(&mut self, region: &[Range<usize>], op: RegionOp) {
+        self.apply(Intervention::Region(region.to_vec(), op));
+    }
+
+    /// The grid indices inside the box spanned by `region`, in index order.
+    ///
+    /// Ranges are clipped to the grid; a region with the wrong number of ranges
+    /// contains no cells.
+    pub fn region_indices(&self, region: &[Range<usize>]) -> Vec<usize> {
+        if region.len() != self.grid_dims.len() {
+            return Vec::new();
+        }
+        let clipped: Vec<Range<usize>> = region
+            .iter()
+            .zip(&self.grid_dims)
+            .map(|(r, &dim)| r.start.min(dim)..r.end.min(dim))
+            .collect();
+        if clipped.iter().any(|r| r.is_empty()) {
+            return Vec::new();
+        }
+        // Count through the box like an odometer, the first axis fastest.
+        let mut indices = Vec::new();
+        let mut coord: Vec<usize> = clipped.iter().map(|r| r.start).collect();
+        loop {
+            indices.extend(self.get_index_from_coord(&coord));
+            let Some(axis) = (0..coord.len()).find(|&d| coord[d] + 1 < clipped[d].end) else {
+                return indices;
+            };
+            coord[axis] += 1;
+            for d in 0..axis {
+                coord[d] = clipped[d].start;
+            }
+        }
+    }
+
+    /// Cuts the box spanned by `region` out of the grid as a reusable [`Pattern`].
+    pub fn extract_pattern(&self, region: &[Range<usize>]) -> Pattern {
+        Pattern::extract(self, region)
+    }
+
+    /// Cuts the box spanned by `region` out of the grid: returns it as a
+    /// [`Pattern`] and empties it, its cells leaving their entanglement groups.
+    ///
+    /// Logged as an intervention, so share codes and replays reproduce it.
+    pub fn cut_pattern(&mut self, region: &[Range<usize>]) -> Pattern {
+        let pattern = self.extract_pattern(region);
+        self.apply_region(region, RegionOp::Cut);
+        pattern
+    }
+
+    /// The box spanned by `region` shifted by `offset` cells along each grid
+    /// dimension, if it still lies wholly on the grid.
+    pub fn shifted_region(
+        &self,
+        region: &[Range<usize>],
+        offset: &[i64],
+    ) -> Option<Vec<Range<usize>>> {
+        if region.len() != self.grid_dims.len() {
+            return None;
+        }
+        region
+            .iter()
+            .zip(&self.grid_dims)
+            .enumerate()
+            .map(|(d, (range, &len))| {
+                let start = range.start as i64 + offset.get(d).copied().unwrap_or(0);
+                let end = start + range.len() as i64;
+                (start >= 0 && end <= len as i64).then_some(start as usize..end as usize)
+            })
+            .collect()
+    }
+
+    /// Moves the box spanned by `region` by `offset` cells along each grid
+    /// dimension, with the entanglements among its cells, and returns where it
+    /// landed. The cells it leaves are emptied as by [`Universe::cut_pattern`].
+    /// A move that would push the box off the grid is refused with `None`.
+    ///
+    /// Logged as interventions, so share codes and replays reproduce it.
+    pub fn move_region(
+        &mut self,
+        region: &[Range<usize>],
+        offset: &[i64],
+    ) -> Option<Vec<Range<usize>>> {
+        let target = self.shifted_region(region, offset)?;
+        let pattern = self.cut_pattern(region);
+        let at: Vec<usize> = target.iter().map(|range| range.start).collect();
+        self.stamp_pattern(&pattern, &at);
+        Some(target)
+    }
+
+    /// Gives the grid new dimensions, which may also add or drop grid dimensions.
+    ///
+    /// Cells inside both the old and the new grid keep their state and ID, and so
+    /// their entanglements; cells that are cut off leave their groups. Added
+    /// dimensions extrude the old grid: its cells stay in the first layer and every
+    /// other layer is a copy with new IDs and no entanglement. Dropped dimensions
+    /// keep only their first layer. Any other new cells are fresh `Potential`
+    /// Existons, and parameter fields are extended from their edges.
+    ///
+    /// Logged as an intervention, so share codes and replays reproduce it.
+    pub fn resize(&mut self, new_dims: &[usize]) {
+        self.apply(Intervention::Resize(new_dims.to_vec()));
+    }
+
+    /// Promotes the grid to one more dimension, `depth` layers deep, each a copy
+    /// of the current grid; see [`Universe::resize`].
+    pub fn extrude(&mut self, depth: usize) {
+        let mut new_dims = self.grid_dims.clone();
+        new_dims.push(depth);
+        self.resize(&new_dims);
+    }
+
+    /// Writes `pattern` onto the grid with its corner at `at`.
+    ///
+    /// Cells past the edge are mapped through the boundary condition. Entangled
+    /// pairs inside the pattern are recreated, replacing any partners the stamped
+    /// cells had before; other entanglements are left alone.
+    pub fn stamp_pattern(&mut self, pattern: &Pattern, at: &[usize]) {
+        self.apply(Intervention::Stamp(at.to_vec(), pattern.clone()));
+    }
+
+    /// Applies an intervention and records it in the log if it changed anything.
+    pub fn apply(&mut self, intervention: Intervention) {
+        // Interventions always land between ticks, so replays can reproduce them.
+        if self.pending.is_some() {
+            self.deferred.push(intervention);
+            return;
+        }
+        let effective = match &intervention {
+            &Intervention::Observe(idx) => {
+                idx < self.grid.len()
+                    && self.grid[idx].consciousness == ConsciousnessState::Potential
+            }
+            &Intervention::SetOperator(idx) => {
+                idx < self.grid.len()
+                    && self.grid[idx].consciousness != ConsciousnessState::Operator
+            }
+            Intervention::SetOperatorState(idx, state) => {
+                let idx = *idx;
+                idx < self.grid.len()
+                    && state.p == self.ga_dims
+                    && (self.grid[idx].consciousness != ConsciousnessState::Operator
+                        || self.grid[idx].state != *state)
+            }
+            Intervention::SetVelocity(idx, velocity) => {
+                let idx = *idx;
+                idx < self.grid.len()
+                    && velocity.len() == self.grid_dims.len()
+                    && self.grid[idx].consciousness == ConsciousnessState::Operator
+                    && self.grid[idx].velocity != Self::moving(velocity)
+            }
+            &Intervention::ClearOperator(idx) => {
+                idx < self.grid.len()
+                    && self.grid[idx].consciousness != ConsciousnessState::Potential
+            }
+            &Intervention::Disrupt(idx) => {
+                idx < self.grid.len()
+                    && self.grid[idx].consciousness == ConsciousnessState::Observed
+            }
+            &Intervention::Entangle(id1, id2) => {
+                id1 != id2
+                    && self.id_index.contains_key(&id1)
+                    && self.id_index.contains_key(&id2)
+                    && !self.entanglement.is_entangled(id2)
+            }
+            &Intervention::SetBoundary(boundary) => boundary != self.boundary,
+            Intervention::Region(region, op) => {
+                let mut cells = self.region_indices(region).into_iter();
+                match op {
+                    RegionOp::Fill(state) => state.p == self.ga_dims && cells.next().is_some(),
+                    RegionOp::Clear | RegionOp::Randomize | RegionOp::Cut => {
+                        cells.next().is_some()
+                    }
+                    RegionOp::Observe => cells
+                        .any(|idx| self.grid[idx].consciousness == ConsciousnessState::Potential),
+                    RegionOp::Disrupt => cells
+                        .any(|idx| self.grid[idx].consciousness == ConsciousnessState::Observed),
+                }
+            }
+            Intervention::Stamp(at, pattern) => {
+                pattern.ga_dims == self.ga_dims
+                    && pattern.placements(self, at).iter().any(Option::is_some)
+            }
+            Intervention::Resize(new_dims) => {
+                *new_dims != self.grid_dims
+                    && !new_dims.is_empty()
+                    && new_dims.iter().all(|&dim| dim > 0)
+            }
+            Intervention::EntangleRegion(region, pairs) => {
+                *pairs > 0
+                    && self
+                        .region_indices(region)
+                        .into_iter()
+                        .filter(|&idx| !self.entanglement.is_entangled(self.grid[idx].id))
+                        .nth(1)
+                        .is_some()
+            }
+            &Intervention::Rewire(pairs) => pairs > 0 || !self.entanglement.is_empty(),
+            Intervention::CollapsePartner(idx, source) => {
+                *idx < self.grid.len()
+                    && source.p == self.ga_dims
+                    && self.grid[*idx].consciousness == ConsciousnessState::Potential
+            }
+            &Intervention::EmitDisruption(idx, amplitude) => {
+                idx < self.grid.len() && amplitude > 0.0 && amplitude <= 1.0
+            }
+            Intervention::SetPairOperator(id, operator) => {
+                self.entanglement.group_of(*id).is_some_and(|group| {
+                    self.entanglement.operator(group) != operator.as_ref()
+                        && !matches!(operator, Some(PairOperator::Rotor(rotor)) if rotor.p != self.ga_dims)
+                })
+            }
+        };
+        if !effective {
+            return;
+        }
+
+        match &intervention {
+            &Intervention::Observe(idx) => self.grid[idx].observe(self.collapse_policy),
+            &Intervention::SetOperator(idx) => {
+                self.grid[idx].consciousness = ConsciousnessState::Operator;
+                self.grid[idx].state = self.fixed_operator_state();
+            }
+            Intervention::SetOperatorState(idx, state) => {
+                self.grid[*idx].consciousness = ConsciousnessState::Operator;
+                self.grid[*idx].state = state.clone();
+            }
+            Intervention::SetVelocity(idx, velocity) => {
+                self.grid[*idx].velocity = Self::moving(velocity);
+            }
+            &Intervention::ClearOperator(idx) => {
+                self.grid[idx].decay(&mut self.rng);
+                // Decay only works on Observed, so we ensure it's reset correctly.
+                if self.grid[idx].consciousness == ConsciousnessState::Operator {
+                    self.grid[idx] = Existon::new(self.grid[idx].id, self.ga_dims, &mut self.rng);
+                    self.grid[idx].born = self.tick_count;
+                }
+            }
+            &Intervention::Entangle(id1, id2) => match self.entanglement.group_of(id1) {
+                Some(group) => self.entanglement.join(id2, group),
+                None => {
+                    self.entanglement.entangle(&[id1, id2]);
+                }
+            },
+            &Intervention::Disrupt(idx) => self.grid[idx].decay(&mut self.rng),
+            &Intervention::SetBoundary(boundary) => {
+                self.boundary = boundary;
+                self.rebuild_neighbor_table();
+            }
+            Intervention::Region(region, op) => {
+                for idx in self.region_indices(region) {
+                    let cell = &mut self.grid[idx];
+                    match op {
+                        RegionOp::Fill(state) => {
+                            cell.consciousness = ConsciousnessState::Potential;
+                            cell.state = state.clone();
+                            cell.velocity = None;
+                        }
+                        RegionOp::Clear => {
+                            cell.consciousness = ConsciousnessState::Potential;
+                            cell.state = Multivector::zero(self.ga_dims);
+                            cell.velocity = None;
+                        }
+                        RegionOp::Randomize => {
+                            *cell = Existon::new(cell.id, self.ga_dims, &mut self.rng);
+                            cell.born = self.tick_count;
+                        }
+                        RegionOp::Observe => cell.observe(self.collapse_policy),
+                        RegionOp::Disrupt => cell.decay(&mut self.rng),
+                        RegionOp::Cut => {
+                            cell.consciousness = ConsciousnessState::Potential;
+                            cell.state = Multivector::zero(self.ga_dims);
+                            cell.velocity = None;
+                            self.entanglement.remove(cell.id);
+                        }
+                    }
+                }
+            }
+            Intervention::Stamp(at, pattern) => {
+                let targets = pattern.placements(self, at);
+                for (cell, &target) in pattern.cells.iter().zip(&targets) {
+                    if let Some(idx) = target {
+                        self.grid[idx].consciousness = cell.consciousness;
+                        self.grid[idx].state = cell.state.clone();
+                        self.grid[idx].velocity = None;
+                    }
+                }
+                for group in &pattern.entanglements {
+                    // Members that land in dead space are left out of the stamped group,
+                    // which replaces whatever groups the stamped cells were in.
+                    let ids: Vec<u64> = group
+                        .iter()
+                        .filter_map(|&pos| targets[pos])
+                        .map(|idx| self.grid[idx].id)
+                        .collect();
+                    self.entanglement.entangle(&ids);
+                }
+            }
+            Intervention::Resize(new_dims) => self.resize_grid(new_dims),
+            Intervention::EntangleRegion(region, pairs) => {
+                let ids: Vec<u64> = self
+                    .region_indices(region)
+                    .into_iter()
+                    .map(|idx| self.grid[idx].id)
+                    .filter(|&id| !self.entanglement.is_entangled(id))
+                    .collect();
+                Self::entangle_random_pairs(&mut self.entanglement, ids, *pairs, &mut self.rng);
+            }
+            &Intervention::Rewire(pairs) => {
+                self.entanglement.clear();
+                let ids = self.grid.iter().map(|cell| cell.id).collect();
+                Self::entangle_random_pairs(&mut self.entanglement, ids, pairs, &mut self.rng);
+            }
+            Intervention::SetPairOperator(id, operator) => {
+                if let Some(group) = self.entanglement.group_of(*id) {
+                    self.entanglement.set_operator(group, operator.clone());
+                }
+            }
+            Intervention::CollapsePartner(idx, source) => {
+                let cell = &mut self.grid[*idx];
+                cell.observe(self.collapse_policy);
+                cell.state = match &self.pair_operator {
+                    PairOperator::Swap => source.clone(),
+                    operator => operator.apply(&cell.state),
+                };
+            }
+            &Intervention::EmitDisruption(idx, amplitude) => {
+                let center = self.get_coord_from_index(idx);
+                self.waves.push(DisruptionWave::new(center, amplitude));
+            }
+        }
+        self.interventions.push(LoggedIntervention {
+            tick: self.tick_count,
+            intervention,
+        });
+    }
+
+    /// Captures the state needed to return to the current tick later.
+    ///
+    /// Mid-tick, this is the state the tick started from.
+    pub fn checkpoint(&self) -> Checkpoint {
+        let rng = match &self.pending {
+            Some(pending) => pending.rng_at_start.clone(),
+            None => self.rng.clone(),
+        };
+        Checkpoint {
+            grid_dims: self.grid_dims.clone(),
+            grid: self.grid.clone(),
+            entanglement: self.entanglement.clone(),
+            boundary: self.boundary,
+            tick_count: self.tick_count,
+            interventions: self.interventions.clone(),
+            next_id: self.next_id,
+            in_flight: self.in_flight.clone(),
+            waves: self.waves.clone(),
+            previous: self.previous.clone(),
+            rng,
+        }
+    }
+
+    /// Returns to a checkpoint taken from this universe, abandoning any tick in
+    /// progress and the interventions waiting for it.
+    ///
+    /// The intervention log and RNG come back too, so a replay of the restored
+    /// universe still matches it.
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.pending = None;
+        self.deferred.clear();
+        self.grid = checkpoint.grid;
+        self.entanglement = checkpoint.entanglement;
+        self.tick_count = checkpoint.tick_count;
+        self.interventions = checkpoint.interventions;
+        self.next_id = checkpoint.next_id;
+        self.in_flight = checkpoint.in_flight;
+        self.waves = checkpoint.waves;
+        self.previous = checkpoint.previous;
+        self.interactions_valid = false;
+        self.rng = checkpoint.rng;
+        self.rebuild_id_index();
+        let reshaped = self.grid_dims != checkpoint.grid_dims;
+        if reshaped {
+            self.reshape_fields(checkpoint.grid_dims);
+        }
+        if reshaped || self.boundary != checkpoint.boundary {
+            self.boundary = checkpoint.boundary;
+            self.rebuild_neighbor_table();
+        }
+    }
 
-impl Universe {
-    /// Creates a new `Universe` with given grid dimensions and GA dimensions.
-    pub fn new(grid_dims: Vec<usize>, ga_dims: usize) -> Self {
-        let size: usize = grid_dims.iter().product();
-        let mut grid = Vec::with_capacity(size);
-        for i in 0..size {
-            // Each Existon is created within the specified p-dimensional GA space.
-            grid.push(Existon::new(i as u64, ga_dims));
+    /// A stable hash of the evolving state: the grid's shape and cells, the
+    /// entanglement groups, the tick count and the RNG. Equal hashes at the
+    /// same tick mean two runs are, for all practical purposes, identical.
+    ///
+    /// FNV-1a over fixed-width values is used rather than the standard hasher,
+    /// so the value is the same on every build and platform and can be kept in
+    /// recordings; see [`Replay::verify`](crate::replay::Replay::verify).
+    /// Mid-tick, this is the hash of the state the tick started from.
+    pub fn state_hash(&self) -> u64 {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        let mut write = |value: u64| {
+            for byte in value.to_le_bytes() {
+                hash = (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3);
+            }
+        };
+        write(self.tick_count);
+        write(self.grid_dims.len() as u64);
+        self.grid_dims.iter().for_each(|&d| write(d as u64));
+        for cell in &self.grid {
+            write(cell.id);
+            write(cell.consciousness as u64);
+            cell.state
+                .coefficients
+                .iter()
+                .for_each(|c| write(c.0 as u64));
+            write(cell.born);
+            write(cell.observed_since.map_or(0, |since| since + 1));
+            match &cell.velocity {
+                Some(velocity) => {
+                    write(velocity.len() as u64);
+                    velocity.iter().for_each(|&v| write(v as u64));
+                }
+                None => write(u64::MAX),
+            }
         }
+        for group in self.entanglement.group_ids() {
+            write(group);
+            write(self.entanglement.strength(group).to_bits());
+            let members = self.entanglement.members(group);
+            write(members.len() as u64);
+            members.iter().for_each(|&id| write(id));
+        }
+        // The generator's state is summed up by the next number it would draw.
+        let mut rng = match &self.pending {
+            Some(pending) => pending.rng_at_start.clone(),
+            None => self.rng.clone(),
+        };
+        write(rng.next_u64());
+        hash
+    }
 
-        let initial_entanglement = 0.05;
-        let entangled_pairs = Self::generate_entangled_pairs(size, initial_entanglement);
+    /// The changes that turn this universe into `other`, a later state of the
+    /// same run: the cells that differ and the entanglement groups that formed,
+    /// broke or changed strength.
+    ///
+    /// Mid-tick, this universe's side is the state the tick started from.
+    pub fn diff(&self, other: &Universe) -> UniverseDelta {
+        let reshaped = self.grid_dims != other.grid_dims;
+        let cells = other
+            .grid
+            .iter()
+            .enumerate()
+            .filter(|&(idx, cell)| reshaped || self.grid.get(idx) != Some(cell))
+            .map(|(idx, cell)| (idx, cell.clone()))
+            .collect();
+        UniverseDelta {
+            tick: other.tick_count,
+            grid_dims: other.grid_dims.clone(),
+            ga_dims: other.ga_dims,
+            cells,
+            entanglement: self.entanglement.diff(&other.entanglement),
+            collapses: (self.in_flight != other.in_flight).then(|| other.in_flight.clone()),
+            waves: (self.waves != other.waves).then(|| other.waves.clone()),
+            next_id: other.next_id,
+        }
+    }
 
-        Universe {
-            grid_dims,
-            ga_dims,
-            grid,
-            entangled_pairs,
-            observation_rate: 0.0005,
-            decay_rate: 0.01,
-            entanglement_percentage: initial_entanglement,
-            fluctuation_rate: 0.001,
+    /// The whole universe as a delta: every cell, entanglement group, collapse
+    /// in flight and wave. Applied to a universe with no entanglement, of any
+    /// size, it brings it to this state, so a remote mirror can start from it
+    /// and follow with [`Universe::diff`]s.
+    pub fn to_delta(&self) -> UniverseDelta {
+        UniverseDelta {
+            tick: self.tick_count,
+            grid_dims: self.grid_dims.clone(),
+            ga_dims: self.ga_dims,
+            cells: self.grid.iter().cloned().enumerate().collect(),
+            entanglement: EntanglementGroups::default().diff(&self.entanglement),
+            collapses: Some(self.in_flight.clone()),
+            waves: Some(self.waves.clone()),
+            next_id: self.next_id,
         }
     }
 
-    /// Places a stable `Operator` cell on the grid at an N-dimensional coordinate.
-    pub fn set_operator(&mut self, coord: &[usize]) {
-        if let Some(idx) = self.get_index_from_coord(coord) {
-            self.grid[idx].consciousness = ConsciousnessState::Operator;
-            self.grid[idx].state = self.fixed_operator_state();
+    /// Brings the universe to the state a [`UniverseDelta`] describes, abandoning
+    /// any tick in progress and the interventions waiting for it.
+    ///
+    /// A delta carries state, not the inputs that led to it, so the intervention
+    /// log and RNG are left alone: the universe shows the run but no longer
+    /// replays it. Cells listed outside the grid are ignored.
+    pub fn apply_delta(&mut self, delta: &UniverseDelta) {
+        self.pending = None;
+        self.deferred.clear();
+        let reshaped = self.grid_dims != delta.grid_dims;
+        if reshaped {
+            let size = delta.grid_dims.iter().product();
+            let vacuum = Existon {
+                id: 0,
+                consciousness: ConsciousnessState::Potential,
+                state: Multivector::zero(self.ga_dims),
+                velocity: None,
+                born: 0,
+                observed_since: None,
+            };
+            self.grid.resize(size, vacuum);
+            self.reshape_fields(delta.grid_dims.clone());
+        }
+        for (idx, cell) in &delta.cells {
+            let Some(slot) = self.grid.get_mut(*idx) else {
+                continue;
+            };
+            if self.id_index.get(&slot.id) == Some(idx) {
+                self.id_index.remove(&slot.id);
+            }
+            self.id_index.insert(cell.id, *idx);
+            slot.clone_from(cell);
+        }
+        self.entanglement.apply_delta(&delta.entanglement);
+        if let Some(collapses) = &delta.collapses {
+            self.in_flight.clone_from(collapses);
+        }
+        if let Some(waves) = &delta.waves {
+            self.waves.clone_from(waves);
+        }
+        self.tick_count = delta.tick;
+        self.next_id = delta.next_id;
+        if reshaped {
+            self.rebuild_id_index();
+            self.rebuild_neighbor_table();
         }
     }
 
-    /// Clears an `Operator` cell, returning it to a `Potential` state.
-    pub fn clear_operator(&mut self, coord: &[usize]) {
-        if let Some(idx) = self.get_index_from_coord(coord) {
-            self.grid[idx].decay();
-            // Decay only works on Observed, so we ensure it's reset correctly.
-            if self.grid[idx].consciousness == ConsciousnessState::Operator {
-                self.grid[idx] = Existon::new(self.grid[idx].id, self.ga_dims);
+    /// Switches to new grid dimensions, resampling the parameter fields to match.
+    fn reshape_fields(&mut self, grid_dims: Vec<usize>) {
+        let old_dims = std::mem::replace(&mut self.grid_dims, grid_dims);
+        for field in self.fields.iter_mut().flatten() {
+            *field = field.resized(&old_dims, &self.grid_dims);
+        }
+    }
+
+    /// Re-runs a seeded universe for `ticks` ticks, applying each logged
+    /// intervention at the tick it was originally made.
+    ///
+    /// Given the same seed, parameters and log, the result is identical to the
+    /// universe that produced the log.
+    pub fn replay(mut self, log: &[LoggedIntervention], ticks: u64) -> Self {
+        self.replay_until(log, ticks);
+        self
+    }
+
+    /// Continues a replay in place until tick `ticks`.
+    ///
+    /// Entries already in the universe's own log are skipped, so a universe
+    /// restored from a checkpoint of an earlier replay picks up where it was.
+    pub fn replay_until(&mut self, log: &[LoggedIntervention], ticks: u64) {
+        let done = self.interventions.len().min(log.len());
+        let mut pending = log[done..].iter().peekable();
+        loop {
+            while let Some(entry) = pending.next_if(|entry| entry.tick <= self.tick_count) {
+                self.apply(entry.intervention.clone());
             }
+            if self.tick_count >= ticks {
+                break;
+            }
+            self.tick();
         }
     }
 
@@ -81,26 +1800,88 @@ impl Universe {
 
     /// Calculates the 1D index for an N-dimensional grid coordinate.
     pub fn get_index_from_coord(&self, coord: &[usize]) -> Option<usize> {
-        if coord.len() != self.grid_dims.len() {
+        Self::index_from_coord(&self.grid_dims, coord)
+    }
+
+    /// Calculates the N-dimensional coordinate from a 1D grid index.
+    pub fn get_coord_from_index(&self, index: usize) -> Vec<usize> {
+        Self::coord_from_index(&self.grid_dims, self.grid.len(), index)
+    }
+
+    /// The grid indices of the neighbors of the cell at `coord`, following the
+    /// universe's neighborhood and boundary condition.
+    ///
+    /// Neighbors in dead space are left out; on a grid narrower than the
+    /// neighborhood, a cell reached by several offsets appears once for each.
+    /// A coordinate off the grid has no neighbors.
+    pub fn neighbors(&self, coord: &[usize]) -> impl Iterator<Item = usize> + '_ {
+        let range = match self.get_index_from_coord(coord) {
+            Some(idx) => self.neighbor_starts[idx]..self.neighbor_starts[idx + 1],
+            None => 0..0,
+        };
+        self.neighbor_table[range].iter().copied()
+    }
+
+    /// Every cell with its coordinate, in grid index order.
+    pub fn iter_cells(&self) -> impl Iterator<Item = (Vec<usize>, &Existon)> {
+        self.grid
+            .iter()
+            .enumerate()
+            .map(|(idx, cell)| (self.get_coord_from_index(idx), cell))
+    }
+
+    /// The grid indices of every cell matching `predicate`, in ascending order.
+    pub fn find(&self, predicate: impl Fn(&Existon) -> bool) -> Vec<usize> {
+        (0..self.grid.len())
+            .filter(|&idx| predicate(&self.grid[idx]))
+            .collect()
+    }
+
+    /// The grid indices of every cell in `state`, in ascending order.
+    pub fn cells_in_state(&self, state: ConsciousnessState) -> Vec<usize> {
+        self.find(|cell| cell.consciousness == state)
+    }
+
+    /// The grid indices of every cell whose state has a nonzero coefficient on
+    /// `blade`, in ascending order.
+    pub fn cells_with_blade(&self, blade: usize) -> Vec<usize> {
+        self.find(|cell| cell.state.coefficients.get(blade).is_some_and(|c| c.0 != 0))
+    }
+
+    /// The grid index of the `Operator` closest to `coord`, counting the cells
+    /// between them along the longest axis and going around the edges of a
+    /// toroidal grid. Ties go to the lowest index; `None` if there is no
+    /// `Operator` or `coord` is off the grid.
+    pub fn nearest_operator(&self, coord: &[usize]) -> Option<usize> {
+        let from = self.get_index_from_coord(coord)?;
+        self.cells_in_state(ConsciousnessState::Operator)
+            .into_iter()
+            .min_by_key(|&idx| (self.light_distance(from, idx), idx))
+    }
+
+    // The helpers below only need the grid shape, so `tick` can use them while
+    // it holds a mutable borrow of the universe's RNG.
+
+    fn index_from_coord(grid_dims: &[usize], coord: &[usize]) -> Option<usize> {
+        if coord.len() != grid_dims.len() {
             return None;
         }
         let mut index = 0;
         let mut stride = 1;
         for (i, &c) in coord.iter().enumerate() {
-            if c >= self.grid_dims[i] {
+            if c >= grid_dims[i] {
                 return None;
             }
             index += stride * c;
-            stride *= self.grid_dims[i];
+            stride *= grid_dims[i];
         }
         Some(index)
     }
 
-    /// Calculates the N-dimensional coordinate from a 1D grid index.
-    pub fn get_coord_from_index(&self, mut index: usize) -> Vec<usize> {
-        let mut coord = vec![0; self.grid_dims.len()];
-        let mut stride = self.grid.len();
-        for (i, &dim) in self.grid_dims.iter().enumerate().rev() {
+    fn coord_from_index(grid_dims: &[usize], size: usize, mut index: usize) -> Vec<usize> {
+        let mut coord = vec![0; grid_dims.len()];
+        let mut stride = size;
+        for (i, &dim) in grid_dims.iter().enumerate().rev() {
             stride /= dim;
             coord[i] = index / stride;
             index %= stride;
@@ -108,33 +1889,108 @@ impl Universe {
         coord
     }
 
-    /// Gets the indices of all neighbors for a given N-dimensional coordinate (Moore neighborhood).
-    fn get_neighbors(&self, coord: &[usize]) -> Vec<usize> {
-        let mut neighbors = Vec::new();
-        let n_dims = self.grid_dims.len();
-
-        // This iterator generates all {-1, 0, 1} combinations for N dimensions.
-        for i in 0..(3_i32.pow(n_dims as u32)) {
-            let mut offset = Vec::new();
-            let mut temp = i;
-            // The all-zero offset is the cell itself, so we skip it.
-            if temp == 0 {
-                continue;
+    /// Rebuilds the grid with new dimensions; see [`Universe::resize`].
+    fn resize_grid(&mut self, new_dims: &[usize]) {
+        let old_dims = std::mem::replace(&mut self.grid_dims, new_dims.to_vec());
+        let old_grid = std::mem::take(&mut self.grid);
+        let size: usize = new_dims.iter().product();
+        let mut kept = vec![false; old_grid.len()];
+        self.grid.reserve(size);
+        for idx in 0..size {
+            let coord = Self::coord_from_index(new_dims, size, idx);
+            let cell = match Self::resize_source(&old_dims, &coord) {
+                Some((old_idx, original)) => {
+                    let mut cell = old_grid[old_idx].clone();
+                    if original {
+                        kept[old_idx] = true;
+                    } else {
+                        cell.id = self.allocate_id();
+                        cell.born = self.tick_count;
+                    }
+                    cell.velocity = cell.velocity.and_then(|mut velocity| {
+                        velocity.resize(new_dims.len(), 0);
+                        Self::moving(&velocity)
+                    });
+                    cell
+                }
+                None => Existon {
+                    born: self.tick_count,
+                    ..Existon::new(self.allocate_id(), self.ga_dims, &mut self.rng)
+                },
+            };
+            self.grid.push(cell);
+        }
+        for (cell, kept) in old_grid.iter().zip(kept) {
+            if !kept {
+                self.entanglement.remove(cell.id);
             }
+        }
+        for field in self.fields.iter_mut().flatten() {
+            *field = field.resized(&old_dims, new_dims);
+        }
+        self.waves
+            .retain(|wave| Self::index_from_coord(new_dims, &wave.center).is_some());
+        self.rebuild_id_index();
+        self.rebuild_neighbor_table();
+    }
 
-            for _ in 0..n_dims {
-                offset.push(temp % 3 - 1);
-                temp /= 3;
+    /// The old grid index a cell of a resized grid is taken from, and whether it
+    /// is the original cell rather than an extruded copy.
+    fn resize_source(old_dims: &[usize], coord: &[usize]) -> Option<(usize, bool)> {
+        // Dimensions the new grid drops are read at their first layer.
+        let mut old_coord = vec![0; old_dims.len()];
+        let mut original = true;
+        for (d, &c) in coord.iter().enumerate() {
+            match old_dims.get(d) {
+                Some(&dim) if c < dim => old_coord[d] = c,
+                Some(_) => return None,
+                None => original &= c == 0,
             }
+        }
+        Self::index_from_coord(old_dims, &old_coord).map(|idx| (idx, original))
+    }
+
+    /// Recomputes the neighbor table for the current shape, boundary and neighborhood.
+    fn rebuild_neighbor_table(&mut self) {
+        let offsets = self
+            .topology
+            .offsets(&self.neighborhood, self.grid_dims.len());
+        let size = self.grid.len();
+        self.interactions.clear();
+        self.neighbor_table.clear();
+        self.neighbor_table.reserve(size * offsets[0].len());
+        self.neighbor_starts.clear();
+        self.neighbor_starts.reserve(size + 1);
+        self.neighbor_starts.push(0);
+        for idx in 0..size {
+            let coord = Self::coord_from_index(&self.grid_dims, size, idx);
+            let offsets = &offsets[coord.get(1).map_or(0, |row| row % 2)];
+            let neighbors = Self::neighbors_of(&self.grid_dims, self.boundary, offsets, &coord);
+            self.neighbor_table.extend(neighbors);
+            self.neighbor_starts.push(self.neighbor_table.len());
+        }
+    }
 
-            let neighbor_coord: Vec<usize> = coord
+    /// Gets the indices of all neighbors of an N-dimensional coordinate, given
+    /// the precomputed offsets of the neighborhood.
+    fn neighbors_of(
+        grid_dims: &[usize],
+        boundary: BoundaryCondition,
+        offsets: &[Vec<i64>],
+        coord: &[usize],
+    ) -> Vec<usize> {
+        let mut neighbors = Vec::with_capacity(offsets.len());
+        for offset in offsets {
+            // Neighbors that fall into dead space are simply left out.
+            let neighbor_coord: Option<Vec<usize>> = coord
                 .iter()
                 .zip(offset.iter())
                 .enumerate()
-                .map(|(d, (&c, &o))| (c as i32 + o).rem_euclid(self.grid_dims[d] as i32) as usize)
+                .map(|(d, (&c, &o))| boundary.resolve(c as i64 + o, grid_dims[d]))
                 .collect();
 
-            if let Some(idx) = self.get_index_from_coord(&neighbor_coord) {
+            if let Some(idx) = neighbor_coord.and_then(|nc| Self::index_from_coord(grid_dims, &nc))
+            {
                 neighbors.push(idx);
             }
         }
@@ -145,120 +2001,1041 @@ impl Universe {
 
     /// Generates a fixed multivector state for a stable `Operator`.
     fn fixed_operator_state(&self) -> Multivector {
-        let mut mv = Multivector::zero(self.ga_dims);
-        // As an example, make it a pure vector state (e.g., e_0 = 1).
-        if self.ga_dims > 0 {
-            mv.coefficients[1] = crate::ga_core::Mod3::new(1); // Blade e_0
-        }
-        mv
+        OperatorPreset::E0.state(self.ga_dims)
+    }
+
+    /// The velocity to store for a requested one: `None` unless it moves.
+    fn moving(velocity: &[i64]) -> Option<Vec<i64>> {
+        velocity.iter().any(|&v| v != 0).then(|| velocity.to_vec())
     }
 
-    /// Generates the operator for entanglement, which inverts a state.
-    fn entanglement_inversion_operator(&self) -> Multivector {
-        let mut mv = Multivector::zero(self.ga_dims);
-        // Scalar value of -1.
-        mv.coefficients[0] = crate::ga_core::Mod3::new(-1);
-        mv
+    /// Private helper to generate new random entangled pairs among the cells of `grid`.
+    fn generate_entangled_pairs(
+        grid: &[Existon],
+        percentage: f64,
+        rng: &mut StdRng,
+    ) -> EntanglementGroups {
+        let mut entanglement = EntanglementGroups::default();
+        let num_pairs = Self::pair_count(grid.len(), percentage);
+        let available_ids = grid.iter().map(|cell| cell.id).collect();
+        Self::entangle_random_pairs(&mut entanglement, available_ids, num_pairs, rng);
+        entanglement
     }
 
-    /// Private helper to generate a new map of entangled pairs.
-    fn generate_entangled_pairs(size: usize, percentage: f64) -> HashMap<u64, u64> {
-        let mut entangled_pairs = HashMap::new();
-        let mut rng = rng();
-        let num_pairs = (size as f64 * percentage / 2.0) as usize;
-        let mut available_ids: Vec<u64> = (0..size as u64).collect();
-        available_ids.shuffle(&mut rng);
+    /// The number of pairs that entangles `percentage` of `cells` cells.
+    fn pair_count(cells: usize, percentage: f64) -> usize {
+        (cells as f64 * percentage / 2.0) as usize
+    }
 
+    /// Shuffles `available_ids` and entangles them two by two, up to `num_pairs` pairs.
+    fn entangle_random_pairs(
+        entanglement: &mut EntanglementGroups,
+        mut available_ids: Vec<u64>,
+        num_pairs: usize,
+        rng: &mut StdRng,
+    ) {
+        available_ids.shuffle(rng);
         for _ in 0..num_pairs {
             if available_ids.len() < 2 {
                 break;
             }
             let id1 = available_ids.pop().unwrap();
             let id2 = available_ids.pop().unwrap();
-            entangled_pairs.insert(id1, id2);
-            entangled_pairs.insert(id2, id1);
+            entanglement.entangle(&[id1, id2]);
         }
-        entangled_pairs
     }
 
-    pub fn observe_cell(&mut self, idx: usize) {
-        if idx < self.grid.len() {
-            self.grid[idx].observe();
+    /// Collapses the `Potential` cell at `coord` as the entangled partner of an
+    /// observed cell outside this universe whose state is `source`, such as
+    /// across a [`Multiverse`] bridge, applying [`Universe::pair_operator`].
+    ///
+    /// A `Swap` gives the cell `source`, but the other cell is left as it is.
+    /// The collapse is logged, so the universe replays on its own.
+    ///
+    /// [`Multiverse`]: crate::multiverse::Multiverse
+    pub fn collapse_as_partner(&mut self, coord: &[usize], source: &Multivector) {
+        if let Some(idx) = self.get_index_from_coord(coord) {
+            self.apply(Intervention::CollapsePartner(idx, source.clone()));
         }
     }
 
-    // In universe.rs, inside the `impl Universe` block
+    pub fn observe_cell(&mut self, idx: usize) {
+        self.apply(Intervention::Observe(idx));
+    }
 
     /// Creates a non-local connection between two Existons.
+    ///
+    /// If `id1` is already entangled, `id2` joins its group. A particle is never
+    /// entangled with itself, and one that is already entangled cannot join another group.
     pub fn entangle_pair(&mut self, id1: u64, id2: u64) {
-        // Ensure we don't entangle a particle with itself or an already-entangled particle.
-        if id1 != id2
-            && !self.entangled_pairs.contains_key(&id1)
-            && !self.entangled_pairs.contains_key(&id2)
-        {
-            self.entangled_pairs.insert(id1, id2);
-            self.entangled_pairs.insert(id2, id1);
+        self.apply(Intervention::Entangle(id1, id2));
+    }
+
+    /// Entangles all of `ids` in one group, logged as one intervention per member
+    /// after the first. Members that are already entangled elsewhere are skipped.
+    pub fn entangle_group(&mut self, ids: &[u64]) {
+        if let Some((&first, rest)) = ids.split_first() {
+            for &id in rest {
+                self.entangle_pair(first, id);
+            }
         }
     }
 
     /// The main simulation step.
-    pub fn tick(&mut self) -> Vec<(u64, u64)> {
-        let mut next_grid = self.grid.clone();
-        let mut observed_in_tick = Vec::new();
-        let mut triggered_entanglements = Vec::new(); // New: Track triggered pairs
-        let mut rng = rng();
+    pub fn tick(&mut self) -> TickReport {
+        if self.reversible {
+            return self.tick_reversible();
+        }
+        // 1. Local Step: the update rule computes every cell's next state.
+        let mut pending = match self.pending.take() {
+            Some(pending) => pending,
+            None if (self.rule.is_deterministic()
+                || self.random_scheme == RandomScheme::PerCell)
+                && self.update_order == UpdateOrder::Synchronous =>
+            {
+                // A whole tick that draws nothing from the shared generator can be
+                // spread over threads.
+                let mut pending = self.begin_tick();
+                let started = self.profiling.then(Instant::now);
+                pending.fluctuations =
+                    self.evaluate_in_parallel(&mut pending.next_grid, pending.cached);
+                pending.next_cell = self.grid.len();
+                if let Some(started) = started {
+                    pending.local += started.elapsed();
+                }
+                pending
+            }
+            None => self.begin_tick(),
+        };
+        self.advance(&mut pending, self.grid.len(), None);
+        self.finish_tick(pending)
+    }
 
-        // 1. Local Interaction & State Transition Step...
-        // (This part of the method remains unchanged)
-        for idx in 0..self.grid.len() {
-            if self.grid[idx].consciousness == ConsciousnessState::Operator {
-                continue;
+    /// Runs a whole tick with every cell's local interaction already computed,
+    /// one product per cell in index order; see [`RuleCtx::local_interaction`].
+    #[cfg(feature = "gpu")]
+    pub(crate) fn tick_with_interactions(&mut self, interactions: &[Multivector]) -> TickReport {
+        // Precomputed interactions only describe the previous generation.
+        if self.update_order != UpdateOrder::Synchronous || self.reversible {
+            return self.tick();
+        }
+        self.settle();
+        let mut pending = self.begin_tick();
+        self.advance(&mut pending, self.grid.len(), Some(interactions));
+        self.finish_tick(pending)
+    }
+
+    /// The neighbor table as (starts, indices); cell `i`'s neighbors are
+    /// `indices[starts[i]..starts[i + 1]]`.
+    #[cfg(feature = "gpu")]
+    pub(crate) fn neighbor_table(&self) -> (&[usize], &[usize]) {
+        (&self.neighbor_starts, &self.neighbor_table)
+    }
+
+    /// Works on the current tick for about `budget`, returning its report once the
+    /// tick completes and `None` while it is still in progress.
+    ///
+    /// Progress is kept between calls, so a frontend can bound its frame time on
+    /// large grids at the cost of a lower tick rate. The result is identical to
+    /// calling [`Universe::tick`]. Interventions made mid-tick are queued until
+    /// the tick completes; configuration changes complete it first, discarding
+    /// its report. Either way, they land between ticks.
+    pub fn tick_within(&mut self, budget: Duration) -> Option<TickReport> {
+        // Checking the clock for every cell would cost more than the cells themselves.
+        const CHUNK: usize = 256;
+        if self.reversible {
+            return Some(self.tick());
+        }
+        let deadline = Instant::now() + budget;
+        let mut pending = self.pending.take().unwrap_or_else(|| self.begin_tick());
+        while pending.next_cell < self.grid.len() {
+            let end = pending.next_cell + CHUNK;
+            self.advance(&mut pending, end, None);
+            if Instant::now() >= deadline {
+                break;
+            }
+        }
+        if pending.next_cell < self.grid.len() {
+            self.pending = Some(pending);
+            None
+        } else {
+            Some(self.finish_tick(pending))
+        }
+    }
+
+    /// Whether a tick started by [`Universe::tick_within`] is still unfinished.
+    pub fn is_mid_tick(&self) -> bool {
+        self.pending.is_some()
+    }
+
+    /// Completes a tick in progress, if any.
+    fn settle(&mut self) {
+        if self.pending.is_some() {
+            self.tick();
+        }
+    }
+
+    fn begin_tick(&mut self) -> PendingTick {
+        let started = self.profiling.then(Instant::now);
+        let refreshed = self.refresh_interactions();
+        let mut next_grid = std::mem::take(&mut self.next_grid);
+        if next_grid.len() != self.grid.len() {
+            next_grid.clone_from(&self.grid);
+        }
+        // Without the cache, the rule computes every cell's interaction itself.
+        let computed = match refreshed {
+            Some(refreshed) => refreshed,
+            None if self.rule.uses_local_interaction() => self.grid.len(),
+            None => 0,
+        };
+        PendingTick {
+            rng_at_start: self.rng.clone(),
+            next_grid,
+            next_cell: 0,
+            cached: refreshed.is_some(),
+            interactions: computed,
+            local: started.map_or(Duration::ZERO, |started| started.elapsed()),
+            fluctuations: Vec::new(),
+        }
+    }
+
+    /// Brings the cached local interactions up to date for the tick about to
+    /// start, returning how many it recomputed if the rule can read them.
+    ///
+    /// Only cells that changed last tick, and their neighbors, are recomputed.
+    /// When most of the grid is changing that saves nothing, so the cache is
+    /// dropped and rebuilt by the next full sweep.
+    fn refresh_interactions(&mut self) -> Option<usize> {
+        // Ticks between full sweeps, which rebuild every cell's interaction.
+        const FULL_SWEEP_INTERVAL: u64 = 64;
+        let size = self.grid.len();
+        let valid = std::mem::take(&mut self.interactions_valid)
+            && self.next_grid.len() == size
+            && self.interactions.len() == size;
+        if !self.rule.uses_local_interaction() || self.update_order != UpdateOrder::Synchronous {
+            return None;
+        }
+        let (grid, previous) = (&self.grid, &self.next_grid);
+        let (table, starts) = (&self.neighbor_table, &self.neighbor_starts);
+        let neighbors = |idx: usize| table[starts[idx]..starts[idx + 1]].iter();
+        let sweep = self.tick_count.is_multiple_of(FULL_SWEEP_INTERVAL);
+        let stale: Vec<usize> = if valid && !sweep {
+            let changed: Vec<bool> = grid
+                .iter()
+                .zip(previous)
+                .map(|(cell, before)| cell.state != before.state)
+                .collect();
+            let stale: Vec<usize> = (0..size)
+                .filter(|&idx| changed[idx] || neighbors(idx).any(|&n| changed[n]))
+                .collect();
+            if stale.len() * 2 > size {
+                return None;
+            }
+            stale
+        } else if sweep || self.interactions.len() != size {
+            self.interactions
+                .resize(size, Multivector::zero(self.ga_dims));
+            (0..size).collect()
+        } else {
+            return None;
+        };
+        for &idx in &stale {
+            let around = neighbors(idx).map(|&n| &grid[n]);
+            self.interactions[idx] = interaction(self.ga_dims, &grid[idx], around);
+        }
+        self.interactions_valid = true;
+        Some(stale.len())
+    }
+
+    /// Runs the update rule on the unvisited cells before `end`.
+    ///
+    /// Cells are always visited in index order, so a stochastic rule draws the same
+    /// numbers however the tick is split up.
+    fn advance(
+        &mut self,
+        pending: &mut PendingTick,
+        end: usize,
+        interactions: Option<&[Multivector]>,
+    ) {
+        let started = self.profiling.then(Instant::now);
+        if self.update_order != UpdateOrder::Synchronous {
+            self.advance_in_order(pending);
+        } else {
+            self.advance_synchronous(pending, end, interactions);
+        }
+        if let Some(started) = started {
+            pending.local += started.elapsed();
+        }
+    }
+
+    /// [`Universe::advance`] in the synchronous update order.
+    fn advance_synchronous(
+        &mut self,
+        pending: &mut PendingTick,
+        end: usize,
+        interactions: Option<&[Multivector]>,
+    ) {
+        let (start, end) = (pending.next_cell, end.min(self.grid.len()));
+        if start >= end {
+            return;
+        }
+        let mut ctx = RuleCtx::new(self).with_rng(&mut self.rng);
+        let cached = pending.cached.then_some(self.interactions.as_slice());
+        if let Some(interactions) = interactions.or(cached) {
+            ctx = ctx.with_interactions(interactions);
+        }
+        let (rule, grid) = (self.rule.as_ref(), &self.grid);
+        let (table, starts) = (&self.neighbor_table, &self.neighbor_starts);
+        let tile = &mut pending.next_grid[start..end];
+        Self::evaluate(rule, grid, table, starts, &ctx, start, tile);
+        pending.fluctuations.extend(ctx.into_fluctuations());
+        pending.next_cell = end;
+    }
+
+    /// Runs the update rule on every cell in the universe's asynchronous
+    /// [`UpdateOrder`], letting cells read neighbors updated earlier in the tick.
+    fn advance_in_order(&mut self, pending: &mut PendingTick) {
+        let size = self.grid.len();
+        if pending.next_cell >= size {
+            return;
+        }
+        // Cells before `first_fresh` read the previous generation; the rest read
+        // the grid as it was when the first of them started.
+        let (order, first_fresh) = match self.update_order {
+            UpdateOrder::RandomSequential => {
+                let mut order: Vec<usize> = (0..size).collect();
+                order.shuffle(&mut self.rng);
+                (order, 0)
+            }
+            _ => {
+                let is_even =
+                    |&idx: &usize| self.get_coord_from_index(idx).iter().sum::<usize>() % 2 == 0;
+                let (mut order, odd): (Vec<usize>, Vec<usize>) = (0..size).partition(is_even);
+                let first_odd = order.len();
+                order.extend(odd);
+                (order, first_odd)
             }
-            let coord = self.get_coord_from_index(idx);
-            let neighbor_indices = self.get_neighbors(&coord);
-            let mut operator = Multivector::zero(self.ga_dims);
-            for neighbor_idx in neighbor_indices {
-                operator = &operator + &self.grid[neighbor_idx].state;
-            }
-            next_grid[idx].state = &operator * &self.grid[idx].state;
-            if self.grid[idx].consciousness == ConsciousnessState::Potential {
-                if rng.random_bool(self.observation_rate) {
-                    next_grid[idx].observe();
-                    observed_in_tick.push(next_grid[idx].id);
-                } else if rng.random_bool(self.fluctuation_rate) {
-                    next_grid[idx] = Existon::new(next_grid[idx].id, self.ga_dims);
+        };
+        let next_grid = &mut pending.next_grid;
+        next_grid.clone_from(&self.grid);
+        let ctx = RuleCtx::new(self).with_rng(&mut self.rng);
+        let (rule, grid) = (self.rule.as_ref(), &self.grid);
+        let (table, starts) = (&self.neighbor_table, &self.neighbor_starts);
+        let mut snapshot = Vec::new();
+        for (n, &idx) in order.iter().enumerate() {
+            // Random-sequential cells read the live grid itself.
+            if n == first_fresh && self.update_order == UpdateOrder::Checkerboard {
+                snapshot.clone_from(next_grid);
+            }
+            let source: &[Existon] = match self.update_order {
+                UpdateOrder::Checkerboard if n < first_fresh => grid,
+                UpdateOrder::Checkerboard => &snapshot,
+                _ => next_grid,
+            };
+            let neighbors: Vec<&Existon> = table[starts[idx]..starts[idx + 1]]
+                .iter()
+                .map(|&neighbor_idx| &source[neighbor_idx])
+                .collect();
+            ctx.visit(idx, source[idx].id);
+            next_grid[idx] = rule.apply(&source[idx], &neighbors, &ctx);
+        }
+        let mut fluctuations = ctx.into_fluctuations();
+        fluctuations.sort_unstable();
+        pending.fluctuations.extend(fluctuations);
+        pending.next_cell = size;
+    }
+
+    /// Classifies what the rule did, fires entanglements and swaps in the new generation.
+    fn finish_tick(&mut self, pending: PendingTick) -> TickReport {
+        let started = self.profiling.then(Instant::now);
+        let PendingTick {
+            mut next_grid,
+            fluctuations,
+            interactions,
+            local,
+            ..
+        } = pending;
+        let mut report = TickReport {
+            tick: self.tick_count,
+            fluctuations: self.coords_of(fluctuations.iter().copied()),
+            ..TickReport::default()
+        };
+
+        // 2. Transient fields: disruption waves decay the cells on their rings.
+        self.propagate_waves(&mut next_grid);
+
+        // 3. Classify what the rule did from each cell's change of consciousness.
+        let mut observed_in_tick = Vec::new();
+        let mut decayed_in_tick = Vec::new();
+        for (cell, next) in self.grid.iter().zip(&next_grid) {
+            match (cell.consciousness, next.consciousness) {
+                (ConsciousnessState::Observed, ConsciousnessState::Potential) => {
+                    decayed_in_tick.push(cell.id)
                 }
-            } else if self.grid[idx].consciousness == ConsciousnessState::Observed {
-                if rng.random_bool(self.decay_rate) {
-                    next_grid[idx].decay();
+                (ConsciousnessState::Observed, _) => {}
+                (_, ConsciousnessState::Observed) => observed_in_tick.push(cell.id),
+                _ => {}
+            }
+        }
+        report.observations = self.coords_of(observed_in_tick.iter().copied());
+        report.decays = self.coords_of(decayed_in_tick);
+        report.operator_interactions = self.operator_interactions();
+
+        // 4. Nonlocal (Entanglement) Step
+        let entangling = self.profiling.then(Instant::now);
+        let triggers = self.entanglement_triggers(&observed_in_tick, &next_grid);
+        let triggers = self.propagate_collapses(triggers, &next_grid);
+        self.collapse_partners(&triggers, &mut next_grid);
+        report.entanglements = triggers
+            .iter()
+            .map(|&(source, target)| {
+                (
+                    self.get_coord_from_index(source),
+                    self.get_coord_from_index(target),
+                )
+            })
+            .collect();
+        let entanglement = entangling.map_or(Duration::ZERO, |at| at.elapsed());
+
+        // 5. Lifetimes: stamp the cells that were reborn or newly observed.
+        self.stamp_lifetimes(&mut next_grid, &fluctuations);
+
+        // 6. Decoherence: every group weakens, and the weakest break apart.
+        let fired = triggers.iter().map(|&(source, _)| self.grid[source].id);
+        let broken = self.entanglement.decohere(&self.decoherence, fired);
+        report.decoherences = broken
+            .into_iter()
+            .map(|members| self.coords_of(members))
+            .collect();
+
+        // 7. Moving operators take a step every `operator_move_interval` ticks.
+        let interval = self.operator_move_interval;
+        if interval > 0 && (self.tick_count + 1).is_multiple_of(interval) {
+            report.operator_moves = self.move_operators(&mut next_grid);
+        }
+
+        // 8. Accounting: how the tick changed the per-grade totals.
+        report.totals_change = self.totals_change(&next_grid);
+
+        // The old generation becomes next tick's scratch buffer.
+        self.next_grid = std::mem::replace(&mut self.grid, next_grid);
+        self.tick_count += 1;
+        for (id, history) in &mut self.watches {
+            if let Some(&idx) = self.id_index.get(id) {
+                history.record(&self.grid[idx], self.tick_count);
+            }
+        }
+        for intervention in std::mem::take(&mut self.deferred) {
+            self.apply(intervention);
+        }
+        if let Some(started) = started {
+            let other = started.elapsed().saturating_sub(entanglement);
+            let (sum, product) = self.interaction_costs();
+            let n = interactions as u32;
+            // The samples run warm, so scale the estimates down if they come
+            // to more than the step they are part of.
+            let estimated = (sum + product) * n;
+            let scale = if estimated > local {
+                local.as_secs_f64() / estimated.as_secs_f64()
+            } else {
+                1.0
+            };
+            self.timings = TickTimings {
+                local,
+                neighbor_sums: (sum * n).mul_f64(scale),
+                products: (product * n).mul_f64(scale),
+                entanglement,
+                other,
+            };
+        }
+        report
+    }
+
+    /// Times the neighbor sums and geometric products of the local
+    /// interactions of a sample of cells, returning the average cost of each
+    /// for one cell.
+    fn interaction_costs(&self) -> (Duration, Duration) {
+        const SAMPLE: usize = 64;
+        let size = self.grid.len();
+        let sample = SAMPLE.min(size);
+        if sample == 0 {
+            return (Duration::ZERO, Duration::ZERO);
+        }
+        let cells: Vec<usize> = (0..sample).map(|i| i * size / sample).collect();
+        let summing = Instant::now();
+        let sums: Vec<Multivector> = cells
+            .iter()
+            .map(|&idx| {
+                let mut sum = Multivector::zero(self.ga_dims);
+                for &n in
+                    &self.neighbor_table[self.neighbor_starts[idx]..self.neighbor_starts[idx + 1]]
+                {
+                    sum += &self.grid[n].state;
                 }
+                sum
+            })
+            .collect();
+        let summing = summing.elapsed();
+        let multiplying = Instant::now();
+        let products: Vec<Multivector> = cells
+            .iter()
+            .zip(&sums)
+            .map(|(&idx, sum)| sum * &self.grid[idx].state)
+            .collect();
+        let multiplying = multiplying.elapsed();
+        std::hint::black_box(products);
+        (summing / sample as u32, multiplying / sample as u32)
+    }
+
+    /// Where the time of the last tick went, if [`Universe::profiling`] was
+    /// set for it. A reversible tick counts entirely as the local step.
+    pub fn tick_timings(&self) -> TickTimings {
+        self.timings
+    }
+
+    /// A tick of the reversible second-order rule; see [`Universe::set_reversible`].
+    fn tick_reversible(&mut self) -> TickReport {
+        let started = self.profiling.then(Instant::now);
+        let (f, fluctuations) = self.reversible_f(&self.grid, self.tick_count);
+        let previous = std::mem::take(&mut self.previous);
+        let next = Self::reversible_combine(&self.grid, f, &previous);
+        let mut report = TickReport {
+            tick: self.tick_count,
+            fluctuations: self.coords_of(fluctuations),
+            operator_interactions: self.operator_interactions(),
+            ..TickReport::default()
+        };
+        let ids = |changed: fn(ConsciousnessState, ConsciousnessState) -> bool| {
+            let cells = self.grid.iter().zip(&next);
+            cells
+                .filter(move |(cell, next)| changed(cell.consciousness, next.consciousness))
+                .map(|(cell, _)| cell.id)
+        };
+        report.observations = self.coords_of(ids(|from, to| {
+            from != ConsciousnessState::Observed && to == ConsciousnessState::Observed
+        }));
+        report.decays = self.coords_of(ids(|from, to| {
+            from == ConsciousnessState::Observed && to == ConsciousnessState::Potential
+        }));
+        report.totals_change = self.totals_change(&next);
+        self.previous = std::mem::replace(&mut self.grid, next);
+        self.interactions_valid = false;
+        self.tick_count += 1;
+        for (id, history) in &mut self.watches {
+            if let Some(&idx) = self.id_index.get(id) {
+                history.record(&self.grid[idx], self.tick_count);
             }
         }
+        if let Some(started) = started {
+            self.timings = TickTimings {
+                local: started.elapsed(),
+                ..TickTimings::default()
+            };
+        }
+        report
+    }
+
+    /// Runs the reversible rule one tick back, returning whether it could.
+    ///
+    /// The universe must be [reversible](Universe::set_reversible) and past
+    /// tick zero. The grid returns exactly to the generation before, and
+    /// interventions logged after it are dropped, since the grid no longer shows
+    /// them; stepping back further across an intervention gives the history
+    /// the edited grid implies rather than the one that ran.
+    pub fn tick_backward(&mut self) -> bool {
+        if !self.reversible || self.tick_count == 0 || self.previous.len() != self.grid.len() {
+            return false;
+        }
+        let tick = self.tick_count - 1;
+        let (f, _) = self.reversible_f(&self.previous, tick);
+        let earlier = Self::reversible_combine(&self.previous, f, &self.grid);
+        self.grid = std::mem::replace(&mut self.previous, earlier);
+        self.tick_count = tick;
+        self.interactions_valid = false;
+        self.interventions.retain(|entry| entry.tick <= tick);
+        self.rebuild_id_index();
+        true
+    }
+
+    /// `F` of the reversible rule: the update rule applied to `grid` as tick
+    /// `tick` would, on per-cell streams, with the fluctuations it reported.
+    fn reversible_f(&self, grid: &[Existon], tick: u64) -> (Vec<Existon>, Vec<u64>) {
+        let ctx = RuleCtx::new(self).keyed(self.seed, tick);
+        let mut next = grid.to_vec();
+        let (table, starts) = (&self.neighbor_table, &self.neighbor_starts);
+        Self::evaluate(self.rule.as_ref(), grid, table, starts, &ctx, 0, &mut next);
+        (next, ctx.into_fluctuations())
+    }
 
-        // 2. Nonlocal (Entanglement) Step
-        let entanglement_inversion = self.entanglement_inversion_operator();
-        for id in observed_in_tick {
-            if let Some(&partner_id) = self.entangled_pairs.get(&id) {
-                let partner_idx = partner_id as usize;
-                if next_grid[partner_idx].consciousness == ConsciousnessState::Potential {
-                    next_grid[partner_idx].observe();
-                    next_grid[partner_idx].state =
-                        &next_grid[partner_idx].state * &entanglement_inversion;
+    /// `F(current) - other` for every cell, where `other` is the generation on
+    /// the far side of `current`: the previous one going forward, the next one
+    /// going back. A generation of the wrong size counts as empty.
+    fn reversible_combine(current: &[Existon], f: Vec<Existon>, other: &[Existon]) -> Vec<Existon> {
+        let observed = |state: ConsciousnessState| state == ConsciousnessState::Observed;
+        let other = (other.len() == current.len()).then_some(other);
+        f.into_iter()
+            .zip(current)
+            .enumerate()
+            .map(|(idx, (mut next, cell))| {
+                if cell.consciousness == ConsciousnessState::Operator {
+                    return cell.clone();
+                }
+                // Lifetimes could not be run backward, so they stand still.
+                next.born = cell.born;
+                next.observed_since = cell.observed_since;
+                let other = other.map(|other| &other[idx]);
+                if let Some(other) = other {
+                    next.state = &next.state - &other.state;
+                }
+                let was_observed = other.is_some_and(|other| observed(other.consciousness));
+                next.consciousness = if observed(next.consciousness) != was_observed {
+                    ConsciousnessState::Observed
+                } else {
+                    ConsciousnessState::Potential
+                };
+                next
+            })
+            .collect()
+    }
 
-                    // New: Record that this entanglement was triggered for visualization
-                    triggered_entanglements.push((id, partner_id));
+    /// Carries every cell's lifetime stamps over to `next`, restarting the
+    /// age of the cells that `fluctuations` replaced and the observed duration
+    /// of the cells that became `Observed`.
+    ///
+    /// A cell found `Observed` without a stamp was observed by an intervention
+    /// since the last tick.
+    fn stamp_lifetimes(&self, next: &mut [Existon], fluctuations: &[u64]) {
+        let reborn: HashSet<u64> = fluctuations.iter().copied().collect();
+        for (cell, next) in self.grid.iter().zip(next) {
+            next.born = if reborn.contains(&cell.id) {
+                self.tick_count + 1
+            } else {
+                cell.born
+            };
+            next.observed_since = match (cell.consciousness, next.consciousness) {
+                (_, ConsciousnessState::Potential | ConsciousnessState::Operator) => None,
+                (ConsciousnessState::Observed, ConsciousnessState::Observed) => {
+                    cell.observed_since.or(Some(self.tick_count))
                 }
+                _ => Some(self.tick_count + 1),
+            };
+        }
+    }
+
+    /// The change in per-grade totals from the current grid to `next`.
+    fn totals_change(&self, next: &[Existon]) -> GradeTotals {
+        let mut change = GradeTotals::zero(self.ga_dims);
+        for (cell, next) in self.grid.iter().zip(next) {
+            if cell.state != next.state {
+                change.add(&next.state, 1);
+                change.add(&cell.state, -1);
+            }
+        }
+        change
+    }
+
+    /// Collects the entanglements fired by this tick's observations, as
+    /// (observed cell, partner to collapse) index pairs in observed-cell order.
+    ///
+    /// Conflicts resolve the same way however the work is split:
+    /// * A partner that the local rule also observed this tick is not collapsed
+    ///   again, so when both partners are observed together nothing fires.
+    /// * A partner triggered by several observed cells collapses once, credited to
+    ///   the lowest-indexed of them.
+    fn entanglement_triggers(
+        &self,
+        observed: &[u64],
+        next_grid: &[Existon],
+    ) -> Vec<(usize, usize)> {
+        let collect = |sources: &[u64]| -> Vec<(usize, usize)> {
+            sources
+                .iter()
+                .flat_map(|&id| {
+                    let source = self.id_index[&id];
+                    self.entanglement
+                        .partners(id)
+                        .filter_map(|partner| self.index_of_id(partner))
+                        .filter(|&target| {
+                            next_grid[target].consciousness == ConsciousnessState::Potential
+                        })
+                        .map(move |target| (source, target))
+                })
+                .collect()
+        };
+        let mut triggers = self.map_tiles(observed, collect);
+        triggers.sort_unstable_by_key(|&(source, target)| (target, source));
+        triggers.dedup_by_key(|&mut (_, target)| target);
+        triggers.sort_unstable();
+        triggers
+    }
+
+    /// Sends this tick's collapses on their way under [`CollapseSpeed::Limited`]
+    /// and returns the (source, target) pairs that collapse now: this tick's
+    /// own under [`CollapseSpeed::Instant`], plus every in-flight collapse that
+    /// arrives at a partner still `Potential`.
+    ///
+    /// Each target appears once, credited to the lowest-indexed source, as in
+    /// [`Universe::entanglement_triggers`].
+    fn propagate_collapses(
+        &mut self,
+        mut triggers: Vec<(usize, usize)>,
+        next_grid: &[Existon],
+    ) -> Vec<(usize, usize)> {
+        let tick = self.tick_count;
+        if self.collapse_speed != CollapseSpeed::Instant {
+            for (source, target) in triggers.drain(..) {
+                let delay = self
+                    .collapse_speed
+                    .delay(self.light_distance(source, target));
+                self.in_flight.push(InFlightCollapse {
+                    due: tick.saturating_add(delay),
+                    source: self.grid[source].id,
+                    target: self.grid[target].id,
+                });
+            }
+        }
+        if self.in_flight.iter().all(|collapse| collapse.due > tick) {
+            return triggers;
+        }
+        let (arrived, waiting) = std::mem::take(&mut self.in_flight)
+            .into_iter()
+            .partition(|collapse| collapse.due <= tick);
+        self.in_flight = waiting;
+        triggers.extend(
+            arrived
+                .into_iter()
+                .filter_map(|collapse: InFlightCollapse| {
+                    let source = self.index_of_id(collapse.source)?;
+                    let target = self.index_of_id(collapse.target)?;
+                    (next_grid[target].consciousness == ConsciousnessState::Potential)
+                        .then_some((source, target))
+                }),
+        );
+        triggers.sort_unstable_by_key(|&(source, target)| (target, source));
+        triggers.dedup_by_key(|&mut (_, target)| target);
+        triggers.sort_unstable();
+        triggers
+    }
+
+    /// The number of cells between two grid indices along the longest axis (the
+    /// Chebyshev distance), going around the edges of a toroidal grid.
+    fn light_distance(&self, a: usize, b: usize) -> usize {
+        let toroidal = self.boundary == BoundaryCondition::Toroidal;
+        self.get_coord_from_index(a)
+            .iter()
+            .zip(self.get_coord_from_index(b))
+            .zip(&self.grid_dims)
+            .map(|((&a, b), &dim)| {
+                let d = a.abs_diff(b);
+                if toroidal { d.min(dim - d) } else { d }
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Steps every `Operator` with a velocity, in index order, returning the
+    /// (from, to) coordinates of those that moved.
+    ///
+    /// An operator swaps places with the cell it lands on, both keeping their
+    /// IDs, and stays put if another `Operator` is there.
+    fn move_operators(&mut self, next_grid: &mut [Existon]) -> Vec<(Vec<usize>, Vec<usize>)> {
+        let movers: Vec<usize> = (0..next_grid.len())
+            .filter(|&idx| {
+                next_grid[idx].consciousness == ConsciousnessState::Operator
+                    && next_grid[idx].velocity.is_some()
+            })
+            .collect();
+        let mut moves = Vec::new();
+        for from in movers {
+            let Some(velocity) = &next_grid[from].velocity else {
+                continue;
+            };
+            let coord: Vec<usize> = self
+                .get_coord_from_index(from)
+                .iter()
+                .zip(velocity)
+                .zip(&self.grid_dims)
+                .map(|((&c, &v), &dim)| (c as i64 + v).rem_euclid(dim as i64) as usize)
+                .collect();
+            let to = self
+                .get_index_from_coord(&coord)
+                .expect("wrapped into the grid");
+            if to == from || next_grid[to].consciousness == ConsciousnessState::Operator {
+                continue;
+            }
+            next_grid.swap(from, to);
+            self.id_index.insert(next_grid[from].id, from);
+            self.id_index.insert(next_grid[to].id, to);
+            moves.push((self.get_coord_from_index(from), coord));
+        }
+        moves
+    }
+
+    /// Collapses each triggered partner and applies its link's [`PairOperator`].
+    ///
+    /// Every target appears once, so tiles of the grid can be updated
+    /// independently. Swaps involve the source too, so they happen afterwards,
+    /// one at a time in trigger order.
+    fn collapse_partners(&self, triggers: &[(usize, usize)], next_grid: &mut [Existon]) {
+        let operators: Vec<&PairOperator> = triggers
+            .iter()
+            .map(|&(source, target)| self.link_operator(next_grid[source].id, next_grid[target].id))
+            .collect();
+        let collapse = |partner: &mut Existon, operator: &PairOperator| {
+            partner.observe(self.collapse_policy);
+            partner.state = operator.apply(&partner.state);
+        };
+        let TickTuning {
+            threads, tile_size, ..
+        } = self.tuning;
+        let tile_size = tile_size.max(1);
+        if threads <= 1 || triggers.len() <= tile_size {
+            for (&(_, target), operator) in triggers.iter().zip(&operators) {
+                collapse(&mut next_grid[target], operator);
+            }
+        } else {
+            self.collapse_in_tiles(triggers, &operators, next_grid, &collapse);
+        }
+        for (&(source, target), operator) in triggers.iter().zip(&operators) {
+            if **operator == PairOperator::Swap {
+                let state = next_grid[source].state.clone();
+                next_grid[source].state = std::mem::replace(&mut next_grid[target].state, state);
+            }
+        }
+    }
+
+    /// Runs `collapse` on every target of `triggers` on the tuned number of threads.
+    fn collapse_in_tiles(
+        &self,
+        triggers: &[(usize, usize)],
+        operators: &[&PairOperator],
+        next_grid: &mut [Existon],
+        collapse: &(impl Fn(&mut Existon, &PairOperator) + Sync),
+    ) {
+        let TickTuning {
+            threads, tile_size, ..
+        } = self.tuning;
+        let tile_size = tile_size.max(1);
+        let mut targets: Vec<(usize, &PairOperator)> = triggers
+            .iter()
+            .zip(operators)
+            .map(|(&(_, target), &operator)| (target, operator))
+            .collect();
+        targets.sort_unstable_by_key(|&(target, _)| target);
+        let mut queues: Vec<Vec<(usize, &mut [Existon])>> =
+            (0..threads).map(|_| Vec::new()).collect();
+        for (n, tile) in next_grid.chunks_mut(tile_size).enumerate() {
+            queues[n % threads].push((n * tile_size, tile));
+        }
+        let targets = &targets;
+        std::thread::scope(|scope| {
+            for queue in queues {
+                scope.spawn(move || {
+                    for (start, tile) in queue {
+                        let first = targets.partition_point(|&(t, _)| t < start);
+                        let last = targets.partition_point(|&(t, _)| t < start + tile.len());
+                        for &(target, operator) in &targets[first..last] {
+                            collapse(&mut tile[target - start], operator);
+                        }
+                    }
+                });
+            }
+        });
+    }
+
+    /// Maps `f` over slices of `items` on the tuned number of threads, concatenating
+    /// the results in item order.
+    fn map_tiles<T: Sync, R: Send>(
+        &self,
+        items: &[T],
+        f: impl Fn(&[T]) -> Vec<R> + Sync,
+    ) -> Vec<R> {
+        let TickTuning {
+            threads, tile_size, ..
+        } = self.tuning;
+        let tile_size = tile_size.max(1);
+        if threads <= 1 || items.len() <= tile_size {
+            return f(items);
+        }
+        // One run of consecutive tiles per thread.
+        let run = items.len().div_ceil(threads).max(tile_size);
+        let f = &f;
+        std::thread::scope(|scope| {
+            let tiles: Vec<_> = items
+                .chunks(run)
+                .map(|tile| scope.spawn(move || f(tile)))
+                .collect();
+            tiles
+                .into_iter()
+                .flat_map(|tile| tile.join().expect("tick worker panicked"))
+                .collect()
+        })
+    }
+
+    /// The coordinates of the cells with the given IDs, skipping any not in the grid.
+    fn coords_of(&self, ids: impl IntoIterator<Item = u64>) -> Vec<Vec<usize>> {
+        ids.into_iter()
+            .filter_map(|id| self.coord_of_id(id))
+            .collect()
+    }
+
+    /// Every (operator, neighbor) pair where an `Operator` is in the neighborhood of
+    /// a cell the rule updates.
+    fn operator_interactions(&self) -> Vec<(Vec<usize>, Vec<usize>)> {
+        let is_operator = |idx: usize| self.grid[idx].consciousness == ConsciousnessState::Operator;
+        if !(0..self.grid.len()).any(is_operator) {
+            return Vec::new();
+        }
+        let mut interactions = Vec::new();
+        for idx in (0..self.grid.len()).filter(|&idx| !is_operator(idx)) {
+            let neighbors =
+                &self.neighbor_table[self.neighbor_starts[idx]..self.neighbor_starts[idx + 1]];
+            for &neighbor_idx in neighbors.iter().filter(|&&n| is_operator(n)) {
+                interactions.push((
+                    self.get_coord_from_index(neighbor_idx),
+                    self.get_coord_from_index(idx),
+                ));
             }
         }
+        interactions
+    }
+
+    /// Runs the update rule for the cells `start..start + tile.len()`, writing
+    /// their next states into `tile`.
+    fn evaluate(
+        rule: &dyn UpdateRule,
+        grid: &[Existon],
+        neighbor_table: &[usize],
+        neighbor_starts: &[usize],
+        ctx: &RuleCtx,
+        start: usize,
+        tile: &mut [Existon],
+    ) {
+        let mut neighbors = Vec::new();
+        for (offset, next) in tile.iter_mut().enumerate() {
+            let idx = start + offset;
+            neighbors.clear();
+            neighbors.extend(
+                neighbor_table[neighbor_starts[idx]..neighbor_starts[idx + 1]]
+                    .iter()
+                    .map(|&neighbor_idx| &grid[neighbor_idx]),
+            );
+            ctx.visit(idx, grid[idx].id);
+            *next = rule.apply(&grid[idx], &neighbors, ctx);
+        }
+    }
+
+    /// A context for evaluating cells off the universe's own RNG, reading the
+    /// cached local interactions if `cached`.
+    fn shared_ctx(&self, cached: bool) -> RuleCtx<'_> {
+        let ctx = RuleCtx::new(self);
+        if cached {
+            ctx.with_interactions(&self.interactions)
+        } else {
+            ctx
+        }
+    }
 
-        self.grid = next_grid;
-        triggered_entanglements // Return the list of events
+    /// Evaluates a rule that draws nothing from the universe's generator in tiles
+    /// spread over the tuned number of threads, returning the fluctuations it
+    /// reported in index order.
+    fn evaluate_in_parallel(&self, next_grid: &mut [Existon], cached: bool) -> Vec<u64> {
+        let (rule, grid) = (self.rule.as_ref(), &self.grid);
+        let (table, starts) = (&self.neighbor_table, &self.neighbor_starts);
+        let TickTuning {
+            threads, tile_size, ..
+        } = self.tuning;
+        let tile_size = tile_size.max(1);
+        if threads <= 1 || next_grid.len() <= tile_size {
+            let ctx = self.shared_ctx(cached);
+            Self::evaluate(rule, grid, table, starts, &ctx, 0, next_grid);
+            return ctx.into_fluctuations();
+        }
+        // Workers take tiles round-robin, so the load evens out across the grid.
+        let mut queues: Vec<Vec<(usize, &mut [Existon])>> =
+            (0..threads).map(|_| Vec::new()).collect();
+        for (n, tile) in next_grid.chunks_mut(tile_size).enumerate() {
+            queues[n % threads].push((n * tile_size, tile));
+        }
+        let mut fluctuations: Vec<u64> = std::thread::scope(|scope| {
+            let workers: Vec<_> = queues
+                .into_iter()
+                .map(|queue| {
+                    scope.spawn(move || {
+                        let ctx = self.shared_ctx(cached);
+                        for (start, tile) in queue {
+                            Self::evaluate(rule, grid, table, starts, &ctx, start, tile);
+                        }
+                        ctx.into_fluctuations()
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("update rule panicked"))
+                .collect()
+        });
+        fluctuations.sort_unstable();
+        fluctuations
     }
 
     pub fn disrupt_cell(&mut self, idx: usize) {
-        if idx < self.grid.len() {
-            // The decay() method already checks if the state is Observed.
-            self.grid[idx].decay();
+        // Only an Observed cell can decay, so only that is applied and logged.
+        self.apply(Intervention::Disrupt(idx));
+    }
+
+    /// Disrupts the cell at `idx` with `probability`, rolled on the caller's
+    /// `rng` so the universe's own stream is left alone; only a disruption
+    /// that happens is logged. Returns whether it happened.
+    pub fn disrupt_cell_with_probability(
+        &mut self,
+        idx: usize,
+        probability: f64,
+        rng: &mut impl Rng,
+    ) -> bool {
+        let hit = idx < self.grid.len()
+            && self.grid[idx].consciousness == ConsciousnessState::Observed
+            && rng.random_bool(probability.clamp(0.0, 1.0));
+        if hit {
+            self.disrupt_cell(idx);
+        }
+        hit
+    }
+
+    /// Emits a [`DisruptionWave`] from `center` that decays `Observed` cells
+    /// with probability `amplitude` there and spreads outward, weakening, over
+    /// the following ticks. Amplitudes outside `(0, 1]` and coordinates off
+    /// the grid are ignored.
+    pub fn emit_disruption(&mut self, center: &[usize], amplitude: f64) {
+        if let Some(idx) = self.get_index_from_coord(center) {
+            self.apply(Intervention::EmitDisruption(idx, amplitude));
+        }
+    }
+
+    /// Decays the `Observed` cells of `next_grid` on every wave's ring, then
+    /// moves the waves one cell outward and drops those that died out.
+    fn propagate_waves(&mut self, next_grid: &mut [Existon]) {
+        if self.waves.is_empty() {
+            return;
+        }
+        let mut waves = std::mem::take(&mut self.waves);
+        let reach = self.grid_dims.iter().max().copied().unwrap_or(0);
+        for wave in &mut waves {
+            let Some(center) = self.get_index_from_coord(&wave.center) else {
+                continue;
+            };
+            let strength = wave.strength();
+            for (idx, cell) in next_grid.iter_mut().enumerate() {
+                if cell.consciousness == ConsciousnessState::Observed
+                    && self.light_distance(center, idx) == wave.radius
+                    && self.rng.random_bool(strength)
+                {
+                    cell.decay(&mut self.rng);
+                }
+            }
+            wave.radius += 1;
         }
+        waves.retain(|wave| !wave.is_spent(reach));
+        self.waves = waves;
     }
 }
@@ -0,0 +1,173 @@
+//! Volume view: a three-dimensional grid drawn whole, turned with the mouse.
+//!
+//! A slice shows one layer at a time, which hides the entanglement links that
+//! run between layers. The volume view instead projects every cell of a 3D
+//! grid into the window as a translucent splat, seen through an
+//! [`OrbitCamera`] that circles the grid's center, with the entanglement
+//! groups drawn as lines between their members. `Potential` cells are faint so
+//! the operators and observed cells inside the grid show through. Projection
+//! happens here in screen pixels, so any 2D renderer can draw the result.
+
+use crate::existon::ConsciousnessState;
+use crate::universe::Universe;
+
+/// A camera circling the center of the grid; see the
+/// [module documentation](self).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrbitCamera {
+    /// The turn about the grid's vertical axis, in radians.
+    pub yaw: f64,
+    /// The tilt above (positive) or below the grid, in radians.
+    pub pitch: f64,
+    /// How far the camera is from the grid's center, in units of the grid's
+    /// longest side.
+    pub distance: f64,
+}
+
+impl Default for OrbitCamera {
+    fn default() -> Self {
+        OrbitCamera {
+            yaw: 0.6,
+            pitch: 0.4,
+            distance: 2.5,
+        }
+    }
+}
+
+/// Where a grid point lands on screen.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Projected {
+    /// The screen position, in pixels.
+    pub pos: [f64; 2],
+    /// The distance in front of the camera; larger is farther away.
+    pub depth: f64,
+    /// Pixels per unit of the grid's longest side at that depth.
+    pub scale: f64,
+}
+
+/// One cell as the volume view draws it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Splat {
+    /// The center of the cell on screen.
+    pub pos: [f64; 2],
+    /// The width of the cell on screen, in pixels.
+    pub size: f64,
+    pub color: [f32; 4],
+    pub depth: f64,
+}
+
+impl OrbitCamera {
+    /// How far a pixel of mouse drag turns the camera.
+    pub const RADIANS_PER_PIXEL: f64 = 0.01;
+    /// The steepest the camera tilts, short of looking straight down.
+    pub const MAX_PITCH: f64 = 1.5;
+
+    /// Turns the camera by a mouse drag of `delta` pixels: across turns it
+    /// about the grid, down tilts it.
+    pub fn orbit(&mut self, delta: [f64; 2]) {
+        self.yaw += delta[0] * Self::RADIANS_PER_PIXEL;
+        self.pitch = (self.pitch + delta[1] * Self::RADIANS_PER_PIXEL)
+            .clamp(-Self::MAX_PITCH, Self::MAX_PITCH);
+    }
+
+    /// Where the center of the cell at `coord`, on a grid of `dims`, lands in
+    /// a window of `size` pixels; `None` behind the camera. Only the first
+    /// three axes are placed; the grid's longest side spans one unit.
+    pub fn project(&self, coord: &[usize], dims: &[usize], size: [f64; 2]) -> Option<Projected> {
+        let longest = dims.iter().copied().max().unwrap_or(1).max(1) as f64;
+        let [x, y, z] = [0, 1, 2].map(|axis| {
+            let len = dims.get(axis).copied().unwrap_or(1) as f64;
+            let at = coord.get(axis).copied().unwrap_or(0) as f64;
+            (at + 0.5 - len / 2.0) / longest
+        });
+        let (sin_yaw, cos_yaw) = self.yaw.sin_cos();
+        let (sin_pitch, cos_pitch) = self.pitch.sin_cos();
+        let (x, z) = (x * cos_yaw - z * sin_yaw, x * sin_yaw + z * cos_yaw);
+        let (y, z) = (y * cos_pitch - z * sin_pitch, y * sin_pitch + z * cos_pitch);
+        let depth = z + self.distance;
+        if depth < 0.05 {
+            return None;
+        }
+        let scale = size[0].min(size[1]) / depth;
+        let pos = [size[0] / 2.0 + x * scale, size[1] / 2.0 + y * scale];
+        Some(Projected { pos, depth, scale })
+    }
+
+    /// A one-line summary of the camera for the HUD.
+    pub fn describe(&self) -> String {
+        format!(
+            "Volume yaw {:.0}° pitch {:.0}°",
+            self.yaw.to_degrees().rem_euclid(360.0),
+            self.pitch.to_degrees()
+        )
+    }
+}
+
+/// How opaque a cell in `state` is drawn: operators and observed cells stand
+/// out, and the `Potential` bulk is a haze.
+pub fn state_alpha(state: ConsciousnessState) -> f32 {
+    match state {
+        ConsciousnessState::Operator => 1.0,
+        ConsciousnessState::Observed => 0.9,
+        ConsciousnessState::Potential => 0.08,
+    }
+}
+
+/// Every cell of `universe` projected through `camera` into a window of
+/// `size` pixels and given the color `color` returns for its grid index,
+/// farthest first, so drawing them in order paints nearer cells over farther
+/// ones.
+pub fn splats(
+    universe: &Universe,
+    color: impl Fn(usize) -> [f32; 4],
+    camera: &OrbitCamera,
+    size: [f64; 2],
+) -> Vec<Splat> {
+    let dims = &universe.grid_dims;
+    let longest = dims.iter().copied().max().unwrap_or(1).max(1) as f64;
+    let mut splats: Vec<Splat> = universe
+        .grid
+        .iter()
+        .enumerate()
+        .filter_map(|(idx, existon)| {
+            let coord = universe.get_coord_from_index(idx);
+            let projected = camera.project(&coord, dims, size)?;
+            let mut color = color(idx);
+            color[3] *= state_alpha(existon.consciousness);
+            Some(Splat {
+                pos: projected.pos,
+                size: projected.scale / longest,
+                color,
+                depth: projected.depth,
+            })
+        })
+        .collect();
+    splats.sort_by(|a, b| b.depth.total_cmp(&a.depth));
+    splats
+}
+
+/// The entanglement links of `universe` as screen lines, each member of a
+/// group joined to the group's first member, with the group's strength.
+pub fn links(universe: &Universe, camera: &OrbitCamera, size: [f64; 2]) -> Vec<([f64; 4], f64)> {
+    let dims = &universe.grid_dims;
+    let at = |id: u64| {
+        let coord = universe.coord_of_id(id)?;
+        camera.project(&coord, dims, size).map(|p| p.pos)
+    };
+    let mut lines = Vec::new();
+    let groups = &universe.entanglement;
+    for group in groups.group_ids() {
+        let Some((&first, rest)) = groups.members(group).split_first() else {
+            continue;
+        };
+        let Some(from) = at(first) else {
+            continue;
+        };
+        for &id in rest {
+            if let Some(to) = at(id) {
+                lines.push(([from[0], from[1], to[0], to[1]], groups.strength(group)));
+            }
+        }
+    }
+    lines
+}
@@ -0,0 +1,133 @@
+//! Per-cell time series: the recent history of individual Existons.
+//!
+//! [`Universe::watch`] starts recording a cell after every tick, keeping its
+//! last few samples in a [`CellHistory`] that [`Universe::history`] returns.
+//! Histories follow the cell's ID, so a moving `Operator` keeps its history.
+//! To measure long runs cheaply, [`WatchMode::Transitions`] only records the
+//! ticks at which the cell's consciousness changed.
+//!
+//! [`Universe::watch`]: crate::universe::Universe::watch
+//! [`Universe::history`]: crate::universe::Universe::history
+
+use crate::existon::{ConsciousnessState, Existon};
+use crate::ga_core::Multivector;
+use std::collections::VecDeque;
+use std::ops::Range;
+
+/// Which ticks a [`CellHistory`] keeps a sample of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WatchMode {
+    /// Every tick, so the multivector's evolution can be followed.
+    #[default]
+    EveryTick,
+    /// Only the ticks at which the consciousness changed.
+    Transitions,
+}
+
+/// A watched cell as it was when the universe's tick count was `tick`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellSample {
+    pub tick: u64,
+    pub consciousness: ConsciousnessState,
+    pub state: Multivector,
+}
+
+/// The most recent samples of one watched cell, oldest first.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CellHistory {
+    /// The ID of the watched cell.
+    pub id: u64,
+    pub mode: WatchMode,
+    /// The most samples kept; older ones are dropped.
+    pub capacity: usize,
+    samples: VecDeque<CellSample>,
+    /// The last tick the cell was looked at, sampled or not.
+    last_tick: u64,
+}
+
+impl CellHistory {
+    /// The number of samples [`Universe::watch`] keeps.
+    ///
+    /// [`Universe::watch`]: crate::universe::Universe::watch
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Starts a history of `cell` with its state at `tick` as the first sample.
+    pub(crate) fn new(cell: &Existon, tick: u64, capacity: usize, mode: WatchMode) -> Self {
+        let mut history = CellHistory {
+            id: cell.id,
+            mode,
+            capacity: capacity.max(1),
+            samples: VecDeque::new(),
+            last_tick: tick,
+        };
+        history.push(cell, tick);
+        history
+    }
+
+    /// Looks at the cell after `tick`, keeping a sample if the mode asks for one.
+    pub(crate) fn record(&mut self, cell: &Existon, tick: u64) {
+        self.last_tick = tick;
+        let changed = self
+            .samples
+            .back()
+            .is_none_or(|last| last.consciousness != cell.consciousness);
+        if self.mode == WatchMode::EveryTick || changed {
+            self.push(cell, tick);
+        }
+    }
+
+    fn push(&mut self, cell: &Existon, tick: u64) {
+        if self.samples.len() == self.capacity {
+            self.samples.pop_front();
+        }
+        self.samples.push_back(CellSample {
+            tick,
+            consciousness: cell.consciousness,
+            state: cell.state.clone(),
+        });
+    }
+
+    /// The kept samples, oldest first.
+    pub fn samples(&self) -> impl Iterator<Item = &CellSample> {
+        self.samples.iter()
+    }
+
+    /// The most recent sample.
+    pub fn latest(&self) -> Option<&CellSample> {
+        self.samples.back()
+    }
+
+    /// The number of kept samples.
+    pub fn len(&self) -> usize {
+        self.samples.len()
+    }
+
+    /// Whether no sample is kept.
+    pub fn is_empty(&self) -> bool {
+        self.samples.is_empty()
+    }
+
+    /// The stretches of ticks the cell spent in `consciousness`, oldest first,
+    /// each from the first tick it was seen so to the first it was seen otherwise.
+    ///
+    /// A stretch still going on ends after the last tick looked at. The first
+    /// stretch may have begun before the oldest kept sample.
+    pub fn spans(&self, consciousness: ConsciousnessState) -> Vec<Range<u64>> {
+        let mut spans = Vec::new();
+        let mut start = None;
+        for sample in &self.samples {
+            match (start, sample.consciousness == consciousness) {
+                (None, true) => start = Some(sample.tick),
+                (Some(from), false) => {
+                    spans.push(from..sample.tick);
+                    start = None;
+                }
+                _ => {}
+            }
+        }
+        if let Some(from) = start {
+            spans.push(from..self.last_tick + 1);
+        }
+        spans
+    }
+}
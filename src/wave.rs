@@ -0,0 +1,55 @@
+//! Transient fields: disturbances that travel across the grid for a while.
+//!
+//! A [`DisruptionWave`] is emitted at a cell by
+//! [`Universe::emit_disruption`] and spreads outward one cell per tick as a
+//! ring of decay probability. Each tick, every `Observed` cell on the ring
+//! decays with the wave's current strength, which falls by
+//! [`DisruptionWave::ATTENUATION`] per cell travelled, so a wave sweeps through
+//! the structures around it rather than stamping them all at once. Rings are
+//! the cells at one Chebyshev distance from the center, wrapping around a
+//! toroidal grid, and a wave dies out once it is too weak to matter or has
+//! crossed the whole grid.
+//!
+//! Emitting a wave is an intervention, so waves replay with the universe.
+//!
+//! [`Universe::emit_disruption`]: crate::universe::Universe::emit_disruption
+
+/// A ring of decay probability spreading from a cell.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisruptionWave {
+    /// The coordinate the wave was emitted at.
+    pub center: Vec<usize>,
+    /// The decay probability at the center.
+    pub amplitude: f64,
+    /// The distance from the center of the ring the next tick reaches.
+    pub radius: usize,
+}
+
+impl DisruptionWave {
+    /// How much of its strength a wave keeps for every cell it travels.
+    pub const ATTENUATION: f64 = 0.8;
+    /// The strength below which a wave dies out.
+    pub const CUTOFF: f64 = 0.01;
+
+    /// A wave about to leave `center`, with the decay probability `amplitude`
+    /// clamped to `[0, 1]`.
+    pub fn new(center: Vec<usize>, amplitude: f64) -> Self {
+        DisruptionWave {
+            center,
+            amplitude: amplitude.clamp(0.0, 1.0),
+            radius: 0,
+        }
+    }
+
+    /// The decay probability on the ring the next tick reaches.
+    pub fn strength(&self) -> f64 {
+        let radius = i32::try_from(self.radius).unwrap_or(i32::MAX);
+        self.amplitude * Self::ATTENUATION.powi(radius)
+    }
+
+    /// Whether the wave has died out on a grid whose farthest cells are
+    /// `reach` cells from the center.
+    pub fn is_spent(&self, reach: usize) -> bool {
+        self.radius > reach || self.strength() < Self::CUTOFF
+    }
+}
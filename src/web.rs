@@ -0,0 +1,259 @@
+//! The browser frontend's engine: a universe and the pixels of its grid for a
+//! web page to draw on a canvas.
+//!
+//! A [`WebSim`] is all a page needs. It ticks a [`Universe`], applies clicks
+//! with the Observe, Operator and Disrupt tools, and renders the grid into RGBA
+//! pixels in the window's colors. Built for `wasm32-unknown-unknown` without
+//! the `desktop` feature, the crate exports it through a plain C ABI, the
+//! `existons_*` functions that `web/index.html` calls, so the page needs no
+//! bindings generator: only the `.wasm` file next to it.
+//!
+//! A page joined to a server (see `serve.rs`) does not tick on its own: it
+//! follows the server's universe with [`WebSim::load_state`] and
+//! [`WebSim::apply_delta`], and sends its clicks to the server instead.
+
+use crate::capture::render_frame;
+use crate::color::{ColorMapping, ViewMode};
+use crate::delta::UniverseDelta;
+use crate::entanglement::EntanglementGroups;
+use crate::slice::Slice;
+use crate::universe::Universe;
+
+/// The GA dimensions of browser universes, as in the window.
+const GA_DIMS: usize = 3;
+/// The amplitude of the wave a Disrupt click emits, as in the window.
+const DISRUPTION_AMPLITUDE: f64 = 0.6;
+
+/// What a click on the page does to the cell under it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WebTool {
+    Observe,
+    Operator,
+    Disrupt,
+}
+
+impl WebTool {
+    /// The tool for the number the page passes: 1 Observe, 3 Operator and
+    /// 4 Disrupt, the window's keys for them.
+    pub fn from_code(code: u32) -> Option<Self> {
+        match code {
+            1 => Some(WebTool::Observe),
+            3 => Some(WebTool::Operator),
+            4 => Some(WebTool::Disrupt),
+            _ => None,
+        }
+    }
+}
+
+/// A 2D universe and its pixels; see the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct WebSim {
+    pub universe: Universe,
+    pub view_mode: ViewMode,
+    mapping: ColorMapping,
+    /// The last frame rendered, one RGBA pixel per cell.
+    pixels: Vec<u8>,
+    /// Where the page writes text for the module to read.
+    text: Vec<u8>,
+}
+
+impl WebSim {
+    /// A `width` by `height` universe seeded with `seed`.
+    pub fn new(width: usize, height: usize, seed: u64) -> Self {
+        WebSim {
+            universe: Universe::with_seed(vec![width.max(1), height.max(1)], GA_DIMS, seed),
+            view_mode: ViewMode::default(),
+            mapping: ColorMapping::GA_BLEND,
+            pixels: Vec::new(),
+            text: Vec::new(),
+        }
+    }
+
+    pub fn width(&self) -> usize {
+        self.universe.grid_dims[0]
+    }
+
+    pub fn height(&self) -> usize {
+        self.universe.grid_dims[1]
+    }
+
+    /// Runs `ticks` ticks.
+    pub fn tick(&mut self, ticks: u32) {
+        for _ in 0..ticks {
+            self.universe.tick();
+        }
+    }
+
+    /// Replaces the universe with a fresh one of the same size.
+    pub fn reset(&mut self, seed: u64) {
+        *self = WebSim {
+            view_mode: self.view_mode,
+            ..WebSim::new(self.width(), self.height(), seed)
+        };
+    }
+
+    /// Uses `tool` on the cell at column `x` and row `y`; clicks off the grid
+    /// do nothing.
+    pub fn click(&mut self, x: usize, y: usize, tool: WebTool) {
+        let coord = [x, y];
+        let Some(idx) = self.universe.get_index_from_coord(&coord) else {
+            return;
+        };
+        match tool {
+            WebTool::Observe => self.universe.observe_cell(idx),
+            WebTool::Operator => self.universe.set_operator(&coord),
+            WebTool::Disrupt => self.universe.emit_disruption(&coord, DISRUPTION_AMPLITUDE),
+        }
+    }
+
+    /// Replaces the universe with the one a whole-universe delta code
+    /// describes, taking on its size; returns whether the code was valid.
+    pub fn load_state(&mut self, code: &str) -> bool {
+        let Ok(delta) = UniverseDelta::decode(code) else {
+            return false;
+        };
+        self.universe.entanglement = EntanglementGroups::default();
+        self.universe.apply_delta(&delta);
+        true
+    }
+
+    /// Applies a delta code to the universe; returns whether the code was
+    /// valid.
+    pub fn apply_delta(&mut self, code: &str) -> bool {
+        let Ok(delta) = UniverseDelta::decode(code) else {
+            return false;
+        };
+        self.universe.apply_delta(&delta);
+        true
+    }
+
+    /// The buffer the page writes text into for the module, resized to `len`
+    /// bytes.
+    pub fn text_buffer(&mut self, len: usize) -> &mut [u8] {
+        self.text.resize(len, 0);
+        &mut self.text
+    }
+
+    /// Renders the grid and returns its pixels: RGBA, row by row, one pixel
+    /// per cell.
+    pub fn render(&mut self) -> &[u8] {
+        let (universe, view_mode, mapping) = (&self.universe, self.view_mode, &self.mapping);
+        let frame = render_frame(universe, &Slice::new(2), 1, |idx| {
+            view_mode.color(universe, idx, mapping)
+        });
+        self.pixels = frame.into_raw();
+        &self.pixels
+    }
+}
+
+/// The C ABI the page calls. Every function but [`existons_new`] takes the
+/// pointer it returned.
+#[cfg(target_arch = "wasm32")]
+mod ffi {
+    use super::{WebSim, WebTool};
+
+    #[unsafe(no_mangle)]
+    pub extern "C" fn existons_new(width: u32, height: u32, seed: u32) -> *mut WebSim {
+        Box::into_raw(Box::new(WebSim::new(
+            width as usize,
+            height as usize,
+            seed as u64,
+        )))
+    }
+
+    /// # Safety
+    /// `sim` must come from [`existons_new`] and not be used afterwards.
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_free(sim: *mut WebSim) {
+        drop(unsafe { Box::from_raw(sim) });
+    }
+
+    /// # Safety
+    /// `sim` must come from [`existons_new`].
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_width(sim: *const WebSim) -> u32 {
+        unsafe { (*sim).width() as u32 }
+    }
+
+    /// # Safety
+    /// `sim` must come from [`existons_new`].
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_height(sim: *const WebSim) -> u32 {
+        unsafe { (*sim).height() as u32 }
+    }
+
+    /// # Safety
+    /// `sim` must come from [`existons_new`].
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_tick(sim: *mut WebSim, ticks: u32) {
+        unsafe { (*sim).tick(ticks) }
+    }
+
+    /// # Safety
+    /// `sim` must come from [`existons_new`].
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_reset(sim: *mut WebSim, seed: u32) {
+        unsafe { (*sim).reset(seed as u64) }
+    }
+
+    /// # Safety
+    /// `sim` must come from [`existons_new`].
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_click(sim: *mut WebSim, x: u32, y: u32, tool: u32) {
+        if let Some(tool) = WebTool::from_code(tool) {
+            unsafe { (*sim).click(x as usize, y as usize, tool) }
+        }
+    }
+
+    /// # Safety
+    /// `sim` must come from [`existons_new`].
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_cycle_view_mode(sim: *mut WebSim) {
+        let sim = unsafe { &mut *sim };
+        sim.view_mode = sim.view_mode.next();
+    }
+
+    /// Returns where the page may write `len` bytes of UTF-8 text for the
+    /// next call that reads text; they stay valid until then.
+    ///
+    /// # Safety
+    /// `sim` must come from [`existons_new`].
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_text(sim: *mut WebSim, len: u32) -> *mut u8 {
+        unsafe { (*sim).text_buffer(len as usize).as_mut_ptr() }
+    }
+
+    /// Replaces the universe with the state code written with
+    /// [`existons_text`]; returns 1 if it was valid and 0 if not.
+    ///
+    /// # Safety
+    /// `sim` must come from [`existons_new`].
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_load_state(sim: *mut WebSim) -> u32 {
+        let sim = unsafe { &mut *sim };
+        let code = String::from_utf8_lossy(&sim.text).into_owned();
+        sim.load_state(&code) as u32
+    }
+
+    /// Applies the delta code written with [`existons_text`]; returns 1 if
+    /// it was valid and 0 if not.
+    ///
+    /// # Safety
+    /// `sim` must come from [`existons_new`].
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_apply_delta(sim: *mut WebSim) -> u32 {
+        let sim = unsafe { &mut *sim };
+        let code = String::from_utf8_lossy(&sim.text).into_owned();
+        sim.apply_delta(&code) as u32
+    }
+
+    /// Renders the grid and returns where its `width * height * 4` bytes of
+    /// RGBA pixels start; they stay valid until the next call.
+    ///
+    /// # Safety
+    /// `sim` must come from [`existons_new`].
+    #[unsafe(no_mangle)]
+    pub unsafe extern "C" fn existons_render(sim: *mut WebSim) -> *const u8 {
+        unsafe { (*sim).render().as_ptr() }
+    }
+}
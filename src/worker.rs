@@ -0,0 +1,125 @@
+//! A simulation worker: a universe ticking on its own thread.
+//!
+//! A [`SimWorker`] owns a [`Universe`] on a background thread and talks to its
+//! frontend over channels: [`Command`]s go in, in order, and [`Update`]s come
+//! out, in the same order. However long a tick takes, the frontend's thread
+//! stays free to handle input and draw. The window keeps its own copy of the
+//! universe and trades it for the worker's after every tick (see
+//! [`AppState::worker`]); a headless or remote frontend can instead ask for a
+//! [`UniverseDelta`] per tick and apply it to a mirror, or send it on.
+//!
+//! [`AppState::worker`]: crate::app::AppState::worker
+
+use crate::delta::UniverseDelta;
+use crate::universe::{Intervention, TickReport, Universe};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::{self, JoinHandle};
+
+/// What a [`SimWorker`] is asked to do.
+#[derive(Debug, Clone)]
+pub enum Command {
+    /// Replace the worker's universe.
+    Load(Box<Universe>),
+    /// Apply an intervention between ticks.
+    Intervene(Intervention),
+    /// Run this many ticks, sending an [`Update::Ticked`] after each.
+    Tick(u64),
+    /// Send a copy of the universe as it is now.
+    Snapshot,
+}
+
+/// What a [`SimWorker`] sends back.
+#[derive(Debug, Clone)]
+pub enum Update {
+    /// A tick completed. The delta from the state before it is included when
+    /// the worker was spawned to send deltas.
+    Ticked {
+        report: Box<TickReport>,
+        delta: Option<UniverseDelta>,
+    },
+    /// The universe, in reply to [`Command::Snapshot`].
+    Snapshot(Box<Universe>),
+}
+
+/// A universe ticking on a background thread; see the [module documentation](self).
+///
+/// Dropping the worker lets it finish its queued commands and waits for it.
+#[derive(Debug)]
+pub struct SimWorker {
+    commands: Option<Sender<Command>>,
+    updates: Receiver<Update>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SimWorker {
+    /// Starts a worker owning `universe`. With `deltas`, every
+    /// [`Update::Ticked`] carries the tick's [`UniverseDelta`], at the cost of
+    /// a copy of the universe per tick.
+    pub fn spawn(universe: Universe, deltas: bool) -> Self {
+        let (commands, inbox) = mpsc::channel();
+        let (outbox, updates) = mpsc::channel();
+        let thread = thread::spawn(move || run(universe, deltas, inbox, outbox));
+        SimWorker {
+            commands: Some(commands),
+            updates,
+            thread: Some(thread),
+        }
+    }
+
+    /// Queues a command, returning whether the worker is still running.
+    pub fn send(&self, command: Command) -> bool {
+        self.commands
+            .as_ref()
+            .is_some_and(|commands| commands.send(command).is_ok())
+    }
+
+    /// The next update if one is ready, without waiting.
+    pub fn try_recv(&self) -> Option<Update> {
+        self.updates.try_recv().ok()
+    }
+
+    /// The next update, waiting for it; `None` once the worker has stopped.
+    pub fn recv(&self) -> Option<Update> {
+        self.updates.recv().ok()
+    }
+}
+
+impl Drop for SimWorker {
+    fn drop(&mut self) {
+        // Closing the command channel ends the worker's loop.
+        self.commands = None;
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// The worker thread: carries out commands until the channel closes or the
+/// frontend stops listening.
+fn run(mut universe: Universe, deltas: bool, inbox: Receiver<Command>, outbox: Sender<Update>) {
+    for command in inbox {
+        let sent = match command {
+            Command::Load(loaded) => {
+                universe = *loaded;
+                true
+            }
+            Command::Intervene(intervention) => {
+                universe.apply(intervention);
+                true
+            }
+            Command::Tick(ticks) => (0..ticks).all(|_| {
+                let before = deltas.then(|| universe.clone());
+                let report = universe.tick();
+                let delta = before.map(|before| before.diff(&universe));
+                let report = Box::new(report);
+                outbox.send(Update::Ticked { report, delta }).is_ok()
+            }),
+            Command::Snapshot => outbox
+                .send(Update::Snapshot(Box::new(universe.clone())))
+                .is_ok(),
+        };
+        if !sent {
+            return;
+        }
+    }
+}
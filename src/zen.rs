@@ -0,0 +1,231 @@
+//! Zen mode: the automaton as an ambient, self-directing piece.
+//!
+//! While [`ZenMode`] runs, the universe's rates wander along smooth random
+//! walks ([`Drift`]) and the camera glides towards whichever region the
+//! [`ActivityTracker`] finds busiest, zooming gently in and out. Nothing needs
+//! touching; the same engine simply plays itself.
+//!
+//! Drifting rates are not interventions, so a share code captured during zen
+//! mode records only the rates of that moment.
+
+use crate::app::{Camera, Config};
+use crate::universe::{TickReport, Universe};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// The side length, in cells, of the square regions activity is counted in.
+const REGION: usize = 8;
+/// The share of a region's activity that carries over to the next tick.
+const HEAT_RETENTION: f64 = 0.95;
+/// Frames between choices of where the camera heads next.
+const RETARGET_FRAMES: u32 = 240;
+/// The fraction of the remaining distance the camera covers each frame.
+const GLIDE: f64 = 0.01;
+
+//================================================================================
+// Activity Tracker
+//================================================================================
+
+/// A decaying heat map of where things happen on the grid's first two axes.
+#[derive(Debug, Clone)]
+pub struct ActivityTracker {
+    /// Regions along each of the first two grid axes.
+    regions: [usize; 2],
+    heat: Vec<f64>,
+}
+
+impl ActivityTracker {
+    /// An empty map for a grid of `grid_dims`.
+    pub fn new(grid_dims: &[usize]) -> Self {
+        let axis = |i: usize| grid_dims.get(i).copied().unwrap_or(1).div_ceil(REGION);
+        let regions = [axis(0), axis(1)];
+        ActivityTracker {
+            regions,
+            heat: vec![0.0; regions[0] * regions[1]],
+        }
+    }
+
+    /// Cools every region, then heats each one where an event of `report` happened.
+    pub fn on_tick(&mut self, report: &TickReport) {
+        for heat in &mut self.heat {
+            *heat *= HEAT_RETENTION;
+        }
+        let entangled = report.entanglements.iter().flat_map(|(a, b)| [a, b]);
+        let events = report
+            .observations
+            .iter()
+            .chain(&report.decays)
+            .chain(&report.fluctuations)
+            .chain(entangled);
+        for coord in events {
+            let x = coord.first().copied().unwrap_or(0) / REGION;
+            let y = coord.get(1).copied().unwrap_or(0) / REGION;
+            if let Some(heat) = self.heat.get_mut(y * self.regions[0] + x) {
+                *heat += 1.0;
+            }
+        }
+    }
+
+    /// The heat of the region containing cell `(x, y)`.
+    pub fn heat_at(&self, x: usize, y: usize) -> f64 {
+        self.heat
+            .get(y / REGION * self.regions[0] + x / REGION)
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// The center of the busiest region, in cells, or `None` before anything happened.
+    pub fn hottest(&self) -> Option<[f64; 2]> {
+        let (i, &heat) = self
+            .heat
+            .iter()
+            .enumerate()
+            .max_by(|a, b| a.1.total_cmp(b.1))?;
+        (heat > 0.0).then(|| {
+            let x = i % self.regions[0];
+            let y = i / self.regions[0];
+            [
+                (x as f64 + 0.5) * REGION as f64,
+                (y as f64 + 0.5) * REGION as f64,
+            ]
+        })
+    }
+}
+
+//================================================================================
+// Drift
+//================================================================================
+
+/// A value wandering smoothly between two bounds.
+///
+/// Random nudges change its velocity rather than its value, and the velocity
+/// is damped, so the value curves instead of jittering.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Drift {
+    pub value: f64,
+    pub min: f64,
+    pub max: f64,
+    velocity: f64,
+}
+
+impl Drift {
+    /// A drift starting at `value`, which is clamped into `min..=max`.
+    pub fn new(value: f64, min: f64, max: f64) -> Self {
+        Drift {
+            value: value.clamp(min, max),
+            min,
+            max,
+            velocity: 0.0,
+        }
+    }
+
+    /// Moves one step, bouncing off the bounds.
+    pub fn step<R: Rng + ?Sized>(&mut self, rng: &mut R) -> f64 {
+        let span = self.max - self.min;
+        self.velocity = self.velocity * 0.98 + rng.random_range(-1.0..1.0) * span * 0.0005;
+        self.value += self.velocity;
+        if self.value < self.min {
+            self.value = self.min;
+            self.velocity = self.velocity.abs();
+        } else if self.value > self.max {
+            self.value = self.max;
+            self.velocity = -self.velocity.abs();
+        }
+        self.value
+    }
+}
+
+//================================================================================
+// Zen Mode
+//================================================================================
+
+/// The state of a running zen mode.
+#[derive(Debug, Clone)]
+pub struct ZenMode {
+    rng: StdRng,
+    pub observation_rate: Drift,
+    pub decay_rate: Drift,
+    pub fluctuation_rate: Drift,
+    pub zoom: Drift,
+    pub activity: ActivityTracker,
+    /// The cell the camera is heading for.
+    focus: [f64; 2],
+    frames_to_retarget: u32,
+    /// The rates and camera from before zen mode, put back by [`ZenMode::stop`].
+    resume: ([f64; 3], Camera),
+}
+
+impl ZenMode {
+    /// Starts drifting from the universe's current rates, centered on the grid.
+    pub fn new(universe: &Universe, camera: Camera) -> Self {
+        let dims = &universe.grid_dims;
+        let rates = [
+            universe.observation_rate,
+            universe.decay_rate,
+            universe.fluctuation_rate,
+        ];
+        ZenMode {
+            rng: StdRng::from_rng(&mut rand::rng()),
+            observation_rate: Drift::new(universe.observation_rate, 0.0001, 0.005),
+            decay_rate: Drift::new(universe.decay_rate, 0.002, 0.05),
+            fluctuation_rate: Drift::new(universe.fluctuation_rate, 0.0002, 0.005),
+            zoom: Drift::new(1.5, 1.0, 2.5),
+            activity: ActivityTracker::new(dims),
+            focus: [
+                dims.first().copied().unwrap_or(0) as f64 / 2.0,
+                dims.get(1).copied().unwrap_or(0) as f64 / 2.0,
+            ],
+            frames_to_retarget: 0,
+            resume: (rates, camera),
+        }
+    }
+
+    /// Ends zen mode, putting the rates and camera back as they were.
+    pub fn stop(self, universe: &mut Universe, camera: &mut Camera) {
+        let ([observation, decay, fluctuation], before) = self.resume;
+        universe.observation_rate = observation;
+        universe.decay_rate = decay;
+        universe.fluctuation_rate = fluctuation;
+        *camera = before;
+    }
+
+    /// Advances one frame: drifts the rates, learns from the tick that just
+    /// completed (if any) and moves the camera a little.
+    pub fn update(
+        &mut self,
+        universe: &mut Universe,
+        camera: &mut Camera,
+        config: &Config,
+        report: Option<&TickReport>,
+    ) {
+        universe.observation_rate = self.observation_rate.step(&mut self.rng);
+        universe.decay_rate = self.decay_rate.step(&mut self.rng);
+        universe.fluctuation_rate = self.fluctuation_rate.step(&mut self.rng);
+        if let Some(report) = report {
+            self.activity.on_tick(report);
+        }
+
+        if self.frames_to_retarget == 0 {
+            self.frames_to_retarget = RETARGET_FRAMES;
+            if let Some(hottest) = self.activity.hottest() {
+                self.focus = hottest;
+            }
+        }
+        self.frames_to_retarget -= 1;
+
+        let zoom = self.zoom.step(&mut self.rng);
+        camera.zoom += (zoom - camera.zoom) * GLIDE;
+        // Put the focus in the middle of the window, without showing past the grid.
+        let size = config.window_size;
+        let grid_px = [
+            config.grid_dims.first().copied().unwrap_or(0) as f64 * config.cell_size,
+            config.grid_dims.get(1).copied().unwrap_or(0) as f64 * config.cell_size,
+        ];
+        for axis in 0..2 {
+            let center = self.focus[axis] * config.cell_size * camera.zoom;
+            let lowest = (size[axis] - grid_px[axis] * camera.zoom).min(0.0);
+            let target = (size[axis] / 2.0 - center).clamp(lowest, 0.0);
+            camera.offset[axis] += (target - camera.offset[axis]) * GLIDE;
+        }
+    }
+}
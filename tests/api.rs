@@ -0,0 +1,108 @@
+//! Tests of driving a run over HTTP.
+#![cfg(feature = "desktop")]
+
+use existons::api::ApiServer;
+use existons::app::{AppState, Config};
+use existons::delta::UniverseDelta;
+use existons::frontend::Frontend;
+use existons::universe::{Intervention, Universe};
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+/// Sends a request and returns the status code and body of the response.
+fn request(address: SocketAddr, method: &str, target: &str, form: &str) -> (u16, Vec<u8>) {
+    let mut stream = TcpStream::connect(address).unwrap();
+    write!(
+        stream,
+        "{} {} HTTP/1.1\r\nHost: localhost\r\nContent-Type: application/x-www-form-urlencoded\r\n\
+         Content-Length: {}\r\n\r\n{}",
+        method,
+        target,
+        form.len(),
+        form
+    )
+    .unwrap();
+    let mut response = Vec::new();
+    stream.read_to_end(&mut response).unwrap();
+    let end = response.windows(4).position(|w| w == b"\r\n\r\n").unwrap();
+    let head = String::from_utf8_lossy(&response[..end]).into_owned();
+    let status = head.split(' ').nth(1).unwrap().parse().unwrap();
+    (status, response[end + 4..].to_vec())
+}
+
+fn text((status, body): (u16, Vec<u8>)) -> (u16, String) {
+    (status, String::from_utf8(body).unwrap())
+}
+
+#[test]
+fn requests_drive_the_run() {
+    let mut app = AppState::new(Config::new());
+    app.universe = Universe::with_seed(vec![12, 10], 3, 11);
+    app.worker = None;
+    let mut server = ApiServer::bind("http://127.0.0.1:0").unwrap();
+    server.frame = Duration::from_millis(2);
+    let address = server.local_addr().unwrap();
+
+    let client = thread::spawn(move || {
+        let (status, body) = text(request(address, "POST", "/stop", ""));
+        assert_eq!(status, 200);
+        assert!(body.contains("\"running\":false"));
+        assert!(body.contains("\"grid_dims\":[12,10]"));
+        assert!(body.contains("\"seed\":11"));
+
+        // Stepping answers once the ticks are done, and nothing runs after.
+        let (_, body) = text(request(address, "POST", "/step?ticks=5", ""));
+        let tick: u64 = body
+            .split("\"tick\":")
+            .nth(1)
+            .and_then(|rest| rest.split(',').next())
+            .unwrap()
+            .parse()
+            .unwrap();
+        thread::sleep(Duration::from_millis(20));
+        let (_, status) = text(request(address, "GET", "/status", ""));
+        assert!(status.contains(&format!("\"tick\":{},", tick)));
+        let (_, stats) = text(request(address, "GET", "/stats", ""));
+        assert!(stats.contains(&format!("\"tick\":{},", tick)));
+        assert!(stats.contains("\"grade_totals\":["));
+
+        let (status, body) = text(request(address, "POST", "/params", "decay_rate=0.5"));
+        assert_eq!(status, 200);
+        assert!(body.contains("\"decay_rate\":0.5"));
+        let (status, body) = text(request(address, "POST", "/params?gravity=1", ""));
+        assert_eq!(status, 400);
+        assert!(body.contains("unknown parameter gravity"));
+
+        assert_eq!(request(address, "POST", "/tool", "operator 2 3").0, 200);
+        let (status, body) = text(request(address, "POST", "/tool", "paint 2 3"));
+        assert_eq!(status, 400);
+        assert!(body.contains("unknown command paint"));
+
+        let (_, body) = text(request(address, "GET", "/snapshot", ""));
+        let code = body.split('"').nth(5).unwrap().to_string();
+        let (_, npy) = request(address, "GET", "/snapshot?format=npy", "");
+        assert!(npy.starts_with(b"\x93NUMPY"));
+
+        assert_eq!(request(address, "GET", "/nowhere", "").0, 404);
+        assert_eq!(request(address, "GET", "/step", "").0, 405);
+        assert_eq!(request(address, "POST", "/quit", "").0, 200);
+        (tick, code)
+    });
+    server.run(&mut app).unwrap();
+    let (tick, code) = client.join().unwrap();
+
+    assert!(tick >= 5);
+    assert_eq!(app.universe.decay_rate, 0.5);
+    let idx = app.universe.get_index_from_coord(&[2, 3]).unwrap();
+    assert!(
+        app.universe
+            .interventions
+            .iter()
+            .any(|entry| entry.intervention == Intervention::SetOperator(idx))
+    );
+    let snapshot = UniverseDelta::decode(&code).unwrap();
+    assert_eq!(snapshot.tick, app.universe.tick_count);
+    assert_eq!(snapshot.cells.len(), 12 * 10);
+}
@@ -0,0 +1,72 @@
+//! Tests of the grid as arrays and `.npy` files.
+
+use existons::array::GridArray;
+use existons::existon::ConsciousnessState;
+use existons::universe::Universe;
+
+#[test]
+fn arrays_are_indexed_by_coordinate_then_blade_in_row_major_order() {
+    let mut universe = Universe::with_seed(vec![4, 3], 2, 5);
+    universe.observe_cell(universe.get_index_from_coord(&[2, 1]).unwrap());
+    let array = universe.to_array();
+    assert_eq!(array.shape(), [4, 3, 4]);
+    assert_eq!(array.strides(), [12, 4, 1]);
+    for x in 0..4 {
+        for y in 0..3 {
+            let cell = &universe.grid[universe.get_index_from_coord(&[x, y]).unwrap()];
+            for (blade, c) in cell.state.coefficients.iter().enumerate() {
+                assert_eq!(array.get(&[x, y, blade]), Some(&c.0));
+                assert_eq!(array.as_slice()[x * 12 + y * 4 + blade], c.0);
+            }
+        }
+    }
+    assert_eq!(array.get(&[4, 0, 0]), None);
+    assert_eq!(array.get(&[0, 0]), None);
+
+    let observed = universe.mask(ConsciousnessState::Observed);
+    assert_eq!(observed.shape(), [4, 3]);
+    assert_eq!(observed.as_slice().iter().filter(|&&b| b).count(), 1);
+    assert_eq!(observed.get(&[2, 1]), Some(&true));
+    let potential = universe.mask(ConsciousnessState::Potential);
+    assert!(
+        observed
+            .as_slice()
+            .iter()
+            .zip(potential.as_slice())
+            .all(|(o, p)| o != p)
+    );
+}
+
+#[test]
+fn npy_files_carry_the_shape_and_start_their_data_aligned() {
+    let array = GridArray::from_shape_vec(vec![2, 3], vec![-1i8, 0, 1, 1, 0, -1]).unwrap();
+    let bytes = array.to_npy();
+    assert_eq!(&bytes[..8], b"\x93NUMPY\x01\x00");
+    let header_len = u16::from_le_bytes([bytes[8], bytes[9]]) as usize;
+    assert_eq!((10 + header_len) % 64, 0);
+    let header = std::str::from_utf8(&bytes[10..10 + header_len]).unwrap();
+    assert!(header.starts_with("{'descr': '|i1', 'fortran_order': False, 'shape': (2, 3), }"));
+    assert!(header.ends_with('\n'));
+    assert_eq!(&bytes[10 + header_len..], [255, 0, 1, 1, 0, 255]);
+
+    let flat = GridArray::from_shape_vec(vec![2], vec![true, false]).unwrap();
+    assert!(
+        String::from_utf8_lossy(&flat.to_npy())
+            .contains("'descr': '|b1', 'fortran_order': False, 'shape': (2,)")
+    );
+    assert!(GridArray::from_shape_vec(vec![2, 2], vec![0.5f64; 3]).is_none());
+}
+
+#[cfg(feature = "ndarray")]
+#[test]
+fn ndarray_arrays_match_grid_arrays() {
+    let universe = Universe::with_seed(vec![5, 3], 3, 8);
+    let array = universe.to_array();
+    let view = array.view();
+    assert_eq!(view.shape(), [5, 3, 8]);
+    assert_eq!(view.as_ptr(), array.as_slice().as_ptr());
+    let owned = universe.to_ndarray();
+    assert_eq!(owned, view);
+    assert_eq!(owned[[4, 2, 7]], *array.get(&[4, 2, 7]).unwrap());
+    assert_eq!(ndarray::ArrayD::from(array.clone()), owned);
+}
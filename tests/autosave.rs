@@ -0,0 +1,63 @@
+//! Tests of autosave checkpoints and resuming from them.
+#![cfg(feature = "desktop")]
+
+mod common;
+
+use common::Driver;
+use existons::app::{AppState, Config};
+use existons::autosave::{AutosavePolicy, Autosaver};
+use existons::existon::ConsciousnessState;
+use piston_window::Key;
+use std::fs;
+use std::time::{Duration, Instant};
+
+#[test]
+fn checkpoints_rotate_and_the_newest_readable_one_resumes() {
+    let dir = std::env::temp_dir().join(format!("existons-autosave-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let policy = AutosavePolicy {
+        dir: dir.clone(),
+        every_ticks: Some(10),
+        every: None,
+        keep: 2,
+    };
+    let mut driver = Driver::new(8);
+    driver.app.config.autosave = Some(policy.clone());
+    driver.app.autosave = Some(Autosaver::new(policy.clone(), 0, Instant::now()));
+    driver.key(Key::D3).click_cell(3, 3);
+    for _ in 0..30 {
+        driver.update();
+    }
+    assert_eq!(policy.checkpoints(), vec![1, 2]);
+
+    let mut config = Config::new();
+    config.autosave = Some(policy.clone());
+    let mut resumed = AppState::new(config);
+    assert_eq!(resumed.resume_autosave(), Some(policy.checkpoint_path(2)));
+    assert_eq!(resumed.universe.tick_count, 30);
+    assert_eq!(resumed.universe.grid, driver.app.universe.grid);
+    let operator = &resumed.universe.grid[driver.index(3, 3)];
+    assert_eq!(operator.consciousness, ConsciousnessState::Operator);
+
+    // A checkpoint cut short by a crash is passed over for the one before.
+    fs::write(policy.checkpoint_path(3), "EXS1-").unwrap();
+    let (path, session) = Autosaver::latest(&policy).unwrap();
+    assert_eq!(path, policy.checkpoint_path(2));
+    assert_eq!(session.scenario.ticks, 30);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn time_based_checkpoints_wait_for_the_universe_to_move() {
+    let policy = AutosavePolicy {
+        every_ticks: None,
+        every: Some(Duration::from_secs(60)),
+        ..AutosavePolicy::default()
+    };
+    let start = Instant::now();
+    let later = start + Duration::from_secs(61);
+    let autosave = Autosaver::new(policy, 7, start);
+    assert!(!autosave.is_due(8, start));
+    assert!(!autosave.is_due(7, later));
+    assert!(autosave.is_due(8, later));
+}
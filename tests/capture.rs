@@ -0,0 +1,112 @@
+//! Tests of animated GIF and APNG captures.
+
+use existons::capture::{Capture, CaptureFormat, CaptureSettings, render_frame};
+use existons::slice::Slice;
+use existons::universe::Universe;
+use image::AnimationDecoder;
+use image::codecs::gif::GifDecoder;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("existons-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn frames_show_the_viewed_plane_at_the_chosen_scale() {
+    let universe = Universe::with_seed(vec![4, 2, 3], 2, 50);
+    let mut slice = Slice::new(3);
+    slice.position[2] = 1;
+    let frame = render_frame(&universe, &slice, 3, |idx| {
+        [(idx % 4) as f32 / 3.0, 0.0, 1.0, 0.5]
+    });
+    assert_eq!(frame.dimensions(), (12, 6));
+    // Cell [2, 1, 1] fills the block from pixel (6, 3), blended onto black.
+    let idx = universe.get_index_from_coord(&[2, 1, 1]).unwrap();
+    let red = (((idx % 4) as f32 / 3.0 * 0.5) * 255.0).round() as u8;
+    assert_eq!(frame.get_pixel(6, 3).0, [red, 0, 128, 255]);
+    assert_eq!(frame.get_pixel(8, 5), frame.get_pixel(6, 3));
+
+    assert_eq!(
+        CaptureFormat::for_path(Path::new("run.PNG")),
+        CaptureFormat::Apng
+    );
+    assert_eq!(
+        CaptureFormat::for_path(Path::new("run.gif")),
+        CaptureFormat::Gif
+    );
+}
+
+#[test]
+fn captures_take_a_frame_every_few_ticks_and_encode_both_formats() {
+    let mut universe = Universe::with_seed(vec![10, 8], 2, 51);
+    let slice = Slice::new(2);
+    let settings = CaptureSettings {
+        every: 3,
+        scale: 2,
+        frames_per_second: 10,
+    };
+    let mut capture = Capture::new(settings);
+    for _ in 0..=7 {
+        capture.capture(&universe, &slice, |_| [1.0, 0.5, 0.0, 1.0]);
+        universe.tick();
+    }
+    // Ticks 0, 3 and 6.
+    assert_eq!(capture.frames().len(), 3);
+    assert_eq!(capture.frames()[0].dimensions(), (20, 16));
+
+    let gif = temp_path("capture.gif");
+    assert_eq!(capture.clone().finish(&gif).unwrap(), 3);
+    let decoder = GifDecoder::new(BufReader::new(File::open(&gif).unwrap())).unwrap();
+    let frames = decoder.into_frames().collect_frames().unwrap();
+    std::fs::remove_file(&gif).unwrap();
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0].delay().numer_denom_ms(), (100, 1));
+
+    let apng = temp_path("capture.png");
+    assert_eq!(capture.finish(&apng).unwrap(), 3);
+    let reader = png::Decoder::new(File::open(&apng).unwrap())
+        .read_info()
+        .unwrap();
+    let control = reader.info().animation_control().unwrap();
+    let size = (reader.info().width, reader.info().height);
+    std::fs::remove_file(&apng).unwrap();
+    assert_eq!(control.num_frames, 3);
+    assert_eq!(size, (20, 16));
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn f9_starts_and_stops_a_capture_of_the_run() {
+    use existons::app::{AppState, Config};
+    use existons::input::{AppAction, Modifiers, key_action};
+    use piston_window::Key;
+
+    let mut app = AppState::new(Config::new());
+    app.autosave = None;
+    app.config.capture_path = temp_path("app-capture.gif");
+    app.config.capture.scale = 1;
+    let toggle = key_action(Key::F9, Modifiers::default()).unwrap();
+    assert_eq!(toggle, AppAction::ToggleCapture);
+
+    app.apply(toggle);
+    // The first frame is taken straight away.
+    assert_eq!(app.capture.as_ref().unwrap().frames().len(), 1);
+    for _ in 0..4 {
+        app.apply(AppAction::Tick);
+    }
+    assert_eq!(app.capture.as_ref().unwrap().frames().len(), 5);
+    app.apply(toggle);
+    assert!(app.capture.is_none());
+    let path = app.config.capture_path.clone();
+    let decoder = GifDecoder::new(BufReader::new(File::open(&path).unwrap())).unwrap();
+    let frames = decoder.into_frames().collect_frames().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(frames.len(), 5);
+    let [width, height] = [app.config.grid_dims[0], app.config.grid_dims[1]];
+    assert_eq!(
+        frames[0].buffer().dimensions(),
+        (width as u32, height as u32)
+    );
+}
@@ -0,0 +1,92 @@
+//! Tests of structure detection.
+
+use existons::census::{Census, StructureKind};
+use existons::existon::{ConsciousnessState, Existon};
+use existons::ga_core::Multivector;
+use existons::rule::{RuleCtx, UpdateRule};
+use existons::universe::Universe;
+
+/// A universe of empty `Potential` cells with `Observed` ones at `cells`.
+fn frame(universe: &mut Universe, cells: &[[usize; 2]]) {
+    for cell in &mut universe.grid {
+        cell.consciousness = ConsciousnessState::Potential;
+        cell.state = Multivector::zero(universe.ga_dims);
+    }
+    for coord in cells {
+        let idx = universe.get_index_from_coord(coord).unwrap();
+        universe.grid[idx].consciousness = ConsciousnessState::Observed;
+    }
+}
+
+#[test]
+fn blocks_blinkers_and_gliders_are_told_apart() {
+    let mut universe = Universe::with_seed(vec![12, 12], 2, 1);
+    let block = [[1, 1], [1, 2], [2, 1], [2, 2]];
+    let blinker = [[[8, 7], [8, 8], [8, 9]], [[7, 8], [8, 8], [9, 8]]];
+    let mut census = Census::new(4);
+    for t in 0..5 {
+        let domino = [[2 + t, 5], [3 + t, 5]];
+        let cells: Vec<[usize; 2]> = block
+            .iter()
+            .chain(&blinker[t % 2])
+            .chain(&domino)
+            .copied()
+            .collect();
+        frame(&mut universe, &cells);
+        census.record(&universe);
+    }
+    assert_eq!(census.frames(), 5);
+
+    let kinds: Vec<(Vec<usize>, StructureKind)> = census
+        .structures()
+        .into_iter()
+        .map(|s| (s.origin, s.kind))
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            (vec![1, 1], StructureKind::StillLife),
+            (
+                vec![6, 5],
+                StructureKind::Glider {
+                    period: 1,
+                    displacement: vec![1, 0],
+                }
+            ),
+            (vec![8, 7], StructureKind::Oscillator { period: 2 }),
+        ]
+    );
+}
+
+#[derive(Debug)]
+struct Frozen;
+
+impl UpdateRule for Frozen {
+    fn apply(&self, cell: &Existon, _: &[&Existon], _: &RuleCtx) -> Existon {
+        cell.clone()
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn a_census_runs_on_a_copy_and_skips_transients() {
+    let mut universe = Universe::with_seed(vec![8, 8], 2, 3).with_rule(Frozen);
+    frame(&mut universe, &[[1, 1], [5, 5], [5, 6]]);
+    let structures = universe.census(3);
+    assert_eq!(universe.tick_count, 0);
+    assert_eq!(structures.len(), 2);
+    assert!(
+        structures
+            .iter()
+            .all(|s| s.kind == StructureKind::StillLife)
+    );
+    assert_eq!(structures[1].cells, vec![vec![5, 5], vec![5, 6]]);
+
+    // A cluster seen once has nothing to match.
+    let mut census = Census::new(3);
+    census.record(&universe);
+    assert!(census.structures().is_empty());
+}
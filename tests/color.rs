@@ -0,0 +1,93 @@
+//! Tests of the cell color mapping and its legend.
+
+use existons::color::{ColorMapping, POTENTIAL_COLOR, UNENTANGLED_COLOR, ViewMode, heat_color};
+use existons::existon::Existon;
+use existons::field::{ParameterField, Rate};
+use existons::ga_core::{Mod3, blade_name};
+use existons::universe::Universe;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+#[test]
+fn blade_names_follow_the_bitmask() {
+    assert_eq!(blade_name(0), "1");
+    assert_eq!(blade_name(0b1), "e0");
+    assert_eq!(blade_name(0b101), "e02");
+    assert_eq!(blade_name((1 << 10) | 1), "e0_10");
+}
+
+#[test]
+fn ga_blend_colors_potential_cells_from_the_first_four_blades() {
+    let mut existon = Existon::new(0, 3, &mut StdRng::seed_from_u64(1));
+    existon.state.coefficients[..4].copy_from_slice(&[Mod3(1), Mod3(0), Mod3(-1), Mod3(1)]);
+    assert_eq!(
+        ColorMapping::GA_BLEND.color(&existon),
+        [0.7, 0.35, 0.0, 1.3]
+    );
+}
+
+#[test]
+fn legend_is_generated_for_the_algebra_dimension() {
+    let legend = ColorMapping::GA_BLEND.describe(3).join("\n");
+    assert!(legend.contains("Alpha <- e01"));
+    assert!(legend.contains("Not shown: e2, e02, e12, e012"));
+
+    let legend = ColorMapping::GA_BLEND.describe(1).join("\n");
+    assert!(legend.contains("Blue <- e1: 0.00 / 0.35 / 0.70 - absent in Cl(1,0)"));
+}
+
+#[test]
+fn view_modes_color_cells_by_one_measure_each() {
+    let mut universe = Universe::with_seed(vec![4, 4], 2, 5);
+    universe.entanglement.clear();
+    let idx = universe.get_index_from_coord(&[1, 1]).unwrap();
+    universe.grid[idx].state.coefficients = vec![Mod3(1), Mod3(0), Mod3(-1), Mod3(0)];
+    let mapping = ColorMapping::GA_BLEND;
+    let color = |universe: &Universe, mode: ViewMode| mode.color(universe, idx, &mapping);
+
+    assert_eq!(
+        color(&universe, ViewMode::Coefficients),
+        mapping.color(&universe.grid[idx])
+    );
+    assert_eq!(color(&universe, ViewMode::GradeWeight), heat_color(0.5));
+    assert_eq!(color(&universe, ViewMode::Consciousness), POTENTIAL_COLOR);
+    assert_eq!(color(&universe, ViewMode::Age), heat_color(0.0));
+    assert_eq!(
+        color(&universe, ViewMode::EntanglementDegree),
+        UNENTANGLED_COLOR
+    );
+
+    let partner = universe.grid[0].id;
+    universe.entangle_pair(universe.grid[idx].id, partner);
+    universe.tick_count = ViewMode::HALF_AGE;
+    assert_eq!(color(&universe, ViewMode::Age), heat_color(0.5));
+    let degree = color(&universe, ViewMode::EntanglementDegree);
+    assert_eq!(degree, heat_color(1.0 / 3.0));
+
+    let mut mode = ViewMode::default();
+    let mut seen = vec![mode];
+    for _ in 0..ViewMode::ALL.len() {
+        mode = mode.next();
+        seen.push(mode);
+    }
+    let cycle = ViewMode::ALL.len();
+    assert_eq!(&seen[..cycle], ViewMode::ALL);
+    assert_eq!(seen[cycle], ViewMode::Coefficients);
+
+    // Rate fields are drawn against the global rate, which sits halfway.
+    let field = ViewMode::Field(Rate::Decay);
+    universe.decay_rate = 0.01;
+    assert_eq!(color(&universe, field), heat_color(0.5));
+    let hot = ParameterField::from_fn(&[4, 4], |coord| if coord == [1, 1] { 0.03 } else { 0.0 });
+    universe.set_parameter_field(Rate::Decay, Some(hot));
+    assert_eq!(color(&universe, field), heat_color(0.75));
+    assert_eq!(field.color(&universe, 0, &mapping), heat_color(0.0));
+    assert_eq!(
+        ViewMode::Age.describe(&mapping, 2)[0],
+        "View mode: Age".to_string()
+    );
+    assert_eq!(
+        ViewMode::Coefficients.describe(&mapping, 2),
+        mapping.describe(2)
+    );
+}
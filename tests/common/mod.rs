@@ -0,0 +1,91 @@
+//! A headless input driver that feeds synthetic Piston events into an `AppState`.
+//!
+//! The events are the same ones a `PistonWindow` produces and go through the
+//! same `input::handle_event`, so every test exercises the GUI's exact path.
+
+use existons::app::{AppState, Config, get_pos_from_coord};
+use existons::input;
+use existons::universe::Universe;
+use piston_window::{
+    Button, ButtonArgs, ButtonState, Event, Input, Key, Loop, Motion, MouseButton, UpdateArgs,
+};
+
+pub struct Driver {
+    pub app: AppState,
+}
+
+impl Driver {
+    /// Creates a driver around a seeded universe whose spontaneous rules are disabled,
+    /// so every change in state is caused by the synthetic input.
+    pub fn new(seed: u64) -> Self {
+        let mut app = AppState::new(Config::new());
+        let mut universe =
+            Universe::with_seed(app.config.grid_dims.clone(), app.config.ga_dims, seed);
+        universe.observation_rate = 0.0;
+        universe.decay_rate = 0.0;
+        universe.fluctuation_rate = 0.0;
+        app.universe = universe;
+        // Tests must not litter the working directory with checkpoints.
+        app.autosave = None;
+        Driver { app }
+    }
+
+    fn send(&mut self, event: Input) -> &mut Self {
+        input::handle_event(&mut self.app, &Event::Input(event, None));
+        self
+    }
+
+    fn button(&mut self, button: Button, state: ButtonState) -> &mut Self {
+        self.send(Input::Button(ButtonArgs {
+            state,
+            button,
+            scancode: None,
+        }))
+    }
+
+    /// Moves the cursor to a pixel position.
+    pub fn move_to(&mut self, pos: [f64; 2]) -> &mut Self {
+        self.send(Input::Move(Motion::MouseCursor(pos)))
+    }
+
+    /// Moves the cursor to the center of a grid cell.
+    pub fn move_to_cell(&mut self, x: usize, y: usize) -> &mut Self {
+        let (config, topology) = (&self.app.config, self.app.universe.topology());
+        let half = config.cell_size / 2.0;
+        let pos = get_pos_from_coord(&[x, y], config, topology).map(|p| p + half);
+        self.move_to(pos)
+    }
+
+    pub fn press(&mut self, button: Button) -> &mut Self {
+        self.button(button, ButtonState::Press)
+    }
+
+    pub fn release(&mut self, button: Button) -> &mut Self {
+        self.button(button, ButtonState::Release)
+    }
+
+    /// Presses and releases a key.
+    pub fn key(&mut self, key: Key) -> &mut Self {
+        self.press(Button::Keyboard(key))
+            .release(Button::Keyboard(key))
+    }
+
+    /// Moves to a cell and clicks it with the left mouse button.
+    pub fn click_cell(&mut self, x: usize, y: usize) -> &mut Self {
+        self.move_to_cell(x, y)
+            .press(Button::Mouse(MouseButton::Left))
+            .release(Button::Mouse(MouseButton::Left))
+    }
+
+    /// Sends one update event, which ticks the universe unless it is paused.
+    pub fn update(&mut self) -> &mut Self {
+        let update = Event::Loop(Loop::Update(UpdateArgs { dt: 1.0 / 60.0 }));
+        input::handle_event(&mut self.app, &update);
+        self
+    }
+
+    /// The grid index of a 2D cell.
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        self.app.universe.get_index_from_coord(&[x, y]).unwrap()
+    }
+}
@@ -0,0 +1,55 @@
+use existons::entanglement::{Decoherence, EntanglementGroups};
+
+#[test]
+fn entangling_moves_ids_out_of_their_old_groups() {
+    let mut groups = EntanglementGroups::default();
+    let a = groups.entangle(&[4, 1, 4]).unwrap();
+    assert_eq!(groups.members(a), &[1, 4]);
+    assert_eq!(groups.entangle(&[9]), None);
+
+    // Taking 4 away leaves 1 alone, so the first group dissolves.
+    let b = groups.entangle(&[4, 7]).unwrap();
+    assert_eq!(groups.len(), 1);
+    assert!(!groups.is_entangled(1));
+    assert_eq!(groups.members(a), &[] as &[u64]);
+    assert_eq!(groups.group_of(7), Some(b));
+}
+
+#[test]
+fn join_and_remove_keep_groups_sorted_and_at_least_pairs() {
+    let mut groups = EntanglementGroups::default();
+    let g = groups.entangle(&[2, 8]).unwrap();
+    groups.join(5, g);
+    assert_eq!(groups.members(g), &[2, 5, 8]);
+    assert_eq!(groups.partners(5).collect::<Vec<_>>(), vec![2, 8]);
+
+    groups.remove(8);
+    assert_eq!(groups.members(g), &[2, 5]);
+    groups.remove(2);
+    assert!(groups.is_empty());
+    assert!(!groups.is_entangled(5));
+}
+
+#[test]
+fn decoherence_weakens_groups_and_firing_refreshes_them() {
+    let mut groups = EntanglementGroups::default();
+    let a = groups.entangle(&[1, 2]).unwrap();
+    let b = groups.entangle(&[3, 4, 5]).unwrap();
+    let decoherence = Decoherence {
+        rate: 0.25,
+        threshold: 0.6,
+        refresh_on_fire: true,
+    };
+    assert!(groups.decohere(&decoherence, []).is_empty());
+    assert_eq!(groups.strength(a), 0.75);
+
+    // 0.75 * 0.75 falls below the threshold; the group that fired starts over.
+    assert_eq!(groups.decohere(&decoherence, [4]), vec![vec![1, 2]]);
+    assert_eq!(groups.strength(a), 0.0);
+    assert_eq!(groups.strength(b), 0.75);
+
+    for _ in 0..100 {
+        groups.decohere(&Decoherence::NONE, []);
+    }
+    assert_eq!(groups.strength(b), 0.75);
+}
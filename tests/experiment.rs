@@ -0,0 +1,83 @@
+//! Tests of the Bell test experiment.
+
+use existons::experiment::{BellSettings, BellTest};
+use existons::ga_core::{Mod3, Multivector};
+use existons::plugin::{PluginCtx, PluginRegistry};
+use existons::universe::{TickReport, Universe};
+
+#[test]
+fn anticorrelated_pairs_reach_the_classical_bound() {
+    let mut universe = Universe::with_seed(vec![4, 4], 2, 1);
+    let mut state = Multivector::zero(2);
+    state.coefficients = vec![Mod3::new(0), Mod3::new(1), Mod3::new(1), Mod3::new(1)];
+    universe.grid[0].state = state.clone();
+    state.coefficients[1] = Mod3::new(-1);
+    state.coefficients[2] = Mod3::new(-1);
+    universe.grid[5].state = state;
+    let report = TickReport {
+        entanglements: vec![(vec![0, 0], vec![1, 1])],
+        ..TickReport::default()
+    };
+
+    let mut test = BellTest::new(BellSettings::default(), 3);
+    for _ in 0..200 {
+        test.record(&universe, &report);
+    }
+    assert_eq!(test.trials(), 200);
+    for (x, y) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+        assert!(test.tally(x, y).trials > 20);
+        assert_eq!(test.tally(x, y).correlation(), Some(-1.0));
+    }
+    assert_eq!(test.chsh(), Some(-2.0));
+
+    // A zero coefficient is a missed click.
+    universe.grid[5].state.coefficients[1] = Mod3::new(0);
+    let mut test = BellTest::new(BellSettings::default(), 3);
+    for _ in 0..50 {
+        test.record(&universe, &report);
+    }
+    assert!(test.discarded() > 0);
+    assert_eq!(test.trials() + test.discarded(), 50);
+    assert_eq!(test.tally(0, 1).trials + test.tally(1, 1).trials, 0);
+}
+
+#[test]
+fn running_a_test_prepares_pairs_and_is_reproducible() {
+    let results: Vec<(u64, Option<f64>)> = (0..2)
+        .map(|_| {
+            let mut universe = Universe::with_seed(vec![24, 24], 3, 7);
+            universe.observation_rate = 0.2;
+            let mut test = BellTest::new(BellSettings::default(), 11);
+            test.run(&mut universe, 60);
+            assert!(!universe.interventions.is_empty());
+            (test.trials(), test.chsh())
+        })
+        .collect();
+    assert!(results[0].0 > 30);
+    let s = results[0].1.unwrap();
+    assert!(s.abs() <= 4.0);
+    assert_eq!(results[0], results[1]);
+}
+
+#[test]
+fn the_built_in_analyzer_reports_the_statistic() {
+    let mut registry = PluginRegistry::with_builtins();
+    let bell = registry
+        .list()
+        .iter()
+        .position(|plugin| plugin.name == "Bell test")
+        .unwrap();
+    registry.toggle(bell);
+    let mut universe = Universe::with_seed(vec![24, 24], 3, 7);
+    universe.observation_rate = 0.2;
+    universe.set_entanglement_percentage(0.8);
+    for _ in 0..40 {
+        let report = universe.tick();
+        registry.on_tick(&PluginCtx {
+            universe: &universe,
+            report: &report,
+        });
+    }
+    let panel = registry.describe();
+    assert!(panel.iter().any(|line| line.contains("S = ")));
+}
@@ -0,0 +1,72 @@
+use existons::existon::ConsciousnessState;
+use existons::field::{ParameterField, Rate};
+use existons::share::Scenario;
+use existons::universe::Universe;
+
+#[test]
+fn fields_are_laid_out_in_grid_index_order() {
+    let field = ParameterField::from_fn(&[3, 2], |coord| (coord[0] + 3 * coord[1]) as f64 / 10.0);
+    let expected: Vec<f32> = (0..6).map(|i| i as f32 / 10.0).collect();
+    assert_eq!(field.values(), &expected[..]);
+
+    let gradient = ParameterField::linear_gradient(&[5, 2], 0, 0.0, 1.0);
+    assert_eq!(gradient.get(0), 0.0);
+    assert_eq!(gradient.get(2), 0.5);
+    assert_eq!(gradient.get(9), 1.0);
+
+    let radial = ParameterField::radial_gradient(&[5, 5], &[2.0, 2.0], 2.0, 1.0, 0.0);
+    assert_eq!(radial.get(12), 1.0);
+    assert_eq!(radial.get(13), 0.5);
+    assert_eq!(radial.get(0), 0.0);
+    // Values outside [0, 1] are clamped.
+    assert_eq!(ParameterField::uniform(&[2], 3.0).get(1), 1.0);
+
+    let painted = gradient.painted([(0, 0.25), (9, -1.0)]);
+    assert_eq!(painted.get(0), 0.25);
+    assert_eq!(painted.get(9), 0.0);
+    assert_eq!(painted.get(2), 0.5);
+    assert_eq!(gradient.get(0), 0.0, "the original is left as it was");
+}
+
+#[test]
+fn fields_load_from_grayscale_images() {
+    let path = std::env::temp_dir().join(format!("existons-field-{}.png", std::process::id()));
+    let picture =
+        image::GrayImage::from_fn(4, 2, |x, _| image::Luma([if x < 2 { 0 } else { 255 }]));
+    picture.save(&path).unwrap();
+    let field = ParameterField::from_image(&[4, 2, 2], &path, 0.2, 0.6).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(field.len(), 16);
+    for idx in [0, 4, 8, 12] {
+        assert!((field.get(idx) - 0.2).abs() < 1e-6);
+        assert!((field.get(idx + 3) - 0.6).abs() < 1e-6);
+    }
+}
+
+#[test]
+fn hot_regions_follow_the_field_and_survive_share_codes() {
+    let mut universe = Universe::with_seed(vec![8, 4], 2, 6);
+    universe.observation_rate = 0.0;
+    universe.fluctuation_rate = 0.0;
+    let hot = ParameterField::from_fn(&[8, 4], |coord| if coord[0] < 4 { 1.0 } else { 0.0 });
+    universe.set_parameter_field(Rate::Observation, Some(hot));
+    universe.tick();
+    for (idx, cell) in universe.grid.iter().enumerate() {
+        let expected = if idx % 8 < 4 {
+            ConsciousnessState::Observed
+        } else {
+            ConsciousnessState::Potential
+        };
+        assert_eq!(cell.consciousness, expected, "cell {idx}");
+    }
+
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(
+        rebuilt.parameter_field(Rate::Observation),
+        universe.parameter_field(Rate::Observation)
+    );
+    assert_eq!(rebuilt.grid, universe.grid);
+}
@@ -0,0 +1,55 @@
+//! Tests of choosing frontends by name.
+#![cfg(feature = "desktop")]
+
+use existons::app::{AppState, Config};
+use existons::frontend::{Frontend, FrontendRegistry, Headless};
+use std::io;
+use std::time::Duration;
+
+/// Runs a single tick.
+struct OneTick;
+
+impl Frontend for OneTick {
+    fn name(&self) -> &str {
+        "one-tick"
+    }
+
+    fn run(&mut self, app: &mut AppState) -> io::Result<()> {
+        app.universe.tick();
+        Ok(())
+    }
+}
+
+fn app() -> AppState {
+    let mut config = Config::new();
+    config.grid_dims = vec![16, 16];
+    AppState::new(config)
+}
+
+#[test]
+fn frontends_are_found_by_name() {
+    let mut frontends = FrontendRegistry::with_builtins();
+    frontends.register(OneTick);
+    assert_eq!(frontends.names(), ["tui", "headless", "one-tick"]);
+    assert!(frontends.get_mut("piston").is_none());
+
+    let mut app = app();
+    frontends
+        .get_mut("one-tick")
+        .unwrap()
+        .run(&mut app)
+        .unwrap();
+    assert_eq!(app.universe.tick_count, 1);
+}
+
+#[test]
+fn the_headless_frontend_stops_after_its_ticks() {
+    let mut app = app();
+    let mut headless = Headless {
+        ticks: Some(5),
+        report_every: Duration::from_secs(60),
+    };
+    headless.run(&mut app).unwrap();
+    assert_eq!(app.universe.tick_count, 5);
+    assert!(app.worker.is_none() && app.timestep.is_none());
+}
@@ -0,0 +1,58 @@
+//! Tests of the GPU tick, which skip themselves on machines without a GPU.
+#![cfg(feature = "gpu")]
+
+use existons::ga_core::Multivector;
+use existons::gpu::{GpuTicker, pack, unpack};
+use existons::universe::{BoundaryCondition, Neighborhood, Universe};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn ticker() -> Option<GpuTicker> {
+    GpuTicker::new()
+        .map_err(|e| eprintln!("skipping: {}", e))
+        .ok()
+}
+
+#[test]
+fn packing_round_trips() {
+    let mut rng = StdRng::seed_from_u64(4);
+    for p in 0..=4 {
+        for _ in 0..20 {
+            let mv = Multivector::random(p, &mut rng);
+            assert_eq!(unpack(pack(&mv), p), mv);
+        }
+    }
+}
+
+#[test]
+fn gpu_tick_matches_the_cpu() {
+    let Some(mut gpu) = ticker() else { return };
+    let mut cpu = Universe::with_seed(vec![37, 23], 3, 9);
+    cpu.set_operator(&[5, 5]);
+    let mut accelerated = cpu.clone();
+    for _ in 0..5 {
+        assert_eq!(gpu.tick(&mut accelerated).unwrap(), cpu.tick());
+    }
+    assert_eq!(accelerated.grid, cpu.grid);
+}
+
+#[test]
+fn gpu_follows_boundary_and_neighborhood_changes() {
+    let Some(mut gpu) = ticker() else { return };
+    let mut cpu = Universe::with_seed(vec![12, 9, 4], 4, 2);
+    let mut accelerated = cpu.clone();
+    for (boundary, neighborhood) in [
+        (BoundaryCondition::Fixed, Neighborhood::VonNeumann),
+        (
+            BoundaryCondition::Reflective,
+            Neighborhood::Moore { radius: 2 },
+        ),
+    ] {
+        for universe in [&mut cpu, &mut accelerated] {
+            universe.set_boundary(boundary);
+            universe.set_neighborhood(neighborhood.clone());
+        }
+        assert_eq!(gpu.tick(&mut accelerated).unwrap(), cpu.tick());
+        assert_eq!(accelerated.grid, cpu.grid);
+    }
+}
@@ -0,0 +1,50 @@
+//! Tests of the entanglement graph export.
+
+use existons::existon::ConsciousnessState;
+use existons::graph::GraphFormat;
+use existons::universe::Universe;
+
+/// A 4x3 universe entangled as one triple, {0, 5, 11}, and one pair, {2, 7}.
+fn wired_universe() -> Universe {
+    let mut universe = Universe::with_seed(vec![4, 3], 3, 1);
+    universe.entanglement.clear();
+    universe.entangle_group(&[0, 5, 11]);
+    universe.entangle_pair(2, 7);
+    universe.set_operator(&[1, 1]);
+    universe
+}
+
+#[test]
+fn groups_become_cliques_of_their_members() {
+    let graph = wired_universe().entanglement_graph();
+    let ids: Vec<u64> = graph.nodes.iter().map(|node| node.id).collect();
+    assert_eq!(ids, [0, 2, 5, 7, 11]);
+    assert_eq!(graph.nodes[2].coord, [1, 1]);
+    assert_eq!(graph.nodes[2].consciousness, ConsciousnessState::Operator);
+    let edges: Vec<(u64, u64)> = graph.edges.iter().map(|edge| edge.ids).collect();
+    assert_eq!(edges, [(0, 5), (0, 11), (5, 11), (2, 7)]);
+    assert!(graph.edges.iter().all(|edge| edge.strength == 1.0));
+    assert_ne!(graph.edges[0].group, graph.edges[3].group);
+}
+
+#[test]
+fn graphs_render_as_dot_and_graphml() {
+    let universe = wired_universe();
+    let dot = universe.entanglement_graph().render(GraphFormat::Dot);
+    assert!(dot.starts_with("graph entanglement {"));
+    assert!(dot.contains("n5 [label=\"5\", coord=\"1,1\", consciousness=\"Operator\"];"));
+    assert!(dot.contains("n2 -- n7 [group="));
+    assert_eq!(dot.matches(" -- ").count(), 4);
+
+    let path = std::env::temp_dir().join(format!("existons-graph-{}.graphml", std::process::id()));
+    universe
+        .export_entanglement_graph(&path, GraphFormat::GraphMl)
+        .unwrap();
+    let graphml = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert!(graphml.contains("edgedefault=\"undirected\""));
+    assert_eq!(graphml.matches("<node ").count(), 5);
+    assert_eq!(graphml.matches("<edge ").count(), 4);
+    assert!(graphml.contains("<edge source=\"n0\" target=\"n11\">"));
+    assert!(graphml.trim_end().ends_with("</graphml>"));
+}
@@ -0,0 +1,57 @@
+//! Tests of the help overlay.
+#![cfg(feature = "desktop")]
+
+use existons::app::{AppState, Config};
+use existons::help::{HelpLine, help_lines};
+use existons::input::{AppAction, Modifiers, key_action};
+use existons::keymap::Keymap;
+use piston_window::Key;
+
+fn entry(lines: &[HelpLine], what: &str) -> Option<String> {
+    lines.iter().find_map(|line| match line {
+        HelpLine::Entry(keys, text) if text.starts_with(what) => Some(keys.clone()),
+        _ => None,
+    })
+}
+
+#[test]
+fn the_overlay_lists_tools_keys_and_colors_from_the_keymap() {
+    let mut app = AppState::new(Config::new());
+    app.autosave = None;
+    assert!(!app.overlays.help);
+    app.apply(key_action(Key::H, Modifiers::default()).unwrap());
+    assert!(app.overlays.help);
+
+    let lines = help_lines(&app);
+    let headings: Vec<_> = lines
+        .iter()
+        .filter_map(|line| match line {
+            HelpLine::Heading(title) => Some(*title),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(headings, ["Tools", "Keys", "Colors"]);
+    assert_eq!(entry(&lines, "Operator:").as_deref(), Some("3"));
+    assert!(lines.contains(&HelpLine::Entry(
+        "Ctrl+PageUp / Ctrl+PageDown".to_string(),
+        "Next depth axis".to_string()
+    )));
+    assert_eq!(entry(&lines, "Pause").as_deref(), Some("Space"));
+    assert!(matches!(lines.last(), Some(HelpLine::Text(_))));
+    // Every bound key is listed once, tools under Tools.
+    let entries = lines
+        .iter()
+        .filter(|line| matches!(line, HelpLine::Entry(..)))
+        .count();
+    assert_eq!(entries, Keymap::default().help().len() + 3);
+
+    // Rebound keys show as bound, and unbound actions drop out.
+    app.keymap = Keymap::parse("tool_operator = \"Q\"\npause = []").unwrap();
+    let lines = help_lines(&app);
+    assert_eq!(entry(&lines, "Operator:").as_deref(), Some("Q"));
+    assert_eq!(entry(&lines, "Pause"), None);
+    assert_eq!(
+        app.keymap.action(Key::Q, Modifiers::default()),
+        Some(AppAction::SelectTool(existons::app::ToolMode::Operator))
+    );
+}
@@ -0,0 +1,46 @@
+//! Tests of the heads-up display's rates and statistics.
+
+use existons::hud::Hud;
+use existons::universe::Universe;
+
+#[test]
+fn the_hud_measures_frames_and_ticks_over_the_last_second() {
+    let mut universe = Universe::with_seed(vec![8, 8], 2, 80);
+    universe.set_operator(&[3, 3]);
+    let mut hud = Hud::default();
+    assert_eq!(hud.fps(), 0.0);
+    hud.record_frame(0.0, &universe);
+    assert_eq!(hud.stats().unwrap().operators, 1);
+
+    // Two seconds at 60 frames a second, ticking every other frame.
+    for frame in 1..=120 {
+        if frame % 2 == 0 {
+            universe.tick();
+        }
+        hud.record_frame(1.0 / 60.0, &universe);
+    }
+    assert!((hud.fps() - 60.0).abs() < 1e-6);
+    assert!((hud.ticks_per_second() - 30.0).abs() < 1.0);
+
+    // The statistics are recomputed only once the tick has moved on, and at
+    // most every quarter second.
+    hud.record_frame(Hud::REFRESH, &universe);
+    universe.set_operator(&[5, 5]);
+    hud.record_frame(0.15, &universe);
+    assert_eq!(hud.stats().unwrap().operators, 1);
+    universe.tick();
+    hud.record_frame(0.05, &universe);
+    assert_eq!(hud.stats().unwrap().operators, 1);
+    hud.record_frame(0.1, &universe);
+    let stats = hud.stats().unwrap();
+    assert_eq!(stats.operators, 2);
+    assert_eq!(stats.tick, universe.tick_count);
+    let line = hud.describe();
+    assert!(line.starts_with(&format!("Tick {}  ", universe.tick_count)));
+    assert!(line.contains("Operators 2  Entanglements"));
+
+    // A reset starts the tick rate afresh instead of counting backwards.
+    let universe = Universe::with_seed(vec![8, 8], 2, 81);
+    hud.record_frame(1.0 / 60.0, &universe);
+    assert_eq!(hud.ticks_per_second(), 0.0);
+}
@@ -0,0 +1,744 @@
+//! End-to-end tests of the tools, driven by synthetic input events.
+#![cfg(feature = "desktop")]
+
+mod common;
+
+use common::Driver;
+use existons::app::{Camera, Falloff, ToolMode, get_pos_from_coord};
+use existons::existon::ConsciousnessState;
+use existons::field::Rate;
+use existons::input::{self, AppAction};
+use existons::keymap::Keymap;
+use existons::multiverse::Multiverse;
+use existons::shape::Shape;
+use existons::toolbar::{Toolbar, ToolbarItem};
+use existons::universe::{BoundaryCondition, OperatorPreset, Topology, Universe};
+use piston_window::{Button, Key, MouseButton};
+
+const LEFT: Button = Button::Mouse(MouseButton::Left);
+const RIGHT: Button = Button::Mouse(MouseButton::Right);
+
+#[test]
+fn number_keys_switch_tools() {
+    let mut driver = Driver::new(1);
+    assert_eq!(driver.app.current_tool, ToolMode::Observe);
+    driver.key(Key::D2);
+    assert_eq!(driver.app.current_tool, ToolMode::Entangle);
+    driver.key(Key::D3);
+    assert_eq!(driver.app.current_tool, ToolMode::Operator);
+    driver.key(Key::D4);
+    assert_eq!(driver.app.current_tool, ToolMode::Disrupt);
+    driver.key(Key::D1);
+    assert_eq!(driver.app.current_tool, ToolMode::Observe);
+}
+
+#[test]
+fn i_toggles_the_hud_which_follows_the_shown_universe() {
+    let mut driver = Driver::new(4);
+    assert!(driver.app.hud.visible);
+    driver.key(Key::I);
+    assert!(!driver.app.hud.visible);
+    driver.key(Key::D3).click_cell(1, 1).update();
+    driver.app.record_frame(1.0 / 60.0);
+    let stats = driver.app.hud.stats().unwrap();
+    assert_eq!((stats.tick, stats.operators), (1, 1));
+}
+
+#[test]
+fn o_cycles_the_operator_palette_only_with_the_operator_tool() {
+    let mut driver = Driver::new(3);
+    driver.key(Key::O);
+    assert_eq!(driver.app.operator_preset, OperatorPreset::E0);
+    driver.key(Key::D3).key(Key::O);
+    assert_eq!(driver.app.operator_preset, OperatorPreset::E0E1);
+
+    driver.click_cell(8, 8);
+    let cell = &driver.app.universe.grid[driver.index(8, 8)];
+    assert_eq!(cell.consciousness, ConsciousnessState::Operator);
+    assert_eq!(
+        cell.state,
+        OperatorPreset::E0E1.state(driver.app.universe.ga_dims)
+    );
+}
+
+#[test]
+fn alt_click_picks_up_a_state_for_the_operator_tool_until_o() {
+    let mut driver = Driver::new(3);
+    let idx = driver.index(5, 5);
+    let picked = OperatorPreset::Pseudoscalar.state(driver.app.universe.ga_dims);
+    driver.app.universe.grid[idx].state = picked.clone();
+    let before = driver.app.universe.grid[idx].consciousness;
+    let alt = Button::Keyboard(Key::LAlt);
+    driver.key(Key::D3).press(alt).click_cell(5, 5).release(alt);
+    assert_eq!(driver.app.operator_sample, Some(picked.clone()));
+    assert_eq!(driver.app.universe.grid[idx].consciousness, before);
+
+    driver.click_cell(8, 8);
+    assert_eq!(driver.app.universe.grid[driver.index(8, 8)].state, picked);
+
+    driver.key(Key::O);
+    assert_eq!(driver.app.operator_sample, None);
+    assert_eq!(driver.app.operator_preset, OperatorPreset::E0);
+    driver.key(Key::O);
+    assert_eq!(driver.app.operator_preset, OperatorPreset::E0E1);
+}
+
+#[test]
+fn ctrl_g_snaps_operators_to_the_nearest_lattice_cell() {
+    let mut driver = Driver::new(6);
+    let ctrl = Button::Keyboard(Key::LCtrl);
+    driver
+        .key(Key::D3)
+        .press(ctrl)
+        .key(Key::G)
+        .key(Key::G)
+        .release(ctrl);
+    assert_eq!(driver.app.operator_snap, 4);
+
+    driver.move_to_cell(9, 14);
+    assert_eq!(driver.app.operator_target(), Some(vec![8, 16]));
+    driver.press(LEFT).release(LEFT);
+    let placed = &driver.app.universe.grid[driver.index(8, 16)];
+    assert_eq!(placed.consciousness, ConsciousnessState::Operator);
+    assert_ne!(
+        driver.app.universe.grid[driver.index(9, 14)].consciousness,
+        ConsciousnessState::Operator
+    );
+
+    // Past the far edge the lattice cell inside the grid is used.
+    driver.move_to_cell(119, 70);
+    assert_eq!(driver.app.operator_target(), Some(vec![116, 72]));
+
+    driver
+        .press(ctrl)
+        .key(Key::G)
+        .key(Key::G)
+        .key(Key::G)
+        .release(ctrl);
+    assert_eq!(driver.app.operator_snap, 1);
+}
+
+#[test]
+fn f_switches_the_operator_tool_to_lines_drawn_on_release() {
+    let mut driver = Driver::new(9);
+    driver.key(Key::D3).key(Key::F);
+    assert_eq!(driver.app.operator_shape, Shape::Line);
+
+    driver.move_to_cell(10, 20).press(LEFT);
+    for x in 11..=16 {
+        driver.move_to_cell(x, 23);
+    }
+    assert_eq!(driver.app.shape_cells().len(), 7);
+    // Nothing is placed until the button is let go.
+    let cell = |driver: &Driver, x, y| driver.app.universe.grid[driver.index(x, y)].consciousness;
+    assert_ne!(cell(&driver, 13, 21), ConsciousnessState::Operator);
+
+    driver.release(LEFT);
+    assert_eq!(driver.app.shape_drag, None);
+    for (x, y) in [(10, 20), (12, 21), (14, 22), (16, 23)] {
+        assert_eq!(cell(&driver, x, y), ConsciousnessState::Operator);
+    }
+    assert_ne!(cell(&driver, 11, 23), ConsciousnessState::Operator);
+
+    // The whole line undoes in one step.
+    driver.press(Button::Keyboard(Key::LCtrl)).key(Key::Z);
+    assert_ne!(cell(&driver, 14, 22), ConsciousnessState::Operator);
+}
+
+#[test]
+fn operator_tool_paints_while_held_and_erases_with_right_button() {
+    let mut driver = Driver::new(2);
+    driver.key(Key::D3).move_to_cell(10, 10).press(LEFT);
+    for x in 11..=15 {
+        driver.move_to_cell(x, 10);
+    }
+    driver.release(LEFT).move_to_cell(20, 10);
+
+    for x in 10..=15 {
+        let idx = driver.index(x, 10);
+        assert_eq!(
+            driver.app.universe.grid[idx].consciousness,
+            ConsciousnessState::Operator
+        );
+    }
+    // Moving after release must not paint.
+    let idx = driver.index(20, 10);
+    assert_ne!(
+        driver.app.universe.grid[idx].consciousness,
+        ConsciousnessState::Operator
+    );
+
+    driver
+        .move_to_cell(12, 10)
+        .press(RIGHT)
+        .move_to_cell(13, 10)
+        .release(RIGHT);
+    for x in 12..=13 {
+        let idx = driver.index(x, 10);
+        assert_eq!(
+            driver.app.universe.grid[idx].consciousness,
+            ConsciousnessState::Potential
+        );
+    }
+}
+
+#[test]
+fn clicks_land_on_the_shifted_cells_of_a_hexagonal_grid() {
+    let mut driver = Driver::new(2);
+    driver.app.universe.set_topology(Topology::Hexagonal);
+    let size = driver.app.config.cell_size;
+    // The left half of the first cell in an odd row is its left neighbor's.
+    driver
+        .key(Key::D3)
+        .move_to([10.25 * size, 11.5 * size])
+        .press(LEFT)
+        .release(LEFT)
+        .move_to_cell(12, 11)
+        .press(LEFT)
+        .release(LEFT);
+    for (x, operator) in [(9, true), (10, false), (12, true)] {
+        let idx = driver.index(x, 11);
+        assert_eq!(
+            driver.app.universe.grid[idx].consciousness == ConsciousnessState::Operator,
+            operator
+        );
+    }
+}
+
+#[test]
+fn holding_observe_collapses_the_brush_area() {
+    let mut driver = Driver::new(3);
+    driver.move_to_cell(60, 40).press(LEFT);
+
+    let centre = driver.index(60, 40);
+    let edge = driver.index(65, 40);
+    let outside = driver.index(80, 40);
+    let grid = &driver.app.universe.grid;
+    assert_eq!(grid[centre].consciousness, ConsciousnessState::Observed);
+    assert_eq!(grid[edge].consciousness, ConsciousnessState::Observed);
+    assert_eq!(grid[outside].consciousness, ConsciousnessState::Potential);
+}
+
+#[test]
+fn disrupt_click_returns_observed_cells_to_potential() {
+    let mut driver = Driver::new(4);
+    driver.move_to_cell(30, 30).press(LEFT).release(LEFT);
+    let idx = driver.index(30, 30);
+    assert_eq!(
+        driver.app.universe.grid[idx].consciousness,
+        ConsciousnessState::Observed
+    );
+
+    driver.key(Key::D4).click_cell(30, 30);
+    assert_eq!(
+        driver.app.universe.grid[idx].consciousness,
+        ConsciousnessState::Potential
+    );
+    let waves = driver.app.universe.disruption_waves();
+    assert_eq!(waves.len(), 1);
+    assert_eq!(waves[0].center, vec![30, 30]);
+}
+
+#[test]
+fn holding_disrupt_charges_the_brush_up_to_twice_its_radius() {
+    assert_eq!(Falloff::Hard.weight(0.5), 1.0);
+    assert_eq!(Falloff::Linear.weight(0.5), 0.5);
+    assert_eq!(Falloff::Smooth.weight(0.5), 0.5);
+    assert_eq!(Falloff::Smooth.weight(1.0), 0.0);
+
+    let mut driver = Driver::new(4);
+    driver.key(Key::D4).move_to_cell(30, 30).press(LEFT);
+    assert_eq!(driver.app.brush_radius(), Some(50.0));
+    for _ in 0..100 {
+        driver.update();
+    }
+    assert_eq!(driver.app.disrupt_charge, 1.0);
+    assert_eq!(driver.app.brush_radius(), Some(100.0));
+
+    driver.release(LEFT);
+    assert_eq!(driver.app.disrupt_charge, 0.0);
+    assert_eq!(driver.app.brush_radius(), Some(50.0));
+}
+
+#[test]
+fn the_paint_tool_raises_and_lowers_a_rate_under_its_brush() {
+    let mut driver = Driver::new(6);
+    driver.app.universe.observation_rate = 0.001;
+    driver.key(Key::D7).move_to_cell(30, 30).press(LEFT);
+    assert_eq!(driver.app.current_tool, ToolMode::Paint);
+    // A quarter of a second held doubles the rate at the center.
+    for _ in 0..15 {
+        driver.update();
+    }
+    driver.release(LEFT);
+    let (centre, edge, outside) = (
+        driver.index(30, 30),
+        driver.index(34, 30),
+        driver.index(50, 30),
+    );
+    let rate = |driver: &Driver, idx| driver.app.universe.rate_at(Rate::Observation, idx);
+    assert!((rate(&driver, centre) - 0.002).abs() < 1e-6);
+    assert!(rate(&driver, edge) > 0.001 && rate(&driver, edge) < 0.002);
+    assert!((rate(&driver, outside) - 0.001).abs() < 1e-9);
+    assert!(driver.app.universe.parameter_field(Rate::Decay).is_none());
+
+    // Right lowers it, down to nothing.
+    driver.press(RIGHT);
+    for _ in 0..300 {
+        driver.update();
+    }
+    driver.release(RIGHT);
+    assert_eq!(rate(&driver, centre), 0.0);
+
+    driver.key(Key::O);
+    assert_eq!(driver.app.paint_rate, Rate::Decay);
+}
+
+#[test]
+fn entangle_tool_selects_then_links_a_pair_and_pauses_meanwhile() {
+    let mut driver = Driver::new(5);
+    driver.app.universe.entanglement.clear();
+    driver.key(Key::D2).click_cell(5, 5);
+
+    let first = driver.index(5, 5) as u64;
+    assert_eq!(driver.app.entangle_first_partner, Some(first));
+    let ticks = driver.app.universe.tick_count;
+    driver.update().update();
+    assert_eq!(
+        driver.app.universe.tick_count, ticks,
+        "paused while selecting"
+    );
+
+    driver.click_cell(50, 50);
+    let second = driver.index(50, 50) as u64;
+    assert_eq!(driver.app.entangle_first_partner, None);
+    let group = driver.app.universe.entanglement.group_of(first);
+    assert!(group.is_some());
+    assert_eq!(
+        driver.app.universe.entanglement.members(group.unwrap()),
+        &[first, second]
+    );
+    assert_eq!(driver.app.entanglement_flashes.len(), 1);
+
+    driver.update();
+    assert_eq!(driver.app.universe.tick_count, ticks + 1);
+}
+
+#[test]
+fn shift_keeps_the_entangle_selection_open_to_grow_a_group() {
+    let mut driver = Driver::new(5);
+    driver.app.universe.entanglement.clear();
+    driver.key(Key::D2).press(Button::Keyboard(Key::LShift));
+    driver
+        .click_cell(5, 5)
+        .click_cell(50, 50)
+        .click_cell(20, 30);
+    let first = driver.index(5, 5) as u64;
+    assert_eq!(driver.app.entangle_first_partner, Some(first));
+
+    driver.release(Button::Keyboard(Key::LShift));
+    assert_eq!(driver.app.entangle_first_partner, Some(first));
+    let group = driver.app.universe.entanglement.group_of(first).unwrap();
+    let mut expected = vec![
+        first,
+        driver.index(50, 50) as u64,
+        driver.index(20, 30) as u64,
+    ];
+    expected.sort_unstable();
+    assert_eq!(driver.app.universe.entanglement.members(group), expected);
+    assert_eq!(driver.app.entanglement_flashes.len(), 2);
+}
+
+#[test]
+fn tool_keys_backspace_and_right_click_cancel_a_pending_entanglement_selection() {
+    let mut driver = Driver::new(6);
+    driver.app.universe.entanglement.clear();
+    driver.key(Key::D2).click_cell(7, 7);
+    // Other keys leave it alone.
+    driver.key(Key::I).key(Key::Space);
+    assert!(driver.app.entangle_first_partner.is_some());
+    driver.key(Key::D2);
+    assert_eq!(driver.app.entangle_first_partner, None);
+
+    driver.click_cell(7, 7).key(Key::Backspace);
+    assert_eq!(driver.app.entangle_first_partner, None);
+    driver.click_cell(7, 7).press(RIGHT).release(RIGHT);
+    assert_eq!(driver.app.entangle_first_partner, None);
+}
+
+#[test]
+fn dragging_from_one_cell_to_another_entangles_them() {
+    let mut driver = Driver::new(6);
+    driver.app.universe.entanglement.clear();
+    driver.key(Key::D2).move_to_cell(5, 5).press(LEFT);
+    let first = driver.index(5, 5) as u64;
+    assert_eq!(driver.app.entangle_first_partner, Some(first));
+
+    driver.move_to_cell(30, 40);
+    let second = driver.index(30, 40);
+    assert_eq!(driver.app.entangle_drag_target(), Some(second));
+    driver.release(LEFT);
+    assert_eq!(driver.app.entangle_first_partner, None);
+    let group = driver.app.universe.entanglement.group_of(first).unwrap();
+    assert_eq!(
+        driver.app.universe.entanglement.members(group),
+        &[first, second as u64]
+    );
+
+    // Let go off any partner, the selection is dropped.
+    let operator = driver.index(12, 12);
+    driver.app.universe.grid[operator].consciousness = ConsciousnessState::Operator;
+    driver.move_to_cell(8, 8).press(LEFT).move_to_cell(12, 12);
+    assert_eq!(driver.app.entangle_drag_target(), None);
+    driver.release(LEFT);
+    assert_eq!(driver.app.entangle_first_partner, None);
+    assert_eq!(driver.app.universe.entanglement.len(), 1);
+}
+
+#[test]
+fn reset_key_creates_a_fresh_universe() {
+    let mut driver = Driver::new(7);
+    driver.update().update();
+    driver.key(Key::R);
+    assert_eq!(driver.app.universe.tick_count, 0);
+    assert_ne!(driver.app.universe.seed, 7);
+}
+
+#[test]
+fn t_key_spreads_ticks_over_frames() {
+    let mut driver = Driver::new(9);
+    driver.app.config.tick_budget = std::time::Duration::ZERO;
+    driver.key(Key::T);
+    assert!(driver.app.soft_real_time);
+    driver.update();
+    assert_eq!(driver.app.universe.tick_count, 0);
+    while driver.app.universe.is_mid_tick() {
+        driver.update();
+    }
+    assert_eq!(driver.app.universe.tick_count, 1);
+}
+
+#[test]
+fn ctrl_z_undoes_a_stroke_and_ctrl_y_redoes_it() {
+    let mut driver = Driver::new(10);
+    driver.key(Key::D3).update().click_cell(5, 5).update();
+    let idx = driver.index(5, 5);
+    let operator = ConsciousnessState::Operator;
+    assert_eq!(driver.app.universe.grid[idx].consciousness, operator);
+
+    // Z alone does nothing; with Ctrl held it undoes back past the stroke.
+    driver.key(Key::Z);
+    assert_eq!(driver.app.universe.grid[idx].consciousness, operator);
+    driver
+        .press(Button::Keyboard(Key::LCtrl))
+        .key(Key::Z)
+        .key(Key::Z);
+    assert!(driver.app.paused);
+    assert_ne!(driver.app.universe.grid[idx].consciousness, operator);
+    assert!(driver.app.universe.interventions.is_empty());
+
+    // Paused, updates leave the redo history intact.
+    driver.update().update().key(Key::Y).key(Key::Y);
+    driver.release(Button::Keyboard(Key::LCtrl));
+    assert_eq!(driver.app.universe.grid[idx].consciousness, operator);
+    assert_eq!(driver.app.universe.tick_count, 2);
+
+    driver.key(Key::Space).update();
+    assert!(!driver.app.paused);
+    assert_eq!(driver.app.universe.tick_count, 3);
+}
+
+#[test]
+fn z_key_drifts_the_view_and_restores_it_afterwards() {
+    let mut driver = Driver::new(40);
+    let rates = |d: &Driver| {
+        let u = &d.app.universe;
+        (u.observation_rate, u.decay_rate, u.fluctuation_rate)
+    };
+    let before = rates(&driver);
+    driver.key(Key::Z);
+    for _ in 0..20 {
+        driver.update();
+    }
+    assert_ne!(rates(&driver), before);
+    assert_ne!(driver.app.camera, Default::default());
+    assert!(driver.app.camera.zoom > 1.0);
+
+    driver.key(Key::Z);
+    assert!(driver.app.zen.is_none());
+    assert_eq!(rates(&driver), before);
+    assert_eq!(driver.app.camera, Default::default());
+}
+
+#[test]
+fn b_key_cycles_boundary_and_fixed_brush_does_not_wrap() {
+    let mut driver = Driver::new(8);
+    driver.key(Key::B);
+    assert_eq!(driver.app.universe.boundary, BoundaryCondition::Fixed);
+
+    driver.move_to_cell(0, 40).press(LEFT);
+    let near = driver.index(1, 40);
+    let wrapped = driver.index(119, 40);
+    let grid = &driver.app.universe.grid;
+    assert_eq!(grid[near].consciousness, ConsciousnessState::Observed);
+    assert_eq!(grid[wrapped].consciousness, ConsciousnessState::Potential);
+
+    driver.release(LEFT).key(Key::B).key(Key::B);
+    assert_eq!(driver.app.universe.boundary, BoundaryCondition::Toroidal);
+}
+
+#[test]
+fn actions_drive_the_state_without_any_events() {
+    let mut driver = Driver::new(9);
+    let app = &mut driver.app;
+    app.apply(AppAction::SelectTool(ToolMode::Operator));
+    app.apply(AppAction::MoveCursor([4.0, 4.0]));
+    app.apply(AppAction::PressPrimary);
+    app.apply_tool_effects();
+    app.apply(AppAction::ReleasePrimary);
+    assert_eq!(
+        app.universe.grid[0].consciousness,
+        ConsciousnessState::Operator
+    );
+}
+
+#[test]
+fn key_presses_translate_to_the_bound_action() {
+    use piston_window::{ButtonArgs, ButtonState, Event, Input};
+    let press = Event::Input(
+        Input::Button(ButtonArgs {
+            state: ButtonState::Press,
+            button: Button::Keyboard(Key::D4),
+            scancode: None,
+        }),
+        None,
+    );
+    assert_eq!(
+        input::input_actions(&press, Default::default(), &Keymap::default()),
+        vec![AppAction::SelectTool(ToolMode::Disrupt)]
+    );
+}
+
+#[test]
+fn middle_drag_pans_and_the_wheel_zooms_about_the_cursor() {
+    use piston_window::{Event, Input, Motion};
+    let scroll = Event::Input(Input::Move(Motion::MouseScroll([0.0, 2.0])), None);
+    let actions = input::input_actions(&scroll, Default::default(), &Keymap::default());
+    assert_eq!(actions, vec![AppAction::Zoom(2.0)]);
+
+    let mut driver = Driver::new(12);
+    let middle = Button::Mouse(MouseButton::Middle);
+    driver.key(Key::D3).move_to([100.0, 100.0]).press(middle);
+    driver.move_to([140.0, 70.0]).release(middle);
+    driver.move_to([200.0, 200.0]);
+    assert_eq!(driver.app.camera.offset, [40.0, -30.0]);
+    assert_eq!(driver.app.camera.zoom, 1.0);
+    assert!(driver.app.universe.interventions.is_empty());
+
+    // The grid under the cursor stays put while zooming.
+    let before = driver.app.cursor_world_pos();
+    driver.app.apply(AppAction::Zoom(3.0));
+    assert!(driver.app.camera.zoom > 1.5);
+    let after = driver.app.cursor_world_pos();
+    assert!((after[0] - before[0]).abs() < 1e-9 && (after[1] - before[1]).abs() < 1e-9);
+    driver.app.apply(AppAction::Zoom(-100.0));
+    assert_eq!(driver.app.camera.zoom, Camera::MIN_ZOOM);
+    driver.app.apply(AppAction::Zoom(100.0));
+    assert_eq!(driver.app.camera.zoom, Camera::MAX_ZOOM);
+
+    // Clicks land on the cell drawn under the cursor.
+    let config = &driver.app.config;
+    let corner = get_pos_from_coord(&[7, 9], config, Topology::Square);
+    let center = corner.map(|p| p + config.cell_size / 2.0);
+    let pos = driver.app.camera.world_to_screen(center);
+    let left = Button::Mouse(MouseButton::Left);
+    driver.move_to(pos).press(left).release(left);
+    let idx = driver.index(7, 9);
+    assert_eq!(
+        driver.app.universe.grid[idx].consciousness,
+        ConsciousnessState::Operator
+    );
+
+    driver.key(Key::D0);
+    assert_eq!(driver.app.camera, Camera::default());
+}
+
+#[test]
+fn resizing_the_window_letterboxes_the_grid_and_keeps_clicks_on_target() {
+    use piston_window::{Event, Input, ResizeArgs};
+    let resize = Event::Input(
+        Input::Resize(ResizeArgs {
+            window_size: [1920.0, 1000.0],
+            draw_size: [3840, 2000],
+        }),
+        None,
+    );
+    let mut driver = Driver::new(13);
+    let panel_x = driver.app.panel.origin[0];
+    input::handle_event(&mut driver.app, &resize);
+    assert_eq!(driver.app.config.window_size, [1920.0, 1000.0]);
+    assert_eq!(driver.app.hidpi_factor, 2.0);
+    assert_eq!(driver.app.panel.origin[0], panel_x + 960.0);
+
+    // The 960x640 grid is scaled to the window's height and centered across.
+    let fit = Camera {
+        offset: [210.0, 0.0],
+        zoom: 1.5625,
+    };
+    assert_eq!(driver.app.camera, fit);
+    let config = &driver.app.config;
+    let corner = get_pos_from_coord(&[30, 20], config, Topology::Square);
+    let center = corner.map(|p| p + config.cell_size / 2.0);
+    let pos = driver.app.camera.world_to_screen(center);
+    driver.key(Key::D3).move_to(pos).press(LEFT).release(LEFT);
+    let idx = driver.index(30, 20);
+    assert_eq!(
+        driver.app.universe.grid[idx].consciousness,
+        ConsciousnessState::Operator
+    );
+
+    // A zoomed view keeps its middle, and the reset fits the new window.
+    driver.app.apply(AppAction::Zoom(2.0));
+    let middle = driver.app.camera.screen_to_world([960.0, 500.0]);
+    driver.app.apply(AppAction::Resize([960.0, 500.0], 1.0));
+    let after = driver.app.camera.screen_to_world([480.0, 250.0]);
+    assert!((after[0] - middle[0]).abs() < 1e-9 && (after[1] - middle[1]).abs() < 1e-9);
+    driver.app.apply(AppAction::Resize([0.0, 0.0], 1.0));
+    assert_eq!(driver.app.config.window_size, [960.0, 500.0]);
+    driver.key(Key::D0);
+    assert_eq!(driver.app.camera.zoom, 500.0 / 640.0);
+}
+
+#[test]
+fn ctrl_e_rewires_the_entanglement_and_undoes() {
+    let mut driver = Driver::new(6);
+    // The Select tool leaves the cells alone, unlike the passive Observe brush.
+    driver.key(Key::D6);
+    driver.app.universe.set_entanglement_percentage(0.2);
+    let (before, grid) = (
+        driver.app.universe.entanglement.clone(),
+        driver.app.universe.grid.clone(),
+    );
+    driver
+        .press(Button::Keyboard(Key::LCtrl))
+        .key(Key::E)
+        .release(Button::Keyboard(Key::LCtrl));
+    assert_ne!(driver.app.universe.entanglement, before);
+    assert_eq!(driver.app.universe.grid, grid);
+    assert_eq!(driver.app.universe.interventions.len(), 1);
+
+    driver.press(Button::Keyboard(Key::LCtrl)).key(Key::Z);
+    assert_eq!(driver.app.universe.entanglement, before);
+}
+
+#[test]
+fn tab_switches_between_the_universes_of_a_multiverse() {
+    let mut driver = Driver::new(3);
+    let dims = driver.app.universe.grid_dims.clone();
+    let multiverse = Multiverse::new(vec![
+        Universe::with_seed(dims.clone(), 3, 11),
+        Universe::with_seed(dims, 3, 12),
+    ]);
+    driver.app.attach_multiverse(multiverse);
+    assert_eq!(driver.app.universe.seed, 11);
+    driver.update().update();
+
+    driver.key(Key::Tab);
+    assert_eq!(driver.app.universe.seed, 12);
+    assert_eq!(driver.app.universe.tick_count, 2);
+    driver.update();
+    driver.key(Key::Tab);
+    assert_eq!(driver.app.multiverse.as_ref().unwrap().active, 0);
+    assert_eq!(driver.app.universe.seed, 11);
+    assert_eq!(driver.app.universe.tick_count, 3);
+}
+
+#[test]
+fn brackets_and_ctrl_wheel_size_each_brush_down_to_one_cell() {
+    use existons::app::{MAX_BRUSH_RADIUS, MIN_BRUSH_RADIUS};
+    use existons::existon::Existon;
+    use existons::input::Modifiers;
+    use piston_window::{Event, Input, Motion};
+    let scroll = Event::Input(Input::Move(Motion::MouseScroll([0.0, -1.0])), None);
+    let ctrl = Modifiers {
+        ctrl: true,
+        ..Default::default()
+    };
+    let actions = input::input_actions(&scroll, ctrl, &Keymap::default());
+    assert_eq!(actions, vec![AppAction::ResizeBrush(-1.0)]);
+
+    let mut driver = Driver::new(13);
+    driver.key(Key::D4).key(Key::RightBracket);
+    assert_eq!(driver.app.config.disruption_radius, 62.5);
+    assert_eq!(driver.app.config.observation_radius, 50.0);
+    driver.app.apply(AppAction::ResizeBrush(100.0));
+    assert_eq!(driver.app.config.disruption_radius, MAX_BRUSH_RADIUS);
+    // Tools without a brush ignore it.
+    driver.key(Key::D3).key(Key::LeftBracket);
+    assert_eq!(driver.app.config.brush_radius(ToolMode::Operator), None);
+
+    // The smallest Observe brush observes just the cell under the cursor.
+    driver.key(Key::D1);
+    for _ in 0..30 {
+        driver.key(Key::LeftBracket);
+    }
+    assert_eq!(driver.app.config.observation_radius, MIN_BRUSH_RADIUS);
+    // Cells observed by the larger brush around the origin meanwhile stay so.
+    let observed = |driver: &Driver| {
+        let is_observed = |e: &Existon| e.consciousness == ConsciousnessState::Observed;
+        driver.app.universe.find(is_observed)
+    };
+    let before = observed(&driver);
+    driver.click_cell(6, 6);
+    let after = observed(&driver);
+    let new: Vec<usize> = after.into_iter().filter(|i| !before.contains(i)).collect();
+    assert_eq!(new, vec![driver.index(6, 6)]);
+}
+
+#[test]
+fn clicking_the_minimap_centers_the_view_there_without_painting() {
+    let mut driver = Driver::new(5);
+    assert_eq!(driver.app.minimap(), None);
+    let window = driver.app.config.window_size;
+    driver.app.camera.zoom_about([0.0, 0.0], 4.0);
+    let map = driver.app.minimap().unwrap();
+    let before = driver.app.universe.grid.clone();
+
+    let [x, y, width, height] = map.bounds;
+    let target = [x + width * 0.75, y + height * 0.5];
+    let world = map.world_at(target).unwrap();
+    driver.move_to(target).press(LEFT).release(LEFT);
+
+    let center = driver
+        .app
+        .camera
+        .screen_to_world([window[0] / 2.0, window[1] / 2.0]);
+    assert!((center[0] - world[0]).abs() < 1e-9 && (center[1] - world[1]).abs() < 1e-9);
+    assert_eq!(driver.app.universe.grid, before);
+}
+
+#[test]
+fn clicking_the_toolbar_switches_tools_and_pauses_without_painting() {
+    let mut driver = Driver::new(8);
+    let before = driver.app.universe.grid.clone();
+    driver.key(Key::D3);
+    let window = driver.app.config.window_size;
+    let item = |wanted| {
+        let items = Toolbar::items(window);
+        let (_, [x, y, width, height]) =
+            items.into_iter().find(|&(item, _)| item == wanted).unwrap();
+        [x + width / 2.0, y + height / 2.0]
+    };
+
+    driver.move_to(item(ToolbarItem::Tool(ToolMode::Disrupt)));
+    assert!(driver.app.over_toolbar());
+    driver.press(LEFT).release(LEFT);
+    assert_eq!(driver.app.current_tool, ToolMode::Disrupt);
+
+    driver
+        .move_to(item(ToolbarItem::Pause))
+        .press(LEFT)
+        .release(LEFT);
+    assert!(driver.app.paused);
+    assert_eq!(driver.app.universe.grid, before);
+}
@@ -0,0 +1,79 @@
+//! Tests of the cell inspector.
+
+#[cfg(feature = "desktop")]
+mod common;
+
+use existons::existon::ConsciousnessState;
+use existons::ga_core::{Mod3, Multivector};
+use existons::inspect::Inspection;
+use existons::universe::Universe;
+
+#[test]
+fn an_inspection_spells_out_the_whole_cell() {
+    let mut universe = Universe::with_seed(vec![6, 6], 3, 70);
+    universe.entanglement.clear();
+    let mut state = Multivector::zero(3);
+    state.coefficients[0] = Mod3(1);
+    state.coefficients[7] = Mod3(-1);
+    universe.set_operator_with_state(&[2, 3], state);
+    let id = |universe: &Universe, coord: &[usize]| {
+        universe.grid[universe.get_index_from_coord(coord).unwrap()].id
+    };
+    let (a, b) = (id(&universe, &[1, 1]), id(&universe, &[4, 5]));
+    universe.entangle_pair(a, b);
+    for _ in 0..3 {
+        universe.tick();
+    }
+
+    let operator = Inspection::at(&universe, &[2, 3]).unwrap();
+    assert_eq!(operator.consciousness, ConsciousnessState::Operator);
+    assert_eq!(operator.state.to_string(), "1 - e012");
+    assert_eq!(operator.lines()[2], "State 1 - e012");
+    assert_eq!(operator.lines()[4], "Not entangled");
+
+    let partner = Inspection::of_id(&universe, a).unwrap();
+    assert_eq!(partner.coord, vec![1, 1]);
+    assert_eq!(partner.partners, vec![vec![4, 5]]);
+    assert_eq!(partner.age, 3);
+    assert!(partner.lines()[4].starts_with("Entangled with 1 at strength"));
+    assert!(partner.lines()[4].ends_with("[4, 5]"));
+    assert!(Inspection::at(&universe, &[6, 0]).is_none());
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn the_inspect_tool_follows_the_cursor_until_a_cell_is_pinned() {
+    use common::Driver;
+    use existons::app::ToolMode;
+    use piston_window::{Button, Key, MouseButton};
+    const LEFT: Button = Button::Mouse(MouseButton::Left);
+
+    // The default Observe tool would observe cells as the cursor passes.
+    let mut driver = Driver::new(71);
+    driver.key(Key::D3).move_to_cell(2, 2);
+    assert!(driver.app.inspection().is_none());
+
+    driver.key(Key::D5);
+    assert_eq!(driver.app.current_tool, ToolMode::Inspect);
+    assert_eq!(driver.app.inspection().unwrap().coord, vec![2, 2]);
+    driver.move_to_cell(5, 1);
+    assert_eq!(driver.app.inspection().unwrap().coord, vec![5, 1]);
+
+    // A click pins the cell and edits nothing.
+    let undo_depth = driver.app.history.undo_depth();
+    driver.click_cell(3, 4).move_to_cell(7, 7);
+    let pinned = driver.app.inspection().unwrap();
+    assert_eq!(pinned.coord, vec![3, 4]);
+    assert_eq!(pinned.id, driver.app.universe.grid[driver.index(3, 4)].id);
+    assert!(driver.app.universe.interventions.is_empty());
+    assert_eq!(driver.app.history.undo_depth(), undo_depth);
+
+    // The pin holds as time passes, until the cell is clicked again.
+    driver.update();
+    assert_eq!(driver.app.inspection().unwrap().coord, vec![3, 4]);
+    driver.move_to_cell(3, 4).press(LEFT).release(LEFT);
+    driver.move_to_cell(6, 6);
+    assert_eq!(driver.app.inspection().unwrap().coord, vec![6, 6]);
+    driver.key(Key::D3);
+    assert!(driver.app.inspection().is_none());
+}
@@ -0,0 +1,130 @@
+//! Tests of the keybindings file.
+#![cfg(feature = "desktop")]
+
+use existons::app::{AppState, Config, ToolMode};
+use existons::input::{AppAction, Modifiers, key_action};
+use existons::keymap::{Chord, Keymap, KeymapError};
+use existons::panel::Setting;
+use piston_window::Key;
+use std::path::Path;
+
+const CTRL: Modifiers = Modifiers {
+    ctrl: true,
+    shift: false,
+    alt: false,
+};
+
+#[test]
+fn the_shipped_file_lists_the_defaults() {
+    let text = std::fs::read_to_string("keybindings.toml").unwrap();
+    let keymap = Keymap::parse(&text).unwrap();
+    assert_eq!(keymap, Keymap::default());
+    assert_eq!(Keymap::parse(&keymap.to_toml()).unwrap(), keymap);
+    assert_eq!(
+        Keymap::load(Path::new("no-such-keybindings.toml")).unwrap(),
+        keymap
+    );
+
+    let none = Modifiers::default();
+    assert_eq!(
+        key_action(Key::D1, none),
+        Some(AppAction::SelectTool(ToolMode::Observe))
+    );
+    assert_eq!(key_action(Key::Z, CTRL), Some(AppAction::Undo));
+    assert_eq!(key_action(Key::Z, none), Some(AppAction::ToggleZen));
+    assert_eq!(
+        key_action(Key::Equals, none),
+        Some(AppAction::ChangeSpeed(true))
+    );
+}
+
+#[test]
+fn bindings_replace_the_defaults_and_steal_their_keys() {
+    let keymap = Keymap::parse(
+        "# A Dvorak-friendly layout\n\
+         [keys]\n\
+         tool_observe = \"a\"   # was 1\n\
+         pause = [\"Return\", 'ctrl+space']\n\
+         toggle_zen = []\n\
+         reset_universe = \"1\"\n",
+    )
+    .unwrap();
+    let none = Modifiers::default();
+    assert_eq!(
+        keymap.action(Key::A, none),
+        Some(AppAction::SelectTool(ToolMode::Observe))
+    );
+    assert_eq!(keymap.action(Key::D1, none), Some(AppAction::ResetUniverse));
+    assert_eq!(keymap.action(Key::R, none), None);
+    assert_eq!(keymap.action(Key::Space, none), None);
+    assert_eq!(
+        keymap.action(Key::Space, CTRL),
+        Some(AppAction::TogglePause)
+    );
+    assert_eq!(
+        keymap.action(Key::Return, none),
+        Some(AppAction::TogglePause)
+    );
+    assert_eq!(keymap.action(Key::Z, none), None);
+    // Untouched actions keep their keys.
+    assert_eq!(keymap.action(Key::B, none), Some(AppAction::CycleBoundary));
+    assert_eq!(
+        keymap.chords(AppAction::TogglePause),
+        [
+            Chord::parse("Return").unwrap(),
+            Chord::parse("Ctrl+Space").unwrap()
+        ]
+    );
+    assert_eq!(Chord::parse("ctrl+f12").unwrap().to_string(), "Ctrl+F12");
+    assert_eq!(
+        Chord::parse("alt + ctrl+5").unwrap().to_string(),
+        "Ctrl+Alt+5"
+    );
+}
+
+#[test]
+fn plugins_toggle_with_alt_and_a_digit() {
+    let alt = Modifiers {
+        alt: true,
+        ..Modifiers::default()
+    };
+    assert_eq!(key_action(Key::D0, alt), Some(AppAction::TogglePluginPanel));
+    assert_eq!(key_action(Key::D3, alt), Some(AppAction::TogglePlugin(2)));
+    assert_eq!(
+        key_action(Key::D3, Modifiers::default()),
+        Some(AppAction::SelectTool(ToolMode::Operator))
+    );
+}
+
+#[test]
+fn mistakes_name_their_line() {
+    let error = |text: &str| Keymap::parse(text).unwrap_err();
+    assert!(matches!(
+        error("[keys]\npause = \"Space\"\nwarp = \"W\""),
+        KeymapError::UnknownAction { line: 3, name } if name == "warp"
+    ));
+    assert!(matches!(
+        error("pause = \"Hyper+Space\""),
+        KeymapError::UnknownKey { line: 1, key } if key == "Hyper+Space"
+    ));
+    assert!(matches!(
+        error("pause = Space"),
+        KeymapError::Syntax { line: 1 }
+    ));
+    assert!(matches!(error("pause\n"), KeymapError::Syntax { line: 1 }));
+}
+
+#[test]
+fn speed_keys_double_and_halve_the_tick_rate() {
+    let mut app = AppState::new(Config::new());
+    app.autosave = None;
+    app.apply(AppAction::ChangeSpeed(true));
+    assert_eq!(Setting::TickRate.get(&app), 120.0);
+    app.apply(AppAction::ChangeSpeed(true));
+    app.apply(AppAction::ChangeSpeed(true));
+    assert_eq!(Setting::TickRate.get(&app), 240.0);
+    for _ in 0..10 {
+        app.apply(AppAction::ChangeSpeed(false));
+    }
+    assert_eq!(Setting::TickRate.get(&app), 1.0);
+}
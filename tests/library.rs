@@ -0,0 +1,108 @@
+//! Tests of the pattern library and its browser.
+
+#[cfg(feature = "desktop")]
+use existons::existon::ConsciousnessState;
+use existons::library::PatternLibrary;
+use existons::universe::Universe;
+
+#[cfg(feature = "desktop")]
+#[test]
+fn the_starter_patterns_stamp_onto_a_default_grid() {
+    use existons::app::Config;
+
+    let library = PatternLibrary::bundled();
+    let names: Vec<&str> = library.entries().iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(
+        names,
+        [
+            "entangled-ladder",
+            "entangled-ring",
+            "operator-lattice",
+            "seeded-cluster"
+        ]
+    );
+    let config = Config::new();
+    for entry in library.entries() {
+        let mut universe = Universe::with_seed(config.grid_dims.clone(), config.ga_dims, 40);
+        universe.set_entanglement_percentage(0.0);
+        universe.stamp_pattern(&entry.pattern, &[10, 10]);
+        assert_eq!(universe.interventions.len(), 1, "{}", entry.name);
+        assert_eq!(
+            universe.entanglement.len(),
+            entry.pattern.entanglements.len(),
+            "{}",
+            entry.name
+        );
+    }
+}
+
+#[test]
+fn a_folder_of_pattern_files_joins_the_library() {
+    let dir = std::env::temp_dir().join(format!("existons-library-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let pattern = Universe::with_seed(vec![8, 8], 3, 41).extract_pattern(&[1..4, 2..4]);
+    pattern.save(&dir.join("block.txt")).unwrap();
+    std::fs::write(dir.join("notes.txt"), "not a pattern").unwrap();
+    std::fs::write(dir.join("block.png"), "ignored").unwrap();
+
+    let mut library = PatternLibrary::bundled();
+    let added = library.load_folder(&dir);
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(added.unwrap(), 1);
+    let last = library.entries().last().unwrap();
+    assert_eq!(last.name, "block");
+    assert_eq!(last.pattern, pattern);
+    assert_eq!(library.describe().last().unwrap(), "    block (3x2)");
+
+    // Choosing wraps around both ways.
+    assert_eq!(library.selected().unwrap().name, "entangled-ladder");
+    library.step(false);
+    assert_eq!(library.selected().unwrap().name, "block");
+    library.step(true);
+    library.step(true);
+    assert_eq!(library.selected().unwrap().name, "entangled-ring");
+    assert_eq!(library.describe()[2], "  > entangled-ring (11x11)");
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn the_browser_chooses_with_the_arrows_and_stamps_on_click() {
+    use existons::app::{AppState, Config};
+    use existons::input::{AppAction, Modifiers, key_action};
+    use piston_window::Key;
+
+    let mut app = AppState::new(Config::new());
+    app.autosave = None;
+    let toggle = key_action(Key::P, Modifiers::default()).unwrap();
+    assert_eq!(toggle, AppAction::ToggleLibrary);
+    app.apply(toggle);
+    assert!(app.library.open);
+    for _ in 0..2 {
+        app.apply(key_action(Key::Down, Modifiers::default()).unwrap());
+    }
+    let chosen = app.library.selected().unwrap().clone();
+    assert_eq!(chosen.name, "operator-lattice");
+
+    let half = app.config.cell_size / 2.0;
+    let pos = |cell: f64| cell * app.config.cell_size + half;
+    app.apply(AppAction::MoveCursor([pos(20.0), pos(30.0)]));
+    let undo_depth = app.history.undo_depth();
+    app.apply(AppAction::PressPrimary);
+    app.apply(AppAction::ReleasePrimary);
+    assert_eq!(app.history.undo_depth(), undo_depth + 1);
+    assert_eq!(
+        app.universe.extract_pattern(&[20..29, 30..39]).cells[0].consciousness,
+        ConsciousnessState::Operator
+    );
+    let operators = app
+        .universe
+        .grid
+        .iter()
+        .filter(|e| e.consciousness == ConsciousnessState::Operator)
+        .count();
+    assert_eq!(operators, chosen.pattern.cells.len());
+
+    // Closed, the arrows and clicks go back to the selection and the tools.
+    app.apply(toggle);
+    assert!(!app.library.open);
+}
@@ -0,0 +1,26 @@
+//! Tests of the minimap's layout.
+#![cfg(feature = "desktop")]
+
+use existons::app::Camera;
+use existons::minimap::Minimap;
+
+const EXTENT: [f64; 2] = [800.0, 400.0];
+const WINDOW: [f64; 2] = [800.0, 600.0];
+
+#[test]
+fn the_minimap_only_shows_while_part_of_the_grid_is_off_screen() {
+    let fitted = Camera::fit(EXTENT, WINDOW);
+    assert_eq!(Minimap::of(EXTENT, &fitted, WINDOW, 550.0), None);
+
+    let zoomed = Camera {
+        offset: [-400.0, -200.0],
+        zoom: 2.0,
+    };
+    let map = Minimap::of(EXTENT, &zoomed, WINDOW, 550.0).unwrap();
+    assert_eq!(map.bounds, [620.0, 470.0, 160.0, 80.0]);
+    assert_eq!(map.scale, 0.2);
+    // The view covers grid-space [200, 400) x [100, 400).
+    assert_eq!(map.viewport(&zoomed, WINDOW), [660.0, 490.0, 80.0, 60.0]);
+    assert_eq!(map.world_at([700.0, 510.0]), Some([400.0, 200.0]));
+    assert_eq!(map.world_at([10.0, 10.0]), None);
+}
@@ -0,0 +1,281 @@
+//! Tests of the `Multivector` constructors and algebra.
+
+use existons::ga_core::verify::{self, Axiom};
+use existons::ga_core::{Mod3, Multivector};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+#[test]
+fn random_with_respects_max_grade_and_density() {
+    let mut rng = StdRng::seed_from_u64(9);
+    let mut nonzero = 0;
+    let mut eligible = 0;
+    for _ in 0..200 {
+        let mv = Multivector::random_with(5, 0.7, 2, &mut rng);
+        for (blade, c) in mv.coefficients.iter().enumerate() {
+            if blade.count_ones() > 2 {
+                assert_eq!(c.0, 0, "blade {blade:#b} is above the max grade");
+            } else {
+                eligible += 1;
+                nonzero += (c.0 != 0) as usize;
+            }
+        }
+    }
+    let density = nonzero as f64 / eligible as f64;
+    assert!((0.25..0.35).contains(&density), "density was {density}");
+}
+
+#[test]
+fn random_with_is_deterministic_for_a_seed() {
+    let a = Multivector::random_with(4, 0.5, 4, &mut StdRng::seed_from_u64(3));
+    let b = Multivector::random_with(4, 0.5, 4, &mut StdRng::seed_from_u64(3));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn reverse_flips_bivectors_and_trivectors() {
+    let mut mv = Multivector::zero(3);
+    for c in &mut mv.coefficients {
+        c.0 = 1;
+    }
+    let signs: Vec<i8> = mv.reverse().coefficients.iter().map(|c| c.0).collect();
+    // 1, e0, e1, e01, e2, e02, e12, e012
+    assert_eq!(signs, [1, 1, 1, -1, 1, -1, -1, -1]);
+}
+
+#[test]
+fn subtraction_undoes_addition() {
+    let mut rng = StdRng::seed_from_u64(4);
+    for _ in 0..50 {
+        let a = Multivector::random(3, &mut rng);
+        let b = Multivector::random(3, &mut rng);
+        assert_eq!(&(&a + &b) - &b, a);
+        assert_eq!(&a - &a, Multivector::zero(3));
+    }
+}
+
+#[test]
+fn scalars_scale_and_negate_every_coefficient() {
+    let mut rng = StdRng::seed_from_u64(5);
+    for _ in 0..50 {
+        let a = Multivector::random(3, &mut rng);
+        let b = Multivector::random(3, &mut rng);
+        let mut minus_one = Multivector::zero(3);
+        minus_one.coefficients[0] = Mod3::MINUS_ONE;
+        assert_eq!(&a * Mod3::MINUS_ONE, &a * &minus_one);
+        assert_eq!(-&a, &a * Mod3::MINUS_ONE);
+        assert_eq!(&a + &(-&a), Multivector::zero(3));
+        assert_eq!(&a - &b, &a + &(-&b));
+        assert_eq!(Mod3::ONE * &a, a);
+        assert_eq!(&a * Mod3::ZERO, Multivector::zero(3));
+    }
+}
+
+#[test]
+fn multivectors_print_in_blade_notation() {
+    let mut mv = Multivector::zero(3);
+    assert_eq!(mv.to_string(), "0");
+    mv.coefficients[0] = Mod3(1);
+    mv.coefficients[1] = Mod3(-1);
+    mv.coefficients[5] = Mod3(1);
+    assert_eq!(mv.to_string(), "1 - e0 + e02");
+    mv.coefficients[0] = Mod3(0);
+    assert_eq!(mv.to_string(), "-e0 + e02");
+}
+
+#[test]
+fn the_geometric_product_obeys_every_axiom() {
+    for dims in 1..=5 {
+        for seed in 0..3 {
+            assert_eq!(verify::check_all(dims, 30, seed), vec![], "Cl({},0)", dims);
+        }
+    }
+}
+
+#[test]
+fn the_axioms_catch_a_product_without_signs() {
+    // Multiplies blades by XOR alone, as if every basis vector commuted.
+    let unsigned = |a: &Multivector, b: &Multivector| {
+        let mut result = Multivector::zero(a.p);
+        for (i, &x) in a.coefficients.iter().enumerate() {
+            for (j, &y) in b.coefficients.iter().enumerate() {
+                result.coefficients[i ^ j] = result.coefficients[i ^ j] + x * y;
+            }
+        }
+        result
+    };
+    let failure = Axiom::Anticommutativity
+        .check_product(2, 0, 0, unsigned)
+        .unwrap_err();
+    assert_eq!(failure.operands.len(), 2);
+    assert_eq!(
+        failure.to_string(),
+        "anticommutativity fails for (e0), (e1): e01 != -e01"
+    );
+    // Associativity and the squares still hold without signs.
+    assert_eq!(Axiom::BasisSquares.check_product(3, 0, 0, unsigned), Ok(()));
+    assert_eq!(
+        Axiom::Associativity.check_product(3, 20, 0, unsigned),
+        Ok(())
+    );
+}
+
+#[test]
+fn mod3_arithmetic_wraps_and_optionally_clamps() {
+    let [zero, one, minus_one] = [Mod3::ZERO, Mod3::ONE, Mod3::MINUS_ONE];
+    assert_eq!(one + one, minus_one);
+    assert_eq!(minus_one - one, one);
+    assert_eq!(zero - one, minus_one);
+    assert_eq!(-minus_one, one);
+    assert_eq!(one.saturating_add(one), one);
+    assert_eq!(minus_one.saturating_sub(one), minus_one);
+    assert_eq!(one.saturating_sub(one), zero);
+    for a in [zero, one, minus_one] {
+        for b in [zero, one, minus_one] {
+            assert_eq!(a - b + b, a);
+        }
+    }
+
+    assert_eq!([one, one, one].into_iter().sum::<Mod3>(), zero);
+    assert_eq!([one, one].iter().sum::<Mod3>(), minus_one);
+    assert_eq!(std::iter::empty::<Mod3>().sum::<Mod3>(), zero);
+    assert_eq!([minus_one, minus_one].iter().product::<Mod3>(), one);
+    assert_eq!(std::iter::empty::<Mod3>().product::<Mod3>(), one);
+}
+
+#[test]
+fn mod3_converts_from_and_into_integers() {
+    assert_eq!(Mod3::from(2), Mod3::MINUS_ONE);
+    assert_eq!(Mod3::from(-2), Mod3::ONE);
+    assert_eq!(Mod3::from(3), Mod3::ZERO);
+    assert_eq!(Mod3::new(2), Mod3::ONE);
+    assert_eq!(i8::from(Mod3::MINUS_ONE), -1);
+    assert_eq!(Mod3::try_from(1i64), Ok(Mod3::ONE));
+    let error = Mod3::try_from(5i64).unwrap_err();
+    assert_eq!(error.to_string(), "5 is not -1, 0 or 1");
+}
+
+/// A matrix over `Mod3` from rows of integers.
+fn matrix(rows: &[&[i8]]) -> Vec<Vec<Mod3>> {
+    rows.iter()
+        .map(|row| row.iter().map(|&x| Mod3::from(x)).collect())
+        .collect()
+}
+
+/// The basis blade with bitmask `index`.
+fn blade(p: usize, index: usize) -> Multivector {
+    Multivector::zero(p).with_blade(index, 1)
+}
+
+#[test]
+fn basis_maps_push_through_every_blade() {
+    let mut rng = StdRng::seed_from_u64(6);
+    let identity = matrix(&[&[1, 0, 0], &[0, 1, 0], &[0, 0, 1]]);
+    let a = Multivector::random(3, &mut rng);
+    assert_eq!(a.map_basis(&identity), a);
+
+    // Swapping e0 and e1 swaps the vectors and flips their bivector.
+    let swap = matrix(&[&[0, 1, 0], &[1, 0, 0], &[0, 0, 1]]);
+    assert_eq!(blade(3, 0b001).map_basis(&swap), blade(3, 0b010));
+    assert_eq!(blade(3, 0b011).map_basis(&swap), -&blade(3, 0b011));
+    assert_eq!(blade(3, 0).map_basis(&swap), blade(3, 0));
+
+    // A shear sends e1 to e0 + e1 and keeps the volume.
+    let shear = matrix(&[&[1, 1, 0], &[0, 1, 0], &[0, 0, 1]]);
+    assert_eq!(
+        blade(3, 0b010).map_basis(&shear),
+        &blade(3, 0b001) + &blade(3, 0b010)
+    );
+    assert_eq!(blade(3, 0b111).map_basis(&shear), blade(3, 0b111));
+    // Doubling e0 (2 = -1 in Z(3)) scales the pseudoscalar by the determinant.
+    let stretch = matrix(&[&[2, 0, 0], &[0, 1, 0], &[0, 0, 1]]);
+    assert_eq!(blade(3, 0b111).map_basis(&stretch), -&blade(3, 0b111));
+
+    // An outermorphism is linear and preserves wedges.
+    let map = matrix(&[&[1, -1, 0], &[0, 1, 1], &[1, 0, 1]]);
+    for _ in 0..30 {
+        let (a, b) = (
+            Multivector::random(3, &mut rng),
+            Multivector::random(3, &mut rng),
+        );
+        assert_eq!(
+            (&a + &b).map_basis(&map),
+            &a.map_basis(&map) + &b.map_basis(&map)
+        );
+        assert_eq!(
+            a.wedge(&b).map_basis(&map),
+            a.map_basis(&map).wedge(&b.map_basis(&map))
+        );
+    }
+}
+
+#[test]
+fn the_wedge_keeps_only_disjoint_blades() {
+    let (e0, e1) = (blade(2, 0b01), blade(2, 0b10));
+    assert_eq!(e0.wedge(&e1), blade(2, 0b11));
+    assert_eq!(e1.wedge(&e0), -&blade(2, 0b11));
+    assert_eq!(e0.wedge(&e0), Multivector::zero(2));
+    assert_eq!(blade(2, 0).wedge(&e1), e1);
+}
+
+#[test]
+fn permuting_the_basis_is_a_cheap_outermorphism() {
+    let mut rng = StdRng::seed_from_u64(7);
+    let permutations = [
+        [0, 1, 2],
+        [0, 2, 1],
+        [1, 0, 2],
+        [1, 2, 0],
+        [2, 0, 1],
+        [2, 1, 0],
+    ];
+    for permutation in permutations {
+        let mut map = vec![vec![Mod3::ZERO; 3]; 3];
+        for (i, &j) in permutation.iter().enumerate() {
+            map[j][i] = Mod3::ONE;
+        }
+        for _ in 0..10 {
+            let (a, b) = (
+                Multivector::random(3, &mut rng),
+                Multivector::random(3, &mut rng),
+            );
+            assert_eq!(a.permute_basis(&permutation), a.map_basis(&map));
+            assert_eq!(
+                (&a * &b).permute_basis(&permutation),
+                &a.permute_basis(&permutation) * &b.permute_basis(&permutation)
+            );
+        }
+    }
+    assert_eq!(blade(3, 0b011).swap_basis(0, 1), -&blade(3, 0b011));
+    assert_eq!(blade(3, 0b001).swap_basis(0, 2), blade(3, 0b100));
+    assert_eq!(blade(3, 0b101).swap_basis(0, 1), blade(3, 0b110));
+}
+
+#[test]
+#[should_panic(expected = "is not a permutation")]
+fn a_repeated_basis_vector_is_not_a_permutation() {
+    Multivector::zero(3).permute_basis(&[0, 0, 1]);
+}
+
+#[test]
+fn states_build_blade_by_blade() {
+    let mv = Multivector::from_blades(3, &[(0b000, 1), (0b011, -1), (0b100, 2)]);
+    assert_eq!(mv.to_string(), "1 - e01 - e2");
+    assert_eq!(
+        Multivector::from_blades(2, &[(0b01, 1), (0b01, 0)]),
+        Multivector::zero(2)
+    );
+    assert_eq!(
+        Multivector::zero(3).with_blade(0b101, Mod3::MINUS_ONE),
+        Multivector::from_blades(3, &[(0b101, -1)])
+    );
+    assert_eq!(Multivector::scalar(2, -1).to_string(), "-1");
+    assert_eq!(Multivector::basis_vector(3, 2).to_string(), "e2");
+    assert_eq!(Multivector::bivector(3, 0, 2).to_string(), "e02");
+    assert_eq!(Multivector::bivector(3, 2, 0).to_string(), "-e02");
+    assert_eq!(
+        Multivector::bivector(3, 1, 0),
+        &Multivector::basis_vector(3, 1) * &Multivector::basis_vector(3, 0)
+    );
+    assert_eq!(Multivector::pseudoscalar(3).to_string(), "e012");
+}
@@ -0,0 +1,119 @@
+//! Tests of universes ticked together and bridged.
+
+use existons::entanglement::PairOperator;
+use existons::existon::{ConsciousnessState, Existon};
+use existons::multiverse::{CellRef, Multiverse};
+use existons::rule::{RuleCtx, UpdateRule};
+use existons::share::Scenario;
+use existons::universe::Universe;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+fn cell(universe: usize, id: u64) -> CellRef {
+    CellRef { universe, id }
+}
+
+#[test]
+fn bridges_join_distinct_free_cells_of_different_universes() {
+    let mut multiverse = Multiverse::new(vec![
+        Universe::with_seed(vec![4, 4], 2, 1),
+        Universe::with_seed(vec![4, 4], 2, 2),
+    ]);
+    assert!(!multiverse.bridge(cell(0, 1), cell(0, 2)));
+    assert!(!multiverse.bridge(cell(0, 1), cell(1, 99)));
+    assert!(!multiverse.bridge(cell(0, 1), cell(2, 1)));
+    assert!(multiverse.bridge(cell(0, 1), cell(1, 1)));
+    assert!(!multiverse.bridge(cell(1, 1), cell(0, 3)));
+    assert_eq!(
+        multiverse.bridge_of(cell(1, 1)),
+        multiverse.bridges().first()
+    );
+
+    multiverse.unbridge(cell(1, 1));
+    assert!(multiverse.bridges().is_empty());
+    let made = multiverse.bridge_random(0, 1, 20, &mut StdRng::seed_from_u64(3));
+    assert_eq!(made, 16);
+    assert_eq!(
+        multiverse.bridge_random(1, 0, 4, &mut StdRng::seed_from_u64(4)),
+        0
+    );
+}
+
+#[test]
+fn bridged_collapses_land_across_universes_and_each_universe_replays() {
+    let mut multiverse = Multiverse::new(vec![
+        Universe::with_seed(vec![8, 8], 2, 5),
+        Universe::with_seed(vec![8, 8], 2, 6),
+    ]);
+    for universe in &mut multiverse.universes {
+        universe.observation_rate = 0.1;
+    }
+    multiverse.bridge_random(0, 1, 32, &mut StdRng::seed_from_u64(7));
+    let mut fired = Vec::new();
+    for _ in 0..20 {
+        fired.extend(multiverse.tick().bridges);
+    }
+    assert!(!fired.is_empty());
+    for (observed, collapsed) in &fired {
+        assert_ne!(observed.universe, collapsed.universe);
+        assert!(multiverse.bridge_of(*observed).is_some());
+    }
+
+    for universe in &multiverse.universes {
+        let rebuilt = Scenario::decode(&Scenario::capture(universe).encode())
+            .unwrap()
+            .build();
+        assert_eq!(rebuilt.interventions, universe.interventions);
+        assert_eq!(rebuilt.grid, universe.grid);
+    }
+}
+
+/// Observes exactly the listed cells and leaves everything else alone.
+#[derive(Debug)]
+struct ObserveOnly(Vec<u64>);
+
+impl UpdateRule for ObserveOnly {
+    fn apply(&self, cell: &Existon, _: &[&Existon], ctx: &RuleCtx) -> Existon {
+        let mut next = cell.clone();
+        if self.0.contains(&cell.id) {
+            next.observe(ctx.collapse_policy);
+        }
+        next
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn a_bridge_fires_one_way_into_a_potential_cell() {
+    let mut multiverse = Multiverse::new(vec![
+        Universe::with_seed(vec![4, 4], 2, 8).with_rule(ObserveOnly(vec![5, 9, 11])),
+        Universe::with_seed(vec![4, 4], 2, 9).with_rule(ObserveOnly(vec![8, 10])),
+    ]);
+    for universe in &mut multiverse.universes {
+        universe.entanglement.clear();
+        for existon in &mut universe.grid {
+            existon.consciousness = ConsciousnessState::Potential;
+        }
+    }
+    multiverse.universes[1].pair_operator = PairOperator::Swap;
+    multiverse.universes[1].grid[12].consciousness = ConsciousnessState::Observed;
+    multiverse.bridge(cell(0, 5), cell(1, 6));
+    multiverse.bridge(cell(0, 7), cell(1, 8));
+    // Both ends observed together, and a partner that is already collapsed.
+    multiverse.bridge(cell(0, 9), cell(1, 10));
+    multiverse.bridge(cell(0, 11), cell(1, 12));
+    let report = multiverse.tick();
+    assert_eq!(
+        report.bridges,
+        vec![(cell(0, 5), cell(1, 6)), (cell(1, 8), cell(0, 7))]
+    );
+    let [first, second] = &multiverse.universes[..] else {
+        unreachable!()
+    };
+    assert_eq!(second.grid[6].state, first.grid[5].state);
+    assert_eq!(second.grid[6].consciousness, ConsciousnessState::Observed);
+    assert_eq!(first.grid[7].consciousness, ConsciousnessState::Observed);
+}
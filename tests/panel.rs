@@ -0,0 +1,95 @@
+//! Tests of the control panel, driven through synthetic input.
+#![cfg(feature = "desktop")]
+
+mod common;
+
+use common::Driver;
+use existons::existon::ConsciousnessState;
+use existons::input::AppAction;
+use existons::panel::{Control, PanelButton, Setting};
+use existons::universe::Intervention;
+use piston_window::{Button, Key, MouseButton};
+
+const LEFT: Button = Button::Mouse(MouseButton::Left);
+
+/// The screen rectangle of a control on the driver's panel.
+fn rect_of(driver: &Driver, control: Control) -> [f64; 4] {
+    let controls = driver.app.panel.controls();
+    controls.into_iter().find(|&(c, _)| c == control).unwrap().1
+}
+
+/// A point `fraction` of the way along a slider's track.
+fn along(driver: &Driver, setting: Setting, fraction: f64) -> [f64; 2] {
+    let [x, y, width, height] = rect_of(driver, Control::Slider(setting));
+    [x + width * fraction, y + height / 2.0]
+}
+
+#[test]
+fn sliders_tune_the_run_only_while_the_panel_is_open() {
+    // The Operator tool would place an operator wherever a click lands.
+    let mut driver = Driver::new(30);
+    driver.key(Key::D3);
+    let pos = along(&driver, Setting::DecayRate, 0.25);
+    let cell_size = driver.app.config.cell_size;
+    let [x, y] = pos.map(|p| (p / cell_size) as usize);
+    driver.click_cell(x, y);
+    assert_eq!(driver.app.universe.decay_rate, 0.0);
+    assert_eq!(driver.app.universe.interventions.len(), 1);
+
+    driver.key(Key::K);
+    assert!(driver.app.panel.open);
+    driver.move_to(pos).press(LEFT);
+    assert!((driver.app.universe.decay_rate - 0.25).abs() < 1e-9);
+    // Dragging follows the cursor, even past the end of the track.
+    let end = along(&driver, Setting::DecayRate, 1.5);
+    driver.move_to(end).release(LEFT);
+    assert_eq!(driver.app.universe.decay_rate, 1.0);
+    assert_eq!(driver.app.panel.dragging, None);
+
+    let tick_rate = along(&driver, Setting::TickRate, 0.5);
+    driver.move_to(tick_rate).press(LEFT).release(LEFT);
+    assert_eq!(driver.app.timestep.as_ref().unwrap().dt, 1.0 / 120.0);
+    let shown = Setting::TickRate.format(Setting::TickRate.get(&driver.app));
+    assert_eq!(shown, "120/s");
+    let action = AppAction::SetSetting(Setting::TickRate, 0.2);
+    driver.app.apply(action);
+    assert!(driver.app.timestep.is_none());
+    assert_eq!(Setting::TickRate.format(0.0), "per frame");
+
+    let radius = along(&driver, Setting::ObservationRadius, 0.1);
+    driver.move_to(radius).press(LEFT).release(LEFT);
+    assert!((driver.app.config.observation_radius - 20.0).abs() < 1e-9);
+
+    // The clicks went to the panel, not to the cells under it.
+    assert_eq!(driver.app.universe.interventions.len(), 1);
+    let [x, y] = radius.map(|p| (p / cell_size) as usize);
+    let under = &driver.app.universe.grid[driver.index(x, y)];
+    assert_eq!(under.consciousness, ConsciousnessState::Potential);
+}
+
+#[test]
+fn the_entanglement_slider_rewires_when_let_go_and_buttons_act() {
+    let mut driver = Driver::new(31);
+    driver.key(Key::D3).key(Key::K);
+    driver.move_to(along(&driver, Setting::EntanglementPercentage, 0.2));
+    driver.press(LEFT);
+    driver.move_to(along(&driver, Setting::EntanglementPercentage, 0.4));
+    assert!(driver.app.universe.interventions.is_empty());
+    driver.release(LEFT);
+    assert!((driver.app.panel.entanglement - 0.4).abs() < 1e-9);
+    let logged = &driver.app.universe.interventions;
+    assert_eq!(logged.len(), 1);
+    assert!(matches!(logged[0].intervention, Intervention::Rewire(_)));
+    assert!(!driver.app.universe.entanglement.is_empty());
+
+    let [x, y, width, height] = rect_of(&driver, Control::Button(PanelButton::Reset));
+    driver.update();
+    driver
+        .move_to([x + width / 2.0, y + height / 2.0])
+        .press(LEFT)
+        .release(LEFT);
+    assert_eq!(driver.app.universe.tick_count, 0);
+    assert!(driver.app.universe.interventions.is_empty());
+    // The chosen percentage outlives the reset, for the next rewire.
+    assert!((driver.app.panel.entanglement - 0.4).abs() < 1e-9);
+}
@@ -0,0 +1,231 @@
+//! Tests of pattern extraction, stamping and the pattern file format.
+
+#[cfg(feature = "desktop")]
+mod common;
+
+use existons::existon::ConsciousnessState;
+#[cfg(feature = "desktop")]
+use existons::ga_core::Multivector;
+use existons::pattern::Pattern;
+use existons::share::Scenario;
+use existons::universe::{BoundaryCondition, Universe};
+
+/// A universe with an operator, an observed cell and a pair entangled inside [2..6, 2..5].
+fn decorated(seed: u64) -> Universe {
+    let mut universe = Universe::with_seed(vec![16, 12], 3, seed);
+    universe.set_entanglement_percentage(0.0);
+    universe.set_operator(&[3, 3]);
+    universe.observe_cell(universe.get_index_from_coord(&[4, 2]).unwrap());
+    let (a, b) = (
+        universe.get_index_from_coord(&[2, 2]).unwrap(),
+        universe.get_index_from_coord(&[5, 4]).unwrap(),
+    );
+    universe.entangle_pair(a as u64, b as u64);
+    // Only one partner is inside the box, so this pair is left out.
+    universe.entangle_pair(
+        universe.get_index_from_coord(&[2, 4]).unwrap() as u64,
+        universe.get_index_from_coord(&[10, 10]).unwrap() as u64,
+    );
+    universe
+}
+
+#[test]
+fn extract_keeps_cells_and_internal_entanglements() {
+    let universe = decorated(3);
+    let pattern = universe.extract_pattern(&[2..6, 2..5]);
+    assert_eq!(pattern.size, vec![4, 3]);
+    assert_eq!(pattern.cells.len(), 12);
+    assert_eq!(pattern.cells[0].offset, vec![0, 0]);
+    assert_eq!(pattern.cells[5].offset, vec![1, 1]);
+    assert_eq!(pattern.cells[5].consciousness, ConsciousnessState::Operator);
+    assert_eq!(pattern.entanglements, vec![vec![0, 11]]);
+}
+
+#[test]
+fn patterns_round_trip_through_codes_and_files() {
+    let pattern = decorated(4).extract_pattern(&[1..7, 0..6]);
+    assert_eq!(Pattern::decode(&pattern.encode()).unwrap(), pattern);
+
+    let path = std::env::temp_dir().join(format!("existons-pattern-{}.txt", std::process::id()));
+    pattern.save(&path).unwrap();
+    let loaded = Pattern::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(loaded.unwrap(), pattern);
+
+    assert!(Pattern::decode("EXS1-AAAA").is_err());
+}
+
+#[test]
+fn stamping_reproduces_the_pattern_elsewhere() {
+    let source = decorated(5);
+    let pattern = source.extract_pattern(&[2..6, 2..5]);
+    let mut target = Universe::with_seed(vec![16, 12], 3, 6);
+    target.set_entanglement_percentage(0.0);
+    target.stamp_pattern(&pattern, &[9, 6]);
+
+    assert_eq!(target.extract_pattern(&[9..13, 6..9]), pattern);
+    let (a, b) = (
+        target.get_index_from_coord(&[9, 6]).unwrap() as u64,
+        target.get_index_from_coord(&[12, 8]).unwrap() as u64,
+    );
+    assert_eq!(target.entanglement.partners(a).collect::<Vec<_>>(), vec![b]);
+    assert_eq!(target.entanglement.len(), 1);
+}
+
+#[test]
+fn stamps_follow_the_boundary_and_replay_from_share_codes() {
+    let pattern = decorated(7).extract_pattern(&[2..6, 2..5]);
+    let mut universe =
+        Universe::with_seed(vec![16, 12], 3, 8).with_boundary(BoundaryCondition::Fixed);
+    universe.tick();
+    // Only the first column fits on a fixed grid.
+    universe.stamp_pattern(&pattern, &[15, 0]);
+    let corner = universe.get_index_from_coord(&[15, 0]).unwrap();
+    assert_eq!(universe.grid[corner].state, pattern.cells[0].state);
+    universe.tick();
+
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.interventions, universe.interventions);
+    assert_eq!(rebuilt.grid, universe.grid);
+}
+
+#[test]
+fn cutting_and_moving_take_the_entanglements_along() {
+    let mut universe = decorated(10);
+    let pattern = universe.extract_pattern(&[2..6, 2..5]);
+    let cut = universe.cut_pattern(&[2..6, 2..5]);
+    assert_eq!(cut, pattern);
+    let hole = universe.extract_pattern(&[2..6, 2..5]);
+    assert!(
+        hole.cells
+            .iter()
+            .all(|cell| cell.consciousness == ConsciousnessState::Potential)
+    );
+    // The pair reaching outside the box was broken too.
+    assert!(universe.entanglement.is_empty());
+
+    // Moves may overlap the block's old place.
+    let mut universe = decorated(11);
+    let pattern = universe.extract_pattern(&[2..6, 2..5]);
+    let moved = universe.move_region(&[2..6, 2..5], &[1, -2]).unwrap();
+    assert_eq!(moved, vec![3..7, 0..3]);
+    assert_eq!(universe.extract_pattern(&moved), pattern);
+    let (a, b) = (
+        universe.get_index_from_coord(&[3, 0]).unwrap() as u64,
+        universe.get_index_from_coord(&[6, 2]).unwrap() as u64,
+    );
+    assert_eq!(
+        universe.entanglement.partners(a).collect::<Vec<_>>(),
+        vec![b]
+    );
+    assert_eq!(universe.entanglement.len(), 1);
+    let left_behind = universe.get_index_from_coord(&[2, 4]).unwrap();
+    assert_eq!(
+        universe.grid[left_behind].consciousness,
+        ConsciousnessState::Potential
+    );
+
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.interventions, universe.interventions);
+    assert_eq!(rebuilt.grid, universe.grid);
+
+    // The block cannot be pushed off the grid.
+    let interventions = universe.interventions.len();
+    assert!(universe.move_region(&moved, &[0, -1]).is_none());
+    assert!(universe.move_region(&moved, &[10, 0]).is_none());
+    assert_eq!(universe.interventions.len(), interventions);
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn select_tool_copies_and_pastes_with_ctrl_c_and_ctrl_v() {
+    use common::Driver;
+    use piston_window::{Button, Key, MouseButton};
+
+    let ctrl = Button::Keyboard(Key::LCtrl);
+    let left = Button::Mouse(MouseButton::Left);
+    let mut driver = Driver::new(9);
+    driver.key(Key::D6).update().click_cell(3, 4);
+    assert_eq!(driver.app.selection, Some(vec![3..4, 4..5]));
+    driver
+        .move_to_cell(12, 8)
+        .press(left)
+        .move_to_cell(10, 10)
+        .release(left);
+    assert_eq!(driver.app.selection, Some(vec![10..13, 8..11]));
+
+    let copied = driver.app.universe.extract_pattern(&[10..13, 8..11]);
+    driver.press(ctrl).key(Key::C).release(ctrl);
+    assert_eq!(driver.app.copied_pattern.as_ref(), Some(&copied));
+
+    driver
+        .move_to_cell(40, 30)
+        .press(ctrl)
+        .key(Key::V)
+        .release(ctrl);
+    assert_eq!(
+        driver.app.universe.extract_pattern(&[40..43, 30..33]),
+        copied
+    );
+    let corner = driver.index(40, 30);
+    assert_eq!(driver.app.universe.interventions.len(), 1);
+    assert_eq!(driver.app.universe.interventions[0].tick, 1);
+    assert_eq!(
+        driver.app.universe.grid[corner].state,
+        copied.cells[0].state
+    );
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn select_tool_cuts_with_ctrl_x_and_moves_with_the_arrow_keys() {
+    use common::Driver;
+    use piston_window::{Button, Key, MouseButton};
+
+    let ctrl = Button::Keyboard(Key::LCtrl);
+    // The Operator tool leaves cells alone as the cursor passes.
+    let mut driver = Driver::new(13);
+    driver.key(Key::D3).key(Key::D6).move_to_cell(4, 4);
+    driver
+        .press(Button::Mouse(MouseButton::Left))
+        .move_to_cell(6, 5)
+        .release(Button::Mouse(MouseButton::Left));
+    assert_eq!(driver.app.selection, Some(vec![4..7, 4..6]));
+    let block = driver.app.universe.extract_pattern(&[4..7, 4..6]);
+
+    // The left and right arrows move the selection rather than scrub.
+    let undo_depth = driver.app.history.undo_depth();
+    driver
+        .key(Key::Right)
+        .key(Key::Down)
+        .key(Key::Down)
+        .key(Key::Left);
+    assert_eq!(driver.app.selection, Some(vec![4..7, 6..8]));
+    assert_eq!(driver.app.history.undo_depth(), undo_depth + 4);
+    assert_eq!(driver.app.universe.extract_pattern(&[4..7, 6..8]), block);
+    let vacated = driver.index(4, 4);
+    assert_eq!(
+        driver.app.universe.grid[vacated].consciousness,
+        ConsciousnessState::Potential
+    );
+
+    // Pushing against the edge changes nothing.
+    for _ in 0..5 {
+        driver.key(Key::Left);
+    }
+    assert_eq!(driver.app.selection, Some(vec![0..3, 6..8]));
+
+    driver.press(ctrl).key(Key::X).release(ctrl);
+    assert_eq!(driver.app.copied_pattern.as_ref(), Some(&block));
+    let hole = driver.app.universe.extract_pattern(&[0..3, 6..8]);
+    assert!(
+        hole.cells
+            .iter()
+            .all(|cell| cell.state == Multivector::zero(cell.state.p))
+    );
+    driver.update();
+}
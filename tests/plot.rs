@@ -0,0 +1,76 @@
+//! Tests of the plot strip's history of population metrics.
+
+use existons::existon::ConsciousnessState;
+use existons::plot::{Metric, MetricHistory, Sample};
+use existons::universe::Universe;
+
+fn sample(tick: u64, observed: usize) -> Sample {
+    Sample {
+        tick,
+        observed,
+        entanglements: 0,
+        activity: observed * 2,
+    }
+}
+
+#[test]
+fn the_history_keeps_the_latest_samples_and_scales_them_to_fit() {
+    let mut history = MetricHistory::with_capacity(5);
+    assert_eq!(history.peak(Metric::Observed), 1);
+    for tick in 1..=7 {
+        history.record(sample(tick, tick as usize));
+    }
+    let ticks: Vec<u64> = history.samples().iter().map(|s| s.tick).collect();
+    assert_eq!(ticks, [3, 4, 5, 6, 7]);
+    assert_eq!(history.latest().unwrap().observed, 7);
+    assert_eq!(history.peak(Metric::Activity), 14);
+
+    // The peak reaches the top, and a full history spans the width.
+    let points = history.polyline(Metric::Observed, [10.0, 20.0, 100.0, 70.0]);
+    assert_eq!(points.len(), 5);
+    assert_eq!(points[0], [10.0, 20.0 + 70.0 * (1.0 - 3.0 / 7.0)]);
+    assert_eq!(points[4], [110.0, 20.0]);
+    // With nothing to show, lines lie along the bottom.
+    let flat = history.polyline(Metric::Entanglements, [0.0, 0.0, 100.0, 70.0]);
+    assert!(flat.iter().all(|&[_, y]| y == 70.0));
+    history.clear();
+    assert!(history.latest().is_none());
+}
+
+#[test]
+fn a_sample_counts_the_observed_cells_and_the_activity() {
+    let mut universe = Universe::with_seed(vec![12, 12], 2, 90);
+    universe.observation_rate = 0.2;
+    let report = universe.tick();
+    let sample = Sample::of(&universe, &report);
+    let observed = universe
+        .grid
+        .iter()
+        .filter(|e| e.consciousness == ConsciousnessState::Observed)
+        .count();
+    assert_eq!(sample.tick, 1);
+    assert_eq!(sample.observed, observed);
+    assert!(sample.activity >= report.observations.len() + sample.entanglements);
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn every_tick_adds_a_sample_of_the_live_universe() {
+    use existons::app::{AppState, Config};
+    use existons::input::{AppAction, Modifiers, key_action};
+    use piston_window::Key;
+
+    let mut app = AppState::new(Config::new());
+    app.autosave = None;
+    let toggle = key_action(Key::G, Modifiers::default()).unwrap();
+    assert_eq!(toggle, AppAction::TogglePlot);
+    app.apply(toggle);
+    assert!(app.overlays.plot);
+    for _ in 0..3 {
+        app.apply(AppAction::Tick);
+    }
+    assert_eq!(app.metrics.samples().len(), 3);
+    assert_eq!(app.metrics.latest().unwrap().tick, 3);
+    app.apply(AppAction::ResetUniverse);
+    assert!(app.metrics.latest().is_none());
+}
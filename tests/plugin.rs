@@ -0,0 +1,158 @@
+//! Tests of the plugin registry.
+
+use existons::existon::ConsciousnessState;
+#[cfg(feature = "desktop")]
+use existons::plugin::{Analyzer, Overlay};
+use existons::plugin::{Canvas, PluginCtx, PluginRegistry};
+use existons::universe::Universe;
+#[cfg(feature = "desktop")]
+use std::sync::{Arc, Mutex};
+
+/// Counts ticks and remembers how many cells it saw.
+#[cfg(feature = "desktop")]
+struct TickCounter(Arc<Mutex<Vec<usize>>>);
+
+#[cfg(feature = "desktop")]
+impl Analyzer for TickCounter {
+    fn name(&self) -> &str {
+        "Tick counter"
+    }
+
+    fn on_tick(&mut self, ctx: &PluginCtx) {
+        self.0.lock().unwrap().push(ctx.universe.grid.len());
+    }
+
+    fn summary(&self) -> Vec<String> {
+        vec![format!("{} ticks", self.0.lock().unwrap().len())]
+    }
+}
+
+/// Fills the first cell.
+#[cfg(feature = "desktop")]
+struct Corner;
+
+#[cfg(feature = "desktop")]
+impl Overlay for Corner {
+    fn name(&self) -> &str {
+        "Corner"
+    }
+
+    fn draw(&self, _: &PluginCtx, canvas: &mut dyn Canvas) {
+        canvas.fill_cell(&[0, 0], [1.0; 4]);
+    }
+}
+
+#[derive(Default)]
+struct Recorder(Vec<Vec<usize>>);
+
+impl Canvas for Recorder {
+    fn fill_cell(&mut self, coord: &[usize], _: [f32; 4]) {
+        self.0.push(coord.to_vec());
+    }
+
+    fn outline_cell(&mut self, _: &[usize], _: [f32; 4]) {}
+
+    fn line(&mut self, _: &[usize], _: &[usize], _: [f32; 4]) {}
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn registered_plugins_are_listed_toggled_and_run() {
+    use existons::app::{AppState, Config};
+    use existons::input::AppAction;
+    use existons::plugin::PluginKind;
+
+    let mut app = AppState::new(Config::new());
+    app.universe = Universe::with_seed(vec![5, 4], 2, 1);
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    app.plugins = PluginRegistry::new();
+    app.plugins.register_overlay(Corner);
+    app.plugins.register_analyzer(TickCounter(seen.clone()));
+
+    let list = app.plugins.list();
+    assert_eq!(list.len(), 2);
+    assert_eq!(
+        (list[0].kind, list[1].kind),
+        (PluginKind::Overlay, PluginKind::Analyzer)
+    );
+    assert!(list.iter().all(|p| p.enabled));
+
+    app.apply(AppAction::Tick);
+    app.apply(AppAction::TogglePlugin(1));
+    app.apply(AppAction::Tick);
+    assert_eq!(*seen.lock().unwrap(), vec![20]);
+    assert!(
+        app.plugins
+            .describe()
+            .iter()
+            .any(|l| l.contains("[Alt+2] [ ] Tick counter"))
+    );
+
+    let mut canvas = Recorder::default();
+    let ctx = PluginCtx {
+        universe: &app.universe,
+        report: &app.last_report,
+    };
+    app.plugins.draw_overlays(&ctx, &mut canvas);
+    assert_eq!(canvas.0, vec![vec![0, 0]]);
+    assert!(!app.plugins.toggle(2));
+}
+
+#[test]
+fn the_accounting_analyzer_plots_the_weight() {
+    let mut registry = PluginRegistry::with_builtins();
+    let accounting = registry
+        .list()
+        .iter()
+        .position(|plugin| plugin.name == "Accounting")
+        .unwrap();
+    registry.toggle(accounting);
+    let mut universe = Universe::with_seed(vec![10, 10], 3, 2);
+    universe.fluctuation_rate = 0.1;
+    for _ in 0..5 {
+        let report = universe.tick();
+        registry.on_tick(&PluginCtx {
+            universe: &universe,
+            report: &report,
+        });
+    }
+    let lines = registry.describe();
+    let weight = universe.grade_totals().total_weight();
+    assert!(
+        lines
+            .iter()
+            .any(|l| l.contains(&format!("weight {} (", weight)))
+    );
+    let plot = lines.iter().find_map(|l| l.trim().strip_prefix("weight |"));
+    assert_eq!(plot.map(|p| p.trim_end_matches('|').len()), Some(5));
+}
+
+#[test]
+fn the_observed_heat_map_fills_only_observed_cells() {
+    let mut registry = PluginRegistry::with_builtins();
+    let heat_map = registry
+        .list()
+        .iter()
+        .position(|plugin| plugin.name == "Observed heat map")
+        .unwrap();
+    registry.toggle(heat_map);
+    let mut universe = Universe::with_seed(vec![8, 8], 2, 4);
+    universe.observation_rate = 0.2;
+    let mut report = universe.tick();
+    for _ in 0..5 {
+        report = universe.tick();
+    }
+    let mut canvas = Recorder::default();
+    let ctx = PluginCtx {
+        universe: &universe,
+        report: &report,
+    };
+    registry.draw_overlays(&ctx, &mut canvas);
+    let observed: Vec<Vec<usize>> = universe
+        .iter_cells()
+        .filter(|(_, cell)| cell.consciousness == ConsciousnessState::Observed)
+        .map(|(coord, _)| coord)
+        .collect();
+    assert!(!observed.is_empty());
+    assert_eq!(canvas.0, observed);
+}
@@ -0,0 +1,132 @@
+//! Tests of the profiler overlay and the tick timings behind it.
+
+use existons::profile::{CountingAllocator, FrameTimings, Profiler, TickTimings};
+use existons::universe::Universe;
+use std::alloc::{GlobalAlloc, Layout};
+use std::time::Duration;
+
+fn ms(n: u64) -> Duration {
+    Duration::from_millis(n)
+}
+
+#[test]
+fn profiling_times_ticks_without_changing_them() {
+    let mut plain = Universe::with_seed(vec![24, 24], 3, 11);
+    let mut profiled = plain.clone();
+    profiled.profiling = true;
+    for _ in 0..10 {
+        assert_eq!(plain.tick(), profiled.tick());
+    }
+    assert_eq!(plain.grid, profiled.grid);
+    assert_eq!(plain.tick_timings(), TickTimings::default());
+
+    let timings = profiled.tick_timings();
+    assert!(timings.local > Duration::ZERO);
+    assert!(timings.neighbor_sums + timings.products <= timings.local);
+    assert_eq!(
+        timings.total(),
+        timings.local + timings.entanglement + timings.other
+    );
+}
+
+#[test]
+fn budgeted_ticks_are_timed_too() {
+    let mut universe = Universe::with_seed(vec![32, 32], 3, 4);
+    universe.profiling = true;
+    while universe.tick_within(Duration::ZERO).is_none() {}
+    assert!(universe.tick_timings().local > Duration::ZERO);
+}
+
+#[test]
+fn the_overlay_averages_recent_frames_and_ticks() {
+    let mut profiler = Profiler::default();
+    assert_eq!(profiler.tick_average(), None);
+    assert_eq!(profiler.simulation_bound(), None);
+    assert!(
+        profiler
+            .describe()
+            .contains(&"Tick: no ticks yet".to_string())
+    );
+
+    profiler.add_tick(TickTimings {
+        local: ms(4),
+        entanglement: ms(2),
+        ..TickTimings::default()
+    });
+    profiler.add_tick(TickTimings {
+        local: ms(6),
+        other: ms(2),
+        ..TickTimings::default()
+    });
+    let tick = profiler.tick_average().unwrap();
+    assert_eq!(tick.local, ms(5));
+    assert_eq!(tick.total(), ms(7));
+
+    profiler.add_frame(FrameTimings {
+        update: ms(10),
+        render: ms(2),
+        allocations: Some(30),
+    });
+    profiler.add_frame(FrameTimings {
+        update: ms(6),
+        render: ms(4),
+        allocations: Some(10),
+    });
+    let frame = profiler.frame_average().unwrap();
+    assert_eq!((frame.update, frame.render), (ms(8), ms(3)));
+    assert_eq!(frame.allocations, Some(20));
+    assert_eq!(profiler.simulation_bound(), Some(true));
+    let lines = profiler.describe();
+    assert!(lines.contains(&"Allocations: 20 per frame".to_string()));
+    assert!(lines.contains(&"Simulation-bound".to_string()));
+
+    // A frame that did not count allocations leaves them out.
+    profiler.add_frame(FrameTimings::default());
+    assert_eq!(profiler.frame_average().unwrap().allocations, None);
+
+    for _ in 0..Profiler::WINDOW {
+        profiler.add_frame(FrameTimings {
+            update: ms(1),
+            render: ms(5),
+            allocations: None,
+        });
+    }
+    assert_eq!(profiler.frame_average().unwrap().update, ms(1));
+    assert!(profiler.describe().contains(&"Render-bound".to_string()));
+}
+
+#[test]
+fn the_allocator_counts_allocations() {
+    let allocator = CountingAllocator::new();
+    let layout = Layout::from_size_align(64, 8).unwrap();
+    unsafe {
+        let ptr = allocator.alloc(layout);
+        let ptr = allocator.realloc(ptr, layout, 128);
+        allocator.dealloc(ptr, Layout::from_size_align(128, 8).unwrap());
+    }
+    assert_eq!(allocator.allocations(), 2);
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn the_toggle_profiles_the_live_universe() {
+    use existons::app::{AppState, Config};
+    use existons::input::{AppAction, Modifiers, key_action};
+    use piston_window::Key;
+
+    let mut app = AppState::new(Config::new());
+    app.universe = Universe::with_seed(vec![16, 16], 3, 2);
+    app.worker = None;
+    let toggle = key_action(Key::F3, Modifiers::default()).unwrap();
+    assert_eq!(toggle, AppAction::ToggleProfiler);
+    app.apply(toggle);
+    assert!(app.profiler.visible);
+    app.apply(AppAction::Tick);
+    assert!(app.universe.profiling);
+    assert!(app.profiler.tick_average().unwrap().local > Duration::ZERO);
+
+    app.apply(AppAction::ToggleProfiler);
+    app.apply(AppAction::Tick);
+    assert!(!app.universe.profiling);
+    assert_eq!(app.profiler.tick_average(), None);
+}
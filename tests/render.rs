@@ -0,0 +1,65 @@
+//! Tests of the grid image the window draws as one texture.
+
+use existons::render::GridImage;
+use existons::slice::Slice;
+use existons::universe::Universe;
+
+#[test]
+fn the_image_holds_one_pixel_per_cell_of_the_viewed_plane() {
+    let universe = Universe::with_seed(vec![4, 2, 3], 2, 70);
+    let mut slice = Slice::new(3);
+    slice.position[2] = 2;
+    let mut grid = GridImage::default();
+    assert!(grid.update(&universe, &slice, |idx| [
+        (idx % 4) as f32 / 3.0,
+        0.0,
+        1.0,
+        0.5
+    ]));
+    assert_eq!(grid.image().dimensions(), (4, 2));
+    // Cell [3, 1, 2] is pixel (3, 1), its color unblended.
+    let idx = universe.get_index_from_coord(&[3, 1, 2]).unwrap();
+    let red = ((idx % 4) as f32 / 3.0 * 255.0).round() as u8;
+    assert_eq!(grid.image().get_pixel(3, 1).0, [red, 0, 255, 128]);
+}
+
+#[test]
+fn updates_report_whether_anything_changed() {
+    let universe = Universe::with_seed(vec![5, 3], 2, 71);
+    let slice = Slice::new(2);
+    let mut grid = GridImage::default();
+    let lit = universe.get_index_from_coord(&[2, 1]).unwrap();
+    let color = |on: bool| {
+        move |idx: usize| {
+            if on && idx == lit {
+                [1.0, 1.0, 1.0, 1.0]
+            } else {
+                [0.0, 0.0, 0.0, 1.0]
+            }
+        }
+    };
+    assert!(grid.update(&universe, &slice, color(false)));
+    assert!(!grid.update(&universe, &slice, color(false)));
+    assert!(grid.update(&universe, &slice, color(true)));
+    assert_eq!(grid.image().get_pixel(2, 1).0, [255; 4]);
+
+    // A different grid size replaces the image.
+    let wider = Universe::with_seed(vec![6, 3], 2, 71);
+    assert!(grid.update(&wider, &slice, color(false)));
+    assert_eq!(grid.image().dimensions(), (6, 3));
+}
+
+#[test]
+fn a_sampled_image_keeps_every_step_th_cell() {
+    let universe = Universe::with_seed(vec![7, 5], 2, 72);
+    let slice = Slice::new(2);
+    let mut grid = GridImage::default();
+    grid.update_sampled(&universe, &slice, 3, |idx| {
+        [idx as f32 / 35.0, 0.0, 0.0, 1.0]
+    });
+    assert_eq!(grid.image().dimensions(), (3, 2));
+    // Pixel (2, 1) is cell [6, 3].
+    let idx = universe.get_index_from_coord(&[6, 3]).unwrap();
+    let red = (idx as f32 / 35.0 * 255.0).round() as u8;
+    assert_eq!(grid.image().get_pixel(2, 1).0, [red, 0, 0, 255]);
+}
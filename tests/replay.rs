@@ -0,0 +1,172 @@
+//! Tests of recording runs and playing them back.
+
+#[cfg(feature = "desktop")]
+mod common;
+
+#[cfg(feature = "desktop")]
+use existons::existon::ConsciousnessState;
+use existons::existon::Existon;
+use existons::replay::{Playback, RecordMode, Recorder, Replay};
+use existons::universe::Universe;
+
+#[test]
+fn input_replays_seek_both_ways_through_the_recorded_run() {
+    let mut universe = Universe::with_seed(vec![16, 16], 3, 31);
+    universe.observation_rate = 0.05;
+    for _ in 0..3 {
+        universe.tick();
+    }
+    let recorder = Recorder::start(&universe, RecordMode::Inputs);
+    let mut states = Vec::new();
+    for tick in 3..120 {
+        if tick == 40 {
+            universe.set_operator(&[4, 4]);
+        }
+        states.push(universe.grid.clone());
+        universe.tick();
+    }
+    let replay = Replay::from_text(&recorder.finish(&universe).to_text()).unwrap();
+    assert_eq!((replay.start, replay.end()), (3, 120));
+    assert!(replay.frames.is_empty());
+
+    let mut playback = Playback::new(replay);
+    assert_eq!(playback.universe().grid, states[0]);
+    playback.seek(120);
+    assert_eq!(playback.universe().grid, universe.grid);
+    assert!(playback.at_end());
+    playback.seek(41);
+    assert_eq!(playback.universe().grid, states[41 - 3]);
+    playback.step(false);
+    assert_eq!(playback.universe().grid, states[40 - 3]);
+    playback.seek(0);
+    assert_eq!(playback.position(), 3);
+}
+
+#[test]
+fn snapshot_replays_step_between_recorded_frames() {
+    let mut universe = Universe::with_seed(vec![10, 10], 2, 32);
+    let mut recorder = Recorder::start(&universe, RecordMode::Snapshots { interval: 4 });
+    for _ in 0..10 {
+        universe.tick();
+        recorder.on_tick(&universe);
+    }
+    let replay = Replay::from_text(&recorder.finish(&universe).to_text()).unwrap();
+    let ticks: Vec<u64> = replay.frames.iter().map(|f| f.tick).collect();
+    assert_eq!(ticks, vec![0, 4, 8, 10]);
+
+    let mut playback = Playback::new(replay);
+    playback.seek(6);
+    assert_eq!(playback.position(), 4);
+    playback.step(true);
+    assert_eq!(playback.position(), 8);
+    playback.step(true);
+    assert_eq!(playback.position(), 10);
+    assert_eq!(playback.universe().grid, universe.grid);
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn ctrl_r_records_and_ctrl_o_plays_back_with_arrow_scrubbing() {
+    use common::Driver;
+    use piston_window::{Button, Key};
+
+    let mut driver = Driver::new(33);
+    let path = std::env::temp_dir().join(format!("existons-replay-{}.txt", std::process::id()));
+    driver.app.config.replay_path = path.clone();
+    let ctrl = Button::Keyboard(Key::LCtrl);
+
+    driver.update().press(ctrl).key(Key::R).release(ctrl);
+    driver
+        .key(Key::D3)
+        .click_cell(6, 6)
+        .update()
+        .update()
+        .update();
+    driver.press(ctrl).key(Key::R).key(Key::O).release(ctrl);
+    std::fs::remove_file(&path).unwrap();
+
+    let live = driver.app.universe.clone();
+    let playback = driver.app.playback.as_ref().unwrap();
+    assert_eq!(playback.position(), 1);
+    // Edits are ignored while watching, and the live universe stays frozen.
+    driver.click_cell(20, 20).update().update();
+    assert_eq!(driver.app.universe.grid, live.grid);
+    assert_eq!(driver.app.playback.as_ref().unwrap().position(), 3);
+
+    driver.key(Key::Right).key(Key::Right).key(Key::Left);
+    assert!(driver.app.paused);
+    assert_eq!(driver.app.shown_universe().tick_count, 3);
+    driver.key(Key::Right);
+    let shown = driver.app.shown_universe();
+    assert_eq!(shown.grid, live.grid);
+    assert_eq!(
+        shown.grid[driver.index(6, 6)].consciousness,
+        ConsciousnessState::Operator
+    );
+
+    driver.press(ctrl).key(Key::O).release(ctrl);
+    assert!(driver.app.playback.is_none());
+}
+
+#[test]
+fn snapshot_replays_follow_a_resized_grid() {
+    let mut universe = Universe::with_seed(vec![6, 4], 2, 33);
+    let mut recorder = Recorder::start(&universe, RecordMode::Snapshots { interval: 1 });
+    universe.tick();
+    recorder.on_tick(&universe);
+    universe.resize(&[8, 5]);
+    universe.tick();
+    recorder.on_tick(&universe);
+    let replay = Replay::from_text(&recorder.finish(&universe).to_text()).unwrap();
+    let dims: Vec<&[usize]> = replay.frames.iter().map(|f| &f.grid_dims[..]).collect();
+    assert_eq!(dims, vec![&[6, 4][..], &[6, 4], &[8, 5]]);
+
+    let mut playback = Playback::new(replay);
+    playback.seek(2);
+    assert_eq!(playback.universe().grid_dims, vec![8, 5]);
+    // Snapshots keep what was on screen, not the cells' IDs.
+    let states = |grid: &[Existon]| grid.iter().map(|c| c.state.clone()).collect::<Vec<_>>();
+    assert_eq!(states(&playback.universe().grid), states(&universe.grid));
+}
+
+#[test]
+fn recordings_verify_as_golden_runs() {
+    let mut universe = Universe::with_seed(vec![16, 12], 3, 34);
+    universe.observation_rate = 0.05;
+    for _ in 0..3 {
+        universe.tick();
+    }
+    let mut recorder = Recorder::start(&universe, RecordMode::Inputs);
+    for tick in 3..130 {
+        universe.tick();
+        recorder.on_tick(&universe);
+        // Made after the tick's hash was taken, at the same tick.
+        if tick == 52 {
+            universe.set_operator(&[4, 4]);
+        }
+    }
+    let text = recorder.finish(&universe).to_text();
+    let replay = Replay::from_text(&text).unwrap();
+    let ticks: Vec<u64> = replay.hashes.iter().map(|h| h.tick).collect();
+    assert_eq!(ticks, vec![3, 53, 103, 130]);
+    assert_eq!(replay.hashes[3].hash, universe.state_hash());
+    assert_eq!(replay.verify(), Ok(4));
+
+    // A run that no longer behaves the same is caught where it diverges.
+    let mut changed = replay.clone();
+    changed.scenario.interventions.clear();
+    changed.hashes.iter_mut().for_each(|h| h.interventions = 0);
+    let mismatch = changed.verify().unwrap_err();
+    assert_eq!(mismatch.tick, 103);
+    assert_eq!(mismatch.expected, replay.hashes[2].hash);
+
+    // Recordings from before hashes were kept still load, with nothing to check.
+    let old: String = text
+        .lines()
+        .take(2)
+        .map(|line| format!("{}\n", line))
+        .collect();
+    let old = Replay::from_text(&old).unwrap();
+    assert!(old.hashes.is_empty());
+    assert_eq!(old.verify(), Ok(0));
+}
@@ -0,0 +1,192 @@
+//! Tests of pluggable update rules.
+
+use existons::existon::{ConsciousnessState, Existon};
+use existons::ga_core::Multivector;
+use existons::rule::{RuleCtx, UpdateRule};
+use existons::share::Scenario;
+use existons::tuning::TickTuning;
+use existons::universe::{BoundaryCondition, Neighborhood, RandomScheme, Universe, UpdateOrder};
+use std::time::Duration;
+
+/// Multiplies the neighbors together and collapses cells whose scalar turns negative.
+#[derive(Debug)]
+struct ProductThresholdRule;
+
+impl UpdateRule for ProductThresholdRule {
+    fn apply(&self, cell: &Existon, neighbors: &[&Existon], ctx: &RuleCtx) -> Existon {
+        let mut operator = Multivector::zero(ctx.ga_dims);
+        operator.coefficients[0] = existons::ga_core::Mod3::new(1);
+        for neighbor in neighbors {
+            operator = &operator * &neighbor.state;
+        }
+        let mut next = cell.clone();
+        next.state = &operator * &cell.state;
+        if next.state.coefficients[0].0 < 0 {
+            next.observe(ctx.collapse_policy);
+        }
+        next
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn custom_rules_replace_the_default() {
+    let mut default = Universe::with_seed(vec![12, 12], 3, 2);
+    let mut custom = Universe::with_seed(vec![12, 12], 3, 2).with_rule(ProductThresholdRule);
+    default.tick();
+    custom.tick();
+    assert_ne!(custom.grid, default.grid);
+    assert!(
+        custom
+            .grid
+            .iter()
+            .any(|e| e.consciousness == ConsciousnessState::Observed)
+    );
+}
+
+#[test]
+fn deterministic_rules_give_the_same_result_on_any_number_of_threads() {
+    let mut single = Universe::with_seed(vec![30, 20], 3, 8).with_rule(ProductThresholdRule);
+    let mut parallel = single.clone();
+    parallel.tuning = TickTuning {
+        threads: 4,
+        tile_size: 13,
+        snapshot_interval: 1,
+    };
+    for _ in 0..4 {
+        assert_eq!(parallel.tick(), single.tick());
+    }
+    assert_eq!(parallel.grid, single.grid);
+}
+
+#[test]
+fn per_cell_randomness_is_the_same_however_the_tick_is_split() {
+    let mut single =
+        Universe::with_seed(vec![30, 20], 3, 8).with_random_scheme(RandomScheme::PerCell);
+    single.fluctuation_rate = 0.3;
+    let mut parallel = single.clone();
+    parallel.tuning = TickTuning {
+        threads: 4,
+        tile_size: 13,
+        snapshot_interval: 1,
+    };
+    let mut stepped = single.clone();
+    let mut sequential = single.clone().with_random_scheme(RandomScheme::Sequential);
+    for _ in 0..4 {
+        let report = single.tick();
+        assert!(!report.fluctuations.is_empty());
+        let mut stepped_report = stepped.tick_within(Duration::ZERO);
+        while stepped_report.is_none() {
+            stepped_report = stepped.tick_within(Duration::ZERO);
+        }
+        assert_eq!(
+            parallel.tick().fluctuations.len(),
+            report.fluctuations.len()
+        );
+        assert_eq!(
+            stepped_report.unwrap().fluctuations.len(),
+            report.fluctuations.len()
+        );
+        sequential.tick();
+    }
+    assert_eq!(parallel.grid, single.grid);
+    assert_eq!(stepped.grid, single.grid);
+    assert_ne!(sequential.grid, single.grid);
+
+    // Share codes keep the scheme, so a rebuilt run draws the same numbers.
+    let rebuilt = Scenario::decode(&Scenario::capture(&single).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.random_scheme(), RandomScheme::PerCell);
+    assert_eq!(rebuilt.grid, single.grid);
+}
+
+/// Observes every cell next to an observed one, so observation spreads one
+/// neighbor per update.
+#[derive(Debug)]
+struct Spread;
+
+impl UpdateRule for Spread {
+    fn apply(&self, cell: &Existon, neighbors: &[&Existon], ctx: &RuleCtx) -> Existon {
+        let mut next = cell.clone();
+        if neighbors
+            .iter()
+            .any(|n| n.consciousness == ConsciousnessState::Observed)
+        {
+            next.observe(ctx.collapse_policy);
+        }
+        next
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+fn spread_once(order: UpdateOrder, seed: u64) -> (Universe, Vec<usize>) {
+    let mut universe = Universe::with_seed(vec![12], 2, seed)
+        .with_rule(Spread)
+        .with_neighborhood(Neighborhood::VonNeumann)
+        .with_boundary(BoundaryCondition::Fixed)
+        .with_update_order(order);
+    universe.observe_cell(1);
+    universe.tick();
+    let observed = (0..12)
+        .filter(|&idx| universe.grid[idx].consciousness == ConsciousnessState::Observed)
+        .collect();
+    (universe, observed)
+}
+
+#[test]
+fn update_orders_decide_which_neighbors_a_cell_sees() {
+    assert_eq!(spread_once(UpdateOrder::Synchronous, 1).1, vec![0, 1, 2]);
+    // Even cells update first, then odd cells see the newly observed cell 2.
+    assert_eq!(
+        spread_once(UpdateOrder::Checkerboard, 1).1,
+        vec![0, 1, 2, 3]
+    );
+
+    // A shuffled order spreads further along runs that happen to be in sequence.
+    let (universe, observed) = spread_once(UpdateOrder::RandomSequential, 4);
+    assert!(observed.starts_with(&[0, 1, 2]));
+    assert!(observed.windows(2).all(|w| w[1] == w[0] + 1));
+    assert_eq!(spread_once(UpdateOrder::RandomSequential, 4).1, observed);
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode()).unwrap();
+    assert_eq!(rebuilt.update_order, UpdateOrder::RandomSequential);
+}
+
+#[test]
+fn asynchronous_orders_finish_budgeted_ticks_in_one_go() {
+    let mut whole =
+        Universe::with_seed(vec![16, 16], 3, 9).with_update_order(UpdateOrder::Checkerboard);
+    let mut budgeted = whole.clone();
+    let report = whole.tick();
+    assert_eq!(budgeted.tick_within(Duration::ZERO), Some(report));
+    assert_eq!(budgeted.grid, whole.grid);
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn registered_rules_are_made_by_name_and_survive_a_reset() {
+    use existons::app::{AppState, Config};
+    use existons::input::AppAction;
+    use existons::rule::RuleRegistry;
+    use std::sync::Arc;
+
+    let mut rules = RuleRegistry::with_builtins();
+    rules.register("product", || Arc::new(ProductThresholdRule));
+    assert_eq!(rules.names(), ["matzke", "product"]);
+    assert!(rules.create("life").is_none());
+    assert!(format!("{:?}", rules.create("matzke").unwrap()).contains("Matzke"));
+
+    let mut config = Config::new();
+    config.grid_dims = vec![12, 12];
+    config.ga_dims = 3;
+    config.rule = rules.create("product").unwrap();
+    let mut app = AppState::new(config);
+    app.apply(AppAction::ResetUniverse);
+    assert!(format!("{:?}", app.universe.rule()).contains("ProductThreshold"));
+}
@@ -0,0 +1,65 @@
+//! Tests of annealing schedules.
+
+use existons::field::Rate;
+use existons::schedule::{Curve, Schedule, ScheduleError};
+use existons::universe::Universe;
+
+#[test]
+fn schedules_parse_every_curve_and_set_the_rates() {
+    let schedule: Schedule = "
+        # Start hot, then freeze.
+        fluctuation linear 0.3 0.0 100
+        observation sine 0.5 0.2 40; decay step 0.1 10 0.2 20 0.05
+    "
+    .parse()
+    .unwrap();
+    assert_eq!(
+        schedule.curves[2],
+        (
+            Rate::Decay,
+            Curve::Step {
+                initial: 0.1,
+                steps: vec![(10, 0.2), (20, 0.05)]
+            }
+        )
+    );
+
+    let value = |rate: Rate, tick: u64| {
+        let (_, curve) = schedule.curves.iter().find(|(r, _)| *r == rate).unwrap();
+        curve.value_at(tick)
+    };
+    assert!((value(Rate::Fluctuation, 50) - 0.15).abs() < 1e-9);
+    assert_eq!(value(Rate::Fluctuation, 500), 0.0);
+    assert!((value(Rate::Observation, 10) - 0.7).abs() < 1e-9);
+    assert_eq!(
+        [0, 9, 10, 19, 20].map(|tick| value(Rate::Decay, tick)),
+        [0.1, 0.1, 0.2, 0.2, 0.05]
+    );
+
+    let mut universe = Universe::with_seed(vec![6, 6], 2, 1);
+    for _ in 0..30 {
+        schedule.apply(&mut universe);
+        universe.tick();
+    }
+    schedule.apply(&mut universe);
+    assert_eq!(universe.decay_rate, 0.05);
+    assert!((universe.fluctuation_rate - 0.21).abs() < 1e-9);
+    assert_eq!(
+        schedule.describe(30),
+        "Anneal fluctuation 0.210 observation 0.300 decay 0.050"
+    );
+}
+
+#[test]
+fn malformed_schedules_name_the_line() {
+    let line = |text: &str| match text.parse::<Schedule>() {
+        Err(ScheduleError::Invalid { line, .. }) => line,
+        other => panic!("expected an invalid line, got {:?}", other),
+    };
+    assert_eq!(line("temperature linear 1 0 10"), 1);
+    assert_eq!(line("decay linear 1 0\n"), 1);
+    assert_eq!(line("\ndecay cubic 1 2 3"), 2);
+    assert_eq!(line("decay step 0.1 20 0.2 10 0.3"), 1);
+    assert_eq!(line("decay sine 0.1 0.1 2.5"), 1);
+    assert_eq!(line("decay step 0.1\ndecay step 0.2"), 2);
+}
@@ -0,0 +1,231 @@
+//! Tests of the scripts' functions, hooks and rules.
+#![cfg(feature = "scripting")]
+
+use existons::existon::ConsciousnessState;
+use existons::rule::RuleRegistry;
+use existons::script::{MAX_STEPS, Script, ScriptError, ScriptRule, Scripts};
+use existons::universe::Universe;
+use rhai::Dynamic;
+use std::fs;
+use std::path::Path;
+
+fn universe() -> Universe {
+    Universe::with_seed(vec![6, 6], 2, 1)
+}
+
+/// Runs `f()` in a script defining it, returning what it gives back.
+fn eval(source: &str) -> Result<Dynamic, ScriptError> {
+    Script::parse(source)?.call(&mut universe(), "f", Vec::new())
+}
+
+#[test]
+fn globals_persist_between_hook_calls() {
+    let mut universe = universe();
+    let mut script = Script::parse(
+        "let count = 10;
+         fn bump(by) { count += by; count }",
+    )
+    .unwrap();
+    for expected in [12, 14] {
+        let result = script.call(&mut universe, "bump", vec![Dynamic::from(2_i64)]);
+        assert_eq!(result.unwrap().as_int(), Ok(expected));
+    }
+    // A hook without such a function is simply not there.
+    assert!(
+        script
+            .call(&mut universe, "on_tick", vec![])
+            .unwrap()
+            .is_unit()
+    );
+}
+
+#[test]
+fn mistakes_are_reported_with_their_line() {
+    let syntax = Script::parse("let a = 1;\nlet b = (2;\n").unwrap_err();
+    assert!(matches!(syntax, ScriptError::Syntax { line: Some(2), .. }));
+    assert!(syntax.to_string().starts_with("line 2: "));
+    let runtime = eval("fn f() {\n  let a = 1;\n  a + missing\n}").unwrap_err();
+    assert!(matches!(
+        runtime,
+        ScriptError::Runtime { line: Some(3), .. }
+    ));
+    assert!(eval("fn f() { [1][1] }").is_err());
+    assert!(eval("fn f() { f() }").is_err());
+    assert!(eval("fn f() { set_state([0, 0], [2, 0, 0, 0]) }").is_err());
+}
+
+#[test]
+fn growing_values_stop_before_they_exhaust_memory() {
+    for body in [
+        "let a = [1]; loop { a += a; }",
+        "let s = \"x\"; loop { s += s; }",
+    ] {
+        let error = eval(&format!("fn f() {{ {} }}", body)).unwrap_err();
+        assert!(matches!(error, ScriptError::Runtime { .. }), "{}", error);
+        assert!(!error.to_string().contains("operations"), "{}", error);
+    }
+    assert_eq!(
+        eval("fn f() { let a = []; a.pad(1000, 0); (a + a).len() }")
+            .unwrap()
+            .as_int(),
+        Ok(2000)
+    );
+    let mut scripts = Scripts::default();
+    scripts.add(
+        "spam",
+        Script::parse("fn on_tick() { loop { print(\"spam\"); } }").unwrap(),
+    );
+    scripts.on_tick(&mut universe());
+    let output = scripts.take_output();
+    assert!(output.len() < 10_000);
+    assert!(output.last().unwrap().ends_with("the script is stopped"));
+}
+
+#[test]
+fn endless_loops_run_out_of_operations() {
+    let error = eval("fn f() { loop { } }").unwrap_err();
+    assert!(error.to_string().contains("operations"), "{}", error);
+    assert!(
+        eval(&format!(
+            "fn f() {{ for i in 0..{} {{ }} }}",
+            MAX_STEPS / 10
+        ))
+        .is_ok()
+    );
+}
+
+#[test]
+fn scripts_query_and_edit_the_universe_through_interventions() {
+    let mut universe = universe();
+    let mut script = Script::parse(
+        "fn f() {
+            observe([1, 1]);
+            operator([2, 2], [1, 0, -1, 0]);
+            entangle([0, 0], [5, 5]);
+            set_state([4, 4], [0, 1, 1, 0]);
+            set_rate(\"decay\", 2);
+            observe([9, 9]);
+            [consciousness([1, 1]), state([2, 2]), partners([0, 0]),
+             neighbors([3, 3]).len(), cells([0, 0], [2, 3]).len(), dims(),
+             state([9, 9]), product([0, 1, 0, 0], [0, 1, 0, 0])]
+        }",
+    )
+    .unwrap();
+    let result = script.call(&mut universe, "f", vec![]).unwrap();
+    assert_eq!(
+        result.to_string(),
+        r#"["observed", [1, 0, -1, 0], [[5, 5]], 8, 6, [6, 6], (), [1, 0, 0, 0]]"#
+    );
+    assert_eq!(universe.grid[7].consciousness, ConsciousnessState::Observed);
+    assert_eq!(universe.decay_rate, 1.0);
+    assert_eq!(universe.interventions.len(), 4);
+}
+
+#[test]
+fn hooks_run_until_a_script_fails() {
+    let mut universe = universe();
+    let mut scripts = Scripts::default();
+    scripts.add(
+        "ticks",
+        Script::parse("fn on_tick(tick) { print(`tick ${tick}`); }").unwrap(),
+    );
+    scripts.add(
+        "clicks",
+        Script::parse("fn on_click(coord) { observe(coord); } fn on_key(key) { key + missing }")
+            .unwrap(),
+    );
+    universe.tick();
+    scripts.on_tick(&mut universe);
+    scripts.on_click(&mut universe, &[3, 2]);
+    assert_eq!(
+        universe.grid[universe.get_index_from_coord(&[3, 2]).unwrap()].consciousness,
+        ConsciousnessState::Observed
+    );
+    scripts.on_key(&mut universe, "J");
+    let output = scripts.take_output();
+    assert_eq!(output[0], "ticks: tick 1");
+    assert!(output[1].starts_with("clicks: line 1: "), "{}", output[1]);
+    assert!(output[1].ends_with("; the script is stopped"));
+    assert!(!scripts.is_idle());
+    scripts.on_key(&mut universe, "J");
+    assert!(scripts.take_output().is_empty());
+}
+
+#[test]
+fn folders_of_scripts_load_in_name_order() {
+    let dir = std::env::temp_dir().join(format!("existons-scripts-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("b.rhai"), "fn on_key(key) { print(key); }").unwrap();
+    fs::write(dir.join("a.rhai"), "let x = ;").unwrap();
+    fs::copy(
+        Path::new("assets/scripts/cross.rhai"),
+        dir.join("cross.rhai"),
+    )
+    .unwrap();
+    fs::write(dir.join("notes.txt"), "not a script").unwrap();
+    let mut scripts = Scripts::default();
+    assert_eq!(scripts.load_folder(&dir).unwrap(), 2);
+    assert_eq!(scripts.names(), ["b", "cross"]);
+    let output = scripts.take_output();
+    assert_eq!(output.len(), 1);
+    assert!(output[0].starts_with("a: line 1: "), "{}", output[0]);
+
+    let mut universe = universe();
+    scripts.on_click(&mut universe, &[2, 2]);
+    scripts.on_key(&mut universe, "J");
+    assert_eq!(
+        scripts.take_output(),
+        ["b: J", "cross: 1 clicks; 9 observed at tick 0"]
+    );
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_script_rule_updates_every_cell() {
+    let rule = ScriptRule::parse(
+        "fn rule(cell, neighbors) {
+            if tick() == 0 && neighbors.len() == 8 && rate(\"decay\") >= 0.0 {
+                #{ state: [1, 0, 0, 0] }
+            } else {
+                cell
+            }
+        }",
+    )
+    .unwrap();
+    let mut universe = universe().with_rule(rule.clone());
+    universe.tick();
+    assert!(
+        universe
+            .grid
+            .iter()
+            .all(|cell| cell.state.coefficients[0].0 == 1)
+    );
+    assert_eq!(rule.failure(), None);
+}
+
+#[test]
+fn a_failing_rule_keeps_its_first_error() {
+    let rule = ScriptRule::parse("fn rule(cell, neighbors) { #{ state: [7] } }").unwrap();
+    let mut universe = universe().with_rule(rule.clone());
+    universe.tick();
+    assert!(rule.failure().unwrap().contains("-1, 0 or 1"));
+    assert!(matches!(
+        ScriptRule::parse("fn on_tick() { }"),
+        Err(ScriptError::Syntax { line: None, .. })
+    ));
+}
+
+#[test]
+fn scripts_register_their_rules_by_name() {
+    let mut scripts = Scripts::default();
+    scripts.add(
+        "frozen",
+        Script::parse("fn rule(cell, neighbors) { cell }").unwrap(),
+    );
+    scripts.add("hooks", Script::parse("fn on_tick() { }").unwrap());
+    let mut rules = RuleRegistry::with_builtins();
+    scripts.register_rules(&mut rules);
+    assert!(rules.names().contains(&"frozen"));
+    assert!(!rules.names().contains(&"hooks"));
+    assert!(rules.create("frozen").is_some());
+}
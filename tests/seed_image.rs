@@ -0,0 +1,74 @@
+//! Tests of seeding a universe from a picture.
+
+use existons::color::{ColorMapping, OBSERVED_COLOR, OPERATOR_COLOR};
+use existons::existon::ConsciousnessState;
+use existons::ga_core::Mod3;
+use existons::seed_image::ImageMapping;
+use existons::share::Scenario;
+use existons::universe::Universe;
+use image::{Rgba, RgbaImage};
+
+/// The pixel `mapping` shows for a `Potential` cell with these coefficients.
+fn pixel(mapping: &ColorMapping, coefficients: &[i8]) -> Rgba<u8> {
+    let mut pixel = [0, 0, 0, 255];
+    for (i, channel) in mapping.channels.iter().enumerate() {
+        let c = coefficients.get(channel.blade).copied().unwrap_or(0);
+        pixel[i] = (channel.value(c).clamp(0.0, 1.0) * 255.0).round() as u8;
+    }
+    Rgba(pixel)
+}
+
+fn solid(color: [f32; 4]) -> Rgba<u8> {
+    Rgba(color.map(|c| (c * 255.0).round() as u8))
+}
+
+#[test]
+fn pixels_become_cells() {
+    let mapping = ImageMapping {
+        seed: 3,
+        ..ImageMapping::default()
+    };
+    let mut picture = RgbaImage::from_pixel(5, 3, pixel(&mapping.colors, &[0; 8]));
+    picture.put_pixel(1, 0, solid(OBSERVED_COLOR));
+    picture.put_pixel(4, 2, solid(OPERATOR_COLOR));
+    let coefficients = [1, -1, 0, 1, 0, 0, 0, 0];
+    picture.put_pixel(2, 1, pixel(&mapping.colors, &coefficients));
+    let path = std::env::temp_dir().join(format!("existons-seed-{}.png", std::process::id()));
+    picture.save(&path).unwrap();
+
+    let universe = Universe::from_image(&path, &mapping).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(universe.grid_dims, [5, 3]);
+    assert_eq!(universe.ga_dims, 3);
+    let cell = |coord: &[usize]| &universe.grid[universe.get_index_from_coord(coord).unwrap()];
+    assert_eq!(cell(&[1, 0]).consciousness, ConsciousnessState::Observed);
+    assert_eq!(cell(&[4, 2]).consciousness, ConsciousnessState::Operator);
+    let potential = cell(&[2, 1]);
+    assert_eq!(potential.consciousness, ConsciousnessState::Potential);
+    let shown: Vec<Mod3> = coefficients.iter().map(|&c| Mod3(c)).collect();
+    assert_eq!(potential.state.coefficients, shown);
+    assert!(cell(&[0, 0]).state.coefficients.iter().all(|c| c.0 == 0));
+
+    // The picture is a logged stamp, so the share code rebuilds it.
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.grid, universe.grid);
+}
+
+#[test]
+fn colors_near_the_markers_count_within_the_tolerance() {
+    let mapping = ImageMapping::default();
+    let near = Rgba([250, 250, 210, 255]);
+    assert_eq!(mapping.cell(near).0, ConsciousnessState::Observed);
+    let strict = ImageMapping {
+        tolerance: 0.0,
+        ..ImageMapping::default()
+    };
+    assert_eq!(strict.cell(near).0, ConsciousnessState::Potential);
+    assert!(strict.cell(near).1.is_some());
+    assert_eq!(
+        mapping.cell(Rgba([0, 240, 250, 255])).0,
+        ConsciousnessState::Operator
+    );
+}
@@ -0,0 +1,43 @@
+//! Tests of the per-tick statistics log.
+
+use existons::series::{StatsLog, StatsRow};
+use existons::universe::Universe;
+use std::fs;
+
+#[test]
+fn rows_describe_the_tick_and_its_parameters() {
+    let mut universe = Universe::with_seed(vec![10, 10], 2, 9);
+    universe.decay_rate = 0.25;
+    let report = universe.tick();
+    let row = StatsRow::of(&universe, &report);
+    assert_eq!((row.seed, row.tick), (9, 1));
+    assert_eq!(row.potential + row.observed + row.operators, 100);
+    assert_eq!(row.observations, report.observations.len());
+    assert_eq!(row.entanglement_triggers, report.entanglements.len());
+    assert_eq!(row.decay_rate, 0.25);
+
+    let csv = row.to_csv();
+    assert_eq!(csv.split(',').count(), StatsRow::HEADER.split(',').count());
+    assert!(csv.starts_with("9,1,"));
+}
+
+#[test]
+fn logs_append_one_row_per_tick_under_a_single_header() {
+    let path = std::env::temp_dir().join(format!("existons-stats-{}.csv", std::process::id()));
+    let _ = fs::remove_file(&path);
+    for seed in [1, 2] {
+        let mut universe = Universe::with_seed(vec![6, 6], 2, seed);
+        let mut log = StatsLog::append(&path).unwrap();
+        for _ in 0..3 {
+            let report = universe.tick();
+            log.record(&universe, &report).unwrap();
+        }
+    }
+    let text = fs::read_to_string(&path).unwrap();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 7);
+    assert_eq!(lines[0], StatsRow::HEADER);
+    assert!(lines[1].starts_with("1,1,"));
+    assert!(lines[6].starts_with("2,3,"));
+    fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,254 @@
+//! Tests of streaming a run to WebSocket clients.
+#![cfg(feature = "desktop")]
+
+use existons::app::{AppState, Config};
+use existons::delta::UniverseDelta;
+use existons::entanglement::EntanglementGroups;
+use existons::existon::ConsciousnessState;
+use existons::frontend::Frontend;
+use existons::serve::{Request, Server, accept_key, command, player_color};
+use existons::universe::{Intervention, Universe};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+/// Sends a masked text frame, as clients must.
+fn send_text(stream: &mut TcpStream, text: &str) {
+    let mask = [0x12, 0x34, 0x56, 0x78];
+    let mut frame = vec![0x81, 0x80 | text.len() as u8];
+    frame.extend(mask);
+    frame.extend(text.bytes().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+    stream.write_all(&frame).unwrap();
+}
+
+/// Reads a frame from the server, returning its opcode and payload, or
+/// `None` once the connection ends.
+fn read_frame(reader: &mut impl Read) -> Option<(u8, Vec<u8>)> {
+    let mut head = [0; 2];
+    reader.read_exact(&mut head).ok()?;
+    assert_eq!(head[1] & 0x80, 0, "servers must not mask frames");
+    let len = match head[1] {
+        126 => {
+            let mut len = [0; 2];
+            reader.read_exact(&mut len).ok()?;
+            u16::from_be_bytes(len) as usize
+        }
+        127 => {
+            let mut len = [0; 8];
+            reader.read_exact(&mut len).ok()?;
+            u64::from_be_bytes(len) as usize
+        }
+        len => len as usize,
+    };
+    let mut payload = vec![0; len];
+    reader.read_exact(&mut payload).ok()?;
+    Some((head[0] & 0x0F, payload))
+}
+
+/// Carries out the handshake on a connection to the server, returning the
+/// stream to write to and a reader for what comes back.
+fn join(mut stream: TcpStream) -> (TcpStream, BufReader<TcpStream>) {
+    write!(
+        stream,
+        "GET / HTTP/1.1\r\nHost: localhost\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\nSec-WebSocket-Version: 13\r\n\r\n"
+    )
+    .unwrap();
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut response = Vec::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if line.trim_end().is_empty() {
+            break;
+        }
+        response.push(line.trim_end().to_string());
+    }
+    assert_eq!(response[0], "HTTP/1.1 101 Switching Protocols");
+    assert!(response.contains(&"Sec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=".into()));
+    (stream, reader)
+}
+
+/// The next text message from the server, or `None` once it closes the
+/// connection.
+fn next_text(reader: &mut impl Read) -> Option<String> {
+    loop {
+        match read_frame(reader)? {
+            (0x1, payload) => return Some(String::from_utf8(payload).unwrap()),
+            (0x8, _) => return None,
+            _ => {}
+        }
+    }
+}
+
+#[test]
+fn the_handshake_answers_the_rfc_example() {
+    assert_eq!(
+        accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+        "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+    );
+}
+
+#[test]
+fn commands_name_cells_by_coordinate() {
+    let universe = Universe::with_seed(vec![8, 6], 3, 1);
+    let idx = universe.get_index_from_coord(&[2, 5]).unwrap();
+    let other = universe.get_index_from_coord(&[7, 0]).unwrap();
+    assert_eq!(
+        command(&universe, "observe 2 5"),
+        Ok(Request::Intervene(Intervention::Observe(idx)))
+    );
+    assert_eq!(
+        command(&universe, "  disrupt 2   5 "),
+        Ok(Request::Intervene(Intervention::Disrupt(idx)))
+    );
+    assert_eq!(
+        command(&universe, "entangle 2 5 7 0"),
+        Ok(Request::Intervene(Intervention::Entangle(
+            universe.grid[idx].id,
+            universe.grid[other].id
+        )))
+    );
+    assert!(command(&universe, "").is_err());
+    assert!(command(&universe, "paint 1 1").is_err());
+    assert!(command(&universe, "clear 1").is_err());
+    assert!(command(&universe, "clear 1 x").is_err());
+    assert!(command(&universe, "operator 8 0").is_err());
+    assert_eq!(
+        command(&universe, "cursor 2 5"),
+        Ok(Request::Cursor(Some(vec![2, 5])))
+    );
+    assert_eq!(command(&universe, "cursor"), Ok(Request::Cursor(None)));
+    assert!(command(&universe, "cursor 9 9").is_err());
+}
+
+#[test]
+fn a_whole_universe_delta_rebuilds_it() {
+    let mut universe = Universe::with_seed(vec![12, 10], 3, 5);
+    for _ in 0..20 {
+        universe.tick();
+    }
+    let delta = UniverseDelta::decode(&universe.to_delta().encode()).unwrap();
+    let mut mirror = Universe::with_seed(vec![4, 4], 3, 9);
+    mirror.entanglement = EntanglementGroups::default();
+    mirror.apply_delta(&delta);
+    assert_eq!(mirror.grid_dims, universe.grid_dims);
+    assert_eq!(mirror.grid, universe.grid);
+    assert_eq!(mirror.entanglement, universe.entanglement);
+    assert_eq!(mirror.tick_count, universe.tick_count);
+}
+
+#[test]
+fn clients_follow_the_run_and_drive_it() {
+    let mut app = AppState::new(Config::new());
+    app.universe = Universe::with_seed(vec![16, 16], 3, 42);
+    app.worker = None;
+    app.timestep = None;
+    let target = app
+        .universe
+        .grid
+        .iter()
+        .position(|cell| cell.consciousness != ConsciousnessState::Operator)
+        .unwrap();
+    let coord = app.universe.get_coord_from_index(target);
+
+    let mut server = Server::bind("ws://127.0.0.1:0").unwrap();
+    server.ticks = Some(100);
+    server.frame = Duration::from_millis(2);
+    // Connecting before the server runs queues the client, so it cannot miss
+    // the run.
+    let stream = TcpStream::connect(server.local_addr().unwrap()).unwrap();
+    let client = thread::spawn(move || {
+        let (mut stream, mut reader) = join(stream);
+        let mut messages = Vec::new();
+        while let Some(message) = next_text(&mut reader) {
+            if messages.is_empty() {
+                send_text(&mut stream, &format!("operator {} {}", coord[0], coord[1]));
+                send_text(&mut stream, "bogus");
+            }
+            messages.push(message);
+        }
+        messages
+    });
+    server.run(&mut app).unwrap();
+    let messages = client.join().unwrap();
+
+    assert_eq!(messages[0], format!("you 0 {}", player_color(0)));
+    assert!(messages[1].starts_with("state "));
+    assert!(messages.contains(&"error: unknown command bogus".to_string()));
+    assert!(
+        app.universe
+            .interventions
+            .iter()
+            .any(|entry| entry.intervention == Intervention::SetOperator(target))
+    );
+
+    let mut mirror = Universe::with_seed(vec![2, 2], 3, 0);
+    mirror.entanglement = EntanglementGroups::default();
+    for message in &messages {
+        let code = message
+            .strip_prefix("state ")
+            .or_else(|| message.strip_prefix("delta "));
+        if let Some(code) = code {
+            mirror.apply_delta(&UniverseDelta::decode(code).unwrap());
+        }
+    }
+    assert_eq!(mirror.tick_count, app.universe.tick_count);
+    assert_eq!(mirror.grid, app.universe.grid);
+    assert_eq!(mirror.entanglement, app.universe.entanglement);
+}
+
+#[test]
+fn players_see_each_others_cursors() {
+    let mut app = AppState::new(Config::new());
+    app.universe = Universe::with_seed(vec![16, 16], 3, 7);
+    app.worker = None;
+    app.timestep = None;
+    let mut server = Server::bind("127.0.0.1:0").unwrap();
+    server.ticks = Some(200);
+    server.frame = Duration::from_millis(2);
+    let address = server.local_addr().unwrap();
+
+    // The first player waits to see the second's cursor, then shows its own
+    // and leaves.
+    let first = TcpStream::connect(address).unwrap();
+    let first = thread::spawn(move || {
+        let (mut stream, mut reader) = join(first);
+        let you = next_text(&mut reader).unwrap();
+        while let Some(message) = next_text(&mut reader) {
+            if message.starts_with("cursor ") {
+                send_text(&mut stream, "cursor 3 4");
+                stream.shutdown(std::net::Shutdown::Both).unwrap();
+                break;
+            }
+        }
+        you
+    });
+    let second = TcpStream::connect(address).unwrap();
+    let second = thread::spawn(move || {
+        let (mut stream, mut reader) = join(second);
+        let you = next_text(&mut reader).unwrap();
+        assert!(next_text(&mut reader).unwrap().starts_with("state "));
+        send_text(&mut stream, "cursor 1 1");
+        let mut messages = vec![you];
+        messages.extend(std::iter::from_fn(|| next_text(&mut reader)));
+        messages
+    });
+    server.run(&mut app).unwrap();
+    let you = first.join().unwrap();
+    let messages = second.join().unwrap();
+
+    let id: usize = you.split(' ').nth(1).unwrap().parse().unwrap();
+    assert_eq!(you, format!("you {} {}", id, player_color(id)));
+    assert_ne!(messages[0], you);
+    let shown = messages
+        .iter()
+        .position(|m| *m == format!("cursor {} {} 3 4", id, player_color(id)))
+        .unwrap();
+    let left = messages
+        .iter()
+        .position(|m| *m == format!("left {}", id))
+        .unwrap();
+    assert!(shown < left);
+}
@@ -0,0 +1,41 @@
+//! Tests of saving and restoring whole sessions.
+#![cfg(feature = "desktop")]
+
+mod common;
+
+use common::Driver;
+use existons::app::{AppState, Camera, Config, ToolMode};
+use existons::session::Session;
+use piston_window::Key;
+
+#[test]
+fn a_restored_session_reproduces_the_full_workspace() {
+    let mut driver = Driver::new(21);
+    driver.key(Key::D3).click_cell(3, 3).update().update();
+    driver.move_to_cell(10, 12).key(Key::W);
+    driver.key(Key::D2).click_cell(40, 40);
+    driver.app.camera = Camera {
+        offset: [-16.0, 8.0],
+        zoom: 2.0,
+    };
+    driver.app.overlays.entanglement_flashes = false;
+    driver.app.overlays.plot = true;
+    driver.key(Key::E);
+    assert!(driver.app.overlays.entanglement_links);
+
+    let text = Session::capture(&driver.app).to_text();
+    let mut restored = AppState::new(Config::new());
+    Session::from_text(&text).unwrap().restore(&mut restored);
+
+    let original = &driver.app;
+    assert_eq!(restored.universe.grid, original.universe.grid);
+    assert_eq!(restored.universe.tick_count, original.universe.tick_count);
+    assert_eq!(restored.current_tool, ToolMode::Entangle);
+    assert_eq!(restored.camera, original.camera);
+    assert_eq!(restored.overlays, original.overlays);
+    assert_eq!(
+        restored.entangle_first_partner,
+        original.entangle_first_partner
+    );
+    assert_eq!(restored.watch_list, vec![driver.index(10, 12)]);
+}
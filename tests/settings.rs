@@ -0,0 +1,138 @@
+//! Tests of the settings file and its live reload.
+#![cfg(feature = "desktop")]
+
+use existons::app::{AppState, Config};
+use existons::panel::Setting;
+use existons::settings::{SettingsError, SettingsFile, SettingsWatcher};
+use existons::universe::Topology;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+fn temp_path(name: &str) -> PathBuf {
+    std::env::temp_dir().join(format!("existons-{}-{}", std::process::id(), name))
+}
+
+fn app() -> AppState {
+    let mut app = AppState::new(Config::new());
+    app.autosave = None;
+    app
+}
+
+#[test]
+fn the_shipped_file_holds_the_compiled_in_settings() {
+    let file = SettingsFile::load(&Config::new().settings_path).unwrap();
+    assert_eq!(file.grid_dims, Some(vec![120, 80]));
+    assert_eq!(file.topology, Some(Topology::Square));
+    assert_eq!(file.text_color, Some([1.0, 1.0, 1.0, 0.9]));
+
+    let (mut config, defaults) = (Config::new(), Config::new());
+    file.apply(&mut config);
+    assert_eq!(config.window_size, defaults.window_size);
+    assert_eq!(config.ticks_per_second, defaults.ticks_per_second);
+    assert_eq!(config.decay_rate, defaults.decay_rate);
+    assert_eq!(config.font_size, defaults.font_size);
+    assert_eq!(config.disruption_strength, defaults.disruption_strength);
+    assert_eq!(config.disruption_falloff, defaults.disruption_falloff);
+    assert_eq!(
+        SettingsFile::load(&temp_path("missing.toml")).unwrap(),
+        SettingsFile::default()
+    );
+}
+
+#[test]
+fn a_file_shapes_the_universe_and_its_window() {
+    let file = SettingsFile::parse(
+        "[grid]\n\
+         dims = [30, 20, 4]\n\
+         cell_size = 12\n\
+         topology = 'hexagonal'\n\
+         [rates]\n\
+         decay = 0.25\n\
+         ticks_per_second = 0\n",
+    )
+    .unwrap();
+    let mut config = Config::new();
+    file.apply(&mut config);
+    assert_eq!(config.window_size, [360.0, 240.0]);
+    assert_eq!(config.slice.position.len(), 3);
+    assert_eq!(config.ticks_per_second, None);
+
+    let app = AppState::new(config);
+    assert_eq!(app.universe.grid_dims, [30, 20, 4]);
+    assert_eq!(app.universe.decay_rate, 0.25);
+    assert!(app.timestep.is_none());
+}
+
+#[test]
+fn mistakes_name_their_line() {
+    let error = |text: &str| SettingsFile::parse(text).unwrap_err();
+    assert!(matches!(
+        error("[rates]\nspeed = 2"),
+        SettingsError::UnknownSetting { line: 2, name } if name == "rates.speed"
+    ));
+    assert!(matches!(
+        error("[colors]\n\ntext = [1, 1, 1]"),
+        SettingsError::Invalid { line: 3, name } if name == "colors.text"
+    ));
+    assert!(matches!(
+        error("[grid]\ndims = [0, 4]"),
+        SettingsError::Invalid { line: 2, .. }
+    ));
+    assert!(matches!(
+        error("[tools]\ndisruption_falloff = 'steep'"),
+        SettingsError::Invalid { line: 2, .. }
+    ));
+    assert!(matches!(
+        error("[grid]\ndims = [1, 4"),
+        SettingsError::Syntax { line: 2 }
+    ));
+}
+
+#[test]
+fn saving_the_file_applies_what_changed() {
+    let path = temp_path("settings.toml");
+    std::fs::write(&path, "[rates]\ndecay = 0.2\nobservation = 0.1\n").unwrap();
+    let mut app = app();
+    let start = SettingsFile::load(&path).unwrap();
+    start.apply(&mut app.config);
+    let mut watcher = SettingsWatcher::new(&path, start);
+    let later = Instant::now() + SettingsWatcher::INTERVAL;
+    assert!(watcher.poll(later, &mut app).is_none());
+
+    // A rate changed in the panel survives a save that leaves it alone.
+    app.apply(existons::input::AppAction::SetSetting(
+        Setting::ObservationRate,
+        0.3,
+    ));
+    std::fs::write(
+        &path,
+        "[grid]\ncell_size = 10\n[rates]\ndecay = 0.4\nobservation = 0.1\n[colors]\nfont_size = 20\n",
+    )
+    .unwrap();
+    // Some filesystems keep modification times to the second.
+    let file = std::fs::File::options().append(true).open(&path).unwrap();
+    let stamp = std::time::SystemTime::now() + Duration::from_secs(5);
+    file.set_modified(stamp).unwrap();
+    assert!(watcher.poll(later, &mut app).is_none());
+    let restart = watcher
+        .poll(later + SettingsWatcher::INTERVAL, &mut app)
+        .unwrap()
+        .unwrap();
+    assert_eq!(restart, ["grid.cell_size"]);
+    assert_eq!(app.universe.decay_rate, 0.4);
+    assert_eq!(app.universe.observation_rate, 0.3);
+    assert_eq!(app.config.font_size, 20);
+    assert_eq!(app.config.cell_size, 8.0);
+
+    // A broken save is reported and changes nothing.
+    std::fs::write(&path, "[rates]\ndecay = lots\n").unwrap();
+    let file = std::fs::File::options().append(true).open(&path).unwrap();
+    file.set_modified(stamp + Duration::from_secs(5)).unwrap();
+    let result = watcher.poll(later + SettingsWatcher::INTERVAL * 2, &mut app);
+    assert!(matches!(
+        result,
+        Some(Err(SettingsError::Syntax { line: 2 }))
+    ));
+    assert_eq!(app.universe.decay_rate, 0.4);
+    std::fs::remove_file(&path).unwrap();
+}
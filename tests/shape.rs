@@ -0,0 +1,39 @@
+//! Tests of the shapes the Operator tool draws.
+
+use existons::shape::{self, Shape};
+
+#[test]
+fn lines_step_one_cell_at_a_time_between_their_ends() {
+    assert_eq!(
+        shape::line([0, 0], [4, 2]),
+        vec![[0, 0], [1, 1], [2, 1], [3, 2], [4, 2]]
+    );
+    assert_eq!(shape::line([2, 3], [2, 0]).len(), 4);
+    assert_eq!(shape::line([5, 5], [5, 5]), vec![[5, 5]]);
+}
+
+#[test]
+fn rectangles_are_outlines_with_each_cell_once() {
+    let points = Shape::Rectangle.points([3, 1], [0, 3]);
+    // A 4x3 outline has 4 + 4 + 1 + 1 cells; the middle row's inside is empty.
+    assert_eq!(points.len(), 10);
+    assert!(points.contains(&[0, 1]) && points.contains(&[3, 3]));
+    assert!(!points.contains(&[1, 2]));
+}
+
+#[test]
+fn circles_pass_through_the_end_of_the_drag() {
+    let points = Shape::Circle.points([10, 10], [13, 14]);
+    // The radius is 5, so the circle reaches 5 cells out along each axis.
+    for point in [[15, 10], [5, 10], [10, 15], [10, 5]] {
+        assert!(points.contains(&point));
+    }
+    assert!(!points.contains(&[10, 10]));
+    assert!(
+        points
+            .iter()
+            .all(|&[x, y]| ((x - 10).pow(2) + (y - 10).pow(2)) as f64 <= 5.5f64.powi(2))
+    );
+    assert_eq!(Shape::Circle.points([1, 1], [1, 1]), vec![[1, 1]]);
+    assert!(Shape::Freehand.points([0, 0], [3, 3]).is_empty());
+}
@@ -0,0 +1,111 @@
+//! Tests of viewing and editing one plane of a higher-dimensional grid.
+
+#[cfg(feature = "desktop")]
+mod common;
+
+#[cfg(feature = "desktop")]
+use existons::existon::ConsciousnessState;
+use existons::slice::Slice;
+
+#[test]
+fn a_slice_maps_plane_cells_to_grid_coordinates() {
+    let mut slice = Slice::new(4);
+    slice.position = vec![9, 9, 3, 5];
+    assert_eq!(slice.coord(4, 1, 2), vec![1, 2, 3, 5]);
+    assert_eq!(slice.project(&[1, 2, 7, 7]), [1, 2]);
+    assert!(slice.contains(&[6, 6, 3, 5]));
+    assert!(!slice.contains(&[6, 6, 4, 5]));
+
+    let dims = [8, 8, 4, 6];
+    slice.step(&dims, true);
+    assert_eq!(slice.position[2], 0);
+    slice.step(&dims, false);
+    assert_eq!(slice.position[2], 3);
+    slice.cycle_depth(4);
+    assert_eq!(slice.depth, 3);
+    slice.step(&dims, true);
+    assert_eq!(slice.position[3], 0);
+    assert_eq!(slice.describe(&dims), "Plane x0 x1 x2=3/4 [x3=0/6]");
+
+    let mut planes = Vec::new();
+    for _ in 0..6 {
+        slice.cycle_axes(4);
+        planes.push(slice.axes);
+        assert!(!slice.axes.contains(&slice.depth));
+    }
+    assert_eq!(planes, [[0, 2], [0, 3], [1, 2], [1, 3], [2, 3], [0, 1]]);
+    assert_eq!(slice.coord(4, 1, 2), vec![1, 2, 3, 0]);
+    slice.cycle_axes(4);
+    assert_eq!(slice.coord(4, 1, 2), vec![1, 9, 2, 0]);
+    assert_eq!(slice.project(&[1, 9, 2, 0]), [1, 2]);
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn paging_and_turning_the_view_reach_every_layer() {
+    use common::Driver;
+    use existons::app::get_pos_from_coord;
+    use existons::universe::Universe;
+    use piston_window::{Button, Key, MouseButton};
+    const LEFT: Button = Button::Mouse(MouseButton::Left);
+
+    let mut driver = Driver::new(50);
+    let dims = vec![16, 16, 16];
+    driver.app.universe = Universe::with_seed(dims.clone(), 2, 50);
+    driver.app.config.grid_dims = dims.clone();
+    driver.app.config.slice = Slice::new(3);
+    driver.key(Key::D3);
+
+    for _ in 0..3 {
+        driver.key(Key::PageUp);
+    }
+    driver.key(Key::PageDown);
+    assert_eq!(driver.app.config.slice.position[2], 2);
+    driver.click_cell(4, 5);
+    let operator = |driver: &Driver, coord: &[usize]| {
+        let universe = &driver.app.universe;
+        let idx = universe.get_index_from_coord(coord).unwrap();
+        universe.grid[idx].consciousness == ConsciousnessState::Operator
+    };
+    assert!(operator(&driver, &[4, 5, 2]));
+    assert!(!operator(&driver, &[4, 5, 0]));
+
+    // Selections span the plane and keep the layer they started in.
+    driver.key(Key::D6);
+    driver.move_to_cell(1, 1).press(LEFT);
+    driver.move_to_cell(3, 4).update().release(LEFT);
+    assert_eq!(driver.app.selection, Some(vec![1..4, 1..5, 2..3]));
+    driver.key(Key::D3);
+
+    // Looking along y, columns are x and rows are z.
+    driver.key(Key::X);
+    assert_eq!(driver.app.config.slice.axes, [0, 2]);
+    assert_eq!(driver.app.config.slice.depth, 1);
+    // Cells are drawn where their coordinates fall in the plane.
+    let config = &driver.app.config;
+    let topology = driver.app.universe.topology();
+    let pos = get_pos_from_coord(&[7, 1, 3], config, topology).map(|p| p + 1.0);
+    driver
+        .key(Key::PageUp)
+        .move_to(pos)
+        .press(LEFT)
+        .release(LEFT);
+    assert!(operator(&driver, &[7, 1, 3]));
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn flat_grids_have_no_depth_to_page_through() {
+    use common::Driver;
+    use piston_window::Key;
+
+    let mut driver = Driver::new(51);
+    driver.key(Key::D3).key(Key::PageUp).key(Key::X);
+    assert_eq!(driver.app.config.slice, Slice::new(2));
+    let dims = driver.app.universe.grid_dims.clone();
+    assert_eq!(driver.app.config.slice.describe(&dims), "");
+    driver.click_cell(2, 3);
+    let idx = driver.index(2, 3);
+    let cell = &driver.app.universe.grid[idx];
+    assert_eq!(cell.consciousness, ConsciousnessState::Operator);
+}
@@ -0,0 +1,92 @@
+//! Tests of how ticks are turned into sound.
+
+use existons::sonify::{self, NOTES_PER_VOICE, Sonifier, Voice};
+use existons::universe::TickReport;
+
+#[test]
+fn columns_climb_a_pentatonic_scale_in_each_voices_register() {
+    assert_eq!(sonify::pitch(Voice::Decay, 0, 150), 110.0);
+    assert_eq!(sonify::pitch(Voice::Observation, 0, 150), 220.0);
+    assert_eq!(sonify::pitch(Voice::Entanglement, 0, 150), 440.0);
+    // Every tenth column of 150 is a step up the scale; five steps make an octave.
+    let fifth = sonify::pitch(Voice::Observation, 30, 150);
+    assert!((fifth - 220.0 * 2f64.powf(7.0 / 12.0)).abs() < 1e-9);
+    assert!((sonify::pitch(Voice::Observation, 50, 150) - 440.0).abs() < 1e-9);
+    let top = sonify::pitch(Voice::Observation, 149, 150);
+    assert!(top < 880.0 * 2f64.powf(10.0 / 12.0));
+    assert_eq!(top, sonify::pitch(Voice::Observation, 500, 150));
+}
+
+#[test]
+fn events_sound_only_once_unmuted_and_louder_when_frequent() {
+    let report = TickReport {
+        observations: (0..10).map(|x| vec![x * 10, 0]).collect(),
+        decays: vec![vec![50, 3]],
+        ..TickReport::default()
+    };
+    let mut sonifier = Sonifier::default();
+    assert!(sonifier.muted);
+    sonifier.listen(&report, &[100, 10]);
+    assert!(sonifier.take().is_empty());
+    assert!(sonifier.level(Voice::Observation) > sonifier.level(Voice::Decay));
+
+    sonifier.toggle_mute();
+    sonifier.listen(&report, &[100, 10]);
+    let tones = sonifier.take();
+    let voiced = |voice| tones.iter().filter(|t| t.voice == voice).count();
+    assert_eq!(voiced(Voice::Observation), NOTES_PER_VOICE);
+    assert_eq!(voiced(Voice::Decay), 1);
+    assert_eq!(voiced(Voice::Entanglement), 0);
+    assert!(tones.iter().all(|t| t.volume > 0.0 && t.volume <= 1.0));
+    assert!(sonifier.take().is_empty());
+
+    // Quiet ticks let the volume fade instead of cutting it.
+    let before = sonifier.level(Voice::Observation);
+    sonifier.listen(&TickReport::default(), &[100, 10]);
+    let after = sonifier.level(Voice::Observation);
+    assert!(after < before && after > 0.0);
+}
+
+#[test]
+fn unheard_tones_do_not_pile_up() {
+    let report = TickReport {
+        observations: vec![vec![1, 1]; 5],
+        ..TickReport::default()
+    };
+    let mut sonifier = Sonifier::default();
+    sonifier.toggle_mute();
+    for _ in 0..1000 {
+        sonifier.listen(&report, &[10, 10]);
+    }
+    assert!(sonifier.take().len() <= 64);
+}
+
+#[cfg(feature = "audio")]
+#[test]
+fn the_synth_rings_each_note_for_its_length_then_falls_silent() {
+    use existons::audio::{NOTE_SECONDS, Synth};
+
+    let mut sonifier = Sonifier::default();
+    sonifier.toggle_mute();
+    sonifier.listen(
+        &TickReport {
+            observations: vec![vec![0, 0]],
+            ..TickReport::default()
+        },
+        &[1, 1],
+    );
+    let mut synth = Synth::new(1000);
+    synth.play(&sonifier.take());
+    assert_eq!(synth.sounding(), 1);
+
+    let mut out = vec![0.0; (NOTE_SECONDS * 1000.0) as usize - 1];
+    synth.fill(&mut out);
+    assert!(out.iter().any(|&s| s.abs() > 0.01));
+    assert!(out.iter().all(|&s| (-1.0..=1.0).contains(&s)));
+    assert_eq!(synth.sounding(), 1);
+
+    let mut rest = vec![1.0; 10];
+    synth.fill(&mut rest);
+    assert_eq!(synth.sounding(), 0);
+    assert!(rest[1..].iter().all(|&s| s == 0.0));
+}
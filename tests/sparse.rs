@@ -0,0 +1,68 @@
+use existons::existon::{ConsciousnessState, Existon};
+use existons::rule::{RuleCtx, UpdateRule};
+use existons::sparse::SparseUniverse;
+use existons::universe::{RegionOp, Topology, Universe};
+
+/// Leaves every cell as it is.
+#[derive(Debug)]
+struct Frozen;
+
+impl UpdateRule for Frozen {
+    fn apply(&self, cell: &Existon, _: &[&Existon], _: &RuleCtx) -> Existon {
+        cell.clone()
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn sparse_ticks_match_a_dense_universe_for_deterministic_runs() {
+    for topology in Topology::ALL {
+        let mut universe = Universe::with_seed(vec![80, 48], 3, 21).with_topology(topology);
+        universe.observation_rate = 0.0;
+        universe.decay_rate = 0.0;
+        universe.fluctuation_rate = 0.0;
+        universe.entanglement.clear();
+        universe.apply_region(&[0..80, 0..48], RegionOp::Clear);
+        universe.apply_region(&[28..38, 26..36], RegionOp::Randomize);
+        universe.set_operator(&[70, 5]);
+        let mut sparse = SparseUniverse::from_universe(&universe);
+        assert_eq!(sparse.chunk_count(), 5);
+
+        for _ in 0..30 {
+            let (dense, quiet) = (universe.tick(), sparse.tick());
+            assert_eq!(quiet.observations, dense.observations);
+            assert_eq!(quiet.totals_change, dense.totals_change);
+        }
+        for (idx, cell) in universe.grid.iter().enumerate() {
+            let coord = universe.get_coord_from_index(idx);
+            let stored = sparse.get(&coord).unwrap();
+            assert_eq!(
+                (stored.consciousness, &stored.state),
+                (cell.consciousness, &cell.state)
+            );
+        }
+    }
+}
+
+#[test]
+fn quiet_chunks_fall_asleep_and_vacuum_stays_unstored() {
+    let mut sparse = SparseUniverse::new(vec![4096, 4096], 3, 22).with_rule(Frozen);
+    sparse.randomize_region(&[100..104, 100..104]);
+    assert_eq!((sparse.chunk_count(), sparse.awake_chunk_count()), (1, 1));
+    for _ in 0..4 {
+        sparse.tick();
+    }
+    assert_eq!((sparse.chunk_count(), sparse.awake_chunk_count()), (1, 0));
+
+    // Operators keep their chunk awake.
+    sparse.set_operator(&[4000, 10]);
+    sparse.tick();
+    assert_eq!((sparse.chunk_count(), sparse.awake_chunk_count()), (2, 1));
+    let operator = sparse.get(&[4000, 10]).unwrap();
+    assert_eq!(operator.consciousness, ConsciousnessState::Operator);
+    assert_eq!(operator.id, 10 * 4096 + 4000);
+    assert_eq!(sparse.get(&[4096, 0]), None);
+}
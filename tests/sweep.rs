@@ -0,0 +1,84 @@
+//! Tests of parameter sweeps.
+#![cfg(feature = "desktop")]
+
+use existons::settings::SettingsError;
+use existons::sweep::{RunSummary, Sweep};
+use std::fs;
+
+#[test]
+fn sweep_files_list_every_combination_with_the_seed_fastest() {
+    let sweep = Sweep::parse(
+        "[grid]\ndims = [8, 8]\nga_dims = 2\n\n[sweep]\nobservation = [0.001, 0.002]\n\
+         decay = 0.02\nseeds = [4, 5, 6]\nticks = 7\nout = \"results\"\n",
+    )
+    .unwrap();
+    assert_eq!(sweep.grid_dims, [8, 8]);
+    assert_eq!(sweep.decay_rates, [0.02]);
+    assert_eq!((sweep.ticks, sweep.out_dir.to_str()), (7, Some("results")));
+    let runs = sweep.runs();
+    assert_eq!(runs.len(), 6);
+    assert_eq!((runs[1].seed, runs[1].observation_rate), (5, 0.001));
+    assert_eq!((runs[5].index, runs[5].observation_rate), (5, 0.002));
+
+    assert!(matches!(
+        Sweep::parse("[sweep]\ndecay = [1.5]\n"),
+        Err(SettingsError::Invalid { line: 2, .. })
+    ));
+    assert!(matches!(
+        Sweep::parse("[sweep]\nseeds = []\n"),
+        Err(SettingsError::Invalid { line: 2, .. })
+    ));
+    assert!(matches!(
+        Sweep::parse("[sweep]\nsteps = 10\n"),
+        Err(SettingsError::UnknownSetting { line: 2, .. })
+    ));
+}
+
+#[test]
+fn sweeps_write_a_log_per_run_and_a_summary() {
+    let out = std::env::temp_dir().join(format!("existons-sweep-{}", std::process::id()));
+    let sweep = Sweep {
+        grid_dims: vec![10, 10],
+        ga_dims: 2,
+        observation_rates: vec![0.0, 0.5],
+        seeds: vec![1, 2, 3, 4, 5, 6],
+        ticks: 4,
+        out_dir: out.clone(),
+        ..Sweep::default()
+    };
+    let summaries = sweep.run(|_| {}).unwrap();
+    assert_eq!(summaries.len(), 12);
+    assert!(
+        summaries
+            .iter()
+            .enumerate()
+            .all(|(i, s)| s.params.index == i)
+    );
+    // Runs are independent of the threads they land on.
+    let again = sweep.run_one(
+        &sweep.runs()[7],
+        &mut existons::series::StatsLog::append(&out.join("again.csv")).unwrap(),
+    );
+    assert_eq!(again.unwrap(), summaries[7]);
+    let busier = summaries[6..].iter().map(|s| s.observations).sum::<usize>();
+    assert!(busier > summaries[..6].iter().map(|s| s.observations).sum::<usize>());
+
+    let log = fs::read_to_string(out.join("run-07.csv")).unwrap();
+    assert_eq!(log.lines().count(), 5);
+    assert!(log.lines().nth(4).unwrap().starts_with("2,4,"));
+    let summary = fs::read_to_string(out.join("summary.csv")).unwrap();
+    let lines: Vec<&str> = summary.lines().collect();
+    assert_eq!(lines[0], RunSummary::HEADER);
+    assert_eq!(lines[8], summaries[7].to_csv());
+
+    // Running again replaces the logs rather than adding to them.
+    sweep.run(|_| {}).unwrap();
+    assert_eq!(
+        fs::read_to_string(out.join("run-07.csv"))
+            .unwrap()
+            .lines()
+            .count(),
+        5
+    );
+    fs::remove_dir_all(&out).unwrap();
+}
@@ -0,0 +1,74 @@
+//! Tests of pacing ticks by real time.
+
+#[cfg(feature = "desktop")]
+mod common;
+
+#[cfg(feature = "desktop")]
+use existons::existon::ConsciousnessState;
+use existons::timestep::FixedTimestep;
+
+#[test]
+fn real_time_is_paid_out_in_whole_ticks() {
+    let mut timestep = FixedTimestep::new(10.0);
+    assert_eq!(timestep.advance(0.05), 0);
+    assert_eq!(timestep.advance(0.05), 1);
+    timestep.record(1);
+    assert_eq!(timestep.advance(0.25), 2);
+    timestep.record(2);
+    assert!((timestep.alpha() - 0.5).abs() < 1e-9);
+
+    // A long frame runs at most the cap, and the rest is drift.
+    assert_eq!(timestep.advance(1.0), 4);
+    timestep.record(4);
+    assert_eq!(timestep.ticks(), 7);
+    assert!((timestep.elapsed() - 1.35).abs() < 1e-9);
+    assert!((timestep.drift() - 0.65).abs() < 1e-9);
+    assert_eq!(timestep.alpha(), 1.0);
+    assert_eq!(timestep.describe(), "10 t/s  drift 0.65s");
+    // Ticks still due are paid out on later frames.
+    assert_eq!(timestep.advance(0.0), 4);
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn frames_run_the_ticks_their_time_is_worth() {
+    use common::Driver;
+    use existons::input::AppAction;
+    use piston_window::Key;
+
+    // The driver's frames are a sixtieth of a second, one tick by default.
+    let mut driver = Driver::new(5);
+    driver.key(Key::D3).click_cell(4, 4);
+    for _ in 0..3 {
+        driver.update();
+    }
+    assert_eq!(driver.app.universe.tick_count, 3);
+
+    driver.app.timestep = Some(FixedTimestep::new(30.0));
+    driver.update();
+    assert_eq!(driver.app.universe.tick_count, 3);
+    driver.update();
+    assert_eq!(driver.app.universe.tick_count, 4);
+
+    driver.app.timestep = Some(FixedTimestep::new(180.0));
+    driver.update();
+    assert_eq!(driver.app.universe.tick_count, 7);
+
+    // Paused time is not owed afterwards.
+    driver.app.apply(AppAction::TogglePause);
+    driver.update().update();
+    driver.app.apply(AppAction::TogglePause);
+    driver.update();
+    assert_eq!(driver.app.universe.tick_count, 10);
+    let timestep = driver.app.timestep.as_ref().unwrap();
+    assert_eq!(timestep.ticks(), 6);
+    assert!(timestep.drift().abs() < 1e-9);
+
+    // Without a timestep every frame is one tick, as is an explicit tick.
+    driver.app.timestep = None;
+    driver.update();
+    driver.app.apply(AppAction::Tick);
+    assert_eq!(driver.app.universe.tick_count, 12);
+    let operator = &driver.app.universe.grid[driver.index(4, 4)];
+    assert_eq!(operator.consciousness, ConsciousnessState::Operator);
+}
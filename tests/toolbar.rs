@@ -0,0 +1,40 @@
+//! Tests of the toolbar's layout and hit-testing.
+#![cfg(feature = "desktop")]
+
+use existons::app::ToolMode;
+use existons::input::AppAction;
+use existons::toolbar::{Toolbar, ToolbarItem};
+
+const WINDOW: [f64; 2] = [960.0, 640.0];
+
+#[test]
+fn items_are_laid_out_left_to_right_along_the_bottom() {
+    let items = Toolbar::items(WINDOW);
+    assert_eq!(items.len(), ToolbarItem::ALL.len());
+    assert_eq!(
+        items[0],
+        (
+            ToolbarItem::Tool(ToolMode::Observe),
+            [20.0, 600.0, 105.0, 40.0]
+        )
+    );
+    assert_eq!(items[7], (ToolbarItem::Pause, [755.0, 600.0, 70.0, 40.0]));
+    assert_eq!(Toolbar::end(WINDOW), 945.0);
+}
+
+#[test]
+fn points_find_the_item_under_them() {
+    assert_eq!(
+        Toolbar::item_at(WINDOW, [300.0, 620.0]),
+        Some(ToolbarItem::Tool(ToolMode::Operator))
+    );
+    assert_eq!(
+        Toolbar::item_at(WINDOW, [900.0, 620.0]),
+        Some(ToolbarItem::Save)
+    );
+    // The margin and the end of the bar are on the toolbar but not on an item.
+    assert!(Toolbar::contains(WINDOW, [10.0, 620.0]));
+    assert_eq!(Toolbar::item_at(WINDOW, [10.0, 620.0]), None);
+    assert_eq!(Toolbar::item_at(WINDOW, [300.0, 580.0]), None);
+    assert_eq!(ToolbarItem::Reset.action(), AppAction::ResetUniverse);
+}
@@ -0,0 +1,86 @@
+//! Tests of the terminal frontend's key decoding and drawing.
+#![cfg(feature = "desktop")]
+
+use existons::app::{AppState, Config, ToolMode};
+use existons::existon::ConsciousnessState;
+use existons::tui::{TermKey, Tui, parse_keys};
+
+fn app() -> AppState {
+    let mut app = AppState::new(Config::new());
+    app.autosave = None;
+    app
+}
+
+#[test]
+fn terminal_bytes_decode_to_keys() {
+    let keys = parse_keys(b"3\x1b[A\x1b[B\x1bOC\x1b[D\r\x1a\x1b[21~\x1bOP\x1b[5~\x1b[99X\x1b1\x1b");
+    assert_eq!(
+        keys,
+        [
+            TermKey::Char('3'),
+            TermKey::Up,
+            TermKey::Down,
+            TermKey::Right,
+            TermKey::Left,
+            TermKey::Enter,
+            TermKey::Ctrl('z'),
+            TermKey::F(10),
+            TermKey::F(1),
+            TermKey::PageUp,
+            TermKey::Alt('1'),
+            TermKey::Esc,
+        ]
+    );
+}
+
+#[test]
+fn keys_drive_the_same_tools_as_the_window() {
+    let mut app = app();
+    let mut tui = Tui::default();
+    tui.handle(TermKey::Char('3'), &mut app);
+    assert_eq!(app.current_tool, ToolMode::Operator);
+    for key in [TermKey::Right, TermKey::Right, TermKey::Down, TermKey::Left] {
+        tui.handle(key, &mut app);
+    }
+    tui.handle(TermKey::Up, &mut app);
+    tui.handle(TermKey::Up, &mut app);
+    assert_eq!(tui.cursor, [1, 0]);
+
+    tui.handle(TermKey::Enter, &mut app);
+    let idx = app.universe.get_index_from_coord(&[1, 0]).unwrap();
+    assert_eq!(
+        app.universe.grid[idx].consciousness,
+        ConsciousnessState::Operator
+    );
+    tui.handle(TermKey::Ctrl('z'), &mut app);
+    assert_ne!(
+        app.universe.grid[idx].consciousness,
+        ConsciousnessState::Operator
+    );
+    // Undoing pauses the run, as in the window, and Space resumes it.
+    assert!(app.paused);
+    tui.handle(TermKey::Char(' '), &mut app);
+    assert!(!app.paused);
+    assert!(!tui.quit);
+    tui.handle(TermKey::Char('q'), &mut app);
+    assert!(tui.quit);
+}
+
+#[test]
+fn frames_pack_two_cells_into_each_character_beside_a_sidebar() {
+    let mut app = app();
+    let mut tui = Tui::default();
+    tui.handle(TermKey::Char('3'), &mut app);
+    tui.cursor = [50, 40];
+    let frame = tui.render(&app, [72, 20]);
+    let lines: Vec<&str> = frame.split("\r\n").collect();
+    assert_eq!(lines.len(), 20);
+    // 72 columns leave 39 for the grid, each a half block.
+    assert_eq!(lines[0].matches('\u{2580}').count(), 39);
+    assert!(frame.starts_with("\x1b[H"));
+    assert!(lines[0].contains("Existon Automaton"));
+    assert!(lines[2].contains("Tick 0"));
+    assert!(lines[3].contains("Tool Operator"));
+    assert!(lines[4].contains("Cursor [50, 40] "));
+    assert!(frame.contains("\x1b[38;2;255;255;255m"));
+}
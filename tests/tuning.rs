@@ -0,0 +1,40 @@
+//! Tests of tick tuning and the preferences cache.
+
+use existons::tuning::{PreferencesCache, TickTuning, config_hash};
+use existons::universe::Universe;
+
+#[test]
+fn tuning_does_not_change_results() {
+    let mut reference = Universe::with_seed(vec![40, 30], 3, 5);
+    reference.set_operator(&[3, 4]);
+    let mut tuned = reference.clone();
+    tuned.tuning = TickTuning {
+        threads: 3,
+        tile_size: 17,
+        snapshot_interval: 1,
+    };
+    for _ in 0..5 {
+        assert_eq!(tuned.tick(), reference.tick());
+    }
+    assert_eq!(tuned.grid, reference.grid);
+}
+
+#[test]
+fn preferences_round_trip_and_skip_calibration_on_a_hit() {
+    let universe = Universe::with_seed(vec![8, 8], 2, 1);
+    let cached = TickTuning {
+        threads: 2,
+        tile_size: 64,
+        snapshot_interval: 7,
+    };
+    let mut cache = PreferencesCache::default();
+    cache.entries.insert(config_hash(&universe), cached);
+
+    let path = std::env::temp_dir().join(format!("existons-prefs-{}.txt", std::process::id()));
+    cache.save(&path).unwrap();
+    let mut loaded = PreferencesCache::load(&path);
+    std::fs::remove_file(&path).unwrap();
+
+    assert_eq!(loaded, cache);
+    assert_eq!(loaded.tuning_for(&universe), cached);
+}
@@ -0,0 +1,1075 @@
+//! Tests of the `Universe` grid rules.
+
+use existons::delta::UniverseDelta;
+use existons::entanglement::{CollapseSpeed, Decoherence, PairOperator};
+use existons::existon::{CollapsePolicy, ConsciousnessState, Existon};
+use existons::ga_core::{Mod3, Multivector};
+use existons::rule::{MatzkeDefaultRule, RuleCtx, UpdateRule};
+use existons::share::Scenario;
+use existons::tuning::TickTuning;
+use existons::universe::{
+    BoundaryCondition, EntanglementLink, Neighborhood, OperatorPreset, RegionOp, Topology, Universe,
+};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use std::time::Duration;
+
+#[test]
+fn boundary_conditions_resolve_off_grid_coordinates() {
+    assert_eq!(BoundaryCondition::Toroidal.resolve(-1, 10), Some(9));
+    assert_eq!(BoundaryCondition::Toroidal.resolve(10, 10), Some(0));
+    assert_eq!(BoundaryCondition::Fixed.resolve(-1, 10), None);
+    assert_eq!(BoundaryCondition::Fixed.resolve(10, 10), None);
+    assert_eq!(BoundaryCondition::Reflective.resolve(-1, 10), Some(0));
+    assert_eq!(BoundaryCondition::Reflective.resolve(-3, 10), Some(2));
+    assert_eq!(BoundaryCondition::Reflective.resolve(11, 10), Some(8));
+    for b in BoundaryCondition::ALL {
+        assert_eq!(b.resolve(4, 10), Some(4));
+    }
+}
+
+#[test]
+fn boundary_changes_are_logged_and_replayed() {
+    let mut universe =
+        Universe::with_seed(vec![16, 16], 3, 11).with_boundary(BoundaryCondition::Fixed);
+    universe.tick();
+    universe.set_boundary(BoundaryCondition::Reflective);
+    universe.tick();
+
+    let replayed = Universe::with_seed(vec![16, 16], 3, 11).replay(&universe.interventions, 2);
+    assert_eq!(replayed.boundary, BoundaryCondition::Reflective);
+    assert_eq!(replayed.grid, universe.grid);
+}
+
+#[test]
+fn neighborhoods_expand_to_the_expected_offsets() {
+    assert_eq!(Neighborhood::VonNeumann.offsets(2).len(), 4);
+    assert_eq!(Neighborhood::VonNeumann.offsets(3).len(), 6);
+    assert_eq!(Neighborhood::Moore { radius: 1 }.offsets(2).len(), 8);
+    assert_eq!(Neighborhood::Moore { radius: 2 }.offsets(2).len(), 24);
+    assert_eq!(Neighborhood::Moore { radius: 1 }.offsets(3).len(), 26);
+
+    let moore = Neighborhood::Moore { radius: 1 }.offsets(2);
+    assert!(moore.contains(&vec![-1, -1]));
+    assert!(!moore.contains(&vec![0, 0]));
+
+    let custom = Neighborhood::Custom(vec![vec![2, 0], vec![0, 0], vec![1, 1, 1]]);
+    assert_eq!(custom.offsets(2), vec![vec![2, 0]]);
+}
+
+#[test]
+fn share_codes_carry_the_neighborhood() {
+    let universe = Universe::with_seed(vec![12, 12], 3, 5)
+        .with_neighborhood(Neighborhood::Custom(vec![vec![-2, 1], vec![3, 0]]));
+    let code = Scenario::capture(&universe).encode();
+    let rebuilt = Scenario::decode(&code).unwrap().build();
+    assert_eq!(rebuilt.neighborhood(), universe.neighborhood());
+}
+
+#[test]
+fn tick_reports_every_kind_of_event() {
+    let mut universe = Universe::with_seed(vec![6, 6], 3, 4);
+    universe.set_operator(&[2, 2]);
+    universe.observation_rate = 0.0;
+    universe.decay_rate = 0.0;
+    universe.fluctuation_rate = 1.0;
+    let report = universe.tick();
+    assert_eq!(report.tick, 0);
+    assert_eq!(report.fluctuations.len(), 35);
+    assert!(report.observations.is_empty());
+    let mut influenced: Vec<_> = report
+        .operator_interactions
+        .iter()
+        .map(|(operator, cell)| {
+            assert_eq!(operator, &vec![2, 2]);
+            cell.clone()
+        })
+        .collect();
+    influenced.sort();
+    assert_eq!(influenced.len(), 8);
+    assert_eq!(influenced[0], vec![1, 1]);
+
+    universe.fluctuation_rate = 0.0;
+    universe.observation_rate = 1.0;
+    let report = universe.tick();
+    assert_eq!(report.observations.len(), 35);
+    assert!(report.entanglements.is_empty());
+
+    universe.observation_rate = 0.0;
+    universe.decay_rate = 1.0;
+    assert_eq!(universe.tick().decays.len(), 35);
+}
+
+#[test]
+fn budgeted_ticks_resume_across_calls_and_match_whole_ticks() {
+    let mut whole = Universe::with_seed(vec![40, 40], 3, 6);
+    whole.observation_rate = 0.05;
+    let mut budgeted = whole.clone();
+
+    let expected = whole.tick();
+    let mut calls = 1;
+    let report = loop {
+        if let Some(report) = budgeted.tick_within(Duration::ZERO) {
+            break report;
+        }
+        assert!(budgeted.is_mid_tick());
+        calls += 1;
+    };
+    assert!(calls > 1);
+    assert_eq!(report, expected);
+    assert_eq!(budgeted.grid, whole.grid);
+
+    // An intervention mid-tick waits for the tick to finish, so replays still agree.
+    assert!(budgeted.tick_within(Duration::ZERO).is_none());
+    budgeted.set_operator(&[1, 1]);
+    assert!(budgeted.interventions.is_empty());
+    while budgeted.tick_within(Duration::ZERO).is_none() {}
+    assert_eq!(budgeted.interventions.len(), 1);
+    assert_eq!(budgeted.interventions[0].tick, 2);
+    let mut fresh = Universe::with_seed(vec![40, 40], 3, 6);
+    fresh.observation_rate = 0.05;
+    let replayed = fresh.replay(&budgeted.interventions, 2);
+    assert_eq!(replayed.grid, budgeted.grid);
+}
+
+/// Observes exactly the listed cells and leaves everything else alone.
+#[derive(Debug)]
+struct ObserveOnly(Vec<u64>);
+
+impl UpdateRule for ObserveOnly {
+    fn apply(&self, cell: &Existon, _: &[&Existon], ctx: &RuleCtx) -> Existon {
+        let mut next = cell.clone();
+        if self.0.contains(&cell.id) {
+            next.observe(ctx.collapse_policy);
+        }
+        next
+    }
+
+    fn is_deterministic(&self) -> bool {
+        true
+    }
+}
+
+#[test]
+fn entanglement_conflicts_resolve_deterministically() {
+    let mut universe =
+        Universe::with_seed(vec![4, 4], 3, 3).with_rule(ObserveOnly(vec![0, 1, 2, 3]));
+    universe.entanglement.clear();
+    // Both partners observed together: nothing fires.
+    universe.entanglement.entangle(&[1, 2]);
+    // Two observed members share a partner: it collapses once, for the lower one.
+    universe.entanglement.entangle(&[0, 3, 9]);
+    let report = universe.tick();
+    assert_eq!(report.entanglements, vec![(vec![0, 0], vec![1, 2])]);
+}
+
+#[test]
+fn observing_one_member_collapses_the_whole_group() {
+    let mut universe = Universe::with_seed(vec![4, 4], 3, 3).with_rule(ObserveOnly(vec![5]));
+    universe.entanglement.clear();
+    for existon in &mut universe.grid {
+        existon.consciousness = ConsciousnessState::Potential;
+    }
+    universe.entanglement.entangle(&[5, 10, 15]);
+    let report = universe.tick();
+    assert_eq!(
+        report.entanglements,
+        vec![(vec![1, 1], vec![2, 2]), (vec![1, 1], vec![3, 3]),]
+    );
+    for idx in [5, 10, 15] {
+        assert_eq!(
+            universe.grid[idx].consciousness,
+            ConsciousnessState::Observed
+        );
+    }
+}
+
+#[test]
+fn entangling_a_new_member_joins_the_existing_group() {
+    let mut universe = Universe::with_seed(vec![4, 4], 3, 3);
+    universe.entanglement.clear();
+    universe.entangle_pair(1, 2);
+    universe.entangle_pair(1, 7);
+    assert_eq!(universe.entanglement.len(), 1);
+    assert_eq!(
+        universe.entanglement.partners(7).collect::<Vec<_>>(),
+        vec![1, 2]
+    );
+    // An ID that is already entangled cannot be pulled into another group.
+    universe.entangle_pair(3, 2);
+    assert_eq!(universe.entanglement.len(), 1);
+    assert!(!universe.entanglement.is_entangled(3));
+}
+
+#[test]
+fn entanglement_links_join_each_group_to_its_first_member() {
+    let mut universe = Universe::with_seed(vec![4, 4], 3, 3);
+    universe.entanglement.clear();
+    universe.entangle_group(&[7, 2, 5]);
+    universe.entangle_pair(9, 12);
+    let pair = universe.entanglement.group_of(9).unwrap();
+
+    let links = universe.entanglement_links();
+    let ends: Vec<_> = links.iter().map(|link| (link.from, link.to)).collect();
+    let index = |id| universe.index_of_id(id).unwrap();
+    assert_eq!(
+        ends,
+        vec![
+            (index(2), index(5)),
+            (index(2), index(7)),
+            (index(9), index(12)),
+        ]
+    );
+    assert_eq!(
+        links[2],
+        EntanglementLink {
+            group: pair,
+            from: index(9),
+            to: index(12),
+            strength: 1.0,
+        }
+    );
+}
+
+#[test]
+fn decohered_groups_break_unless_they_keep_firing() {
+    let mut universe = Universe::with_seed(vec![4, 4], 3, 3).with_rule(ObserveOnly(vec![1]));
+    universe.entanglement.clear();
+    universe.decoherence = Decoherence {
+        rate: 0.5,
+        threshold: 0.2,
+        refresh_on_fire: true,
+    };
+    universe.entangle_group(&[1, 2, 3]);
+    universe.entangle_pair(4, 5);
+    let (fired, idle) = (
+        universe.entanglement.group_of(1).unwrap(),
+        universe.entanglement.group_of(4).unwrap(),
+    );
+
+    assert_eq!(universe.tick().entanglements.len(), 2);
+    universe.tick();
+    assert_eq!(universe.entanglement.strength(fired), 0.25);
+    assert_eq!(universe.entanglement.strength(idle), 0.25);
+
+    // Back to Potential, so the rule observes cell 1 again and its group fires.
+    universe.apply_region(&[1..4, 0..1], RegionOp::Disrupt);
+    let report = universe.tick();
+    assert_eq!(report.entanglements.len(), 2);
+    assert_eq!(report.decoherences, vec![vec![vec![0, 1], vec![1, 1]]]);
+    assert_eq!(universe.entanglement.strength(fired), 0.5);
+    assert!(!universe.entanglement.is_entangled(4));
+
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.decoherence, universe.decoherence);
+}
+
+#[test]
+fn parallel_entanglement_matches_the_serial_result() {
+    let ids: Vec<u64> = (0..900).step_by(3).collect();
+    let mut serial = Universe::with_seed(vec![30, 30], 3, 12).with_rule(ObserveOnly(ids));
+    serial.set_entanglement_percentage(0.8);
+    let mut parallel = serial.clone();
+    parallel.tuning = TickTuning {
+        threads: 4,
+        tile_size: 7,
+        snapshot_interval: 1,
+    };
+    let report = serial.tick();
+    assert!(report.entanglements.len() > 7);
+    assert_eq!(parallel.tick(), report);
+    assert_eq!(parallel.grid, serial.grid);
+}
+
+/// The default rule without the cached local interactions, as a reference.
+#[derive(Debug)]
+struct Uncached;
+
+impl UpdateRule for Uncached {
+    fn apply(&self, cell: &Existon, neighbors: &[&Existon], ctx: &RuleCtx) -> Existon {
+        MatzkeDefaultRule.apply(cell, neighbors, ctx)
+    }
+}
+
+#[test]
+fn cached_interactions_match_a_full_evaluation() {
+    let mut cached = Universe::with_seed(vec![24, 24], 3, 5);
+    for (idx, cell) in cached.grid.iter_mut().enumerate() {
+        cell.consciousness = ConsciousnessState::Potential;
+        if idx % 24 > 4 || idx / 24 > 4 {
+            cell.state = Multivector::zero(3);
+        }
+    }
+    let mut full = cached.clone().with_rule(Uncached);
+    let mut checkpoint = None;
+    for tick in 0..150 {
+        for universe in [&mut cached, &mut full] {
+            match tick {
+                // Edits between ticks, including ones that bypass interventions.
+                40 => universe.grid[300].state.coefficients[3] = Mod3::new(1),
+                60 => universe.set_operator(&[12, 12]),
+                90 => checkpoint = Some(universe.checkpoint()),
+                120 => universe.restore(checkpoint.clone().unwrap()),
+                _ => {}
+            }
+        }
+        assert_eq!(cached.tick(), full.tick());
+        assert_eq!(cached.grid, full.grid, "tick {tick}");
+    }
+}
+
+#[test]
+fn stats_count_states_grades_and_entropy() {
+    let mut universe = Universe::with_seed(vec![2, 2], 2, 1);
+    for existon in &mut universe.grid {
+        existon.state = Multivector::zero(2);
+    }
+    // Two distinct states, two cells each: exactly one bit of entropy.
+    for idx in [0, 1] {
+        universe.grid[idx].state.coefficients[0b01] = Mod3::new(1);
+        universe.grid[idx].state.coefficients[0b11] = Mod3::new(-1);
+    }
+    universe.grid[2].consciousness = ConsciousnessState::Observed;
+    universe.grid[3].consciousness = ConsciousnessState::Operator;
+    universe.entanglement.clear();
+    universe.entangle_pair(universe.grid[0].id, universe.grid[1].id);
+
+    let stats = universe.stats();
+    assert_eq!(
+        (stats.potential, stats.observed, stats.operators),
+        (2, 1, 1)
+    );
+    assert_eq!(stats.grade_totals, vec![0, 2, 2]);
+    assert_eq!(stats.mean_nonzero_coefficients, 1.0);
+    assert!((stats.entropy - 1.0).abs() < 1e-12);
+    assert_eq!(stats.entanglements, 1);
+}
+
+#[test]
+fn restoring_a_checkpoint_rewinds_state_log_and_rng() {
+    let mut universe = Universe::with_seed(vec![30, 30], 3, 21);
+    universe.observation_rate = 0.1;
+    universe.tick();
+    let checkpoint = universe.checkpoint();
+    let mut expected = universe.clone();
+    expected.tick();
+
+    universe.set_operator(&[2, 2]);
+    universe.set_boundary(BoundaryCondition::Fixed);
+    universe.tick();
+    // Mid-tick checkpoints describe the tick boundary.
+    assert!(universe.tick_within(Duration::ZERO).is_none());
+    let mid_tick = universe.checkpoint();
+
+    universe.restore(checkpoint);
+    assert!(universe.interventions.is_empty());
+    assert_eq!(universe.boundary, BoundaryCondition::Toroidal);
+    universe.tick();
+    assert_eq!(universe.grid, expected.grid);
+
+    universe.restore(mid_tick);
+    assert_eq!(universe.tick_count, 2);
+    assert!(!universe.is_mid_tick());
+}
+
+#[test]
+fn neighbors_follow_the_neighborhood_and_boundary() {
+    let mut universe = Universe::with_seed(vec![5, 4], 3, 1)
+        .with_neighborhood(Neighborhood::VonNeumann)
+        .with_boundary(BoundaryCondition::Fixed);
+    assert_eq!(universe.neighbors(&[0, 0]).collect::<Vec<_>>(), vec![1, 5]);
+    assert_eq!(universe.neighbors(&[2, 1]).count(), 4);
+    assert_eq!(universe.neighbors(&[5, 0]).count(), 0);
+    universe.set_boundary(BoundaryCondition::Toroidal);
+    assert_eq!(
+        universe.neighbors(&[0, 0]).collect::<Vec<_>>(),
+        vec![4, 1, 15, 5]
+    );
+
+    let cells: Vec<_> = universe.iter_cells().collect();
+    assert_eq!(cells.len(), 20);
+    for (idx, (coord, cell)) in cells.into_iter().enumerate() {
+        assert_eq!(universe.get_index_from_coord(&coord), Some(idx));
+        assert_eq!(cell, &universe.grid[idx]);
+    }
+}
+
+#[test]
+fn hexagonal_grids_have_six_symmetric_neighbors() {
+    let mut universe = Universe::with_seed(vec![6, 4], 3, 1).with_topology(Topology::Hexagonal);
+    let sorted = |universe: &Universe, coord: &[usize]| {
+        let mut neighbors: Vec<usize> = universe.neighbors(coord).collect();
+        neighbors.sort_unstable();
+        neighbors
+    };
+    // Odd rows sit half a cell right of the rows above and below.
+    assert_eq!(sorted(&universe, &[2, 1]), vec![2, 3, 7, 9, 14, 15]);
+    assert_eq!(sorted(&universe, &[2, 2]), vec![7, 8, 13, 15, 19, 20]);
+    for (coord, _) in universe.iter_cells() {
+        let idx = universe.get_index_from_coord(&coord).unwrap();
+        for neighbor in universe.neighbors(&coord) {
+            let back = universe.get_coord_from_index(neighbor);
+            assert!(universe.neighbors(&back).any(|n| n == idx));
+        }
+    }
+    universe.set_neighborhood(Neighborhood::Moore { radius: 2 });
+    assert_eq!(universe.neighbors(&[3, 2]).count(), 18);
+
+    universe.tick();
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.topology(), Topology::Hexagonal);
+    assert_eq!(rebuilt.grid, universe.grid);
+}
+
+#[test]
+fn region_indices_cover_the_clipped_box_in_index_order() {
+    let universe = Universe::with_seed(vec![4, 3, 2], 2, 1);
+    assert_eq!(
+        universe.region_indices(&[1..3, 1..2, 0..2]),
+        vec![5, 6, 17, 18]
+    );
+    assert_eq!(universe.region_indices(&[2..9, 2..9, 1..9]).len(), 2);
+    assert!(universe.region_indices(&[1..1, 0..3, 0..2]).is_empty());
+    assert!(universe.region_indices(&[0..4, 0..3]).is_empty());
+}
+
+#[test]
+fn region_operations_change_only_the_box() {
+    let mut universe = Universe::with_seed(vec![10, 10], 3, 8);
+    let before = universe.grid.clone();
+    let region = [2..5, 3..7];
+    let inside = universe.region_indices(&region);
+
+    let mut state = Multivector::zero(3);
+    state.coefficients[3] = Mod3(-1);
+    universe.apply_region(&region, RegionOp::Fill(state.clone()));
+    for (idx, cell) in universe.grid.iter().enumerate() {
+        if inside.contains(&idx) {
+            assert_eq!(cell.state, state);
+        } else {
+            assert_eq!(cell, &before[idx]);
+        }
+    }
+
+    universe.apply_region(&region, RegionOp::Observe);
+    assert!(
+        inside
+            .iter()
+            .all(|&idx| universe.grid[idx].consciousness == ConsciousnessState::Observed)
+    );
+    universe.apply_region(&region, RegionOp::Disrupt);
+    universe.apply_region(&region, RegionOp::Clear);
+    assert!(inside.iter().all(|&idx| {
+        let cell = &universe.grid[idx];
+        cell.consciousness == ConsciousnessState::Potential && cell.state == Multivector::zero(3)
+    }));
+    universe.apply_region(&region, RegionOp::Randomize);
+    assert_eq!(universe.interventions.len(), 5);
+
+    // Operations that change nothing are not logged.
+    universe.apply_region(&region, RegionOp::Disrupt);
+    universe.apply_region(&[20..30, 0..10], RegionOp::Clear);
+    universe.apply_region(&region, RegionOp::Fill(Multivector::zero(2)));
+    assert_eq!(universe.interventions.len(), 5);
+}
+
+#[test]
+fn region_operations_replay_through_share_codes() {
+    let mut universe = Universe::with_seed(vec![12, 12], 3, 21);
+    universe.tick();
+    universe.apply_region(&[0..6, 0..12], RegionOp::Randomize);
+    let mut state = Multivector::zero(3);
+    state.coefficients[0] = Mod3(1);
+    state.coefficients[5] = Mod3(-1);
+    universe.apply_region(&[4..8, 4..8], RegionOp::Fill(state));
+    universe.tick();
+    universe.apply_region(&[0..12, 6..12], RegionOp::Observe);
+    universe.tick();
+
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.interventions, universe.interventions);
+    assert_eq!(rebuilt.grid, universe.grid);
+}
+
+#[test]
+fn operator_presets_place_their_blades_and_replay() {
+    let blade_of = |preset: OperatorPreset, ga_dims| {
+        let state = preset.state(ga_dims);
+        let blades: Vec<(usize, Mod3)> = (0..state.coefficients.len())
+            .filter(|&b| state.coefficients[b] != Mod3(0))
+            .map(|b| (b, state.coefficients[b]))
+            .collect();
+        blades
+    };
+    assert_eq!(blade_of(OperatorPreset::ScalarPlus, 3), vec![(0, Mod3(1))]);
+    assert_eq!(
+        blade_of(OperatorPreset::ScalarMinus, 3),
+        vec![(0, Mod3(-1))]
+    );
+    assert_eq!(blade_of(OperatorPreset::E0, 3), vec![(0b001, Mod3(1))]);
+    assert_eq!(blade_of(OperatorPreset::E0E1, 3), vec![(0b011, Mod3(1))]);
+    assert_eq!(
+        blade_of(OperatorPreset::Pseudoscalar, 3),
+        vec![(0b111, Mod3(1))]
+    );
+    assert_eq!(blade_of(OperatorPreset::E0E1, 1), vec![]);
+    assert_eq!(
+        OperatorPreset::Pseudoscalar.next(),
+        OperatorPreset::ScalarPlus
+    );
+
+    let mut universe = Universe::with_seed(vec![8, 8], 3, 4);
+    let bivector = OperatorPreset::E0E1.state(3);
+    universe.set_operator_with_state(&[2, 3], bivector.clone());
+    universe.tick();
+    // Repainting the same state changes nothing; a new state replaces the old one.
+    universe.set_operator_with_state(&[2, 3], bivector);
+    universe.set_operator_with_state(&[2, 3], OperatorPreset::ScalarMinus.state(3));
+    universe.set_operator_with_state(&[4, 4], OperatorPreset::E0.state(2));
+    assert_eq!(universe.interventions.len(), 2);
+    let idx = universe.get_index_from_coord(&[2, 3]).unwrap();
+    assert_eq!(
+        universe.grid[idx].consciousness,
+        ConsciousnessState::Operator
+    );
+    assert_eq!(
+        universe.grid[idx].state,
+        OperatorPreset::ScalarMinus.state(3)
+    );
+
+    universe.tick();
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.interventions, universe.interventions);
+    assert_eq!(rebuilt.grid, universe.grid);
+}
+
+#[test]
+fn moving_operators_step_wrap_and_wait_behind_others() {
+    let mut universe = Universe::with_seed(vec![4, 3], 2, 8);
+    universe.operator_move_interval = 2;
+    universe.set_operator(&[3, 0]);
+    universe.set_operator_velocity(&[3, 0], &[1, 1]);
+    universe.set_operator(&[1, 2]);
+    universe.set_operator_velocity(&[1, 2], &[1, 0]);
+    // A stationary operator in the second mover's way.
+    universe.set_operator(&[2, 2]);
+    universe.set_operator_velocity(&[2, 2], &[0, 0]);
+    assert_eq!(universe.interventions.len(), 5);
+
+    assert!(universe.tick().operator_moves.is_empty());
+    let report = universe.tick();
+    assert_eq!(report.operator_moves, vec![(vec![3, 0], vec![0, 1])]);
+    let (from, to) = (
+        universe.get_index_from_coord(&[3, 0]).unwrap(),
+        universe.get_index_from_coord(&[0, 1]).unwrap(),
+    );
+    assert_eq!(
+        universe.grid[to].consciousness,
+        ConsciousnessState::Operator
+    );
+    assert_eq!(universe.grid[to].velocity, Some(vec![1, 1]));
+    // Both cells took their IDs with them.
+    assert_eq!(universe.grid[to].id, from as u64);
+    assert_ne!(
+        universe.grid[from].consciousness,
+        ConsciousnessState::Operator
+    );
+    assert_eq!(universe.grid[from].id, to as u64);
+    let blocked = universe.get_index_from_coord(&[1, 2]).unwrap();
+    assert_eq!(universe.grid[blocked].velocity, Some(vec![1, 0]));
+
+    universe.tick();
+    universe.tick();
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.operator_move_interval, 2);
+    assert_eq!(rebuilt.grid, universe.grid);
+}
+
+#[test]
+fn collapse_policies_decide_what_an_observation_keeps() {
+    let mut potential = Existon::new(0, 3, &mut StdRng::seed_from_u64(0));
+    potential.state = Multivector::zero(3);
+    for blade in [0b000, 0b001, 0b011, 0b111] {
+        potential.state.coefficients[blade] = Mod3(1);
+    }
+    let kept = |policy| {
+        let mut cell = potential.clone();
+        cell.observe(policy);
+        assert_eq!(cell.consciousness, ConsciousnessState::Observed);
+        (0..8)
+            .filter(|&b| cell.state.coefficients[b] != Mod3(0))
+            .collect::<Vec<usize>>()
+    };
+    assert_eq!(kept(CollapsePolicy::KeepScalarVector), vec![0b000, 0b001]);
+    assert_eq!(kept(CollapsePolicy::ProjectOntoOperatorBlade), vec![0b001]);
+    assert_eq!(kept(CollapsePolicy::KeepEvenGrades), vec![0b000, 0b011]);
+    let single = kept(CollapsePolicy::ProjectToSingleRandomBlade);
+    assert_eq!(single.len(), 1);
+    assert!([0b000, 0b001, 0b011, 0b111].contains(&single[0]));
+    assert_eq!(kept(CollapsePolicy::ProjectToSingleRandomBlade), single);
+
+    // The policy applies to tools and entanglement too, and travels in share codes.
+    let mut universe = Universe::with_seed(vec![6, 6], 3, 17);
+    universe.collapse_policy = CollapsePolicy::KeepEvenGrades;
+    universe.observe_cell(7);
+    assert!(
+        (0..8)
+            .filter(|b: &usize| b.count_ones() % 2 == 1)
+            .all(|b| universe.grid[7].state.coefficients[b] == Mod3(0))
+    );
+    universe.tick();
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.collapse_policy, CollapsePolicy::KeepEvenGrades);
+    assert_eq!(rebuilt.grid, universe.grid);
+}
+
+#[test]
+fn entanglement_follows_cells_that_move() {
+    let mut universe = Universe::with_seed(vec![4, 3], 2, 8).with_rule(ObserveOnly(vec![]));
+    universe.operator_move_interval = 1;
+    universe.entanglement.clear();
+    for existon in &mut universe.grid {
+        existon.consciousness = ConsciousnessState::Potential;
+    }
+    let displaced = universe.grid[universe.get_index_from_coord(&[0, 1]).unwrap()].id;
+    let partner = universe.grid[universe.get_index_from_coord(&[2, 2]).unwrap()].id;
+    universe.entangle_pair(displaced, partner);
+    universe.set_operator(&[3, 0]);
+    universe.set_operator_velocity(&[3, 0], &[1, 1]);
+    let operator = universe.grid[universe.get_index_from_coord(&[3, 0]).unwrap()].id;
+
+    universe.tick();
+    assert_eq!(universe.coord_of_id(operator), Some(vec![0, 1]));
+    assert_eq!(universe.coord_of_id(displaced), Some(vec![3, 0]));
+    assert_eq!(universe.index_of_id(u64::MAX), None);
+    let checkpoint = universe.checkpoint();
+
+    // Observing the displaced cell at its new place still collapses its partner.
+    universe.operator_move_interval = 0;
+    universe.set_rule(ObserveOnly(vec![displaced]));
+    let report = universe.tick();
+    assert_eq!(report.observations, vec![vec![3, 0]]);
+    assert_eq!(report.entanglements, vec![(vec![3, 0], vec![2, 2])]);
+
+    universe.restore(checkpoint);
+    assert_eq!(universe.coord_of_id(displaced), Some(vec![3, 0]));
+}
+
+#[test]
+fn resizing_keeps_the_overlap_and_its_entanglements() {
+    let mut universe = Universe::with_seed(vec![4, 4], 2, 9);
+    universe.entanglement.clear();
+    let id_at = |universe: &Universe, coord: &[usize]| {
+        universe.grid[universe.get_index_from_coord(coord).unwrap()].id
+    };
+    let (inside, partner, cut) = (
+        id_at(&universe, &[1, 1]),
+        id_at(&universe, &[0, 1]),
+        id_at(&universe, &[3, 3]),
+    );
+    universe.entangle_pair(inside, partner);
+    universe.entangle_pair(inside, cut);
+    let kept = universe.grid[universe.get_index_from_coord(&[1, 1]).unwrap()].clone();
+
+    universe.resize(&[6, 5]);
+    assert_eq!(universe.grid.len(), 30);
+    let idx = universe.get_index_from_coord(&[1, 1]).unwrap();
+    assert_eq!(universe.grid[idx], kept);
+    assert_eq!(universe.index_of_id(inside), Some(idx));
+    assert!(universe.grid.iter().filter(|cell| cell.id >= 16).count() == 14);
+    assert_eq!(universe.entanglement.partners(inside).count(), 2);
+
+    // Shrinking cuts one member off, and it leaves the group.
+    universe.resize(&[2, 2]);
+    assert_eq!(universe.index_of_id(cut), None);
+    assert_eq!(
+        universe.entanglement.partners(inside).collect::<Vec<_>>(),
+        vec![partner]
+    );
+    universe.tick();
+
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.initial_dims(), &[4, 4]);
+    assert_eq!(rebuilt.grid_dims, vec![2, 2]);
+    assert_eq!(rebuilt.grid, universe.grid);
+}
+
+#[test]
+fn extruding_copies_the_grid_into_new_layers() {
+    let mut universe = Universe::with_seed(vec![3, 2], 2, 10);
+    universe.set_operator(&[2, 1]);
+    universe.set_operator_velocity(&[2, 1], &[1, 0]);
+    let checkpoint = universe.checkpoint();
+    let original = universe.grid.clone();
+
+    universe.extrude(3);
+    assert_eq!(universe.grid_dims, vec![3, 2, 3]);
+    for (idx, cell) in original.iter().enumerate() {
+        let [x, y] = [idx % 3, idx / 3];
+        let layer0 = &universe.grid[universe.get_index_from_coord(&[x, y, 0]).unwrap()];
+        let layer2 = &universe.grid[universe.get_index_from_coord(&[x, y, 2]).unwrap()];
+        assert_eq!(layer0.id, cell.id);
+        assert!(layer2.id >= 6);
+        assert_eq!(layer2.state, cell.state);
+        assert_eq!(layer2.consciousness, cell.consciousness);
+    }
+    let operator = universe.get_index_from_coord(&[2, 1, 1]).unwrap();
+    assert_eq!(universe.grid[operator].velocity, Some(vec![1, 0, 0]));
+
+    universe.restore(checkpoint);
+    assert_eq!(universe.grid_dims, vec![3, 2]);
+    assert_eq!(universe.grid, original);
+    universe.tick();
+}
+
+#[test]
+fn region_entanglement_pairs_only_free_cells_inside_the_box() {
+    let mut universe = Universe::with_seed(vec![10, 10], 3, 4);
+    universe.entanglement.clear();
+    universe.entangle_pair(0, 11);
+    let grid = universe.grid.clone();
+    universe.entangle_region_random(&[0..4, 0..5], 1.0);
+    assert_eq!(universe.grid, grid);
+    // Cell 11 was taken, leaving 19 free cells in the box for 9 new pairs.
+    assert_eq!(universe.entanglement.len(), 10);
+    assert_eq!(universe.entanglement.partners(0).collect::<Vec<_>>(), [11]);
+    for members in universe.entanglement.groups() {
+        for &id in members {
+            let coord = universe.get_coord_from_index(universe.index_of_id(id).unwrap());
+            assert!(coord[0] < 4 && coord[1] < 5);
+        }
+    }
+
+    // A box without two free cells left changes nothing and is not logged.
+    let logged = universe.interventions.len();
+    universe.entangle_region_random(&[0..4, 0..5], 1.0);
+    assert_eq!(universe.interventions.len(), logged);
+}
+
+#[test]
+fn rewiring_replaces_the_groups_and_replays_from_a_share_code() {
+    let mut universe = Universe::with_seed(vec![12, 12], 3, 9);
+    universe.set_entanglement_percentage(0.1);
+    for _ in 0..3 {
+        universe.tick();
+    }
+    let (before, grid) = (universe.entanglement.clone(), universe.grid.clone());
+    universe.rewire_entanglement(0.5);
+    assert_eq!(universe.grid, grid);
+    assert_ne!(universe.entanglement, before);
+    assert_eq!(universe.entanglement.len(), 36);
+    assert_eq!(universe.entanglement_percentage, 0.1);
+    universe.entangle_region_random(&[6..12, 0..12], 1.0);
+    for _ in 0..3 {
+        universe.tick();
+    }
+
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.entanglement, universe.entanglement);
+    assert_eq!(rebuilt.grid, universe.grid);
+}
+
+#[test]
+fn limited_collapse_speed_delays_distant_partners() {
+    let mut universe = Universe::with_seed(vec![20, 4], 3, 2).with_rule(ObserveOnly(vec![0]));
+    for cell in &mut universe.grid {
+        cell.consciousness = ConsciousnessState::Potential;
+    }
+    universe.entanglement.clear();
+    universe.entanglement.entangle(&[0, 6, 13]);
+    let mut instant = universe.clone();
+    universe.collapse_speed = CollapseSpeed::Limited(2.0);
+    assert_eq!(instant.tick().entanglements.len(), 2);
+
+    // Six cells away at two cells per tick, and seven the short way around.
+    let mut fired = Vec::new();
+    let mut checkpoint = None;
+    for _ in 0..6 {
+        let report = universe.tick();
+        fired.extend(
+            report
+                .entanglements
+                .into_iter()
+                .map(|(_, to)| (report.tick, to)),
+        );
+        if report.tick == 1 {
+            assert_eq!(universe.in_flight_collapses().len(), 2);
+            checkpoint = Some(universe.checkpoint());
+        }
+    }
+    assert_eq!(fired, [(3, vec![6, 0]), (4, vec![13, 0])]);
+    assert!(universe.in_flight_collapses().is_empty());
+    // The same collapses, only observed later.
+    let states = |u: &Universe| -> Vec<(ConsciousnessState, Multivector)> {
+        u.grid
+            .iter()
+            .map(|cell| (cell.consciousness, cell.state.clone()))
+            .collect()
+    };
+    assert_eq!(states(&universe), states(&instant));
+    assert_eq!(universe.grid[6].observed_duration(universe.tick_count), 2);
+
+    // Collapses still on their way come back with a checkpoint or a delta.
+    let mut rewound = universe.clone();
+    rewound.restore(checkpoint.unwrap());
+    let back = UniverseDelta::decode(&universe.diff(&rewound).encode()).unwrap();
+    assert_eq!(back.collapses.as_ref().map(Vec::len), Some(2));
+    universe.apply_delta(&back);
+    assert_eq!(
+        universe.in_flight_collapses(),
+        rewound.in_flight_collapses()
+    );
+    for _ in 0..4 {
+        rewound.tick();
+    }
+    assert_eq!(states(&rewound), states(&instant));
+
+    let scenario = Scenario::capture(&universe);
+    let rebuilt = Scenario::decode(&scenario.encode()).unwrap();
+    assert_eq!(rebuilt.collapse_speed, CollapseSpeed::Limited(2.0));
+}
+
+#[test]
+fn pair_operators_act_on_each_link_and_replay() {
+    let mv = |c: [i8; 4]| Multivector {
+        p: 2,
+        coefficients: c.map(Mod3::new).to_vec(),
+    };
+    let mut universe =
+        Universe::with_seed(vec![4, 4], 2, 4).with_rule(ObserveOnly(vec![0, 2, 4, 6]));
+    for cell in &mut universe.grid {
+        cell.consciousness = ConsciousnessState::Potential;
+        cell.state = mv([1, 1, -1, 1]);
+    }
+    universe.grid[3].state = mv([0, -1, 0, 0]);
+    universe.entanglement.clear();
+    for pair in [[0, 1], [2, 3], [4, 5], [6, 7]] {
+        universe.entanglement.entangle(&pair);
+    }
+    universe.set_link_operator(1, Some(PairOperator::Identity));
+    universe.set_link_operator(2, Some(PairOperator::Swap));
+    let rotor = PairOperator::Rotor(mv([0, 0, 0, 1]));
+    universe.set_link_operator(5, Some(rotor.clone()));
+    assert_eq!(universe.link_operator(4, 5), &rotor);
+    assert_eq!(universe.link_operator(6, 7), &PairOperator::Inversion);
+    universe.tick();
+
+    let states: Vec<Multivector> = (0..8).map(|i| universe.grid[i].state.clone()).collect();
+    let observed = mv([1, 1, -1, 0]);
+    assert_eq!(states[1], observed);
+    assert_eq!((&states[2], &states[3]), (&mv([0, -1, 0, 0]), &observed));
+    // Conjugating by e0e1 is a half-turn: vectors flip, the scalar stays.
+    assert_eq!(states[5], mv([1, -1, 1, 0]));
+    assert_eq!(states[7], mv([-1, -1, 1, 0]));
+
+    let mut universe = Universe::with_seed(vec![6, 6], 2, 4);
+    universe.pair_operator = rotor;
+    universe.set_link_operator(0, Some(PairOperator::Swap));
+    universe.entangle_pair(0, 1);
+    universe.set_link_operator(0, Some(PairOperator::Swap));
+    universe.set_link_operator(0, Some(PairOperator::Swap));
+    assert_eq!(universe.interventions.len(), 2);
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.pair_operator, universe.pair_operator);
+    assert_eq!(rebuilt.entanglement, universe.entanglement);
+}
+
+#[test]
+fn reversible_ticks_run_backwards_exactly_and_replay() {
+    let mut universe = Universe::with_seed(vec![10, 8], 3, 21).with_reversible(true);
+    universe.observation_rate = 0.2;
+    universe.decay_rate = 0.1;
+    universe.fluctuation_rate = 0.05;
+    universe.set_operator(&[4, 4]);
+    let start = universe.grid.clone();
+    let mut generations = vec![start.clone()];
+    for _ in 0..25 {
+        universe.tick();
+        generations.push(universe.grid.clone());
+    }
+    assert_ne!(universe.grid, start);
+
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert!(rebuilt.is_reversible());
+    assert_eq!(rebuilt.grid, universe.grid);
+
+    while universe.tick_count > 0 {
+        assert!(universe.tick_backward());
+        assert_eq!(universe.grid, generations[universe.tick_count as usize]);
+    }
+    assert!(!universe.tick_backward());
+    assert_eq!(universe.interventions.len(), 1);
+    universe.tick();
+    assert_eq!(universe.grid, generations[1]);
+}
+
+#[test]
+fn tick_reports_account_for_the_change_in_grade_totals() {
+    for reversible in [false, true] {
+        let mut universe = Universe::with_seed(vec![12, 10], 3, 17).with_reversible(reversible);
+        universe.observation_rate = 0.2;
+        universe.fluctuation_rate = 0.05;
+        universe.set_entanglement_percentage(0.5);
+        universe.set_operator(&[3, 3]);
+        for _ in 0..10 {
+            let before = universe.grade_totals();
+            let change = universe.tick().totals_change;
+            let after = universe.grade_totals();
+            let expected = |a: &[i64], b: &[i64]| -> Vec<i64> {
+                a.iter().zip(b).map(|(a, b)| a - b).collect()
+            };
+            assert_eq!(change.weight, expected(&after.weight, &before.weight));
+            assert_eq!(change.charge, expected(&after.charge, &before.charge));
+        }
+    }
+    let mut universe = Universe::with_seed(vec![2, 2], 2, 1);
+    universe.apply_region(&[0..2, 0..2], RegionOp::Clear);
+    universe.grid[0].state.coefficients[0b01] = Mod3::new(-1);
+    universe.grid[1].state.coefficients[0b11] = Mod3::new(1);
+    let totals = universe.grade_totals();
+    assert_eq!(
+        (totals.weight, totals.charge),
+        (vec![0, 1, 1], vec![0, -1, 1])
+    );
+}
+
+#[test]
+fn existons_track_their_age_and_how_long_they_have_been_observed() {
+    let mut universe = Universe::with_seed(vec![10, 10], 3, 31);
+    universe.observation_rate = 0.2;
+    universe.decay_rate = 0.1;
+    universe.fluctuation_rate = 0.1;
+    universe.set_entanglement_percentage(0.3);
+    let idx = universe
+        .grid
+        .iter()
+        .position(|cell| cell.consciousness == ConsciousnessState::Potential)
+        .unwrap();
+    universe.observe_cell(idx);
+    for _ in 0..20 {
+        let before = universe.grid.clone();
+        let report = universe.tick();
+        let now = universe.tick_count;
+        for (idx, (was, cell)) in before.iter().zip(&universe.grid).enumerate() {
+            let coord = universe.get_coord_from_index(idx);
+            let age = if report.fluctuations.contains(&coord) {
+                0
+            } else {
+                was.age_ticks(now - 1) + 1
+            };
+            assert_eq!(cell.age_ticks(now), age);
+            let observed = match (was.consciousness, cell.consciousness) {
+                (ConsciousnessState::Observed, ConsciousnessState::Observed) => {
+                    was.observed_duration(now - 1) + 1
+                }
+                _ => 0,
+            };
+            assert_eq!(cell.observed_duration(now), observed);
+        }
+    }
+    let now = universe.tick_count;
+    assert!(
+        universe
+            .grid
+            .iter()
+            .any(|cell| cell.observed_duration(now) > 1)
+    );
+    assert!(universe.grid.iter().any(|cell| cell.age_ticks(now) < now));
+
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.grid, universe.grid);
+    universe.resize(&[12, 10]);
+    for (coord, cell) in universe.iter_cells().filter(|(coord, _)| coord[0] >= 10) {
+        assert_eq!(cell.age_ticks(now), 0, "{coord:?}");
+    }
+}
+
+#[test]
+fn queries_find_cells_by_state_blade_and_distance() {
+    let mut universe = Universe::with_seed(vec![10, 10], 2, 41);
+    universe.entanglement.clear();
+    universe.apply_region(&[0..10, 0..10], RegionOp::Clear);
+    assert_eq!(universe.nearest_operator(&[5, 5]), None);
+    universe.set_operator(&[1, 1]);
+    universe.set_operator(&[8, 5]);
+    universe.set_operator(&[5, 9]);
+    universe.grid[22].state.coefficients[0b10] = Mod3::new(-1);
+    universe.observe_cell(22);
+
+    let operators = universe.cells_in_state(ConsciousnessState::Operator);
+    assert_eq!(operators, vec![11, 58, 95]);
+    assert_eq!(universe.cells_with_blade(0b01), operators);
+    assert_eq!(universe.cells_with_blade(0b10), vec![22]);
+    assert_eq!(universe.cells_with_blade(0b100), Vec::<usize>::new());
+    assert_eq!(
+        universe.find(|cell| cell.consciousness != ConsciousnessState::Potential),
+        vec![11, 22, 58, 95]
+    );
+
+    assert_eq!(universe.nearest_operator(&[6, 5]), Some(58));
+    assert_eq!(universe.nearest_operator(&[2, 2]), Some(11));
+    // Around the edge, [5, 9] is a cell away from [5, 0].
+    assert_eq!(universe.nearest_operator(&[5, 0]), Some(95));
+    universe.set_boundary(BoundaryCondition::Fixed);
+    assert_eq!(universe.nearest_operator(&[5, 0]), Some(11));
+    assert_eq!(universe.nearest_operator(&[10, 0]), None);
+}
+
+#[test]
+fn disrupting_with_a_probability_only_decays_observed_cells_it_hits() {
+    let mut rng = StdRng::seed_from_u64(3);
+    let mut universe = Universe::with_seed(vec![6, 6], 3, 17);
+    assert!(!universe.disrupt_cell_with_probability(7, 1.0, &mut rng));
+
+    universe.observe_cell(7);
+    assert!(!universe.disrupt_cell_with_probability(7, 0.0, &mut rng));
+    assert_eq!(universe.grid[7].consciousness, ConsciousnessState::Observed);
+    assert!(universe.disrupt_cell_with_probability(7, 1.0, &mut rng));
+    assert_eq!(
+        universe.grid[7].consciousness,
+        ConsciousnessState::Potential
+    );
+}
+
+#[test]
+fn the_state_hash_follows_the_evolving_state() {
+    let mut universe = Universe::with_seed(vec![10, 10], 3, 8);
+    let mut twin = universe.clone();
+    assert_eq!(universe.state_hash(), twin.state_hash());
+    assert_ne!(
+        universe.state_hash(),
+        Universe::with_seed(vec![10, 10], 3, 9).state_hash()
+    );
+    universe.tick();
+    assert_ne!(universe.state_hash(), twin.state_hash());
+    twin.tick();
+    assert_eq!(universe.state_hash(), twin.state_hash());
+
+    // Parameters are not state; the cells and the generator are.
+    twin.decay_rate = 0.5;
+    assert_eq!(universe.state_hash(), twin.state_hash());
+    twin.grid[0].state.coefficients[0] = twin.grid[0].state.coefficients[0] + Mod3(1);
+    assert_ne!(universe.state_hash(), twin.state_hash());
+}
@@ -0,0 +1,118 @@
+//! Tests of the volume view of three-dimensional grids.
+
+#[cfg(feature = "desktop")]
+mod common;
+
+use existons::color::ColorMapping;
+use existons::existon::ConsciousnessState;
+use existons::universe::Universe;
+use existons::volume::{self, OrbitCamera};
+
+const SIZE: [f64; 2] = [800.0, 600.0];
+
+#[test]
+fn the_orbit_camera_projects_the_grid_around_its_center() {
+    let mut camera = OrbitCamera {
+        yaw: 0.0,
+        pitch: 0.0,
+        distance: 2.0,
+    };
+    let dims = [5, 5, 5];
+    let center = camera.project(&[2, 2, 2], &dims, SIZE).unwrap();
+    assert_eq!(center.pos, [400.0, 300.0]);
+    assert_eq!(center.depth, 2.0);
+    assert_eq!(center.scale, 300.0);
+
+    // Head on, x runs right, y runs down and z runs away from the camera.
+    let right = camera.project(&[4, 2, 2], &dims, SIZE).unwrap();
+    assert!((right.pos[0] - 520.0).abs() < 1e-9);
+    let far = camera.project(&[2, 2, 4], &dims, SIZE).unwrap();
+    assert!(far.depth > center.depth && far.scale < center.scale);
+
+    // A quarter turn brings the far side round to the left.
+    camera.orbit([
+        std::f64::consts::FRAC_PI_2 / OrbitCamera::RADIANS_PER_PIXEL,
+        0.0,
+    ]);
+    let far = camera.project(&[2, 2, 4], &dims, SIZE).unwrap();
+    assert!(far.pos[0] < 300.0);
+    assert!((far.depth - 2.0).abs() < 1e-9);
+    camera.orbit([0.0, 1000.0]);
+    assert_eq!(camera.pitch, OrbitCamera::MAX_PITCH);
+    assert_eq!(camera.describe(), "Volume yaw 90° pitch 86°");
+
+    // Nothing behind the camera is drawn.
+    camera.distance = 0.1;
+    assert!(camera.project(&[2, 0, 2], &dims, SIZE).is_none());
+}
+
+#[test]
+fn splats_are_drawn_far_to_near_and_links_join_the_layers() {
+    let mut universe = Universe::with_seed(vec![4, 4, 4], 2, 60);
+    universe.set_operator(&[1, 1, 0]);
+    let ids = [[0, 0, 0], [3, 3, 3]].map(|c| {
+        let idx = universe.get_index_from_coord(&c).unwrap();
+        universe.grid[idx].id
+    });
+    universe.entanglement.clear();
+    universe.entangle_group(&ids);
+
+    let camera = OrbitCamera::default();
+    let mapping = ColorMapping::GA_BLEND;
+    let color = |idx| mapping.color(&universe.grid[idx]);
+    let splats = volume::splats(&universe, color, &camera, SIZE);
+    assert_eq!(splats.len(), 64);
+    assert!(splats.windows(2).all(|w| w[0].depth >= w[1].depth));
+    let opaque = splats.iter().filter(|s| s.color[3] == 1.0).count();
+    assert_eq!(opaque, 1);
+    assert_eq!(volume::state_alpha(ConsciousnessState::Potential), 0.08);
+
+    let links = volume::links(&universe, &camera, SIZE);
+    assert_eq!(links.len(), 1);
+    let ends = ids.map(|id| universe.coord_of_id(id).unwrap());
+    let [from, to] = ends.map(|c| camera.project(&c, &universe.grid_dims, SIZE).unwrap().pos);
+    assert_eq!(links[0].0, [from[0], from[1], to[0], to[1]]);
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn dragging_turns_the_volume_instead_of_using_the_tool() {
+    use common::Driver;
+    use piston_window::{Button, Key, MouseButton};
+    const LEFT: Button = Button::Mouse(MouseButton::Left);
+
+    // Flat grids have no volume to show.
+    let mut driver = Driver::new(61);
+    driver.key(Key::D3).key(Key::D);
+    assert!(driver.app.volume.is_none());
+    driver.click_cell(1, 2);
+    let idx = driver.index(1, 2);
+    assert_eq!(
+        driver.app.universe.grid[idx].consciousness,
+        ConsciousnessState::Operator
+    );
+
+    let dims = vec![8, 8, 8];
+    driver.app.universe = Universe::with_seed(dims.clone(), 2, 61);
+    driver.app.config.grid_dims = dims;
+    driver.key(Key::D);
+    let start = driver.app.volume.unwrap();
+    let undo_depth = driver.app.history.undo_depth();
+    driver.move_to_cell(2, 2).press(LEFT);
+    driver.move_to([150.0, 100.0]).release(LEFT);
+    let turned = driver.app.volume.unwrap();
+    assert!(turned.yaw > start.yaw && turned.pitch > start.pitch);
+    assert!(driver.app.universe.interventions.is_empty());
+    assert_eq!(driver.app.history.undo_depth(), undo_depth);
+
+    // Back on the slice, clicks place operators again.
+    driver.update().key(Key::D);
+    assert!(driver.app.volume.is_none());
+    driver.click_cell(2, 2);
+    let universe = &driver.app.universe;
+    let idx = universe.get_index_from_coord(&[2, 2, 0]).unwrap();
+    assert_eq!(
+        universe.grid[idx].consciousness,
+        ConsciousnessState::Operator
+    );
+}
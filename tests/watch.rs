@@ -0,0 +1,73 @@
+//! Tests of per-cell histories.
+
+use existons::existon::ConsciousnessState;
+use existons::universe::Universe;
+use existons::watch::WatchMode;
+
+#[test]
+fn watched_cells_keep_their_latest_states() {
+    let mut universe = Universe::with_seed(vec![8, 8], 3, 3);
+    universe.fluctuation_rate = 0.5;
+    universe.watch(&[2, 3]);
+    universe.watch_with(&[5, 5], 4, WatchMode::EveryTick);
+    for _ in 0..10 {
+        universe.tick();
+    }
+    let history = universe.history(&[2, 3]).unwrap();
+    let ticks: Vec<u64> = history.samples().map(|sample| sample.tick).collect();
+    assert_eq!(ticks, (0..=10).collect::<Vec<_>>());
+    let idx = universe.get_index_from_coord(&[2, 3]).unwrap();
+    assert_eq!(history.latest().unwrap().state, universe.grid[idx].state);
+    assert_eq!(history.id, universe.grid[idx].id);
+
+    let short = universe.history(&[5, 5]).unwrap();
+    assert_eq!(short.samples().next().unwrap().tick, 7);
+    assert_eq!(short.len(), 4);
+
+    universe.unwatch(&[2, 3]);
+    assert!(universe.history(&[2, 3]).is_none());
+    assert!(universe.history(&[0, 0]).is_none());
+}
+
+#[test]
+fn transitions_measure_how_long_a_cell_stays_observed() {
+    let mut universe = Universe::with_seed(vec![6, 6], 3, 5);
+    universe.observation_rate = 0.0;
+    universe.decay_rate = 0.0;
+    universe.fluctuation_rate = 0.0;
+    universe.set_entanglement_percentage(0.0);
+    let idx = universe.get_index_from_coord(&[1, 1]).unwrap();
+    universe.grid[idx].consciousness = ConsciousnessState::Potential;
+    universe.watch_with(&[1, 1], 16, WatchMode::Transitions);
+    for tick in 0..12 {
+        match tick {
+            2 | 8 => universe.observe_cell(idx),
+            5 => universe.disrupt_cell(idx),
+            _ => {}
+        }
+        universe.tick();
+    }
+    let history = universe.history(&[1, 1]).unwrap();
+    assert_eq!(history.len(), 4);
+    assert_eq!(history.spans(ConsciousnessState::Observed), [3..6, 9..13]);
+    assert_eq!(history.spans(ConsciousnessState::Potential), [0..3, 6..9]);
+}
+
+#[test]
+fn histories_follow_a_moving_operator() {
+    let mut universe = Universe::with_seed(vec![10, 4], 3, 7);
+    universe.operator_move_interval = 1;
+    universe.set_operator(&[2, 1]);
+    universe.set_operator_velocity(&[2, 1], &[1, 0]);
+    universe.watch(&[2, 1]);
+    for _ in 0..3 {
+        universe.tick();
+    }
+    let history = universe.history(&[5, 1]).unwrap();
+    assert_eq!(history.len(), 4);
+    assert!(
+        history
+            .samples()
+            .all(|sample| sample.consciousness == ConsciousnessState::Operator)
+    );
+}
@@ -0,0 +1,101 @@
+//! Tests of disruption waves spreading over the grid.
+
+use existons::delta::UniverseDelta;
+use existons::existon::ConsciousnessState;
+use existons::share::Scenario;
+use existons::universe::{BoundaryCondition, RegionOp, Universe};
+use existons::wave::DisruptionWave;
+
+/// A universe of `Observed` cells that stay as they are unless disrupted.
+fn observed_universe(seed: u64) -> Universe {
+    let mut universe = Universe::with_seed(vec![21, 21], 2, seed);
+    universe.observation_rate = 0.0;
+    universe.decay_rate = 0.0;
+    universe.fluctuation_rate = 0.0;
+    universe.entanglement.clear();
+    universe.set_boundary(BoundaryCondition::Fixed);
+    universe.apply_region(&[0..21, 0..21], RegionOp::Observe);
+    universe
+}
+
+fn distance(a: &[usize], b: &[usize]) -> usize {
+    a.iter().zip(b).map(|(a, b)| a.abs_diff(*b)).max().unwrap()
+}
+
+#[test]
+fn waves_weaken_as_they_spread_and_die_out() {
+    let wave = DisruptionWave::new(vec![3, 4], 1.5);
+    assert_eq!((wave.amplitude, wave.radius), (1.0, 0));
+    let farther = DisruptionWave { radius: 3, ..wave };
+    assert!((farther.strength() - 0.512).abs() < 1e-9);
+    assert!(!farther.is_spent(10));
+    assert!(farther.is_spent(2));
+    let faint = DisruptionWave {
+        radius: 30,
+        ..farther
+    };
+    assert!(faint.is_spent(100));
+}
+
+#[test]
+fn a_wave_decays_observed_cells_one_ring_per_tick() {
+    let mut universe = observed_universe(3);
+    let center = [10, 10];
+    universe.emit_disruption(&center, 1.0);
+    universe.emit_disruption(&[30, 0], 1.0);
+    universe.emit_disruption(&center, 0.0);
+    assert_eq!(universe.disruption_waves().len(), 1);
+
+    for tick in 0..6 {
+        let report = universe.tick();
+        for coord in &report.decays {
+            assert_eq!(distance(coord, &center), tick);
+        }
+        for (coord, cell) in universe.iter_cells() {
+            if distance(&coord, &center) > tick {
+                assert_eq!(cell.consciousness, ConsciousnessState::Observed);
+            }
+        }
+        if tick == 0 {
+            assert_eq!(report.decays, vec![center.to_vec()]);
+        }
+    }
+    let decayed = universe
+        .grid
+        .iter()
+        .filter(|cell| cell.consciousness == ConsciousnessState::Potential)
+        .count();
+    assert!(decayed > 9 && decayed < 121);
+
+    for _ in 0..20 {
+        universe.tick();
+    }
+    assert!(universe.disruption_waves().is_empty());
+}
+
+#[test]
+fn waves_replay_and_travel_in_checkpoints_and_deltas() {
+    let mut universe = observed_universe(4);
+    universe.emit_disruption(&[5, 5], 0.9);
+    let start = universe.checkpoint();
+    for _ in 0..3 {
+        universe.tick();
+    }
+    universe.emit_disruption(&[15, 12], 0.7);
+    universe.tick();
+    assert_eq!(universe.disruption_waves().len(), 2);
+
+    let rebuilt = Scenario::decode(&Scenario::capture(&universe).encode())
+        .unwrap()
+        .build();
+    assert_eq!(rebuilt.grid, universe.grid);
+    assert_eq!(rebuilt.disruption_waves(), universe.disruption_waves());
+
+    let mut rewound = universe.clone();
+    rewound.restore(start);
+    assert_eq!(rewound.disruption_waves()[0].radius, 0);
+    let forward = UniverseDelta::decode(&rewound.diff(&universe).encode()).unwrap();
+    assert_eq!(forward.waves.as_deref(), Some(universe.disruption_waves()));
+    rewound.apply_delta(&forward);
+    assert_eq!(rewound.disruption_waves(), universe.disruption_waves());
+}
@@ -0,0 +1,67 @@
+//! Tests of the browser frontend's engine.
+
+use existons::color::{OPERATOR_COLOR, ViewMode};
+use existons::existon::ConsciousnessState;
+use existons::universe::Universe;
+use existons::web::{WebSim, WebTool};
+
+#[test]
+fn the_page_gets_one_rgba_pixel_per_cell() {
+    let mut sim = WebSim::new(12, 7, 60);
+    assert_eq!((sim.width(), sim.height()), (12, 7));
+    assert_eq!(sim.render().len(), 12 * 7 * 4);
+
+    // The operator at [5, 2] is drawn in the window's operator color.
+    sim.click(5, 2, WebTool::from_code(3).unwrap());
+    let pixels = sim.render();
+    let at = (2 * 12 + 5) * 4;
+    let operator = OPERATOR_COLOR.map(|c| (c * 255.0).round() as u8);
+    assert_eq!(
+        pixels[at..at + 4],
+        [operator[0], operator[1], operator[2], 255]
+    );
+
+    // Clicks off the grid and unknown tools are ignored.
+    sim.click(12, 0, WebTool::Observe);
+    assert!(WebTool::from_code(2).is_none());
+    assert_eq!(sim.universe.interventions.len(), 1);
+}
+
+#[test]
+fn clicks_and_ticks_act_on_the_universe() {
+    let mut sim = WebSim::new(16, 16, 61);
+    sim.click(3, 4, WebTool::Observe);
+    let idx = sim.universe.get_index_from_coord(&[3, 4]).unwrap();
+    assert_eq!(
+        sim.universe.grid[idx].consciousness,
+        ConsciousnessState::Observed
+    );
+    sim.click(8, 8, WebTool::Disrupt);
+    assert_eq!(sim.universe.disruption_waves().len(), 1);
+    sim.tick(5);
+    assert_eq!(sim.universe.tick_count, 5);
+
+    // A reset keeps the size and the view mode.
+    sim.view_mode = ViewMode::Age;
+    sim.reset(62);
+    assert_eq!(sim.universe.tick_count, 0);
+    assert!(sim.universe.interventions.is_empty());
+    assert_eq!((sim.width(), sim.view_mode), (16, ViewMode::Age));
+}
+
+#[test]
+fn a_page_follows_a_served_universe() {
+    let mut served = Universe::with_seed(vec![20, 9], 3, 31);
+    let mut sim = WebSim::new(12, 7, 60);
+    assert!(!sim.load_state("not a code"));
+    assert!(sim.load_state(&served.to_delta().encode()));
+    assert_eq!((sim.width(), sim.height()), (20, 9));
+
+    let before = served.clone();
+    served.tick();
+    served.set_operator(&[4, 4]);
+    assert!(sim.apply_delta(&before.diff(&served).encode()));
+    assert_eq!(sim.universe.grid, served.grid);
+    assert_eq!(sim.universe.entanglement, served.entanglement);
+    assert_eq!(sim.render().len(), 20 * 9 * 4);
+}
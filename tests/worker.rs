@@ -0,0 +1,106 @@
+//! Tests of ticking on a simulation worker thread.
+
+#[cfg(feature = "desktop")]
+mod common;
+
+#[cfg(feature = "desktop")]
+use common::Driver;
+#[cfg(feature = "desktop")]
+use existons::existon::ConsciousnessState;
+use existons::universe::{Intervention, Universe};
+use existons::worker::{Command, SimWorker, Update};
+#[cfg(feature = "desktop")]
+use std::thread;
+#[cfg(feature = "desktop")]
+use std::time::{Duration, Instant};
+
+/// Sends update events until the universe reaches `ticks`, waiting on the
+/// worker between them.
+#[cfg(feature = "desktop")]
+fn update_until(driver: &mut Driver, ticks: u64) {
+    let start = Instant::now();
+    while driver.app.universe.tick_count < ticks {
+        assert!(start.elapsed() < Duration::from_secs(30), "worker stalled");
+        driver.update();
+        thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn a_mirror_kept_from_worker_deltas_matches_ticking_inline() {
+    let mut inline = Universe::with_seed(vec![24, 24], 2, 11);
+    let mut mirror = inline.clone();
+    let worker = SimWorker::spawn(inline.clone(), true);
+    let intervention = Intervention::SetOperator(inline.get_index_from_coord(&[5, 5]).unwrap());
+    assert!(worker.send(Command::Tick(3)));
+    assert!(worker.send(Command::Intervene(intervention.clone())));
+    assert!(worker.send(Command::Tick(2)));
+    assert!(worker.send(Command::Snapshot));
+
+    for tick in 0..5 {
+        if tick == 3 {
+            inline.apply(intervention.clone());
+            mirror.apply(intervention.clone());
+        }
+        let expected = inline.tick();
+        let Some(Update::Ticked { report, delta }) = worker.recv() else {
+            panic!("expected a tick");
+        };
+        assert_eq!(report.decays, expected.decays);
+        mirror.apply_delta(&delta.unwrap());
+        assert_eq!(mirror.grid, inline.grid);
+    }
+    let Some(Update::Snapshot(snapshot)) = worker.recv() else {
+        panic!("expected a snapshot");
+    };
+    assert_eq!(snapshot.tick_count, 5);
+    assert_eq!(snapshot.grid, inline.grid);
+}
+
+#[cfg(feature = "desktop")]
+#[test]
+fn the_app_adopts_worker_ticks_and_keeps_interventions_made_meanwhile() {
+    use existons::input::AppAction;
+    use piston_window::Key;
+
+    let mut inline = Driver::new(12);
+    inline.app.universe.observation_rate = 0.05;
+    inline.app.universe.fluctuation_rate = 0.02;
+    let mut threaded = Driver::new(12);
+    threaded.app.universe = inline.app.universe.clone();
+    threaded.app.worker = Some(SimWorker::spawn(Universe::new(vec![1], 1), false));
+    // The Observe tool observes around the cursor at random; operators are
+    // placed exactly.
+    for driver in [&mut inline, &mut threaded] {
+        driver.key(Key::D3);
+    }
+
+    // The first update only sends the tick, so the click lands while it runs
+    // and must be applied again to the ticked universe.
+    threaded.update();
+    assert_eq!(threaded.app.universe.tick_count, 0);
+    threaded.click_cell(8, 8);
+    update_until(&mut threaded, 1);
+    inline.update().click_cell(8, 8);
+    let operator = inline.index(8, 8);
+    assert_eq!(
+        threaded.app.universe.grid[operator].consciousness,
+        ConsciousnessState::Operator
+    );
+    assert_eq!(threaded.app.universe.grid, inline.app.universe.grid);
+
+    update_until(&mut threaded, 6);
+    for _ in 0..5 {
+        inline.update();
+    }
+    assert_eq!(threaded.app.universe.grid, inline.app.universe.grid);
+
+    // A reset while a tick is under way throws the ticked universe away.
+    threaded.update();
+    threaded.app.apply(AppAction::ResetUniverse);
+    let reset = threaded.app.universe.clone();
+    update_until(&mut threaded, 1);
+    let mut expected = reset;
+    expected.tick();
+    assert_eq!(threaded.app.universe.grid, expected.grid);
+}
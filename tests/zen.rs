@@ -0,0 +1,37 @@
+//! Tests of zen mode's drifting parameters and activity-guided camera.
+#![cfg(feature = "desktop")]
+
+use existons::universe::TickReport;
+use existons::zen::{ActivityTracker, Drift};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+#[test]
+fn drifts_stay_in_bounds_and_move_smoothly() {
+    let mut rng = StdRng::seed_from_u64(1);
+    let mut drift = Drift::new(5.0, 0.0, 1.0);
+    assert_eq!(drift.value, 1.0);
+    let mut previous = drift.value;
+    for _ in 0..10_000 {
+        let value = drift.step(&mut rng);
+        assert!((0.0..=1.0).contains(&value));
+        assert!((value - previous).abs() < 0.05);
+        previous = value;
+    }
+}
+
+#[test]
+fn the_hottest_region_is_where_events_keep_happening() {
+    let mut tracker = ActivityTracker::new(&[64, 32]);
+    assert_eq!(tracker.hottest(), None);
+    let report = TickReport {
+        observations: vec![vec![1, 1]],
+        entanglements: vec![(vec![50, 20], vec![51, 21])],
+        ..TickReport::default()
+    };
+    tracker.on_tick(&report);
+    assert_eq!(tracker.hottest(), Some([52.0, 20.0]));
+    assert_eq!(tracker.heat_at(1, 1), 1.0);
+    tracker.on_tick(&TickReport::default());
+    assert!(tracker.heat_at(1, 1) < 1.0);
+}